@@ -0,0 +1,35 @@
+//! Parses arbitrary text as a `.proto` file. The parser must not panic, and every name it records
+//! must be a single token, since the metadata the derive reads back is split on whitespace.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use protto::build::{ModuleLayout, parse_proto_content, render_metadata_with_layout};
+
+fuzz_target!(|content: &str| {
+    let file = parse_proto_content(content);
+
+    let names = file
+        .package
+        .iter()
+        .chain(&file.enums)
+        .chain(file.enum_values.values().flatten().map(|value| &value.name))
+        .chain(file.messages.iter().flat_map(|message| {
+            std::iter::once(&message.name).chain(&message.oneofs).chain(
+                message
+                    .fields
+                    .iter()
+                    .flat_map(|field| [&field.name, &field.type_name]),
+            )
+        }));
+    for name in names {
+        assert!(
+            !name.is_empty() && !name.contains(char::is_whitespace),
+            "{name:?} would not read back from the metadata as one name"
+        );
+    }
+
+    for layout in [ModuleLayout::Flat, ModuleLayout::Nested] {
+        render_metadata_with_layout(std::slice::from_ref(&file), layout);
+    }
+});
@@ -0,0 +1,23 @@
+//! Renders the metadata of several arbitrary `.proto` files together, as `generate_metadata` does
+//! for a build's files, and checks each line is one of the records the derive reads.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use protto::build::{ModuleLayout, parse_proto_content, render_metadata_with_layout};
+
+const RECORDS: &[&str] = &[
+    "#", "layout", "package", "enum", "value", "message", "field", "rename", "oneof",
+];
+
+fuzz_target!(|contents: Vec<&str>| {
+    let files: Vec<_> = contents.into_iter().map(parse_proto_content).collect();
+    let metadata = render_metadata_with_layout(&files, ModuleLayout::Nested);
+    for line in metadata.lines() {
+        let record = line.split_whitespace().next().unwrap_or_default();
+        assert!(
+            RECORDS.contains(&record),
+            "unexpected metadata line {line:?}"
+        );
+    }
+});
@@ -0,0 +1,76 @@
+//! Runtime support for structs marked `#[protto(any_type_url = "...")]`, which implement
+//! [`ToProtoAny`] so values of different message types can be handled as trait objects and sent
+//! as `google.protobuf.Any`.
+//!
+//! ```ignore
+//! trait DomainEvent: protto::any::ToProtoAny {}
+//!
+//! let events: Vec<Box<dyn DomainEvent>> = vec![Box::new(created), Box::new(deleted)];
+//! let packed: Vec<prost_types::Any> = events.iter().map(|event| event.to_proto_any()).collect();
+//! ```
+
+pub use prost;
+pub use prost_types;
+
+/// Converts a value into the proto message it derives its conversions for and packs it into an
+/// `Any`. The trait is dyn-compatible, so plugin registries can hold `Box<dyn ToProtoAny>` (or a
+/// trait extending it) without knowing the concrete message types.
+pub trait ToProtoAny {
+    /// The type URL the message is packed under, e.g. `type.googleapis.com/my.package.Event`
+    fn type_url(&self) -> &'static str;
+
+    /// Convert a clone of the value into its proto message and encode it as an `Any`.
+    fn to_proto_any(&self) -> prost_types::Any;
+}
+
+/// Encode `message` with prost and pack it into an `Any` under `type_url`.
+pub fn pack<M: prost::Message>(type_url: &str, message: &M) -> prost_types::Any {
+    prost_types::Any {
+        type_url: type_url.to_string(),
+        value: message.encode_to_vec(),
+    }
+}
+
+/// Decode the message packed in `any`, failing if it was packed under a different type URL or
+/// does not decode as `M`.
+pub fn unpack<M: prost::Message + Default>(
+    any: &prost_types::Any,
+    type_url: &str,
+) -> Result<M, UnpackError> {
+    if any.type_url != type_url {
+        return Err(UnpackError::TypeUrl {
+            expected: type_url.to_string(),
+            found: any.type_url.clone(),
+        });
+    }
+    M::decode(any.value.as_slice()).map_err(UnpackError::Decode)
+}
+
+/// Why [`unpack`] failed.
+#[derive(Debug)]
+pub enum UnpackError {
+    /// The `Any` holds a message of another type
+    TypeUrl { expected: String, found: String },
+    /// The packed bytes are not a valid encoding of the message
+    Decode(prost::DecodeError),
+}
+
+impl std::fmt::Display for UnpackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TypeUrl { expected, found } => {
+                write!(f, "expected an Any of {expected}, found {found}")
+            }
+            Self::Decode(err) => write!(f, "Any value did not decode: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for UnpackError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::TypeUrl { .. } => None,
+            Self::Decode(err) => Some(err),
+        }
+    }
+}
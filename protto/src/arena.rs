@@ -0,0 +1,114 @@
+//! Runtime support for structs marked `#[protto(arena)]`, which convert from a borrowed proto
+//! message into arena-backed fields allocated in a [`bumpalo::Bump`], so short-lived domain
+//! values built per request cost a pointer bump instead of a global allocation per string.
+//!
+//! The derive generates `from_proto_in(&proto, &bump)` for the struct, converting each field with
+//! [`FromProtoIn`]: strings become `&'a str`, bytes `&'a [u8]`, repeated fields
+//! `bumpalo::collections::Vec<'a, _>`, and nested `arena` structs convert in the same arena.
+//! The conversion is one-way and infallible; message fields must be `Option`s.
+//!
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(arena)]
+//! pub struct Request<'a> {
+//!     pub user: &'a str,
+//!     pub tags: bumpalo::collections::Vec<'a, &'a str>,
+//!     pub header: Option<Header<'a>>,
+//! }
+//!
+//! let bump = protto::arena::Bump::new();
+//! let request = Request::from_proto_in(&message, &bump);
+//! ```
+
+pub use bumpalo;
+pub use bumpalo::Bump;
+
+use bumpalo::collections::{String as BumpString, Vec as BumpVec};
+
+/// Converts a borrowed proto value into `Self`, allocating in the arena `bump`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be converted from `{P}` in an arena",
+    label = "fields of a struct marked `#[protto(arena)]` must implement `protto::arena::FromProtoIn`",
+    note = "use arena-backed types such as `&'a str` and `bumpalo::collections::Vec<'a, _>`, or implement `FromProtoIn` for the field's type"
+)]
+pub trait FromProtoIn<'a, P: ?Sized>: Sized {
+    /// Converts the proto value, copying its data into the arena.
+    fn from_proto_in(proto: &P, bump: &'a Bump) -> Self;
+}
+
+macro_rules! copy_from_proto_in {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<'a> FromProtoIn<'a, $ty> for $ty {
+                fn from_proto_in(proto: &$ty, _bump: &'a Bump) -> Self {
+                    *proto
+                }
+            }
+        )*
+    };
+}
+
+copy_from_proto_in!(bool, i32, i64, u32, u64, f32, f64);
+
+impl<'a> FromProtoIn<'a, String> for &'a str {
+    fn from_proto_in(proto: &String, bump: &'a Bump) -> Self {
+        bump.alloc_str(proto)
+    }
+}
+
+impl<'a> FromProtoIn<'a, String> for BumpString<'a> {
+    fn from_proto_in(proto: &String, bump: &'a Bump) -> Self {
+        BumpString::from_str_in(proto, bump)
+    }
+}
+
+impl<'a> FromProtoIn<'a, Vec<u8>> for &'a [u8] {
+    fn from_proto_in(proto: &Vec<u8>, bump: &'a Bump) -> Self {
+        bump.alloc_slice_copy(proto)
+    }
+}
+
+impl<'a, T, P> FromProtoIn<'a, Vec<P>> for BumpVec<'a, T>
+where
+    T: FromProtoIn<'a, P>,
+{
+    fn from_proto_in(proto: &Vec<P>, bump: &'a Bump) -> Self {
+        let mut values = BumpVec::with_capacity_in(proto.len(), bump);
+        values.extend(proto.iter().map(|value| T::from_proto_in(value, bump)));
+        values
+    }
+}
+
+impl<'a, T, P> FromProtoIn<'a, Option<P>> for Option<T>
+where
+    T: FromProtoIn<'a, P>,
+{
+    fn from_proto_in(proto: &Option<P>, bump: &'a Bump) -> Self {
+        proto.as_ref().map(|value| T::from_proto_in(value, bump))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_converts_into_arena_backed_values() {
+        let bump = Bump::new();
+
+        let name: &str = FromProtoIn::from_proto_in(&"ada".to_string(), &bump);
+        assert_eq!(name, "ada");
+        let blob: &[u8] = FromProtoIn::from_proto_in(&vec![1u8, 2], &bump);
+        assert_eq!(blob, &[1, 2]);
+
+        let tags: BumpVec<&str> =
+            FromProtoIn::from_proto_in(&vec!["a".to_string(), "b".to_string()], &bump);
+        assert_eq!(tags.as_slice(), &["a", "b"]);
+
+        let note: Option<BumpString> = FromProtoIn::from_proto_in(&Some("hi".to_string()), &bump);
+        assert_eq!(note.as_deref(), Some("hi"));
+        let seq: u64 = FromProtoIn::from_proto_in(&7u64, &bump);
+        assert_eq!(seq, 7);
+        assert!(bump.allocated_bytes() > 0);
+    }
+}
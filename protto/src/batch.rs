@@ -0,0 +1,135 @@
+//! Converts batches of proto messages into derived rust types, reporting the index of the first
+//! message that fails to convert.
+//!
+//! Both `From` and `TryFrom` conversions work, since every `From` is a `TryFrom` with an
+//! uninhabited error. With the `rayon` feature, [`convert_batch_par`] spreads very large batches
+//! over rayon's thread pool.
+//!
+//! ```rust,ignore
+//! let tracks: Vec<Track> = protto::batch::convert_batch(response.tracks)
+//!     .map_err(|(index, err)| format!("track {index}: {err}"))?;
+//! ```
+
+/// Converts every message, stopping at the first failure, which is returned with its index.
+pub fn convert_batch<P, T>(protos: Vec<P>) -> Result<Vec<T>, (usize, T::Error)>
+where
+    T: TryFrom<P>,
+{
+    protos
+        .into_iter()
+        .enumerate()
+        .map(|(index, proto)| T::try_from(proto).map_err(|err| (index, err)))
+        .collect()
+}
+
+/// Converts every message on rayon's thread pool, returning the failure with the lowest index
+/// when any message fails. Unlike [`convert_batch`], the messages after a failure are still
+/// converted. The [`ConvertOptions`](crate::options::ConvertOptions) in effect on the calling
+/// thread apply on every worker.
+#[cfg(feature = "rayon")]
+pub fn convert_batch_par<P, T>(protos: Vec<P>) -> Result<Vec<T>, (usize, T::Error)>
+where
+    P: Send,
+    T: TryFrom<P> + Send,
+    T::Error: Send,
+{
+    use rayon::prelude::*;
+
+    let options = crate::options::current();
+    let results: Vec<Result<T, T::Error>> = protos
+        .into_par_iter()
+        .map(|proto| crate::options::with_options(&options, || T::try_from(proto)))
+        .collect();
+    results
+        .into_iter()
+        .enumerate()
+        .map(|(index, result)| result.map_err(|err| (index, err)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Even(u32);
+
+    impl TryFrom<u32> for Even {
+        type Error = String;
+
+        fn try_from(value: u32) -> Result<Self, Self::Error> {
+            if value.is_multiple_of(2) {
+                Ok(Self(value))
+            } else {
+                Err(format!("{value} is odd"))
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_batch_reports_first_failure() {
+        assert_eq!(
+            convert_batch::<_, Even>(vec![2, 4, 6]),
+            Ok(vec![Even(2), Even(4), Even(6)])
+        );
+        assert_eq!(
+            convert_batch::<_, Even>(vec![2, 3, 5]),
+            Err((1, "3 is odd".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_convert_batch_accepts_infallible_conversions() {
+        let converted: Result<Vec<u64>, _> = convert_batch(vec![1u32, 2, 3]);
+        assert_eq!(converted.unwrap(), vec![1, 2, 3]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_convert_batch_par_reports_lowest_failing_index() {
+        let protos: Vec<u32> = (0..10_000).map(|value| value * 2).collect();
+        let converted = convert_batch_par::<_, Even>(protos.clone()).unwrap();
+        assert_eq!(converted.len(), protos.len());
+        assert_eq!(converted[4_999], Even(9_998));
+
+        let mut protos = protos;
+        protos[7_000] = 1;
+        protos[123] = 3;
+        assert_eq!(
+            convert_batch_par::<_, Even>(protos),
+            Err((123, "3 is odd".to_string()))
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_convert_batch_par_applies_the_callers_options_on_every_worker() {
+        use crate::options::{ConvertOptions, lenient_missing, with_options};
+
+        #[derive(Debug, PartialEq)]
+        struct Name(String);
+
+        impl TryFrom<Option<String>> for Name {
+            type Error = &'static str;
+
+            fn try_from(name: Option<String>) -> Result<Self, Self::Error> {
+                name.or_else(lenient_missing)
+                    .map(Self)
+                    .ok_or("name is missing")
+            }
+        }
+
+        let protos = vec![None; 10_000];
+        assert_eq!(
+            convert_batch_par::<_, Name>(protos.clone()),
+            Err((0, "name is missing"))
+        );
+
+        let converted = with_options(&ConvertOptions::LENIENT, || {
+            convert_batch_par::<_, Name>(protos)
+        })
+        .unwrap();
+        assert_eq!(converted.len(), 10_000);
+        assert!(converted.iter().all(|name| name.0.is_empty()));
+    }
+}
@@ -0,0 +1,76 @@
+//! Runtime helpers used by generated code for repeated proto fields collected into an
+//! `ArrayVec<T, N>`.
+//!
+//! A proto message can carry any number of elements, so the conversion decides what to do with
+//! those past the capacity `N`: fail with [`CapacityError`], or keep the first `N` with
+//! [`truncate`]. `SmallVec` fields need no helper, since they spill to the heap.
+
+pub use arrayvec;
+use arrayvec::ArrayVec;
+use std::fmt;
+
+/// A repeated proto field with more elements than the `ArrayVec` it converts into can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError {
+    pub len: usize,
+    pub capacity: usize,
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} elements exceed the capacity of {}",
+            self.len, self.capacity
+        )
+    }
+}
+
+impl std::error::Error for CapacityError {}
+
+/// Convert every element, failing if there are more than `N`.
+pub fn try_collect<T, U: Into<T>, const N: usize>(
+    items: Vec<U>,
+) -> Result<ArrayVec<T, N>, CapacityError> {
+    if items.len() > N {
+        return Err(CapacityError {
+            len: items.len(),
+            capacity: N,
+        });
+    }
+    Ok(items.into_iter().map(Into::into).collect())
+}
+
+/// Convert the first `N` elements, dropping the rest.
+pub fn truncate<T, U: Into<T>, const N: usize>(items: Vec<U>) -> ArrayVec<T, N> {
+    items.into_iter().take(N).map(Into::into).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_within_capacity() {
+        let collected: ArrayVec<u64, 3> = try_collect(vec![1u32, 2]).unwrap();
+        assert_eq!(collected.as_slice(), &[1, 2]);
+    }
+
+    #[test]
+    fn rejects_elements_past_capacity() {
+        let result: Result<ArrayVec<u64, 2>, _> = try_collect(vec![1u32, 2, 3]);
+        assert_eq!(
+            result,
+            Err(CapacityError {
+                len: 3,
+                capacity: 2
+            })
+        );
+    }
+
+    #[test]
+    fn truncates_to_capacity() {
+        let truncated: ArrayVec<String, 2> = truncate(vec!["a", "b", "c"]);
+        assert_eq!(truncated.as_slice(), &["a", "b"]);
+    }
+}
@@ -0,0 +1,1540 @@
+//! Build-script support for schema-driven field inference.
+//!
+//! The derive only sees the Rust side of a conversion, so by default it infers the shape of each
+//! proto field (repeated, map, optional or required) from the Rust field type and attributes.
+//! Calling [`generate_metadata`] from a `build.rs` records the actual shapes declared in the
+//! `.proto` files; the derive reads them back while expanding and prefers them over its
+//! heuristics.
+//!
+//! ```rust,ignore
+//! // build.rs
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let protos = ["proto/service.proto"];
+//!     tonic_build::configure().compile_protos(&protos, &["proto"])?;
+//!     protto::build::generate_metadata(&protos)?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The metadata is written to `$OUT_DIR/protto_metadata.txt`, where the derive looks for it. It
+//! also reads the files listed in the `PROTTO_METADATA` environment variable, so crates deriving
+//! conversions for protos compiled by another crate can use that crate's metadata: publish it with
+//! [`export_metadata`] and list it in the dependent crates with [`import_metadata`]. Every file is
+//! merged, and messages recorded differently by two files fail the derive.
+//!
+//! With the `meta-file` feature, `coverage_report` lists the proto messages with and without
+//! a derived Rust type.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "meta-file")]
+mod coverage;
+#[cfg(feature = "meta-file")]
+pub use coverage::{
+    COVERAGE_DIR_NAME, CoverageRow, DerivedField, DerivedType, coverage, coverage_report,
+    parse_coverage_record, read_coverage, render_coverage_report,
+};
+
+/// File name of the metadata written into `OUT_DIR`.
+pub const METADATA_FILE_NAME: &str = "protto_metadata.txt";
+
+/// Environment variable listing further metadata files for the derive to read, separated like
+/// `PATH`.
+pub const METADATA_ENV: &str = "PROTTO_METADATA";
+
+/// Key [`export_metadata`] publishes the metadata path under; cargo passes it to the build
+/// scripts of dependent crates as `DEP_<LINKS>_PROTTO_METADATA`.
+const EXPORT_KEY: &str = "protto_metadata";
+
+/// Keywords prost-build turns into raw identifiers when they name a field.
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "false", "fn",
+    "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+const SCALAR_TYPES: &[&str] = &[
+    "double", "float", "int32", "int64", "uint32", "uint64", "sint32", "sint64", "fixed32",
+    "fixed64", "sfixed32", "sfixed64", "bool", "string", "bytes",
+];
+
+/// Label written in front of a field declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldLabel {
+    /// No label (proto3 singular field).
+    Singular,
+    Optional,
+    Required,
+    Repeated,
+    /// A `map<K, V>` field.
+    Map,
+}
+
+/// What the field's type refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Scalar,
+    Enum,
+    Message,
+}
+
+/// Shape of the field prost generates, which is what the derive converts against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldShape {
+    /// `T`
+    Required,
+    /// `Option<T>`
+    Optional,
+    /// `Vec<T>`
+    Repeated,
+    /// `HashMap<K, V>`
+    Map,
+}
+
+impl FieldShape {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Required => "required",
+            Self::Optional => "optional",
+            Self::Repeated => "repeated",
+            Self::Map => "map",
+        }
+    }
+}
+
+impl FieldKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Scalar => "scalar",
+            Self::Enum => "enum",
+            Self::Message => "message",
+        }
+    }
+}
+
+/// A field declared in a proto message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldInfo {
+    /// Field name as written in the `.proto` file.
+    pub name: String,
+    pub label: FieldLabel,
+    /// Declared type, e.g. `string`, `Track` or `map<string,Track>`.
+    pub type_name: String,
+    pub number: u32,
+    /// The field belongs to a `oneof`, so prost moves it into a generated enum.
+    pub in_oneof: bool,
+    /// prost-build is configured to box the field, generating `Option<Box<T>>`; see
+    /// [`mark_boxed`].
+    pub boxed: bool,
+    /// Declared with the `[deprecated = true]` field option.
+    pub deprecated: bool,
+}
+
+/// A message declared in a `.proto` file. Nested messages are listed separately with their
+/// parents in `name`, e.g. `Outer.Inner`; so are proto2 `group` bodies, which prost generates as
+/// nested messages named after the group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageInfo {
+    pub name: String,
+    pub fields: Vec<FieldInfo>,
+    /// Names of the message's `oneof` blocks, each generated by prost as one enum-typed field.
+    pub oneofs: Vec<String>,
+    /// Field names listed in `reserved` statements.
+    pub reserved_names: Vec<String>,
+    /// Field number ranges listed in `reserved` statements, inclusive; `max` is
+    /// [`MAX_FIELD_NUMBER`].
+    pub reserved_numbers: Vec<(u32, u32)>,
+}
+
+/// Deepest message nesting the parser descends into; the bodies of messages nested deeper are
+/// skipped rather than recursed into, so hostile input cannot overflow the stack.
+const MAX_NESTING_DEPTH: usize = 64;
+
+/// Largest field number protobuf allows, which `max` stands for in a reserved range.
+pub const MAX_FIELD_NUMBER: u32 = 536_870_911;
+
+/// The declarations of a parsed `.proto` file that the metadata records.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtoFile {
+    pub package: Option<String>,
+    pub proto2: bool,
+    pub messages: Vec<MessageInfo>,
+    /// Enum names, qualified by their parent messages like [`MessageInfo::name`].
+    pub enums: Vec<String>,
+    /// The values each enum declares, keyed by the names in [`Self::enums`].
+    pub enum_values: BTreeMap<String, Vec<EnumValue>>,
+}
+
+/// A value declared by a proto enum, e.g. `STATUS_ACTIVE = 1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumValue {
+    pub name: String,
+    pub number: i32,
+}
+
+/// Error raised while generating metadata.
+#[derive(Debug)]
+pub enum MetadataError {
+    Io(PathBuf, io::Error),
+    MissingOutDir,
+    /// A metadata path holds the separator `PROTTO_METADATA` lists paths with
+    UnjoinablePaths(std::env::JoinPathsError),
+}
+
+impl fmt::Display for MetadataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "{}: {err}", path.display()),
+            Self::MissingOutDir => write!(f, "OUT_DIR is not set; call from a build script"),
+            Self::UnjoinablePaths(err) => {
+                write!(f, "cannot list the metadata paths in {METADATA_ENV}: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MetadataError {}
+
+/// How the prost-generated code is laid out under the `module` a derive names.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ModuleLayout {
+    /// Every package is included directly into the module, e.g. `proto::Track`.
+    #[default]
+    Flat,
+    /// Each package is nested in modules following its path, as prost-build's `include_file`
+    /// generates them, e.g. `proto::media::v1::Track` for package `media.v1`. The derive adds
+    /// the package path to `module` itself.
+    Nested,
+}
+
+/// Parse the `.proto` files, write their metadata to `$OUT_DIR` and register the files with
+/// cargo's change detection. Returns the path of the metadata file.
+pub fn generate_metadata(proto_files: &[impl AsRef<Path>]) -> Result<PathBuf, MetadataError> {
+    generate_metadata_with_boxed(proto_files, &[] as &[&str])
+}
+
+/// Like [`generate_metadata`], for builds that nest each package in its own modules. The derive
+/// then resolves `#[protto(module = "proto")]` on a `Track` of package `media.v1` to
+/// `proto::media::v1::Track`.
+///
+/// ```rust,ignore
+/// prost_build::Config::new().include_file("protos.rs").compile_protos(&protos, &["proto"])?;
+/// protto::build::generate_nested_metadata(&protos)?;
+/// ```
+pub fn generate_nested_metadata(
+    proto_files: &[impl AsRef<Path>],
+) -> Result<PathBuf, MetadataError> {
+    generate_metadata_with_layout(proto_files, &[] as &[&str], ModuleLayout::Nested)
+}
+
+/// Like [`generate_metadata`], for builds that box fields through prost-build's `boxed`. Given
+/// the same paths, the derive unboxes those fields without `#[protto(proto_boxed)]`.
+///
+/// ```rust,ignore
+/// prost_build::Config::new().boxed(".service.TreeNode.parent").compile_protos(&protos, &["proto"])?;
+/// protto::build::generate_metadata_with_boxed(&protos, &[".service.TreeNode.parent"])?;
+/// ```
+pub fn generate_metadata_with_boxed(
+    proto_files: &[impl AsRef<Path>],
+    boxed: &[impl AsRef<str>],
+) -> Result<PathBuf, MetadataError> {
+    generate_metadata_with_layout(proto_files, boxed, ModuleLayout::Flat)
+}
+
+/// Like [`generate_metadata_with_boxed`], for a build laid out as `layout` describes.
+pub fn generate_metadata_with_layout(
+    proto_files: &[impl AsRef<Path>],
+    boxed: &[impl AsRef<str>],
+    layout: ModuleLayout,
+) -> Result<PathBuf, MetadataError> {
+    let out_dir = std::env::var_os("OUT_DIR").ok_or(MetadataError::MissingOutDir)?;
+    let mut parsed = Vec::with_capacity(proto_files.len());
+    for path in proto_files {
+        let path = path.as_ref();
+        println!("cargo:rerun-if-changed={}", path.display());
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| MetadataError::Io(path.to_path_buf(), err))?;
+        parsed.push(parse_proto_content(&content));
+    }
+    mark_boxed(&mut parsed, boxed);
+
+    let metadata_path = Path::new(&out_dir).join(METADATA_FILE_NAME);
+    std::fs::write(&metadata_path, render_metadata_with_layout(&parsed, layout))
+        .map_err(|err| MetadataError::Io(metadata_path.clone(), err))?;
+    Ok(metadata_path)
+}
+
+/// Publishes a metadata file to the build scripts of the crates depending on this one, for a
+/// crate compiling protos that other crates derive conversions for. Cargo only passes the path on
+/// from packages declaring `links` in their `Cargo.toml`.
+///
+/// ```rust,ignore
+/// // shared_protos/build.rs, with `links = "shared_protos"` in its Cargo.toml
+/// let metadata = protto::build::generate_metadata(&protos)?;
+/// protto::build::export_metadata(&metadata);
+/// ```
+pub fn export_metadata(metadata_path: impl AsRef<Path>) {
+    println!("cargo:{EXPORT_KEY}={}", metadata_path.as_ref().display());
+}
+
+/// Points the derive at the metadata of the crate's direct dependencies published with
+/// [`export_metadata`], adding them to the paths already in `PROTTO_METADATA`. The derive merges
+/// them with the crate's own metadata and fails the build on messages the files disagree on.
+/// Returns the paths listed.
+///
+/// ```rust,ignore
+/// // leaf crate build.rs
+/// protto::build::import_metadata()?;
+/// ```
+pub fn import_metadata() -> Result<Vec<PathBuf>, MetadataError> {
+    println!("cargo:rerun-if-env-changed={METADATA_ENV}");
+    let mut paths: Vec<PathBuf> = std::env::var_os(METADATA_ENV)
+        .map(|paths| std::env::split_paths(&paths).collect())
+        .unwrap_or_default();
+    for path in dependency_metadata_paths(std::env::vars_os()) {
+        println!("cargo:rerun-if-changed={}", path.display());
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths.retain(|path| !path.as_os_str().is_empty());
+
+    if !paths.is_empty() {
+        let joined = std::env::join_paths(&paths).map_err(MetadataError::UnjoinablePaths)?;
+        println!(
+            "cargo:rustc-env={METADATA_ENV}={}",
+            joined.to_string_lossy()
+        );
+    }
+    Ok(paths)
+}
+
+/// The metadata paths dependencies published with [`export_metadata`], ordered by variable name.
+fn dependency_metadata_paths(
+    vars: impl IntoIterator<Item = (std::ffi::OsString, std::ffi::OsString)>,
+) -> Vec<PathBuf> {
+    let suffix = format!("_{}", EXPORT_KEY.to_uppercase());
+    let mut exported: Vec<(String, PathBuf)> = vars
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let key = key.into_string().ok()?;
+            (key.starts_with("DEP_") && key.ends_with(&suffix)).then(|| (key, value.into()))
+        })
+        .collect();
+    exported.sort();
+    exported.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Flag the fields matched by prost-build `boxed` paths. A path starting with `.` is fully
+/// qualified and matches the field or everything under the package or message it names, e.g.
+/// `.service.TreeNode`; other paths match by suffix, e.g. `TreeNode.parent`.
+pub fn mark_boxed(files: &mut [ProtoFile], paths: &[impl AsRef<str>]) {
+    for file in files.iter_mut() {
+        let package = file
+            .package
+            .as_deref()
+            .map(|p| format!(".{p}"))
+            .unwrap_or_default();
+        for message in file.messages.iter_mut() {
+            for field in message.fields.iter_mut() {
+                let field_path = format!("{package}.{}.{}", message.name, field.name);
+                if paths
+                    .iter()
+                    .any(|path| boxed_path_matches(path.as_ref(), &field_path))
+                {
+                    field.boxed = true;
+                }
+            }
+        }
+    }
+}
+
+fn boxed_path_matches(path: &str, field_path: &str) -> bool {
+    if path == "." {
+        true
+    } else if path.starts_with('.') {
+        field_path == path || field_path.starts_with(&format!("{path}."))
+    } else {
+        field_path.ends_with(&format!(".{path}"))
+    }
+}
+
+/// Render the metadata for a set of parsed files.
+///
+/// Each message becomes a `message <package>.<Name>` line followed by one
+/// `field <prost_name> <shape> <kind> <type>` line per field, ending in `boxed` for fields
+/// prost boxes: recursive message fields, and those flagged by [`mark_boxed`]. A field prost
+/// renames, such as `type` or `startMs`, is followed by `rename <proto_name> <prost_ident>`.
+/// Fields inside a `oneof` are omitted because prost does not generate them on the message
+/// struct; each `oneof` block is listed instead as `oneof <prost_ident>`, the field prost
+/// generates for it.
+///
+/// Each package is listed once as `package <name> <module path>`, the modules prost generates
+/// for it, and each enum as `enum <package>.<Name>` followed by one `value <NAME> <number>` line
+/// per value it declares.
+pub fn render_metadata(files: &[ProtoFile]) -> String {
+    render_metadata_with_layout(files, ModuleLayout::Flat)
+}
+
+/// Like [`render_metadata`], starting with a `layout nested` line for [`ModuleLayout::Nested`].
+pub fn render_metadata_with_layout(files: &[ProtoFile], layout: ModuleLayout) -> String {
+    let declarations = Declarations::new(files);
+    let graph = message_graph(files, &declarations);
+
+    let mut out = String::from("# generated by protto::build\n");
+    if layout == ModuleLayout::Nested {
+        out.push_str("layout nested\n");
+    }
+    let mut packages = HashSet::new();
+    for package in files.iter().filter_map(|file| file.package.as_deref()) {
+        if packages.insert(package) {
+            out.push_str(&format!(
+                "package {package} {}\n",
+                prost_module_path(package)
+            ));
+        }
+    }
+    for file in files {
+        for name in &file.enums {
+            match &file.package {
+                Some(package) => out.push_str(&format!("enum {package}.{name}\n")),
+                None => out.push_str(&format!("enum {name}\n")),
+            }
+            for value in file.enum_values.get(name).into_iter().flatten() {
+                out.push_str(&format!("value {} {}\n", value.name, value.number));
+            }
+        }
+        for message in &file.messages {
+            let qualified = qualified_name(file.package.as_deref(), &message.name);
+            out.push_str(&format!("message {qualified}\n"));
+            for field in message.fields.iter().filter(|field| !field.in_oneof) {
+                let kind = declarations.field_kind(field, &qualified);
+                out.push_str(&format!(
+                    "field {} {} {} {}{}\n",
+                    prost_field_name(&field.name),
+                    field_shape(field, kind, file.proto2).as_str(),
+                    kind.as_str(),
+                    field.type_name,
+                    if field.boxed || is_recursive(&graph, &declarations, &qualified, field) {
+                        " boxed"
+                    } else {
+                        ""
+                    },
+                ));
+                let ident = prost_field_ident(&field.name);
+                if ident != field.name {
+                    out.push_str(&format!("rename {} {ident}\n", field.name));
+                }
+            }
+            for oneof in &message.oneofs {
+                out.push_str(&format!("oneof {}\n", prost_field_ident(oneof)));
+            }
+        }
+    }
+    out
+}
+
+/// The messages and enums the parsed files declare, by fully qualified name such as
+/// `media.v1.Track.Segment`, for resolving the types fields name.
+struct Declarations<'a> {
+    messages: HashSet<String>,
+    enums: HashSet<String>,
+    /// Simple names of the enums, for a type name that does not resolve
+    enum_names: Vec<&'a str>,
+}
+
+impl<'a> Declarations<'a> {
+    fn new(files: &'a [ProtoFile]) -> Self {
+        let qualified =
+            |file: &ProtoFile, name: &str| qualified_name(file.package.as_deref(), name);
+        Self {
+            messages: files
+                .iter()
+                .flat_map(|file| file.messages.iter().map(|m| qualified(file, &m.name)))
+                .collect(),
+            enums: files
+                .iter()
+                .flat_map(|file| file.enums.iter().map(|name| qualified(file, name)))
+                .collect(),
+            enum_names: files
+                .iter()
+                .flat_map(|file| file.enums.iter())
+                .map(|name| simple_name(name))
+                .collect(),
+        }
+    }
+
+    /// Resolve a type named inside message `scope` as protoc does: a leading `.` makes it fully
+    /// qualified, otherwise the innermost enclosing scope declaring it wins, so `Inner` inside
+    /// `Outer.Inner` is `Outer.Inner.Inner` when that is declared. `None` when no parsed file
+    /// declares it.
+    fn resolve(&self, scope: &str, type_name: &str) -> Option<String> {
+        let declared = |name: &String| self.messages.contains(name) || self.enums.contains(name);
+        if let Some(qualified) = type_name.strip_prefix('.') {
+            return Some(qualified.to_string()).filter(declared);
+        }
+        let mut scope = scope;
+        loop {
+            let candidate = qualified_name(Some(scope).filter(|s| !s.is_empty()), type_name);
+            if declared(&candidate) {
+                return Some(candidate);
+            }
+            if scope.is_empty() {
+                return None;
+            }
+            scope = scope.rsplit_once('.').map_or("", |(parent, _)| parent);
+        }
+    }
+
+    fn field_kind(&self, field: &FieldInfo, scope: &str) -> FieldKind {
+        let value_type = match field.label {
+            FieldLabel::Map => map_value_type(&field.type_name),
+            _ => field.type_name.as_str(),
+        };
+        if SCALAR_TYPES.contains(&value_type) {
+            return FieldKind::Scalar;
+        }
+        match self.resolve(scope, value_type) {
+            Some(resolved) if self.enums.contains(&resolved) => FieldKind::Enum,
+            Some(_) => FieldKind::Message,
+            None if self.enum_names.contains(&simple_name(value_type)) => FieldKind::Enum,
+            None => FieldKind::Message,
+        }
+    }
+}
+
+/// `name` qualified with the file's package, if it has one.
+fn qualified_name(package: Option<&str>, name: &str) -> String {
+    match package {
+        Some(package) => format!("{package}.{name}"),
+        None => name.to_string(),
+    }
+}
+
+/// The message types each message holds in singular fields (oneofs included), by fully
+/// qualified name.
+fn message_graph(files: &[ProtoFile], declarations: &Declarations) -> HashMap<String, Vec<String>> {
+    let mut graph: HashMap<String, Vec<String>> = HashMap::new();
+    for file in files {
+        for message in &file.messages {
+            let qualified = qualified_name(file.package.as_deref(), &message.name);
+            let nested: Vec<_> = message
+                .fields
+                .iter()
+                .filter(|field| !matches!(field.label, FieldLabel::Repeated | FieldLabel::Map))
+                .filter(|field| declarations.field_kind(field, &qualified) == FieldKind::Message)
+                .filter_map(|field| declarations.resolve(&qualified, &field.type_name))
+                .collect();
+            graph.entry(qualified).or_default().extend(nested);
+        }
+    }
+    graph
+}
+
+/// Mirrors prost-build, which boxes a singular message field whose type leads back to the
+/// message containing it, since the struct would otherwise have infinite size.
+fn is_recursive(
+    graph: &HashMap<String, Vec<String>>,
+    declarations: &Declarations,
+    message: &str,
+    field: &FieldInfo,
+) -> bool {
+    if matches!(field.label, FieldLabel::Repeated | FieldLabel::Map) {
+        return false;
+    }
+    let Some(field_type) = declarations.resolve(message, &field.type_name) else {
+        return false;
+    };
+    let mut visited = HashSet::new();
+    let mut pending = vec![field_type.as_str()];
+    while let Some(name) = pending.pop() {
+        if name == message {
+            return true;
+        }
+        if visited.insert(name) {
+            pending.extend(graph.get(name).into_iter().flatten().map(String::as_str));
+        }
+    }
+    false
+}
+
+/// Mirrors prost-build: message fields and explicitly optional fields become `Option<T>`, except
+/// proto2 `required` fields.
+fn field_shape(field: &FieldInfo, kind: FieldKind, proto2: bool) -> FieldShape {
+    match field.label {
+        FieldLabel::Repeated => FieldShape::Repeated,
+        FieldLabel::Map => FieldShape::Map,
+        FieldLabel::Required => FieldShape::Required,
+        FieldLabel::Optional => FieldShape::Optional,
+        FieldLabel::Singular if kind == FieldKind::Message || proto2 => FieldShape::Optional,
+        FieldLabel::Singular => FieldShape::Required,
+    }
+}
+
+fn map_value_type(type_name: &str) -> &str {
+    type_name
+        .trim_start_matches("map<")
+        .trim_end_matches('>')
+        .split_once(',')
+        .map(|(_, value)| value)
+        .unwrap_or(type_name)
+}
+
+fn simple_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// Identifier prost generates for a proto field: the snake_case name, as a raw identifier when
+/// it is a Rust keyword (`r#type`) and with a trailing `_` when it cannot be one (`self_`).
+pub fn prost_field_ident(name: &str) -> String {
+    let snake = crate::naming::to_snake_case(name);
+    match snake.as_str() {
+        "self" | "super" | "crate" | "extern" => format!("{snake}_"),
+        keyword if RUST_KEYWORDS.contains(&keyword) => format!("r#{snake}"),
+        _ => snake,
+    }
+}
+
+/// Module path prost generates for a proto package, one snake_case module per segment, e.g.
+/// `media::v1` for `media.v1`.
+pub fn prost_module_path(package: &str) -> String {
+    package
+        .split('.')
+        .map(prost_field_ident)
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Name of the field on the prost-generated struct, without any `r#` prefix.
+fn prost_field_name(name: &str) -> String {
+    let ident = prost_field_ident(name);
+    match ident.strip_prefix("r#") {
+        Some(stripped) => stripped.to_string(),
+        None => ident,
+    }
+}
+
+/// Parse the message, field and enum declarations of a `.proto` file.
+///
+/// This is a lightweight scanner rather than a full protobuf parser: it tracks `package`,
+/// `syntax`, `message`, `enum`, `oneof` and proto2 `group` blocks, field declarations with their
+/// `deprecated` option, and `reserved` names and numbers. Everything else (other options,
+/// services, extensions) is skipped. Input that is not valid proto never panics; whatever can
+/// be recognized in it is returned.
+pub fn parse_proto_content(content: &str) -> ProtoFile {
+    let tokens = tokenize(content);
+    let mut file = ProtoFile::default();
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        depth: 0,
+    };
+    parser.parse_body(&mut file, &[], None);
+    file
+}
+
+struct Parser<'t> {
+    tokens: &'t [String],
+    pos: usize,
+    /// Number of message bodies being parsed
+    depth: usize,
+}
+
+impl<'t> Parser<'t> {
+    fn next(&mut self) -> Option<&'t str> {
+        let token = self.tokens.get(self.pos).map(String::as_str);
+        self.pos += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&'t str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    /// Take the next token if it can name a declaration or a type.
+    fn declaration_name(&mut self) -> Option<String> {
+        let name = self
+            .peek()
+            .filter(|token| is_identifier(token))?
+            .to_string();
+        self.pos += 1;
+        Some(name)
+    }
+
+    /// Skip to the end of the current statement, including any block it opens. A `}` ends the
+    /// enclosing block, so it is left for the block's parser even if the statement lacks its `;`.
+    fn skip_statement(&mut self) {
+        while let Some(token) = self.peek() {
+            self.pos += 1;
+            match token {
+                ";" => return,
+                "{" => {
+                    self.skip_block();
+                    return;
+                }
+                "}" => {
+                    self.pos -= 1;
+                    return;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Skip past the `}` closing a block whose `{` was just consumed.
+    fn skip_block(&mut self) {
+        let mut depth = 1;
+        while let Some(token) = self.next() {
+            match token {
+                "{" => depth += 1,
+                "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Parse declarations until the closing `}` (or end of input at file level). Fields are
+    /// collected into `message` when inside a message body.
+    fn parse_body(
+        &mut self,
+        file: &mut ProtoFile,
+        scope: &[String],
+        mut message: Option<&mut MessageInfo>,
+    ) {
+        let mut in_oneof = false;
+        while let Some(token) = self.peek() {
+            match token {
+                "}" => {
+                    self.pos += 1;
+                    if in_oneof {
+                        in_oneof = false;
+                        continue;
+                    }
+                    // An unbalanced `}` at file level closes nothing
+                    if message.is_none() {
+                        continue;
+                    }
+                    return;
+                }
+                ";" => self.pos += 1,
+                "syntax" => {
+                    self.pos += 1;
+                    while let Some(token) = self.next() {
+                        if token == ";" {
+                            break;
+                        }
+                        if token.trim_matches(['"', '\'']) == "proto2" {
+                            file.proto2 = true;
+                        }
+                    }
+                }
+                "package" => {
+                    self.pos += 1;
+                    file.package = self.declaration_name();
+                    self.skip_statement();
+                }
+                "message" => {
+                    self.pos += 1;
+                    match self.declaration_name() {
+                        Some(name) if self.peek() == Some("{") => {
+                            self.pos += 1;
+                            self.parse_message(file, scope, name);
+                        }
+                        _ => self.skip_statement(),
+                    }
+                }
+                "enum" => {
+                    self.pos += 1;
+                    match self.declaration_name() {
+                        Some(name) if self.peek() == Some("{") => {
+                            self.pos += 1;
+                            let mut qualified = scope.to_vec();
+                            qualified.push(name);
+                            let qualified = qualified.join(".");
+                            let values = self.parse_enum_values();
+                            file.enum_values.insert(qualified.clone(), values);
+                            file.enums.push(qualified);
+                        }
+                        _ => self.skip_statement(),
+                    }
+                }
+                "oneof" if message.is_some() => {
+                    self.pos += 1;
+                    match self.declaration_name() {
+                        Some(name) if self.peek() == Some("{") => {
+                            self.pos += 1;
+                            if let Some(message) = message.as_deref_mut() {
+                                message.oneofs.push(name);
+                            }
+                            in_oneof = true;
+                        }
+                        _ => self.skip_statement(),
+                    }
+                }
+                "reserved" if message.is_some() => {
+                    self.pos += 1;
+                    if let Some(message) = message.as_deref_mut() {
+                        self.parse_reserved(message);
+                    }
+                }
+                "option" | "reserved" | "extensions" | "import" | "service" | "extend" => {
+                    self.skip_statement();
+                }
+                _ => match message.as_deref_mut() {
+                    Some(message) if self.is_group() => {
+                        if let Some(field) = self.parse_group(file, scope, in_oneof) {
+                            message.fields.push(field);
+                        }
+                    }
+                    Some(message) => {
+                        if let Some(field) = self.parse_field(in_oneof) {
+                            message.fields.push(field);
+                        }
+                    }
+                    None => self.skip_statement(),
+                },
+            }
+        }
+    }
+
+    /// Parse the body of message `name`, whose `{` was just consumed, listing it in `file`
+    /// ahead of the messages nested in it.
+    fn parse_message(&mut self, file: &mut ProtoFile, scope: &[String], name: String) {
+        if self.depth == MAX_NESTING_DEPTH {
+            self.skip_block();
+            return;
+        }
+
+        let mut nested_scope = scope.to_vec();
+        nested_scope.push(name);
+        let mut nested = MessageInfo {
+            name: nested_scope.join("."),
+            fields: Vec::new(),
+            oneofs: Vec::new(),
+            reserved_names: Vec::new(),
+            reserved_numbers: Vec::new(),
+        };
+        let index = file.messages.len();
+        file.messages.push(nested.clone());
+        self.depth += 1;
+        self.parse_body(file, &nested_scope, Some(&mut nested));
+        self.depth -= 1;
+        file.messages[index] = nested;
+    }
+
+    /// Parse the `NAME = number [options];` values of an enum whose `{` was just consumed, up to
+    /// its closing `}`.
+    fn parse_enum_values(&mut self) -> Vec<EnumValue> {
+        let mut values = Vec::new();
+        while let Some(token) = self.next() {
+            match token {
+                "}" => break,
+                ";" => {}
+                "option" | "reserved" => self.skip_statement(),
+                name if !is_identifier(name) => self.skip_statement(),
+                name => {
+                    let name = name.to_string();
+                    let number = if self.peek() == Some("=") {
+                        self.pos += 1;
+                        self.next().and_then(parse_enum_number)
+                    } else {
+                        None
+                    };
+                    self.parse_field_options();
+                    self.skip_statement();
+                    if let Some(number) = number {
+                        values.push(EnumValue { name, number });
+                    }
+                }
+            }
+        }
+        values
+    }
+
+    /// Parse the rest of `reserved 2, 9 to 11, 40 to max;` or `reserved "foo", "bar";`.
+    fn parse_reserved(&mut self, message: &mut MessageInfo) {
+        let mut pending_start = None;
+        while let Some(token) = self.next() {
+            match token {
+                ";" => break,
+                "," => {}
+                "to" => {}
+                token if token.starts_with(['"', '\'']) => {
+                    message
+                        .reserved_names
+                        .push(token.trim_matches(['"', '\'']).to_string());
+                }
+                token => {
+                    let Some(number) = parse_field_number(token) else {
+                        continue;
+                    };
+                    if self.peek() == Some("to") {
+                        pending_start = Some(number);
+                    } else {
+                        let start = pending_start.take().unwrap_or(number);
+                        message.reserved_numbers.push((start, number));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether the statement ahead is a proto2 `[label] group Name = number { ... }`.
+    fn is_group(&self) -> bool {
+        let label = matches!(self.peek(), Some("optional" | "required" | "repeated"));
+        let offset = usize::from(label);
+        self.tokens.get(self.pos + offset).map(String::as_str) == Some("group")
+    }
+
+    /// Parse a proto2 group: its body becomes a nested message named after the group, and the
+    /// group itself a field of that type named in lowercase, as protoc and prost see it.
+    fn parse_group(
+        &mut self,
+        file: &mut ProtoFile,
+        scope: &[String],
+        in_oneof: bool,
+    ) -> Option<FieldInfo> {
+        let label = self.parse_label();
+        self.next(); // `group`
+        let name = self.declaration_name()?;
+        let number = if self.peek() == Some("=") {
+            self.pos += 1;
+            self.next().and_then(parse_field_number)
+        } else {
+            None
+        };
+        let deprecated = self.parse_field_options();
+        if self.next() != Some("{") {
+            self.skip_statement();
+            return None;
+        }
+
+        self.parse_message(file, scope, name.clone());
+
+        Some(FieldInfo {
+            name: name.to_lowercase(),
+            label,
+            type_name: name,
+            number: number?,
+            in_oneof,
+            boxed: false,
+            deprecated,
+        })
+    }
+
+    fn parse_label(&mut self) -> FieldLabel {
+        let label = match self.peek() {
+            Some("optional") => FieldLabel::Optional,
+            Some("required") => FieldLabel::Required,
+            Some("repeated") => FieldLabel::Repeated,
+            _ => FieldLabel::Singular,
+        };
+        if label != FieldLabel::Singular {
+            self.pos += 1;
+        }
+        label
+    }
+
+    /// Parse a `[name = value, ...]` option list if one follows, returning whether it sets
+    /// `deprecated = true`.
+    fn parse_field_options(&mut self) -> bool {
+        if self.peek() != Some("[") {
+            return false;
+        }
+        self.pos += 1;
+        let mut deprecated = false;
+        let mut depth = 1;
+        while let Some(token) = self.next() {
+            match token {
+                "[" | "{" => depth += 1,
+                "]" | "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                "deprecated" if depth == 1 => {
+                    deprecated = self.peek() == Some("=")
+                        && self.tokens.get(self.pos + 1).map(String::as_str) == Some("true");
+                }
+                _ => {}
+            }
+        }
+        deprecated
+    }
+
+    /// Parse `[label] type name = number [options];`.
+    fn parse_field(&mut self, in_oneof: bool) -> Option<FieldInfo> {
+        let mut label = self.parse_label();
+
+        let Some(mut type_name) = self.declaration_name() else {
+            self.skip_statement();
+            return None;
+        };
+        if type_name == "map" && self.peek() == Some("<") {
+            label = FieldLabel::Map;
+            while let Some(token) = self.peek() {
+                if matches!(token, ";" | "{" | "}") {
+                    break;
+                }
+                let closed = token == ">";
+                type_name.push_str(token);
+                self.pos += 1;
+                if closed {
+                    break;
+                }
+            }
+            let well_formed = type_name.ends_with('>')
+                && type_name
+                    .split(['<', ',', '>'])
+                    .filter(|part| !part.is_empty())
+                    .all(is_identifier);
+            if !well_formed {
+                self.skip_statement();
+                return None;
+            }
+        }
+
+        let Some(name) = self.declaration_name() else {
+            self.skip_statement();
+            return None;
+        };
+        let number = if self.peek() == Some("=") {
+            self.pos += 1;
+            self.next().and_then(parse_field_number)
+        } else {
+            None
+        };
+        let deprecated = self.parse_field_options();
+        self.skip_statement();
+
+        // Anything without `= number` is not a field declaration and has been skipped.
+        Some(FieldInfo {
+            name,
+            label,
+            type_name,
+            number: number?,
+            in_oneof,
+            boxed: false,
+            deprecated,
+        })
+    }
+}
+
+/// Whether a token can name a declaration or a type: an identifier, possibly qualified with
+/// dots like `.media.v1.Track`. String literals and numbers cannot.
+fn is_identifier(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '.')
+        && token
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+}
+
+/// A field number: decimal, `0x` hex or `0` octal as protobuf accepts them, or `max`.
+fn parse_field_number(token: &str) -> Option<u32> {
+    if token == "max" {
+        Some(MAX_FIELD_NUMBER)
+    } else if let Some(hex) = token
+        .strip_prefix("0x")
+        .or_else(|| token.strip_prefix("0X"))
+    {
+        u32::from_str_radix(hex, 16).ok()
+    } else if token.len() > 1 && token.starts_with('0') {
+        u32::from_str_radix(&token[1..], 8).ok()
+    } else {
+        token.parse().ok()
+    }
+}
+
+/// An enum value's number: decimal, `0x` hex or `0` octal, possibly negative.
+fn parse_enum_number(token: &str) -> Option<i32> {
+    match token.strip_prefix('-') {
+        Some(magnitude) => parse_field_number(magnitude)
+            .filter(|_| magnitude != "max")
+            .map(|number| -i64::from(number))
+            .and_then(|number| i32::try_from(number).ok()),
+        None if token == "max" => None,
+        None => parse_field_number(token).and_then(|number| i32::try_from(number).ok()),
+    }
+}
+
+/// Split proto source into identifiers, numbers, string literals and single-character
+/// punctuation, dropping comments. A backslash escapes the next character of a string literal,
+/// so an escaped quote does not end it.
+fn tokenize(content: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            '"' | '\'' => {
+                let mut literal = String::from(c);
+                while let Some(next) = chars.next() {
+                    literal.push(next);
+                    if next == '\\' {
+                        literal.extend(chars.next());
+                    } else if next == c {
+                        break;
+                    }
+                }
+                tokens.push(literal);
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut word = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if next.is_alphanumeric() || next == '_' || next == '.' {
+                        word.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(word);
+            }
+            c => tokens.push(c.to_string()),
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERVICE_PROTO: &str = r#"
+        syntax = "proto3";
+        package service;
+
+        // A track.
+        message Track {
+            uint64 track_id = 1;
+            optional string name = 2 [deprecated = true];
+            repeated string tags = 3;
+            map<string, Track> related = 4;
+            Header header = 5;
+            Status status = 6;
+            oneof payload {
+                string text = 7;
+                bytes blob = 8;
+            }
+            /* nested */
+            message Segment { int32 startMs = 1; }
+            reserved 9, 10;
+        }
+
+        message Header { string request_id = 1; }
+
+        enum Status {
+            STATUS_OK = 0;
+        }
+
+        service Tracks {
+            rpc Get(Track) returns (Track) {}
+        }
+    "#;
+
+    #[test]
+    fn test_dependency_metadata_paths_from_links_variables() {
+        let vars = [
+            (
+                "DEP_MEDIA_PROTOS_PROTTO_METADATA",
+                "/out/media/protto_metadata.txt",
+            ),
+            ("CARGO_PKG_NAME", "leaf"),
+            (
+                "DEP_BILLING_PROTTO_METADATA",
+                "/out/billing/protto_metadata.txt",
+            ),
+            ("DEP_OPENSSL_INCLUDE", "/usr/include"),
+        ]
+        .map(|(key, value)| (key.into(), value.into()));
+
+        assert_eq!(
+            dependency_metadata_paths(vars),
+            [
+                PathBuf::from("/out/billing/protto_metadata.txt"),
+                PathBuf::from("/out/media/protto_metadata.txt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_messages_fields_and_enums() {
+        let file = parse_proto_content(SERVICE_PROTO);
+        assert_eq!(file.package.as_deref(), Some("service"));
+        assert!(!file.proto2);
+        assert_eq!(file.enums, vec!["Status".to_string()]);
+
+        let names: Vec<_> = file.messages.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["Track", "Track.Segment", "Header"]);
+
+        let track = &file.messages[0];
+        let labels: Vec<_> = track
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.label, f.in_oneof))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("track_id", FieldLabel::Singular, false),
+                ("name", FieldLabel::Optional, false),
+                ("tags", FieldLabel::Repeated, false),
+                ("related", FieldLabel::Map, false),
+                ("header", FieldLabel::Singular, false),
+                ("status", FieldLabel::Singular, false),
+                ("text", FieldLabel::Singular, true),
+                ("blob", FieldLabel::Singular, true),
+            ]
+        );
+        assert_eq!(track.fields[3].type_name, "map<string,Track>");
+        assert_eq!(track.oneofs, vec!["payload".to_string()]);
+        assert_eq!(track.fields[3].number, 4);
+    }
+
+    #[test]
+    fn test_render_metadata_uses_prost_shapes() {
+        let metadata = render_metadata(&[parse_proto_content(SERVICE_PROTO)]);
+        let lines: Vec<_> = metadata.lines().skip(1).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "package service service",
+                "enum service.Status",
+                "value STATUS_OK 0",
+                "message service.Track",
+                "field track_id required scalar uint64",
+                "field name optional scalar string",
+                "field tags repeated scalar string",
+                "field related map message map<string,Track>",
+                "field header optional message Header",
+                "field status required enum Status",
+                "oneof payload",
+                "message service.Track.Segment",
+                "field start_ms required scalar int32",
+                "rename startMs start_ms",
+                "message service.Header",
+                "field request_id required scalar string",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_layout_records_package_modules() {
+        let files = [
+            parse_proto_content("package media.v1; message Track {} enum Kind { KIND_UNSET = 0; }"),
+            parse_proto_content("package media.v1; message Album {}"),
+            parse_proto_content("package acme.mediaLibrary.type; message Shelf {}"),
+        ];
+        let metadata = render_metadata_with_layout(&files, ModuleLayout::Nested);
+        let lines: Vec<_> = metadata.lines().skip(1).take(6).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "layout nested",
+                "package media.v1 media::v1",
+                "package acme.mediaLibrary.type acme::media_library::r#type",
+                "enum media.v1.Kind",
+                "value KIND_UNSET 0",
+                "message media.v1.Track",
+            ]
+        );
+        assert!(!render_metadata(&files).contains("layout"));
+    }
+
+    #[test]
+    fn test_parse_enum_values() {
+        let file = parse_proto_content(
+            "message Job {
+                enum State {
+                    option allow_alias = true;
+                    STATE_UNSPECIFIED = 0;
+                    STATE_ACTIVE = 1;
+                    STATE_RUNNING = 1;
+                    STATE_DISABLED = 0x5 [deprecated = true];
+                    STATE_FAILED = -2;
+                    reserved 3, 4;
+                }
+                State state = 1;
+             }",
+        );
+        assert_eq!(file.enums, vec!["Job.State".to_string()]);
+        let values: Vec<_> = file.enum_values["Job.State"]
+            .iter()
+            .map(|value| (value.name.as_str(), value.number))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("STATE_UNSPECIFIED", 0),
+                ("STATE_ACTIVE", 1),
+                ("STATE_RUNNING", 1),
+                ("STATE_DISABLED", 5),
+                ("STATE_FAILED", -2),
+            ]
+        );
+        assert_eq!(file.messages[0].fields[0].name, "state");
+        assert!(render_metadata(&[file]).contains("enum Job.State\nvalue STATE_UNSPECIFIED 0\n"));
+    }
+
+    #[test]
+    fn test_recursive_message_fields_are_boxed() {
+        let file = parse_proto_content(
+            "message Node { Node parent = 1; repeated Node children = 2; Leaf leaf = 3; }
+             message Leaf { Branch branch = 1; string name = 2; }
+             message Branch { Leaf leaf = 1; }",
+        );
+        let metadata = render_metadata(&[file]);
+        assert!(metadata.contains("field parent optional message Node boxed\n"));
+        assert!(metadata.contains("field children repeated message Node\n"));
+        assert!(metadata.contains("field leaf optional message Leaf\n"));
+        assert!(metadata.contains("field branch optional message Branch boxed\n"));
+    }
+
+    #[test]
+    fn test_field_types_resolve_in_the_innermost_scope() {
+        let file = parse_proto_content(
+            "package media;
+             message Outer {
+                 message Inner { message Inner { int32 id = 1; } Inner inner = 1; }
+                 Inner inner = 1;
+                 Status status = 2;
+                 .media.Outer outer = 3;
+             }
+             message Status { Outer.Inner.Inner deepest = 1; }
+             message Holder { enum Status { STATUS_OK = 0; } Status status = 1; }",
+        );
+        let metadata = render_metadata(&[file]);
+        // `Inner` inside `Outer.Inner` is `Outer.Inner.Inner`, which does not lead back
+        assert!(
+            metadata.contains("message media.Outer.Inner\nfield inner optional message Inner\n")
+        );
+        assert!(metadata.contains("field status optional message Status\n"));
+        assert!(metadata.contains("field outer optional message .media.Outer boxed\n"));
+        assert!(metadata.contains("field status required enum Status\n"));
+    }
+
+    #[test]
+    fn test_boxed_paths_mark_fields() {
+        let mut files = [parse_proto_content(SERVICE_PROTO)];
+        mark_boxed(&mut files, &[".service.Track.header", "Header.request_id"]);
+        let metadata = render_metadata(&files);
+        assert!(metadata.contains("field header optional message Header boxed\n"));
+        assert!(metadata.contains("field request_id required scalar string boxed\n"));
+        assert!(metadata.contains("field status required enum Status\n"));
+
+        assert!(boxed_path_matches(
+            ".service.Track",
+            ".service.Track.header"
+        ));
+        assert!(!boxed_path_matches(".service.Tr", ".service.Track.header"));
+        assert!(boxed_path_matches(".", ".service.Track.header"));
+    }
+
+    #[test]
+    fn test_keyword_fields_are_renamed_like_prost() {
+        assert_eq!(prost_field_ident("type"), "r#type");
+        assert_eq!(prost_field_ident("self"), "self_");
+        assert_eq!(prost_field_ident("displayName"), "display_name");
+
+        let file = parse_proto_content("message Item { string type = 1; int32 self = 2; }");
+        let metadata = render_metadata(&[file]);
+        assert!(metadata.contains("field type required scalar string\nrename type r#type\n"));
+        assert!(metadata.contains("field self_ required scalar int32\nrename self self_\n"));
+    }
+
+    const LEGACY_PROTO: &str = r#"
+        syntax = "proto2";
+        package legacy;
+
+        message SearchResponse {
+            reserved 2, 9 to 11, 0x10, 40 to max;
+            reserved "snippet", 'rank';
+            repeated group Result = 1 {
+                required string url = 3;
+                optional string title = 4 [deprecated = true, default = "untitled"];
+            }
+            optional int32 total = 5 [(custom.opt) = { deprecated: true }, deprecated=false];
+            optional string cursor = 6 [deprecated = true];
+        }
+    "#;
+
+    #[test]
+    fn test_parse_groups_as_nested_messages() {
+        let file = parse_proto_content(LEGACY_PROTO);
+        let names: Vec<_> = file.messages.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["SearchResponse", "SearchResponse.Result"]);
+
+        let response = &file.messages[0];
+        let fields: Vec<_> = response
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.type_name.as_str(), f.number))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("result", "Result", 1),
+                ("total", "int32", 5),
+                ("cursor", "string", 6),
+            ]
+        );
+        assert_eq!(response.fields[0].label, FieldLabel::Repeated);
+
+        let result = &file.messages[1];
+        let result_fields: Vec<_> = result.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(result_fields, vec!["url", "title"]);
+
+        let metadata = render_metadata(&[file]);
+        assert!(metadata.contains("field result repeated message Result\n"));
+        assert!(metadata.contains("message legacy.SearchResponse.Result\n"));
+    }
+
+    #[test]
+    fn test_parse_reserved_and_deprecated_fields() {
+        let file = parse_proto_content(LEGACY_PROTO);
+        let response = &file.messages[0];
+        assert_eq!(
+            response.reserved_numbers,
+            vec![(2, 2), (9, 11), (16, 16), (40, MAX_FIELD_NUMBER)]
+        );
+        assert_eq!(response.reserved_names, vec!["snippet", "rank"]);
+
+        let deprecated: Vec<_> = file
+            .messages
+            .iter()
+            .flat_map(|m| m.fields.iter())
+            .filter(|f| f.deprecated)
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(deprecated, vec!["cursor", "title"]);
+    }
+
+    #[test]
+    fn test_proto2_singular_scalars_are_optional() {
+        let file = parse_proto_content(
+            "syntax = \"proto2\"; message Legacy { required int32 id = 1; optional int32 age = 2; }",
+        );
+        let metadata = render_metadata(&[file]);
+        assert!(metadata.contains("field id required scalar int32"));
+        assert!(metadata.contains("field age optional scalar int32"));
+    }
+
+    /// Field names and numbers of each message, for comparing parses.
+    fn field_numbers(file: &ProtoFile) -> Vec<(String, Vec<(String, u32)>)> {
+        file.messages
+            .iter()
+            .map(|message| {
+                let fields = message
+                    .fields
+                    .iter()
+                    .map(|field| (field.name.clone(), field.number))
+                    .collect();
+                (message.name.clone(), fields)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_layout_and_comments_do_not_change_the_parse() {
+        let compact = parse_proto_content(
+            "message Outer { message Inner { int32 id = 1; } repeated Inner inner = 1 \
+            [deprecated = true]; int32 id = 2; }",
+        );
+        let spread = parse_proto_content(
+            "message Outer { // ids; { are } not closed here\n\
+            message Inner { int32 id = 1; } /* } */\n\
+            repeated\n  Inner // the nested message\n  inner\n  = 1\n  [deprecated = true]\n  ;\n\
+            int32 id = 2; // same name as Inner.id\n}",
+        );
+        assert_eq!(field_numbers(&spread), field_numbers(&compact));
+        assert_eq!(
+            field_numbers(&compact),
+            vec![
+                (
+                    "Outer".to_string(),
+                    vec![("inner".to_string(), 1), ("id".to_string(), 2)]
+                ),
+                ("Outer.Inner".to_string(), vec![("id".to_string(), 1)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_escaped_quotes_stay_inside_string_literals() {
+        let file = parse_proto_content(
+            r#"message Quoted {
+                string name = 1 [json_name = "na\"];me"];
+                int32 id = 2 [(note) = { text: 'it\'s' }];
+                reserved "old\"name";
+                bool active = 3;
+            }"#,
+        );
+        let quoted = &file.messages[0];
+        let names: Vec<_> = quoted.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["name", "id", "active"]);
+    }
+
+    #[test]
+    fn test_malformed_input_is_parsed_without_panicking() {
+        let deep = "message Deep { ".repeat(10_000);
+        let file = parse_proto_content(&deep);
+        assert_eq!(file.messages.len(), MAX_NESTING_DEPTH);
+
+        let file = parse_proto_content("} message After { int32 id = 1; } } enum");
+        assert_eq!(
+            field_numbers(&file),
+            vec![("After".to_string(), vec![("id".to_string(), 1)])]
+        );
+
+        // Only identifiers name declarations, so no name can break a line of the metadata
+        let file = parse_proto_content(
+            "message \"Bad Name\" { int32 x = 1; } \
+            message Named { \"a b\" c = 1; string \"d\ne\" = 2; int32 f = 3 } \
+            enum Level { LOW = 0 }",
+        );
+        assert_eq!(
+            field_numbers(&file),
+            vec![("Named".to_string(), vec![("f".to_string(), 3)])]
+        );
+        assert_eq!(
+            file.enum_values["Level"],
+            vec![EnumValue {
+                name: "LOW".to_string(),
+                number: 0
+            }]
+        );
+
+        // Every prefix of a valid file is input a crashing build could hand the parser
+        for (index, _) in SERVICE_PROTO.char_indices() {
+            parse_proto_content(&SERVICE_PROTO[..index]);
+        }
+    }
+}
@@ -0,0 +1,329 @@
+//! Workspace conversion coverage: which proto messages have a derived Rust type.
+//!
+//! With the `meta-file` feature the derive records every struct it expands, the proto message
+//! it converts and the strategy chosen for each field, in `$OUT_DIR/protto_coverage/`. A report
+//! compares those records against the `.proto` files, e.g. from a test in the crate owning the
+//! derives:
+//!
+//! ```rust,ignore
+//! #[test]
+//! fn print_conversion_coverage() {
+//!     let report = protto::build::coverage_report(&["proto/service.proto"], env!("OUT_DIR"));
+//!     println!("{}", report.unwrap());
+//! }
+//! ```
+//!
+//! Records are only rewritten when the crate compiles, so a record of a deleted struct remains
+//! until `OUT_DIR` is cleaned.
+
+use super::{MetadataError, ProtoFile, parse_proto_content, prost_field_name, simple_name};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Directory under `OUT_DIR` holding one record per derived struct.
+pub const COVERAGE_DIR_NAME: &str = "protto_coverage";
+
+/// A field of a derived struct, as recorded by the derive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivedField {
+    pub rust_name: String,
+    /// Category of the conversion strategy, e.g. `direct`, `option` or `collection`.
+    pub strategy: String,
+    /// Proto fields the conversion reads and writes; empty for ignored fields.
+    pub proto_fields: Vec<String>,
+}
+
+/// A struct deriving `Protto`, as recorded by the derive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivedType {
+    pub rust_name: String,
+    /// Path of the proto message as the derive resolves it, e.g. `proto::Track`.
+    pub proto_path: String,
+    pub fields: Vec<DerivedField>,
+}
+
+/// Coverage of one proto message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageRow {
+    /// Package-qualified message name, e.g. `service.Track`.
+    pub message: String,
+    /// Rust types deriving a conversion for the message.
+    pub derived: Vec<String>,
+    /// Message fields mapped by at least one derived type.
+    pub mapped_fields: usize,
+    /// Message fields, excluding those inside a `oneof`.
+    pub total_fields: usize,
+}
+
+/// Parse a record written by the derive; `None` if it has no `derive` line.
+pub fn parse_coverage_record(content: &str) -> Option<DerivedType> {
+    let mut derived: Option<DerivedType> = None;
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("derive") => {
+                let (Some(rust_name), Some(proto_path)) = (parts.next(), parts.next()) else {
+                    continue;
+                };
+                derived = Some(DerivedType {
+                    rust_name: rust_name.to_string(),
+                    proto_path: proto_path.to_string(),
+                    fields: Vec::new(),
+                });
+            }
+            Some("field") => {
+                let (Some(derived), Some(rust_name), Some(strategy)) =
+                    (derived.as_mut(), parts.next(), parts.next())
+                else {
+                    continue;
+                };
+                derived.fields.push(DerivedField {
+                    rust_name: rust_name.to_string(),
+                    strategy: strategy.to_string(),
+                    proto_fields: parts.map(str::to_string).collect(),
+                });
+            }
+            _ => {}
+        }
+    }
+    derived
+}
+
+/// Read every record in `$out_dir/protto_coverage`, sorted by Rust type name. A missing
+/// directory means nothing was derived with the `meta-file` feature and yields no records.
+pub fn read_coverage(out_dir: impl AsRef<Path>) -> Result<Vec<DerivedType>, MetadataError> {
+    let dir = out_dir.as_ref().join(COVERAGE_DIR_NAME);
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut derived = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|err| MetadataError::Io(dir.clone(), err))?
+            .path();
+        let content =
+            std::fs::read_to_string(&path).map_err(|err| MetadataError::Io(path.clone(), err))?;
+        derived.extend(parse_coverage_record(&content));
+    }
+    derived.sort_by(|a, b| a.rust_name.cmp(&b.rust_name));
+    Ok(derived)
+}
+
+/// Match each message of the parsed files with the derived types converting it.
+///
+/// A derived type targets the message with the same simple name; when several packages declare
+/// that name, the one whose package ends with the last module segment of the proto path wins,
+/// as in the derive's own metadata lookup.
+pub fn coverage(files: &[ProtoFile], derived: &[DerivedType]) -> Vec<CoverageRow> {
+    let messages: Vec<(String, &super::MessageInfo)> = files
+        .iter()
+        .flat_map(|file| {
+            file.messages.iter().map(move |message| {
+                let qualified = match &file.package {
+                    Some(package) => format!("{package}.{}", message.name),
+                    None => message.name.clone(),
+                };
+                (qualified, message)
+            })
+        })
+        .collect();
+
+    messages
+        .iter()
+        .map(|(qualified, message)| {
+            let targeting: Vec<&DerivedType> = derived
+                .iter()
+                .filter(|derived| {
+                    targets_message(&messages, qualified, &message.name, &derived.proto_path)
+                })
+                .collect();
+            let mapped: HashSet<&str> = targeting
+                .iter()
+                .flat_map(|derived| derived.fields.iter())
+                .flat_map(|field| field.proto_fields.iter())
+                .map(String::as_str)
+                .collect();
+            let fields: Vec<String> = message
+                .fields
+                .iter()
+                .filter(|field| !field.in_oneof)
+                .map(|field| prost_field_name(&field.name))
+                .collect();
+
+            CoverageRow {
+                message: qualified.clone(),
+                derived: targeting.iter().map(|d| d.rust_name.clone()).collect(),
+                mapped_fields: fields
+                    .iter()
+                    .filter(|field| mapped.contains(field.as_str()))
+                    .count(),
+                total_fields: fields.len(),
+            }
+        })
+        .collect()
+}
+
+fn targets_message(
+    messages: &[(String, &super::MessageInfo)],
+    qualified: &str,
+    message_name: &str,
+    proto_path: &str,
+) -> bool {
+    let (module, proto_name) = proto_path.rsplit_once("::").unwrap_or(("", proto_path));
+    if simple_name(message_name) != proto_name {
+        return false;
+    }
+
+    let same_name = messages
+        .iter()
+        .filter(|(_, message)| simple_name(&message.name) == proto_name)
+        .count();
+    let module = module.rsplit("::").next().unwrap_or(module);
+    same_name == 1 || qualified.ends_with(&format!("{module}.{proto_name}"))
+}
+
+/// Render the rows as a table followed by a summary line, listing messages without a derived
+/// type first.
+pub fn render_coverage_report(rows: &[CoverageRow]) -> String {
+    let mut rows: Vec<&CoverageRow> = rows.iter().collect();
+    rows.sort_by_key(|row| (!row.derived.is_empty(), row.message.clone()));
+
+    let cells: Vec<[String; 3]> = rows
+        .iter()
+        .map(|row| {
+            let derived = if row.derived.is_empty() {
+                "-".to_string()
+            } else {
+                row.derived.join(", ")
+            };
+            [
+                row.message.clone(),
+                derived,
+                format!("{}/{}", row.mapped_fields, row.total_fields),
+            ]
+        })
+        .collect();
+    let header = ["message", "derived types", "fields"].map(str::to_string);
+    let widths: Vec<usize> = (0..3)
+        .map(|col| {
+            cells
+                .iter()
+                .chain(std::iter::once(&header))
+                .map(|row| row[col].len())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut out = String::new();
+    for row in std::iter::once(&header).chain(cells.iter()) {
+        let line = format!(
+            "{:<w0$}  {:<w1$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            w0 = widths[0],
+            w1 = widths[1]
+        );
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+
+    let covered = rows.iter().filter(|row| !row.derived.is_empty()).count();
+    out.push_str(&format!(
+        "{covered} of {} messages have a derived type\n",
+        rows.len()
+    ));
+    out
+}
+
+/// Parse the `.proto` files and render the coverage report against the records in `out_dir`.
+pub fn coverage_report(
+    proto_files: &[impl AsRef<Path>],
+    out_dir: impl AsRef<Path>,
+) -> Result<String, MetadataError> {
+    let mut parsed = Vec::with_capacity(proto_files.len());
+    for path in proto_files {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| MetadataError::Io(path.to_path_buf(), err))?;
+        parsed.push(parse_proto_content(&content));
+    }
+    let derived = read_coverage(out_dir)?;
+    Ok(render_coverage_report(&coverage(&parsed, &derived)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROTOS: &str = r#"
+        syntax = "proto3";
+        package service;
+
+        message Track {
+            uint64 track_id = 1;
+            string name = 2;
+            oneof payload { string text = 3; }
+        }
+        message Header { string request_id = 1; }
+        message Unused { string note = 1; }
+    "#;
+
+    const TRACK_RECORD: &str = "\
+derive Track proto::Track
+field id direct track_id
+field cache ignore
+";
+
+    #[test]
+    fn test_parse_coverage_record() {
+        let track = parse_coverage_record(TRACK_RECORD).unwrap();
+        assert_eq!(track.rust_name, "Track");
+        assert_eq!(track.proto_path, "proto::Track");
+        assert_eq!(track.fields.len(), 2);
+        assert_eq!(track.fields[0].strategy, "direct");
+        assert_eq!(track.fields[0].proto_fields, vec!["track_id".to_string()]);
+        assert!(track.fields[1].proto_fields.is_empty());
+
+        assert!(parse_coverage_record("field id direct track_id\n").is_none());
+    }
+
+    #[test]
+    fn test_coverage_report_lists_unmapped_messages_first() {
+        let files = vec![parse_proto_content(PROTOS)];
+        let derived = vec![
+            parse_coverage_record(TRACK_RECORD).unwrap(),
+            parse_coverage_record(
+                "derive RequestHeader proto::Header\nfield id direct request_id\n",
+            )
+            .unwrap(),
+        ];
+
+        let rows = coverage(&files, &derived);
+        let track = rows
+            .iter()
+            .find(|row| row.message == "service.Track")
+            .unwrap();
+        assert_eq!(track.derived, vec!["Track".to_string()]);
+        assert_eq!((track.mapped_fields, track.total_fields), (1, 2));
+
+        assert_eq!(
+            render_coverage_report(&rows),
+            "\
+message         derived types  fields
+service.Unused  -              0/1
+service.Header  RequestHeader  1/1
+service.Track   Track          1/2
+2 of 3 messages have a derived type
+"
+        );
+    }
+
+    #[test]
+    fn test_read_coverage_without_records() {
+        let dir = std::env::temp_dir().join("protto-coverage-missing");
+        assert!(read_coverage(dir).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+//! Conversion of repeated fields, shared by the derived `Vec` conversions.
+//!
+//! A derived `Vec<T>` field converts its elements through [`convert_vec`], which picks by element
+//! layout rather than by what the derive can see of the element type: elements of the same size
+//! and alignment, such as a newtype `struct TrackId(u64)` over `repeated uint64`, are collected in
+//! place, reusing the source allocation; any other elements are pushed into a vector allocated once
+//! at the final length.
+
+use std::mem::{align_of, size_of};
+
+/// Converts every element of `elements` with `convert`, without an intermediate vector.
+pub fn convert_vec<S, T>(elements: Vec<S>, mut convert: impl FnMut(S) -> T) -> Vec<T> {
+    if size_of::<S>() == size_of::<T>() && align_of::<S>() == align_of::<T>() {
+        // the standard library collects a vector's own `IntoIter` in place when layouts match
+        elements.into_iter().map(convert).collect()
+    } else {
+        let mut converted = Vec::with_capacity(elements.len());
+        for element in elements {
+            converted.push(convert(element));
+        }
+        converted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct TrackId(u64);
+
+    impl From<u64> for TrackId {
+        fn from(id: u64) -> Self {
+            Self(id)
+        }
+    }
+
+    #[test]
+    fn test_same_layout_elements_reuse_the_allocation() {
+        let mut ids = Vec::with_capacity(8);
+        ids.extend([1_u64, 2, 3]);
+        let source = ids.as_ptr() as usize;
+
+        let converted: Vec<TrackId> = convert_vec(ids, Into::into);
+        assert_eq!(converted, [TrackId(1), TrackId(2), TrackId(3)]);
+        assert_eq!(converted.as_ptr() as usize, source);
+        assert_eq!(converted.capacity(), 8);
+    }
+
+    #[test]
+    fn test_other_elements_are_allocated_exactly() {
+        let mut ids = Vec::with_capacity(8);
+        ids.extend([1_u32, 2, 3]);
+
+        let converted: Vec<u64> = convert_vec(ids, Into::into);
+        assert_eq!(converted, [1, 2, 3]);
+        assert_eq!(converted.capacity(), 3);
+    }
+}
@@ -0,0 +1,197 @@
+//! Runtime helpers used by generated code for `rust_decimal::Decimal` fields annotated with
+//! `#[protto(decimal = "string" | "units_nanos")]`.
+//!
+//! `"string"` fields hold the decimal's text, e.g. `"12.50"`. `"units_nanos"` fields hold a
+//! message of whole `units` and `nanos` (billionths), as in `google.type.Money`; both parts
+//! carry the sign of the amount. Such messages implement [`UnitsNanos`], usually through
+//! [`impl_units_nanos!`](crate::impl_units_nanos).
+//!
+//! Encoding as units and nanos rounds to nine fractional digits, midpoint away from zero. An
+//! amount whose units overflow `i64` cannot be encoded, and text or parts that do not form a
+//! decimal cannot be decoded; the derive turns those failures into conversion errors, or panics
+//! for `expect(panic)` fields.
+
+pub use rust_decimal;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::fmt;
+
+const NANOS_PER_UNIT: i64 = 1_000_000_000;
+const NANOS_SCALE: u32 = 9;
+
+/// A decimal that could not be converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecimalError {
+    /// The proto string is not a decimal number.
+    Parse(String),
+    /// `nanos` is out of range or its sign disagrees with `units`.
+    InvalidNanos { units: i64, nanos: i32 },
+    /// The whole units of the amount do not fit in `i64`.
+    Overflow(Decimal),
+}
+
+impl fmt::Display for DecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(value) => write!(f, "'{value}' is not a decimal number"),
+            Self::InvalidNanos { units, nanos } => write!(
+                f,
+                "nanos {nanos} must be within ±999,999,999 and share the sign of units {units}"
+            ),
+            Self::Overflow(value) => write!(f, "units of {value} overflow i64"),
+        }
+    }
+}
+
+impl std::error::Error for DecimalError {}
+
+/// A proto message holding an amount as whole `units` and `nanos`.
+pub trait UnitsNanos {
+    fn units(&self) -> i64;
+    fn nanos(&self) -> i32;
+    fn from_units_nanos(units: i64, nanos: i32) -> Self;
+}
+
+/// Implements [`UnitsNanos`] for prost messages with `units: i64` and `nanos: i32` fields; any
+/// other fields are left at their defaults when encoding.
+///
+/// ```rust,ignore
+/// protto::impl_units_nanos!(proto::MoneyAmount);
+/// ```
+#[macro_export]
+macro_rules! impl_units_nanos {
+    ($($message:ty),+ $(,)?) => {
+        $(
+            impl $crate::decimal::UnitsNanos for $message {
+                fn units(&self) -> i64 {
+                    self.units
+                }
+
+                fn nanos(&self) -> i32 {
+                    self.nanos
+                }
+
+                fn from_units_nanos(units: i64, nanos: i32) -> Self {
+                    Self {
+                        units,
+                        nanos,
+                        ..::core::default::Default::default()
+                    }
+                }
+            }
+        )+
+    };
+}
+
+/// Decode a proto string.
+pub fn from_string(value: &str) -> Result<Decimal, DecimalError> {
+    Decimal::from_str_exact(value.trim()).map_err(|_| DecimalError::Parse(value.to_string()))
+}
+
+/// Encode as a proto string.
+pub fn to_string(value: Decimal) -> String {
+    value.to_string()
+}
+
+/// Decode whole units and nanos.
+pub fn from_units_nanos(units: i64, nanos: i32) -> Result<Decimal, DecimalError> {
+    let invalid = DecimalError::InvalidNanos { units, nanos };
+    if i64::from(nanos).abs() >= NANOS_PER_UNIT
+        || (units > 0 && nanos < 0)
+        || (units < 0 && nanos > 0)
+    {
+        return Err(invalid);
+    }
+
+    Decimal::from(units)
+        .checked_add(Decimal::new(i64::from(nanos), NANOS_SCALE))
+        .ok_or(invalid)
+}
+
+/// Encode as whole units and nanos, rounding to nanos midpoint away from zero.
+pub fn to_units_nanos(value: Decimal) -> Result<(i64, i32), DecimalError> {
+    let rounded = value.round_dp_with_strategy(NANOS_SCALE, RoundingStrategy::MidpointAwayFromZero);
+    let units = rounded.trunc();
+    let nanos = (rounded - units)
+        .checked_mul(Decimal::from(NANOS_PER_UNIT))
+        .and_then(|nanos| nanos.to_i32());
+
+    match (units.to_i64(), nanos) {
+        (Some(units), Some(nanos)) => Ok((units, nanos)),
+        _ => Err(DecimalError::Overflow(value)),
+    }
+}
+
+/// Decode a units and nanos message; an absent message is zero, as proto3 defaults it.
+pub fn from_message<M: UnitsNanos>(message: Option<&M>) -> Result<Decimal, DecimalError> {
+    message.map_or(Ok(Decimal::ZERO), |message| {
+        from_units_nanos(message.units(), message.nanos())
+    })
+}
+
+/// Encode as a units and nanos message.
+pub fn to_message<M: UnitsNanos>(value: Decimal) -> Result<M, DecimalError> {
+    to_units_nanos(value).map(|(units, nanos)| M::from_units_nanos(units, nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Money {
+        currency_code: String,
+        units: i64,
+        nanos: i32,
+    }
+
+    crate::impl_units_nanos!(Money);
+
+    #[test]
+    fn round_trips_strings() {
+        let value = from_string("12.50").unwrap();
+        assert_eq!(value, Decimal::new(1250, 2));
+        assert_eq!(to_string(value), "12.50");
+        assert_eq!(
+            from_string("twelve"),
+            Err(DecimalError::Parse("twelve".to_string()))
+        );
+    }
+
+    #[test]
+    fn round_trips_units_nanos() {
+        let value = Decimal::new(-1_750_000_001, 9);
+        assert_eq!(to_units_nanos(value), Ok((-1, -750_000_001)));
+        assert_eq!(from_units_nanos(-1, -750_000_001), Ok(value));
+
+        let money: Money = to_message(Decimal::new(1999, 2)).unwrap();
+        assert_eq!((money.units, money.nanos), (19, 990_000_000));
+        assert_eq!(from_message(Some(&money)), Ok(Decimal::new(1999, 2)));
+        assert_eq!(from_message::<Money>(None), Ok(Decimal::ZERO));
+    }
+
+    #[test]
+    fn rounds_to_nanos_midpoint_away_from_zero() {
+        assert_eq!(to_units_nanos(Decimal::new(10_000_000_005, 10)), Ok((1, 1)));
+        assert_eq!(
+            to_units_nanos(Decimal::new(-10_000_000_005, 10)),
+            Ok((-1, -1))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_parts_and_overflow() {
+        assert_eq!(
+            from_units_nanos(1, -5),
+            Err(DecimalError::InvalidNanos {
+                units: 1,
+                nanos: -5
+            })
+        );
+        assert!(from_units_nanos(0, 1_000_000_000).is_err());
+        assert!(matches!(
+            to_units_nanos(Decimal::MAX),
+            Err(DecimalError::Overflow(_))
+        ));
+    }
+}
@@ -0,0 +1,165 @@
+//! Runtime helpers used by generated code for `Duration` fields annotated with
+//! `#[protto(duration = "seconds" | "millis" | "nanos")]`, which carry the duration as a whole
+//! number of that unit in a proto `int64`.
+//!
+//! `std::time::Duration` is supported out of the box, and `chrono::Duration` (`TimeDelta`) with
+//! the `chrono` feature. Encoding truncates toward zero to whole units. A duration whose count of
+//! units overflows `i64`, or a count the duration type cannot hold (such as a negative count for
+//! `std::time::Duration`), cannot be converted; the derive turns those failures into conversion
+//! errors, or panics for `expect(panic)` fields.
+
+use std::fmt;
+
+/// The unit of the proto `int64` a duration is carried in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DurationUnit {
+    Seconds,
+    Millis,
+    Nanos,
+}
+
+impl fmt::Display for DurationUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Seconds => f.write_str("seconds"),
+            Self::Millis => f.write_str("milliseconds"),
+            Self::Nanos => f.write_str("nanoseconds"),
+        }
+    }
+}
+
+/// A duration that could not be converted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationError {
+    /// The duration's count of units does not fit in `i64`.
+    Overflow(DurationUnit),
+    /// The proto count is outside the range of the rust duration type.
+    OutOfRange { value: i64, unit: DurationUnit },
+}
+
+impl fmt::Display for DurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow(unit) => write!(f, "duration in {unit} overflows i64"),
+            Self::OutOfRange { value, unit } => {
+                write!(f, "{value} {unit} is out of range for the duration type")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DurationError {}
+
+/// A rust duration type that converts to and from a count of [`DurationUnit`]s.
+pub trait ProtoDuration: Sized {
+    fn to_units(&self, unit: DurationUnit) -> Result<i64, DurationError>;
+    fn from_units(value: i64, unit: DurationUnit) -> Result<Self, DurationError>;
+}
+
+/// Encodes a duration as a whole number of `unit`s, truncating toward zero.
+pub fn to_units<D: ProtoDuration>(duration: &D, unit: DurationUnit) -> Result<i64, DurationError> {
+    duration.to_units(unit)
+}
+
+/// Decodes a duration from a number of `unit`s.
+pub fn from_units<D: ProtoDuration>(value: i64, unit: DurationUnit) -> Result<D, DurationError> {
+    D::from_units(value, unit)
+}
+
+impl ProtoDuration for std::time::Duration {
+    fn to_units(&self, unit: DurationUnit) -> Result<i64, DurationError> {
+        let units = match unit {
+            DurationUnit::Seconds => u128::from(self.as_secs()),
+            DurationUnit::Millis => self.as_millis(),
+            DurationUnit::Nanos => self.as_nanos(),
+        };
+        i64::try_from(units).map_err(|_| DurationError::Overflow(unit))
+    }
+
+    fn from_units(value: i64, unit: DurationUnit) -> Result<Self, DurationError> {
+        let units = u64::try_from(value).map_err(|_| DurationError::OutOfRange { value, unit })?;
+        Ok(match unit {
+            DurationUnit::Seconds => Self::from_secs(units),
+            DurationUnit::Millis => Self::from_millis(units),
+            DurationUnit::Nanos => Self::from_nanos(units),
+        })
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ProtoDuration for chrono::TimeDelta {
+    fn to_units(&self, unit: DurationUnit) -> Result<i64, DurationError> {
+        match unit {
+            DurationUnit::Seconds => Ok(self.num_seconds()),
+            DurationUnit::Millis => Ok(self.num_milliseconds()),
+            DurationUnit::Nanos => self.num_nanoseconds().ok_or(DurationError::Overflow(unit)),
+        }
+    }
+
+    fn from_units(value: i64, unit: DurationUnit) -> Result<Self, DurationError> {
+        match unit {
+            DurationUnit::Seconds => Self::try_seconds(value),
+            DurationUnit::Millis => Self::try_milliseconds(value),
+            DurationUnit::Nanos => Some(Self::nanoseconds(value)),
+        }
+        .ok_or(DurationError::OutOfRange { value, unit })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_std_duration_truncates_to_whole_units() {
+        let duration = Duration::from_millis(2_999);
+        assert_eq!(to_units(&duration, DurationUnit::Seconds), Ok(2));
+        assert_eq!(to_units(&duration, DurationUnit::Millis), Ok(2_999));
+        assert_eq!(to_units(&duration, DurationUnit::Nanos), Ok(2_999_000_000));
+        assert_eq!(
+            from_units::<Duration>(2_999, DurationUnit::Millis),
+            Ok(duration)
+        );
+    }
+
+    #[test]
+    fn test_std_duration_overflow_and_range() {
+        assert_eq!(
+            to_units(&Duration::MAX, DurationUnit::Millis),
+            Err(DurationError::Overflow(DurationUnit::Millis))
+        );
+        assert_eq!(
+            from_units::<Duration>(-1, DurationUnit::Seconds),
+            Err(DurationError::OutOfRange {
+                value: -1,
+                unit: DurationUnit::Seconds
+            })
+        );
+        assert_eq!(
+            from_units::<Duration>(i64::MAX, DurationUnit::Nanos)
+                .and_then(|duration| to_units(&duration, DurationUnit::Nanos)),
+            Ok(i64::MAX)
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_duration_round_trips_negative_values() {
+        let delta = chrono::TimeDelta::milliseconds(-1_500);
+        assert_eq!(to_units(&delta, DurationUnit::Seconds), Ok(-1));
+        assert_eq!(to_units(&delta, DurationUnit::Millis), Ok(-1_500));
+        assert_eq!(from_units(-1_500, DurationUnit::Millis), Ok(delta));
+        assert_eq!(
+            from_units::<chrono::TimeDelta>(i64::MAX, DurationUnit::Seconds),
+            Err(DurationError::OutOfRange {
+                value: i64::MAX,
+                unit: DurationUnit::Seconds
+            })
+        );
+        assert_eq!(
+            to_units(&chrono::TimeDelta::MAX, DurationUnit::Nanos),
+            Err(DurationError::Overflow(DurationUnit::Nanos))
+        );
+    }
+}
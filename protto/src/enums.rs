@@ -0,0 +1,20 @@
+//! Runtime support for enums deriving with `#[protto(enum_as_i32)]`, which convert to and from a
+//! plain proto `int32` by their discriminants instead of through a prost enum type.
+
+use std::fmt;
+
+/// An `i32` matching no discriminant of the enum it was converted into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownEnumValue {
+    /// The rust enum the value was converted into.
+    pub target: &'static str,
+    pub value: i32,
+}
+
+impl fmt::Display for UnknownEnumValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} matches no variant of {}", self.value, self.target)
+    }
+}
+
+impl std::error::Error for UnknownEnumValue {}
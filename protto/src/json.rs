@@ -0,0 +1,123 @@
+//! Runtime helpers used by the `to_proto_json`/`from_proto_json` methods generated under the
+//! `json` feature.
+//!
+//! Structs go through their proto message, so the JSON follows the protobuf JSON mapping
+//! (lowerCamelCase field names, 64-bit integers as strings, enums by name) as long as the proto
+//! messages implement it with `serde`, e.g. the impls generated by `pbjson-build`.
+
+pub use serde_json;
+use std::fmt;
+
+/// Why `from_proto_json` failed: the JSON is not a valid proto message, or the message did not
+/// convert into the struct.
+#[derive(Debug)]
+pub enum JsonError<E> {
+    Json(serde_json::Error),
+    Conversion(E),
+}
+
+impl<E: fmt::Display> fmt::Display for JsonError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(err) => write!(f, "invalid proto JSON: {err}"),
+            Self::Conversion(err) => write!(f, "proto JSON did not convert: {err}"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for JsonError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(err) => Some(err),
+            Self::Conversion(err) => Some(err),
+        }
+    }
+}
+
+impl<E> From<serde_json::Error> for JsonError<E> {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+/// Converts a struct to and from proto JSON through its proto message. The derive implements it
+/// for every struct; the methods are available wherever the conversions and serde impls exist.
+pub trait ProtoJson: Sized {
+    /// The proto message the struct converts through
+    type Proto;
+
+    /// Encode the struct as the proto JSON of its message.
+    fn to_proto_json(&self) -> String
+    where
+        Self: Clone + Into<Self::Proto>,
+        Self::Proto: serde::Serialize,
+    {
+        let proto_struct: Self::Proto = self.clone().into();
+        // proto messages have string keys and no fallible serialization, so encoding cannot fail
+        serde_json::to_string(&proto_struct).expect("proto messages always encode as JSON")
+    }
+
+    /// Decode the struct from the proto JSON of its message.
+    fn from_proto_json(json: &str) -> Result<Self, JsonError<<Self as TryFrom<Self::Proto>>::Error>>
+    where
+        Self: TryFrom<Self::Proto>,
+        Self::Proto: serde::de::DeserializeOwned,
+    {
+        let proto_struct: Self::Proto = serde_json::from_str(json)?;
+        Self::try_from(proto_struct).map_err(JsonError::Conversion)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Contact {
+        display_name: String,
+    }
+
+    impl ProtoJson for Contact {
+        type Proto = Value;
+    }
+
+    impl From<Contact> for Value {
+        fn from(contact: Contact) -> Self {
+            json!({ "displayName": contact.display_name })
+        }
+    }
+
+    impl TryFrom<Value> for Contact {
+        type Error = &'static str;
+
+        fn try_from(value: Value) -> Result<Self, Self::Error> {
+            let display_name = value["displayName"].as_str().ok_or("displayName")?;
+            Ok(Self {
+                display_name: display_name.to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn proto_json_round_trips_through_the_message() {
+        let contact = Contact {
+            display_name: "Ada".to_string(),
+        };
+        let json = contact.to_proto_json();
+        assert_eq!(json, r#"{"displayName":"Ada"}"#);
+        assert_eq!(Contact::from_proto_json(&json).unwrap(), contact);
+    }
+
+    #[test]
+    fn proto_json_reports_decode_and_conversion_failures() {
+        assert!(matches!(
+            Contact::from_proto_json("{"),
+            Err(JsonError::Json(_))
+        ));
+        assert!(matches!(
+            Contact::from_proto_json(r#"{"name":"Ada"}"#),
+            Err(JsonError::Conversion("displayName"))
+        ));
+    }
+}
@@ -67,6 +67,12 @@
 //!
 //! ## Attribute Reference
 //!
+//! Every key is checked against the keys supported where it is attached; a typo such as
+//! `#[protto(proto_nmae = "id")]` fails to compile with a "did you mean `proto_name`?" hint.
+//! Keys taking a value accept `key = value` or `key(value)`, and nested keys such as `expect`
+//! accept `expect(panic)` or `expect = "panic"`. Enums take only `module`; enum variants take no
+//! attributes.
+//!
 //! ### Struct-Level Attributes
 //!
 //! #### `#[protto(module = "path")]`
@@ -78,11 +84,17 @@
 //! ```
 //!
 //! #### `#[protto(proto_name = "ProtoName")]`
-//! Maps the struct to a different protobuf type name.
+//! Maps the struct to a different protobuf type name. prost generates a message nested in another
+//! in a module named after its parent, so it is named by that path relative to `module`; with
+//! schema metadata the derive places the path in the package of its top-level message.
 //! ```rust,ignore
 //! #[derive(Protto)]
 //! #[protto(proto_name = "StateMessage")]  // maps to proto::StateMessage
 //! struct State { ... }
+//!
+//! #[derive(Protto)]
+//! #[protto(proto_name = "order::Item")]  // `message Item` nested in `message Order`
+//! struct OrderItem { ... }
 //! ```
 //!
 //! #### `#[protto(error_type = ErrorType)]`
@@ -96,6 +108,293 @@
 //! #### `#[protto(error_fn = "function_name")]`
 //! Specifies a function to handle conversion errors at the struct level.
 //!
+//! #### `#[protto(error_mod = "module_name")]`
+//! Generates the struct's default `<Struct>ConversionError` inside `mod module_name` rather
+//! than at the struct's scope, so modules defining same-named structs can be glob re-exported
+//! without their error types colliding. Each struct in a scope needs its own module name.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(error_mod = "user_errors")]
+//! struct User { ... }
+//!
+//! let err: user_errors::UserConversionError = ...;
+//! ```
+//!
+//! #### `#[protto(error_vis = "pub(crate)")]`
+//! The generated `<Struct>ConversionError` and `<Struct>Presence` take the struct's visibility,
+//! so a `pub(crate)` struct doesn't leak a `pub` error and a `pub` struct's `TryFrom` never names
+//! a private one. `error_vis` sets the error's visibility (or its `error_mod` module's) instead.
+//!
+//! #### `#[protto(static_errors)]`
+//! Makes the generated `<Struct>ConversionError` variants carry the field name as a
+//! `&'static str` instead of a `String`, so no allocation happens when a conversion fails. The
+//! error type is then `Copy` and implements `From<&'static str>` in place of `From<String>`.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(static_errors)]
+//! struct User { #[protto(expect(error))] email: String }
+//!
+//! assert_eq!(err, UserConversionError::MissingField("email"));
+//! ```
+//!
+//! #### `#[protto(presence)]`
+//! Generates a `<Struct>Presence` companion with a `bool` per field whose proto field is
+//! optional, and a `from_proto_with_presence` constructor returning it alongside the converted
+//! struct (inside a `Result` when the conversion is `TryFrom`). Useful for auditing which fields
+//! a sender actually set, since `default` and `Option` conversions hide the difference.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(presence)]
+//! struct Contact { id: u64, email: Option<String>, #[protto(default)] age: u32 }
+//!
+//! let (contact, presence) = Contact::from_proto_with_presence(proto);
+//! if !presence.age { /* age was not sent; contact.age is the default */ }
+//! ```
+//!
+//! #### `#[protto(proto_parts)]`
+//! Generates a `<Struct>ProtoParts` holding every field as a public `Option`, for messages that
+//! need assembly beyond what the attributes express. It converts from the proto message like the
+//! struct does (`From` or `TryFrom`), leaving `ignore` fields `None`; `finish()` then builds the
+//! struct, or returns [`parts::MissingPart`] naming the first field still `None`.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(proto_parts)]
+//! struct Invoice { lines: Vec<Line>, #[protto(ignore)] total: u64 }
+//!
+//! let mut parts = InvoiceProtoParts::from(proto);
+//! parts.total = parts.lines.as_ref().map(|lines| lines.iter().map(|line| line.amount).sum());
+//! let invoice = parts.finish()?;
+//! ```
+//!
+//! #### `#[protto(view)]`
+//! Generates a `<Struct>View<'a>` over a borrowed proto message, for hot paths that only read it.
+//! Fields converted as-is borrow their strings and bytes (`&'a str`, `&'a [u8]`, or
+//! `Option`/`Vec` of them for optional and repeated strings) and copy proto scalars; fields with
+//! conversion attributes or of other types stay behind `proto()`. `to_owned()` converts a clone
+//! of the message into the struct, returning a `Result` when the conversion is `TryFrom`.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(view)]
+//! struct Article { id: u64, title: String, body: Vec<u8> }
+//!
+//! let view = ArticleView::from(&message);  // title: &str, body: &[u8]
+//! let article: Article = view.to_owned();
+//! ```
+//!
+//! #### `#[protto(validate)]`
+//! Requires the `validate` feature. The proto -> rust conversion first checks the message with
+//! `protto::validate::ValidateProto`, implemented for every message deriving `validator::Validate`
+//! or by hand, so it becomes a `TryFrom` even when no field can fail. `validate = true` in a
+//! `protto.toml` marks every struct of the crate. The
+//! generated error gains a `Validation` variant; a struct-level `error_type` converts from
+//! `protto::validate::ValidationError` instead.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(validate)]
+//! struct Signup { email: String }
+//!
+//! match Signup::try_from(message) {
+//!     Err(SignupConversionError::Validation(err)) => reject(err.field, err.message),
+//!     ...
+//! }
+//! ```
+//!
+//! #### `#[protto(debug_roundtrip)]`
+//! In debug builds, the proto -> rust conversion asserts that converting its result back gives
+//! the proto message it started from, panicking on lossy mappings such as a dropped field or an
+//! empty string standing for an unset one. Release builds compile the check out. The struct must
+//! implement `Clone`; structs that are lossy by design (`partial`, struct-level `ignore`) trip it
+//! whenever the dropped fields hold values.
+//! ```rust,ignore
+//! #[derive(Protto, Clone)]
+//! #[protto(debug_roundtrip)]
+//! struct Contact { name: String, age: u32 }
+//! ```
+//!
+//! #### `#[protto(use_rules = "crate::money_rules")]`
+//! Applies project-wide conventions from a module marked `#[protto::protto_rules]`: a field
+//! whose type is `Money` converts through the module's `from_proto_Money` and `to_proto_Money`,
+//! as if it carried `from_proto_fn` and `to_proto_fn`. Fields with their own conversion
+//! attributes keep them. The derive learns the module's functions when the module expands, so,
+//! as with enums, the rules module must come before the structs using it in the same crate.
+//! ```rust,ignore
+//! #[protto::protto_rules]
+//! mod money_rules {
+//!     pub fn from_proto_Money(cents: i64) -> Money { Money::from_cents(cents) }
+//!     pub fn to_proto_Money(money: Money) -> i64 { money.cents() }
+//! }
+//!
+//! #[derive(Protto)]
+//! #[protto(use_rules = "money_rules")]
+//! struct Invoice { total: Money, tax: Money, memo: String }
+//! ```
+//!
+//! #### `#[protto(proto_only(checksum = "compute_checksum"))]`
+//! Proto fields without a rust counterpart, each set from a function of the whole struct when
+//! converting to proto; the proto -> rust conversion drops them. The function takes `&Struct`
+//! and returns the proto field's type, and may be a method such as `Self::checksum`.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(proto_only(word_count = "count_words"))]
+//! struct Document { title: String, body: String }
+//!
+//! fn count_words(document: &Document) -> u32 {
+//!     document.body.split_whitespace().count() as u32
+//! }
+//! ```
+//!
+//! #### `#[protto(partial)]`
+//! Marks the struct as a projection of part of the proto message. Proto fields without a
+//! matching struct field are skipped when converting from the message and filled from `Default`
+//! when converting back, so summary and detail types can derive from the same message without
+//! naming every skipped field in a struct-level `ignore`.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(proto_name = "User", partial)]
+//! struct UserSummary { id: u64, display_name: String }  // proto::User has a dozen more fields
+//! ```
+//!
+//! #### `#[protto(custom_fns_are = "pure")]`
+//! A documented contract that the struct's custom conversion functions are pure: they map their
+//! input to their output without I/O or other side effects. The derive backs the part it can
+//! check, asserting every `from_proto_fn` is a `fn(_) -> FieldType` (`fn(&_) -> FieldType` for
+//! `from_proto_fn_ref`) and every `to_proto_fn` a `fn(FieldType) -> _`, so a function with the
+//! wrong arity or types fails with the expected and found signatures at the field rather than
+//! an inference error inside the generated struct literal.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(custom_fns_are = "pure")]
+//! struct Track {
+//!     #[protto(from_proto_fn = "from_millis", to_proto_fn = "to_millis")]
+//!     duration: Duration,  // from_millis: fn(u64) -> Duration, to_millis: fn(Duration) -> u64
+//! }
+//! ```
+//!
+//! #### `#[protto(conversions = "both")]`
+//! Keeps the fallible `TryFrom` impls and adds infallible inherent methods for trusted data:
+//! `from_proto` (when converting from proto can fail) and `into_proto` (when converting to
+//! proto can fail), each panicking with the conversion error. They are methods rather than
+//! `From` impls since std's blanket `TryFrom` for `From` types would conflict with the derived
+//! `TryFrom`.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(conversions = "both")]
+//! struct Track { #[protto(expect)] name: String }
+//!
+//! let track = Track::try_from(untrusted)?;       // validated
+//! let track = Track::from_proto(from_our_cache); // panics if `name` is missing
+//! ```
+//!
+//! #### `#[protto(transparent)]` on the struct
+//! For a single-field struct standing in for a whole proto message, such as an id newtype around
+//! a `Uuid` message. The struct converts to and from the message named by `proto_name` by
+//! delegating to its field's conversion: the field is either the prost message itself or a type
+//! converting to and from it. Only `module` and `proto_name` may accompany it, and the field takes
+//! no attributes.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(transparent, proto_name = "Uuid")]
+//! struct UserId(proto::Uuid);  // From<proto::Uuid> for UserId, and back
+//!
+//! #[derive(Protto)]
+//! #[protto(transparent, proto_name = "Uuid")]
+//! struct AccountId { uuid: Uuid }  // Uuid: From<proto::Uuid> + Into<proto::Uuid>
+//! ```
+//!
+//! #### `#[protto(arena)]`
+//! Experimental, behind the `arena` feature. For short-lived structs with one lifetime
+//! parameter, generates `from_proto_in(&proto, &bump)` allocating the struct's strings, bytes and
+//! repeated fields in a `bumpalo::Bump` rather than on the heap. See `protto::arena` for the field
+//! types it converts into.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(arena)]
+//! struct Request<'a> { user: &'a str, tags: bumpalo::collections::Vec<'a, &'a str> }
+//!
+//! let bump = protto::arena::Bump::new();
+//! let request = Request::from_proto_in(&message, &bump);
+//! ```
+//!
+//! #### `#[protto(passthrough)]`
+//! For a prost message used directly as the domain type, typically given the derive with
+//! prost-build's `type_attribute`. The field analysis is skipped: the message converts to and
+//! from itself through the standard library's identity `From`, so code generic over the
+//! [`version`] traits accepts it like any derived struct. The derive adds only the items the
+//! struct would otherwise get, with `Self` as the proto type; `any_type_url` and
+//! `estimated_size` may accompany it, and the fields take no attributes.
+//! ```rust,ignore
+//! tonic_build::configure().type_attribute(
+//!     "pkg.Ping",
+//!     "#[derive(::protto::Protto)] #[protto(passthrough)]",
+//! );
+//!
+//! fn send<T: protto::version::ProtoConvertTo<proto::Ping>>(value: T) { /* ... */ }
+//! send(ping_message);  // as well as any struct converting into proto::Ping
+//! ```
+//!
+//! #### `#[protto(also_proto = "proto_v2::User")]`
+//! Further prost messages, as full paths, the struct converts to and from alongside the one
+//! named by `module` and `proto_name`, typically other versions of the same schema. Each gets its
+//! own `From`/`Into` (or `TryFrom`) impls built from the same field attributes, while the error
+//! type and every other generated item belong to the primary message. The traits in
+//! [`version`] let bridging code be generic over the message.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(module = "proto_v1", proto_name = "User", also_proto = "proto_v2::User", partial)]
+//! struct User { id: u64, name: String }  // proto_v2::User adds a `locale` left at its default
+//!
+//! fn upgrade<P>(user: User) -> P where User: protto::version::ProtoConvertTo<P> {
+//!     user.convert_to_proto()
+//! }
+//! ```
+//!
+//! #### `#[protto(sources(header = "proto::Header", body = "proto::Body"))]`
+//! For a struct whose fields come from sibling messages delivered together. The struct converts
+//! from and into the tuple of the messages, in the listed order, rather than a single message,
+//! and each field names the message it belongs to with `source`. Fields take their usual
+//! attributes, and an `ignore`d field needs no source.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(sources(header = "proto::Header", body = "proto::Body"))]
+//! struct Envelope {
+//!     #[protto(source = "header")]
+//!     request_id: String,
+//!     #[protto(source = "body")]
+//!     text: String,
+//! }
+//!
+//! let envelope = Envelope::from((header, body));
+//! let (header, body): (proto::Header, proto::Body) = envelope.into();
+//! ```
+//!
+//! #### `#[protto(cfg(feature = "grpc"))]`
+//! Puts every generated item under a `#[cfg(...)]` with the predicate, for crates whose proto
+//! module only exists behind a feature. Unlike `cfg_attr(feature = "grpc", derive(Protto))`, the
+//! `#[protto]` field attributes stay valid without the feature. Also accepted on enums.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(cfg(feature = "grpc"), module = "grpc_proto")]
+//! struct User { id: u64 }  // From impls only when `grpc` is enabled
+//! ```
+//!
+//! #### `#[protto(resolver = ResolverType)]`
+//! The type passed by reference to `from_proto_async` for fields using `resolve_with`. Requires
+//! `error_type` (and so `error_fn`); resolver errors must convert into it via `?`.
+//!
+//! ### Workspace Defaults
+//! A `protto.toml` at the workspace root (or named by `PROTTO_CONFIG`; the nearest one from the
+//! crate's directory up is used) sets struct-level keys for every derived type that leaves them
+//! out, so policies are changed in one place:
+//! ```toml
+//! [defaults]
+//! module = "crate::proto"
+//! error_type = "crate::ConversionError"
+//! error_fn = "crate::ConversionError::invalid"
+//! ```
+//! Only policy keys can be set: `module`, `error_type`, `error_fn`, `error_fn_with_value`,
+//! `error_vis`, `static_errors`, `custom_fns_are`, `conversions`, `extra_fields`,
+//! `debug_roundtrip` and `use_rules`.
+//!
 //! ### Field-Level Attributes
 //!
 //! #### `#[protto(transparent)]`
@@ -105,11 +404,24 @@
 //! struct UserId(#[protto(transparent)] u64);
 //! ```
 //!
+//! A `transparent` field of a newtype over a `Vec` or map converts the `repeated` or `map` proto
+//! field element by element; see [`newtype`].
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! struct Tags(Vec<Tag>);
+//!
+//! #[protto(transparent)]
+//! pub tags: Tags,  // repeated TagMessage tags
+//! ```
+//!
 //! #### `#[protto(proto_name = "proto_field_name")]`
-//! Maps the field to a different name in the protobuf.
+//! Maps the field to a different name in the protobuf. Fields prost generates as raw identifiers
+//! can be named as in the `.proto` file or with the `r#` prefix.
 //! ```rust,ignore
 //! #[protto(proto_name = "user_id")]
 //! pub id: u64,  // maps to proto.user_id
+//! #[protto(proto_name = "type")]
+//! pub kind: String,  // maps to proto.r#type
 //! ```
 //!
 //! #### `#[protto(ignore)]`
@@ -119,6 +431,49 @@
 //! pub runtime_data: HashMap<String, String>,
 //! ```
 //!
+//! #### `#[protto(manual)]`
+//! Leaves the field to a hand-written [`parts::FromProtoParts`] impl when no attribute
+//! describes its conversion. The derive converts every other field and calls the impl for the
+//! manual ones, which are passed as a tuple in declaration order when there are several.
+//! ```rust,ignore
+//! #[protto(manual)]
+//! pub window: Range<u64>,  // impl FromProtoParts<proto::Schedule> for Schedule { type Parts = Range<u64>; ... }
+//! ```
+//!
+//! #### `#[protto(preserve_unknown)]`
+//! Keeps the proto message the struct was converted from in an `Option` of the message, so
+//! proto fields the struct does not model survive a pass through it. The rust -> proto
+//! conversion starts from the preserved message, or the message's `Default` when the field is
+//! `None`, and overwrites only the mapped fields; struct-level `ignore` fields keep their
+//! preserved values too. Converting from proto clones the message once.
+//! ```rust,ignore
+//! #[protto(preserve_unknown)]
+//! pub original: Option<proto::User>,
+//! ```
+//!
+//! #### `#[protto(presence_of = "proto_field")]`
+//! Reads whether an optional proto field, such as a sub-message, is set into a `bool`, without a
+//! domain type for the message. The rust -> proto conversion leaves the proto field unset,
+//! unless another field of the struct converts it; a bool cannot rebuild the message, so the
+//! attribute takes no other keys.
+//! ```rust,ignore
+//! #[protto(presence_of = "audit")]
+//! pub has_audit: bool,  // proto: optional Audit audit = 5;
+//! ```
+//!
+//! #### Feature-gated fields
+//! Derive macros only see fields whose `#[cfg(...)]` holds, so a gated field maps like any other
+//! when its feature is on. When it is off, the proto field still needs a value; ignore it at the
+//! struct level under the opposite condition and it is filled with its default.
+//! ```rust,ignore
+//! #[cfg_attr(not(feature = "premium"), protto(ignore = "tier"))]
+//! pub struct Account {
+//!     pub name: String,
+//!     #[cfg(feature = "premium")]
+//!     pub tier: String,
+//! }
+//! ```
+//!
 //! #### Custom Conversion Functions
 //!
 //! ##### `#[protto(from_proto_fn = "function")]`
@@ -135,12 +490,282 @@
 //! pub created_at: DateTime<Utc>,
 //! ```
 //!
+//! On a generic struct the function paths may name its generic parameters, as in
+//! `"decode_as::<T>"` or `"<T as Codec>::decode"`, and the struct as `Self`
+//! (`"Self::id_from_proto"`), which resolves to `Tagged<T>` wherever the function is called.
+//!
+//! ##### `#[protto(from_proto_fn_ref = "function")]`
+//! Like `from_proto_fn`, but the function takes the proto field by reference, so a function
+//! reading a few sub-fields of a large nested message doesn't force a move of it. Borrowing
+//! conversions run before the other fields move out of the proto message.
+//! ```rust,ignore
+//! #[protto(proto_name = "header", proto_optional, from_proto_fn_ref = "request_id_of")]
+//! pub request_id: String,  // fn request_id_of(header: &proto::Header) -> String
+//! ```
+//!
+//! ##### `#[protto(depends_on = "field")]`
+//! Passes other fields of the struct, already converted, to the field's `from_proto_fn` (or
+//! `from_proto_fn_ref`, `try_from_proto_fn`) by reference after the proto value. The fields it
+//! depends on are converted first, whatever their order in the struct; several are listed as
+//! `"currency, scale"`, and a cycle is a compile error.
+//! ```rust,ignore
+//! #[protto(from_proto_fn = "amount_in", depends_on = "currency")]
+//! pub amount: Amount,  // fn amount_in(minor_units: i64, currency: &Currency) -> Amount
+//! pub currency: Currency,
+//! ```
+//!
 //! Both can be combined for bidirectional custom conversion:
 //! ```rust,ignore
 //! #[protto(from_proto_fn = "from_proto_map", to_proto_fn = "to_proto_map")]
 //! pub metadata: HashMap<String, Value>,
 //! ```
 //!
+//! #### `#[protto(group(sub_field = "proto_field", ...))]`
+//! Groups several flat proto fields into a Rust sub-struct that has no proto message of its own.
+//! ```rust,ignore
+//! #[protto(group(street, city, zip = "postal_code"))]
+//! pub address: Address,  // Address { street, city, zip } <-> proto.street, proto.city, proto.postal_code
+//! ```
+//!
+//! #### `#[protto(path_encoding = "utf8_strict" | "lossy" | "bytes")]`
+//! Required on `PathBuf` and `OsString` fields (and `Option` of either). `"utf8_strict"` and
+//! `"lossy"` map to a proto `string`; strict makes the rust → proto conversion a `TryFrom` that
+//! fails with `InvalidValue` (or the result of `error_fn`) on non-UTF-8 values, while lossy
+//! replaces invalid sequences. `"bytes"` maps to a proto `bytes` field, losslessly on unix. The
+//! conversions live in [`path_encoding`].
+//! ```rust,ignore
+//! #[protto(path_encoding = "lossy")]
+//! pub config_path: PathBuf,  // proto string
+//! ```
+//!
+//! #### `#[protto(fallible)]`
+//! For an `Option<T>` field mapped to an optional proto message when `T`'s own proto → rust
+//! conversion is `TryFrom`. The child conversion is mapped over the option and transposed, so a
+//! failing child makes the parent's `TryFrom` return `InvalidValue` (or the result of
+//! `error_fn`). Children with an infallible `From` also work through the blanket `TryFrom`.
+//! ```rust,ignore
+//! #[protto(fallible)]
+//! pub child: Option<ChildDomain>,  // ChildDomain: TryFrom<proto::Child>
+//! ```
+//! On a `Vec<T>` or `Option<Vec<T>>` field mapped to a repeated message field, each element is
+//! converted with `TryFrom` and the first failure fails the parent the same way; an
+//! `Option<Vec<T>>` still follows `empty_repeated`.
+//! ```rust,ignore
+//! #[protto(fallible)]
+//! pub children: Vec<ChildDomain>,
+//! ```
+//!
+//! #### Bytes fields
+//! No attribute is needed for a proto `bytes` field: `Vec<u8>` and `bytes::Bytes` fields (or
+//! `Option` of either) convert with `Into`, whether prost generates `Vec<u8>` or, under
+//! prost-build's `bytes` configuration, `Bytes`.
+//! ```rust,ignore
+//! pub payload: Vec<u8>,  // proto bytes generated as prost::bytes::Bytes
+//! ```
+//!
+//! #### `#[protto(bytes_as = "pod_slice")]`
+//! Packs a `Vec<T>` of plain-old-data elements into a proto `bytes` field, back to back in their
+//! native-endian layout, for high-throughput arrays such as telemetry samples. Requires the
+//! `zerocopy` feature. Decoding makes the proto → rust conversion a `TryFrom` that fails with
+//! `InvalidValue` (or the result of `error_fn`) when the length is not a whole number of
+//! elements; alignment is not required.
+//! ```rust,ignore
+//! #[derive(FromBytes, IntoBytes, Immutable, KnownLayout)]
+//! #[repr(C)]
+//! pub struct Sample { timestamp: u32, value: f32 }
+//!
+//! #[protto(bytes_as = "pod_slice")]
+//! pub samples: Vec<Sample>,  // proto bytes
+//! ```
+//!
+//! #### `#[protto(decimal = "string" | "units_nanos")]`
+//! Carries a `rust_decimal::Decimal` (or `Option<Decimal>`) field as a proto `string` holding its
+//! text, or as a message of whole `units` and `nanos` like `google.type.Money`, whose prost type
+//! implements [`decimal::UnitsNanos`] (usually via `protto::impl_units_nanos!`). Requires the
+//! `decimal` feature. Encoding as units/nanos rounds to nine fractional digits, midpoint away
+//! from zero; a missing units/nanos message is zero unless the field is `expect`. Text that is
+//! not a number, inconsistent nanos and units overflowing `i64` make the conversions `TryFrom`s
+//! failing with `InvalidValue` (or the result of `error_fn`), or panic under `expect(panic)`.
+//! ```rust,ignore
+//! protto::impl_units_nanos!(proto::MoneyAmount);
+//!
+//! #[protto(decimal = "string")]
+//! pub amount: Decimal,  // proto: string
+//! #[protto(decimal = "units_nanos")]
+//! pub total: Decimal,   // proto: MoneyAmount { int64 units; int32 nanos }
+//! ```
+//!
+//! #### `#[protto(duration = "seconds" | "millis" | "nanos")]`
+//! Carries a `std::time::Duration` or `chrono::Duration` (or `Option` of either) field as a
+//! whole number of seconds, milliseconds or nanoseconds in a proto `int64` (or `optional int64`),
+//! truncating toward zero. `chrono::Duration` requires the `chrono` feature. Counts overflowing
+//! `i64`, and proto counts the rust type cannot hold, such as negative ones for
+//! `std::time::Duration`, make both conversions `TryFrom`s failing with `InvalidValue` (or the
+//! result of `error_fn`), or panic under `expect(panic)`.
+//! ```rust,ignore
+//! #[protto(proto_name = "timeout_ms", duration = "millis")]
+//! pub timeout: Duration,  // proto: int64 timeout_ms
+//! ```
+//!
+//! #### `#[protto(with_str)]`
+//! Carries a type implementing `Display` and `FromStr`, such as `IpAddr`, `SocketAddr` or `Url`
+//! (or `Option` of one), as a proto `string` (or `optional string`): `to_string()` writes it and
+//! `parse()` reads it back. Strings that do not parse make the proto → rust conversion a
+//! `TryFrom` failing with `InvalidValue` (or the result of `error_fn`), or panic under
+//! `expect(panic)`.
+//! ```rust,ignore
+//! #[protto(with_str)]
+//! pub address: SocketAddr,  // proto: string address
+//! ```
+//!
+//! #### `#[protto(json)]`
+//! Carries a type implementing serde's `Serialize` and `DeserializeOwned` (or `Option` of one)
+//! as a JSON document in a proto `string` (or `optional string`), for legacy fields holding JSON
+//! blobs. Requires the `json` feature. `serde_json::to_string` writes it and
+//! `serde_json::from_str` reads it back; since either can fail, both conversions become
+//! `TryFrom`, failing with `InvalidValue` (or the result of `error_fn`), unless the field is
+//! `expect(panic)`.
+//! ```rust,ignore
+//! #[protto(json)]
+//! pub settings: WidgetSettings,  // proto: string settings, e.g. {"width":240}
+//! ```
+//!
+//! #### `#[protto(map_by = "key")]`
+//! Carries a `HashMap<K, V>` or `BTreeMap<K, V>` as a repeated proto message, keyed by the named
+//! field of each element. The key is converted with `Into<K>` and the element with `Into<V>`, so
+//! `V` may be the proto message itself or a derived type; of two elements with the same key, the
+//! later one is kept. Going back to proto, the elements are written sorted by key, so a
+//! `HashMap` converts deterministically.
+//! ```rust,ignore
+//! #[protto(map_by = "key")]
+//! pub settings: HashMap<String, Setting>,  // proto: repeated Setting settings
+//! ```
+//!
+//! #### `#[protto(proto_boxed)]`
+//! For a message field prost generates as `Option<Box<T>>`, either configured with
+//! `prost_build::Config::boxed` or boxed because the message is recursive. The box is removed
+//! on the way in and added back on the way out; the rust field may itself be a `Box<T>` or
+//! `Option<Box<T>>`. Recursive fields, and fields recorded by
+//! [`build::generate_metadata_with_boxed`], need no attribute.
+//! ```rust,ignore
+//! #[protto(proto_boxed, expect)]
+//! pub header: Header,  // proto: Option<Box<Header>>
+//! ```
+//!
+//! #### `#[protto(proto_field_type = "Type")]`
+//! The type prost generates for the proto field, when a build config replaced the default one,
+//! e.g. a `uint64` swapped for an id newtype with `field_attribute`/`type_attribute` and
+//! `serde(with)`. The derive stops assuming the prost scalar type, so a primitive rust field is
+//! converted with `Into` (both ways) rather than assigned directly.
+//! ```rust,ignore
+//! #[protto(proto_field_type = "crate::ids::AccountId")]
+//! pub id: u64,  // AccountId: From<u64>, u64: From<AccountId>
+//! ```
+//!
+//! #### `#[protto(alias_of = "Type")]`
+//! The type a field's type alias stands for. The derive reads field types by their text, so an
+//! alias like `UserId` for `u64` looks like a message type; `alias_of` makes the field convert as
+//! its target. An alias inside an `Option` or `Vec` is replaced in place. A field of an
+//! unannotated alias of a scalar fails to compile with an error at the field naming `alias_of`.
+//! ```rust,ignore
+//! pub type UserId = u64;
+//!
+//! #[protto(alias_of = "u64")]
+//! pub owner: UserId,
+//! #[protto(alias_of = "u64")]
+//! pub members: Vec<UserId>,
+//! ```
+//!
+//! #### `#[protto(kind = "primitive" | "message" | "enum")]`
+//! What the field's type is, in place of the name heuristics and the enum registry. Use it for
+//! an enum deriving `Protto` after the struct naming it or in another crate, a path-qualified
+//! type, or an alias `alias_of` cannot spell. On an `Option`, `Vec` or map field it applies to the
+//! element.
+//! ```rust,ignore
+//! #[protto(kind = "enum")]
+//! pub severity: alerts::Severity,
+//! #[protto(kind = "primitive")]
+//! pub raised_at: Millis,
+//! ```
+//!
+//! #### `#[protto(memoize)]`
+//! For a `Vec<Arc<T>>` field mapped to a repeated proto message, such as telemetry batches
+//! repeating the same resource thousands of times. Each sub-message is keyed by its encoded
+//! bytes, so identical ones are converted once and share one `Arc<T>`; going back to proto,
+//! elements sharing an `Arc` are converted once. Requires the `memoize` feature, and `T: Clone`.
+//! ```rust,ignore
+//! #[protto(memoize)]
+//! pub resources: Vec<Arc<Resource>>,  // proto: repeated ResourceMessage
+//! ```
+//!
+//! #### `#[protto(unspecified = "error" | "default" | "map_to = Variant")]`
+//! For an enum field whose proto enum starts with the `FOO_UNSPECIFIED = 0` value proto style
+//! requires, when the rust enum has no variant for it. A 0 on the wire (or an unset `optional`
+//! enum) fails the conversion with `InvalidValue` (or the result of `error_fn`), takes the
+//! enum's `Default`, or becomes the named variant; other values convert as usual. On an
+//! `Option<Enum>` field `"default"` is `None`, an unset `optional` field is always `None`, and
+//! `None` is written back as 0. With the build-script metadata, the enum itself may leave the
+//! `*_UNSPECIFIED = 0` value uncovered.
+//! ```rust,ignore
+//! #[protto(unspecified = "map_to = Queued")]
+//! pub phase: TaskPhase,  // proto: TaskPhase phase, with TASK_PHASE_UNSPECIFIED = 0
+//! ```
+//!
+//! #### `#[protto(bool_enum(proto_enum = "Enum", true = "Variant", false = "Variant", unknown = "..."))]`
+//! Maps a `bool` onto two variants of a proto enum, for legacy protos using enums like
+//! `ENABLED`/`DISABLED`. `unknown` decides what any other enum value converts to: `"panic"`
+//! (the default), `"true"`, `"false"`, or `"error"`, which makes the proto → rust conversion a
+//! `TryFrom` returning `InvalidValue` (or the result of `error_fn`).
+//! ```rust,ignore
+//! #[protto(proto_name = "state", bool_enum(proto_enum = "FeatureState", true = "Enabled", false = "Disabled", unknown = "error"))]
+//! pub enabled: bool,
+//! ```
+//!
+//! #### `#[protto(result_oneof(ok = "field", err = "field"))]`
+//! Maps a `Result<T, E>` onto a proto oneof holding one field per outcome, converting the value
+//! of either with `Into`. The prost oneof enum is taken to be the one prost generates for the
+//! message and field, e.g. `proto::get_user_response::Outcome`; `proto_enum` names it otherwise.
+//! An unset oneof is handled by the field's `expect` mode, or read as `None` by an
+//! `Option<Result<T, E>>` field.
+//! ```rust,ignore
+//! // oneof outcome { User user = 1; ErrorInfo error = 2; }
+//! #[protto(result_oneof(ok = "user", err = "error"), expect)]
+//! pub outcome: Result<User, ErrorInfo>,
+//! ```
+//!
+//! #### Map fields
+//! A proto map converts into a `HashMap<K, V>` or `BTreeMap<K, V>` whose keys and values each
+//! convert with `Into`, so values may be derived structs. A `BTreeMap` is walked in key order
+//! going back to proto; with prost's `btree_map` configuration for the field, the proto map and
+//! so its encoding follow that order, which keeps serialized bytes reproducible.
+//!
+//! Proto map keys cannot be enums, so a map keyed by a rust enum (with `TryFrom<i32>`, as an
+//! `enum_as_i32` enum has, and `Ord` for a `BTreeMap`) sets `enum_keys` for a proto map with
+//! `int32` keys. Keys matching no variant fail the conversion with `InvalidValue` (or the
+//! result of `error_fn`) under `"error"`, panic under `"panic"` (the default for bare
+//! `enum_keys`), or become the enum's `Default` under `"default"`.
+//! ```rust,ignore
+//! #[protto(enum_keys = "error")]
+//! pub quotas: BTreeMap<Tier, u32>,  // proto: map<int32, uint32> quotas
+//! ```
+//!
+//! #### `#[protto(resolve_with = "Resolver::fetch")]`
+//! Resolves the field through an async lookup on the proto value, e.g. turning a user id into a
+//! user record. The function has the signature
+//! `async fn(&Resolver, ProtoField) -> Result<T, E>` where `E: Into<ErrorType>`. Structs with
+//! resolved fields generate `from_proto_async` instead of a sync `From`/`TryFrom`; other fields
+//! convert as usual and rust → proto is unaffected.
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(resolver = Directory, error_type = LookupError, error_fn = "LookupError::missing_field")]
+//! struct Order {
+//!     #[protto(proto_name = "owner_id", proto_required, resolve_with = "Directory::fetch_user")]
+//!     pub owner: User,
+//! }
+//!
+//! let order = Order::from_proto_async(proto_order, &directory).await?;
+//! ```
+//!
 //! #### Optionality Control
 //!
 //! ##### `#[protto(proto_optional)]`
@@ -153,21 +778,61 @@
 //! ##### `#[protto(proto_required)]`
 //! Explicitly treats the proto field as required.
 //! ```rust,ignore
-//! #[protto(proto_required)]
+//! #[protto(proto_required, none_as = "default")]
 //! pub field: Option<String>,  // proto field is String, gets wrapped
 //! ```
 //!
+//! ##### `#[protto(required)]` and `#[protto(optional)]`
+//! Shorthands for the two common proto `optional` cases, expanded before any other key is read:
+//! `required` stands for `proto_optional, expect(error)` and `optional` for `proto_optional`.
+//! ```rust,ignore
+//! #[protto(required)]
+//! pub limit: u64,  // MissingField("limit") when the proto field is None
+//! #[protto(optional)]
+//! pub burst: Option<u64>,  // passed through, None to None
+//! ```
+//!
+//! ##### `#[protto(none_as = "default" | "error" | "panic")]`
+//! Required whenever an `Option<T>` field maps to a required proto field, since `None` has no
+//! proto representation. `"default"` writes the proto default, `"panic"` panics, and `"error"`
+//! makes the rust → proto conversion fallible: `TryFrom<MyStruct>` for the proto type replaces
+//! `Into`, returning `MissingField` (or the result of `error_fn`) for a `None`.
+//! ```rust,ignore
+//! #[protto(proto_required, none_as = "error")]
+//! pub street: Option<String>,  // proto::Msg::try_from(my_struct) fails if None
+//! ```
+//!
+//! ##### `#[protto(none_as_empty)]` and `#[protto(none_as_unset)]`
+//! State how a `None` is written for APIs that tell an empty value from an unset one.
+//! `none_as_empty` writes the proto default: an optional proto field is set to it (`Some("")`),
+//! and a required one gets it as with `none_as = "default"`. `none_as_unset` leaves an optional
+//! proto field `None`, as a plain `Option` does, and is a compile error on a required one. Only
+//! one of `none_as`, `none_as_empty` and `none_as_unset` may be given.
+//! ```rust,ignore
+//! #[protto(none_as_empty)]
+//! pub nickname: Option<String>,  // None -> Some(""), the field is present on the wire
+//! #[protto(none_as_unset)]
+//! pub bio: Option<String>,  // None -> None, the field is absent
+//! ```
+//!
 //! #### Error Handling
 //!
 //! ##### `#[protto(expect)]`
-//! Uses `.expect()` with panic on missing optional fields.
+//! States what a missing proto value becomes. Bare `expect` (or `expect(error)`) returns a
+//! `MissingField` error, `expect(panic)` panics, and `expect = "default"` or
+//! `expect(default = "function")` fall back to a default. Combining `expect` with `default` or
+//! `default_fn` is a compile error.
 //! ```rust,ignore
-//! #[protto(expect)]
+//! #[protto(expect(panic))]
 //! pub required_field: String,  // panics if proto field is None
+//!
+//! #[protto(expect(default = "default_count"))]
+//! pub count: u32,  // default_count() if proto field is None
 //! ```
 //!
 //! ##### `#[protto(error_type = ErrorType)]`
-//! Field-level error type override.
+//! Restates the struct-level error type; a struct converts with a single error type, so a
+//! field-level `error_type` without a matching struct-level one is a compile error.
 //!
 //! ##### `#[protto(error_fn = "function")]`
 //! Custom error handling function for this field.
@@ -175,6 +840,22 @@
 //! #[protto(expect, error_fn = "handle_missing_field")]
 //! pub critical_field: String,
 //! ```
+//! A failing field builds its error with the first of: its own `error_fn`, the struct-level
+//! `error_fn`, or the generated `<Struct>ConversionError` variant. The generated type only exists
+//! without a struct-level `error_type`, so with one, every field that can fail needs an
+//! `error_fn` at one of the two levels. Each function's signature is checked at the first field
+//! using it: it takes the field name (and the value, for `error_fn_with_value`) and returns a
+//! type the struct's error type converts from with `?`.
+//!
+//! ##### `#[protto(error_fn_with_value = "function")]`
+//! Like `error_fn`, but the function also receives the proto value that failed to convert, e.g. an
+//! unknown enum discriminant, and `None` for a missing field. Also valid at the struct level.
+//! ```rust,ignore
+//! fn rejected(field: &'static str, value: &dyn std::fmt::Debug) -> MyError { ... }
+//!
+//! #[protto(fallible, error_fn_with_value = "rejected")]
+//! pub track: Option<ValidTrack>,
+//! ```
 //!
 //! #### Default Values
 //!
@@ -272,6 +953,104 @@
 //! }
 //! ```
 //!
+//! An enum whose variants have explicit discriminants converts by number instead, so its
+//! variants may be named freely. With the build-script metadata, each discriminant must be a
+//! value of the proto enum or the build fails.
+//!
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! pub enum Status {
+//!     Ok = 0,
+//!     Redirect = 1,        // STATUS_MOVED_PERMANENTLY
+//!     Missing = 3,         // STATUS_NOT_FOUND
+//! }
+//! ```
+//!
+//! With the build-script metadata, the derive knows every value of the proto enum, aliases
+//! included. Variants matched by name then convert by the matched value's number, so a variant
+//! named after an alias prost leaves out of its generated enum (`JOB_PHASE_RUNNING = 1` beside
+//! `JOB_PHASE_STARTED = 1` under `allow_alias`) still converts, and reading a number back gives
+//! the first variant with it. A variant matching no proto value, or a proto value no variant
+//! covers, fails the build instead of panicking when the value is converted.
+//!
+//! Variant names are matched the way prost names enum values, through [`naming`]: acronyms stay
+//! one word, so `HttpServer2` and `HTTPServer2` both match `STATUS_HTTP_SERVER2`, as well as the
+//! `STATUS_HTTP_SERVER_2` spelling prost gives the same variant name.
+//!
+//! ### Sharing a oneof across messages
+//!
+//! Messages that embed the same oneof structure can share one Rust enum. The enum lists every
+//! prost oneof enum it converts to and from (relative to `module`), so the `From` impls are
+//! generated once; each struct field then names its proto oneof with `oneof`. Every variant wraps
+//! one value, converted with `Into`, or is a unit variant standing for an empty marker message
+//! such as `message Ping {}`, sent as the message's `Default`.
+//!
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(oneof = "envelope::Payload, notice::Payload")]
+//! pub enum Payload {
+//!     Text(String),
+//!     Code(u64),
+//!     Ping,  // Ping ping = 4; for message Ping {}
+//! }
+//!
+//! #[derive(Protto)]
+//! pub struct Envelope {
+//!     #[protto(oneof = "payload")]
+//!     pub payload: Option<Payload>,
+//! }
+//!
+//! #[derive(Protto)]
+//! pub struct Notice {
+//!     #[protto(oneof = "payload", expect(panic))]
+//!     pub payload: Payload,
+//! }
+//! ```
+//!
+//! ### Types with their own proto representation
+//!
+//! A field whose type implements [`ProtoRepr`] converts through its `from_proto_repr` and
+//! `to_proto_repr` instead of `From`/`Into`, alone or inside an `Option` or a `Vec`, so library
+//! types can publish a proto mapping that works in any derived struct without attributes. See
+//! [`repr`].
+//!
+//! ```rust,ignore
+//! impl protto::ProtoRepr for Cents {
+//!     type ProtoType = String;
+//!
+//!     fn from_proto_repr(proto: String) -> Self { Cents(proto.parse().unwrap_or_default()) }
+//!     fn to_proto_repr(self) -> String { self.0.to_string() }
+//! }
+//!
+//! #[derive(Protto)]
+//! pub struct InvoiceLine {
+//!     pub price: Cents,            // `string price = 1;`
+//!     pub discount: Option<Cents>, // `optional string discount = 2;`
+//! }
+//! ```
+//!
+//! ### Reading the schema from prost's code
+//!
+//! Without a build script, `#[protto::protto_schema]` on the module holding the prost-generated
+//! code gives the derive the same exact field shapes: prost writes `#[prost(string, optional)]`,
+//! `#[prost(message, repeated)]`, `#[prost(map = "...")]` or `#[prost(enumeration = "...")]` on
+//! every field, and the attribute reads them, following `include!` of a string literal or of
+//! `concat!(env!("OUT_DIR"), "/...")`. Messages the build metadata also records keep its version.
+//! The module must come before the structs converting to its messages in the same crate.
+//!
+//! ```rust,ignore
+//! #[protto::protto_schema]
+//! pub mod proto {
+//!     include!(concat!(env!("OUT_DIR"), "/service.rs"));
+//! }
+//!
+//! #[derive(Protto)]
+//! pub struct Profile {
+//!     pub nickname: String, // `optional string nickname = 1;`, read as `Option<String>`
+//!     pub type_: String,    // prost's `r#type`
+//! }
+//! ```
+//!
 //! ### Error handling strategies
 //!
 //! ```rust,ignore
@@ -340,6 +1119,18 @@
 //! - Rust enums ↔ proto enums (with automatic prefix handling)
 //! - Custom discriminant handling
 //!
+//! ### Borrowed Structs
+//! - Structs with lifetime parameters only derive the rust → proto conversion
+//! - The proto message owns its data, so from-proto is only possible into owned types
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! #[protto(proto_name = "Header")]
+//! pub struct HeaderView<'a> {
+//!     pub request_id: &'a str,  // converted with `.into()`, no intermediate owned struct
+//!     pub timestamp: i64,
+//! }
+//! ```
+//!
 //! ## Error Handling
 //!
 //! The macro supports multiple error handling strategies:
@@ -349,6 +1140,132 @@
 //! 3. **Custom Errors**: Use `#[protto(expect, error_type = T, error_fn = "f")]` - custom error handling
 //! 4. **Result Types**: Generated `TryFrom` implementations for fallible conversions
 //!
+//! [`options::FromProtoWithOptions::from_proto_with_options`], implemented for every type
+//! converting from a proto message, converts with [`options::ConvertOptions`] in effect: its lenient policies read missing fields, unknown enum
+//! values and `ArrayVec` overflows declared as errors as the proto default instead, so the same
+//! build can be strict in staging and lenient in production.
+//!
+//! ## Cargo Features
+//!
+//! ### `schema-drift-tests`
+//! Generates a `#[test]` per derived struct that converts a `Default` proto message into the
+//! struct and back, asserting every mapped proto field survives the round trip. It is a canary
+//! for proto regenerations that change field shapes in ways the inference silently absorbs.
+//! A struct that cannot be built from a default message (e.g. one with an `expect` field) fails
+//! its test, explaining that the round trip could not run.
+//! ```toml
+//! [dev-dependencies]
+//! protto = { version = "0.6", features = ["schema-drift-tests"] }
+//! ```
+//!
+//! ### `arena`
+//! Enables `#[protto(arena)]` and the `protto::arena` runtime: the `FromProtoIn` trait its
+//! fields convert through and the re-exported `bumpalo`. Experimental.
+//!
+//! ### `build`
+//! Enables the [`build`] module for build scripts. [`build::generate_metadata`] records the
+//! shape of every proto field (repeated, map, optional or required) in `OUT_DIR`, and the derive
+//! uses it in place of inferring those shapes from the Rust field types.
+//! [`build::generate_metadata_with_boxed`] takes the same paths passed to
+//! `prost_build::Config::boxed`, so boxed message fields are recognized too. The metadata also
+//! records the fields prost renames (`type` to `r#type`, `self` to `self_`, `displayName` to
+//! `display_name`), so a Rust field `type_` or a `proto_name` spelled as in the `.proto` file
+//! maps to the generated field without further attributes.
+//! [`build::generate_nested_metadata`] records that prost nests each package in its own modules,
+//! so `module = "proto"` resolves to `proto::media::v1` for a message of package `media.v1`.
+//! ```toml
+//! [build-dependencies]
+//! protto = { version = "0.6", features = ["build"] }
+//! ```
+//!
+//! ### `chrono`
+//! Supports `chrono::Duration` (`TimeDelta`) fields in `#[protto(duration = "...")]`, which
+//! converts `std::time::Duration` fields without any feature.
+//!
+//! ### `decimal`
+//! Enables `#[protto(decimal = "...")]` and the [`decimal`] runtime helpers it uses, for
+//! `rust_decimal::Decimal` fields (re-exported as `protto::decimal::rust_decimal`).
+//!
+//! ### `json`
+//! Implements [`json::ProtoJson`] for every derived struct, providing `to_proto_json(&self)` and
+//! `from_proto_json(&str)`. Both go through the proto message, so field names and value
+//! encodings follow the protobuf JSON mapping, provided the proto messages implement it with
+//! `serde` (e.g. generated by `pbjson-build`). The methods are available wherever the struct's
+//! conversions and the message's serde impls exist; `from_proto_json` reports invalid JSON and
+//! failed conversions through [`json::JsonError`]. The feature also enables the field-level
+//! `#[protto(json)]`, carrying a serde type as JSON in a proto string.
+//! ```rust,ignore
+//! use protto::json::ProtoJson;
+//!
+//! let json = header.to_proto_json();  // {"requestId":"req-1","timestamp":"42"}
+//! let header = Header::from_proto_json(&json)?;
+//! ```
+//!
+//! ### `memoize`
+//! Enables `#[protto(memoize)]` and the [`memoize`] runtime helpers it uses, which depend on
+//! `prost` to key sub-messages by their encoding.
+//!
+//! ### `meta-file`
+//! Every derive records the struct, the proto message it converts and the strategy chosen for
+//! each field in `$OUT_DIR/protto_coverage/` (crates with a build script). `build::coverage_report`
+//! (this feature includes `build`) compares the records against the `.proto` files and renders a
+//! table of the messages with and without a derived Rust type, unmapped messages first.
+//! ```rust,ignore
+//! #[test]
+//! fn print_conversion_coverage() {
+//!     let report = protto::build::coverage_report(&["proto/service.proto"], env!("OUT_DIR"));
+//!     println!("{}", report.unwrap());
+//! }
+//! ```
+//!
+//! ### `proto-compat`
+//! Also accepts the `#[proto(...)]` attributes of `proto_convert_derive`, for migrating one type
+//! at a time. Legacy keys are translated (`rename` → `proto_name`, `derive_from_with` →
+//! `from_proto_fn`, `derive_into_with` → `to_proto_fn`) and every legacy attribute emits a
+//! deprecation warning naming its exact `#[protto(...)]` replacement.
+//! ```toml
+//! [dependencies]
+//! protto = { version = "0.6", features = ["proto-compat"] }
+//! ```
+//!
+//! ### `rayon`
+//! Adds [`batch::convert_batch_par`], which converts a large batch of proto messages on rayon's
+//! thread pool and, like [`batch::convert_batch`], reports the index of the first failing
+//! message.
+//! ```rust,ignore
+//! let tracks: Vec<Track> = protto::batch::convert_batch_par(response.tracks)
+//!     .map_err(|(index, err)| format!("track {index}: {err}"))?;
+//! ```
+//!
+//! ### `tonic`
+//! Adds the [`tonic`] extension traits for gRPC handlers. [`tonic::RequestExt`] converts a
+//! `tonic::Request`'s message into a derived type with `into_domain()` (or `map_domain()`,
+//! keeping the metadata), turning a failed conversion into `Status::invalid_argument`, and
+//! [`tonic::IntoResponse`] wraps a value's proto message in a `tonic::Response`.
+//! ```rust,ignore
+//! use protto::tonic::{IntoResponse, RequestExt};
+//!
+//! let command: CreateUser = request.into_domain()?;
+//! Ok(service.create(command)?.into_response())
+//! ```
+//!
+//! ### `wrapper_types`
+//! Converts an `Option<T>` field through a proto field holding a `google.protobuf` wrapper
+//! message (`UInt64Value`, `StringValue`, ...), `Some(5)` to `Some(UInt64Value { value: 5 })`,
+//! for builds that generate the wrappers as messages instead of prost's plain `Option<T>`.
+//! Wrapper fields are recognized from the [`build`] metadata, and the wrapper messages implement
+//! [`wrappers::WrapperValue`], usually through `protto::impl_wrapper_types!`.
+//! ```rust,ignore
+//! protto::impl_wrapper_types!(google::protobuf::UInt64Value => u64);
+//!
+//! pub id: Option<u64>,  // proto: google.protobuf.UInt64Value id = 1;
+//! ```
+//!
+//! ### `zerocopy`
+//! Enables `#[protto(bytes_as = "pod_slice")]` and the [`pod`] runtime helpers it uses, for
+//! packing a `Vec` of plain-old-data structs into a proto `bytes` field. Element types implement
+//! zerocopy's `FromBytes`, `IntoBytes` and `Immutable` (re-exported as `protto::pod::zerocopy`).
+//!
 //! ## Limitations
 //!
 //! - Assumes Protobuf-generated types live in a single module (configurable).
@@ -368,3 +1285,57 @@
 
 // re-export the derive macro
 pub use protto_derive::*;
+pub use repr::ProtoRepr;
+
+pub mod batch;
+pub mod collection;
+pub mod duration;
+pub mod enums;
+pub mod naming;
+pub mod newtype;
+pub mod oneof;
+pub mod options;
+pub mod parts;
+pub mod path_encoding;
+pub mod repr;
+pub mod type_alias;
+pub mod version;
+
+#[cfg(feature = "any")]
+pub mod any;
+
+#[cfg(feature = "arena")]
+pub mod arena;
+
+#[cfg(feature = "arrayvec")]
+pub mod bounded;
+
+#[cfg(feature = "build")]
+pub mod build;
+
+#[cfg(feature = "decimal")]
+pub mod decimal;
+
+#[cfg(feature = "json")]
+pub mod json;
+
+#[cfg(feature = "memoize")]
+pub mod memoize;
+
+#[cfg(feature = "zerocopy")]
+pub mod pod;
+
+#[cfg(feature = "size-estimate")]
+pub mod size;
+
+#[cfg(feature = "tonic")]
+pub mod tonic;
+
+#[cfg(feature = "trace-runtime")]
+pub mod trace;
+
+#[cfg(feature = "validate")]
+pub mod validate;
+
+#[cfg(feature = "wrapper_types")]
+pub mod wrappers;
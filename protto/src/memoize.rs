@@ -0,0 +1,108 @@
+//! Runtime helpers used by generated code for `Vec<Arc<T>>` fields marked
+//! `#[protto(memoize)]`.
+//!
+//! Repeated sub-messages are keyed by their encoded bytes, so identical messages are converted
+//! once and share one `Arc<T>`. In the other direction, elements sharing an allocation are
+//! converted once and the resulting message is cloned.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub use prost;
+
+/// Convert proto messages to shared Rust values, converting each distinct message only once.
+pub fn from_messages<P, T>(messages: Vec<P>) -> Vec<Arc<T>>
+where
+    P: prost::Message,
+    T: From<P>,
+{
+    let mut cache: HashMap<Vec<u8>, Arc<T>> = HashMap::new();
+    let mut values = Vec::with_capacity(messages.len());
+
+    for message in messages {
+        let value = cache
+            .entry(message.encode_to_vec())
+            .or_insert_with(|| Arc::new(T::from(message)));
+        values.push(Arc::clone(value));
+    }
+
+    values
+}
+
+/// Convert shared Rust values to proto messages, converting each shared allocation only once.
+pub fn to_messages<T, P>(values: Vec<Arc<T>>) -> Vec<P>
+where
+    T: Clone + Into<P>,
+    P: Clone,
+{
+    let mut cache: HashMap<*const T, P> = HashMap::new();
+    let mut messages = Vec::with_capacity(values.len());
+
+    for value in values {
+        let key = Arc::as_ptr(&value);
+        if let Some(message) = cache.get(&key) {
+            messages.push(message.clone());
+            continue;
+        }
+
+        // A uniquely owned value cannot appear again, so only shared ones are cached. Addresses
+        // never collide: every input allocation is alive at once, before any is dropped here.
+        let shared = Arc::strong_count(&value) > 1;
+        let message: P = Arc::unwrap_or_clone(value).into();
+        if shared {
+            cache.insert(key, message.clone());
+        }
+        messages.push(message);
+    }
+
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct Resource {
+        #[prost(string, tag = "1")]
+        host: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Host(String);
+
+    impl From<Resource> for Host {
+        fn from(resource: Resource) -> Self {
+            Self(resource.host)
+        }
+    }
+
+    impl From<Host> for Resource {
+        fn from(host: Host) -> Self {
+            Self { host: host.0 }
+        }
+    }
+
+    fn resource(host: &str) -> Resource {
+        Resource {
+            host: host.to_string(),
+        }
+    }
+
+    #[test]
+    fn identical_messages_share_one_value() {
+        let hosts: Vec<Arc<Host>> =
+            from_messages(vec![resource("a"), resource("b"), resource("a")]);
+        assert!(Arc::ptr_eq(&hosts[0], &hosts[2]));
+        assert!(!Arc::ptr_eq(&hosts[0], &hosts[1]));
+        assert_eq!(*hosts[1], Host("b".to_string()));
+    }
+
+    #[test]
+    fn shared_and_unique_values_convert_in_order() {
+        let shared = Arc::new(Host("a".to_string()));
+        let values = vec![shared.clone(), Arc::new(Host("b".to_string())), shared];
+        let messages: Vec<Resource> = to_messages(values);
+        assert_eq!(messages, vec![resource("a"), resource("b"), resource("a")]);
+    }
+}
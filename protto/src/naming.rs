@@ -0,0 +1,109 @@
+//! The name conversions prost-build applies to `.proto` names, for code that has to agree with
+//! the names prost generates: snake_case fields and modules, UpperCamelCase types and enum
+//! variants, and the SCREAMING_SNAKE_CASE protoc style uses for enum values.
+//!
+//! Names are split into words the way prost-build does through `heck`: at every character that
+//! is not a letter or digit, between a lowercase letter and an uppercase one, and before the last
+//! capital of an acronym followed by lowercase (`HTTPServer` is `HTTP`, `Server`). Digits belong
+//! to the word before them, so `HTTPServer2` is `HTTP`, `Server2`.
+//!
+//! ```
+//! use protto::naming;
+//!
+//! assert_eq!(naming::to_snake_case("displayName"), "display_name");
+//! assert_eq!(naming::to_upper_camel_case("HTTP_STATUS"), "HttpStatus");
+//! assert_eq!(naming::to_screaming_snake_case("HTTPServer2"), "HTTP_SERVER2");
+//! ```
+
+/// Splits a name into the words prost-build sees in it.
+pub fn words(name: &str) -> Vec<&str> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mode {
+        Boundary,
+        Lowercase,
+        Uppercase,
+    }
+
+    let mut words = Vec::new();
+    for word in name.split(|c: char| !c.is_alphanumeric()) {
+        let mut chars = word.char_indices().peekable();
+        let mut start = 0;
+        let mut mode = Mode::Boundary;
+        while let Some((i, c)) = chars.next() {
+            let Some(&(next_i, next)) = chars.peek() else {
+                words.push(&word[start..]);
+                break;
+            };
+            let next_mode = if c.is_lowercase() {
+                Mode::Lowercase
+            } else if c.is_uppercase() {
+                Mode::Uppercase
+            } else {
+                mode
+            };
+            if next_mode == Mode::Lowercase && next.is_uppercase() {
+                words.push(&word[start..next_i]);
+                start = next_i;
+                mode = Mode::Boundary;
+            } else if mode == Mode::Uppercase && c.is_uppercase() && next.is_lowercase() {
+                words.push(&word[start..i]);
+                start = i;
+                mode = Mode::Boundary;
+            } else {
+                mode = next_mode;
+            }
+        }
+    }
+    words
+}
+
+/// The snake_case identifier prost generates for a field or a package, e.g. `startMs` ->
+/// `start_ms` and `HTTPRequest` -> `http_request`. Keywords are not escaped here.
+pub fn to_snake_case(name: &str) -> String {
+    words(name)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// The UpperCamelCase identifier prost generates for a message, enum, enum value or oneof, e.g.
+/// `payment_method` -> `PaymentMethod` and `HTTP_STATUS` -> `HttpStatus`.
+pub fn to_upper_camel_case(name: &str) -> String {
+    words(name)
+        .iter()
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().into_iter().flat_map(char::to_uppercase);
+            first.chain(chars.flat_map(char::to_lowercase))
+        })
+        .collect()
+}
+
+/// The SCREAMING_SNAKE_CASE form of a name, as protoc style writes enum values, e.g.
+/// `NotFound` -> `NOT_FOUND` and `HTTPStatus` -> `HTTP_STATUS`.
+pub fn to_screaming_snake_case(name: &str) -> String {
+    words(name)
+        .iter()
+        .map(|word| word.to_uppercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_convert_like_prost() {
+        assert_eq!(to_snake_case("startMs"), "start_ms");
+        assert_eq!(to_snake_case("XMLHttpRequest"), "xml_http_request");
+        assert_eq!(to_snake_case("FIELD_NAME11"), "field_name11");
+        assert_eq!(to_snake_case("abc123DEF456"), "abc123_def456");
+        assert_eq!(to_upper_camel_case("payment_method"), "PaymentMethod");
+        assert_eq!(to_upper_camel_case("HTTP_SERVER_2"), "HttpServer2");
+        assert_eq!(to_screaming_snake_case("OK"), "OK");
+        assert_eq!(to_screaming_snake_case("IPv4Addr"), "I_PV4_ADDR");
+        assert_eq!(to_screaming_snake_case("Ipv4Addr"), "IPV4_ADDR");
+    }
+}
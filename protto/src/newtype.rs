@@ -0,0 +1,32 @@
+//! Newtypes over a collection, converted element by element when they are transparent fields.
+//!
+//! Deriving `Protto` on a single-field tuple struct over a `Vec` or a map implements
+//! [`CollectionNewtype`] for it. A `#[protto(transparent)]` field of that type then carries the
+//! proto `repeated` or `map` field, converting each element with `From`/`Into`:
+//!
+//! ```rust,ignore
+//! #[derive(Protto)]
+//! pub struct Tags(Vec<Tag>);
+//!
+//! #[derive(Protto)]
+//! #[protto(module = "proto", proto_name = "Post")]
+//! pub struct Post {
+//!     #[protto(transparent)]
+//!     pub tags: Tags, // repeated TagMessage tags = 1;
+//! }
+//! ```
+//!
+//! The newtype has to be derived before the structs using it, since the derive of those structs
+//! decides how to convert the field from the types seen so far.
+
+/// A newtype wrapping a collection, and the conversions to and from that collection.
+pub trait CollectionNewtype: Sized {
+    /// The wrapped collection, such as `Vec<Tag>` or `HashMap<String, Tag>`
+    type Collection;
+
+    /// Wraps the collection.
+    fn from_collection(collection: Self::Collection) -> Self;
+
+    /// Unwraps the collection.
+    fn into_collection(self) -> Self::Collection;
+}
@@ -0,0 +1,25 @@
+//! Runtime support for oneof enums deriving with `#[protto(non_exhaustive_oneof = "error_variant")]`.
+//!
+//! Such an enum may cover only some of the variants of its prost oneofs, so the proto → rust
+//! conversion is a `TryFrom` that fails with [`UnmappedOneofVariant`] for the rest.
+
+use std::fmt;
+
+/// A proto oneof holding a variant the rust enum has no counterpart for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnmappedOneofVariant {
+    /// The rust enum the oneof was converted into.
+    pub target: &'static str,
+}
+
+impl fmt::Display for UnmappedOneofVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "proto oneof variant has no counterpart in {}",
+            self.target
+        )
+    }
+}
+
+impl std::error::Error for UnmappedOneofVariant {}
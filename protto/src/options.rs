@@ -0,0 +1,213 @@
+//! Runtime options relaxing the failures a derived conversion declares, so one binary can convert
+//! strictly in staging and leniently in production without a second set of attributes.
+//!
+//! [`FromProtoWithOptions::from_proto_with_options`], implemented for every type converting from
+//! a proto message with `From`/`TryFrom`, runs that conversion with the options in effect on the
+//! current thread, nested conversions included. A [`Policy::Lenient`] policy reads a value that would fail as
+//! the proto default:
+//!
+//! - `missing_field`: a missing proto value of an `expect(error)` field, other than an unset
+//!   `result_oneof`, which has no default
+//! - `unknown_enum`: an `int32` matching no variant of an `enum_as_i32`, `enum_values`,
+//!   `enum_keys` or `bool_enum` field whose unknown values are errors (`bool_enum` reads it as
+//!   `false` unless the `true` variant is `0`)
+//! - `overflow`: elements past the capacity of an `ArrayVec` field with `overflow = "error"`,
+//!   which are truncated
+//!
+//! [`Policy::Strict`] keeps the declared error. Conversions outside `from_proto_with_options`
+//! and [`with_options`] use [`ConvertOptions::STRICT`]; so do those handed to other threads,
+//! except the workers of [`convert_batch_par`](crate::batch), which take the caller's options.
+//!
+//! ```rust,ignore
+//! use protto::options::{ConvertOptions, FromProtoWithOptions};
+//!
+//! let options = if cfg.production { ConvertOptions::LENIENT } else { ConvertOptions::STRICT };
+//! let order = Order::from_proto_with_options(proto_order, &options)?;
+//! ```
+
+use std::cell::Cell;
+
+/// How a conversion treats one kind of invalid proto value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Policy {
+    /// Fail the conversion as the field's attributes declare
+    #[default]
+    Strict,
+    /// Read the value as the proto default instead of failing
+    Lenient,
+}
+
+impl Policy {
+    pub fn is_lenient(self) -> bool {
+        self == Self::Lenient
+    }
+}
+
+/// The policies a conversion started with `from_proto_with_options` applies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ConvertOptions {
+    pub unknown_enum: Policy,
+    pub missing_field: Policy,
+    pub overflow: Policy,
+}
+
+impl ConvertOptions {
+    /// Every failure is an error, as in a plain `TryFrom`.
+    pub const STRICT: Self = Self {
+        unknown_enum: Policy::Strict,
+        missing_field: Policy::Strict,
+        overflow: Policy::Strict,
+    };
+
+    /// Every failure the options cover falls back to the proto default.
+    pub const LENIENT: Self = Self {
+        unknown_enum: Policy::Lenient,
+        missing_field: Policy::Lenient,
+        overflow: Policy::Lenient,
+    };
+}
+
+thread_local! {
+    static CURRENT: Cell<ConvertOptions> = const { Cell::new(ConvertOptions::STRICT) };
+}
+
+/// Runs `convert` with `options` in effect on this thread, restoring the previous options
+/// afterwards, even if `convert` panics.
+pub fn with_options<R>(options: &ConvertOptions, convert: impl FnOnce() -> R) -> R {
+    struct Restore(ConvertOptions);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT.with(|current| current.set(self.0));
+        }
+    }
+
+    let _restore = Restore(CURRENT.with(|current| current.replace(*options)));
+    convert()
+}
+
+/// Converts the proto message `P` into `Self` with [`ConvertOptions`] in effect. Implemented for
+/// every type with a `TryFrom<P>`, so both the derive's `From` and `TryFrom` conversions qualify;
+/// a `From` conversion has an uninhabited error.
+pub trait FromProtoWithOptions<P>: Sized {
+    /// The error the conversion fails with
+    type Error;
+
+    /// Converts from the proto message, applying `options` to this conversion and the nested
+    /// conversions it runs on this thread.
+    fn from_proto_with_options(proto: P, options: &ConvertOptions) -> Result<Self, Self::Error>;
+}
+
+impl<T, P> FromProtoWithOptions<P> for T
+where
+    T: TryFrom<P>,
+{
+    type Error = T::Error;
+
+    fn from_proto_with_options(proto: P, options: &ConvertOptions) -> Result<Self, Self::Error> {
+        with_options(options, || T::try_from(proto))
+    }
+}
+
+/// The options in effect on this thread.
+pub fn current() -> ConvertOptions {
+    CURRENT.with(Cell::get)
+}
+
+/// The proto default standing in for a missing value under a lenient `missing_field` policy,
+/// for generated code to chain as `value.or_else(lenient_missing).ok_or_else(...)`.
+pub fn lenient_missing<T: Default>() -> Option<T> {
+    current().missing_field.is_lenient().then(T::default)
+}
+
+/// The enum read from `value`, or under a lenient `unknown_enum` policy from the proto default
+/// `0` when `value` matches no variant.
+pub fn enum_or_default<T: TryFrom<i32>>(value: i32) -> Result<T, T::Error> {
+    T::try_from(value).or_else(|err| {
+        if current().unknown_enum.is_lenient() {
+            T::try_from(0).map_err(|_| err)
+        } else {
+            Err(err)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Level {
+        Unset = 0,
+        High = 2,
+    }
+
+    impl TryFrom<i32> for Level {
+        type Error = i32;
+
+        fn try_from(value: i32) -> Result<Self, Self::Error> {
+            match value {
+                0 => Ok(Self::Unset),
+                2 => Ok(Self::High),
+                other => Err(other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_options_apply_only_inside_with_options() {
+        assert_eq!(current(), ConvertOptions::STRICT);
+        assert_eq!(lenient_missing::<String>(), None);
+        assert_eq!(enum_or_default::<Level>(7), Err(7));
+
+        with_options(&ConvertOptions::LENIENT, || {
+            assert_eq!(lenient_missing::<String>(), Some(String::new()));
+            assert_eq!(enum_or_default::<Level>(7), Ok(Level::Unset));
+            assert_eq!(enum_or_default::<Level>(2), Ok(Level::High));
+        });
+        assert_eq!(current(), ConvertOptions::STRICT);
+    }
+
+    #[test]
+    fn test_from_proto_with_options_applies_options_to_the_conversion() {
+        struct Named(Level);
+
+        impl TryFrom<i32> for Named {
+            type Error = i32;
+
+            fn try_from(value: i32) -> Result<Self, Self::Error> {
+                enum_or_default(value).map(Self)
+            }
+        }
+
+        let strict = Named::from_proto_with_options(7, &ConvertOptions::STRICT);
+        assert_eq!(strict.map(|named| named.0), Err(7));
+        let lenient = Named::from_proto_with_options(7, &ConvertOptions::LENIENT);
+        assert_eq!(lenient.map(|named| named.0), Ok(Level::Unset));
+        assert_eq!(current(), ConvertOptions::STRICT);
+
+        let infallible: Result<i64, _> =
+            i64::from_proto_with_options(7_i32, &ConvertOptions::LENIENT);
+        assert_eq!(infallible, Ok(7));
+    }
+
+    #[test]
+    fn test_nested_options_are_restored() {
+        let missing_only = ConvertOptions {
+            missing_field: Policy::Lenient,
+            ..ConvertOptions::STRICT
+        };
+        with_options(&missing_only, || {
+            with_options(&ConvertOptions::LENIENT, || {
+                assert!(current().unknown_enum.is_lenient());
+            });
+            assert_eq!(current(), missing_only);
+        });
+
+        let result = std::panic::catch_unwind(|| {
+            with_options(&ConvertOptions::LENIENT, || panic!("conversion panicked"))
+        });
+        assert!(result.is_err());
+        assert_eq!(current(), ConvertOptions::STRICT);
+    }
+}
@@ -0,0 +1,63 @@
+//! Runtime support for fields marked `#[protto(manual)]`, which the derive leaves to a
+//! user-implemented [`FromProtoParts`] instead of generating their conversions, and for the
+//! `<Struct>ProtoParts` types generated under struct-level `#[protto(proto_parts)]`.
+
+use std::fmt;
+
+/// Converts the fields of `Self` marked `#[protto(manual)]` to and from the proto message `P`.
+///
+/// The derived conversions call [`from_proto_parts`](Self::from_proto_parts) with the whole
+/// proto message before converting any other field, and
+/// [`into_proto_parts`](Self::into_proto_parts) once the proto message has been built from the
+/// other fields, with each manual field's own proto field left at its default.
+///
+/// ```rust,ignore
+/// #[derive(Protto)]
+/// pub struct Schedule {
+///     pub name: String,
+///     #[protto(manual)]
+///     pub window: Range<u64>,
+/// }
+///
+/// impl protto::parts::FromProtoParts<proto::Schedule> for Schedule {
+///     type Parts = Range<u64>;
+///
+///     fn from_proto_parts(proto: &proto::Schedule) -> Self::Parts {
+///         proto.window.as_ref().map_or(0..0, |window| window.start..window.end)
+///     }
+///
+///     fn into_proto_parts(window: Self::Parts, proto: &mut proto::Schedule) {
+///         proto.window = Some(proto::Window { start: window.start, end: window.end });
+///     }
+/// }
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has `#[protto(manual)]` fields, so it must implement `protto::parts::FromProtoParts<{P}>`",
+    label = "the derived conversions build every other field and call into `FromProtoParts` for the manual ones",
+    note = "`Parts` is the manual field's type, or a tuple of the manual fields' types in declaration order"
+)]
+pub trait FromProtoParts<P> {
+    /// The values of the manual fields
+    type Parts;
+
+    /// Builds the manual fields from the proto message, before the derived fields move out of it.
+    fn from_proto_parts(proto: &P) -> Self::Parts;
+
+    /// Writes the manual fields into the proto message built from the derived fields.
+    fn into_proto_parts(parts: Self::Parts, proto: &mut P);
+}
+
+/// Returned by a generated `<Struct>ProtoParts::finish` when a field is still `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingPart {
+    /// The rust field left unset
+    pub field: &'static str,
+}
+
+impl fmt::Display for MissingPart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "part `{}` was never set", self.field)
+    }
+}
+
+impl std::error::Error for MissingPart {}
@@ -0,0 +1,87 @@
+//! Runtime helpers used by generated code for `PathBuf` and `OsString` fields
+//! annotated with `#[protto(path_encoding = "...")]`.
+//!
+//! The helpers accept anything convertible into an `OsString` and produce anything
+//! constructible from one, so the same functions serve both `PathBuf` and `OsString`.
+
+use std::ffi::OsString;
+
+/// Encode as a UTF-8 string, returning the original value if it is not valid UTF-8.
+pub fn to_utf8_strict(value: impl Into<OsString>) -> Result<String, OsString> {
+    value.into().into_string()
+}
+
+/// Encode as a UTF-8 string, replacing invalid sequences with `U+FFFD`.
+pub fn to_utf8_lossy(value: impl Into<OsString>) -> String {
+    let value = value.into();
+    match value.into_string() {
+        Ok(utf8) => utf8,
+        Err(value) => value.to_string_lossy().into_owned(),
+    }
+}
+
+/// Decode from a proto string.
+pub fn from_string<T: From<OsString>>(value: String) -> T {
+    T::from(OsString::from(value))
+}
+
+/// Encode as raw bytes. Lossless on unix; elsewhere the value is encoded as lossy UTF-8.
+pub fn to_bytes(value: impl Into<OsString>) -> Vec<u8> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        value.into().into_vec()
+    }
+
+    #[cfg(not(unix))]
+    {
+        to_utf8_lossy(value).into_bytes()
+    }
+}
+
+/// Decode from raw proto bytes. Lossless on unix; elsewhere the bytes are decoded as lossy UTF-8.
+pub fn from_bytes<T: From<OsString>>(value: Vec<u8>) -> T {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        T::from(OsString::from_vec(value))
+    }
+
+    #[cfg(not(unix))]
+    {
+        from_string(String::from_utf8_lossy(&value).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_utf8_round_trip() {
+        let path = PathBuf::from("/var/log/app.log");
+        let encoded = to_utf8_strict(path.clone()).unwrap();
+        assert_eq!(encoded, "/var/log/app.log");
+        assert_eq!(from_string::<PathBuf>(encoded), path);
+        assert_eq!(to_utf8_lossy(path), "/var/log/app.log");
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let os = OsString::from("data/ü.bin");
+        let encoded = to_bytes(os.clone());
+        assert_eq!(from_bytes::<OsString>(encoded), os);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_handling() {
+        use std::os::unix::ffi::OsStringExt;
+        let os = OsString::from_vec(vec![b'a', 0xff, b'b']);
+
+        assert!(to_utf8_strict(os.clone()).is_err());
+        assert_eq!(to_utf8_lossy(os.clone()), "a\u{fffd}b");
+        assert_eq!(from_bytes::<OsString>(to_bytes(os.clone())), os);
+    }
+}
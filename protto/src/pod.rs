@@ -0,0 +1,97 @@
+//! Runtime helpers used by generated code for `Vec<T>` fields of plain-old-data structs packed
+//! into a proto `bytes` field with `#[protto(bytes_as = "pod_slice")]`.
+//!
+//! Elements are laid out back to back in their in-memory (native-endian) representation, so
+//! producers and consumers must agree on the struct layout and byte order. Decoding copies each
+//! element out of the buffer, so the proto bytes need no particular alignment.
+
+use std::fmt;
+pub use zerocopy;
+use zerocopy::{FromBytes, Immutable, IntoBytes};
+
+/// A proto bytes field whose length is not a whole number of elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PodError {
+    pub len: usize,
+    pub element_size: usize,
+}
+
+impl fmt::Display for PodError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "byte length {} is not a multiple of the element size {}",
+            self.len, self.element_size
+        )
+    }
+}
+
+impl std::error::Error for PodError {}
+
+/// Encode a slice of elements as their packed bytes.
+pub fn to_bytes<T: IntoBytes + Immutable>(values: &[T]) -> Vec<u8> {
+    values.as_bytes().to_vec()
+}
+
+/// Decode packed bytes into elements, failing if the length is not a multiple of the element
+/// size.
+pub fn from_bytes<T: FromBytes>(bytes: &[u8]) -> Result<Vec<T>, PodError> {
+    let element_size = size_of::<T>();
+    let error = PodError {
+        len: bytes.len(),
+        element_size,
+    };
+
+    if element_size == 0 {
+        return if bytes.is_empty() {
+            Ok(Vec::new())
+        } else {
+            Err(error)
+        };
+    }
+    if !bytes.len().is_multiple_of(element_size) {
+        return Err(error);
+    }
+
+    bytes
+        .chunks_exact(element_size)
+        .map(|chunk| T::read_from_bytes(chunk).map_err(|_| error))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_elements() {
+        let values: Vec<[u32; 2]> = vec![[1, 2], [3, u32::MAX]];
+        let bytes = to_bytes(&values);
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(from_bytes::<[u32; 2]>(&bytes), Ok(values));
+    }
+
+    #[test]
+    fn empty_bytes_decode_to_empty_vec() {
+        assert_eq!(from_bytes::<u64>(&[]), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn rejects_partial_elements() {
+        assert_eq!(
+            from_bytes::<u32>(&[0; 6]),
+            Err(PodError {
+                len: 6,
+                element_size: 4
+            })
+        );
+    }
+
+    #[test]
+    fn decodes_unaligned_bytes() {
+        let values: Vec<u64> = vec![7, 9];
+        let mut buffer = vec![0u8];
+        buffer.extend(to_bytes(&values));
+        assert_eq!(from_bytes::<u64>(&buffer[1..]), Ok(values));
+    }
+}
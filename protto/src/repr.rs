@@ -0,0 +1,164 @@
+//! A proto representation a type declares for itself, so library types convert inside any
+//! protto-derived struct without attributes.
+//!
+//! A field whose type implements [`ProtoRepr`] converts through [`ProtoRepr::from_proto_repr`]
+//! and [`ProtoRepr::to_proto_repr`] in place of `From`/`Into`, alone or inside an `Option` or a
+//! `Vec`. The trait can be implemented next to the type, even when its `ProtoType` is a foreign
+//! type such as `prost_types::Timestamp`:
+//!
+//! ```rust,ignore
+//! impl protto::ProtoRepr for Instant {
+//!     type ProtoType = prost_types::Timestamp;
+//!
+//!     fn from_proto_repr(proto: prost_types::Timestamp) -> Self { ... }
+//!     fn to_proto_repr(self) -> prost_types::Timestamp { ... }
+//! }
+//! ```
+//!
+//! A proc macro cannot see which traits a type implements, so the derive emits
+//! [`__repr_from_proto!`](crate::__repr_from_proto) and
+//! [`__repr_to_proto!`](crate::__repr_to_proto) for custom field types, which pick the
+//! `ProtoRepr` conversion when the field's type has one and `Into` otherwise.
+
+use std::marker::PhantomData;
+
+/// The proto type a rust type is carried as, and the conversions between them.
+pub trait ProtoRepr: Sized {
+    /// The prost type of the proto field, such as a message or a scalar
+    type ProtoType;
+
+    /// Converts from the proto representation.
+    fn from_proto_repr(proto: Self::ProtoType) -> Self;
+
+    /// Converts into the proto representation.
+    fn to_proto_repr(self) -> Self::ProtoType;
+}
+
+/// Selects the conversion for `T` by method resolution: [`ViaRepr`] is implemented one reference
+/// deeper than [`ViaInto`], so it wins whenever `T: ProtoRepr` holds.
+#[doc(hidden)]
+pub struct Select<T>(PhantomData<T>);
+
+impl<T> Select<T> {
+    #[doc(hidden)]
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for Select<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaRepr<T: ProtoRepr> {
+    fn select_from_proto(self, proto: T::ProtoType) -> T;
+    fn select_to_proto(self, value: T) -> T::ProtoType;
+}
+
+impl<T: ProtoRepr> ViaRepr<T> for &&Select<T> {
+    fn select_from_proto(self, proto: T::ProtoType) -> T {
+        T::from_proto_repr(proto)
+    }
+
+    fn select_to_proto(self, value: T) -> T::ProtoType {
+        value.to_proto_repr()
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaInto<T> {
+    fn select_from_proto<P: Into<T>>(self, proto: P) -> T;
+    fn select_to_proto<P>(self, value: T) -> P
+    where
+        T: Into<P>;
+}
+
+impl<T> ViaInto<T> for &Select<T> {
+    fn select_from_proto<P: Into<T>>(self, proto: P) -> T {
+        proto.into()
+    }
+
+    fn select_to_proto<P>(self, value: T) -> P
+    where
+        T: Into<P>,
+    {
+        value.into()
+    }
+}
+
+/// Converts a proto value into the rust type `$ty`, through [`ProtoRepr`] when `$ty` implements
+/// it and `Into` otherwise.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __repr_from_proto {
+    ($ty:ty, $value:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::repr::{ViaInto as _, ViaRepr as _};
+        (&&$crate::repr::Select::<$ty>::new()).select_from_proto($value)
+    }};
+}
+
+/// Converts a value of the rust type `$ty` into its proto value, through [`ProtoRepr`] when `$ty`
+/// implements it and `Into` otherwise.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __repr_to_proto {
+    ($ty:ty, $value:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::repr::{ViaInto as _, ViaRepr as _};
+        (&&$crate::repr::Select::<$ty>::new()).select_to_proto($value)
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    #[derive(Debug, PartialEq)]
+    struct Cents(i64);
+
+    impl crate::ProtoRepr for Cents {
+        type ProtoType = String;
+
+        fn from_proto_repr(proto: String) -> Self {
+            Self(proto.parse().unwrap())
+        }
+
+        fn to_proto_repr(self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Meters(f64);
+
+    impl From<f64> for Meters {
+        fn from(value: f64) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<Meters> for f64 {
+        fn from(value: Meters) -> Self {
+            value.0
+        }
+    }
+
+    #[test]
+    fn test_repr_preferred_over_into() {
+        assert_eq!(
+            crate::__repr_from_proto!(Cents, "125".to_string()),
+            Cents(125)
+        );
+        let proto: String = crate::__repr_to_proto!(Cents, Cents(125));
+        assert_eq!(proto, "125");
+    }
+
+    #[test]
+    fn test_into_without_repr() {
+        assert_eq!(crate::__repr_from_proto!(Meters, 2.5), Meters(2.5));
+        let proto: f64 = crate::__repr_to_proto!(Meters, Meters(2.5));
+        assert_eq!(proto, 2.5);
+    }
+}
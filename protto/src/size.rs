@@ -0,0 +1,181 @@
+//! Runtime support for the `estimated_proto_size` method of structs and oneof enums marked
+//! `#[protto(estimated_size)]`, for pre-allocating buffers before converting and encoding.
+//!
+//! Estimates are taken from the rust values, without converting them. Leaf values are sized with
+//! prost's varint encoding; every field key is counted as one byte (field numbers up to 15),
+//! repeated scalars as unpacked and scalars holding their default as present, so an estimate
+//! errs on the large side of the encoded length of the proto message.
+
+pub use prost;
+use prost::encoding::encoded_len_varint;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Bytes counted for each field key
+pub const KEY_LEN: usize = 1;
+
+/// The estimated encoded size of a value held by a proto field.
+pub trait EstimatedProtoSize {
+    /// Whether the value is written with a length prefix, like strings and messages
+    const LENGTH_DELIMITED: bool = false;
+
+    /// Estimated bytes of the encoded value, without its field key or length prefix. For oneof
+    /// enums, the bytes of the whole oneof field.
+    fn estimated_proto_size(&self) -> usize;
+}
+
+/// Estimated bytes of a field holding `value`: key, length prefix and value.
+pub fn field_size<T: EstimatedProtoSize + ?Sized>(value: &T) -> usize {
+    let len = value.estimated_proto_size();
+    let prefix = if T::LENGTH_DELIMITED {
+        encoded_len_varint(len as u64)
+    } else {
+        0
+    };
+    KEY_LEN + prefix + len
+}
+
+/// Estimated bytes of a repeated field.
+pub fn repeated_size<'a, T: EstimatedProtoSize + 'a>(
+    values: impl IntoIterator<Item = &'a T>,
+) -> usize {
+    values.into_iter().map(field_size).sum()
+}
+
+/// Estimated bytes of a map field, written as one entry message per pair.
+pub fn map_size<'a, K, V>(entries: impl IntoIterator<Item = (&'a K, &'a V)>) -> usize
+where
+    K: EstimatedProtoSize + 'a,
+    V: EstimatedProtoSize + 'a,
+{
+    entries
+        .into_iter()
+        .map(|(key, value)| {
+            let len = field_size(key) + field_size(value);
+            KEY_LEN + encoded_len_varint(len as u64) + len
+        })
+        .sum()
+}
+
+/// Estimated bytes of a `bytes` field.
+pub fn bytes_size(bytes: &[u8]) -> usize {
+    KEY_LEN + encoded_len_varint(bytes.len() as u64) + bytes.len()
+}
+
+macro_rules! varint_size {
+    ($($ty:ty),*) => {$(
+        impl EstimatedProtoSize for $ty {
+            fn estimated_proto_size(&self) -> usize {
+                // negative values sign-extend to ten bytes, as prost encodes them
+                encoded_len_varint(*self as i64 as u64)
+            }
+        }
+    )*};
+}
+
+varint_size!(i32, i64, u32, u64);
+
+impl EstimatedProtoSize for bool {
+    fn estimated_proto_size(&self) -> usize {
+        1
+    }
+}
+
+impl EstimatedProtoSize for f32 {
+    fn estimated_proto_size(&self) -> usize {
+        4
+    }
+}
+
+impl EstimatedProtoSize for f64 {
+    fn estimated_proto_size(&self) -> usize {
+        8
+    }
+}
+
+impl EstimatedProtoSize for str {
+    const LENGTH_DELIMITED: bool = true;
+
+    fn estimated_proto_size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl EstimatedProtoSize for String {
+    const LENGTH_DELIMITED: bool = true;
+
+    fn estimated_proto_size(&self) -> usize {
+        self.len()
+    }
+}
+
+macro_rules! forward_size {
+    ($($wrapper:ident),*) => {$(
+        impl<T: EstimatedProtoSize + ?Sized> EstimatedProtoSize for $wrapper<T> {
+            const LENGTH_DELIMITED: bool = T::LENGTH_DELIMITED;
+
+            fn estimated_proto_size(&self) -> usize {
+                (**self).estimated_proto_size()
+            }
+        }
+    )*};
+}
+
+forward_size!(Box, Arc, Rc);
+
+impl<T: EstimatedProtoSize + ?Sized> EstimatedProtoSize for &T {
+    const LENGTH_DELIMITED: bool = T::LENGTH_DELIMITED;
+
+    fn estimated_proto_size(&self) -> usize {
+        (**self).estimated_proto_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Sample {
+        #[prost(uint64, tag = "1")]
+        id: u64,
+        #[prost(int32, tag = "2")]
+        delta: i32,
+        #[prost(string, tag = "3")]
+        name: String,
+        #[prost(bytes = "vec", tag = "4")]
+        payload: Vec<u8>,
+        #[prost(string, repeated, tag = "5")]
+        tags: Vec<String>,
+        #[prost(map = "string, uint32", tag = "6")]
+        counts: std::collections::HashMap<String, u32>,
+    }
+
+    #[test]
+    fn test_estimates_match_prost_for_non_default_values() {
+        let sample = Sample {
+            id: 300,
+            delta: -5,
+            name: "estimate".to_string(),
+            payload: vec![7; 200],
+            tags: vec!["a".to_string(), "bc".to_string()],
+            counts: [("x".to_string(), 1)].into_iter().collect(),
+        };
+
+        let estimate = field_size(&sample.id)
+            + field_size(&sample.delta)
+            + field_size(&sample.name)
+            + bytes_size(&sample.payload)
+            + repeated_size(&sample.tags)
+            + map_size(&sample.counts);
+        assert_eq!(estimate, sample.encoded_len());
+    }
+
+    #[test]
+    fn test_wrappers_forward_to_the_value() {
+        let name = Arc::new("shared".to_string());
+        assert_eq!(field_size(&name), field_size(&"shared".to_string()));
+        assert_eq!(field_size(&Box::new(1_u32)), 2);
+    }
+}
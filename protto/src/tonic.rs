@@ -0,0 +1,145 @@
+//! Extension traits for converting between `tonic` request/response wrappers and derived rust
+//! types, so gRPC handlers do not unwrap and rewrap every message by hand.
+//!
+//! ```rust,ignore
+//! use protto::tonic::{IntoResponse, RequestExt};
+//!
+//! async fn create_user(
+//!     &self,
+//!     request: tonic::Request<proto::CreateUser>,
+//! ) -> Result<tonic::Response<proto::User>, tonic::Status> {
+//!     let command: CreateUser = request.into_domain()?;
+//!     let user: User = self.users.create(command).await?;
+//!     Ok(user.into_response())
+//! }
+//! ```
+
+// handlers return `tonic::Status` as is, however large
+#![allow(clippy::result_large_err)]
+
+use std::fmt::Display;
+
+/// Converts the message of a `tonic::Request` into a derived rust type. A failed conversion is
+/// the client's fault, so it becomes `Status::invalid_argument` with the error's message.
+pub trait RequestExt<P> {
+    /// Converts the request's message, dropping its metadata and extensions.
+    fn into_domain<T>(self) -> Result<T, ::tonic::Status>
+    where
+        T: TryFrom<P>,
+        T::Error: Display;
+
+    /// Converts the request's message, keeping the request around it.
+    fn map_domain<T>(self) -> Result<::tonic::Request<T>, ::tonic::Status>
+    where
+        T: TryFrom<P>,
+        T::Error: Display;
+}
+
+impl<P> RequestExt<P> for ::tonic::Request<P> {
+    fn into_domain<T>(self) -> Result<T, ::tonic::Status>
+    where
+        T: TryFrom<P>,
+        T::Error: Display,
+    {
+        T::try_from(self.into_inner()).map_err(invalid_argument)
+    }
+
+    fn map_domain<T>(self) -> Result<::tonic::Request<T>, ::tonic::Status>
+    where
+        T: TryFrom<P>,
+        T::Error: Display,
+    {
+        let (metadata, extensions, message) = self.into_parts();
+        let message = T::try_from(message).map_err(invalid_argument)?;
+        Ok(::tonic::Request::from_parts(metadata, extensions, message))
+    }
+}
+
+/// Converts a rust value into the proto message it derives conversions for, wrapped in a
+/// `tonic::Response`. Implemented for every type, like [`crate::version::ProtoConvertTo`].
+pub trait IntoResponse: Sized {
+    /// Converts into the response's message.
+    fn into_response<P>(self) -> ::tonic::Response<P>
+    where
+        Self: Into<P>,
+    {
+        ::tonic::Response::new(self.into())
+    }
+
+    /// Converts into the response's message when the rust -> proto conversion is fallible. A
+    /// failure is the server's fault, so it becomes `Status::internal` with the error's message.
+    fn try_into_response<P>(self) -> Result<::tonic::Response<P>, ::tonic::Status>
+    where
+        Self: TryInto<P>,
+        <Self as TryInto<P>>::Error: Display,
+    {
+        self.try_into()
+            .map(::tonic::Response::new)
+            .map_err(|err| ::tonic::Status::internal(err.to_string()))
+    }
+}
+
+impl<T> IntoResponse for T {}
+
+fn invalid_argument(err: impl Display) -> ::tonic::Status {
+    ::tonic::Status::invalid_argument(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Celsius(i32);
+
+    struct Reading {
+        degrees: i64,
+    }
+
+    impl TryFrom<Reading> for Celsius {
+        type Error = std::num::TryFromIntError;
+
+        fn try_from(reading: Reading) -> Result<Self, Self::Error> {
+            i32::try_from(reading.degrees).map(Self)
+        }
+    }
+
+    impl From<Celsius> for Reading {
+        fn from(celsius: Celsius) -> Self {
+            Self {
+                degrees: i64::from(celsius.0),
+            }
+        }
+    }
+
+    #[test]
+    fn test_request_converts_into_the_domain_type() {
+        let celsius: Celsius = ::tonic::Request::new(Reading { degrees: 21 })
+            .into_domain()
+            .unwrap();
+        assert_eq!(celsius.0, 21);
+
+        let mut request = ::tonic::Request::new(Reading { degrees: -4 });
+        request
+            .metadata_mut()
+            .insert("x-station", "north".parse().unwrap());
+        let request: ::tonic::Request<Celsius> = request.map_domain().unwrap();
+        assert_eq!(request.metadata().get("x-station").unwrap(), "north");
+        assert_eq!(request.into_inner().0, -4);
+
+        let Err(status) =
+            ::tonic::Request::new(Reading { degrees: i64::MAX }).into_domain::<Celsius>()
+        else {
+            panic!("an out of range reading does not convert");
+        };
+        assert_eq!(status.code(), ::tonic::Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_value_converts_into_a_response() {
+        let response: ::tonic::Response<Reading> = Celsius(30).into_response();
+        assert_eq!(response.into_inner().degrees, 30);
+
+        let response: ::tonic::Response<Reading> = Celsius(12).try_into_response().unwrap();
+        assert_eq!(response.get_ref().degrees, 12);
+    }
+}
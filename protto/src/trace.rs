@@ -0,0 +1,9 @@
+//! Runtime support for the `trace-runtime` feature, under which the derived conversions enter a
+//! `tracing` trace span around each field converted through nested messages, collections or
+//! custom functions.
+//!
+//! Spans are named `from_proto` or `into_proto` and record the rust `struct` and `field`, so a
+//! subscriber timing spans shows which fields dominate the conversion of large messages. Scalar
+//! fields are not instrumented.
+
+pub use tracing;
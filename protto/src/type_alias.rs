@@ -0,0 +1,22 @@
+//! Compile-time check behind the derive's reading of unannotated custom field types.
+//!
+//! A field whose type the derive does not recognize, with no attribute saying how its proto field
+//! looks, converts as an optional proto message. When the type is really a type alias of a
+//! scalar, as in `type UserId = u64`, the proto field is not an `Option` and the conversion cannot
+//! compile; the derive calls [`message_field`] on each such proto field so the error names the
+//! field and points at `#[protto(alias_of = "...")]`.
+
+/// Proto fields the derive may read as an optional message: prost generates message fields as
+/// `Option<T>`.
+#[diagnostic::on_unimplemented(
+    message = "this field converts as an optional proto message, but its proto field is `{Self}`",
+    label = "protto reads this field's type as a proto message",
+    note = "if the field's type is a type alias, name the type it stands for with `#[protto(alias_of = \"...\")]`"
+)]
+pub trait MessageField {}
+
+impl<T> MessageField for Option<T> {}
+
+/// Fails to compile unless the proto field can hold the optional message its rust field
+/// converts as.
+pub fn message_field<P: MessageField>(_proto_field: &P) {}
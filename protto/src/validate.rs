@@ -0,0 +1,194 @@
+//! Runtime support for structs marked `#[protto(validate)]`, whose proto -> rust conversion checks
+//! the proto message with [`ValidateProto`] before converting any field, so a message breaking
+//! its validation rules never becomes a domain value. `validate = true` in the `[defaults]` of a
+//! `protto.toml` marks every struct of the crate.
+//!
+//! [`ValidateProto`] is implemented for every message implementing [`validator::Validate`], which
+//! the proto codegen derives when told to, e.g. with prost-build's `type_attribute` and
+//! `field_attribute`. A failure becomes the generated error's `Validation` variant, or is
+//! converted with `From` into the struct-level `error_type`.
+//!
+//! ```rust,ignore
+//! // build.rs
+//! tonic_build::configure()
+//!     .type_attribute("service.User", "#[derive(validator::Validate)]")
+//!     .field_attribute("service.User.email", "#[validate(email)]")
+//!     .compile_protos(&["proto/service.proto"], &["proto"])?;
+//!
+//! // src/lib.rs
+//! #[derive(Protto)]
+//! #[protto(validate)]
+//! pub struct User {
+//!     pub email: String,
+//! }
+//!
+//! let user = User::try_from(message)?;  // UserConversionError::Validation on a bad email
+//! ```
+//!
+//! A message checked by another validator, such as prost-validate's `Validator`, implements
+//! [`ValidateProto`] by hand, forwarding to it.
+
+use std::fmt;
+
+/// Checks a proto message against the validation rules of its schema.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` must implement `protto::validate::ValidateProto` to convert into a struct marked `#[protto(validate)]`",
+    label = "the derived conversion validates this proto message before converting it",
+    note = "derive `validator::Validate` for the message in the proto codegen, or implement `ValidateProto` by forwarding to its validator"
+)]
+pub trait ValidateProto {
+    /// Returns the first rule the message breaks.
+    fn validate_proto(&self) -> Result<(), ValidationError>;
+}
+
+/// A validation rule the proto message breaks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Path of the offending proto field, e.g. `address.postal_code`
+    pub field: String,
+    /// What the field's value violates
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl<T: validator::Validate> ValidateProto for T {
+    fn validate_proto(&self) -> Result<(), ValidationError> {
+        self.validate()
+            .map_err(|errors| first_violation(&errors, String::new()))
+    }
+}
+
+/// The violation of the field first in name order, reached through nested messages and lists so
+/// its path names the offending value, e.g. `addresses[1].postal_code`.
+fn first_violation(errors: &validator::ValidationErrors, path: String) -> ValidationError {
+    let join = |field: &str| {
+        if path.is_empty() {
+            field.to_string()
+        } else {
+            format!("{path}.{field}")
+        }
+    };
+
+    let Some((field, kind)) = errors.errors().iter().min_by(|(a, _), (b, _)| a.cmp(b)) else {
+        return ValidationError::new(path, "validation failed");
+    };
+    match kind {
+        validator::ValidationErrorsKind::Field(violations) => {
+            let message = violations.first().map_or_else(
+                || "validation failed".to_string(),
+                |violation| {
+                    violation
+                        .message
+                        .as_deref()
+                        .unwrap_or(&violation.code)
+                        .to_string()
+                },
+            );
+            ValidationError::new(join(field), message)
+        }
+        validator::ValidationErrorsKind::Struct(nested) => first_violation(nested, join(field)),
+        validator::ValidationErrorsKind::List(elements) => match elements.iter().next() {
+            Some((index, nested)) => first_violation(nested, format!("{}[{index}]", join(field))),
+            None => ValidationError::new(join(field), "validation failed"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_error_names_the_field() {
+        let err = ValidationError::new("address.postal_code", "value length must be 5");
+        assert_eq!(
+            err.to_string(),
+            "address.postal_code: value length must be 5"
+        );
+    }
+
+    struct Signup {
+        email: String,
+        seats: u32,
+    }
+
+    impl validator::Validate for Signup {
+        fn validate(&self) -> Result<(), validator::ValidationErrors> {
+            let mut errors = validator::ValidationErrors::new();
+            if !self.email.contains('@') {
+                errors.add(
+                    "email",
+                    validator::ValidationError::new("email")
+                        .with_message("value must be a valid email address".into()),
+                );
+            }
+            if self.seats == 0 {
+                errors.add("seats", validator::ValidationError::new("range"));
+            }
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                Err(errors)
+            }
+        }
+    }
+
+    #[test]
+    fn test_validator_messages_validate_through_the_blanket_impl() {
+        let valid = Signup {
+            email: "ada@example.com".to_string(),
+            seats: 1,
+        };
+        assert_eq!(valid.validate_proto(), Ok(()));
+
+        let invalid = Signup {
+            email: "ada".to_string(),
+            seats: 0,
+        };
+        assert_eq!(
+            invalid.validate_proto(),
+            Err(ValidationError::new(
+                "email",
+                "value must be a valid email address"
+            ))
+        );
+
+        let no_seats = Signup { seats: 0, ..valid };
+        assert_eq!(
+            no_seats.validate_proto(),
+            Err(ValidationError::new("seats", "range"))
+        );
+    }
+
+    #[test]
+    fn test_nested_violations_name_their_path() {
+        let mut address = validator::ValidationErrors::new();
+        address.add("postal_code", validator::ValidationError::new("length"));
+        let mut errors = validator::ValidationErrors::new();
+        errors.0.insert(
+            "addresses".into(),
+            validator::ValidationErrorsKind::List([(1, Box::new(address))].into()),
+        );
+
+        assert_eq!(
+            first_violation(&errors, String::new()),
+            ValidationError::new("addresses[1].postal_code", "length")
+        );
+    }
+}
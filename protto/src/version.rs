@@ -0,0 +1,121 @@
+//! Traits naming the proto messages a derived type converts to and from, so code bridging
+//! several versions of a proto schema can be generic over the message it targets.
+//!
+//! A struct marked `#[protto(module = "proto_v1", also_proto = "proto_v2::User")]` converts to
+//! and from both `proto_v1::User` and `proto_v2::User`; both traits are implemented for every
+//! message the struct has a conversion for.
+//!
+//! ```rust,ignore
+//! fn publish<P: prost::Message>(user: User, sink: &mut impl Sink<P>)
+//! where
+//!     User: protto::version::ProtoConvertTo<P>,
+//! {
+//!     sink.send(user.convert_to_proto());
+//! }
+//! ```
+
+/// Converts `Self` into the proto message `P`. Implemented for every type with an `Into<P>`,
+/// which the derive generates for each proto message unless the rust -> proto conversion is
+/// fallible (`none_as = "error"`).
+pub trait ProtoConvertTo<P> {
+    /// Converts into the proto message.
+    fn convert_to_proto(self) -> P;
+}
+
+impl<T, P> ProtoConvertTo<P> for T
+where
+    T: Into<P>,
+{
+    fn convert_to_proto(self) -> P {
+        self.into()
+    }
+}
+
+/// Converts the proto message `P` into `Self`. Implemented for every type with a `TryFrom<P>`,
+/// so both the derive's `From` and `TryFrom` conversions qualify; a `From` conversion has an
+/// uninhabited error.
+pub trait ProtoConvertFrom<P>: Sized {
+    /// The error the conversion fails with
+    type Error;
+
+    /// Converts from the proto message.
+    fn convert_from_proto(proto: P) -> Result<Self, Self::Error>;
+}
+
+impl<T, P> ProtoConvertFrom<P> for T
+where
+    T: TryFrom<P>,
+{
+    type Error = T::Error;
+
+    fn convert_from_proto(proto: P) -> Result<Self, Self::Error> {
+        T::try_from(proto)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Celsius(i32);
+
+    mod v1 {
+        pub struct Reading {
+            pub degrees: i32,
+        }
+    }
+
+    mod v2 {
+        pub struct Reading {
+            pub millidegrees: i64,
+        }
+    }
+
+    impl From<Celsius> for v1::Reading {
+        fn from(celsius: Celsius) -> Self {
+            Self { degrees: celsius.0 }
+        }
+    }
+
+    impl From<Celsius> for v2::Reading {
+        fn from(celsius: Celsius) -> Self {
+            Self {
+                millidegrees: i64::from(celsius.0) * 1000,
+            }
+        }
+    }
+
+    impl From<v1::Reading> for Celsius {
+        fn from(reading: v1::Reading) -> Self {
+            Self(reading.degrees)
+        }
+    }
+
+    impl TryFrom<v2::Reading> for Celsius {
+        type Error = std::num::TryFromIntError;
+
+        fn try_from(reading: v2::Reading) -> Result<Self, Self::Error> {
+            i32::try_from(reading.millidegrees / 1000).map(Self)
+        }
+    }
+
+    fn round_trip<P>(celsius: Celsius) -> Result<i32, <Celsius as ProtoConvertFrom<P>>::Error>
+    where
+        Celsius: ProtoConvertTo<P> + ProtoConvertFrom<P>,
+    {
+        let proto: P = celsius.convert_to_proto();
+        Celsius::convert_from_proto(proto).map(|celsius| celsius.0)
+    }
+
+    #[test]
+    fn test_generic_over_the_proto_version() {
+        assert_eq!(round_trip::<v1::Reading>(Celsius(21)), Ok(21));
+        assert_eq!(round_trip::<v2::Reading>(Celsius(-4)), Ok(-4));
+        assert!(
+            Celsius::convert_from_proto(v2::Reading {
+                millidegrees: i64::MAX
+            })
+            .is_err()
+        );
+    }
+}
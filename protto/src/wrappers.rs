@@ -0,0 +1,63 @@
+//! Runtime support for `google.protobuf` wrapper messages (`UInt64Value`, `StringValue`, ...)
+//! carrying optional scalars.
+//!
+//! prost maps the wrappers to plain `Option<T>` fields by default, but builds that compile the
+//! well-known types, or vendor their own copies, generate `Option<UInt64Value>` instead. With the
+//! `wrapper_types` feature an `Option<u64>` rust field converts through such a field,
+//! `Some(5)` to `Some(UInt64Value { value: 5 })`, once the wrapper implements [`WrapperValue`],
+//! usually through [`impl_wrapper_types!`](crate::impl_wrapper_types).
+
+/// A proto message wrapping a single `value`.
+pub trait WrapperValue {
+    type Value;
+
+    fn into_value(self) -> Self::Value;
+    fn from_value(value: Self::Value) -> Self;
+}
+
+/// Implements [`WrapperValue`] for prost wrapper messages with a single `value` field, given as
+/// `Message => ValueType` pairs.
+///
+/// ```rust,ignore
+/// protto::impl_wrapper_types!(
+///     google::protobuf::UInt64Value => u64,
+///     google::protobuf::StringValue => String,
+/// );
+/// ```
+#[macro_export]
+macro_rules! impl_wrapper_types {
+    ($($message:ty => $value:ty),+ $(,)?) => {
+        $(
+            impl $crate::wrappers::WrapperValue for $message {
+                type Value = $value;
+
+                fn into_value(self) -> $value {
+                    self.value
+                }
+
+                fn from_value(value: $value) -> Self {
+                    Self { value }
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct UInt64Value {
+        value: u64,
+    }
+
+    crate::impl_wrapper_types!(UInt64Value => u64);
+
+    #[test]
+    fn test_wrapper_round_trip() {
+        let wrapper = UInt64Value::from_value(5);
+        assert_eq!(wrapper, UInt64Value { value: 5 });
+        assert_eq!(wrapper.into_value(), 5);
+    }
+}
@@ -1,6 +0,0 @@
-pub mod attribute_parser;
-pub mod error_analysis;
-pub mod expect_analysis;
-pub mod macro_input;
-pub mod optionality;
-pub mod type_analysis;
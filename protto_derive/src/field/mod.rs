@@ -1,10 +0,0 @@
-mod context;
-mod conversion_codegen;
-mod conversion_strategy;
-mod custom_conversion;
-mod error_mode;
-mod generator;
-mod info;
-
-pub use context::FieldProcessingContext;
-pub use generator::generate_bidirectional_field_conversion;
@@ -0,0 +1,3475 @@
+use crate::analysis::error_analysis;
+use crate::analysis::optionality::{FieldOptionality, NonePolicy};
+use crate::analysis::type_analysis::{
+    BytesAs, CapacityOverflow, DecimalAs, DurationUnit, EmptyRepeated, PathEncoding, TypeKind,
+    UnknownI32Policy, UnspecifiedPolicy,
+};
+use crate::constants;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{Attribute, Expr, Field, Lit, Meta};
+
+#[derive(Debug, Default, Clone)]
+pub struct ProtoFieldMeta {
+    pub expect: bool,
+    pub error_fn: Option<String>,
+    pub error_fn_with_value: bool,
+    pub error_type: Option<String>,
+    pub default_fn: Option<String>,
+    pub optionality: Option<FieldOptionality>,
+    pub from_proto_fn: Option<String>,
+    pub from_proto_by_ref: bool,
+    /// `from_proto_fn` was given as `try_from_proto_fn`, returning `Result<T, E>`
+    pub from_proto_fallible: bool,
+    pub to_proto_fn: Option<String>,
+    pub group: Option<Vec<(String, String)>>,
+    pub none_as: Option<NonePolicy>,
+    pub path_encoding: Option<PathEncoding>,
+    pub bytes_as: Option<BytesAs>,
+    pub decimal: Option<DecimalAs>,
+    pub duration: Option<DurationUnit>,
+    /// The proto string is the field's `Display` output, read back with `FromStr`
+    pub with_str: bool,
+    /// The proto string is the field serialized as JSON, read back with serde_json
+    pub json: bool,
+    /// The element field a map is keyed by when carried as a repeated message field
+    pub map_by: Option<String>,
+    pub overflow: Option<CapacityOverflow>,
+    pub enum_as_i32: Option<UnknownI32Policy>,
+    pub enum_values: Option<UnknownI32Policy>,
+    pub enum_keys: Option<UnknownI32Policy>,
+    /// What a proto enum value of 0 converts to when the rust enum has no variant for it
+    pub unspecified: Option<UnspecifiedPolicy>,
+    pub empty_repeated: Option<EmptyRepeated>,
+    pub manual: bool,
+    pub bool_enum: Option<BoolEnumMapping>,
+    pub result_oneof: Option<ResultOneofMapping>,
+    pub resolve_with: Option<String>,
+    pub fallible: bool,
+    pub memoize: bool,
+    pub proto_boxed: bool,
+    pub proto_field_type: Option<String>,
+    pub alias_of: Option<String>,
+    pub kind: Option<TypeKind>,
+    pub oneof: Option<String>,
+    /// Fields converted before this one and passed by reference to its `from_proto_fn`
+    pub depends_on: Vec<String>,
+    /// The struct-level `sources` entry naming the proto message this field belongs to
+    pub source: Option<String>,
+    /// The field holds the proto message it was converted from, the base the rust -> proto
+    /// conversion writes the mapped fields over
+    pub preserve_unknown: bool,
+    /// The optional proto field whose presence a `bool` field reads
+    pub presence_of: Option<String>,
+}
+
+/// Maps a rust `bool` onto two designated variants of a proto enum, set via `bool_enum(...)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BoolEnumMapping {
+    pub proto_enum: String,
+    pub true_variant: String,
+    pub false_variant: String,
+    pub unknown: UnknownVariantPolicy,
+}
+
+/// Maps a rust `Result<T, E>` onto a proto oneof with one variant for each outcome, set via
+/// `result_oneof(...)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResultOneofMapping {
+    /// The prost oneof enum, relative to the proto module; derived from the message and field
+    /// names when not given
+    pub proto_enum: Option<String>,
+    pub ok: String,
+    pub err: String,
+}
+
+/// A proto field with no rust counterpart, computed from the whole struct when converting to
+/// proto; set via struct-level `proto_only(field = "fn")`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProtoOnlyField {
+    pub proto_field: String,
+    /// Path of a `fn(&Struct) -> ProtoFieldType`
+    pub to_proto_fn: String,
+}
+
+/// What a proto enum value matching neither designated variant converts to
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UnknownVariantPolicy {
+    /// Fail the proto -> rust conversion, which becomes `TryFrom`
+    Error,
+
+    /// Panic with a descriptive message
+    Panic,
+
+    /// Convert to the given bool
+    Value(bool),
+}
+
+const FROM_PROTO_FN_CONFLICT: &str =
+    "Only one of 'from_proto_fn', 'try_from_proto_fn' and 'from_proto_fn_ref' may be specified";
+
+impl ProtoFieldMeta {
+    pub fn from_field(field: &syn::Field) -> Result<Self, String> {
+        let mut meta = ProtoFieldMeta::default();
+        let field_name = field
+            .ident
+            .as_ref()
+            .map(|i| i.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let metas = protto_metas(&field.attrs).map_err(|e| {
+            format!(
+                "Failed to parse {} attribute: {e}",
+                constants::PROTTO_ATTRIBUTE
+            )
+        })?;
+        // `expect` and `default` both state what a missing proto value becomes, so they are
+        // checked against each other once every key is read
+        let mut expect_form: Option<String> = None;
+        let mut expect_default: Option<String> = None;
+        let mut default_key: Option<&str> = None;
+        let key_count = metas.len();
+        for nested_meta in metas {
+            match nested_meta {
+                Meta::Path(path) if path.is_ident("expect") => {
+                    meta.expect = true;
+                    expect_form = Some("expect".to_string());
+                }
+                Meta::List(list) if list.path.is_ident("expect") => {
+                    // `expect(error)`, `expect(panic)`, `expect(default)` or
+                    // `expect(default = "fn")`, also written `expect = "..."`
+                    match parse_expect_policy(&list, &field_name)? {
+                        Some(default_fn) => expect_default = Some(default_fn),
+                        None => meta.expect = true,
+                    }
+                    expect_form = Some(format!("expect({})", list.tokens));
+                }
+
+                Meta::Path(path) if path.is_ident("fallible") => {
+                    meta.fallible = true;
+                }
+
+                Meta::Path(path) if path.is_ident("json") => {
+                    meta.json = true;
+                }
+                Meta::Path(path) if path.is_ident("with_str") => {
+                    meta.with_str = true;
+                }
+
+                Meta::Path(path) if path.is_ident("memoize") => {
+                    meta.memoize = true;
+                }
+
+                Meta::Path(path) if path.is_ident("manual") => {
+                    meta.manual = true;
+                }
+
+                Meta::Path(path) if path.is_ident("preserve_unknown") => {
+                    meta.preserve_unknown = true;
+                }
+
+                Meta::Path(path) if path.is_ident("proto_boxed") => {
+                    meta.proto_boxed = true;
+                }
+
+                Meta::Path(path) if path.is_ident("proto_optional") => {
+                    if meta.optionality.is_some() {
+                        return Err(
+                            "Cannot specify both proto_optional and proto_required".to_string()
+                        );
+                    }
+                    meta.optionality = Some(FieldOptionality::Optional);
+                }
+                Meta::Path(path) if path.is_ident("proto_required") => {
+                    if meta.optionality.is_some() {
+                        return Err(
+                            "Cannot specify both proto_optional and proto_required".to_string()
+                        );
+                    }
+                    meta.optionality = Some(FieldOptionality::Required);
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("error_type") => {
+                    if let Expr::Path(expr_path) = &nv.value {
+                        meta.error_type = Some(quote!(#expr_path).to_string());
+                    }
+                }
+                Meta::NameValue(nv)
+                    if nv.path.is_ident("error_fn") || nv.path.is_ident("error_fn_with_value") =>
+                {
+                    if meta.error_fn.is_some() {
+                        return Err(format!(
+                            "Field '{field_name}': Cannot specify both 'error_fn' and \
+                                'error_fn_with_value'"
+                        ));
+                    }
+                    meta.error_fn_with_value = nv.path.is_ident("error_fn_with_value");
+                    let key = if meta.error_fn_with_value {
+                        "error_fn_with_value"
+                    } else {
+                        "error_fn"
+                    };
+                    match parse_function_value(&nv.value, key, &field_name) {
+                        Ok(fn_name) => meta.error_fn = Some(fn_name),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("default") => {
+                    default_key = Some("default");
+                    if meta.default_fn.is_some() {
+                        return Err(format!(
+                            "Field '{}': Cannot specify both 'default' and 'default_fn'. \
+                                Use 'default = \"function_name\"' for custom default functions.",
+                            field_name
+                        ));
+                    }
+                    match &nv.value {
+                        Expr::Lit(expr_lit) => {
+                            if let Lit::Str(lit_str) = &expr_lit.lit {
+                                let fn_name = lit_str.value();
+                                meta.default_fn = Some(fn_name);
+                            }
+                        }
+                        Expr::Path(expr_path) => {
+                            let fn_name = quote!(#expr_path).to_string();
+                            meta.default_fn = Some(fn_name);
+                        }
+                        _ => {
+                            return Err(format!(
+                                "Field '{}': default value must be a string literal or path. \
+                                    Examples: default = \"my_function\" or default = my_function",
+                                field_name
+                            ));
+                        }
+                    }
+                }
+                Meta::NameValue(nv) if nv.path.is_ident("default_fn") => {
+                    default_key = Some("default_fn");
+                    if meta.default_fn.is_some() {
+                        return Err(format!(
+                            "Field '{}': Cannot specify both 'default' and 'default_fn'. \
+                                Use 'default = \"function_name\"' instead.",
+                            field_name
+                        ));
+                    }
+                    match &nv.value {
+                        Expr::Lit(expr_lit) => {
+                            if let Lit::Str(lit_str) = &expr_lit.lit {
+                                let fn_name = lit_str.value();
+                                meta.default_fn = Some(fn_name);
+                            }
+                        }
+                        Expr::Path(expr_path) => {
+                            let fn_name = quote!(#expr_path).to_string();
+                            meta.default_fn = Some(fn_name);
+                        }
+                        _ => {
+                            return Err(format!(
+                                "Field '{}': default_fn value must be a string literal or path. \
+                                    Examples: default_fn = \"my_function\" or default_fn = my_function",
+                                field_name
+                            ));
+                        }
+                    }
+                }
+                // Handle bare 'default' to use Default::default - add to separate field
+                Meta::Path(path) if path.is_ident("default") => {
+                    default_key = Some("default");
+                    if meta.default_fn.is_some() {
+                        return Err(format!(
+                            "Field '{}': Cannot specify both 'default' and 'default_fn'. \
+                                Use 'default' for Default::default() or 'default_fn = \"function\"' for custom functions.",
+                            field_name
+                        ));
+                    }
+                    // Use a special marker to distinguish from custom default_fn
+                    meta.default_fn = Some(constants::USE_DEFAULT_IMPL.to_string());
+                }
+                Meta::Path(path) if path.is_ident("default_fn") => {
+                    return Err(format!(
+                        "Field '{}': 'default_fn' requires a value. \
+                            Use 'default_fn = \"function_name\"' or 'default = \"function_name\"'.",
+                        field_name
+                    ));
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("from_proto_fn") => {
+                    if meta.from_proto_fn.is_some() {
+                        return Err(format!("Field '{field_name}': {FROM_PROTO_FN_CONFLICT}"));
+                    }
+                    match parse_function_value(&nv.value, "from_proto_fn", &field_name) {
+                        Ok(fn_name) => meta.from_proto_fn = Some(fn_name),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+                Meta::NameValue(nv) if nv.path.is_ident("try_from_proto_fn") => {
+                    if meta.from_proto_fn.is_some() {
+                        return Err(format!("Field '{field_name}': {FROM_PROTO_FN_CONFLICT}"));
+                    }
+                    match parse_function_value(&nv.value, "try_from_proto_fn", &field_name) {
+                        Ok(fn_name) => {
+                            meta.from_proto_fn = Some(fn_name);
+                            meta.from_proto_fallible = true;
+                        }
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+                Meta::NameValue(nv) if nv.path.is_ident("from_proto_fn_ref") => {
+                    if meta.from_proto_fn.is_some() {
+                        return Err(format!("Field '{field_name}': {FROM_PROTO_FN_CONFLICT}"));
+                    }
+                    match parse_function_value(&nv.value, "from_proto_fn_ref", &field_name) {
+                        Ok(fn_name) => {
+                            meta.from_proto_fn = Some(fn_name);
+                            meta.from_proto_by_ref = true;
+                        }
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("to_proto_fn") => {
+                    match parse_function_value(&nv.value, "to_proto_fn", &field_name) {
+                        Ok(fn_name) => meta.to_proto_fn = Some(fn_name),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("depends_on") => {
+                    match parse_depends_on(&nv.value, &field_name) {
+                        Ok(fields) => meta.depends_on = fields,
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("none_as") => {
+                    check_single_none_policy(&meta, &field_name)?;
+                    match parse_none_policy(&nv.value, &field_name) {
+                        Ok(policy) => meta.none_as = Some(policy),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+                Meta::Path(path) if path.is_ident("none_as_empty") => {
+                    check_single_none_policy(&meta, &field_name)?;
+                    meta.none_as = Some(NonePolicy::Empty);
+                }
+                Meta::Path(path) if path.is_ident("none_as_unset") => {
+                    check_single_none_policy(&meta, &field_name)?;
+                    meta.none_as = Some(NonePolicy::Unset);
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("path_encoding") => {
+                    match parse_path_encoding(&nv.value, &field_name) {
+                        Ok(encoding) => meta.path_encoding = Some(encoding),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("bytes_as") => {
+                    match parse_bytes_as(&nv.value, &field_name) {
+                        Ok(bytes_as) => meta.bytes_as = Some(bytes_as),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("decimal") => {
+                    match parse_decimal(&nv.value, &field_name) {
+                        Ok(decimal) => meta.decimal = Some(decimal),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("duration") => {
+                    match parse_duration(&nv.value, &field_name) {
+                        Ok(duration) => meta.duration = Some(duration),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("map_by") => {
+                    match parse_map_by(&nv.value, &field_name) {
+                        Ok(key) => meta.map_by = Some(key),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("presence_of") => {
+                    match parse_presence_of(&nv.value, &field_name) {
+                        Ok(proto_field) => meta.presence_of = Some(proto_field),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("source") => {
+                    match parse_source(&nv.value, &field_name) {
+                        Ok(source) => meta.source = Some(source),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("unspecified") => {
+                    match parse_unspecified(&nv.value, &field_name) {
+                        Ok(policy) => meta.unspecified = Some(policy),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::Path(path) if path.is_ident("enum_as_i32") => {
+                    meta.enum_as_i32 = Some(UnknownI32Policy::Panic);
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("enum_as_i32") => {
+                    match parse_unknown_i32_policy("enum_as_i32", &nv.value, &field_name) {
+                        Ok(policy) => meta.enum_as_i32 = Some(policy),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::Path(path) if path.is_ident("enum_values") => {
+                    meta.enum_values = Some(UnknownI32Policy::Panic);
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("enum_values") => {
+                    match parse_unknown_i32_policy("enum_values", &nv.value, &field_name) {
+                        Ok(policy) => meta.enum_values = Some(policy),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::Path(path) if path.is_ident("enum_keys") => {
+                    meta.enum_keys = Some(UnknownI32Policy::Panic);
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("enum_keys") => {
+                    match parse_unknown_i32_policy("enum_keys", &nv.value, &field_name) {
+                        Ok(policy) => meta.enum_keys = Some(policy),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("empty_repeated") => {
+                    match parse_empty_repeated(&nv.value, &field_name) {
+                        Ok(empty_repeated) => meta.empty_repeated = Some(empty_repeated),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("overflow") => {
+                    match parse_overflow(&nv.value, &field_name) {
+                        Ok(overflow) => meta.overflow = Some(overflow),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("resolve_with") => {
+                    match parse_function_value(&nv.value, "resolve_with", &field_name) {
+                        Ok(fn_name) => meta.resolve_with = Some(fn_name),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("proto_field_type") => {
+                    match parse_proto_field_type(&nv.value, &field_name) {
+                        Ok(proto_type) => meta.proto_field_type = Some(proto_type),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("alias_of") => {
+                    match parse_alias_of(&nv.value, &field_name) {
+                        Ok(target) => meta.alias_of = Some(target),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("kind") => {
+                    match parse_kind(&nv.value, &field_name) {
+                        Ok(kind) => meta.kind = Some(kind),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::List(list) if list.path.is_ident("bool_enum") => {
+                    match parse_bool_enum_mapping(&list, &field_name) {
+                        Ok(mapping) => meta.bool_enum = Some(mapping),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::List(list) if list.path.is_ident("result_oneof") => {
+                    match parse_result_oneof_mapping(&list, &field_name) {
+                        Ok(mapping) => meta.result_oneof = Some(mapping),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("oneof") => {
+                    if get_proto_field_name(field).is_some() {
+                        return Err(format!(
+                            "Field '{field_name}': Cannot specify both 'oneof' and 'proto_name'; \
+                                'oneof' already names the proto field"
+                        ));
+                    }
+                    let oneof = match &nv.value {
+                        Expr::Lit(syn::ExprLit {
+                            lit: Lit::Str(lit_str),
+                            ..
+                        }) => lit_str.value(),
+                        Expr::Path(expr_path) => quote!(#expr_path).to_string(),
+                        _ => {
+                            return Err(format!(
+                                "Field '{field_name}': oneof value must name the proto oneof field, \
+                                    e.g. oneof = \"payload\""
+                            ));
+                        }
+                    };
+                    meta.oneof = Some(oneof);
+                }
+
+                Meta::List(list) if list.path.is_ident("group") => {
+                    match parse_group_mappings(&list, &field_name) {
+                        Ok(mappings) => meta.group = Some(mappings),
+                        Err(err_msg) => return Err(err_msg),
+                    }
+                }
+
+                _ => {
+                    // unknown keys are rejected up front by validate_attributes
+                }
+            }
+        }
+
+        if let (Some(expect_form), Some(default_key)) = (&expect_form, default_key) {
+            return Err(format!(
+                "Field '{field_name}': `{expect_form}` and `{default_key}` both set what a missing \
+                proto value becomes; state it once, with `expect = \"default\"` or \
+                `expect(default = \"function_name\")` to fall back to a default"
+            ));
+        }
+        if expect_default.is_some() {
+            meta.default_fn = expect_default;
+        }
+        if meta.preserve_unknown && key_count > 1 {
+            return Err(format!(
+                "Field '{field_name}': preserve_unknown holds the whole proto message and maps no \
+                proto field of its own, so it takes no other keys"
+            ));
+        }
+        if meta.preserve_unknown && !crate::analysis::type_analysis::is_option_type(&field.ty) {
+            return Err(format!(
+                "Field '{field_name}': preserve_unknown holds the proto message the struct was \
+                converted from, so its type must be an Option of the message, e.g. \
+                Option<proto::User>"
+            ));
+        }
+        if meta.presence_of.is_some() && key_count > 1 {
+            return Err(format!(
+                "Field '{field_name}': presence_of reads whether the proto field is set and \
+                cannot set it back from a bool, so it takes no other keys"
+            ));
+        }
+        if meta.presence_of.is_some()
+            && !matches!(&field.ty, syn::Type::Path(p) if p.path.is_ident("bool"))
+        {
+            return Err(format!(
+                "Field '{field_name}': presence_of requires a bool field, true when the proto \
+                field is set"
+            ));
+        }
+        if !meta.depends_on.is_empty() && meta.from_proto_fn.is_none() {
+            return Err(format!(
+                "Field '{field_name}': depends_on passes the converted fields to the field's \
+                custom conversion, so it requires from_proto_fn, from_proto_fn_ref or \
+                try_from_proto_fn"
+            ));
+        }
+
+        Ok(meta)
+    }
+
+    /// Get the explicit proto optionality flag if present
+    #[allow(unused)]
+    pub fn get_proto_optionality(&self) -> Option<&FieldOptionality> {
+        self.optionality.as_ref()
+    }
+
+    /// Check if this field is explicitly marked as proto optional
+    #[allow(unused)]
+    pub fn is_proto_optional(&self) -> bool {
+        self.optionality.map(|o| o.is_optional()).unwrap_or(false)
+    }
+
+    /// Check if this field is explicitly marked as proto required
+    #[allow(unused)]
+    pub fn is_proto_required(&self) -> bool {
+        self.optionality.map(|o| o.is_required()).unwrap_or(false)
+    }
+
+    /// Check if any explicit optionality annotation is present
+    pub fn has_explicit_optionality(&self) -> bool {
+        self.optionality.is_some()
+    }
+
+    /// Get the proto-to-rust conversion function if specified
+    pub fn get_proto_to_rust_fn(&self) -> Option<&str> {
+        self.from_proto_fn.as_deref()
+    }
+
+    /// Get the rust-to-proto conversion function if specified
+    pub fn get_rust_to_proto_fn(&self) -> Option<&str> {
+        self.to_proto_fn.as_deref()
+    }
+
+    /// Check if bidirectional custom conversion is specified
+    #[allow(unused)]
+    pub fn has_bidirectional_conversion(&self) -> bool {
+        self.from_proto_fn.is_some() && self.to_proto_fn.is_some()
+    }
+
+    /// Check if any custom conversion function is specified
+    #[allow(unused)]
+    pub fn has_custom_conversion(&self) -> bool {
+        self.from_proto_fn.is_some() || self.to_proto_fn.is_some()
+    }
+}
+
+/// Where a `#[protto(...)]` attribute is attached
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AttributeLevel {
+    Struct,
+    Field,
+    Enum,
+    Variant,
+}
+
+impl std::fmt::Display for AttributeLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Self::Struct => "struct",
+            Self::Field => "field",
+            Self::Enum => "enum",
+            Self::Variant => "variant",
+        };
+        write!(f, "{label}")
+    }
+}
+
+/// The syntax a key accepts; `key(value)` and `key = value` are interchangeable for value keys,
+/// and `key = "nested, entries"` is interchangeable with `key(nested, entries)` for nested keys.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum KeyShape {
+    /// `key`
+    Flag,
+    /// `key = value`
+    Value,
+    /// `key` or `key = value`
+    FlagOrValue,
+    /// `key(entries)`
+    Nested,
+    /// `key` or `key(entries)`
+    FlagOrNested,
+}
+
+/// Every key recognized inside `#[protto(...)]`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ProttoKey {
+    Module,
+    ProtoName,
+    ErrorType,
+    ErrorFn,
+    ErrorFnWithValue,
+    ErrorMod,
+    ErrorVis,
+    StaticErrors,
+    Presence,
+    CustomFnsAre,
+    Conversions,
+    Ignore,
+    Resolver,
+    Transparent,
+    Expect,
+    ProtoOptional,
+    ProtoRequired,
+    Default,
+    DefaultFn,
+    FromProtoFn,
+    FromProtoFnRef,
+    TryFromProtoFn,
+    ToProtoFn,
+    NoneAs,
+    PathEncoding,
+    BytesAs,
+    Decimal,
+    Duration,
+    Overflow,
+    ResolveWith,
+    Fallible,
+    Memoize,
+    ProtoBoxed,
+    ProtoFieldType,
+    AliasOf,
+    Kind,
+    BoolEnum,
+    ResultOneof,
+    Group,
+    Oneof,
+    NonExhaustiveOneof,
+    EstimatedSize,
+    FieldPaths,
+    EnumAsI32,
+    EnumValues,
+    EnumKeys,
+    EmptyRepeated,
+    Manual,
+    ExtraFields,
+    Partial,
+    ProtoParts,
+    AnyTypeUrl,
+    AlsoProto,
+    Validate,
+    Passthrough,
+    Cfg,
+    ProtoOnly,
+    Arena,
+    DebugRoundtrip,
+    NoneAsEmpty,
+    NoneAsUnset,
+    UseRules,
+    DependsOn,
+    WithStr,
+    MapBy,
+    Unspecified,
+    Json,
+    Sources,
+    Source,
+    PreserveUnknown,
+    View,
+    PresenceOf,
+}
+
+impl ProttoKey {
+    pub const ALL: &'static [ProttoKey] = &[
+        Self::Module,
+        Self::ProtoName,
+        Self::ErrorType,
+        Self::ErrorFn,
+        Self::ErrorFnWithValue,
+        Self::ErrorMod,
+        Self::ErrorVis,
+        Self::StaticErrors,
+        Self::Presence,
+        Self::CustomFnsAre,
+        Self::Conversions,
+        Self::Ignore,
+        Self::Resolver,
+        Self::Transparent,
+        Self::Expect,
+        Self::ProtoOptional,
+        Self::ProtoRequired,
+        Self::Default,
+        Self::DefaultFn,
+        Self::FromProtoFn,
+        Self::FromProtoFnRef,
+        Self::TryFromProtoFn,
+        Self::ToProtoFn,
+        Self::NoneAs,
+        Self::PathEncoding,
+        Self::BytesAs,
+        Self::Decimal,
+        Self::Duration,
+        Self::Overflow,
+        Self::ResolveWith,
+        Self::Fallible,
+        Self::Memoize,
+        Self::ProtoBoxed,
+        Self::ProtoFieldType,
+        Self::AliasOf,
+        Self::Kind,
+        Self::BoolEnum,
+        Self::ResultOneof,
+        Self::Group,
+        Self::Oneof,
+        Self::NonExhaustiveOneof,
+        Self::EstimatedSize,
+        Self::FieldPaths,
+        Self::EnumAsI32,
+        Self::EnumValues,
+        Self::EnumKeys,
+        Self::EmptyRepeated,
+        Self::Manual,
+        Self::ExtraFields,
+        Self::Partial,
+        Self::ProtoParts,
+        Self::AnyTypeUrl,
+        Self::AlsoProto,
+        Self::Validate,
+        Self::Passthrough,
+        Self::Cfg,
+        Self::ProtoOnly,
+        Self::Arena,
+        Self::DebugRoundtrip,
+        Self::NoneAsEmpty,
+        Self::NoneAsUnset,
+        Self::UseRules,
+        Self::DependsOn,
+        Self::WithStr,
+        Self::MapBy,
+        Self::Unspecified,
+        Self::Json,
+        Self::Sources,
+        Self::Source,
+        Self::PreserveUnknown,
+        Self::View,
+        Self::PresenceOf,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Module => "module",
+            Self::ProtoName => "proto_name",
+            Self::ErrorType => "error_type",
+            Self::ErrorFn => "error_fn",
+            Self::ErrorFnWithValue => "error_fn_with_value",
+            Self::ErrorMod => "error_mod",
+            Self::ErrorVis => "error_vis",
+            Self::StaticErrors => "static_errors",
+            Self::Presence => "presence",
+            Self::CustomFnsAre => "custom_fns_are",
+            Self::Conversions => "conversions",
+            Self::Ignore => "ignore",
+            Self::Resolver => "resolver",
+            Self::Transparent => "transparent",
+            Self::Expect => "expect",
+            Self::ProtoOptional => "proto_optional",
+            Self::ProtoRequired => "proto_required",
+            Self::Default => "default",
+            Self::DefaultFn => "default_fn",
+            Self::FromProtoFn => "from_proto_fn",
+            Self::FromProtoFnRef => "from_proto_fn_ref",
+            Self::TryFromProtoFn => "try_from_proto_fn",
+            Self::ToProtoFn => "to_proto_fn",
+            Self::NoneAs => "none_as",
+            Self::PathEncoding => "path_encoding",
+            Self::BytesAs => "bytes_as",
+            Self::Decimal => "decimal",
+            Self::Duration => "duration",
+            Self::Overflow => "overflow",
+            Self::ResolveWith => "resolve_with",
+            Self::Fallible => "fallible",
+            Self::Memoize => "memoize",
+            Self::ProtoBoxed => "proto_boxed",
+            Self::ProtoFieldType => "proto_field_type",
+            Self::AliasOf => "alias_of",
+            Self::Kind => "kind",
+            Self::BoolEnum => "bool_enum",
+            Self::ResultOneof => "result_oneof",
+            Self::Group => "group",
+            Self::Oneof => "oneof",
+            Self::NonExhaustiveOneof => "non_exhaustive_oneof",
+            Self::EstimatedSize => "estimated_size",
+            Self::FieldPaths => "field_paths",
+            Self::EnumAsI32 => "enum_as_i32",
+            Self::EnumValues => "enum_values",
+            Self::EnumKeys => "enum_keys",
+            Self::EmptyRepeated => "empty_repeated",
+            Self::Manual => "manual",
+            Self::ExtraFields => "extra_fields",
+            Self::Partial => "partial",
+            Self::ProtoParts => "proto_parts",
+            Self::AnyTypeUrl => "any_type_url",
+            Self::AlsoProto => "also_proto",
+            Self::Validate => "validate",
+            Self::Passthrough => "passthrough",
+            Self::Cfg => "cfg",
+            Self::ProtoOnly => "proto_only",
+            Self::Arena => "arena",
+            Self::DebugRoundtrip => "debug_roundtrip",
+            Self::NoneAsEmpty => "none_as_empty",
+            Self::NoneAsUnset => "none_as_unset",
+            Self::UseRules => "use_rules",
+            Self::DependsOn => "depends_on",
+            Self::WithStr => "with_str",
+            Self::MapBy => "map_by",
+            Self::Unspecified => "unspecified",
+            Self::Json => "json",
+            Self::Sources => "sources",
+            Self::Source => "source",
+            Self::PreserveUnknown => "preserve_unknown",
+            Self::View => "view",
+            Self::PresenceOf => "presence_of",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|key| key.name() == name)
+    }
+
+    /// The levels a key may be attached at
+    pub fn levels(self) -> &'static [AttributeLevel] {
+        use AttributeLevel::*;
+        match self {
+            Self::Module | Self::EstimatedSize | Self::Cfg => &[Struct, Enum],
+            Self::Oneof | Self::EnumAsI32 => &[Enum, Field],
+            Self::NonExhaustiveOneof => &[Enum],
+            Self::ProtoName
+            | Self::ErrorType
+            | Self::ErrorFn
+            | Self::ErrorFnWithValue
+            | Self::Ignore
+            | Self::Transparent => &[Struct, Field],
+            Self::Resolver
+            | Self::ErrorMod
+            | Self::ErrorVis
+            | Self::StaticErrors
+            | Self::Presence
+            | Self::FieldPaths
+            | Self::CustomFnsAre
+            | Self::Conversions
+            | Self::ExtraFields
+            | Self::Partial
+            | Self::ProtoParts
+            | Self::AnyTypeUrl
+            | Self::AlsoProto
+            | Self::Validate
+            | Self::Passthrough
+            | Self::ProtoOnly
+            | Self::Arena
+            | Self::DebugRoundtrip
+            | Self::UseRules
+            | Self::Sources
+            | Self::View => &[Struct],
+            _ => &[Field],
+        }
+    }
+
+    fn shape(self) -> KeyShape {
+        match self {
+            Self::ProtoOptional
+            | Self::ProtoRequired
+            | Self::Fallible
+            | Self::Memoize
+            | Self::Manual
+            | Self::ProtoBoxed
+            | Self::StaticErrors
+            | Self::Presence
+            | Self::FieldPaths
+            | Self::Partial
+            | Self::ProtoParts
+            | Self::Validate
+            | Self::Passthrough
+            | Self::Arena
+            | Self::DebugRoundtrip
+            | Self::NoneAsEmpty
+            | Self::NoneAsUnset
+            | Self::WithStr
+            | Self::Json
+            | Self::PreserveUnknown
+            | Self::View
+            | Self::EstimatedSize => KeyShape::Flag,
+            Self::Transparent
+            | Self::Ignore
+            | Self::Default
+            | Self::EnumAsI32
+            | Self::EnumValues
+            | Self::EnumKeys => KeyShape::FlagOrValue,
+            Self::Expect => KeyShape::FlagOrNested,
+            Self::BoolEnum
+            | Self::ResultOneof
+            | Self::Group
+            | Self::Cfg
+            | Self::ProtoOnly
+            | Self::Sources => KeyShape::Nested,
+            _ => KeyShape::Value,
+        }
+    }
+
+    fn keys_at(level: AttributeLevel) -> Vec<&'static str> {
+        let aliases = ProttoAlias::ALL
+            .iter()
+            .filter(move |_| level == AttributeLevel::Field)
+            .map(|alias| alias.name());
+        Self::ALL
+            .iter()
+            .filter(|key| key.levels().contains(&level))
+            .map(|key| key.name())
+            .chain(aliases)
+            .collect()
+    }
+}
+
+/// Field flags standing for a common combination of keys. `protto_metas` expands them before any
+/// key is read, so everything downstream only sees the keys they stand for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ProttoAlias {
+    /// A proto `optional` field that must be set: missing values are a conversion error
+    Required,
+    /// A proto `optional` field passed through to an `Option<T>`, `None` to `None`
+    Optional,
+}
+
+impl ProttoAlias {
+    pub const ALL: &'static [ProttoAlias] = &[Self::Required, Self::Optional];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Required => "required",
+            Self::Optional => "optional",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|alias| alias.name() == name)
+    }
+
+    /// The keys the alias stands for, as written inside `#[protto(...)]`
+    pub fn expansion(self) -> &'static str {
+        match self {
+            Self::Required => "proto_optional, expect(error)",
+            Self::Optional => "proto_optional",
+        }
+    }
+
+    /// The alias's keys, spanned at the alias so errors about them point at what was written
+    fn expand(self, span: proc_macro2::Span) -> Vec<Meta> {
+        let tokens: proc_macro2::TokenStream = self
+            .expansion()
+            .parse()
+            .expect("alias expansions are valid tokens");
+        Punctuated::<Meta, Comma>::parse_terminated
+            .parse2(respan(tokens, span))
+            .expect("alias expansions are valid metas")
+            .into_iter()
+            .map(normalize_meta)
+            .collect()
+    }
+}
+
+fn respan(tokens: proc_macro2::TokenStream, span: proc_macro2::Span) -> proc_macro2::TokenStream {
+    use proc_macro2::{Group, TokenTree};
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                token = TokenTree::Group(respanned);
+            } else {
+                token.set_span(span);
+            }
+            token
+        })
+        .collect()
+}
+
+/// Collect the nested metas of every `#[protto(...)]` attribute, normalizing the alternate
+/// `key(value)` / `key = "entries"` syntaxes so parsers only match the canonical form, and
+/// expanding aliases into the keys they stand for.
+pub fn protto_metas(attrs: &[Attribute]) -> syn::Result<Vec<Meta>> {
+    Ok(written_metas(attrs)?
+        .into_iter()
+        .flat_map(|meta| {
+            let alias = match &meta {
+                Meta::Path(path) => path.get_ident().and_then(|ident| {
+                    ProttoAlias::from_name(&ident.to_string()).map(|alias| (alias, ident.span()))
+                }),
+                _ => None,
+            };
+            match alias {
+                Some((alias, span)) => alias.expand(span),
+                None => vec![meta],
+            }
+        })
+        .collect())
+}
+
+/// The normalized metas as written, aliases unexpanded
+fn written_metas(attrs: &[Attribute]) -> syn::Result<Vec<Meta>> {
+    let mut metas = Vec::new();
+    for attr in attrs {
+        if !attr.path().is_ident(constants::PROTTO_ATTRIBUTE) {
+            continue;
+        }
+        let Meta::List(meta_list) = &attr.meta else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!("expected #[{}(...)]", constants::PROTTO_ATTRIBUTE),
+            ));
+        };
+        let nested: Punctuated<Meta, Comma> =
+            Punctuated::parse_terminated.parse2(meta_list.tokens.clone())?;
+        metas.extend(nested.into_iter().map(normalize_meta));
+    }
+    Ok(metas)
+}
+
+/// Struct-level getters run after `validate_attributes`, so a parse failure here is a bug
+fn protto_metas_or_panic(attrs: &[Attribute]) -> Vec<Meta> {
+    protto_metas(attrs).unwrap_or_else(|e| {
+        panic!(
+            "Failed to parse {} attribute: {e}",
+            constants::PROTTO_ATTRIBUTE
+        )
+    })
+}
+
+fn normalize_meta(meta: Meta) -> Meta {
+    let Some(key) = meta
+        .path()
+        .get_ident()
+        .and_then(|ident| ProttoKey::from_name(&ident.to_string()))
+    else {
+        return meta;
+    };
+
+    match (key.shape(), meta) {
+        (KeyShape::Value | KeyShape::FlagOrValue, Meta::List(list)) => {
+            match list.parse_args::<Expr>() {
+                Ok(value) => Meta::NameValue(syn::MetaNameValue {
+                    path: list.path,
+                    eq_token: Default::default(),
+                    value,
+                }),
+                Err(_) => Meta::List(list),
+            }
+        }
+        (KeyShape::Nested | KeyShape::FlagOrNested, Meta::NameValue(nv)) => {
+            let tokens = match &nv.value {
+                Expr::Lit(expr_lit) => match &expr_lit.lit {
+                    Lit::Str(lit_str) => lit_str.value().parse().ok(),
+                    _ => None,
+                },
+                Expr::Path(expr_path) => Some(quote!(#expr_path)),
+                _ => None,
+            };
+            match tokens {
+                Some(tokens) => Meta::List(syn::MetaList {
+                    path: nv.path,
+                    delimiter: syn::MacroDelimiter::Paren(Default::default()),
+                    tokens,
+                }),
+                None => Meta::NameValue(nv),
+            }
+        }
+        (_, meta) => meta,
+    }
+}
+
+/// Check every `#[protto(...)]` attribute on the input against the keys supported at its level,
+/// so typos and misplaced keys fail compilation instead of being silently ignored.
+pub fn validate_attributes(ast: &syn::DeriveInput) -> syn::Result<()> {
+    let mut errors: Option<syn::Error> = None;
+    let mut push = |error: syn::Error| match errors.as_mut() {
+        Some(errors) => errors.combine(error),
+        None => errors = Some(error),
+    };
+
+    let (container_level, members): (AttributeLevel, Vec<(AttributeLevel, &[Attribute])>) =
+        match &ast.data {
+            syn::Data::Struct(data_struct) => (
+                AttributeLevel::Struct,
+                data_struct
+                    .fields
+                    .iter()
+                    .map(|field| (AttributeLevel::Field, field.attrs.as_slice()))
+                    .collect(),
+            ),
+            syn::Data::Enum(data_enum) => (
+                AttributeLevel::Enum,
+                data_enum
+                    .variants
+                    .iter()
+                    .map(|variant| (AttributeLevel::Variant, variant.attrs.as_slice()))
+                    .collect(),
+            ),
+            syn::Data::Union(_) => (AttributeLevel::Struct, Vec::new()),
+        };
+
+    for (level, attrs) in std::iter::once((container_level, ast.attrs.as_slice())).chain(members) {
+        match written_metas(attrs) {
+            Ok(metas) => metas
+                .iter()
+                .filter_map(|meta| validate_key(meta, level).err())
+                .for_each(&mut push),
+            Err(error) => push(error),
+        }
+    }
+
+    if let syn::Data::Struct(data_struct) = &ast.data {
+        for field in &data_struct.fields {
+            if let Err(msg) = ProtoFieldMeta::from_field(field) {
+                push(syn::Error::new_spanned(field, msg));
+            }
+        }
+    }
+
+    if let syn::Data::Struct(data_struct) = &ast.data
+        && protto_metas(&ast.attrs).is_ok_and(|metas| {
+            metas
+                .iter()
+                .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("transparent")))
+        })
+        && let Err(error) = validate_transparent_struct(&ast.attrs, &data_struct.fields)
+    {
+        push(error);
+    }
+
+    if let syn::Data::Struct(data_struct) = &ast.data
+        && protto_metas(&ast.attrs).is_ok_and(|metas| {
+            metas
+                .iter()
+                .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("arena")))
+        })
+        && let Err(error) = validate_arena_struct(&ast.attrs, &data_struct.fields)
+    {
+        push(error);
+    }
+
+    if let Err(error) = get_cfg_predicates(&ast.attrs) {
+        push(error);
+    }
+    if let Err(error) = get_struct_level_proto_only(&ast.attrs) {
+        push(error);
+    }
+
+    if let syn::Data::Struct(data_struct) = &ast.data
+        && protto_metas(&ast.attrs).is_ok_and(|metas| {
+            metas
+                .iter()
+                .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("passthrough")))
+        })
+        && let Err(error) = validate_passthrough_struct(&ast.attrs, &data_struct.fields)
+    {
+        push(error);
+    }
+
+    if let syn::Data::Struct(data_struct) = &ast.data
+        && let Err(error) = validate_sources_struct(&ast.attrs, &data_struct.fields)
+    {
+        push(error);
+    }
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(()),
+    }
+}
+
+/// A struct-level `transparent` struct wraps exactly one field and converts only through it, so
+/// beyond naming the proto message (and its `Any` type URL) it takes no other attributes
+fn validate_transparent_struct(attrs: &[Attribute], fields: &syn::Fields) -> syn::Result<()> {
+    if fields.len() != 1 {
+        return Err(syn::Error::new_spanned(
+            fields,
+            format!(
+                "struct-level transparent requires exactly one field, found {}",
+                fields.len()
+            ),
+        ));
+    }
+
+    let allowed = [
+        ProttoKey::Module,
+        ProttoKey::ProtoName,
+        ProttoKey::Transparent,
+        ProttoKey::AnyTypeUrl,
+    ];
+    if let Some(meta) = protto_metas(attrs)?
+        .iter()
+        .find(|meta| !allowed.iter().any(|key| meta.path().is_ident(key.name())))
+    {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "a transparent struct delegates to its field's conversion and only takes module, \
+            proto_name and any_type_url alongside transparent",
+        ));
+    }
+
+    let field = fields.iter().next().expect("exactly one field");
+    if !protto_metas(&field.attrs)?.is_empty() {
+        return Err(syn::Error::new_spanned(
+            field,
+            "the field of a transparent struct takes no protto attributes; its type's own \
+            conversion is used",
+        ));
+    }
+    Ok(())
+}
+
+/// A struct-level `passthrough` struct is the proto message itself, so it has no fields to map
+/// and takes only the keys adding impls for the message: any_type_url and estimated_size
+fn validate_passthrough_struct(attrs: &[Attribute], fields: &syn::Fields) -> syn::Result<()> {
+    let allowed = [
+        ProttoKey::Passthrough,
+        ProttoKey::AnyTypeUrl,
+        ProttoKey::EstimatedSize,
+    ];
+    if let Some(meta) = protto_metas(attrs)?
+        .iter()
+        .find(|meta| !allowed.iter().any(|key| meta.path().is_ident(key.name())))
+    {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "a passthrough struct is its own proto message and only takes any_type_url and \
+            estimated_size alongside passthrough",
+        ));
+    }
+
+    for field in fields {
+        if !protto_metas(&field.attrs)?.is_empty() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "the fields of a passthrough struct take no protto attributes; the struct \
+                converts to and from itself unchanged",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A struct-level `sources` struct converts from and into the tuple of its proto messages, so each
+/// mapped field names the message it belongs to, and the struct takes only the keys that apply to
+/// those conversions. Outside such a struct, fields may not name a source.
+fn validate_sources_struct(attrs: &[Attribute], fields: &syn::Fields) -> syn::Result<()> {
+    let sources = get_struct_level_sources(attrs)?;
+    if sources.is_empty() {
+        if let Some(field) = fields
+            .iter()
+            .find(|field| ProtoFieldMeta::from_field(field).is_ok_and(|meta| meta.source.is_some()))
+        {
+            return Err(syn::Error::new_spanned(
+                field,
+                "source names an entry of the struct-level sources, which this struct does not \
+                declare",
+            ));
+        }
+        return Ok(());
+    }
+
+    let allowed = [
+        ProttoKey::Sources,
+        ProttoKey::ErrorType,
+        ProttoKey::ErrorFn,
+        ProttoKey::ErrorFnWithValue,
+        ProttoKey::ErrorMod,
+        ProttoKey::ErrorVis,
+        ProttoKey::StaticErrors,
+        ProttoKey::Partial,
+        ProttoKey::Cfg,
+    ];
+    if let Some(meta) = protto_metas(attrs)?
+        .iter()
+        .find(|meta| !allowed.iter().any(|key| meta.path().is_ident(key.name())))
+    {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "a sources struct converts to and from the tuple of its proto messages and only takes \
+            the error keys, partial and cfg alongside sources",
+        ));
+    }
+
+    let field_denied = [
+        ProttoKey::ResolveWith,
+        ProttoKey::Manual,
+        ProttoKey::DependsOn,
+        ProttoKey::PreserveUnknown,
+    ];
+    for field in fields {
+        if let Some(meta) = protto_metas(&field.attrs)?.iter().find(|meta| {
+            field_denied
+                .iter()
+                .any(|key| meta.path().is_ident(key.name()))
+        }) {
+            return Err(syn::Error::new_spanned(
+                meta,
+                "the fields of a sources struct each convert from a single proto message, so \
+                they do not take resolve_with, manual, depends_on or preserve_unknown",
+            ));
+        }
+        if has_proto_ignore(field) {
+            continue;
+        }
+        let Ok(meta) = ProtoFieldMeta::from_field(field) else {
+            continue;
+        };
+        match meta.source {
+            Some(source) if sources.iter().any(|(name, _)| *name == source) => {}
+            Some(source) => {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!(
+                        "source `{source}` is not one of the struct-level sources: {}",
+                        sources
+                            .iter()
+                            .map(|(name, _)| name.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                ));
+            }
+            None => {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "each field of a sources struct names the proto message it belongs to, e.g. \
+                    #[protto(source = \"header\")], or is ignored",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A struct-level `arena` struct converts each field through `protto::arena::FromProtoIn`, so
+/// beyond naming the proto message its fields may only be renamed or ignored
+fn validate_arena_struct(attrs: &[Attribute], fields: &syn::Fields) -> syn::Result<()> {
+    let allowed = [
+        ProttoKey::Arena,
+        ProttoKey::Module,
+        ProttoKey::ProtoName,
+        ProttoKey::Cfg,
+    ];
+    if let Some(meta) = protto_metas(attrs)?
+        .iter()
+        .find(|meta| !allowed.iter().any(|key| meta.path().is_ident(key.name())))
+    {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "an arena struct converts each field in the arena and only takes module, proto_name \
+            and cfg alongside arena",
+        ));
+    }
+
+    let field_allowed = [ProttoKey::ProtoName, ProttoKey::Ignore];
+    for field in fields {
+        if let Some(meta) = protto_metas(&field.attrs)?.iter().find(|meta| {
+            !field_allowed
+                .iter()
+                .any(|key| meta.path().is_ident(key.name()))
+        }) {
+            return Err(syn::Error::new_spanned(
+                meta,
+                "the fields of an arena struct convert through protto::arena::FromProtoIn and \
+                only take proto_name and ignore",
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_key(meta: &Meta, level: AttributeLevel) -> syn::Result<()> {
+    let path = meta.path();
+    let name = path
+        .get_ident()
+        .map(|ident| ident.to_string())
+        .unwrap_or_else(|| quote!(#path).to_string());
+    let supported = ProttoKey::keys_at(level);
+
+    if let Some(alias) = ProttoAlias::from_name(&name) {
+        return match (level, meta) {
+            (AttributeLevel::Field, Meta::Path(_)) => Ok(()),
+            (AttributeLevel::Field, _) => Err(syn::Error::new_spanned(
+                meta,
+                format!("`{name}` takes no value"),
+            )),
+            _ => Err(syn::Error::new_spanned(
+                path,
+                format!(
+                    "`{name}` (short for `{}`) is not supported on {level}; {}",
+                    alias.expansion(),
+                    describe_supported(level, &supported)
+                ),
+            )),
+        };
+    }
+
+    let Some(key) = ProttoKey::from_name(&name) else {
+        let suggestion = closest_key(&name, &supported)
+            .map(|key| format!("did you mean `{key}`? "))
+            .unwrap_or_default();
+        return Err(syn::Error::new_spanned(
+            path,
+            format!(
+                "unknown {} attribute `{name}` on {level}; {suggestion}{}",
+                constants::PROTTO_ATTRIBUTE,
+                describe_supported(level, &supported)
+            ),
+        ));
+    };
+
+    if !key.levels().contains(&level) {
+        return Err(syn::Error::new_spanned(
+            path,
+            format!(
+                "`{name}` is not supported on {level}; {}",
+                describe_supported(level, &supported)
+            ),
+        ));
+    }
+
+    let shape_ok = matches!(
+        (key.shape(), meta),
+        (
+            KeyShape::Flag | KeyShape::FlagOrValue | KeyShape::FlagOrNested,
+            Meta::Path(_)
+        ) | (KeyShape::Value | KeyShape::FlagOrValue, Meta::NameValue(_))
+            | (KeyShape::Nested | KeyShape::FlagOrNested, Meta::List(_))
+    );
+    if !shape_ok {
+        let expected = match key.shape() {
+            KeyShape::Flag => format!("`{name}` takes no value"),
+            KeyShape::Value => format!("`{name}` expects a value, e.g. {name} = \"...\""),
+            KeyShape::FlagOrValue => format!("`{name}` expects `{name}` or `{name} = ...`"),
+            KeyShape::Nested => format!("`{name}` expects nested entries, e.g. {name}(...)"),
+            KeyShape::FlagOrNested => format!("`{name}` expects `{name}` or `{name}(...)`"),
+        };
+        return Err(syn::Error::new_spanned(meta, expected));
+    }
+
+    Ok(())
+}
+
+fn describe_supported(level: AttributeLevel, supported: &[&str]) -> String {
+    if supported.is_empty() {
+        format!(
+            "{level}s take no {} attributes",
+            constants::PROTTO_ATTRIBUTE
+        )
+    } else {
+        format!("supported {level} attributes: {}", supported.join(", "))
+    }
+}
+
+/// The supported key closest to a misspelled one, if any is close enough to be a likely typo
+fn closest_key<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|candidate| (*candidate, edit_distance(name, candidate)))
+        .filter(|(candidate, distance)| *distance <= (candidate.len() / 3).max(1))
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+pub fn get_proto_struct_error_type(attrs: &[Attribute]) -> Option<syn::Type> {
+    for meta in protto_metas_or_panic(attrs) {
+        if let Meta::NameValue(meta_nv) = meta
+            && meta_nv.path.is_ident("error_type")
+        {
+            if let Expr::Path(expr_path) = &meta_nv.value {
+                return Some(syn::Type::Path(syn::TypePath {
+                    qself: None,
+                    path: expr_path.path.clone(),
+                }));
+            }
+            panic!(
+                "error_type value must be a type path; e.g., #[{}(error_type = MyError)]",
+                constants::PROTTO_ATTRIBUTE
+            );
+        }
+    }
+    None
+}
+
+pub fn get_struct_level_resolver(attrs: &[Attribute]) -> Option<syn::Type> {
+    for meta in protto_metas_or_panic(attrs) {
+        if let Meta::NameValue(meta_nv) = meta
+            && meta_nv.path.is_ident("resolver")
+        {
+            if let Expr::Path(expr_path) = &meta_nv.value {
+                return Some(syn::Type::Path(syn::TypePath {
+                    qself: None,
+                    path: expr_path.path.clone(),
+                }));
+            }
+            panic!(
+                "resolver value must be a type path; e.g., #[{}(resolver = MyResolver)]",
+                constants::PROTTO_ATTRIBUTE
+            );
+        }
+    }
+    None
+}
+
+/// Struct-level `error_fn`, or `error_fn_with_value` which also passes the offending proto value
+pub fn get_struct_level_error_fn(attrs: &[Attribute]) -> Option<String> {
+    let metas = protto_metas_or_panic(attrs);
+    let error_fn_keys = metas
+        .iter()
+        .filter(|meta| {
+            meta.path().is_ident("error_fn") || meta.path().is_ident("error_fn_with_value")
+        })
+        .count();
+    if error_fn_keys > 1 {
+        panic!("Cannot specify both 'error_fn' and 'error_fn_with_value'");
+    }
+
+    for meta in metas {
+        if let Meta::NameValue(meta_nv) = meta
+            && (meta_nv.path.is_ident("error_fn") || meta_nv.path.is_ident("error_fn_with_value"))
+        {
+            match &meta_nv.value {
+                Expr::Lit(expr_lit) => {
+                    if let Lit::Str(lit_str) = &expr_lit.lit {
+                        return Some(lit_str.value());
+                    }
+                }
+                Expr::Path(expr_path) => {
+                    return Some(quote!(#expr_path).to_string());
+                }
+                _ => {
+                    panic!(
+                        "error_fn value must be a string literal or path. \
+                        Examples: error_fn = \"my_function\" or error_fn = my_function"
+                    );
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Checks that every field error has exactly one way to be built. A failing field calls its own
+/// `error_fn`, else the struct-level `error_fn`, else the generated `<Struct>ConversionError`
+/// variant; the generated type only exists without a struct-level `error_type`, so with one,
+/// every field that can fail needs an `error_fn` at one of the two levels.
+pub fn validate_error_configuration(
+    struct_level_error_type: &Option<syn::Type>,
+    struct_level_error_fn: &Option<String>,
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+) -> Result<(), String> {
+    let mut fields_needing_fallback = Vec::new();
+
+    for field in fields {
+        if has_proto_ignore(field) {
+            continue;
+        }
+        // Malformed field attributes are reported when the field itself is generated
+        let Ok(meta) = ProtoFieldMeta::from_field(field) else {
+            continue;
+        };
+        let field_name = field
+            .ident
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+
+        if let Some(field_error_type) = &meta.error_type {
+            match struct_level_error_type {
+                None => {
+                    return Err(format!(
+                        "Field '{field_name}' sets 'error_type = {field_error_type}', but a \
+                        struct converts with a single error type. Set it on the struct instead: \
+                        #[protto(error_type = {field_error_type})]"
+                    ));
+                }
+                Some(error_type) if quote!(#error_type).to_string() != *field_error_type => {
+                    return Err(format!(
+                        "Field '{field_name}' sets 'error_type = {field_error_type}', which \
+                        conflicts with the struct-level 'error_type = {}'. A struct converts \
+                        with a single error type; give the field an 'error_fn' returning it \
+                        instead",
+                        quote!(#error_type)
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+
+        if struct_level_error_type.is_some()
+            && meta.error_fn.is_none()
+            && error_analysis::reports_field_errors(field, &meta)
+        {
+            fields_needing_fallback.push(field_name);
+        }
+    }
+
+    if !fields_needing_fallback.is_empty() && struct_level_error_fn.is_none() {
+        return Err(format!(
+            "When 'error_type' is specified, fields that can fail but have no 'error_fn' \
+            require a struct-level 'error_fn' as fallback. Fields needing fallback: {}. \
+            Add: #[protto(error_fn = \"YourErrorType::missing_field\")]",
+            fields_needing_fallback.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Parse struct-level `ignore` attribute
+/// Returns a HashSet of field names to ignore during proto generation
+pub fn get_struct_level_proto_ignore(attrs: &[Attribute]) -> std::collections::HashSet<String> {
+    let mut ignored_fields = std::collections::HashSet::new();
+
+    for meta in protto_metas_or_panic(attrs) {
+        if let Meta::NameValue(meta_nv) = meta
+            && meta_nv.path.is_ident("ignore")
+        {
+            if let Expr::Lit(expr_lit) = &meta_nv.value
+                && let Lit::Str(lit_str) = &expr_lit.lit
+            {
+                // Parse comma-separated field names
+                let field_names = lit_str.value();
+                for field_name in field_names.split(',') {
+                    let trimmed = field_name.trim();
+                    if !trimmed.is_empty() {
+                        ignored_fields.insert(trimmed.to_string());
+                    }
+                }
+            } else {
+                panic!(
+                    "ignore value must be a string literal with comma-separated field names, \
+                    e.g., #[{}(ignore = \"field1, field2\")]",
+                    constants::PROTTO_ATTRIBUTE
+                );
+            }
+        }
+    }
+
+    ignored_fields
+}
+
+pub fn get_proto_module(attrs: &[Attribute]) -> Option<String> {
+    for meta in protto_metas_or_panic(attrs) {
+        if let Meta::NameValue(meta_nv) = meta
+            && meta_nv.path.is_ident("module")
+        {
+            if let Expr::Lit(expr_lit) = meta_nv.value
+                && let Lit::Str(lit_str) = expr_lit.lit
+            {
+                return Some(lit_str.value());
+            }
+            panic!(
+                "module value must be a string literal, e.g., #[{}(module = \"path\")]",
+                constants::PROTTO_ATTRIBUTE
+            );
+        }
+    }
+    None
+}
+
+pub fn get_struct_level_error_mod(attrs: &[Attribute]) -> Option<syn::Ident> {
+    for meta in protto_metas_or_panic(attrs) {
+        if let Meta::NameValue(meta_nv) = meta
+            && meta_nv.path.is_ident("error_mod")
+        {
+            if let Expr::Lit(expr_lit) = &meta_nv.value
+                && let Lit::Str(lit_str) = &expr_lit.lit
+                && let Ok(error_mod) = lit_str.parse::<syn::Ident>()
+            {
+                return Some(error_mod);
+            }
+            panic!(
+                "error_mod value must be a module name string, e.g., #[{}(error_mod = \"user_errors\")]",
+                constants::PROTTO_ATTRIBUTE
+            );
+        }
+    }
+    None
+}
+
+/// Parse `error_vis = "pub(crate)"`, overriding the struct's visibility on the generated error
+/// type
+pub fn get_struct_level_error_vis(attrs: &[Attribute]) -> Option<syn::Visibility> {
+    for meta in protto_metas_or_panic(attrs) {
+        if let Meta::NameValue(meta_nv) = meta
+            && meta_nv.path.is_ident("error_vis")
+        {
+            if let Expr::Lit(expr_lit) = &meta_nv.value
+                && let Lit::Str(lit_str) = &expr_lit.lit
+                && let Ok(error_vis) = lit_str.parse::<syn::Visibility>()
+            {
+                return Some(error_vis);
+            }
+            panic!(
+                "error_vis value must be a visibility string, e.g., #[{}(error_vis = \"pub(crate)\")]",
+                constants::PROTTO_ATTRIBUTE
+            );
+        }
+    }
+    None
+}
+
+pub fn has_struct_level_error_fn_with_value(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs)
+        .iter()
+        .any(|meta| meta.path().is_ident("error_fn_with_value"))
+}
+
+pub fn has_struct_level_static_errors(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs)
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("static_errors")))
+}
+
+/// Parse struct-level `partial`: the struct projects a subset of the proto message, and the proto
+/// fields it has no field for are filled from `Default` when building the message
+pub fn has_struct_level_partial(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs)
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("partial")))
+}
+
+/// Parse struct-level `validate`: the proto message is checked with its
+/// `protto::validate::ValidateProto` impl before it is converted
+pub fn has_struct_level_validate(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs)
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("validate")))
+}
+
+/// Parse struct-level `debug_roundtrip`: in debug builds, the proto -> rust conversion asserts
+/// that converting the result back yields the same proto message
+pub fn has_struct_level_debug_roundtrip(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs)
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("debug_roundtrip")))
+}
+
+/// Parse struct-level `arena`: the struct converts from a borrowed proto message into fields
+/// allocated in a `bumpalo::Bump`
+pub fn has_struct_level_arena(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs)
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("arena")))
+}
+
+/// Parse struct-level `passthrough`: the struct is the proto message itself, so the derive
+/// generates only the identity conversions and skips the field analysis
+pub fn has_struct_level_passthrough(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs)
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("passthrough")))
+}
+
+/// Parse struct- and enum-level `cfg(...)`: the predicates every generated item is compiled
+/// under, e.g. `cfg(feature = "grpc")` for a crate whose proto module is feature-gated
+pub fn get_cfg_predicates(attrs: &[Attribute]) -> syn::Result<Vec<Meta>> {
+    protto_metas(attrs)?
+        .into_iter()
+        .filter_map(|meta| match meta {
+            Meta::List(list) if list.path.is_ident("cfg") => Some(list),
+            _ => None,
+        })
+        .map(|list| {
+            syn::parse2::<Meta>(list.tokens.clone()).map_err(|_| {
+                syn::Error::new_spanned(
+                    &list,
+                    "cfg expects one cfg predicate, e.g. cfg(feature = \"grpc\") or \
+                    cfg(all(feature = \"grpc\", not(test)))",
+                )
+            })
+        })
+        .collect()
+}
+
+/// Parse struct-level `proto_only(checksum = "compute_checksum", ...)`: proto fields the struct
+/// has no field for, each set from a function of the struct when converting to proto
+pub fn get_struct_level_proto_only(attrs: &[Attribute]) -> syn::Result<Vec<ProtoOnlyField>> {
+    let mut proto_only = Vec::new();
+    for meta in protto_metas(attrs)? {
+        let Meta::List(list) = meta else {
+            continue;
+        };
+        if !list.path.is_ident("proto_only") {
+            continue;
+        }
+        let entries = parse_string_entries(&list).map_err(|e| {
+            syn::Error::new_spanned(
+                &list,
+                format!("proto_only entries must be `proto_field = \"to_proto_fn\"`: {e}"),
+            )
+        })?;
+        for (proto_field, to_proto_fn) in entries {
+            if syn::parse_str::<syn::Path>(&to_proto_fn).is_err() {
+                return Err(syn::Error::new_spanned(
+                    &list,
+                    format!(
+                        "proto_only: `{to_proto_fn}` for `{proto_field}` is not a function path"
+                    ),
+                ));
+            }
+            if proto_only
+                .iter()
+                .any(|field: &ProtoOnlyField| field.proto_field == proto_field)
+            {
+                return Err(syn::Error::new_spanned(
+                    &list,
+                    format!("proto_only lists `{proto_field}` more than once"),
+                ));
+            }
+            proto_only.push(ProtoOnlyField {
+                proto_field,
+                to_proto_fn,
+            });
+        }
+    }
+    Ok(proto_only)
+}
+
+/// Parse struct-level `sources(header = "proto::Header", body = "proto::Body")`: the proto messages
+/// a struct's fields are split across, in the order of the tuple the struct converts from and into
+pub fn get_struct_level_sources(attrs: &[Attribute]) -> syn::Result<Vec<(syn::Ident, syn::Path)>> {
+    let mut sources: Vec<(syn::Ident, syn::Path)> = Vec::new();
+    let mut sources_list = None;
+    for meta in protto_metas(attrs)? {
+        let Meta::List(list) = meta else {
+            continue;
+        };
+        if !list.path.is_ident("sources") {
+            continue;
+        }
+        let entries = parse_string_entries(&list).map_err(|e| {
+            syn::Error::new_spanned(
+                &list,
+                format!("sources entries must be `name = \"proto::Message\"`: {e}"),
+            )
+        })?;
+        for (name, proto_path) in entries {
+            let path = match syn::parse_str::<syn::Path>(&proto_path) {
+                Ok(path) if path.segments.len() > 1 => path,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &list,
+                        format!(
+                            "sources: `{proto_path}` for `{name}` must be a proto message path \
+                            including its module, e.g. proto::Header"
+                        ),
+                    ));
+                }
+            };
+            let Ok(ident) = syn::parse_str::<syn::Ident>(&name) else {
+                return Err(syn::Error::new_spanned(
+                    &list,
+                    format!("sources: `{name}` is a keyword; name the source with an identifier"),
+                ));
+            };
+            if sources.iter().any(|(source, _)| *source == name) {
+                return Err(syn::Error::new_spanned(
+                    &list,
+                    format!("sources lists `{name}` more than once"),
+                ));
+            }
+            sources.push((ident, path));
+        }
+        sources_list = Some(list);
+    }
+    if let Some(list) = sources_list.filter(|_| sources.len() < 2) {
+        return Err(syn::Error::new_spanned(
+            list,
+            "sources names two or more proto messages the struct is split across; for a single \
+            message use module and proto_name",
+        ));
+    }
+    Ok(sources)
+}
+
+/// Parse struct-level `proto_parts`: the derive also generates `<Struct>ProtoParts`, holding the
+/// converted fields as `Option`s until `finish` builds the struct
+pub fn has_struct_level_proto_parts(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs)
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("proto_parts")))
+}
+
+/// Parse struct-level `view`: the derive also generates `<Struct>View<'a>`, borrowing the strings
+/// and bytes of a proto message for reads that need no owned copy
+pub fn has_struct_level_view(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs)
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("view")))
+}
+
+/// Parse struct-level `transparent`: the struct converts to and from the proto message by
+/// delegating to its single field's conversion
+pub fn has_struct_level_transparent(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs)
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("transparent")))
+}
+
+pub fn has_struct_level_presence(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs)
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("presence")))
+}
+
+/// Parse struct-level `field_paths`: associated `FIELD_PATHS` and `<FIELD>_PATH` constants naming
+/// the mapped proto fields
+pub fn has_struct_level_field_paths(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs)
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("field_paths")))
+}
+
+/// Parse struct-level `extra_fields = "default"`: rust fields the proto message lacks, per the
+/// build metadata, are ignored and filled from `Default`
+pub fn has_struct_level_extra_fields_default(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs).into_iter().any(|meta| {
+        let Meta::NameValue(meta_nv) = meta else {
+            return false;
+        };
+        if !meta_nv.path.is_ident("extra_fields") {
+            return false;
+        }
+        match &meta_nv.value {
+            Expr::Lit(syn::ExprLit {
+                lit: Lit::Str(lit_str),
+                ..
+            }) if lit_str.value() == "default" => true,
+            _ => panic!(
+                "extra_fields only supports \"default\", e.g., #[{}(extra_fields = \"default\")]",
+                constants::PROTTO_ATTRIBUTE
+            ),
+        }
+    })
+}
+
+/// Parse struct-level `any_type_url = "type.googleapis.com/pkg.Message"`: implement
+/// `protto::any::ToProtoAny`, packing the proto message under that type URL
+pub fn get_struct_level_any_type_url(attrs: &[Attribute]) -> Option<String> {
+    for meta in protto_metas_or_panic(attrs) {
+        if let Meta::NameValue(meta_nv) = meta
+            && meta_nv.path.is_ident("any_type_url")
+        {
+            if let Expr::Lit(expr_lit) = &meta_nv.value
+                && let Lit::Str(lit_str) = &expr_lit.lit
+                && let Some((_, type_name)) = lit_str.value().rsplit_once('/')
+                && !type_name.is_empty()
+            {
+                return Some(lit_str.value());
+            }
+            panic!(
+                "any_type_url must be a type URL ending in the full proto message name, e.g., \
+                #[{}(any_type_url = \"type.googleapis.com/my.package.Event\")]",
+                constants::PROTTO_ATTRIBUTE
+            );
+        }
+    }
+    None
+}
+
+/// Parse struct-level `use_rules = "crate::money_rules"`: the `#[protto_rules]` module whose
+/// convention functions convert fields of the types they name
+pub fn get_struct_level_use_rules(attrs: &[Attribute]) -> Option<syn::Path> {
+    for meta in protto_metas_or_panic(attrs) {
+        if let Meta::NameValue(meta_nv) = meta
+            && meta_nv.path.is_ident("use_rules")
+        {
+            if let Expr::Lit(expr_lit) = &meta_nv.value
+                && let Lit::Str(lit_str) = &expr_lit.lit
+                && let Ok(path) = lit_str.parse::<syn::Path>()
+            {
+                return Some(path);
+            }
+            panic!(
+                "use_rules must name a module path, e.g., #[{}(use_rules = \"crate::money_rules\")]",
+                constants::PROTTO_ATTRIBUTE
+            );
+        }
+    }
+    None
+}
+
+/// Parse struct-level `also_proto = "proto_v2::User, proto_v3::User"`: further prost messages
+/// the struct converts to and from with the same field attributes. Repeated `also_proto` keys
+/// add to the list.
+pub fn get_struct_level_also_proto(attrs: &[Attribute]) -> Vec<syn::Path> {
+    let mut targets = Vec::new();
+
+    for meta in protto_metas_or_panic(attrs) {
+        if let Meta::NameValue(meta_nv) = meta
+            && meta_nv.path.is_ident("also_proto")
+        {
+            let Expr::Lit(syn::ExprLit {
+                lit: Lit::Str(lit_str),
+                ..
+            }) = &meta_nv.value
+            else {
+                panic!(
+                    "also_proto value must be a string literal with comma-separated proto message \
+                    paths, e.g., #[{}(also_proto = \"proto_v2::User, proto_v3::User\")]",
+                    constants::PROTTO_ATTRIBUTE
+                );
+            };
+            for target in lit_str.value().split(',') {
+                let trimmed = target.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                match syn::parse_str::<syn::Path>(trimmed) {
+                    Ok(path) if path.segments.len() > 1 => targets.push(path),
+                    _ => panic!(
+                        "also_proto entries must be proto message paths including their module, \
+                        e.g., proto_v2::User; found: {trimmed}"
+                    ),
+                }
+            }
+        }
+    }
+
+    targets
+}
+
+/// Parse struct- or enum-level `estimated_size`: implement `protto::size::EstimatedProtoSize`
+pub fn has_estimated_size(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs)
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("estimated_size")))
+}
+
+/// Parse `custom_fns_are = "pure"`, the contract that custom conversion functions only map their
+/// input to their output, which the derive backs by asserting their exact signatures
+pub fn has_struct_level_pure_custom_fns(attrs: &[Attribute]) -> bool {
+    for meta in protto_metas_or_panic(attrs) {
+        if let Meta::NameValue(meta_nv) = meta
+            && meta_nv.path.is_ident("custom_fns_are")
+        {
+            if let Expr::Lit(expr_lit) = &meta_nv.value
+                && let Lit::Str(lit_str) = &expr_lit.lit
+                && lit_str.value() == "pure"
+            {
+                return true;
+            }
+            panic!(
+                "custom_fns_are only supports \"pure\", e.g., #[{}(custom_fns_are = \"pure\")]",
+                constants::PROTTO_ATTRIBUTE
+            );
+        }
+    }
+    false
+}
+
+/// Parse `conversions = "both"`, which adds panicking conversions alongside the fallible ones
+pub fn has_struct_level_both_conversions(attrs: &[Attribute]) -> bool {
+    for meta in protto_metas_or_panic(attrs) {
+        if let Meta::NameValue(meta_nv) = meta
+            && meta_nv.path.is_ident("conversions")
+        {
+            if let Expr::Lit(expr_lit) = &meta_nv.value
+                && let Lit::Str(lit_str) = &expr_lit.lit
+                && lit_str.value() == "both"
+            {
+                return true;
+            }
+            panic!(
+                "conversions only supports \"both\", e.g., #[{}(conversions = \"both\")]",
+                constants::PROTTO_ATTRIBUTE
+            );
+        }
+    }
+    false
+}
+
+/// Parse enum-level `oneof`: the comma-separated prost oneof enums, relative to `module`, that
+/// share this enum's variants
+pub fn get_enum_level_oneofs(attrs: &[Attribute]) -> Vec<syn::Path> {
+    let mut oneofs = Vec::new();
+
+    for meta in protto_metas_or_panic(attrs) {
+        if let Meta::NameValue(meta_nv) = meta
+            && meta_nv.path.is_ident("oneof")
+        {
+            let Expr::Lit(syn::ExprLit {
+                lit: Lit::Str(lit_str),
+                ..
+            }) = &meta_nv.value
+            else {
+                panic!(
+                    "oneof value must be a string literal with comma-separated oneof enum paths, \
+                    e.g., #[{}(oneof = \"envelope::Payload, notice::Payload\")]",
+                    constants::PROTTO_ATTRIBUTE
+                );
+            };
+            for oneof in lit_str.value().split(',') {
+                let trimmed = oneof.trim();
+                if !trimmed.is_empty() {
+                    let path = syn::parse_str(trimmed)
+                        .unwrap_or_else(|_| panic!("Invalid oneof enum path: {trimmed}"));
+                    oneofs.push(path);
+                }
+            }
+        }
+    }
+
+    oneofs
+}
+
+/// Parse enum-level `non_exhaustive_oneof = "error_variant"`: proto oneof variants without a
+/// rust counterpart fail the conversion instead of failing the build.
+pub fn get_enum_level_non_exhaustive_oneof(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs).into_iter().any(|meta| {
+        let Meta::NameValue(meta_nv) = meta else {
+            return false;
+        };
+        if !meta_nv.path.is_ident("non_exhaustive_oneof") {
+            return false;
+        }
+        match &meta_nv.value {
+            Expr::Lit(syn::ExprLit {
+                lit: Lit::Str(lit_str),
+                ..
+            }) if lit_str.value() == "error_variant" => true,
+            _ => panic!(
+                "non_exhaustive_oneof only supports \"error_variant\", e.g., \
+                #[{}(non_exhaustive_oneof = \"error_variant\")]",
+                constants::PROTTO_ATTRIBUTE
+            ),
+        }
+    })
+}
+
+pub fn get_proto_struct_name(attrs: &[Attribute]) -> Option<String> {
+    for meta in protto_metas_or_panic(attrs) {
+        if let Meta::NameValue(meta_nv) = meta
+            && meta_nv.path.is_ident("proto_name")
+        {
+            if let Expr::Lit(expr_lit) = meta_nv.value
+                && let Lit::Str(lit_str) = expr_lit.lit
+            {
+                return Some(lit_str.value());
+            }
+            panic!(
+                "proto_name value must be a string literal, e.g., #[{}(proto_name = \"...\")]",
+                constants::PROTTO_ATTRIBUTE
+            );
+        }
+    }
+    None
+}
+
+pub fn has_transparent_attr(field: &Field) -> bool {
+    let Ok(metas) = protto_metas(&field.attrs) else {
+        return false;
+    };
+    for nested_meta in metas {
+        match nested_meta {
+            // Only match actual transparent attribute, not values containing "transparent"
+            Meta::Path(path) if path.is_ident("transparent") => {
+                return true;
+            }
+            Meta::NameValue(nv) if nv.path.is_ident("transparent") => {
+                // Handle transparent = true/false
+                if let Expr::Lit(expr_lit) = &nv.value
+                    && let Lit::Bool(lit_bool) = &expr_lit.lit
+                {
+                    return lit_bool.value;
+                }
+
+                return true; // Default to true if not a boolean
+            }
+            _ => {} // Ignore other attributes
+        }
+    }
+    false
+}
+
+pub fn get_proto_field_name(field: &Field) -> Option<String> {
+    for meta in protto_metas_or_panic(&field.attrs) {
+        if let Meta::NameValue(meta_nv) = meta
+            && meta_nv.path.is_ident("proto_name")
+        {
+            if let Expr::Lit(expr_lit) = &meta_nv.value
+                && let Lit::Str(lit_str) = &expr_lit.lit
+            {
+                return Some(lit_str.value());
+            }
+            panic!("proto_name value must be a string literal, e.g., proto_name = \"field_name\"");
+        }
+    }
+    None
+}
+
+pub fn has_proto_ignore(field: &Field) -> bool {
+    for meta in protto_metas_or_panic(&field.attrs) {
+        // a field preserving the proto message maps no proto field of its own
+        if let Meta::Path(path) = meta
+            && (path.is_ident("ignore") || path.is_ident("preserve_unknown"))
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Parse `group(sub_field = "proto_field", ...)` into (sub-struct field, proto field) pairs.
+/// A bare `sub_field` maps to the proto field of the same name.
+fn parse_group_mappings(
+    list: &syn::MetaList,
+    field_name: &str,
+) -> Result<Vec<(String, String)>, String> {
+    let nested: Punctuated<Meta, Comma> = Punctuated::parse_terminated
+        .parse2(list.tokens.clone())
+        .map_err(|e| format!("Field '{field_name}': failed to parse group attribute: {e}"))?;
+
+    let mut mappings = Vec::new();
+    for meta in nested {
+        let mapping = match meta {
+            Meta::Path(path) if path.get_ident().is_some() => {
+                let sub_field = path.get_ident().unwrap().to_string();
+                (sub_field.clone(), sub_field)
+            }
+            Meta::NameValue(nv) if nv.path.get_ident().is_some() => {
+                let sub_field = nv.path.get_ident().unwrap().to_string();
+                if let Expr::Lit(expr_lit) = &nv.value
+                    && let Lit::Str(lit_str) = &expr_lit.lit
+                {
+                    (sub_field, lit_str.value())
+                } else {
+                    return Err(format!(
+                        "Field '{field_name}': group mapping for '{sub_field}' must be a string literal, \
+                        e.g., group({sub_field} = \"proto_field\")"
+                    ));
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "Field '{field_name}': group entries must be `sub_field = \"proto_field\"` or `sub_field`"
+                ));
+            }
+        };
+
+        if mappings.iter().any(|(sub, _)| sub == &mapping.0) {
+            return Err(format!(
+                "Field '{field_name}': group maps sub-field '{}' more than once",
+                mapping.0
+            ));
+        }
+        mappings.push(mapping);
+    }
+
+    if mappings.is_empty() {
+        return Err(format!(
+            "Field '{field_name}': group requires at least one sub-field mapping"
+        ));
+    }
+
+    Ok(mappings)
+}
+
+/// Parse `bool_enum(proto_enum = "Enum", true = "Variant", false = "Variant", unknown = "...")`,
+/// where `unknown` is one of "error", "panic", "true" or "false" and defaults to "panic".
+fn parse_bool_enum_mapping(
+    list: &syn::MetaList,
+    field_name: &str,
+) -> Result<BoolEnumMapping, String> {
+    let entries = parse_string_entries(list).map_err(|e| {
+        format!("Field '{field_name}': bool_enum entries must be `key = \"value\"`: {e}")
+    })?;
+
+    let mut proto_enum = None;
+    let mut true_variant = None;
+    let mut false_variant = None;
+    let mut unknown = UnknownVariantPolicy::Panic;
+    for (key, value) in entries {
+        match key.as_str() {
+            "proto_enum" => proto_enum = Some(value),
+            "true" => true_variant = Some(value),
+            "false" => false_variant = Some(value),
+            "unknown" => {
+                unknown = match value.as_str() {
+                    "error" => UnknownVariantPolicy::Error,
+                    "panic" => UnknownVariantPolicy::Panic,
+                    "true" => UnknownVariantPolicy::Value(true),
+                    "false" => UnknownVariantPolicy::Value(false),
+                    _ => {
+                        return Err(format!(
+                            "Field '{field_name}': bool_enum unknown must be one of \"error\", \"panic\", \"true\" or \"false\""
+                        ));
+                    }
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "Field '{field_name}': unknown bool_enum key '{key}'; expected proto_enum, true, false or unknown"
+                ));
+            }
+        }
+    }
+
+    match (proto_enum, true_variant, false_variant) {
+        (Some(proto_enum), Some(true_variant), Some(false_variant)) => Ok(BoolEnumMapping {
+            proto_enum,
+            true_variant,
+            false_variant,
+            unknown,
+        }),
+        _ => Err(format!(
+            "Field '{field_name}': bool_enum requires proto_enum, true and false, \
+            e.g., bool_enum(proto_enum = \"FeatureState\", true = \"Enabled\", false = \"Disabled\")"
+        )),
+    }
+}
+
+/// Parse `result_oneof(ok = "variant", err = "variant", proto_enum = "message::Oneof")`; the
+/// variants are the oneof's fields, by proto name or by prost variant name
+fn parse_result_oneof_mapping(
+    list: &syn::MetaList,
+    field_name: &str,
+) -> Result<ResultOneofMapping, String> {
+    let entries = parse_string_entries(list).map_err(|e| {
+        format!("Field '{field_name}': result_oneof entries must be `key = \"value\"`: {e}")
+    })?;
+
+    let mut proto_enum = None;
+    let mut ok = None;
+    let mut err = None;
+    for (key, value) in entries {
+        match key.as_str() {
+            "proto_enum" => proto_enum = Some(value),
+            "ok" => ok = Some(value),
+            "err" => err = Some(value),
+            _ => {
+                return Err(format!(
+                    "Field '{field_name}': unknown result_oneof key '{key}'; expected ok, err or proto_enum"
+                ));
+            }
+        }
+    }
+
+    match (ok, err) {
+        (Some(ok), Some(err)) => Ok(ResultOneofMapping {
+            proto_enum,
+            ok,
+            err,
+        }),
+        _ => Err(format!(
+            "Field '{field_name}': result_oneof requires ok and err, naming the oneof fields \
+            holding each outcome, e.g., result_oneof(ok = \"user\", err = \"error\")"
+        )),
+    }
+}
+
+/// Entries of a nested attribute written as `key = "value"` pairs. Keys may be keywords such as
+/// `true` and `false`, so they are parsed as any identifier rather than as `Meta`.
+fn parse_string_entries(list: &syn::MetaList) -> syn::Result<Vec<(String, String)>> {
+    let entries_parser = |input: syn::parse::ParseStream| {
+        let mut entries = Vec::new();
+        while !input.is_empty() {
+            let key = input.call(syn::Ident::parse_any)?;
+            input.parse::<syn::Token![=]>()?;
+            let value: syn::LitStr = input.parse()?;
+            entries.push((key.to_string(), value.value()));
+            if !input.is_empty() {
+                input.parse::<Comma>()?;
+            }
+        }
+        Ok(entries)
+    };
+    entries_parser.parse2(list.tokens.clone())
+}
+
+/// `none_as`, `none_as_empty` and `none_as_unset` each say how a `None` is written; only one may
+/// be given
+fn check_single_none_policy(meta: &ProtoFieldMeta, field_name: &str) -> Result<(), String> {
+    match meta.none_as {
+        Some(_) => Err(format!(
+            "Field '{field_name}': Cannot specify more than one of 'none_as', 'none_as_empty' and \
+            'none_as_unset'"
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Parse `none_as = "default" | "error" | "panic"`
+fn parse_none_policy(value: &Expr, field_name: &str) -> Result<NonePolicy, String> {
+    if let Expr::Lit(expr_lit) = value
+        && let Lit::Str(lit_str) = &expr_lit.lit
+        && let Some(policy) = NonePolicy::parse(&lit_str.value())
+    {
+        Ok(policy)
+    } else {
+        Err(format!(
+            "Field '{field_name}': none_as must be one of \"default\", \"error\" or \"panic\""
+        ))
+    }
+}
+
+/// Parse `path_encoding = "utf8_strict" | "lossy" | "bytes"`
+fn parse_path_encoding(value: &Expr, field_name: &str) -> Result<PathEncoding, String> {
+    if let Expr::Lit(expr_lit) = value
+        && let Lit::Str(lit_str) = &expr_lit.lit
+        && let Some(encoding) = PathEncoding::parse(&lit_str.value())
+    {
+        Ok(encoding)
+    } else {
+        Err(format!(
+            "Field '{field_name}': path_encoding must be one of \"utf8_strict\", \"lossy\" or \"bytes\""
+        ))
+    }
+}
+
+/// Parse `bytes_as = "pod_slice"`
+fn parse_bytes_as(value: &Expr, field_name: &str) -> Result<BytesAs, String> {
+    if let Expr::Lit(expr_lit) = value
+        && let Lit::Str(lit_str) = &expr_lit.lit
+        && let Some(bytes_as) = BytesAs::parse(&lit_str.value())
+    {
+        Ok(bytes_as)
+    } else {
+        Err(format!(
+            "Field '{field_name}': bytes_as must be \"pod_slice\""
+        ))
+    }
+}
+
+/// Parse `decimal = "string" | "units_nanos"`
+fn parse_decimal(value: &Expr, field_name: &str) -> Result<DecimalAs, String> {
+    if let Expr::Lit(expr_lit) = value
+        && let Lit::Str(lit_str) = &expr_lit.lit
+        && let Some(decimal) = DecimalAs::parse(&lit_str.value())
+    {
+        Ok(decimal)
+    } else {
+        Err(format!(
+            "Field '{field_name}': decimal must be \"string\" or \"units_nanos\""
+        ))
+    }
+}
+
+/// Parse `duration = "seconds" | "millis" | "nanos"`
+fn parse_duration(value: &Expr, field_name: &str) -> Result<DurationUnit, String> {
+    if let Expr::Lit(expr_lit) = value
+        && let Lit::Str(lit_str) = &expr_lit.lit
+        && let Some(unit) = DurationUnit::parse(&lit_str.value())
+    {
+        Ok(unit)
+    } else {
+        Err(format!(
+            "Field '{field_name}': duration must be one of \"seconds\", \"millis\" or \"nanos\""
+        ))
+    }
+}
+
+/// Parse `enum_as_i32`, `enum_values` or `enum_keys` = `"error" | "panic" | "default"`
+fn parse_unknown_i32_policy(
+    key: &str,
+    value: &Expr,
+    field_name: &str,
+) -> Result<UnknownI32Policy, String> {
+    if let Expr::Lit(expr_lit) = value
+        && let Lit::Str(lit_str) = &expr_lit.lit
+        && let Some(policy) = UnknownI32Policy::parse(&lit_str.value())
+    {
+        Ok(policy)
+    } else {
+        Err(format!(
+            "Field '{field_name}': {key} must be one of \"error\", \"panic\" or \"default\""
+        ))
+    }
+}
+
+/// Parse `unspecified = "error" | "default" | "map_to = Variant"`
+fn parse_unspecified(value: &Expr, field_name: &str) -> Result<UnspecifiedPolicy, String> {
+    if let Expr::Lit(expr_lit) = value
+        && let Lit::Str(lit_str) = &expr_lit.lit
+        && let Some(policy) = UnspecifiedPolicy::parse(&lit_str.value())
+    {
+        Ok(policy)
+    } else {
+        Err(format!(
+            "Field '{field_name}': unspecified must be \"error\", \"default\" or \
+            \"map_to = Variant\""
+        ))
+    }
+}
+
+/// Parse `empty_repeated = "none" | "some_empty"`
+fn parse_empty_repeated(value: &Expr, field_name: &str) -> Result<EmptyRepeated, String> {
+    if let Expr::Lit(expr_lit) = value
+        && let Lit::Str(lit_str) = &expr_lit.lit
+        && let Some(empty_repeated) = EmptyRepeated::parse(&lit_str.value())
+    {
+        Ok(empty_repeated)
+    } else {
+        Err(format!(
+            "Field '{field_name}': empty_repeated must be \"none\" or \"some_empty\""
+        ))
+    }
+}
+
+/// Parse enum-level `enum_as_i32`: the enum converts to and from a plain proto `int32` by its
+/// discriminants, without a prost enum type
+pub fn has_enum_level_enum_as_i32(attrs: &[Attribute]) -> bool {
+    protto_metas_or_panic(attrs).iter().any(|meta| match meta {
+        Meta::Path(path) => path.is_ident("enum_as_i32"),
+        Meta::NameValue(meta_nv) if meta_nv.path.is_ident("enum_as_i32") => panic!(
+            "enum_as_i32 takes no value on an enum; the unknown value policy is set on each \
+            field, e.g., #[{}(enum_as_i32 = \"error\")]",
+            constants::PROTTO_ATTRIBUTE
+        ),
+        _ => false,
+    })
+}
+
+/// Parse `overflow = "error" | "panic" | "truncate"`
+fn parse_overflow(value: &Expr, field_name: &str) -> Result<CapacityOverflow, String> {
+    if let Expr::Lit(expr_lit) = value
+        && let Lit::Str(lit_str) = &expr_lit.lit
+        && let Some(overflow) = CapacityOverflow::parse(&lit_str.value())
+    {
+        Ok(overflow)
+    } else {
+        Err(format!(
+            "Field '{field_name}': overflow must be one of \"error\", \"panic\" or \"truncate\""
+        ))
+    }
+}
+
+/// Parse the policy of `expect(...)`: `None` for `error` and `panic`, or the default function
+/// for `default` (`Default::default`) and `default = "function_name"`
+fn parse_expect_policy(list: &syn::MetaList, field_name: &str) -> Result<Option<String>, String> {
+    match syn::parse2::<Meta>(list.tokens.clone()) {
+        Ok(Meta::Path(path)) if path.is_ident("error") || path.is_ident("panic") => Ok(None),
+        Ok(Meta::Path(path)) if path.is_ident("default") => {
+            Ok(Some(constants::USE_DEFAULT_IMPL.to_string()))
+        }
+        Ok(Meta::NameValue(nv)) if nv.path.is_ident("default") => {
+            parse_function_value(&nv.value, "expect(default)", field_name).map(Some)
+        }
+        _ => Err(format!(
+            "Field '{field_name}': expect must be one of `expect`, `expect(error)`, \
+            `expect(panic)`, `expect(default)` or `expect(default = \"function_name\")`, \
+            found `expect({})`",
+            list.tokens
+        )),
+    }
+}
+
+fn parse_function_value(value: &Expr, attr_name: &str, field_name: &str) -> Result<String, String> {
+    match value {
+        Expr::Lit(expr_lit) => {
+            if let Lit::Str(lit_str) = &expr_lit.lit {
+                Ok(lit_str.value())
+            } else {
+                Err(format!(
+                    "Field '{}': {} value must be a string literal or path. \
+                    Examples: {} = \"my_function\" or {} = my_function",
+                    field_name, attr_name, attr_name, attr_name
+                ))
+            }
+        }
+        Expr::Path(expr_path) => Ok(quote!(#expr_path).to_string()),
+        _ => Err(format!(
+            "Field '{}': {} value must be a string literal or path. \
+                Examples: {} = \"my_function\" or {} = my_function",
+            field_name, attr_name, attr_name, attr_name
+        )),
+    }
+}
+
+/// The type prost generates for the proto field, as a normalized type string; it must parse as
+/// a rust type
+fn parse_proto_field_type(value: &Expr, field_name: &str) -> Result<String, String> {
+    parse_type_value(value).ok_or_else(|| {
+        format!(
+            "Field '{field_name}': proto_field_type must name the type prost generates for \
+                the proto field, e.g. proto_field_type = \"crate::ids::AccountId\""
+        )
+    })
+}
+
+/// The type a field's type alias stands for, as a normalized type string; it must parse as a
+/// rust type
+fn parse_alias_of(value: &Expr, field_name: &str) -> Result<String, String> {
+    parse_type_value(value).ok_or_else(|| {
+        format!(
+            "Field '{field_name}': alias_of must name the type the field's type alias stands \
+                for, e.g. alias_of = \"u64\""
+        )
+    })
+}
+
+/// Parse `depends_on = "currency, scale"`: the names of other fields of the struct
+fn parse_depends_on(value: &Expr, field_name: &str) -> Result<Vec<String>, String> {
+    let usage = || {
+        format!(
+            "Field '{field_name}': depends_on must list other fields of the struct, e.g. \
+            depends_on = \"currency\" or depends_on = \"currency, scale\""
+        )
+    };
+    let Expr::Lit(syn::ExprLit {
+        lit: Lit::Str(lit_str),
+        ..
+    }) = value
+    else {
+        return Err(usage());
+    };
+    let fields: Vec<String> = lit_str
+        .value()
+        .split(',')
+        .map(|name| name.trim().to_string())
+        .collect();
+    if fields
+        .iter()
+        .any(|name| syn::parse_str::<syn::Ident>(name.trim_start_matches("r#")).is_err())
+    {
+        return Err(usage());
+    }
+    Ok(fields)
+}
+
+/// Parse `map_by = "key"`: the field of the repeated proto message a map is keyed by
+fn parse_map_by(value: &Expr, field_name: &str) -> Result<String, String> {
+    if let Expr::Lit(expr_lit) = value
+        && let Lit::Str(lit_str) = &expr_lit.lit
+        && syn::parse_str::<syn::Ident>(lit_str.value().trim_start_matches("r#")).is_ok()
+    {
+        Ok(lit_str.value())
+    } else {
+        Err(format!(
+            "Field '{field_name}': map_by must name a field of the repeated proto message, e.g. \
+            map_by = \"key\""
+        ))
+    }
+}
+
+/// Parse `presence_of = "audit"`: the optional proto field whose presence the field reads
+fn parse_presence_of(value: &Expr, field_name: &str) -> Result<String, String> {
+    if let Expr::Lit(expr_lit) = value
+        && let Lit::Str(lit_str) = &expr_lit.lit
+        && syn::parse_str::<syn::Ident>(lit_str.value().trim_start_matches("r#")).is_ok()
+    {
+        Ok(lit_str.value())
+    } else {
+        Err(format!(
+            "Field '{field_name}': presence_of must name an optional proto field, e.g. \
+            presence_of = \"audit\""
+        ))
+    }
+}
+
+/// Parse `source = "header"`: the struct-level `sources` entry the field is read from and written to
+fn parse_source(value: &Expr, field_name: &str) -> Result<String, String> {
+    if let Expr::Lit(expr_lit) = value
+        && let Lit::Str(lit_str) = &expr_lit.lit
+        && syn::parse_str::<syn::Ident>(&lit_str.value()).is_ok()
+    {
+        Ok(lit_str.value())
+    } else {
+        Err(format!(
+            "Field '{field_name}': source must name an entry of the struct-level sources, e.g. \
+            source = \"header\""
+        ))
+    }
+}
+
+/// Parse `kind = "primitive" | "message" | "enum"`
+fn parse_kind(value: &Expr, field_name: &str) -> Result<TypeKind, String> {
+    if let Expr::Lit(expr_lit) = value
+        && let Lit::Str(lit_str) = &expr_lit.lit
+        && let Some(kind) = TypeKind::parse(&lit_str.value())
+    {
+        Ok(kind)
+    } else {
+        Err(format!(
+            "Field '{field_name}': kind must be \"primitive\", \"message\" or \"enum\""
+        ))
+    }
+}
+
+fn parse_type_value(value: &Expr) -> Option<String> {
+    let type_str = match value {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        }) => lit_str.value(),
+        Expr::Path(expr_path) => quote!(#expr_path).to_string(),
+        _ => return None,
+    };
+    syn::parse_str::<syn::Type>(&type_str)
+        .ok()
+        .map(|parsed| quote!(#parsed).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validation_errors(input: &str) -> Vec<String> {
+        let ast: syn::DeriveInput = syn::parse_str(input).expect("test input must parse");
+        match validate_attributes(&ast) {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.into_iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_unknown_field_key_suggests_closest() {
+        let errors = validation_errors("struct S { #[protto(proto_nmae = \"id\")] id: u64 }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("unknown protto attribute `proto_nmae` on field"));
+        assert!(errors[0].contains("did you mean `proto_name`?"));
+        assert!(errors[0].contains("supported field attributes: proto_name"));
+    }
+
+    #[test]
+    fn test_unknown_key_without_close_match_lists_supported() {
+        let errors = validation_errors("#[protto(typo_attr)] struct S { id: u64 }");
+        assert_eq!(errors.len(), 1);
+        assert!(!errors[0].contains("did you mean"));
+        assert!(errors[0].contains("supported struct attributes: module, proto_name"));
+    }
+
+    #[test]
+    fn test_key_at_wrong_level_is_rejected() {
+        let errors = validation_errors("struct S { #[protto(resolver = Directory)] id: u64 }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("`resolver` is not supported on field"));
+    }
+
+    #[test]
+    fn test_variant_attributes_are_rejected() {
+        let errors = validation_errors("enum E { #[protto(proto_name = \"OK\")] Ok }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("variants take no protto attributes"));
+    }
+
+    #[test]
+    fn test_key_shape_is_checked() {
+        let errors = validation_errors(
+            "struct S { #[protto(proto_optional = true)] a: u64, #[protto(none_as)] b: Option<u64> }",
+        );
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("`proto_optional` takes no value"));
+        assert!(errors[1].contains("`none_as` expects a value"));
+    }
+
+    #[test]
+    fn test_errors_are_reported_for_every_field() {
+        let errors =
+            validation_errors("struct S { #[protto(defualt)] a: u64, #[protto(expcet)] b: u64 }");
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn test_field_meta_errors_are_surfaced() {
+        let errors =
+            validation_errors("struct S { #[protto(proto_optional, proto_required)] a: u64 }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("Cannot specify both proto_optional and proto_required"));
+    }
+
+    #[test]
+    fn test_arena_struct_only_renames_or_ignores_fields() {
+        assert!(
+            validation_errors(
+                "#[protto(arena, proto_name = \"Req\")] struct R<'a> { \
+                #[protto(proto_name = \"user_name\")] user: &'a str, #[protto(ignore)] n: u8 }"
+            )
+            .is_empty()
+        );
+
+        let errors = validation_errors("#[protto(arena, partial)] struct R<'a> { user: &'a str }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("only takes module, proto_name and cfg"));
+
+        let errors =
+            validation_errors("#[protto(arena)] struct R<'a> { #[protto(expect)] user: &'a str }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("only take proto_name and ignore"));
+    }
+
+    #[test]
+    fn test_passthrough_struct_takes_no_mapping_attributes() {
+        assert!(
+            validation_errors(
+                "#[protto(passthrough, any_type_url = \"type.googleapis.com/a.Ping\")] \
+                struct Ping { id: u64 }"
+            )
+            .is_empty()
+        );
+
+        let errors =
+            validation_errors("#[protto(passthrough, proto_name = \"Pong\")] struct Ping;");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("only takes any_type_url and estimated_size"));
+
+        let errors =
+            validation_errors("#[protto(passthrough)] struct Ping { #[protto(expect)] id: u64 }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("take no protto attributes"));
+    }
+
+    #[test]
+    fn test_proto_only_names_a_fn_per_proto_field() {
+        let ast: syn::DeriveInput = syn::parse_str(
+            "#[protto(proto_only(checksum = \"compute_checksum\", r#type = \"Self::kind\"))] \
+            struct S { id: u64 }",
+        )
+        .unwrap();
+        assert_eq!(
+            get_struct_level_proto_only(&ast.attrs).unwrap(),
+            vec![
+                ProtoOnlyField {
+                    proto_field: "checksum".to_string(),
+                    to_proto_fn: "compute_checksum".to_string(),
+                },
+                ProtoOnlyField {
+                    proto_field: "r#type".to_string(),
+                    to_proto_fn: "Self::kind".to_string(),
+                },
+            ]
+        );
+
+        let errors = validation_errors(
+            "#[protto(proto_only(checksum = \"a\", checksum = \"b\"))] struct S { id: u64 }",
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("lists `checksum` more than once"));
+
+        let errors = validation_errors("#[protto(proto_only(checksum = 1))] struct S { id: u64 }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("proto_only entries must be"));
+    }
+
+    #[test]
+    fn test_preserve_unknown_holds_an_optional_message() {
+        assert!(
+            validation_errors(
+                "struct S { id: u64, #[protto(preserve_unknown)] original: Option<proto::S> }"
+            )
+            .is_empty()
+        );
+
+        let errors =
+            validation_errors("struct S { #[protto(preserve_unknown)] original: proto::S }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("must be an Option of the message"));
+
+        let errors = validation_errors(
+            "struct S { #[protto(preserve_unknown, proto_name = \"raw\")] original: Option<proto::S> }",
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("takes no other keys"));
+    }
+
+    #[test]
+    fn test_presence_of_reads_into_a_bool() {
+        assert!(
+            validation_errors("struct S { #[protto(presence_of = \"audit\")] has_audit: bool }")
+                .is_empty()
+        );
+
+        let errors =
+            validation_errors("struct S { #[protto(presence_of = \"audit\")] has_audit: u8 }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("presence_of requires a bool field"));
+
+        let errors = validation_errors(
+            "struct S { #[protto(presence_of = \"audit\", to_proto_fn = \"audit_of\")] \
+            has_audit: bool }",
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("cannot set it back from a bool"));
+    }
+
+    #[test]
+    fn test_sources_fields_name_a_declared_source() {
+        let sources = "#[protto(sources(header = \"proto::Header\", body = \"proto::Body\"))]";
+        assert!(
+            validation_errors(&format!(
+                "{sources} struct S {{ #[protto(source = \"header\")] id: u64, \
+                #[protto(source = \"body\")] text: String, #[protto(ignore)] cache: u8 }}"
+            ))
+            .is_empty()
+        );
+
+        let errors = validation_errors(&format!(
+            "{sources} struct S {{ #[protto(source = \"head\")] id: u64 }}"
+        ));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("source `head` is not one of the struct-level sources"));
+
+        let errors = validation_errors(&format!("{sources} struct S {{ id: u64 }}"));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("names the proto message it belongs to"));
+
+        let errors = validation_errors("struct S { #[protto(source = \"header\")] id: u64 }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("which this struct does not declare"));
+
+        let errors = validation_errors(
+            "#[protto(sources(header = \"proto::Header\"))] \
+            struct S { #[protto(source = \"header\")] id: u64 }",
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("two or more proto messages"));
+    }
+
+    #[test]
+    fn test_cfg_takes_one_predicate() {
+        assert!(
+            validation_errors("#[protto(cfg(feature = \"grpc\"))] struct S { id: u64 }").is_empty()
+        );
+        assert!(
+            validation_errors(
+                "#[protto(cfg = \"all(feature = \\\"grpc\\\", not(test))\")] enum E { A }"
+            )
+            .is_empty()
+        );
+
+        let errors =
+            validation_errors("#[protto(cfg(feature = \"a\", test))] struct S { id: u64 }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("cfg expects one cfg predicate"));
+
+        let errors = validation_errors("struct S { #[protto(cfg(test))] id: u64 }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("`cfg` is not supported on field"));
+    }
+
+    #[test]
+    fn test_transparent_struct_wraps_one_plain_field() {
+        assert!(
+            validation_errors("#[protto(transparent, proto_name = \"Uuid\")] struct Id(Uuid);")
+                .is_empty()
+        );
+
+        let errors = validation_errors("#[protto(transparent)] struct S { a: u64, b: u64 }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("requires exactly one field, found 2"));
+
+        let errors = validation_errors("#[protto(transparent, error_type = E)] struct Id(Uuid);");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("only takes module, proto_name and any_type_url"));
+
+        let errors = validation_errors("#[protto(transparent)] struct Id(#[protto(expect)] Uuid);");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("takes no protto attributes"));
+    }
+
+    #[test]
+    fn test_expect_forms_state_the_missing_value_policy() {
+        let meta = |attrs: &str| {
+            let field: syn::Field = syn::Field::parse_named
+                .parse_str(&format!("{attrs} count: u32"))
+                .unwrap();
+            ProtoFieldMeta::from_field(&field)
+        };
+
+        for attrs in [
+            "#[protto(expect)]",
+            "#[protto(expect(error))]",
+            "#[protto(expect = \"panic\")]",
+        ] {
+            let meta = meta(attrs).unwrap();
+            assert!(meta.expect, "{attrs}");
+            assert_eq!(meta.default_fn, None, "{attrs}");
+        }
+
+        let meta_default = meta("#[protto(expect = \"default\")]").unwrap();
+        assert!(!meta_default.expect);
+        assert_eq!(
+            meta_default.default_fn.as_deref(),
+            Some(constants::USE_DEFAULT_IMPL)
+        );
+        let meta_default_fn = meta("#[protto(expect(default = \"zero\"))]").unwrap();
+        assert_eq!(meta_default_fn.default_fn.as_deref(), Some("zero"));
+
+        let err = meta("#[protto(expect(pnic))]").unwrap_err();
+        assert!(err.contains("found `expect(pnic)`"));
+    }
+
+    #[test]
+    fn test_aliases_expand_to_their_keys() {
+        let meta = |attrs: &str| {
+            let field: syn::Field = syn::Field::parse_named
+                .parse_str(&format!("{attrs} count: u32"))
+                .unwrap();
+            ProtoFieldMeta::from_field(&field).unwrap()
+        };
+
+        let required = meta("#[protto(required)]");
+        assert!(required.expect);
+        assert_eq!(required.optionality, Some(FieldOptionality::Optional));
+        let optional = meta("#[protto(optional, proto_name = \"total\")]");
+        assert!(!optional.expect);
+        assert_eq!(optional.optionality, Some(FieldOptionality::Optional));
+
+        assert!(validation_errors("struct S { #[protto(required)] a: u64 }").is_empty());
+        let errors = validation_errors(
+            "#[protto(required)] struct S { #[protto(optional = true)] a: Option<u64> }",
+        );
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains(
+            "`required` (short for `proto_optional, expect(error)`) is not supported on struct"
+        ));
+        assert!(errors[1].contains("`optional` takes no value"));
+
+        let errors = validation_errors("struct S { #[protto(requird)] a: u64 }");
+        assert!(errors[0].contains("did you mean `required`?"));
+    }
+
+    #[test]
+    fn test_expect_conflicts_with_default() {
+        let errors = validation_errors(
+            "struct S { #[protto(expect(panic), default)] a: u64, \
+            #[protto(default_fn = \"zero\", expect = \"default\")] b: u64 }",
+        );
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("`expect(panic)` and `default` both set what a missing"));
+        assert!(errors[1].contains("`expect(default)` and `default_fn` both set what a missing"));
+    }
+
+    #[test]
+    fn test_only_one_none_policy() {
+        let field: syn::Field = syn::Field::parse_named
+            .parse_str("#[protto(proto_optional, none_as_empty)] nickname: Option<String>")
+            .unwrap();
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.none_as, Some(NonePolicy::Empty));
+
+        let errors = validation_errors(
+            "struct S { #[protto(none_as_unset, none_as = \"default\")] a: Option<u64>, \
+            #[protto(none_as_empty, none_as_unset)] b: Option<u64> }",
+        );
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|error| error.contains(
+            "Cannot specify more than one of 'none_as', 'none_as_empty' and 'none_as_unset'"
+        )));
+    }
+
+    #[test]
+    fn test_depends_on_lists_fields_for_a_custom_conversion() {
+        let field: syn::Field = syn::Field::parse_named
+            .parse_str(
+                "#[protto(from_proto_fn = \"to_amount\", depends_on = \"currency, scale\")] \
+                amount: Amount",
+            )
+            .unwrap();
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.depends_on, ["currency", "scale"]);
+
+        let errors = validation_errors(
+            "struct S { #[protto(depends_on = \"currency\")] a: Amount, \
+            #[protto(from_proto_fn = \"f\", depends_on = \"currency,\")] b: Amount }",
+        );
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("depends_on passes the converted fields"));
+        assert!(errors[1].contains("depends_on must list other fields of the struct"));
+    }
+
+    #[test]
+    fn test_value_keys_accept_nested_syntax() {
+        let field: syn::Field = syn::Field::parse_named
+            .parse_str("#[protto(proto_name(\"user_id\"), to_proto_fn(encode), none_as(\"panic\"))] id: Option<u64>")
+            .unwrap();
+        assert_eq!(get_proto_field_name(&field), Some("user_id".to_string()));
+
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.to_proto_fn.as_deref(), Some("encode"));
+        assert_eq!(meta.none_as, Some(NonePolicy::Panic));
+    }
+
+    #[test]
+    fn test_nested_keys_accept_value_syntax() {
+        let field: syn::Field = syn::Field::parse_named
+            .parse_str("#[protto(expect = \"panic\", group = \"street, zip = \\\"postal_code\\\"\")] address: Address")
+            .unwrap();
+        assert!(crate::analysis::expect_analysis::has_expect_panic_syntax(
+            &field
+        ));
+
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(
+            meta.group,
+            Some(vec![
+                ("street".to_string(), "street".to_string()),
+                ("zip".to_string(), "postal_code".to_string()),
+            ])
+        );
+        assert!(
+            validation_errors(
+                "struct S { #[protto(expect = \"panic\", group = \"street\")] address: Address }"
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_oneof_names_proto_field_and_shared_enums() {
+        let field: syn::Field = syn::Field::parse_named
+            .parse_str("#[protto(oneof = \"payload\")] body: Option<Payload>")
+            .unwrap();
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.oneof.as_deref(), Some("payload"));
+
+        let ast: syn::DeriveInput = syn::parse_str(
+            "#[protto(oneof = \"envelope::Payload, notice::Payload\")] enum Payload { Text(String) }",
+        )
+        .unwrap();
+        let oneofs: Vec<_> = get_enum_level_oneofs(&ast.attrs)
+            .iter()
+            .map(|path| quote!(#path).to_string())
+            .collect();
+        assert_eq!(oneofs, vec!["envelope :: Payload", "notice :: Payload"]);
+
+        let errors = validation_errors(
+            "struct S { #[protto(oneof = \"payload\", proto_name = \"other\")] body: Payload }",
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("Cannot specify both 'oneof' and 'proto_name'"));
+    }
+
+    #[test]
+    fn test_error_fn_with_value_excludes_error_fn() {
+        let field: syn::Field = syn::parse_quote! {
+            #[protto(expect, error_fn_with_value = "Rejected::value")]
+            pub state: bool
+        };
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.error_fn.as_deref(), Some("Rejected::value"));
+        assert!(meta.error_fn_with_value);
+
+        let errors = validation_errors(
+            "struct S { #[protto(error_fn = \"f\", error_fn_with_value = \"g\")] state: bool }",
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("Cannot specify both 'error_fn' and 'error_fn_with_value'"));
+    }
+
+    #[test]
+    fn test_proto_field_type_must_be_a_type() {
+        let field: syn::Field = syn::parse_quote! {
+            #[protto(proto_field_type = "crate::ids::AccountId")]
+            pub id: u64
+        };
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(
+            meta.proto_field_type.as_deref(),
+            Some("crate :: ids :: AccountId")
+        );
+
+        let errors =
+            validation_errors("struct S { #[protto(proto_field_type = \"not a type\")] id: u64 }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("proto_field_type must name the type prost generates"));
+    }
+
+    #[test]
+    fn test_kind_names_a_type_kind() {
+        let field: syn::Field = syn::parse_quote! {
+            #[protto(kind = "enum")]
+            pub status: other::Status
+        };
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.kind, Some(TypeKind::Enum));
+
+        let errors = validation_errors("struct S { #[protto(kind = \"struct\")] id: UserId }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("kind must be \"primitive\", \"message\" or \"enum\""));
+    }
+
+    #[test]
+    fn test_alias_of_must_be_a_type() {
+        let field: syn::Field = syn::parse_quote! {
+            #[protto(alias_of = "Vec<u64>")]
+            pub ids: UserIds
+        };
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.alias_of.as_deref(), Some("Vec < u64 >"));
+
+        let errors =
+            validation_errors("struct S { #[protto(alias_of = \"not a type\")] id: UserId }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("alias_of must name the type the field's type alias"));
+    }
+
+    #[test]
+    fn test_result_oneof_requires_ok_and_err() {
+        let field: syn::Field = syn::parse_quote! {
+            #[protto(result_oneof(ok = "user", err = "error", proto_enum = "reply::Outcome"))]
+            pub outcome: Result<User, ErrorInfo>
+        };
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(
+            meta.result_oneof,
+            Some(ResultOneofMapping {
+                proto_enum: Some("reply::Outcome".to_string()),
+                ok: "user".to_string(),
+                err: "error".to_string(),
+            })
+        );
+
+        let errors = validation_errors(
+            "struct S { #[protto(result_oneof(ok = \"user\"))] outcome: Result<User, E> }",
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("result_oneof requires ok and err"));
+    }
+
+    #[test]
+    fn test_enum_as_i32_policies() {
+        let field: syn::Field = syn::parse_quote! { #[protto(enum_as_i32)] pub status: Status };
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.enum_as_i32, Some(UnknownI32Policy::Panic));
+
+        let field: syn::Field =
+            syn::parse_quote! { #[protto(enum_as_i32 = "default")] pub status: Status };
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.enum_as_i32, Some(UnknownI32Policy::Default));
+
+        let errors =
+            validation_errors("struct S { #[protto(enum_as_i32 = \"ignore\")] status: Status }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("enum_as_i32 must be one of"));
+
+        let ast: syn::DeriveInput =
+            syn::parse_quote! { #[protto(enum_as_i32)] enum Status { Idle = 0 } };
+        assert!(validation_errors(&quote!(#ast).to_string()).is_empty());
+        assert!(has_enum_level_enum_as_i32(&ast.attrs));
+    }
+
+    #[test]
+    fn test_enum_values_policies() {
+        let field: syn::Field =
+            syn::parse_quote! { #[protto(enum_values)] pub states: HashMap<String, Status> };
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.enum_values, Some(UnknownI32Policy::Panic));
+
+        let field: syn::Field = syn::parse_quote! {
+            #[protto(enum_values = "error")] pub states: HashMap<String, Status>
+        };
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.enum_values, Some(UnknownI32Policy::Error));
+
+        let errors = validation_errors(
+            "struct S { #[protto(enum_values = \"skip\")] states: HashMap<String, Status> }",
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("enum_values must be one of"));
+    }
+
+    #[test]
+    fn test_enum_keys_policies() {
+        let field: syn::Field =
+            syn::parse_quote! { #[protto(enum_keys)] pub quotas: BTreeMap<Priority, u32> };
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.enum_keys, Some(UnknownI32Policy::Panic));
+
+        let field: syn::Field = syn::parse_quote! {
+            #[protto(enum_keys = "default")] pub quotas: BTreeMap<Priority, u32>
+        };
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.enum_keys, Some(UnknownI32Policy::Default));
+
+        let errors = validation_errors(
+            "struct S { #[protto(enum_keys = \"skip\")] quotas: BTreeMap<Priority, u32> }",
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("enum_keys must be one of"));
+    }
+
+    #[test]
+    fn test_empty_repeated_policies() {
+        let field: syn::Field = syn::parse_quote! {
+            #[protto(empty_repeated = "some_empty")] pub tags: Option<Vec<String>>
+        };
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.empty_repeated, Some(EmptyRepeated::SomeEmpty));
+
+        let field: syn::Field = syn::parse_quote! {
+            #[protto(empty_repeated = "none")] pub tags: Option<Vec<String>>
+        };
+        let meta = ProtoFieldMeta::from_field(&field).unwrap();
+        assert_eq!(meta.empty_repeated, Some(EmptyRepeated::None));
+
+        let errors =
+            validation_errors("struct S { #[protto(empty_repeated)] tags: Option<Vec<String>> }");
+        assert_eq!(errors.len(), 1);
+
+        let errors = validation_errors(
+            "struct S { #[protto(empty_repeated = \"empty\")] tags: Option<Vec<String>> }",
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("empty_repeated must be \"none\" or \"some_empty\""));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("expect", "expect"), 0);
+        assert_eq!(edit_distance("expcet", "expect"), 2);
+        assert_eq!(edit_distance("default", "default_fn"), 3);
+    }
+
+    fn error_configuration(input: &str) -> Result<(), String> {
+        let ast: syn::DeriveInput = syn::parse_str(input).expect("test input must parse");
+        let syn::Data::Struct(data) = &ast.data else {
+            panic!("test input must be a struct");
+        };
+        let syn::Fields::Named(fields) = &data.fields else {
+            panic!("test input must have named fields");
+        };
+        validate_error_configuration(
+            &get_proto_struct_error_type(&ast.attrs),
+            &get_struct_level_error_fn(&ast.attrs),
+            &fields.named,
+        )
+    }
+
+    #[test]
+    fn test_error_type_requires_an_error_fn_for_every_fallible_field() {
+        assert!(
+            error_configuration(
+                "#[protto(error_type = E, error_fn = \"E::missing\")] \
+                struct S { #[protto(expect)] id: u64, #[protto(enum_as_i32 = \"error\")] kind: Kind }"
+            )
+            .is_ok()
+        );
+        assert!(
+            error_configuration(
+                "#[protto(error_type = E)] struct S { \
+                #[protto(expect, error_fn = \"E::missing\")] id: u64, \
+                #[protto(expect(default))] name: String }"
+            )
+            .is_ok()
+        );
+
+        let error = error_configuration(
+            "#[protto(error_type = E)] struct S { \
+            #[protto(expect, error_fn = \"E::missing\")] id: u64, \
+            #[protto(enum_as_i32 = \"error\")] kind: Kind, \
+            #[protto(path_encoding = \"utf8_strict\")] path: PathBuf }",
+        )
+        .unwrap_err();
+        assert!(error.contains("Fields needing fallback: kind, path."));
+    }
+
+    #[test]
+    fn test_field_error_type_must_match_struct_error_type() {
+        assert!(
+            error_configuration(
+                "#[protto(error_type = E, error_fn = \"E::missing\")] \
+                struct S { #[protto(expect, error_type = E)] id: u64 }"
+            )
+            .is_ok()
+        );
+
+        let error = error_configuration("struct S { #[protto(expect, error_type = E)] id: u64 }")
+            .unwrap_err();
+        assert!(error.contains("Field 'id' sets 'error_type = E'"));
+        assert!(error.contains("#[protto(error_type = E)]"));
+
+        let error = error_configuration(
+            "#[protto(error_type = E, error_fn = \"E::missing\")] \
+            struct S { #[protto(expect, error_type = F)] id: u64 }",
+        )
+        .unwrap_err();
+        assert!(error.contains("conflicts with the struct-level 'error_type = E'"));
+    }
+}
@@ -0,0 +1,231 @@
+use crate::analysis::{
+    attribute_parser::{self, UnknownVariantPolicy},
+    expect_analysis::ExpectMode,
+    optionality::NonePolicy,
+    type_analysis::{
+        self, BoundedCollection, CapacityOverflow, DecimalAs, PathEncoding, UnknownI32Policy,
+        UnspecifiedPolicy,
+    },
+};
+
+/// Comprehensive analysis of error requirements for a struct
+pub struct ErrorRequirements {
+    pub needs_try_from: bool,
+    pub needs_try_into: bool,
+    pub needs_invalid_value: bool,
+    pub needs_default_error: bool,
+    pub needs_error_conversions: bool,
+}
+
+pub fn analyze_error_requirements(
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    struct_level_error_type: &Option<syn::Type>,
+) -> ErrorRequirements {
+    let needs_try_from = requires_try_from(fields);
+    let needs_try_into = requires_try_into(fields);
+    let needs_invalid_value = requires_invalid_value(fields);
+    let needs_default_error = requires_default_error_type(fields, struct_level_error_type);
+    let needs_error_conversions = (needs_try_from || needs_try_into)
+        && needs_default_error
+        && struct_level_error_type.is_none();
+
+    ErrorRequirements {
+        needs_try_from,
+        needs_try_into,
+        needs_invalid_value,
+        needs_default_error,
+        needs_error_conversions,
+    }
+}
+
+/// Analyzes fields to determine if TryFrom trait is needed
+fn requires_try_from(fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>) -> bool {
+    fields.iter().any(|field| {
+        if attribute_parser::has_proto_ignore(field) {
+            false
+        } else {
+            let proto_meta =
+                attribute_parser::ProtoFieldMeta::from_field(field).unwrap_or_default();
+            let expect_mode = ExpectMode::from_field_meta(field, &proto_meta);
+            matches!(expect_mode, ExpectMode::Error)
+                || has_fallible_proto_to_rust(&proto_meta)
+                || has_fallible_decimal(field, &proto_meta, None)
+                || has_fallible_duration(field, &proto_meta)
+                || has_fallible_with_str(field, &proto_meta)
+                || has_fallible_json(field, &proto_meta)
+                || has_fallible_from_proto_fn(field, &proto_meta)
+                || has_fallible_capacity(field, &proto_meta)
+        }
+    })
+}
+
+/// Analyzes fields to determine if the rust -> proto conversion must be fallible
+fn requires_try_into(fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>) -> bool {
+    fields.iter().any(|field| {
+        if attribute_parser::has_proto_ignore(field) {
+            false
+        } else {
+            let proto_meta =
+                attribute_parser::ProtoFieldMeta::from_field(field).unwrap_or_default();
+            has_fallible_rust_to_proto(&proto_meta)
+                || has_fallible_decimal(field, &proto_meta, Some(DecimalAs::UnitsNanos))
+                || has_fallible_duration(field, &proto_meta)
+                || has_fallible_json(field, &proto_meta)
+        }
+    })
+}
+
+/// Analyzes fields to determine if the default error type needs an `InvalidValue` variant
+fn requires_invalid_value(
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+) -> bool {
+    fields.iter().any(|field| {
+        if attribute_parser::has_proto_ignore(field) {
+            false
+        } else {
+            let proto_meta =
+                attribute_parser::ProtoFieldMeta::from_field(field).unwrap_or_default();
+            proto_meta.path_encoding == Some(PathEncoding::Utf8Strict)
+                || has_fallible_proto_to_rust(&proto_meta)
+                || has_fallible_decimal(field, &proto_meta, None)
+                || has_fallible_duration(field, &proto_meta)
+                || has_fallible_with_str(field, &proto_meta)
+                || has_fallible_json(field, &proto_meta)
+                || has_fallible_from_proto_fn(field, &proto_meta)
+                || has_fallible_capacity(field, &proto_meta)
+        }
+    })
+}
+
+/// Whether a field's conversion, in either direction, can fail with an error built through the
+/// `error_fn` cascade: the field's `error_fn`, else the struct's, else the generated error type.
+/// A `try_from_proto_fn` error is converted with `From` instead, so it is not counted.
+pub fn reports_field_errors(
+    field: &syn::Field,
+    proto_meta: &attribute_parser::ProtoFieldMeta,
+) -> bool {
+    matches!(
+        ExpectMode::from_field_meta(field, proto_meta),
+        ExpectMode::Error
+    ) || has_fallible_proto_to_rust(proto_meta)
+        || has_fallible_rust_to_proto(proto_meta)
+        || has_fallible_decimal(field, proto_meta, None)
+        || has_fallible_duration(field, proto_meta)
+        || has_fallible_with_str(field, proto_meta)
+        || has_fallible_json(field, proto_meta)
+        || has_fallible_capacity(field, proto_meta)
+}
+
+/// Field attributes, other than `expect`, whose proto -> rust conversion can fail
+fn has_fallible_proto_to_rust(proto_meta: &attribute_parser::ProtoFieldMeta) -> bool {
+    proto_meta
+        .bool_enum
+        .as_ref()
+        .is_some_and(|mapping| mapping.unknown == UnknownVariantPolicy::Error)
+        || proto_meta.enum_as_i32 == Some(UnknownI32Policy::Error)
+        || proto_meta.enum_values == Some(UnknownI32Policy::Error)
+        || proto_meta.enum_keys == Some(UnknownI32Policy::Error)
+        || proto_meta.unspecified == Some(UnspecifiedPolicy::Error)
+        || proto_meta.bytes_as.is_some()
+        || proto_meta.fallible
+}
+
+/// Whether a `with_str` field reports strings that fail to parse as errors rather than panicking
+/// under `expect(panic)`
+fn has_fallible_with_str(
+    field: &syn::Field,
+    proto_meta: &attribute_parser::ProtoFieldMeta,
+) -> bool {
+    proto_meta.with_str && ExpectMode::from_field_meta(field, proto_meta) != ExpectMode::Panic
+}
+
+/// Whether a `json` field reports JSON that fails to (de)serialize as errors, in both directions,
+/// rather than panicking under `expect(panic)`
+fn has_fallible_json(field: &syn::Field, proto_meta: &attribute_parser::ProtoFieldMeta) -> bool {
+    proto_meta.json && ExpectMode::from_field_meta(field, proto_meta) != ExpectMode::Panic
+}
+
+/// Field attributes whose rust -> proto conversion can fail
+fn has_fallible_rust_to_proto(proto_meta: &attribute_parser::ProtoFieldMeta) -> bool {
+    proto_meta.none_as == Some(NonePolicy::Error)
+        || proto_meta.path_encoding == Some(PathEncoding::Utf8Strict)
+}
+
+/// Whether a `decimal` field, restricted to the given encoding if any, reports invalid values as
+/// errors rather than panicking under `expect(panic)`
+fn has_fallible_decimal(
+    field: &syn::Field,
+    proto_meta: &attribute_parser::ProtoFieldMeta,
+    encoding: Option<DecimalAs>,
+) -> bool {
+    proto_meta
+        .decimal
+        .is_some_and(|decimal| encoding.is_none_or(|encoding| encoding == decimal))
+        && ExpectMode::from_field_meta(field, proto_meta) != ExpectMode::Panic
+}
+
+/// Whether a `duration` field reports out-of-range counts as errors, in either direction, rather
+/// than panicking under `expect(panic)`
+fn has_fallible_duration(
+    field: &syn::Field,
+    proto_meta: &attribute_parser::ProtoFieldMeta,
+) -> bool {
+    proto_meta.duration.is_some()
+        && ExpectMode::from_field_meta(field, proto_meta) != ExpectMode::Panic
+}
+
+/// Whether a `try_from_proto_fn` field propagates the function's error rather than panicking
+/// under `expect(panic)`
+fn has_fallible_from_proto_fn(
+    field: &syn::Field,
+    proto_meta: &attribute_parser::ProtoFieldMeta,
+) -> bool {
+    proto_meta.from_proto_fallible
+        && ExpectMode::from_field_meta(field, proto_meta) != ExpectMode::Panic
+}
+
+/// Whether an `ArrayVec` field fails the conversion when the proto has more elements than fit
+fn has_fallible_capacity(
+    field: &syn::Field,
+    proto_meta: &attribute_parser::ProtoFieldMeta,
+) -> bool {
+    cfg!(feature = "arrayvec")
+        && type_analysis::bounded_collection_type(&field.ty) == Some(BoundedCollection::ArrayVec)
+        && proto_meta.overflow.unwrap_or(CapacityOverflow::Error) == CapacityOverflow::Error
+}
+
+/// Analyzes fields to determine if default error type generation is needed
+fn requires_default_error_type(
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    struct_level_error_type: &Option<syn::Type>,
+) -> bool {
+    fields.iter().any(|field| {
+        if attribute_parser::has_proto_ignore(field) {
+            return false;
+        }
+        let proto_meta = attribute_parser::ProtoFieldMeta::from_field(field).unwrap_or_default();
+        if reports_field_errors(field, &proto_meta)
+            || has_fallible_from_proto_fn(field, &proto_meta)
+        {
+            let effective_error_type =
+                get_effective_error_type(&proto_meta, struct_level_error_type);
+            effective_error_type.is_none()
+        } else {
+            false
+        }
+    })
+}
+
+/// Determines the effective error type for a field
+fn get_effective_error_type(
+    proto_meta: &attribute_parser::ProtoFieldMeta,
+    struct_level_error_type: &Option<syn::Type>,
+) -> Option<syn::Type> {
+    if let Some(field_error_type) = &proto_meta.error_type {
+        return Some(
+            syn::parse_str(field_error_type).expect("Failed to parse field-level error_type"),
+        );
+    }
+
+    struct_level_error_type.clone()
+}
@@ -1,5 +1,4 @@
 use crate::analysis::attribute_parser;
-use crate::constants;
 use crate::debug::CallStackDebug;
 use syn::{Field, Meta};
 
@@ -47,15 +46,12 @@ impl ExpectMode {
 }
 
 pub fn has_expect_panic_syntax(field: &Field) -> bool {
-    for attr in &field.attrs {
-        if attr.path().is_ident(constants::PROTTO_ATTRIBUTE)
-            && let Meta::List(meta_list) = &attr.meta
-        {
-            let tokens_str = meta_list.tokens.to_string();
-            if tokens_str.replace(" ", "").contains("expect(panic)") {
-                return true;
-            }
-        }
-    }
-    false
+    attribute_parser::protto_metas(&field.attrs)
+        .map(|metas| {
+            metas.iter().any(|meta| {
+                matches!(meta, Meta::List(list)
+                    if list.path.is_ident("expect") && list.tokens.to_string() == "panic")
+            })
+        })
+        .unwrap_or(false)
 }
@@ -0,0 +1,157 @@
+//! Struct-level `extra_fields = "default"`: rust fields whose proto field the build metadata
+//! does not record are marked `#[protto(ignore)]`, so they are filled from `Default` in the
+//! proto -> rust conversion and left out of the proto message, like any ignored field.
+
+use crate::analysis::attribute_parser;
+use crate::analysis::proto_metadata::{self, ProtoMetadata};
+use quote::quote;
+
+/// Marks the rust fields missing from the targeted proto message as ignored, returning the
+/// rewritten input and an associated `EXTRA_FIELDS` constant listing them.
+pub fn mark_extra_fields(
+    ast: syn::DeriveInput,
+    proto_module: &str,
+    proto_name: &str,
+) -> syn::Result<(syn::DeriveInput, proc_macro2::TokenStream)> {
+    let Some(metadata) = proto_metadata::load() else {
+        return Err(syn::Error::new_spanned(
+            &ast.ident,
+            "extra_fields = \"default\" reads the proto schema metadata; call \
+             protto::build::generate_metadata from the build script",
+        ));
+    };
+    mark_from_metadata(ast, &metadata, proto_module, proto_name)
+}
+
+fn mark_from_metadata(
+    mut ast: syn::DeriveInput,
+    metadata: &ProtoMetadata,
+    proto_module: &str,
+    proto_name: &str,
+) -> syn::Result<(syn::DeriveInput, proc_macro2::TokenStream)> {
+    let Some(schema) = metadata.message_schema(proto_module, proto_name) else {
+        return Err(syn::Error::new_spanned(
+            &ast.ident,
+            format!("extra_fields: message `{proto_name}` is not in the proto metadata"),
+        ));
+    };
+
+    let syn::Data::Struct(data_struct) = &mut ast.data else {
+        return Ok((ast, quote! {}));
+    };
+
+    let mut extra_fields = Vec::new();
+    for field in data_struct.fields.iter_mut() {
+        let Some(field_ident) = &field.ident else {
+            continue;
+        };
+        if attribute_parser::has_proto_ignore(field) {
+            continue;
+        }
+        let proto_meta = attribute_parser::ProtoFieldMeta::from_field(field).unwrap_or_default();
+        if proto_meta.group.is_some() {
+            continue;
+        }
+
+        let proto_field = attribute_parser::get_proto_field_name(field)
+            .or(proto_meta.oneof)
+            .unwrap_or_else(|| crate::utils::ident_name(field_ident));
+        let prost_field = metadata
+            .prost_field_ident(proto_module, proto_name, &proto_field)
+            .unwrap_or(proto_field);
+        let prost_field = prost_field.trim_start_matches("r#");
+        if schema.fields.contains_key(prost_field)
+            || schema.oneofs.iter().any(|oneof| oneof == prost_field)
+        {
+            continue;
+        }
+
+        extra_fields.push(crate::utils::ident_name(field_ident));
+        field.attrs.push(syn::parse_quote! { #[protto(ignore)] });
+    }
+
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let doc = format!(
+        "Rust fields absent from the proto message `{}`, filled from `Default` when converting \
+         from proto and left out when converting to proto.",
+        schema.qualified_name
+    );
+    let note = quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc = #doc]
+            pub const EXTRA_FIELDS: &'static [&'static str] = &[#(#extra_fields),*];
+        }
+    };
+
+    Ok((ast, note))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METADATA: &str = "\
+message shop.Profile
+field id required scalar uint64
+field type required scalar string
+rename type r#type
+oneof contact
+";
+
+    fn mark(input: syn::DeriveInput) -> syn::Result<(Vec<String>, String)> {
+        let metadata = ProtoMetadata::parse(METADATA);
+        let (ast, note) = mark_from_metadata(input, &metadata, "proto", "Profile")?;
+        let syn::Data::Struct(data_struct) = &ast.data else {
+            panic!("expected struct");
+        };
+        let ignored = data_struct
+            .fields
+            .iter()
+            .filter(|field| attribute_parser::has_proto_ignore(field))
+            .map(|field| field.ident.as_ref().unwrap().to_string())
+            .collect();
+        Ok((ignored, note.to_string()))
+    }
+
+    #[test]
+    fn test_fields_missing_from_proto_are_ignored() {
+        let (ignored, note) = mark(syn::parse_quote! {
+            struct Profile {
+                id: u64,
+                r#type: String,
+                #[protto(oneof = "contact")]
+                reach: Option<Contact>,
+                #[protto(proto_name = "id")]
+                key: u64,
+                cache: Vec<u8>,
+                #[protto(ignore)]
+                seen: bool,
+            }
+        })
+        .unwrap();
+        assert_eq!(ignored, ["cache", "seen"]);
+        assert!(
+            note.contains("pub const EXTRA_FIELDS : & 'static [& 'static str] = & [\"cache\"]")
+        );
+        assert!(note.contains("`shop.Profile`"));
+    }
+
+    #[test]
+    fn test_unknown_message_is_an_error() {
+        let metadata = ProtoMetadata::parse(METADATA);
+        let Err(err) = mark_from_metadata(
+            syn::parse_quote! { struct Missing { id: u64 } },
+            &metadata,
+            "proto",
+            "Missing",
+        ) else {
+            panic!("expected an unknown message error");
+        };
+        assert!(
+            err.to_string()
+                .contains("`Missing` is not in the proto metadata")
+        );
+    }
+}
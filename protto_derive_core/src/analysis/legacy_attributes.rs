@@ -0,0 +1,246 @@
+//! Accepts the `#[proto(...)]` attributes of `proto_convert_derive` behind the `proto-compat`
+//! feature, so codebases can move to protto one type at a time.
+//!
+//! Legacy attributes are rewritten to `#[protto(...)]` before any analysis runs, and each one
+//! produces a deprecation warning spelling out the exact replacement.
+
+use crate::constants;
+use proc_macro2::Span;
+use quote::{quote, quote_spanned};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::token::Comma;
+use syn::{Attribute, DeriveInput, Meta};
+
+const LEGACY_ATTRIBUTE: &str = "proto";
+
+/// Keys whose names changed from `proto_convert_derive`; every other key carries over as is
+const RENAMED_KEYS: &[(&str, &str)] = &[
+    ("rename", "proto_name"),
+    ("derive_from_with", "from_proto_fn"),
+    ("derive_into_with", "to_proto_fn"),
+];
+
+/// A rewritten legacy attribute, kept to warn at its original location
+pub struct LegacyAttribute {
+    pub span: Span,
+    pub original: String,
+    pub replacement: String,
+}
+
+/// Rewrite every `#[proto(...)]` on the input, its fields and its variants to `#[protto(...)]`
+pub fn migrate(mut ast: DeriveInput) -> syn::Result<(DeriveInput, Vec<LegacyAttribute>)> {
+    let mut legacy = Vec::new();
+    migrate_attrs(&mut ast.attrs, &mut legacy)?;
+
+    match &mut ast.data {
+        syn::Data::Struct(data_struct) => {
+            for field in data_struct.fields.iter_mut() {
+                migrate_attrs(&mut field.attrs, &mut legacy)?;
+            }
+        }
+        syn::Data::Enum(data_enum) => {
+            for variant in data_enum.variants.iter_mut() {
+                migrate_attrs(&mut variant.attrs, &mut legacy)?;
+                for field in variant.fields.iter_mut() {
+                    migrate_attrs(&mut field.attrs, &mut legacy)?;
+                }
+            }
+        }
+        syn::Data::Union(_) => {}
+    }
+
+    Ok((ast, legacy))
+}
+
+/// One deprecated item use per legacy attribute; stable proc macros have no other way to warn
+pub fn deprecation_warnings(legacy: &[LegacyAttribute]) -> proc_macro2::TokenStream {
+    let warnings = legacy.iter().map(|attr| {
+        let note = format!(
+            "`{}` is deprecated; replace it with `{}`",
+            attr.original, attr.replacement
+        );
+        quote_spanned! { attr.span=>
+            const _: () = {
+                #[deprecated(note = #note)]
+                struct ProtoAttribute;
+                let _ = ProtoAttribute;
+            };
+        }
+    });
+
+    quote! { #(#warnings)* }
+}
+
+fn migrate_attrs(attrs: &mut [Attribute], legacy: &mut Vec<LegacyAttribute>) -> syn::Result<()> {
+    for attr in attrs
+        .iter_mut()
+        .filter(|attr| attr.path().is_ident(LEGACY_ATTRIBUTE))
+    {
+        let Meta::List(meta_list) = &attr.meta else {
+            return Err(syn::Error::new_spanned(
+                &*attr,
+                format!("expected #[{LEGACY_ATTRIBUTE}(...)]"),
+            ));
+        };
+        let mut metas: Punctuated<Meta, Comma> =
+            Punctuated::parse_terminated.parse2(meta_list.tokens.clone())?;
+        let original = render_attribute(&meta_list.path, &metas);
+        metas.iter_mut().for_each(rename_key);
+
+        let span = attr.span();
+        let protto = syn::Ident::new(constants::PROTTO_ATTRIBUTE, meta_list.path.span());
+        let replacement = render_attribute(&protto.clone().into(), &metas);
+        *attr = syn::parse_quote_spanned! { span=> #[#protto(#metas)] };
+
+        legacy.push(LegacyAttribute {
+            span,
+            original,
+            replacement,
+        });
+    }
+    Ok(())
+}
+
+fn rename_key(meta: &mut Meta) {
+    let path = match meta {
+        Meta::Path(path) => path,
+        Meta::List(list) => &mut list.path,
+        Meta::NameValue(nv) => &mut nv.path,
+    };
+    let Some(ident) = path.get_ident() else {
+        return;
+    };
+    if let Some((_, renamed)) = RENAMED_KEYS.iter().find(|(old, _)| ident == old) {
+        *path = syn::Ident::new(renamed, ident.span()).into();
+    }
+}
+
+/// Attribute source text as a user would write it, built from its metas rather than from the
+/// spacing of `to_string`, so literal values appear exactly as written
+fn render_attribute(path: &syn::Path, metas: &Punctuated<Meta, Comma>) -> String {
+    let metas: Vec<_> = metas.iter().map(render_meta).collect();
+    format!("#[{}({})]", render_path(path), metas.join(", "))
+}
+
+fn render_meta(meta: &Meta) -> String {
+    match meta {
+        Meta::Path(path) => render_path(path),
+        Meta::List(list) => {
+            let nested = match list.parse_args_with(Punctuated::<Meta, Comma>::parse_terminated) {
+                Ok(metas) => metas.iter().map(render_meta).collect::<Vec<_>>().join(", "),
+                Err(_) => list.tokens.to_string(),
+            };
+            format!("{}({nested})", render_path(&list.path))
+        }
+        Meta::NameValue(nv) => {
+            let value = match &nv.value {
+                syn::Expr::Path(expr_path) if expr_path.qself.is_none() => {
+                    render_path(&expr_path.path)
+                }
+                value => quote!(#value).to_string(),
+            };
+            format!("{} = {value}", render_path(&nv.path))
+        }
+    }
+}
+
+fn render_path(path: &syn::Path) -> String {
+    let segments: Vec<_> = path
+        .segments
+        .iter()
+        .map(|segment| match &segment.arguments {
+            syn::PathArguments::None => segment.ident.to_string(),
+            arguments => format!("{}{}", segment.ident, quote!(#arguments)),
+        })
+        .collect();
+    let leading = if path.leading_colon.is_some() {
+        "::"
+    } else {
+        ""
+    };
+    format!("{leading}{}", segments.join("::"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_attributes_are_rewritten_with_renamed_keys() {
+        let ast: DeriveInput = syn::parse_str(
+            "#[proto(module = \"proto\", rename = \"State\")] struct S { \
+                #[proto(derive_from_with = \"from_map\", derive_into_with = \"to_map\")] tracks: Tracks, \
+                #[proto(expect(panic))] header: Header, \
+                #[protto(ignore)] cache: Cache }",
+        )
+        .unwrap();
+
+        let Ok((ast, legacy)) = migrate(ast) else {
+            panic!("legacy attributes must migrate");
+        };
+        let replacements: Vec<_> = legacy.iter().map(|l| l.replacement.as_str()).collect();
+        assert_eq!(
+            replacements,
+            vec![
+                "#[protto(module = \"proto\", proto_name = \"State\")]",
+                "#[protto(from_proto_fn = \"from_map\", to_proto_fn = \"to_map\")]",
+                "#[protto(expect(panic))]",
+            ]
+        );
+        assert_eq!(
+            legacy[0].original,
+            "#[proto(module = \"proto\", rename = \"State\")]"
+        );
+
+        assert!(
+            ast.attrs
+                .iter()
+                .all(|attr| attr.path().is_ident(constants::PROTTO_ATTRIBUTE))
+        );
+        assert!(crate::analysis::attribute_parser::validate_attributes(&ast).is_ok());
+    }
+
+    #[test]
+    fn test_legacy_keys_inside_string_literals_are_left_as_written() {
+        let ast: DeriveInput = syn::parse_str(
+            "struct S { \
+                #[proto(rename = \"rename ( derive_from_with )\", derive_into_with = \"crate :: rename\")] \
+                tracks: Tracks }",
+        )
+        .unwrap();
+
+        let Ok((ast, legacy)) = migrate(ast) else {
+            panic!("legacy attributes must migrate");
+        };
+        assert_eq!(
+            legacy[0].original,
+            "#[proto(rename = \"rename ( derive_from_with )\", \
+            derive_into_with = \"crate :: rename\")]"
+        );
+        assert_eq!(
+            legacy[0].replacement,
+            "#[protto(proto_name = \"rename ( derive_from_with )\", \
+            to_proto_fn = \"crate :: rename\")]"
+        );
+
+        let syn::Data::Struct(data_struct) = &ast.data else {
+            panic!("expected struct");
+        };
+        let field = data_struct.fields.iter().next().unwrap();
+        assert_eq!(
+            crate::analysis::attribute_parser::get_proto_field_name(field).as_deref(),
+            Some("rename ( derive_from_with )")
+        );
+    }
+
+    #[test]
+    fn test_non_list_legacy_attribute_is_rejected() {
+        let ast: DeriveInput = syn::parse_str("#[proto] struct S { id: u64 }").unwrap();
+        let Err(error) = migrate(ast) else {
+            panic!("a bare #[proto] must not migrate");
+        };
+        assert!(error.to_string().contains("expected #[proto(...)]"));
+    }
+}
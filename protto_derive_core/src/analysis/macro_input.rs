@@ -0,0 +1,262 @@
+use crate::analysis::attribute_parser::ProtoOnlyField;
+use crate::analysis::{attribute_parser, proto_metadata};
+use crate::constants;
+use quote::quote;
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+/// A further proto message named by `also_proto` or `sources`, split like the primary one into
+/// its module and message name
+pub struct ProtoTarget {
+    pub module: String,
+    pub name: String,
+    pub path: syn::Path,
+}
+
+impl ProtoTarget {
+    fn new(path: syn::Path) -> Self {
+        let mut segments: Vec<String> = path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect();
+        let name = segments
+            .pop()
+            .expect("also_proto and sources paths include a module");
+        let leading = if path.leading_colon.is_some() {
+            "::"
+        } else {
+            ""
+        };
+        let module = format!("{leading}{}", segments.join("::"));
+        Self { module, name, path }
+    }
+}
+
+pub struct ParsedInput {
+    pub name: syn::Ident,
+    pub proto_module: String,
+    pub proto_name: String,
+    pub struct_level_error_type: Option<syn::Type>,
+    pub struct_level_error_fn: Option<String>,
+    pub struct_level_error_fn_with_value: bool,
+    pub proto_ignored_fields: HashSet<String>,
+    pub proto_only: Vec<ProtoOnlyField>,
+    pub proto_path: syn::Path,
+    pub generics: syn::Generics,
+    pub resolver_type: Option<syn::Type>,
+    pub error_mod: Option<syn::Ident>,
+    pub static_errors: bool,
+    pub presence: bool,
+    pub field_paths: bool,
+    pub partial: bool,
+    pub proto_parts: bool,
+    pub view: bool,
+    pub validate: bool,
+    pub debug_roundtrip: bool,
+    pub passthrough: bool,
+    pub arena: bool,
+    pub estimated_size: bool,
+    pub pure_custom_fns: bool,
+    pub both_conversions: bool,
+    pub transparent: bool,
+    pub any_type_url: Option<String>,
+    pub also_proto: Vec<ProtoTarget>,
+    /// The named proto messages of struct-level `sources`, in tuple order
+    pub sources: Vec<(syn::Ident, ProtoTarget)>,
+    pub vis: syn::Visibility,
+    pub error_vis: syn::Visibility,
+}
+
+impl Debug for ParsedInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let error_type = self
+            .struct_level_error_type
+            .as_ref()
+            .map(|error_type| quote! { #error_type })
+            .map(|error_type| error_type.to_string())
+            .unwrap_or_default();
+
+        let proto_path = &self.proto_path;
+        let proto_path = quote! { #proto_path };
+        let proto_path = proto_path.to_string();
+
+        let generics = &self.generics;
+        let generics = quote! { #generics }.to_string();
+
+        let resolver_type = self
+            .resolver_type
+            .as_ref()
+            .map(|resolver_type| quote! { #resolver_type }.to_string())
+            .unwrap_or_default();
+
+        let vis = &self.vis;
+        let vis = quote! { #vis }.to_string();
+        let error_vis = &self.error_vis;
+        let error_vis = quote! { #error_vis }.to_string();
+
+        f.debug_struct("ParsedInput")
+            .field("name", &self.name)
+            .field("proto_module", &self.proto_module)
+            .field("proto_name", &self.proto_name)
+            .field("struct_level_error_type", &error_type)
+            .field("struct_level_error_fn", &self.struct_level_error_fn)
+            .field(
+                "struct_level_error_fn_with_value",
+                &self.struct_level_error_fn_with_value,
+            )
+            .field("proto_ignored_fields", &self.proto_ignored_fields)
+            .field("proto_only", &self.proto_only)
+            .field("proto_path", &proto_path)
+            .field("generics", &generics)
+            .field("resolver_type", &resolver_type)
+            .field("error_mod", &self.error_mod)
+            .field("static_errors", &self.static_errors)
+            .field("presence", &self.presence)
+            .field("field_paths", &self.field_paths)
+            .field("partial", &self.partial)
+            .field("proto_parts", &self.proto_parts)
+            .field("view", &self.view)
+            .field("validate", &self.validate)
+            .field("debug_roundtrip", &self.debug_roundtrip)
+            .field("passthrough", &self.passthrough)
+            .field("arena", &self.arena)
+            .field("estimated_size", &self.estimated_size)
+            .field("pure_custom_fns", &self.pure_custom_fns)
+            .field("both_conversions", &self.both_conversions)
+            .field("transparent", &self.transparent)
+            .field("any_type_url", &self.any_type_url)
+            .field(
+                "also_proto",
+                &self
+                    .also_proto
+                    .iter()
+                    .map(|target| format!("{}::{}", target.module, target.name))
+                    .collect::<Vec<_>>(),
+            )
+            .field(
+                "sources",
+                &self
+                    .sources
+                    .iter()
+                    .map(|(name, target)| format!("{name} = {}::{}", target.module, target.name))
+                    .collect::<Vec<_>>(),
+            )
+            .field("vis", &vis)
+            .field("error_vis", &error_vis)
+            .finish()
+    }
+}
+
+impl ParsedInput {
+    pub fn new(ast: syn::DeriveInput) -> ParsedInput {
+        let proto_module = attribute_parser::get_proto_module(&ast.attrs)
+            .unwrap_or_else(|| constants::DEFAULT_PROTO_MODULE.to_string());
+        let proto_name = attribute_parser::get_proto_struct_name(&ast.attrs)
+            .unwrap_or_else(|| ast.ident.to_string());
+        let proto_module = proto_metadata::resolve_module(&proto_module, &proto_name);
+        let struct_level_error_type = attribute_parser::get_proto_struct_error_type(&ast.attrs);
+        let struct_level_error_fn = attribute_parser::get_struct_level_error_fn(&ast.attrs);
+        let struct_level_error_fn_with_value =
+            attribute_parser::has_struct_level_error_fn_with_value(&ast.attrs);
+        if let Err(msg) = attribute_parser::validate_error_configuration(
+            &struct_level_error_type,
+            &struct_level_error_fn,
+            &Self::fields_from(&ast),
+        ) {
+            panic!("Invalid error configuration: {msg}");
+        }
+
+        let proto_ignored_fields = attribute_parser::get_struct_level_proto_ignore(&ast.attrs);
+        let proto_only = attribute_parser::get_struct_level_proto_only(&ast.attrs)
+            .expect("proto_only is checked by validate_attributes");
+        let resolver_type = attribute_parser::get_struct_level_resolver(&ast.attrs);
+        let error_mod = attribute_parser::get_struct_level_error_mod(&ast.attrs);
+        let static_errors = attribute_parser::has_struct_level_static_errors(&ast.attrs);
+        let presence = attribute_parser::has_struct_level_presence(&ast.attrs);
+        let field_paths = attribute_parser::has_struct_level_field_paths(&ast.attrs);
+        let partial = attribute_parser::has_struct_level_partial(&ast.attrs);
+        let proto_parts = attribute_parser::has_struct_level_proto_parts(&ast.attrs);
+        let view = attribute_parser::has_struct_level_view(&ast.attrs);
+        let validate = attribute_parser::has_struct_level_validate(&ast.attrs);
+        let debug_roundtrip = attribute_parser::has_struct_level_debug_roundtrip(&ast.attrs);
+        let passthrough = attribute_parser::has_struct_level_passthrough(&ast.attrs);
+        let arena = attribute_parser::has_struct_level_arena(&ast.attrs);
+        let estimated_size = attribute_parser::has_estimated_size(&ast.attrs);
+        let pure_custom_fns = attribute_parser::has_struct_level_pure_custom_fns(&ast.attrs);
+        let both_conversions = attribute_parser::has_struct_level_both_conversions(&ast.attrs);
+        let transparent = attribute_parser::has_struct_level_transparent(&ast.attrs);
+        let any_type_url = attribute_parser::get_struct_level_any_type_url(&ast.attrs);
+        let also_proto = attribute_parser::get_struct_level_also_proto(&ast.attrs)
+            .into_iter()
+            .map(ProtoTarget::new)
+            .collect();
+        let sources = attribute_parser::get_struct_level_sources(&ast.attrs)
+            .expect("sources is checked by validate_attributes")
+            .into_iter()
+            .map(|(name, path)| (name, ProtoTarget::new(path)))
+            .collect();
+        let error_vis = attribute_parser::get_struct_level_error_vis(&ast.attrs)
+            .unwrap_or_else(|| ast.vis.clone());
+        let proto_path = syn::parse_str::<syn::Path>(&format!("{}::{}", proto_module, proto_name))
+            .expect("Failed to create proto path");
+
+        ParsedInput {
+            name: ast.ident,
+            proto_module,
+            proto_name,
+            struct_level_error_type,
+            struct_level_error_fn,
+            struct_level_error_fn_with_value,
+            proto_ignored_fields,
+            proto_only,
+            proto_path,
+            generics: ast.generics,
+            resolver_type,
+            error_mod,
+            static_errors,
+            presence,
+            field_paths,
+            partial,
+            proto_parts,
+            view,
+            validate,
+            debug_roundtrip,
+            passthrough,
+            arena,
+            estimated_size,
+            pure_custom_fns,
+            both_conversions,
+            transparent,
+            any_type_url,
+            also_proto,
+            sources,
+            vis: ast.vis,
+            error_vis,
+        }
+    }
+
+    fn fields_from(
+        ast: &syn::DeriveInput,
+    ) -> syn::punctuated::Punctuated<syn::Field, syn::token::Comma> {
+        match &ast.data {
+            syn::Data::Struct(data_struct) => {
+                match &data_struct.fields {
+                    syn::Fields::Named(fields_named) => fields_named.named.clone(),
+                    syn::Fields::Unnamed(_) => {
+                        // Tuple structs - validation may not apply the same way
+                        syn::punctuated::Punctuated::new()
+                    }
+                    syn::Fields::Unit => {
+                        // Unit structs have no fields
+                        syn::punctuated::Punctuated::new()
+                    }
+                }
+            }
+            _ => {
+                // Enums don't have fields in the same sense, skip validation
+                syn::punctuated::Punctuated::new()
+            }
+        }
+    }
+}
@@ -0,0 +1,12 @@
+pub mod attribute_parser;
+pub mod error_analysis;
+pub mod expect_analysis;
+pub mod extra_fields;
+#[cfg(feature = "proto-compat")]
+pub mod legacy_attributes;
+pub mod macro_input;
+pub mod optionality;
+pub mod proto_metadata;
+pub mod type_aliases;
+pub mod type_analysis;
+pub mod workspace_defaults;
@@ -103,13 +103,13 @@ impl FieldOptionality {
         }
 
         // Pattern: Primitives -> required
-        if type_analysis::is_primitive_type(field_type) {
+        if ctx.is_primitive_type(field_type) {
             _trace.decision("is_primitive", "Primitive -> required proto field");
             return Some(Self::Required);
         }
 
         // Pattern: Enums -> required (like primitives)
-        if type_analysis::is_enum_type(field_type) {
+        if ctx.is_enum_type(field_type) {
             _trace.decision("is_enum", "Enum -> required proto field");
             return Some(Self::Required);
         }
@@ -190,7 +190,7 @@ impl FieldOptionality {
 
             // Single-segment types that aren't primitives or std types
             if segments.len() == 1 {
-                let is_primitive = type_analysis::is_primitive_type(field_type);
+                let is_primitive = ctx.is_primitive_type(field_type);
                 let is_std_type = Self::is_std_type(field_type);
                 let is_proto_type = type_analysis::is_proto_type(field_type, ctx.proto_module);
 
@@ -338,3 +338,47 @@ impl FieldOptionality {
         matches!(self, Self::Required)
     }
 }
+
+/// How a rust `None` is written into a required proto field, set via `none_as`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NonePolicy {
+    /// Write the proto field's `Default::default()`
+    Default,
+
+    /// Fail the rust -> proto conversion, which becomes `TryFrom` instead of `Into`
+    Error,
+
+    /// Panic with a descriptive message
+    Panic,
+
+    /// Write the proto field's `Default::default()`, also into an optional proto field, which
+    /// is then set rather than left unset; set via `none_as_empty`
+    Empty,
+
+    /// Leave an optional proto field unset, as a plain `Option` mapping does; set via
+    /// `none_as_unset`, which a required proto field cannot take
+    Unset,
+}
+
+impl std::fmt::Display for NonePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::Error => write!(f, "error"),
+            Self::Panic => write!(f, "panic"),
+            Self::Empty => write!(f, "empty"),
+            Self::Unset => write!(f, "unset"),
+        }
+    }
+}
+
+impl NonePolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "default" => Some(Self::Default),
+            "error" => Some(Self::Error),
+            "panic" => Some(Self::Panic),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,925 @@
+//! Reads the proto schema metadata written by `protto::build::generate_metadata`.
+//!
+//! When metadata is available, the shape prost generates for a proto field (plain, `Option`,
+//! `Vec` or map) is known exactly instead of inferred from the Rust field. The file is located
+//! through the `PROTTO_METADATA` environment variable, falling back to `protto_metadata.txt` in
+//! the compiling crate's `OUT_DIR`. Messages read from `#[protto_schema]` modules are added to
+//! whatever the files record.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+const METADATA_ENV: &str = "PROTTO_METADATA";
+const METADATA_FILE_NAME: &str = "protto_metadata.txt";
+
+/// Shape of the field on the prost-generated message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SchemaShape {
+    Required,
+    Optional,
+    Repeated,
+    Map,
+}
+
+/// What the proto field's type refers to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SchemaKind {
+    Scalar,
+    Enum,
+    Message,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SchemaField {
+    pub shape: SchemaShape,
+    pub kind: SchemaKind,
+    pub type_name: String,
+    /// prost generates the field as `Option<Box<T>>`
+    pub boxed: bool,
+}
+
+/// `google.protobuf` wrapper messages, which hold an optional scalar in a single `value` field.
+const WRAPPER_TYPES: &[&str] = &[
+    "DoubleValue",
+    "FloatValue",
+    "Int64Value",
+    "UInt64Value",
+    "Int32Value",
+    "UInt32Value",
+    "BoolValue",
+    "StringValue",
+    "BytesValue",
+];
+
+impl SchemaField {
+    /// The field holds a wrapper message such as `google.protobuf.UInt64Value`, matched on the
+    /// message name so vendored copies of `wrappers.proto` count too.
+    pub fn is_wrapper(&self) -> bool {
+        self.kind == SchemaKind::Message
+            && self.shape == SchemaShape::Optional
+            && WRAPPER_TYPES.contains(&self.type_name.rsplit('.').next().unwrap_or_default())
+    }
+}
+
+/// Fields of each proto message, keyed by the package-qualified message name.
+#[derive(Debug, Default, Clone)]
+pub struct ProtoMetadata {
+    messages: HashMap<String, HashMap<String, SchemaField>>,
+    /// Proto field names prost renames, mapped to the identifier it generates, per message.
+    renames: HashMap<String, HashMap<String, String>>,
+    /// Fields prost generates for `oneof` blocks, per message.
+    oneofs: HashMap<String, Vec<String>>,
+    /// Package-qualified enum names.
+    enums: Vec<String>,
+    /// The names and numbers each enum declares, by package-qualified enum name.
+    enum_values: HashMap<String, Vec<(String, i32)>>,
+    /// The Rust module path prost generates for each package.
+    packages: HashMap<String, String>,
+    /// Each package is nested in its own modules under the derive's `module`.
+    nested: bool,
+    /// Declarations the merged files disagree on; see [`Self::merge`].
+    conflicts: Vec<String>,
+}
+
+/// Everything the metadata records about one message.
+#[derive(Debug, Clone, Copy)]
+pub struct MessageSchema<'a> {
+    pub qualified_name: &'a str,
+    pub fields: &'a HashMap<String, SchemaField>,
+    pub oneofs: &'a [String],
+}
+
+impl ProtoMetadata {
+    /// Parses the line format rendered by `protto::build::render_metadata`; unrecognized lines
+    /// are ignored.
+    pub fn parse(content: &str) -> Self {
+        let mut messages: HashMap<String, HashMap<String, SchemaField>> = HashMap::new();
+        let mut renames: HashMap<String, HashMap<String, String>> = HashMap::new();
+        let mut oneofs: HashMap<String, Vec<String>> = HashMap::new();
+        let mut enums = Vec::new();
+        let mut enum_values: HashMap<String, Vec<(String, i32)>> = HashMap::new();
+        let mut packages = HashMap::new();
+        let mut nested = false;
+        let mut current = None;
+        let mut current_enum: Option<String> = None;
+
+        for line in content.lines() {
+            let mut parts = line.split_whitespace();
+            match parts.next() {
+                Some("message") => {
+                    current_enum = None;
+                    current = parts.next().map(str::to_string);
+                    if let Some(name) = &current {
+                        messages.entry(name.clone()).or_default();
+                    }
+                }
+                Some("field") => {
+                    let (Some(message), Some(name), Some(shape), Some(kind), Some(type_name)) = (
+                        &current,
+                        parts.next(),
+                        parts.next(),
+                        parts.next(),
+                        parts.next(),
+                    ) else {
+                        continue;
+                    };
+                    let shape = match shape {
+                        "required" => SchemaShape::Required,
+                        "optional" => SchemaShape::Optional,
+                        "repeated" => SchemaShape::Repeated,
+                        "map" => SchemaShape::Map,
+                        _ => continue,
+                    };
+                    let kind = match kind {
+                        "scalar" => SchemaKind::Scalar,
+                        "enum" => SchemaKind::Enum,
+                        "message" => SchemaKind::Message,
+                        _ => continue,
+                    };
+                    messages.entry(message.clone()).or_default().insert(
+                        name.to_string(),
+                        SchemaField {
+                            shape,
+                            kind,
+                            type_name: type_name.to_string(),
+                            boxed: parts.next() == Some("boxed"),
+                        },
+                    );
+                }
+                Some("rename") => {
+                    if let (Some(message), Some(proto_name), Some(prost_ident)) =
+                        (&current, parts.next(), parts.next())
+                    {
+                        renames
+                            .entry(message.clone())
+                            .or_default()
+                            .insert(proto_name.to_string(), prost_ident.to_string());
+                    }
+                }
+                Some("oneof") => {
+                    if let (Some(message), Some(ident)) = (&current, parts.next()) {
+                        oneofs
+                            .entry(message.clone())
+                            .or_default()
+                            .push(ident.to_string());
+                    }
+                }
+                Some("layout") => nested = parts.next() == Some("nested"),
+                Some("package") => {
+                    if let (Some(package), Some(module)) = (parts.next(), parts.next()) {
+                        packages.insert(package.to_string(), module.to_string());
+                    }
+                }
+                Some("enum") => {
+                    current_enum = parts.next().map(str::to_string);
+                    enums.extend(current_enum.clone());
+                }
+                Some("value") => {
+                    let (Some(name), Some(enum_name), Some(number)) = (
+                        parts.next(),
+                        &current_enum,
+                        parts.next().and_then(|number| number.parse().ok()),
+                    ) else {
+                        continue;
+                    };
+                    enum_values
+                        .entry(enum_name.clone())
+                        .or_default()
+                        .push((name.to_string(), number));
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            messages,
+            renames,
+            oneofs,
+            enums,
+            enum_values,
+            packages,
+            nested,
+            conflicts: Vec::new(),
+        }
+    }
+
+    /// Merges the metadata files of several crates, such as a shared proto crate's and the
+    /// compiling crate's own. A message or package recorded the same way by several files is kept
+    /// once; one recorded differently, or files mixing flat and nested layouts, is a conflict.
+    pub fn merge(files: Vec<(PathBuf, ProtoMetadata)>) -> Self {
+        let mut merged = ProtoMetadata::default();
+        let mut message_sources: HashMap<String, PathBuf> = HashMap::new();
+        let mut package_sources: HashMap<String, PathBuf> = HashMap::new();
+        let mut enum_sources: HashMap<String, PathBuf> = HashMap::new();
+        let mut layout_source: Option<(bool, PathBuf)> = None;
+
+        for (path, file) in files {
+            match &layout_source {
+                Some((nested, source)) if *nested != file.nested => {
+                    merged.conflicts.push(format!(
+                        "`{}` and `{}` record different module layouts; generate both with \
+                        `generate_metadata` or both with `generate_nested_metadata`",
+                        source.display(),
+                        path.display()
+                    ));
+                }
+                Some(_) => {}
+                None => {
+                    merged.nested = file.nested;
+                    layout_source = Some((file.nested, path.clone()));
+                }
+            }
+
+            for (name, fields) in file.messages {
+                let renames = file.renames.get(&name);
+                let oneofs = file.oneofs.get(&name);
+                if let Some(source) = message_sources.get(&name) {
+                    if merged.messages.get(&name) != Some(&fields)
+                        || merged.renames.get(&name) != renames
+                        || merged.oneofs.get(&name) != oneofs
+                    {
+                        merged.conflicts.push(format!(
+                            "message `{name}` differs between `{}` and `{}`",
+                            source.display(),
+                            path.display()
+                        ));
+                    }
+                    continue;
+                }
+                if let Some(renames) = renames {
+                    merged.renames.insert(name.clone(), renames.clone());
+                }
+                if let Some(oneofs) = oneofs {
+                    merged.oneofs.insert(name.clone(), oneofs.clone());
+                }
+                merged.messages.insert(name.clone(), fields);
+                message_sources.insert(name, path.clone());
+            }
+
+            for (package, module) in file.packages {
+                match merged.packages.get(&package) {
+                    Some(existing) if *existing != module => {
+                        merged.conflicts.push(format!(
+                            "package `{package}` is generated as `{existing}` in `{}` and as \
+                            `{module}` in `{}`",
+                            package_sources[&package].display(),
+                            path.display()
+                        ));
+                    }
+                    Some(_) => {}
+                    None => {
+                        merged.packages.insert(package.clone(), module);
+                        package_sources.insert(package, path.clone());
+                    }
+                }
+            }
+
+            for name in file.enums {
+                if !merged.enums.contains(&name) {
+                    merged.enums.push(name);
+                }
+            }
+
+            for (name, values) in file.enum_values {
+                match merged.enum_values.get(&name) {
+                    Some(existing) if *existing != values => {
+                        merged.conflicts.push(format!(
+                            "enum `{name}` differs between `{}` and `{}`",
+                            enum_sources[&name].display(),
+                            path.display()
+                        ));
+                    }
+                    Some(_) => {}
+                    None => {
+                        merged.enum_values.insert(name.clone(), values);
+                        enum_sources.insert(name, path.clone());
+                    }
+                }
+            }
+        }
+        merged
+    }
+
+    /// Adds the messages and enums of `schema` this metadata does not record, keeping its own
+    /// declarations where both know a name.
+    fn extend_missing(&mut self, schema: ProtoMetadata) {
+        for (name, fields) in schema.messages {
+            if self.messages.contains_key(&name) {
+                continue;
+            }
+            if let Some(renames) = schema.renames.get(&name) {
+                self.renames.insert(name.clone(), renames.clone());
+            }
+            if let Some(oneofs) = schema.oneofs.get(&name) {
+                self.oneofs.insert(name.clone(), oneofs.clone());
+            }
+            self.messages.insert(name, fields);
+        }
+        for name in schema.enums {
+            if !self.enums.contains(&name) {
+                self.enums.push(name);
+            }
+        }
+        for (name, values) in schema.enum_values {
+            self.enum_values.entry(name).or_insert(values);
+        }
+    }
+
+    /// Declarations the merged metadata files disagree on, one message per conflict.
+    pub fn conflicts(&self) -> &[String] {
+        &self.conflicts
+    }
+
+    /// The module holding the prost type a derive targets. Under a nested layout this is
+    /// `proto_module` followed by the module path of the package declaring the message or enum
+    /// `proto_name`, e.g. `proto::media::v1`. A `proto_name` nested in a message, written as the
+    /// path prost generates it at (`order::Item`, `order::Payment` for a oneof), resolves to the
+    /// package of its top-level message. `proto_module` is kept as written when it already ends
+    /// with the package path, when `proto_name` is a path through the package modules, or when the
+    /// type is unknown or declared under several packages.
+    pub fn resolve_module(&self, proto_module: &str, proto_name: &str) -> String {
+        let package_module = if !self.nested {
+            None
+        } else if proto_name.contains("::") {
+            self.nested_package_module(proto_name)
+        } else {
+            self.package_module(proto_name)
+        };
+        match package_module {
+            Some(package_module)
+                if proto_module != package_module
+                    && !proto_module.ends_with(&format!("::{package_module}")) =>
+            {
+                format!("{proto_module}::{package_module}")
+            }
+            _ => proto_module.to_string(),
+        }
+    }
+
+    /// The module path of the package declaring the top-level message or enum `name`, when a
+    /// single package does.
+    fn package_module(&self, name: &str) -> Option<&str> {
+        let mut modules = self
+            .messages
+            .keys()
+            .chain(&self.enums)
+            .filter_map(|qualified| {
+                let (package, simple_name) = qualified.rsplit_once('.')?;
+                (simple_name == name).then(|| self.packages.get(package))?
+            });
+        match (modules.next(), modules.next()) {
+            (Some(module), None) => Some(module.as_str()),
+            _ => None,
+        }
+    }
+
+    /// The module path of the package whose top-level message declares the type prost generates
+    /// at `path`, when a single package does.
+    fn nested_package_module(&self, path: &str) -> Option<&str> {
+        let depth = path.split("::").count();
+        let oneof_enums: Vec<String> = self
+            .oneofs
+            .iter()
+            .flat_map(|(message, oneofs)| {
+                oneofs.iter().map(move |oneof| {
+                    format!("{message}.{}", crate::utils::to_upper_camel_case(oneof))
+                })
+            })
+            .collect();
+        let mut modules = self
+            .messages
+            .keys()
+            .chain(&self.enums)
+            .chain(&oneof_enums)
+            .filter(|qualified| is_nested_at(qualified, path))
+            .filter_map(|qualified| {
+                let top_level = qualified.rsplitn(depth, '.').last()?;
+                if !self.messages.contains_key(top_level) {
+                    return None;
+                }
+                let (package, _) = top_level.rsplit_once('.')?;
+                self.packages.get(package)
+            });
+        match (modules.next(), modules.next()) {
+            (Some(module), None) => Some(module.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Finds the message a derive targets from its `module` and `proto_name`.
+    ///
+    /// Messages are matched on their unqualified name; when several packages declare the same
+    /// name, the one whose package ends with the last segment of `proto_module` wins and
+    /// anything still ambiguous is treated as unknown.
+    pub fn message(
+        &self,
+        proto_module: &str,
+        proto_name: &str,
+    ) -> Option<&HashMap<String, SchemaField>> {
+        let qualified = self.qualified_name(proto_module, proto_name)?;
+        self.messages.get(qualified)
+    }
+
+    /// The message a derive targets along with its qualified name and `oneof` fields; see
+    /// [`Self::message`].
+    pub fn message_schema(
+        &self,
+        proto_module: &str,
+        proto_name: &str,
+    ) -> Option<MessageSchema<'_>> {
+        let qualified_name = self.qualified_name(proto_module, proto_name)?;
+        Some(MessageSchema {
+            qualified_name,
+            fields: self.messages.get(qualified_name)?,
+            oneofs: self
+                .oneofs
+                .get(qualified_name)
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Package-qualified name of the message a derive targets; see [`qualified_name_in`].
+    fn qualified_name(&self, proto_module: &str, proto_name: &str) -> Option<&str> {
+        qualified_name_in(self.messages.keys(), proto_module, proto_name)
+    }
+
+    /// The values the enum a derive targets declares, along with its qualified name. The enum
+    /// is found from `module` and the enum's name like a message is; see [`Self::message`].
+    pub fn enum_values(
+        &self,
+        proto_module: &str,
+        proto_name: &str,
+    ) -> Option<(&str, &[(String, i32)])> {
+        let qualified_name = qualified_name_in(self.enum_values.keys(), proto_module, proto_name)?;
+        Some((qualified_name, self.enum_values.get(qualified_name)?))
+    }
+
+    pub fn field(&self, proto_module: &str, proto_name: &str, field: &str) -> Option<&SchemaField> {
+        self.message(proto_module, proto_name)?
+            .get(field.trim_start_matches("r#"))
+    }
+
+    /// The prost field a name refers to when it differs from the name itself: a proto field
+    /// prost renames (`type` to `r#type`, `self` to `self_`, `displayName` to `display_name`),
+    /// or a Rust-style `type_` for the field prost generates as `r#type`.
+    pub fn prost_field_ident(
+        &self,
+        proto_module: &str,
+        proto_name: &str,
+        field: &str,
+    ) -> Option<String> {
+        let qualified = self.qualified_name(proto_module, proto_name)?;
+        let fields = self.messages.get(qualified)?;
+        let renames = self.renames.get(qualified);
+        let rename = |name: &str| renames.and_then(|renames| renames.get(name)).cloned();
+
+        let field = field.trim_start_matches("r#");
+        if let Some(ident) = rename(field) {
+            return Some(ident);
+        }
+        if fields.contains_key(field) {
+            return None;
+        }
+        let base = field.strip_suffix('_')?;
+        rename(base).or_else(|| fields.contains_key(base).then(|| base.to_string()))
+    }
+}
+
+type MetadataCache = HashMap<Vec<PathBuf>, (Vec<Option<SystemTime>>, Arc<ProtoMetadata>)>;
+
+static METADATA_CACHE: OnceLock<Mutex<MetadataCache>> = OnceLock::new();
+
+type SchemaCache = Option<(String, Option<Arc<ProtoMetadata>>, Arc<ProtoMetadata>)>;
+
+static SCHEMA_CACHE: OnceLock<Mutex<SchemaCache>> = OnceLock::new();
+
+/// The metadata files to read: those listed in `PROTTO_METADATA`, separated like `PATH`
+/// (`a.txt:b.txt`, `;` on Windows), followed by the compiling crate's own file in `OUT_DIR`.
+fn metadata_paths() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = std::env::var_os(METADATA_ENV)
+        .map(|paths| {
+            std::env::split_paths(&paths)
+                .filter(|path| !path.as_os_str().is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(out_dir) = std::env::var_os("OUT_DIR") {
+        let path = PathBuf::from(out_dir).join(METADATA_FILE_NAME);
+        if path.exists() && !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Loads the metadata for the crate being compiled, if any: the files it reads, along with the
+/// messages of the `#[protto_schema]` modules expanded so far that the files do not record.
+pub fn load() -> Option<Arc<ProtoMetadata>> {
+    let files = load_files();
+    let schema = crate::registry::registered_schema();
+    if schema.is_empty() {
+        return files;
+    }
+    let mut cache = SCHEMA_CACHE.get_or_init(|| Mutex::new(None)).lock().ok()?;
+    if let Some((cached_schema, cached_files, metadata)) = cache.as_ref()
+        && *cached_schema == schema
+        && cached_files.as_ref().map(Arc::as_ptr) == files.as_ref().map(Arc::as_ptr)
+    {
+        return Some(metadata.clone());
+    }
+
+    let mut metadata = files.as_deref().cloned().unwrap_or_default();
+    metadata.extend_missing(ProtoMetadata::parse(&schema));
+    let metadata = Arc::new(metadata);
+    *cache = Some((schema, files, metadata.clone()));
+    Some(metadata)
+}
+
+/// Loads the metadata files for the crate being compiled, if any, merging every file it reads;
+/// see [`ProtoMetadata::merge`]. Merged files are cached per set of paths and reloaded when any
+/// of their modification times changes.
+fn load_files() -> Option<Arc<ProtoMetadata>> {
+    let paths = metadata_paths();
+    if paths.is_empty() {
+        return None;
+    }
+    let modified: Vec<_> = paths
+        .iter()
+        .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect();
+    let mut cache = METADATA_CACHE
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .ok()?;
+
+    if let Some((cached_modified, metadata)) = cache.get(&paths)
+        && *cached_modified == modified
+    {
+        return Some(metadata.clone());
+    }
+
+    let mut files = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let content = std::fs::read_to_string(path).ok()?;
+        files.push((path.clone(), ProtoMetadata::parse(&content)));
+    }
+    let metadata = Arc::new(match files.len() {
+        1 => files
+            .pop()
+            .map(|(_, metadata)| metadata)
+            .unwrap_or_default(),
+        _ => ProtoMetadata::merge(files),
+    });
+    cache.insert(paths, (modified, metadata.clone()));
+    Some(metadata)
+}
+
+/// A compile error listing the declarations the metadata files disagree on, if any.
+pub fn conflict_error() -> Option<syn::Error> {
+    let metadata = load()?;
+    let conflicts = metadata.conflicts();
+    (!conflicts.is_empty()).then(|| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!(
+                "the proto metadata files disagree: {}",
+                conflicts.join("; ")
+            ),
+        )
+    })
+}
+
+/// Resolves a field name to the identifier prost generates for it, if the metadata knows it
+/// under a different name; see [`ProtoMetadata::prost_field_ident`].
+pub fn prost_field_ident(proto_module: &str, proto_name: &str, field: &str) -> Option<String> {
+    load()?.prost_field_ident(proto_module, proto_name, field)
+}
+
+/// Whether prost generates the package-qualified proto type `qualified` at `path` relative to
+/// its package: types nested in a message live in a module named after it, so `shop.Order.Item`
+/// is generated at `order::Item`.
+fn is_nested_at(qualified: &str, path: &str) -> bool {
+    let segments: Vec<&str> = path.split("::").collect();
+    let mut names: Vec<&str> = qualified.rsplit('.').take(segments.len()).collect();
+    names.reverse();
+    let Some((type_name, parents)) = names.split_last() else {
+        return false;
+    };
+    names.len() == segments.len()
+        && parents
+            .iter()
+            .zip(&segments)
+            .all(|(parent, segment)| crate::utils::to_snake_case(parent) == *segment)
+        && crate::utils::to_upper_camel_case(&crate::utils::to_snake_case(type_name))
+            == segments[segments.len() - 1]
+}
+
+/// Package-qualified name of the message or enum among `names` a derive targets; see
+/// [`ProtoMetadata::message`]. A `proto_name` written as the path prost generates a nested type at
+/// (`order::Item`) matches through its enclosing messages.
+fn qualified_name_in<'a>(
+    names: impl Iterator<Item = &'a String>,
+    proto_module: &str,
+    proto_name: &str,
+) -> Option<&'a str> {
+    let names: Vec<&String> = names.collect();
+    let simple_name = proto_name.rsplit("::").next().unwrap_or(proto_name);
+    let nested: Vec<_> = names
+        .iter()
+        .copied()
+        .filter(|name| proto_name.contains("::") && is_nested_at(name, proto_name))
+        .collect();
+    let (candidates, depth) = if nested.is_empty() {
+        let candidates: Vec<_> = names
+            .iter()
+            .copied()
+            .filter(|name| name.rsplit('.').next() == Some(simple_name))
+            .collect();
+        (candidates, 1)
+    } else {
+        (nested, proto_name.split("::").count())
+    };
+
+    match candidates.as_slice() {
+        [name] => Some(name.as_str()),
+        [] => None,
+        _ => {
+            let module = proto_module.rsplit("::").next().unwrap_or(proto_module);
+            let mut in_module = candidates.iter().filter(|name| {
+                let mut tail: Vec<_> = name.rsplit('.').take(depth).collect();
+                tail.reverse();
+                let suffix = format!("{module}.{}", tail.join("."));
+                **name == &suffix || name.ends_with(&format!(".{suffix}"))
+            });
+            match (in_module.next(), in_module.next()) {
+                (Some(name), None) => Some(name.as_str()),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// The module holding the prost type a derive targets; see [`ProtoMetadata::resolve_module`].
+pub fn resolve_module(proto_module: &str, proto_name: &str) -> String {
+    match load() {
+        Some(metadata) => metadata.resolve_module(proto_module, proto_name),
+        None => proto_module.to_string(),
+    }
+}
+
+/// Looks up a field of the proto message targeted by a derive.
+pub fn lookup_field(proto_module: &str, proto_name: &str, field: &str) -> Option<SchemaField> {
+    load()?.field(proto_module, proto_name, field).cloned()
+}
+
+/// Looks up the qualified name and values of the proto enum targeted by a derive.
+pub fn lookup_enum_values(
+    proto_module: &str,
+    proto_name: &str,
+) -> Option<(String, Vec<(String, i32)>)> {
+    let metadata = load()?;
+    let (qualified_name, values) = metadata.enum_values(proto_module, proto_name)?;
+    Some((qualified_name.to_string(), values.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METADATA: &str = "\
+# generated by protto::build
+message service.Track
+field track_id required scalar uint64
+field tags repeated scalar string
+field related map message map<string,Track>
+field header optional message Header
+field parent optional message Track boxed
+message billing.Track
+field amount required scalar int64
+message service.Header
+field request_id required scalar string
+field type required scalar string
+rename type r#type
+field self_ required scalar int32
+rename self self_
+field display_name required scalar string
+rename displayName display_name
+field retries optional message google.protobuf.UInt32Value
+oneof payload
+";
+
+    #[test]
+    fn test_parse_and_lookup_field_shapes() {
+        let metadata = ProtoMetadata::parse(METADATA);
+        let header = metadata.field("proto", "Header", "request_id").unwrap();
+        assert_eq!(header.shape, SchemaShape::Required);
+        assert_eq!(header.kind, SchemaKind::Scalar);
+        assert!(metadata.field("proto", "Header", "missing").is_none());
+        assert!(metadata.field("proto", "Unknown", "request_id").is_none());
+    }
+
+    #[test]
+    fn test_ambiguous_message_resolved_by_module() {
+        let metadata = ProtoMetadata::parse(METADATA);
+        assert!(metadata.field("proto", "Track", "tags").is_none());
+
+        let tags = metadata.field("crate::service", "Track", "tags").unwrap();
+        assert_eq!(tags.shape, SchemaShape::Repeated);
+        let related = metadata.field("service", "Track", "related").unwrap();
+        assert_eq!(related.shape, SchemaShape::Map);
+        let amount = metadata.field("billing", "Track", "amount").unwrap();
+        assert_eq!(amount.type_name, "int64");
+        assert!(!amount.boxed);
+        let parent = metadata.field("service", "Track", "parent").unwrap();
+        assert!(parent.boxed);
+    }
+
+    #[test]
+    fn test_prost_renamed_fields_resolve_to_prost_idents() {
+        let metadata = ProtoMetadata::parse(METADATA);
+        let resolve = |field| metadata.prost_field_ident("proto", "Header", field);
+        assert_eq!(resolve("type").as_deref(), Some("r#type"));
+        assert_eq!(resolve("type_").as_deref(), Some("r#type"));
+        assert_eq!(resolve("self").as_deref(), Some("self_"));
+        assert_eq!(resolve("displayName").as_deref(), Some("display_name"));
+        assert_eq!(resolve("request_id_").as_deref(), Some("request_id"));
+        assert_eq!(resolve("request_id"), None);
+        assert_eq!(resolve("self_"), None);
+        assert_eq!(resolve("missing"), None);
+    }
+
+    #[test]
+    fn test_message_schema_lists_oneofs() {
+        let metadata = ProtoMetadata::parse(METADATA);
+        let header = metadata.message_schema("proto", "Header").unwrap();
+        assert_eq!(header.qualified_name, "service.Header");
+        assert_eq!(header.oneofs, ["payload".to_string()]);
+        assert!(header.fields.contains_key("request_id"));
+
+        let track = metadata.message_schema("service", "Track").unwrap();
+        assert!(track.oneofs.is_empty());
+    }
+
+    #[test]
+    fn test_enum_values_found_by_module() {
+        let metadata = ProtoMetadata::parse(
+            "enum service.Status
+value STATUS_UNSPECIFIED 0
+value STATUS_ACTIVE 1
+value STATUS_DISABLED 5
+enum billing.Status
+value STATUS_OPEN -1
+message service.Job
+field status required enum Status
+enum service.Job.Phase
+value PHASE_DONE 3
+",
+        );
+        let (name, values) = metadata.enum_values("crate::service", "Status").unwrap();
+        assert_eq!(name, "service.Status");
+        let numbers: Vec<_> = values.iter().map(|(_, number)| *number).collect();
+        assert_eq!(numbers, [0, 1, 5]);
+        assert_eq!(
+            metadata.enum_values("billing", "Status").unwrap().1,
+            [("STATUS_OPEN".to_string(), -1)]
+        );
+        assert!(metadata.enum_values("proto", "Status").is_none());
+        assert_eq!(
+            metadata.enum_values("proto", "job::Phase").unwrap().0,
+            "service.Job.Phase"
+        );
+        assert!(metadata.field("proto", "Job", "status").is_some());
+    }
+
+    #[test]
+    fn test_merge_combines_files_and_reports_conflicts() {
+        let shared = ProtoMetadata::parse(
+            "package media media
+message media.Track
+field track_id required scalar uint64
+",
+        );
+        let leaf = ProtoMetadata::parse(
+            "package media media
+message media.Track
+field track_id required scalar uint64
+message app.Playlist
+field tracks repeated message Track
+",
+        );
+        let merged = ProtoMetadata::merge(vec![
+            (PathBuf::from("shared.txt"), shared),
+            (PathBuf::from("leaf.txt"), leaf),
+        ]);
+        assert!(merged.conflicts().is_empty());
+        assert!(merged.field("proto", "Track", "track_id").is_some());
+        assert!(merged.field("proto", "Playlist", "tracks").is_some());
+
+        let stale = ProtoMetadata::parse(
+            "layout nested
+package media media::v1
+message media.Track
+field track_id optional scalar uint64
+",
+        );
+        let shared = ProtoMetadata::parse(
+            "package media media\nmessage media.Track\nfield track_id required scalar uint64\n",
+        );
+        let merged = ProtoMetadata::merge(vec![
+            (PathBuf::from("shared.txt"), shared),
+            (PathBuf::from("stale.txt"), stale),
+        ]);
+        assert_eq!(
+            merged.conflicts(),
+            [
+                "`shared.txt` and `stale.txt` record different module layouts; generate both with \
+                `generate_metadata` or both with `generate_nested_metadata`",
+                "message `media.Track` differs between `shared.txt` and `stale.txt`",
+                "package `media` is generated as `media` in `shared.txt` and as `media::v1` in \
+                `stale.txt`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_message_found_by_prost_path() {
+        let metadata = ProtoMetadata::parse(
+            "message shop.Order
+message shop.Order.Item
+field sku required scalar string
+message shop.Cart.Item
+field quantity required scalar uint32
+message shop.HTTPRequest.Header
+field name required scalar string
+",
+        );
+        assert!(metadata.field("proto", "Item", "sku").is_none());
+        assert!(metadata.field("proto", "order::Item", "sku").is_some());
+        assert!(metadata.field("proto", "cart::Item", "quantity").is_some());
+        assert!(metadata.field("proto", "cart::Item", "sku").is_none());
+        assert!(
+            metadata
+                .field("proto", "http_request::Header", "name")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_nested_layout_resolves_package_modules() {
+        let metadata = ProtoMetadata::parse(
+            "layout nested
+package media.v1 media::v1
+package billing billing
+enum media.v1.Kind
+message media.v1.Track
+oneof source
+message media.v1.Track.Segment
+message media.v1.Header
+message billing.Header
+",
+        );
+        let resolve = |module, name| metadata.resolve_module(module, name);
+        assert_eq!(resolve("proto", "Track"), "proto::media::v1");
+        assert_eq!(resolve("crate::proto", "Kind"), "crate::proto::media::v1");
+        assert_eq!(resolve("proto::media::v1", "Track"), "proto::media::v1");
+        assert_eq!(resolve("proto", "media::v1::Track"), "proto");
+        assert_eq!(resolve("proto", "Header"), "proto");
+        assert_eq!(resolve("proto", "Segment"), "proto");
+        assert_eq!(resolve("proto", "Unknown"), "proto");
+        assert_eq!(resolve("proto", "track::Segment"), "proto::media::v1");
+        assert_eq!(resolve("proto", "track::Source"), "proto::media::v1");
+        assert_eq!(
+            resolve("proto::media::v1", "track::Source"),
+            "proto::media::v1"
+        );
+        assert_eq!(resolve("proto", "track::Unknown"), "proto");
+
+        let flat = ProtoMetadata::parse("package media.v1 media::v1\nmessage media.v1.Track\n");
+        assert_eq!(flat.resolve_module("proto", "Track"), "proto");
+    }
+
+    #[test]
+    fn test_wrapper_fields() {
+        let metadata = ProtoMetadata::parse(METADATA);
+        assert!(
+            metadata
+                .field("proto", "Header", "retries")
+                .unwrap()
+                .is_wrapper()
+        );
+        assert!(
+            !metadata
+                .field("proto", "Track", "header")
+                .is_some_and(SchemaField::is_wrapper)
+        );
+        assert!(
+            !metadata
+                .field("proto", "Header", "type")
+                .unwrap()
+                .is_wrapper()
+        );
+    }
+}
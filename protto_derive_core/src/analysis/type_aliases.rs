@@ -0,0 +1,102 @@
+//! Field-level `alias_of`: type analysis matches on the text of a field's type, so a type alias
+//! such as `type UserId = u64` reads as an unknown custom type. Fields naming the alias's target
+//! have their type replaced by it before anything else looks at them; the alias and its target
+//! are the same type, so the generated conversions compile against the field either way.
+
+use crate::analysis::attribute_parser;
+
+/// Replaces the type of every field carrying `alias_of` with the type it names. An alias wrapped
+/// in `Option` or `Vec` is replaced inside the wrapper, so `Option<UserId>` with
+/// `alias_of = "u64"` becomes `Option<u64>`.
+pub fn resolve_type_aliases(mut ast: syn::DeriveInput) -> syn::Result<syn::DeriveInput> {
+    let syn::Data::Struct(data_struct) = &mut ast.data else {
+        return Ok(ast);
+    };
+
+    for field in data_struct.fields.iter_mut() {
+        let Some(target) = attribute_parser::ProtoFieldMeta::from_field(field)
+            .ok()
+            .and_then(|meta| meta.alias_of)
+        else {
+            continue;
+        };
+        let target: syn::Type = syn::parse_str(&target)
+            .map_err(|err| syn::Error::new_spanned(&field.ty, err.to_string()))?;
+        substitute_alias(&mut field.ty, target);
+    }
+
+    Ok(ast)
+}
+
+fn substitute_alias(ty: &mut syn::Type, target: syn::Type) {
+    if let Some(inner) = wrapped_type(ty) {
+        substitute_alias(inner, target);
+    } else {
+        *ty = target;
+    }
+}
+
+/// The element of an `Option<T>` or `Vec<T>`
+fn wrapped_type(ty: &mut syn::Type) -> Option<&mut syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last_mut()?;
+    if segment.ident != "Option" && segment.ident != "Vec" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &mut segment.arguments else {
+        return None;
+    };
+    if args.args.len() != 1 {
+        return None;
+    }
+    match args.args.first_mut()? {
+        syn::GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    fn resolved_field_types(input: &str) -> Vec<String> {
+        let ast = resolve_type_aliases(syn::parse_str(input).unwrap()).unwrap();
+        let syn::Data::Struct(data_struct) = ast.data else {
+            unreachable!()
+        };
+        data_struct
+            .fields
+            .iter()
+            .map(|field| {
+                let ty = &field.ty;
+                quote!(#ty).to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_alias_of_replaces_the_field_type() {
+        let types = resolved_field_types(
+            "struct Account {
+                #[protto(alias_of = \"u64\")] id: UserId,
+                #[protto(alias_of = \"u64\")] referrer: Option<UserId>,
+                #[protto(alias_of = \"String\")] tags: Vec<Tag>,
+                #[protto(alias_of = \"Vec<u64>\")] friends: Option<UserIds>,
+                name: Name,
+            }",
+        );
+        assert_eq!(
+            types,
+            [
+                "u64",
+                "Option < u64 >",
+                "Vec < String >",
+                "Option < Vec < u64 > >",
+                "Name"
+            ]
+        );
+    }
+}
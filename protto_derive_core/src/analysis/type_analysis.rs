@@ -0,0 +1,530 @@
+use crate::constants::PRIMITIVE_TYPES;
+use crate::registry;
+use syn::Type;
+
+pub fn is_option_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.segments.first().map(|s| s.ident == "Option").unwrap_or(false))
+}
+
+pub fn get_inner_type_from_option(ty: &Type) -> Option<Type> {
+    if let Type::Path(type_path) = ty
+        && type_path.path.segments.len() == 1
+        && type_path.path.segments[0].ident == "Option"
+        && let syn::PathArguments::AngleBracketed(angle_bracketed) =
+            &type_path.path.segments[0].arguments
+        && let Some(syn::GenericArgument::Type(inner_type)) = angle_bracketed.args.first()
+    {
+        Some(inner_type.clone())
+    } else {
+        None
+    }
+}
+
+pub fn is_vec_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty
+        && type_path.path.segments.len() == 1
+        && type_path.path.segments[0].ident == "Vec"
+    {
+        true
+    } else {
+        false
+    }
+}
+
+pub fn get_inner_type_from_vec(ty: &Type) -> Option<Type> {
+    if let Type::Path(type_path) = ty
+        && type_path.path.segments.len() == 1
+        && type_path.path.segments[0].ident == "Vec"
+        && let syn::PathArguments::AngleBracketed(angle_bracketed) =
+            &type_path.path.segments[0].arguments
+        && let Some(syn::GenericArgument::Type(inner_type)) = angle_bracketed.args.first()
+    {
+        Some(inner_type.clone())
+    } else {
+        None
+    }
+}
+
+/// `HashMap<K, V>` or `BTreeMap<K, V>`, with or without a path prefix
+pub fn is_map_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+    {
+        last_segment.ident == "HashMap" || last_segment.ident == "BTreeMap"
+    } else {
+        false
+    }
+}
+
+/// `HashMap<K, V>`, the map type prost generates by default
+pub fn is_hash_map_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+    {
+        last_segment.ident == "HashMap"
+    } else {
+        false
+    }
+}
+
+/// The `K` of a `HashMap<K, V>` or `BTreeMap<K, V>`
+pub fn get_key_type_from_map(ty: &Type) -> Option<Type> {
+    if is_map_type(ty)
+        && let Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+        && let syn::PathArguments::AngleBracketed(angle_bracketed) = &last_segment.arguments
+        && let Some(syn::GenericArgument::Type(key_type)) = angle_bracketed.args.first()
+    {
+        Some(key_type.clone())
+    } else {
+        None
+    }
+}
+
+/// The `V` of a `HashMap<K, V>` or `BTreeMap<K, V>`
+pub fn get_value_type_from_map(ty: &Type) -> Option<Type> {
+    if is_map_type(ty)
+        && let Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+        && let syn::PathArguments::AngleBracketed(angle_bracketed) = &last_segment.arguments
+        && let Some(syn::GenericArgument::Type(value_type)) = angle_bracketed.args.iter().nth(1)
+    {
+        Some(value_type.clone())
+    } else {
+        None
+    }
+}
+
+pub fn is_primitive_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty {
+        type_path.path.segments.len() == 1
+            && PRIMITIVE_TYPES
+                .iter()
+                .any(|&p| type_path.path.segments[0].ident == p)
+    } else {
+        false
+    }
+}
+
+/// `Result<T, E>` with both type arguments spelled out, as a `result_oneof` field needs
+pub fn is_result_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+        && last_segment.ident == "Result"
+        && let syn::PathArguments::AngleBracketed(angle_bracketed) = &last_segment.arguments
+    {
+        angle_bracketed.args.len() == 2
+    } else {
+        false
+    }
+}
+
+pub fn is_bool_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.is_ident("bool"))
+}
+
+/// Unified detection for any non-primitive, non-collection custom type
+pub fn is_custom_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty {
+        // Skip primitives, collections, and proto types
+        if is_primitive_type(ty) || is_vec_type(ty) || is_option_type(ty) {
+            return false;
+        }
+
+        // Skip proto module types
+        if type_path
+            .path
+            .segments
+            .first()
+            .map(|segment| segment.ident == "proto")
+            .unwrap_or(false)
+        {
+            return false;
+        }
+
+        // Any remaining single-segment type is a custom type (struct, enum, newtype)
+        // Let the Rust compiler and From trait implementations determine what works
+        type_path.path.segments.len() == 1
+    } else {
+        false
+    }
+}
+
+/// A type generated under the proto module, named from the module's root: `proto::Track`,
+/// `crate::proto::Track` or `proto::media::v1::Track` for a `module` of `proto`,
+/// `crate::proto` or `proto::media::v1`.
+pub fn is_proto_type(ty: &Type, proto_module: &str) -> bool {
+    let is_anchor = |segment: &str| matches!(segment, "crate" | "self" | "super");
+    let Some(module_root) = proto_module.split("::").find(|segment| !is_anchor(segment)) else {
+        return false;
+    };
+
+    if let Type::Path(type_path) = ty
+        && type_path.qself.is_none()
+    {
+        let mut segments = type_path
+            .path
+            .segments
+            .iter()
+            .skip_while(|segment| is_anchor(&segment.ident.to_string()));
+        segments
+            .next()
+            .is_some_and(|root| root.ident == module_root)
+            && segments.next().is_some()
+    } else {
+        false
+    }
+}
+
+pub fn is_enum_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+    {
+        let type_name = last_segment.ident.to_string();
+        registry::is_registered_enum_type(&type_name)
+    } else {
+        false
+    }
+}
+
+/// Detects `Arc<T>`, with or without its `std::sync` path prefix
+pub fn is_arc_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+    {
+        last_segment.ident == "Arc"
+            && matches!(
+                last_segment.arguments,
+                syn::PathArguments::AngleBracketed(_)
+            )
+    } else {
+        false
+    }
+}
+
+/// Detects `Box<T>`, with or without its `std::boxed` path prefix
+pub fn is_box_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+    {
+        last_segment.ident == "Box"
+            && matches!(
+                last_segment.arguments,
+                syn::PathArguments::AngleBracketed(_)
+            )
+    } else {
+        false
+    }
+}
+
+/// The collection a newtype deriving `Protto` wraps, e.g. `struct Tags(Vec<Tag>)`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NewtypeCollection {
+    /// `Vec<T>`, carried as a proto `repeated` field
+    Vec,
+
+    /// `HashMap<K, V>` or `BTreeMap<K, V>`, carried as a proto `map` field
+    Map,
+}
+
+/// The collection the type wraps, if it is a newtype over a `Vec` or map deriving `Protto`
+pub fn newtype_collection(ty: &Type) -> Option<NewtypeCollection> {
+    if let Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+    {
+        registry::registered_collection_newtype(&last_segment.ident.to_string())
+    } else {
+        None
+    }
+}
+
+/// What a field's type is taken to be, set via `kind` in place of the name heuristics and the
+/// enum registry
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TypeKind {
+    /// Assigned to and from the proto scalar as is
+    Primitive,
+
+    /// Converted to and from a proto message with `From` / `TryFrom`
+    Message,
+
+    /// Converted to and from the proto `i32` of a rust enum deriving `Protto`
+    Enum,
+}
+
+impl TypeKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "primitive" => Some(Self::Primitive),
+            "message" => Some(Self::Message),
+            "enum" => Some(Self::Enum),
+            _ => None,
+        }
+    }
+}
+
+/// Encoding of `PathBuf` / `OsString` fields in proto, set via `path_encoding`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PathEncoding {
+    /// proto `string`; the rust -> proto conversion fails on non-UTF-8 values
+    Utf8Strict,
+
+    /// proto `string`; non-UTF-8 sequences are replaced with `U+FFFD`
+    Lossy,
+
+    /// proto `bytes`; the raw OS bytes (lossless on unix)
+    Bytes,
+}
+
+impl PathEncoding {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "utf8_strict" => Some(Self::Utf8Strict),
+            "lossy" => Some(Self::Lossy),
+            "bytes" => Some(Self::Bytes),
+            _ => None,
+        }
+    }
+}
+
+/// How a `Vec` field is packed into a proto `bytes` field, set via `bytes_as`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BytesAs {
+    /// Elements are plain-old-data structs laid out back to back (requires the `zerocopy`
+    /// feature); decoding fails when the length is not a whole number of elements
+    PodSlice,
+}
+
+impl BytesAs {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pod_slice" => Some(Self::PodSlice),
+            _ => None,
+        }
+    }
+}
+
+/// How a `rust_decimal::Decimal` field is carried in proto, set via `decimal`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DecimalAs {
+    /// A proto string holding the decimal's text; decoding fails on text that is not a number
+    String,
+    /// A proto message of whole `units` and `nanos`; encoding rounds to nanos and fails when the
+    /// units overflow `i64`
+    UnitsNanos,
+}
+
+impl DecimalAs {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "string" => Some(Self::String),
+            "units_nanos" => Some(Self::UnitsNanos),
+            _ => None,
+        }
+    }
+}
+
+/// Unit of the proto `int64` a `Duration` field is carried in, set via `duration`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DurationUnit {
+    Seconds,
+    Millis,
+    Nanos,
+}
+
+impl DurationUnit {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "seconds" => Some(Self::Seconds),
+            "millis" => Some(Self::Millis),
+            "nanos" => Some(Self::Nanos),
+            _ => None,
+        }
+    }
+
+    /// The matching `protto::duration::DurationUnit` variant
+    pub fn runtime_path(self) -> proc_macro2::TokenStream {
+        match self {
+            Self::Seconds => quote::quote! { ::protto::duration::DurationUnit::Seconds },
+            Self::Millis => quote::quote! { ::protto::duration::DurationUnit::Millis },
+            Self::Nanos => quote::quote! { ::protto::duration::DurationUnit::Nanos },
+        }
+    }
+}
+
+/// Inline- or fixed-capacity collection a repeated proto field converts into
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BoundedCollection {
+    /// `smallvec::SmallVec<[T; N]>`, which spills to the heap past its inline capacity
+    SmallVec,
+    /// `arrayvec::ArrayVec<T, N>`, which cannot hold more than `N` elements
+    ArrayVec,
+}
+
+/// What happens when a repeated proto field has more elements than an `ArrayVec` holds, set via
+/// `overflow`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CapacityOverflow {
+    /// Fail the proto -> rust conversion with `InvalidValue`, which makes it `TryFrom`
+    Error,
+    /// Panic with a descriptive message
+    Panic,
+    /// Keep the first `N` elements and drop the rest
+    Truncate,
+}
+
+impl CapacityOverflow {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "error" => Some(Self::Error),
+            "panic" => Some(Self::Panic),
+            "truncate" => Some(Self::Truncate),
+            _ => None,
+        }
+    }
+}
+
+/// What an `i32` matching no variant of an `enum_as_i32` field's enum converts to
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UnknownI32Policy {
+    /// Fail the proto -> rust conversion with `InvalidValue`, which makes it `TryFrom`
+    Error,
+    /// Panic with a descriptive message
+    Panic,
+    /// Use the enum's `Default` variant
+    Default,
+}
+
+impl UnknownI32Policy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "error" => Some(Self::Error),
+            "panic" => Some(Self::Panic),
+            "default" => Some(Self::Default),
+            _ => None,
+        }
+    }
+}
+
+/// What a proto enum value of 0, the `*_UNSPECIFIED` value proto style requires, converts to on
+/// a field whose rust enum has no variant for it, set via `unspecified`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum UnspecifiedPolicy {
+    /// Fail the proto -> rust conversion with `InvalidValue`, which makes it `TryFrom`
+    Error,
+    /// Use the field type's `Default`: the enum's default variant, or `None` for an `Option`
+    Default,
+    /// Use the named variant of the rust enum
+    MapTo(String),
+}
+
+impl UnspecifiedPolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "error" => Some(Self::Error),
+            "default" => Some(Self::Default),
+            value => {
+                let (key, variant) = value.split_once('=')?;
+                let variant = variant.trim();
+                (key.trim() == "map_to" && syn::parse_str::<syn::Ident>(variant).is_ok())
+                    .then(|| Self::MapTo(variant.to_string()))
+            }
+        }
+    }
+}
+
+/// What an empty repeated proto field converts to on an `Option<Vec<T>>` field, set via
+/// `empty_repeated`. The proto side cannot tell an empty list from an absent one, so both `None`
+/// and `Some(vec![])` always convert into an empty repeated field.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum EmptyRepeated {
+    /// An empty repeated field converts to `None`, so `Some(vec![])` round trips as `None`
+    #[default]
+    None,
+    /// A repeated field always converts to `Some`, so `None` round trips as `Some(vec![])`
+    SomeEmpty,
+}
+
+impl EmptyRepeated {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "none" => Some(Self::None),
+            "some_empty" => Some(Self::SomeEmpty),
+            _ => None,
+        }
+    }
+}
+
+/// Detects `SmallVec<..>` and `ArrayVec<..>`, with or without their path prefix
+pub fn bounded_collection_type(ty: &Type) -> Option<BoundedCollection> {
+    if let Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+        && matches!(
+            last_segment.arguments,
+            syn::PathArguments::AngleBracketed(_)
+        )
+    {
+        match last_segment.ident.to_string().as_str() {
+            "SmallVec" => Some(BoundedCollection::SmallVec),
+            "ArrayVec" => Some(BoundedCollection::ArrayVec),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+/// Detects `std::time::Duration` and chrono's `Duration` / `TimeDelta`, with or without their path
+/// prefix
+pub fn is_duration_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+        && last_segment.arguments.is_empty()
+    {
+        last_segment.ident == "Duration" || last_segment.ident == "TimeDelta"
+    } else {
+        false
+    }
+}
+
+/// Detects `rust_decimal::Decimal`, with or without its path prefix
+pub fn is_decimal_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+        && last_segment.arguments.is_empty()
+    {
+        last_segment.ident == "Decimal"
+    } else {
+        false
+    }
+}
+
+/// Detects `PathBuf` and `OsString`, with or without their `std` path prefix
+/// `Vec<u8>` or `bytes::Bytes`, the two types prost generates for a proto `bytes` field
+/// depending on its `bytes` configuration
+pub fn is_bytes_type(ty: &Type) -> bool {
+    if get_inner_type_from_vec(ty)
+        .is_some_and(|inner| matches!(&inner, Type::Path(p) if p.path.is_ident("u8")))
+    {
+        return true;
+    }
+    if let Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+        && last_segment.arguments.is_empty()
+    {
+        last_segment.ident == "Bytes"
+    } else {
+        false
+    }
+}
+
+pub fn is_os_path_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty
+        && let Some(last_segment) = type_path.path.segments.last()
+        && last_segment.arguments.is_empty()
+    {
+        last_segment.ident == "PathBuf" || last_segment.ident == "OsString"
+    } else {
+        false
+    }
+}
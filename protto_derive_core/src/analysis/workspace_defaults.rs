@@ -0,0 +1,415 @@
+//! Workspace-wide defaults for struct- and enum-level attributes, read from a `protto.toml`.
+//!
+//! The file is located through the `PROTTO_CONFIG` environment variable, falling back to the
+//! nearest `protto.toml` in the compiling crate's directory or one of its parents, so a file at
+//! the workspace root covers every member crate and a crate can still bring its own. Its
+//! `[defaults]` table holds attribute keys as they are written in `#[protto(...)]`:
+//!
+//! ```toml
+//! [defaults]
+//! module = "crate::proto"
+//! error_type = "crate::ConversionError"
+//! error_fn = "crate::ConversionError::invalid"
+//! static_errors = true
+//! ```
+//!
+//! A default applies to every derived type whose own attributes leave the key out.
+
+use crate::analysis::attribute_parser::{self, ProttoKey};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+const CONFIG_ENV: &str = "PROTTO_CONFIG";
+const CONFIG_FILE_NAME: &str = "protto.toml";
+const DEFAULTS_TABLE: &str = "defaults";
+
+/// How a key's value is written in the attribute
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ValueKind {
+    /// `key`, set by `key = true`
+    Flag,
+    /// `key = "value"`
+    Str,
+    /// `key = path::Type`, given in the file as a string
+    Path,
+}
+
+/// The keys a `protto.toml` can set: the policies a project would otherwise repeat on every
+/// struct. Keys naming one message or type, such as `proto_name`, stay on the type.
+fn value_kind(key: ProttoKey) -> Option<ValueKind> {
+    match key {
+        ProttoKey::StaticErrors | ProttoKey::DebugRoundtrip | ProttoKey::Validate => {
+            Some(ValueKind::Flag)
+        }
+        ProttoKey::Module
+        | ProttoKey::ErrorFn
+        | ProttoKey::ErrorFnWithValue
+        | ProttoKey::ErrorVis
+        | ProttoKey::CustomFnsAre
+        | ProttoKey::Conversions
+        | ProttoKey::ExtraFields
+        | ProttoKey::UseRules => Some(ValueKind::Str),
+        ProttoKey::ErrorType => Some(ValueKind::Path),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DefaultValue {
+    Bool(bool),
+    Str(String),
+}
+
+/// The `[defaults]` of a `protto.toml`, in file order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkspaceDefaults {
+    path: PathBuf,
+    defaults: Vec<(ProttoKey, DefaultValue)>,
+}
+
+impl WorkspaceDefaults {
+    /// Parses the file's content. Only what the defaults need of TOML is read: tables, comments,
+    /// and `key = "string"` or `key = true`/`false` pairs.
+    pub fn parse(path: &Path, content: &str) -> Result<Self, String> {
+        let mut defaults = Vec::new();
+        let mut table = None;
+        for (number, line) in content.lines().enumerate() {
+            let error = |message: String| format!("{}:{}: {message}", path.display(), number + 1);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[') {
+                let name = strip_comment(name)
+                    .strip_suffix(']')
+                    .ok_or_else(|| error(format!("unterminated table header `{line}`")))?;
+                table = Some(name.trim().to_string());
+                continue;
+            }
+
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(format!("expected `key = value`, found `{line}`")))?;
+            let name = name.trim();
+            match table.as_deref() {
+                Some(DEFAULTS_TABLE) => {}
+                Some(other) => {
+                    return Err(error(format!(
+                        "unknown table `[{other}]`; attribute defaults go in `[{DEFAULTS_TABLE}]`"
+                    )));
+                }
+                None => {
+                    return Err(error(format!(
+                        "`{name}` is outside a table; attribute defaults go in \
+                        `[{DEFAULTS_TABLE}]`"
+                    )));
+                }
+            }
+
+            let key = ProttoKey::from_name(name)
+                .filter(|key| value_kind(*key).is_some())
+                .ok_or_else(|| {
+                    error(format!(
+                        "`{name}` cannot be set in {CONFIG_FILE_NAME}; the keys it can set are {}",
+                        supported_keys()
+                    ))
+                })?;
+            let value = parse_value(value).map_err(&error)?;
+            match (value_kind(key), &value) {
+                (Some(ValueKind::Flag), DefaultValue::Bool(_))
+                | (Some(ValueKind::Str | ValueKind::Path), DefaultValue::Str(_)) => {}
+                (Some(ValueKind::Flag), _) => {
+                    return Err(error(format!(
+                        "`{name}` is a flag, set with `{name} = true`"
+                    )));
+                }
+                _ => {
+                    return Err(error(format!(
+                        "`{name}` takes a string, e.g. `{name} = \"...\"`"
+                    )));
+                }
+            }
+            if defaults.iter().any(|(existing, _)| *existing == key) {
+                return Err(error(format!("`{name}` is set twice")));
+            }
+            defaults.push((key, value));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            defaults,
+        })
+    }
+
+    /// Adds a `#[protto(...)]` attribute to the type for every default it does not set itself.
+    /// `error_fn` and `error_fn_with_value` count as one key. Transparent and arena structs only
+    /// take `module`, and passthrough structs none of the defaults, since they generate no
+    /// conversion of their own for the rest to apply to. Sources structs name their messages in
+    /// full, so they only take the error keys. Borrowed structs skip `validate`, which they do
+    /// not support.
+    pub fn apply(&self, mut ast: syn::DeriveInput) -> syn::Result<syn::DeriveInput> {
+        let level = match ast.data {
+            syn::Data::Enum(_) => attribute_parser::AttributeLevel::Enum,
+            _ => attribute_parser::AttributeLevel::Struct,
+        };
+        let written: Vec<String> = attribute_parser::protto_metas(&ast.attrs)?
+            .iter()
+            .filter_map(|meta| meta.path().get_ident().map(ToString::to_string))
+            .collect();
+        let is_written = |key: ProttoKey| match key {
+            ProttoKey::ErrorFn | ProttoKey::ErrorFnWithValue => written
+                .iter()
+                .any(|name| name == "error_fn" || name == "error_fn_with_value"),
+            key => written.iter().any(|name| name == key.name()),
+        };
+        let is_borrowed = ast.generics.lifetimes().next().is_some();
+        let accepts = |key: ProttoKey| {
+            if is_written(ProttoKey::Passthrough) || (key == ProttoKey::Validate && is_borrowed) {
+                false
+            } else if is_written(ProttoKey::Transparent) || is_written(ProttoKey::Arena) {
+                key == ProttoKey::Module
+            } else if is_written(ProttoKey::Sources) {
+                matches!(
+                    key,
+                    ProttoKey::ErrorType
+                        | ProttoKey::ErrorFn
+                        | ProttoKey::ErrorFnWithValue
+                        | ProttoKey::ErrorVis
+                        | ProttoKey::StaticErrors
+                )
+            } else {
+                true
+            }
+        };
+
+        let mut keys = Vec::new();
+        for (key, value) in &self.defaults {
+            if !key.levels().contains(&level) || is_written(*key) || !accepts(*key) {
+                continue;
+            }
+            let name = syn::Ident::new(key.name(), proc_macro2::Span::call_site());
+            match (value_kind(*key), value) {
+                (Some(ValueKind::Flag), DefaultValue::Bool(true)) => {
+                    keys.push(quote::quote!(#name))
+                }
+                (Some(ValueKind::Path), DefaultValue::Str(path)) => {
+                    let path: syn::Path = syn::parse_str(path).map_err(|_| {
+                        syn::Error::new(
+                            proc_macro2::Span::call_site(),
+                            format!(
+                                "{}: `{}` must be a type path, found \"{path}\"",
+                                self.path.display(),
+                                key.name()
+                            ),
+                        )
+                    })?;
+                    keys.push(quote::quote!(#name = #path));
+                }
+                (Some(ValueKind::Str), DefaultValue::Str(value)) => {
+                    keys.push(quote::quote!(#name = #value))
+                }
+                _ => {}
+            }
+        }
+        if !keys.is_empty() {
+            ast.attrs.push(syn::parse_quote!(#[protto(#(#keys),*)]));
+        }
+        Ok(ast)
+    }
+
+    /// Makes the crate depend on the file, so cargo recompiles it when the defaults change.
+    pub fn track_file(&self) -> proc_macro2::TokenStream {
+        let path = self.path.display().to_string();
+        quote::quote! {
+            const _: &[u8] = include_bytes!(#path);
+        }
+    }
+}
+
+fn supported_keys() -> String {
+    ProttoKey::ALL
+        .iter()
+        .filter(|key| value_kind(**key).is_some())
+        .map(|key| format!("`{}`", key.name()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn strip_comment(text: &str) -> &str {
+    text.split_once('#')
+        .map_or(text, |(before, _)| before)
+        .trim()
+}
+
+fn parse_value(value: &str) -> Result<DefaultValue, String> {
+    let value = value.trim();
+    if let Some(rest) = value.strip_prefix('"') {
+        let (string, after) = rest
+            .split_once('"')
+            .ok_or_else(|| format!("unterminated string `{value}`"))?;
+        if string.contains('\\') {
+            return Err(format!("escapes are not supported in `{value}`"));
+        }
+        if !strip_comment(after).is_empty() {
+            return Err(format!("unexpected `{}` after the value", after.trim()));
+        }
+        return Ok(DefaultValue::Str(string.to_string()));
+    }
+    match strip_comment(value) {
+        "true" => Ok(DefaultValue::Bool(true)),
+        "false" => Ok(DefaultValue::Bool(false)),
+        other => Err(format!(
+            "expected a string or `true`/`false`, found `{other}`"
+        )),
+    }
+}
+
+type DefaultsCache = HashMap<PathBuf, (Option<SystemTime>, Arc<WorkspaceDefaults>)>;
+
+static DEFAULTS_CACHE: OnceLock<Mutex<DefaultsCache>> = OnceLock::new();
+
+/// The file named by `PROTTO_CONFIG`, or the nearest `protto.toml` from the compiling crate's
+/// directory up.
+fn config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(CONFIG_ENV).filter(|path| !path.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    let manifest_dir = PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR")?);
+    manifest_dir
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// Loads the defaults for the crate being compiled, if it has a `protto.toml`. The parsed file is
+/// cached and reloaded when its modification time changes.
+pub fn load() -> syn::Result<Option<Arc<WorkspaceDefaults>>> {
+    let Some(path) = config_path() else {
+        return Ok(None);
+    };
+    let error = |message: String| syn::Error::new(proc_macro2::Span::call_site(), message);
+    let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+    let mut cache = DEFAULTS_CACHE
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .map_err(|_| error(format!("{} could not be read", path.display())))?;
+
+    if let Some((cached_modified, defaults)) = cache.get(&path)
+        && *cached_modified == modified
+    {
+        return Ok(Some(defaults.clone()));
+    }
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(|err| error(format!("{} could not be read: {err}", path.display())))?;
+    let defaults = Arc::new(WorkspaceDefaults::parse(&path, &content).map_err(error)?);
+    cache.insert(path, (modified, defaults.clone()));
+    Ok(Some(defaults))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Result<WorkspaceDefaults, String> {
+        WorkspaceDefaults::parse(Path::new("protto.toml"), content)
+    }
+
+    fn applied(defaults: &WorkspaceDefaults, input: &str) -> Vec<String> {
+        let ast = defaults.apply(syn::parse_str(input).unwrap()).unwrap();
+        ast.attrs
+            .iter()
+            .map(|attr| quote::quote!(#attr).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_defaults_fill_in_unset_keys() {
+        let defaults = parse(
+            "# project conventions\n\
+            [defaults]\n\
+            module = \"crate::proto\"  # generated by build.rs\n\
+            error_type = \"crate::ConversionError\"\n\
+            error_fn = \"crate::ConversionError::invalid\"\n\
+            static_errors = true\n\
+            debug_roundtrip = false\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            applied(&defaults, "struct Track { id: u64 }"),
+            [
+                "# [protto (module = \"crate::proto\" , error_type = crate :: ConversionError , \
+             error_fn = \"crate::ConversionError::invalid\" , static_errors)]"
+            ]
+        );
+        assert_eq!(
+            applied(
+                &defaults,
+                "#[protto(module = \"legacy\", error_fn_with_value = \"rejected\")] \
+                struct Track { id: u64 }"
+            ),
+            [
+                "# [protto (module = \"legacy\" , error_fn_with_value = \"rejected\")]",
+                "# [protto (error_type = crate :: ConversionError , static_errors)]"
+            ]
+        );
+        assert_eq!(
+            applied(&defaults, "enum Status { Ok }"),
+            ["# [protto (module = \"crate::proto\")]"]
+        );
+        assert_eq!(
+            applied(&defaults, "#[protto(transparent)] struct Id(u64);"),
+            [
+                "# [protto (transparent)]",
+                "# [protto (module = \"crate::proto\")]"
+            ]
+        );
+        assert_eq!(
+            applied(
+                &defaults,
+                "#[protto(sources(a = \"p::A\", b = \"p::B\"))] struct Pair { id: u64 }"
+            ),
+            [
+                "# [protto (sources (a = \"p::A\" , b = \"p::B\"))]",
+                "# [protto (error_type = crate :: ConversionError , \
+             error_fn = \"crate::ConversionError::invalid\" , static_errors)]"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_default_marks_every_owned_struct() {
+        let defaults = parse("[defaults]\nvalidate = true\n").unwrap();
+
+        assert_eq!(
+            applied(&defaults, "struct User { email: String }"),
+            ["# [protto (validate)]"]
+        );
+        assert!(applied(&defaults, "struct UserView<'a> { email: &'a str }").is_empty());
+        assert!(applied(&defaults, "enum Status { Ok }").is_empty());
+    }
+
+    #[test]
+    fn test_invalid_defaults_are_reported_with_their_line() {
+        let err = parse("[defaults]\nrename_all = \"snake_case\"\n").unwrap_err();
+        assert!(err.starts_with("protto.toml:2: `rename_all` cannot be set in protto.toml"));
+        assert!(err.contains("`module`"));
+
+        let err = parse("[defaults]\nproto_name = \"Track\"\n").unwrap_err();
+        assert!(err.contains("`proto_name` cannot be set"));
+
+        let err = parse("module = \"proto\"\n").unwrap_err();
+        assert!(err.contains("`module` is outside a table"));
+
+        let err = parse("[defaults]\nstatic_errors = \"yes\"\n").unwrap_err();
+        assert!(err.contains("`static_errors` is a flag, set with `static_errors = true`"));
+
+        let err = parse("[defaults]\nmodule = true\n").unwrap_err();
+        assert!(err.contains("`module` takes a string"));
+
+        let err = parse("[defaults]\nmodule = \"a\"\nmodule = \"b\"\n").unwrap_err();
+        assert!(err.starts_with("protto.toml:3: `module` is set twice"));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::analysis::attribute_parser;
+use crate::utils;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+
+/// Struct-level `arena` (behind the `arena` feature): converts a borrowed proto message into a
+/// struct whose fields live in a `bumpalo::Bump`. Each field goes through
+/// `protto::arena::FromProtoIn` in the arena named by the struct's single lifetime, so nested
+/// arena structs, `&'a str` and `bumpalo::collections::Vec<'a, _>` share one allocation scheme.
+/// Only this one-way, infallible conversion is generated.
+pub fn generate_arena_implementations(
+    struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    fields: &syn::Fields,
+    proto_path: &syn::Path,
+) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "arena") {
+        let error_msg =
+            format!("`{struct_name}` enables arena, which requires the `arena` feature of protto");
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    let syn::Fields::Named(fields_named) = fields else {
+        return syn::Error::new_spanned(
+            struct_name,
+            "arena requires a struct with named fields, converting from a whole proto message",
+        )
+        .to_compile_error();
+    };
+
+    let lifetimes: Vec<_> = generics.lifetimes().collect();
+    let [arena_lifetime] = lifetimes.as_slice() else {
+        return syn::Error::new_spanned(
+            struct_name,
+            format!(
+                "arena requires exactly one lifetime parameter, the arena's, e.g. \
+                `struct {struct_name}<'a>`; found {}",
+                lifetimes.len()
+            ),
+        )
+        .to_compile_error();
+    };
+    let arena_lifetime = &arena_lifetime.lifetime;
+
+    let field_values = fields_named.named.iter().map(|field| {
+        let field_name = field.ident.as_ref().expect("named field");
+        if attribute_parser::has_proto_ignore(field) {
+            return quote! { #field_name: ::core::default::Default::default() };
+        }
+        let proto_field = attribute_parser::get_proto_field_name(field)
+            .map(|name| utils::field_ident(&name))
+            .unwrap_or_else(|| field_name.clone());
+        quote_spanned! { field.ty.span()=>
+            #field_name: ::protto::arena::FromProtoIn::from_proto_in(&proto_struct.#proto_field, bump)
+        }
+    });
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::protto::arena::FromProtoIn<#arena_lifetime, #proto_path>
+            for #struct_name #ty_generics #where_clause
+        {
+            fn from_proto_in(
+                proto_struct: &#proto_path,
+                bump: &#arena_lifetime ::protto::arena::Bump,
+            ) -> Self {
+                Self {
+                    #(#field_values,)*
+                }
+            }
+        }
+
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            /// Converts the proto message, allocating the struct's data in `bump`.
+            pub fn from_proto_in(
+                proto_struct: &#proto_path,
+                bump: &#arena_lifetime ::protto::arena::Bump,
+            ) -> Self {
+                <Self as ::protto::arena::FromProtoIn<#arena_lifetime, #proto_path>>::from_proto_in(
+                    proto_struct,
+                    bump,
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate_for(input: &str) -> String {
+        let ast: syn::DeriveInput = syn::parse_str(input).unwrap();
+        let syn::Data::Struct(data_struct) = &ast.data else {
+            panic!("expected struct");
+        };
+        generate_arena_implementations(
+            &ast.ident,
+            &ast.generics,
+            &data_struct.fields,
+            &syn::parse_quote!(proto::Request),
+        )
+        .to_string()
+    }
+
+    #[test]
+    fn test_arena_fields_convert_in_the_arena() {
+        let code = generate_for(
+            "struct Request<'a> { #[protto(proto_name = \"user_name\")] user: &'a str, \
+            tags: Vec<'a, &'a str>, #[protto(ignore)] seen: u8 }",
+        );
+        if !cfg!(feature = "arena") {
+            assert!(code.contains("requires the `arena` feature"));
+            return;
+        }
+        assert!(code.contains(
+            "impl < 'a > :: protto :: arena :: FromProtoIn < 'a , proto :: Request > for Request < 'a >"
+        ));
+        assert!(code.contains(
+            "user : :: protto :: arena :: FromProtoIn :: from_proto_in (& proto_struct . user_name , bump)"
+        ));
+        assert!(code.contains(
+            "tags : :: protto :: arena :: FromProtoIn :: from_proto_in (& proto_struct . tags , bump)"
+        ));
+        assert!(code.contains("seen : :: core :: default :: Default :: default ()"));
+        assert!(code.contains("pub fn from_proto_in (proto_struct : & proto :: Request , bump : & 'a :: protto :: arena :: Bump ,) -> Self"));
+
+        let code = generate_for("struct Request { user: String }");
+        assert!(code.contains("arena requires exactly one lifetime parameter"));
+    }
+}
@@ -0,0 +1,105 @@
+//! Records every derived struct for the coverage report of `protto::build::coverage_report`.
+//!
+//! With the `meta-file` feature, each struct derive writes one record into
+//! `$OUT_DIR/protto_coverage/`: the proto message it converts and, per field, the proto fields it
+//! maps and the conversion strategy chosen. Crates without a build script have no `OUT_DIR` and
+//! record nothing; write failures are ignored so they never break the derive.
+
+use std::path::PathBuf;
+
+const COVERAGE_DIR_NAME: &str = "protto_coverage";
+
+/// A field of a derived struct and the strategy its conversion uses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldRecord {
+    pub rust_field: String,
+    pub strategy: &'static str,
+    /// Proto fields read and written; several for `group`, none for ignored fields.
+    pub proto_fields: Vec<String>,
+}
+
+/// A derived struct and the proto message it converts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructRecord {
+    pub rust_name: String,
+    pub proto_module: String,
+    pub proto_name: String,
+    pub fields: Vec<FieldRecord>,
+}
+
+impl StructRecord {
+    /// Renders the line format parsed by `protto::build::parse_coverage_record`: a
+    /// `derive <Struct> <proto_module>::<ProtoName>` line followed by one
+    /// `field <rust_field> <strategy> [<proto_field>...]` line per field.
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "derive {} {}::{}\n",
+            self.rust_name, self.proto_module, self.proto_name
+        );
+        for field in &self.fields {
+            out.push_str(&format!("field {} {}", field.rust_field, field.strategy));
+            for proto_field in &field.proto_fields {
+                out.push(' ');
+                out.push_str(proto_field);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn file_name(&self) -> String {
+        let proto_path = format!("{}::{}", self.proto_module, self.proto_name).replace("::", ".");
+        format!("{proto_path}-{}.txt", self.rust_name)
+    }
+}
+
+/// Writes the record into the compiling crate's `OUT_DIR`, if it has one.
+pub fn record(record: &StructRecord) {
+    let Some(out_dir) = std::env::var_os("OUT_DIR") else {
+        return;
+    };
+    let dir = PathBuf::from(out_dir).join(COVERAGE_DIR_NAME);
+    if std::fs::create_dir_all(&dir).is_ok() {
+        let _ = std::fs::write(dir.join(record.file_name()), record.render());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_struct_record() {
+        let record = StructRecord {
+            rust_name: "Track".to_string(),
+            proto_module: "crate::proto".to_string(),
+            proto_name: "Track".to_string(),
+            fields: vec![
+                FieldRecord {
+                    rust_field: "id".to_string(),
+                    strategy: "direct",
+                    proto_fields: vec!["track_id".to_string()],
+                },
+                FieldRecord {
+                    rust_field: "address".to_string(),
+                    strategy: "group",
+                    proto_fields: vec!["street".to_string(), "city".to_string()],
+                },
+                FieldRecord {
+                    rust_field: "cache".to_string(),
+                    strategy: "ignore",
+                    proto_fields: Vec::new(),
+                },
+            ],
+        };
+
+        assert_eq!(
+            record.render(),
+            "derive Track crate::proto::Track\n\
+             field id direct track_id\n\
+             field address group street city\n\
+             field cache ignore\n"
+        );
+        assert_eq!(record.file_name(), "crate.proto.Track-Track.txt");
+    }
+}
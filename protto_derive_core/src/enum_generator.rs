@@ -0,0 +1,900 @@
+use crate::analysis::proto_metadata;
+use crate::utils;
+use quote::quote;
+
+pub fn generate_enum_conversions(
+    name: &syn::Ident,
+    variants: &syn::punctuated::Punctuated<syn::Variant, syn::token::Comma>,
+    proto_module: &str,
+) -> proc_macro2::TokenStream {
+    let enum_name_str = name.to_string();
+    let enum_prefix = utils::to_screaming_snake_case(&enum_name_str);
+    let proto_enum_path: syn::Path = syn::parse_str(&format!("{}::{}", proto_module, name))
+        .expect("Failed to parse proto enum path");
+
+    let size_impl = generate_enum_size_impl(name);
+    let arena_impl = generate_enum_arena_impl(name);
+    let proto_values = proto_metadata::lookup_enum_values(proto_module, &enum_name_str);
+
+    if variants
+        .iter()
+        .any(|variant| variant.discriminant.is_some())
+    {
+        let conversions = generate_discriminant_enum_conversions(
+            name,
+            variants,
+            proto_values.as_ref(),
+            &proto_enum_path,
+        );
+        return quote! {
+            #size_impl
+            #arena_impl
+            #conversions
+        };
+    }
+
+    // With the proto enum's values from the build metadata, variants convert by number, which
+    // also reaches the aliases prost leaves out of its enum. Variants matching no value keep the
+    // name-based conversions, so the mismatch is the only error reported.
+    let mut unmatched = None;
+    if let Some((qualified_name, values)) = &proto_values {
+        match generate_numbered_enum_conversions(
+            name,
+            variants,
+            &enum_prefix,
+            &proto_enum_path,
+            qualified_name,
+            values,
+        ) {
+            Ok(conversions) => {
+                return quote! {
+                    #size_impl
+                    #arena_impl
+                    #conversions
+                };
+            }
+            Err(err) => unmatched = Some(err.to_compile_error()),
+        }
+    }
+
+    let from_proto_enum_arms = generate_from_proto_enum_arms(variants, name, &enum_prefix);
+    let from_proto_arms = generate_from_proto_arms(variants, name, &enum_prefix, &proto_enum_path);
+
+    quote! {
+        #unmatched
+        #size_impl
+        #arena_impl
+
+        impl From<i32> for #name {
+            fn from(value: i32) -> Self {
+                let proto_val = <#proto_enum_path>::from_i32(value)
+                    .unwrap_or_else(|| panic!("Unknown enum value: {}", value));
+                let proto_str = proto_val.as_str_name();
+                match proto_str {
+                    #(#from_proto_enum_arms)*
+                    _ => panic!("No matching Rust variant for proto enum string: {}", proto_str),
+                }
+            }
+        }
+
+        impl From<#name> for i32 {
+            fn from(rust_enum: #name) -> Self {
+                let proto: #proto_enum_path = rust_enum.into();
+                proto as i32
+            }
+        }
+
+        impl From<#name> for #proto_enum_path {
+            fn from(rust_enum: #name) -> Self {
+                match rust_enum {
+                    #(#from_proto_arms)*
+                }
+            }
+        }
+
+        impl From<#proto_enum_path> for #name {
+            fn from(proto_enum: #proto_enum_path) -> Self {
+                let proto_str = proto_enum.as_str_name();
+                match proto_str {
+                    #(#from_proto_enum_arms)*
+                    _ => panic!("No matching Rust variant for proto enum string: {proto_str}"),
+                }
+            }
+        }
+    }
+}
+
+/// Conversions between a rust enum with explicit discriminants (`Active = 1, Disabled = 5`) and
+/// its prost enum by number rather than by variant name. When the metadata lists the proto enum,
+/// a discriminant it does not declare, or a value no discriminant covers, fails the build.
+fn generate_discriminant_enum_conversions(
+    name: &syn::Ident,
+    variants: &syn::punctuated::Punctuated<syn::Variant, syn::token::Comma>,
+    proto_values: Option<&(String, Vec<(String, i32)>)>,
+    proto_enum_path: &syn::Path,
+) -> proc_macro2::TokenStream {
+    if let Some(variant) = variants
+        .iter()
+        .find(|variant| !matches!(variant.fields, syn::Fields::Unit))
+    {
+        return syn::Error::new_spanned(
+            variant,
+            format!(
+                "enum `{name}` converts by its discriminants: variant `{}` must be a unit variant",
+                variant.ident
+            ),
+        )
+        .to_compile_error();
+    }
+
+    // The conversions are still generated so the mismatch is the only error reported
+    let mismatches = proto_values
+        .and_then(|(qualified_name, values)| {
+            let numbers = discriminant_values(variants);
+            let uncovered = numbers
+                .iter()
+                .all(|(_, number)| number.is_some())
+                .then(|| {
+                    let covered: Vec<i64> = numbers.iter().filter_map(|(_, n)| *n).collect();
+                    check_coverage(name, qualified_name, values, |value| {
+                        covered.contains(&i64::from(value))
+                    })
+                })
+                .flatten();
+            [
+                check_discriminants(variants, qualified_name, values),
+                uncovered,
+            ]
+            .into_iter()
+            .flatten()
+            .reduce(|mut combined, err| {
+                combined.combine(err);
+                combined
+            })
+        })
+        .map(|err| err.to_compile_error());
+
+    let variant_idents: Vec<_> = variants.iter().map(|variant| &variant.ident).collect();
+
+    quote! {
+        #mismatches
+
+        impl From<i32> for #name {
+            fn from(value: i32) -> Self {
+                match value {
+                    #(value if value == #name::#variant_idents as i32 => #name::#variant_idents,)*
+                    value => panic!("No matching Rust variant for proto enum value: {}", value),
+                }
+            }
+        }
+
+        impl From<#name> for i32 {
+            fn from(rust_enum: #name) -> Self {
+                rust_enum as i32
+            }
+        }
+
+        impl From<#name> for #proto_enum_path {
+            fn from(rust_enum: #name) -> Self {
+                let value = rust_enum as i32;
+                <#proto_enum_path>::from_i32(value)
+                    .unwrap_or_else(|| panic!("Unknown enum value: {}", value))
+            }
+        }
+
+        impl From<#proto_enum_path> for #name {
+            fn from(proto_enum: #proto_enum_path) -> Self {
+                Self::from(proto_enum as i32)
+            }
+        }
+    }
+}
+
+/// Conversions between a rust enum and its prost enum by the numbers the build metadata gives
+/// the proto values its variants are named after: `Running` matches `RUNNING` or
+/// `<ENUM>_RUNNING`, and converts to that value's number even when it is an alias prost leaves
+/// out of its enum. Reading a number back gives the first variant with it. Errors when a variant
+/// matches no value; values no variant matches are reported alongside the conversions.
+fn generate_numbered_enum_conversions(
+    name: &syn::Ident,
+    variants: &syn::punctuated::Punctuated<syn::Variant, syn::token::Comma>,
+    enum_prefix: &str,
+    proto_enum_path: &syn::Path,
+    qualified_name: &str,
+    values: &[(String, i32)],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut numbered = Vec::new();
+    let mut unmatched: Option<syn::Error> = None;
+    for variant in variants {
+        let candidates = variant_name_candidates(&variant.ident, enum_prefix);
+        let number = candidates.iter().find_map(|candidate| {
+            values
+                .iter()
+                .find(|(value_name, _)| utils::same_prost_name(value_name, candidate))
+                .map(|(_, number)| *number)
+        });
+        match number {
+            Some(number) => numbered.push((&variant.ident, number)),
+            None => {
+                let err = syn::Error::new_spanned(
+                    &variant.ident,
+                    format!(
+                        "`{}` matches no value of proto enum `{qualified_name}`, looking for {}; \
+                        it declares {}",
+                        variant.ident,
+                        candidates
+                            .iter()
+                            .map(|candidate| format!("`{candidate}`"))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        declared_values(values)
+                    ),
+                );
+                match &mut unmatched {
+                    Some(combined) => combined.combine(err),
+                    None => unmatched = Some(err),
+                }
+            }
+        }
+    }
+    if let Some(err) = unmatched {
+        return Err(err);
+    }
+
+    let uncovered = check_coverage(name, qualified_name, values, |value| {
+        numbered.iter().any(|(_, number)| *number == value)
+    })
+    .map(|err| err.to_compile_error());
+
+    // A number shared by several variants reads back as the first of them
+    let mut read_back: Vec<(&syn::Ident, i32)> = Vec::new();
+    for (ident, number) in &numbered {
+        if !read_back.iter().any(|(_, seen)| seen == number) {
+            read_back.push((ident, *number));
+        }
+    }
+    let (read_idents, read_numbers): (Vec<_>, Vec<_>) = read_back.into_iter().unzip();
+    let (idents, numbers): (Vec<_>, Vec<_>) = numbered.into_iter().unzip();
+
+    Ok(quote! {
+        #uncovered
+
+        impl From<i32> for #name {
+            fn from(value: i32) -> Self {
+                match value {
+                    #(#read_numbers => #name::#read_idents,)*
+                    value => panic!("Unknown enum value: {}", value),
+                }
+            }
+        }
+
+        impl From<#name> for i32 {
+            fn from(rust_enum: #name) -> Self {
+                match rust_enum {
+                    #(#name::#idents => #numbers,)*
+                }
+            }
+        }
+
+        impl From<#name> for #proto_enum_path {
+            fn from(rust_enum: #name) -> Self {
+                let value = i32::from(rust_enum);
+                <#proto_enum_path>::from_i32(value)
+                    .unwrap_or_else(|| panic!("Unknown enum value: {}", value))
+            }
+        }
+
+        impl From<#proto_enum_path> for #name {
+            fn from(proto_enum: #proto_enum_path) -> Self {
+                Self::from(proto_enum as i32)
+            }
+        }
+    })
+}
+
+/// The proto value names a variant may stand for, in the order they are tried: the variant's own
+/// name, its SCREAMING_SNAKE_CASE form prefixed with the enum's, and that form alone. A value
+/// matches a candidate when prost would give both the same name, so `HTTP_SERVER_2` matches
+/// `HttpServer2` too.
+fn variant_name_candidates(variant: &syn::Ident, enum_prefix: &str) -> Vec<String> {
+    let screaming_variant = utils::to_screaming_snake_case(&variant.to_string());
+    vec![
+        variant.to_string(),
+        format!("{enum_prefix}_{screaming_variant}"),
+        screaming_variant,
+    ]
+}
+
+/// The candidates compared with `as_str_name()` when there is no build metadata, and so no
+/// values to compare through prost's names: the SCREAMING_SNAKE_CASE candidates are also tried
+/// with digits set apart (`HTTP_SERVER_2` as well as `HTTP_SERVER2`), since both are written.
+fn str_name_candidates(variant: &syn::Ident, enum_prefix: &str) -> Vec<String> {
+    let mut candidates = variant_name_candidates(variant, enum_prefix);
+    let separated: Vec<String> = candidates[1..]
+        .iter()
+        .map(|candidate| separate_digits(candidate))
+        .filter(|separated| !candidates.contains(separated))
+        .collect();
+    candidates.extend(separated);
+    candidates
+}
+
+/// Sets each run of digits following a letter apart with `_`, e.g. `HTTP_SERVER2` ->
+/// `HTTP_SERVER_2`
+fn separate_digits(name: &str) -> String {
+    let mut separated = String::with_capacity(name.len() + 1);
+    let mut prev = None;
+    for c in name.chars() {
+        if c.is_ascii_digit() && prev.is_some_and(char::is_alphabetic) {
+            separated.push('_');
+        }
+        separated.push(c);
+        prev = Some(c);
+    }
+    separated
+}
+
+/// Reports the values of the proto enum `qualified_name` no variant of the rust enum covers,
+/// which would otherwise panic when read from the proto message. A `*_UNSPECIFIED = 0` value may
+/// go uncovered: fields of the enum say what it converts to with `unspecified`.
+fn check_coverage(
+    name: &syn::Ident,
+    qualified_name: &str,
+    values: &[(String, i32)],
+    covers: impl Fn(i32) -> bool,
+) -> Option<syn::Error> {
+    let uncovered: Vec<_> = values
+        .iter()
+        .filter(|(value_name, number)| {
+            let unspecified = *number == 0 && value_name.ends_with("UNSPECIFIED");
+            !covers(*number) && !unspecified
+        })
+        .map(|(value_name, number)| format!("{value_name} = {number}"))
+        .collect();
+    if uncovered.is_empty() {
+        return None;
+    }
+    Some(syn::Error::new_spanned(
+        name,
+        format!(
+            "enum `{name}` has no variant for {} of proto enum `{qualified_name}`; add a variant \
+            for each value the proto message may carry",
+            uncovered.join(", ")
+        ),
+    ))
+}
+
+fn declared_values(values: &[(String, i32)]) -> String {
+    values
+        .iter()
+        .map(|(value_name, number)| format!("{value_name} = {number}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Reports the variants whose discriminant is not a value of the proto enum `qualified_name`.
+fn check_discriminants(
+    variants: &syn::punctuated::Punctuated<syn::Variant, syn::token::Comma>,
+    qualified_name: &str,
+    values: &[(String, i32)],
+) -> Option<syn::Error> {
+    let declared = declared_values(values);
+    discriminant_values(variants)
+        .into_iter()
+        .filter_map(|(variant, number)| {
+            let number = number?;
+            if values.iter().any(|(_, value)| i64::from(*value) == number) {
+                return None;
+            }
+            let message = format!(
+                "`{}` = {number} is not a value of proto enum `{qualified_name}`, which declares \
+                {declared}",
+                variant.ident
+            );
+            Some(match &variant.discriminant {
+                Some((_, expr)) => syn::Error::new_spanned(expr, message),
+                None => syn::Error::new_spanned(&variant.ident, message),
+            })
+        })
+        .reduce(|mut combined, err| {
+            combined.combine(err);
+            combined
+        })
+}
+
+/// The number of each variant: its integer literal discriminant, or one more than the previous
+/// variant's as rust counts them. Unknown after a discriminant that is not a literal.
+fn discriminant_values(
+    variants: &syn::punctuated::Punctuated<syn::Variant, syn::token::Comma>,
+) -> Vec<(&syn::Variant, Option<i64>)> {
+    let mut next = Some(0i64);
+    variants
+        .iter()
+        .map(|variant| {
+            let number = match &variant.discriminant {
+                Some((_, expr)) => literal_discriminant(expr),
+                None => next,
+            };
+            next = number.and_then(|number| number.checked_add(1));
+            (variant, number)
+        })
+        .collect()
+}
+
+fn literal_discriminant(expr: &syn::Expr) -> Option<i64> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(int),
+            ..
+        }) => int.base10_parse().ok(),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => literal_discriminant(expr).map(|number| -number),
+        syn::Expr::Group(group) => literal_discriminant(&group.expr),
+        syn::Expr::Paren(paren) => literal_discriminant(&paren.expr),
+        _ => None,
+    }
+}
+
+/// Conversions between a rust enum and a plain proto `int32` by the enum's discriminants, for
+/// `enum_as_i32` enums that have no prost enum type to go through.
+pub fn generate_i32_enum_conversions(
+    name: &syn::Ident,
+    variants: &syn::punctuated::Punctuated<syn::Variant, syn::token::Comma>,
+) -> proc_macro2::TokenStream {
+    if let Some(variant) = variants
+        .iter()
+        .find(|variant| !matches!(variant.fields, syn::Fields::Unit))
+    {
+        return syn::Error::new_spanned(
+            variant,
+            format!(
+                "enum_as_i32 enum `{name}`: variant `{}` must be a unit variant",
+                variant.ident
+            ),
+        )
+        .to_compile_error();
+    }
+
+    let variant_idents: Vec<_> = variants.iter().map(|variant| &variant.ident).collect();
+    let target = name.to_string();
+    let size_impl = generate_enum_size_impl(name);
+
+    quote! {
+        #size_impl
+
+        impl From<#name> for i32 {
+            fn from(rust_enum: #name) -> Self {
+                rust_enum as i32
+            }
+        }
+
+        impl TryFrom<i32> for #name {
+            type Error = ::protto::enums::UnknownEnumValue;
+
+            fn try_from(value: i32) -> Result<Self, Self::Error> {
+                match value {
+                    #(value if value == #name::#variant_idents as i32 => Ok(#name::#variant_idents),)*
+                    value => Err(::protto::enums::UnknownEnumValue { target: #target, value }),
+                }
+            }
+        }
+    }
+}
+
+/// Under the `size-estimate` feature, sizes a derived enum as a one-byte varint, which proto
+/// enum values almost always are
+fn generate_enum_size_impl(name: &syn::Ident) -> proc_macro2::TokenStream {
+    if cfg!(feature = "size-estimate") {
+        quote! {
+            impl ::protto::size::EstimatedProtoSize for #name {
+                fn estimated_proto_size(&self) -> usize {
+                    1
+                }
+            }
+        }
+    } else {
+        quote! {}
+    }
+}
+
+/// Under `arena`, lets fields of `arena` structs hold the enum, converting the proto `i32` with
+/// the enum's `From<i32>`
+fn generate_enum_arena_impl(name: &syn::Ident) -> proc_macro2::TokenStream {
+    if cfg!(feature = "arena") {
+        quote! {
+            impl<'a> ::protto::arena::FromProtoIn<'a, i32> for #name {
+                fn from_proto_in(proto: &i32, _bump: &'a ::protto::arena::Bump) -> Self {
+                    Self::from(*proto)
+                }
+            }
+        }
+    } else {
+        quote! {}
+    }
+}
+
+/// Conversions between a rust enum and each prost oneof enum listed in its `oneof` attribute.
+///
+/// Every variant wraps a single value and maps to the same-named variant of each oneof, so
+/// several proto messages embedding the same oneof structure share one rust enum. The matches
+/// have no catch-all arm, so a variant added to the proto fails the build until the rust enum
+/// covers it. With `non_exhaustive`, proto → rust is instead a `TryFrom` whose catch-all arm
+/// returns `UnmappedOneofVariant`. With `estimated_size`, the enum sizes the oneof field holding
+/// its variant.
+pub fn generate_oneof_conversions(
+    name: &syn::Ident,
+    variants: &syn::punctuated::Punctuated<syn::Variant, syn::token::Comma>,
+    proto_module: &str,
+    oneofs: &[syn::Path],
+    non_exhaustive: bool,
+    estimated_size: bool,
+) -> proc_macro2::TokenStream {
+    if let Some(variant) = variants.iter().find(|variant| match &variant.fields {
+        syn::Fields::Unnamed(fields) => fields.unnamed.len() != 1,
+        syn::Fields::Named(_) => true,
+        syn::Fields::Unit => false,
+    }) {
+        return syn::Error::new_spanned(
+            variant,
+            format!(
+                "oneof enum `{name}`: variant `{}` must wrap exactly one value, e.g. {}(String), \
+                or be a unit variant standing for an empty proto message",
+                variant.ident, variant.ident
+            ),
+        )
+        .to_compile_error();
+    }
+
+    if estimated_size && !cfg!(feature = "size-estimate") {
+        return syn::Error::new_spanned(
+            name,
+            format!(
+                "`{name}` enables estimated_size, which requires the `size-estimate` feature of \
+                protto"
+            ),
+        )
+        .to_compile_error();
+    }
+
+    // Unit variants pair with empty marker messages (`message Ping {}`): the proto side is built
+    // from the message's Default and its (empty) contents are dropped on the way back
+    let (unit_variants, value_variants): (Vec<_>, Vec<_>) = variants
+        .iter()
+        .partition(|variant| variant.fields.is_empty());
+    let unit_variants: Vec<_> = unit_variants.iter().map(|variant| &variant.ident).collect();
+    let value_variants: Vec<_> = value_variants
+        .iter()
+        .map(|variant| &variant.ident)
+        .collect();
+    let size_impl = if estimated_size {
+        quote! {
+            impl ::protto::size::EstimatedProtoSize for #name {
+                fn estimated_proto_size(&self) -> usize {
+                    match self {
+                        #(Self::#value_variants(value) => ::protto::size::field_size(value),)*
+                        #(Self::#unit_variants => ::protto::size::KEY_LEN + 1,)*
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let impls = oneofs.iter().map(|oneof| {
+        // prost nests a oneof enum in the module of its message, which sits in the package's
+        // module under a nested layout
+        let oneof = quote!(#oneof).to_string().replace(' ', "");
+        let oneof_module = proto_metadata::resolve_module(proto_module, &oneof);
+        let oneof_path: syn::Path = syn::parse_str(&format!("{oneof_module}::{oneof}"))
+            .expect("Failed to parse oneof enum path");
+
+        let from_proto = if non_exhaustive {
+            let target = name.to_string();
+            quote! {
+                impl TryFrom<#oneof_path> for #name {
+                    type Error = ::protto::oneof::UnmappedOneofVariant;
+
+                    fn try_from(proto_oneof: #oneof_path) -> Result<Self, Self::Error> {
+                        #[allow(unreachable_patterns)]
+                        match proto_oneof {
+                            #(#oneof_path::#value_variants(value) => Ok(Self::#value_variants(value.into())),)*
+                            #(#oneof_path::#unit_variants(_) => Ok(Self::#unit_variants),)*
+                            _ => Err(::protto::oneof::UnmappedOneofVariant { target: #target }),
+                        }
+                    }
+                }
+            }
+        } else {
+            quote! {
+                impl From<#oneof_path> for #name {
+                    fn from(proto_oneof: #oneof_path) -> Self {
+                        match proto_oneof {
+                            #(#oneof_path::#value_variants(value) => Self::#value_variants(value.into()),)*
+                            #(#oneof_path::#unit_variants(_) => Self::#unit_variants,)*
+                        }
+                    }
+                }
+            }
+        };
+
+        quote! {
+            #from_proto
+
+            impl From<#name> for #oneof_path {
+                fn from(rust_enum: #name) -> Self {
+                    match rust_enum {
+                        #(#name::#value_variants(value) => Self::#value_variants(value.into()),)*
+                        #(#name::#unit_variants => Self::#unit_variants(::core::default::Default::default()),)*
+                    }
+                }
+            }
+        }
+    });
+
+    quote! {
+        #(#impls)*
+        #size_impl
+    }
+}
+
+fn generate_from_proto_enum_arms(
+    variants: &syn::punctuated::Punctuated<syn::Variant, syn::token::Comma>,
+    name: &syn::Ident,
+    enum_prefix: &str,
+) -> Vec<proc_macro2::TokenStream> {
+    variants
+        .iter()
+        .map(|variant| {
+            let variant_ident = &variant.ident;
+            let candidates = str_name_candidates(variant_ident, enum_prefix);
+
+            // The PascalCase candidate is future-proofing for non-standard proto enums: prost's
+            // as_str_name() currently always returns the name as written in the .proto file
+            quote! {
+                candidate if #(candidate == #candidates)||* => #name::#variant_ident,
+            }
+        })
+        .collect()
+}
+
+fn generate_from_proto_arms(
+    variants: &syn::punctuated::Punctuated<syn::Variant, syn::token::Comma>,
+    name: &syn::Ident,
+    enum_prefix: &str,
+    proto_enum_path: &syn::Path,
+) -> Vec<proc_macro2::TokenStream> {
+    variants
+        .iter()
+        .map(|variant| {
+            let variant_ident = &variant.ident;
+            let candidates = str_name_candidates(variant_ident, enum_prefix);
+            let (first, rest) = candidates[1..]
+                .split_first()
+                .expect("a variant has SCREAMING_SNAKE_CASE candidates");
+
+            quote! {
+                #name::#variant_ident => <#proto_enum_path>::from_str_name(#first)
+                    #(.or_else(|| <#proto_enum_path>::from_str_name(#rest)))*
+                    .unwrap_or_else(|| panic!("No matching proto variant for {rust_enum:?}")),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variants_of(input: &str) -> syn::punctuated::Punctuated<syn::Variant, syn::token::Comma> {
+        let ast: syn::DeriveInput = syn::parse_str(input).unwrap();
+        let syn::Data::Enum(data_enum) = ast.data else {
+            panic!("expected enum");
+        };
+        data_enum.variants
+    }
+
+    #[test]
+    fn test_discriminants_convert_by_number() {
+        let variants = variants_of("enum Status { Unknown, Active = 1, Disabled = 5 }");
+        let code =
+            generate_enum_conversions(&syn::parse_quote!(Status), &variants, "proto").to_string();
+        assert!(code.contains("value if value == Status :: Disabled as i32 => Status :: Disabled"));
+        assert!(code.contains("< proto :: Status > :: from_i32 (value)"));
+        assert!(code.contains("Self :: from (proto_enum as i32)"));
+        assert!(!code.contains("as_str_name"));
+
+        let code = generate_enum_conversions(
+            &syn::parse_quote!(Status),
+            &variants_of("enum Status { Unknown, Active }"),
+            "proto",
+        )
+        .to_string();
+        assert!(code.contains("as_str_name"));
+    }
+
+    #[test]
+    fn test_discriminants_missing_from_proto_enum_are_reported() {
+        let variants = variants_of("enum Status { Unknown, Active = 1, Paused, Disabled = -5 }");
+        let numbers: Vec<_> = discriminant_values(&variants)
+            .into_iter()
+            .map(|(_, number)| number)
+            .collect();
+        assert_eq!(numbers, [Some(0), Some(1), Some(2), Some(-5)]);
+
+        let values = [
+            ("STATUS_UNKNOWN".to_string(), 0),
+            ("STATUS_ACTIVE".to_string(), 1),
+            ("STATUS_DISABLED".to_string(), 5),
+        ];
+        let errors: Vec<_> = check_discriminants(&variants, "service.Status", &values)
+            .unwrap()
+            .into_iter()
+            .map(|err| err.to_string())
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                "`Paused` = 2 is not a value of proto enum `service.Status`, which declares \
+                STATUS_UNKNOWN = 0, STATUS_ACTIVE = 1, STATUS_DISABLED = 5",
+                "`Disabled` = -5 is not a value of proto enum `service.Status`, which declares \
+                STATUS_UNKNOWN = 0, STATUS_ACTIVE = 1, STATUS_DISABLED = 5",
+            ]
+        );
+
+        let variants = variants_of("enum Status { Unknown, Active = 1, Disabled = 5 }");
+        assert!(check_discriminants(&variants, "service.Status", &values).is_none());
+    }
+
+    fn job_state_values() -> Vec<(String, i32)> {
+        [
+            ("JOB_STATE_UNSPECIFIED", 0),
+            ("JOB_STATE_ACTIVE", 1),
+            ("JOB_STATE_RUNNING", 1),
+            ("JOB_STATE_DONE", 3),
+        ]
+        .into_iter()
+        .map(|(value_name, number)| (value_name.to_string(), number))
+        .collect()
+    }
+
+    #[test]
+    fn test_proto_values_convert_by_number_including_aliases() {
+        let variants = variants_of("enum JobState { Unspecified, Active, Running, Done }");
+        let code = generate_numbered_enum_conversions(
+            &syn::parse_quote!(JobState),
+            &variants,
+            "JOB_STATE",
+            &syn::parse_quote!(proto::JobState),
+            "jobs.JobState",
+            &job_state_values(),
+        )
+        .unwrap()
+        .to_string();
+        assert!(code.contains("1i32 => JobState :: Active ,"));
+        assert!(!code.contains("1i32 => JobState :: Running ,"));
+        assert!(code.contains("JobState :: Running => 1i32 ,"));
+        assert!(code.contains("value => panic ! (\"Unknown enum value: {}\" , value)"));
+        assert!(code.contains("< proto :: JobState > :: from_i32 (value)"));
+        assert!(!code.contains("as_str_name"));
+        assert!(!code.contains("compile_error"));
+
+        let code = generate_numbered_enum_conversions(
+            &syn::parse_quote!(JobState),
+            &variants_of("enum JobState { Unspecified, Running }"),
+            "JOB_STATE",
+            &syn::parse_quote!(proto::JobState),
+            "jobs.JobState",
+            &job_state_values(),
+        )
+        .unwrap()
+        .to_string();
+        assert!(code.contains(
+            "enum `JobState` has no variant for JOB_STATE_DONE = 3 of proto enum `jobs.JobState`"
+        ));
+    }
+
+    #[test]
+    fn test_variant_names_follow_prost() {
+        let variants = variants_of("enum ServerKind { HTTPServer2, GrpcServer }");
+        let values = [
+            ("SERVER_KIND_HTTP_SERVER_2".to_string(), 0),
+            ("SERVER_KIND_GRPC_SERVER".to_string(), 1),
+        ];
+        let code = generate_numbered_enum_conversions(
+            &syn::parse_quote!(ServerKind),
+            &variants,
+            "SERVER_KIND",
+            &syn::parse_quote!(proto::ServerKind),
+            "net.ServerKind",
+            &values,
+        )
+        .unwrap()
+        .to_string();
+        assert!(code.contains("0i32 => ServerKind :: HTTPServer2 ,"));
+        assert!(code.contains("1i32 => ServerKind :: GrpcServer ,"));
+
+        let candidates = str_name_candidates(&variants[0].ident, "SERVER_KIND");
+        assert_eq!(
+            candidates,
+            [
+                "HTTPServer2",
+                "SERVER_KIND_HTTP_SERVER2",
+                "HTTP_SERVER2",
+                "SERVER_KIND_HTTP_SERVER_2",
+                "HTTP_SERVER_2",
+            ]
+        );
+        let arms = generate_from_proto_arms(
+            &variants,
+            &syn::parse_quote!(ServerKind),
+            "SERVER_KIND",
+            &syn::parse_quote!(proto::ServerKind),
+        );
+        assert!(arms[1].to_string().contains(
+            "from_str_name (\"SERVER_KIND_GRPC_SERVER\") . or_else (|| < proto :: ServerKind > :: \
+             from_str_name (\"GRPC_SERVER\")) . unwrap_or_else"
+        ));
+    }
+
+    #[test]
+    fn test_variants_matching_no_proto_value_are_reported() {
+        let Err(err) = generate_numbered_enum_conversions(
+            &syn::parse_quote!(JobState),
+            &variants_of("enum JobState { Unspecified, Paused, Done }"),
+            "JOB_STATE",
+            &syn::parse_quote!(proto::JobState),
+            "jobs.JobState",
+            &job_state_values(),
+        ) else {
+            panic!("a variant matching no proto value is an error");
+        };
+        assert_eq!(
+            err.to_string(),
+            "`Paused` matches no value of proto enum `jobs.JobState`, looking for `Paused`, \
+            `JOB_STATE_PAUSED`, `PAUSED`; it declares JOB_STATE_UNSPECIFIED = 0, \
+            JOB_STATE_ACTIVE = 1, JOB_STATE_RUNNING = 1, JOB_STATE_DONE = 3"
+        );
+
+        let variants = variants_of("enum JobState { Unspecified = 0, Active = 1 }");
+        let numbers: Vec<_> = discriminant_values(&variants)
+            .into_iter()
+            .filter_map(|(_, number)| number)
+            .collect();
+        let err = check_coverage(
+            &syn::parse_quote!(JobState),
+            "jobs.JobState",
+            &job_state_values(),
+            |value| numbers.contains(&i64::from(value)),
+        )
+        .unwrap();
+        assert!(
+            err.to_string()
+                .contains("no variant for JOB_STATE_DONE = 3")
+        );
+
+        // Fields of the enum handle the unspecified value with `unspecified`
+        let err = check_coverage(
+            &syn::parse_quote!(JobState),
+            "jobs.JobState",
+            &job_state_values(),
+            |value| value == 1,
+        )
+        .unwrap();
+        assert!(
+            err.to_string()
+                .contains("no variant for JOB_STATE_DONE = 3 of")
+        );
+        assert!(
+            check_coverage(
+                &syn::parse_quote!(JobState),
+                "jobs.JobState",
+                &job_state_values(),
+                |value| value != 0,
+            )
+            .is_none()
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use crate::analysis::expect_analysis::ExpectMode;
+use crate::analysis::type_analysis::{self, TypeKind};
+use crate::analysis::{attribute_parser, proto_metadata};
+use crate::constants::DEFAULT_CONVERSION_ERROR_SUFFIX;
+use quote::quote;
+
+#[derive(Clone)]
+pub struct FieldProcessingContext<'a> {
+    pub struct_name: &'a syn::Ident,
+    pub struct_generics: &'a syn::Generics,
+    pub field_name: &'a syn::Ident,
+    pub field_type: &'a syn::Type,
+    pub proto_field_ident: syn::Ident,
+    pub protto_meta: attribute_parser::ProtoFieldMeta,
+    pub expect_mode: ExpectMode,
+    pub has_default: bool,
+    pub default_fn: Option<String>,
+    pub struct_level_error_type: &'a Option<syn::Type>,
+    pub struct_level_error_fn: &'a Option<String>,
+    pub struct_level_error_fn_with_value: bool,
+    pub error_mod: &'a Option<syn::Ident>,
+    pub static_errors: bool,
+    pub proto_module: &'a str,
+    pub proto_name: &'a str,
+}
+
+impl<'a> std::fmt::Debug for FieldProcessingContext<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let error_type = self
+            .struct_level_error_type
+            .as_ref()
+            .map(|et| quote! { #et }.to_string());
+
+        f.debug_struct("FieldProcessingContext")
+            .field("struct_name", &self.struct_name)
+            .field("field_name", &self.field_name)
+            .field("proto_field_ident", &self.proto_field_ident)
+            .field("proto_meta", &self.protto_meta)
+            .field("expect_mode", &self.expect_mode)
+            .field("has_default", &self.has_default)
+            .field("default_fn", &self.default_fn)
+            .field("struct_level_error_type", &error_type)
+            .field("struct_level_error_fn", &self.struct_level_error_fn)
+            .field(
+                "struct_level_error_fn_with_value",
+                &self.struct_level_error_fn_with_value,
+            )
+            .field("error_mod", &self.error_mod)
+            .field("static_errors", &self.static_errors)
+            .field("proto_module", &self.proto_module)
+            .field("proto_name", &self.proto_name)
+            .finish()
+    }
+}
+
+impl<'a> FieldProcessingContext<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        struct_name: &'a syn::Ident,
+        struct_generics: &'a syn::Generics,
+        field: &'a syn::Field,
+        struct_level_error_type: &'a Option<syn::Type>,
+        struct_level_error_fn: &'a Option<String>,
+        struct_level_error_fn_with_value: bool,
+        error_mod: &'a Option<syn::Ident>,
+        static_errors: bool,
+        proto_module: &'a str,
+        proto_name: &'a str,
+    ) -> Self {
+        let field_name = field.ident.as_ref().unwrap();
+        let field_type = &field.ty;
+        let proto_meta = attribute_parser::ProtoFieldMeta::from_field(field).unwrap_or_default();
+        let expect_mode = ExpectMode::from_field_meta(field, &proto_meta);
+        let has_default = proto_meta.default_fn.is_some();
+        let default_fn = proto_meta.default_fn.clone();
+
+        let explicit_proto_name = attribute_parser::get_proto_field_name(field)
+            .or_else(|| proto_meta.oneof.clone())
+            .or_else(|| proto_meta.presence_of.clone());
+        let proto_field_ident = match proto_metadata::prost_field_ident(
+            proto_module,
+            proto_name,
+            &explicit_proto_name
+                .clone()
+                .unwrap_or_else(|| crate::utils::ident_name(field_name)),
+        )
+        .or(explicit_proto_name)
+        {
+            Some(proto_field_name) => crate::utils::field_ident(&proto_field_name),
+            None => field_name.clone(),
+        };
+
+        Self {
+            struct_name,
+            struct_generics,
+            field_name,
+            field_type,
+            proto_field_ident,
+            protto_meta: proto_meta,
+            expect_mode,
+            has_default,
+            default_fn,
+            struct_level_error_type,
+            struct_level_error_fn,
+            struct_level_error_fn_with_value,
+            error_mod,
+            static_errors,
+            proto_module,
+            proto_name,
+        }
+    }
+
+    /// Path of a custom conversion function, spanned at the field. Qualified paths such as
+    /// `<T as FromProto>::from_proto` and turbofish paths such as `convert::<T>` name the struct's
+    /// generic parameters as written; `Self` is desugared to the struct type, since the generated
+    /// code is not always inside an impl for the struct.
+    pub fn custom_fn_path(&self, fn_path: &str) -> syn::ExprPath {
+        let tokens: proc_macro2::TokenStream = syn::LitStr::new(fn_path, self.field_name.span())
+            .parse()
+            .expect("Failed to parse function path");
+        let struct_name = self.struct_name;
+        let (_, ty_generics, _) = self.struct_generics.split_for_impl();
+        let struct_type = quote! { #struct_name #ty_generics };
+        syn::parse2(desugar_self(tokens, &struct_type, true))
+            .expect("Failed to parse function path")
+    }
+
+    pub fn has_error_fn(&self) -> bool {
+        self.struct_level_error_fn.is_some() || self.field_level_error_fn().is_some()
+    }
+
+    pub fn get_effective_field_error_fn(&self) -> Option<syn::Path> {
+        self.field_level_error_fn()
+            .as_ref()
+            .or(self.struct_level_error_fn.as_ref())
+            .map(|error_fn| {
+                syn::parse_str::<syn::Path>(error_fn).expect("Failed to parse error function path")
+            })
+    }
+
+    pub fn field_level_error_fn(&self) -> &Option<String> {
+        &self.protto_meta.error_fn
+    }
+
+    /// Whether the effective error_fn was given as `error_fn_with_value`
+    pub fn error_fn_takes_value(&self) -> bool {
+        if self.field_level_error_fn().is_some() {
+            self.protto_meta.error_fn_with_value
+        } else {
+            self.struct_level_error_fn_with_value
+        }
+    }
+
+    /// Call to the effective error_fn, if there is one. An `error_fn_with_value` also receives
+    /// the offending proto value, or `None` when the proto field is absent.
+    pub fn error_fn_call(
+        &self,
+        proto_field: &syn::Ident,
+        value: Option<proc_macro2::TokenStream>,
+    ) -> Option<proc_macro2::TokenStream> {
+        let error_fn = self.get_effective_field_error_fn()?;
+        let proto_field_name = crate::utils::ident_name(proto_field);
+        if self.error_fn_takes_value() {
+            let value = value.unwrap_or_else(|| quote! { ::core::option::Option::<()>::None });
+            Some(quote! { #error_fn(#proto_field_name, &#value) })
+        } else {
+            Some(quote! { #error_fn(#proto_field_name) })
+        }
+    }
+
+    /// Whether prost generates the proto field boxed, as declared by `proto_boxed` or recorded
+    /// in the build metadata
+    pub fn is_proto_boxed(&self) -> bool {
+        self.protto_meta.proto_boxed
+            || proto_metadata::lookup_field(
+                self.proto_module,
+                self.proto_name,
+                &self.proto_field_ident.to_string(),
+            )
+            .is_some_and(|schema| schema.boxed)
+    }
+
+    /// Whether the proto field holds a `google.protobuf` wrapper message per the build metadata;
+    /// only consulted with the `wrapper_types` feature
+    pub fn is_proto_wrapper(&self) -> bool {
+        cfg!(feature = "wrapper_types")
+            && proto_metadata::lookup_field(
+                self.proto_module,
+                self.proto_name,
+                &self.proto_field_ident.to_string(),
+            )
+            .is_some_and(|schema| schema.is_wrapper())
+    }
+
+    /// The `kind` a type is declared to be. It applies to the field's type, or to the element of
+    /// an `Option`, `Vec` or map field; the wrappers keep their own classification. A field with
+    /// `unspecified` is an enum.
+    fn declared_kind(&self, ty: &syn::Type) -> Option<TypeKind> {
+        let kind = self.protto_meta.kind.or(self
+            .protto_meta
+            .unspecified
+            .as_ref()
+            .map(|_| TypeKind::Enum));
+        kind.filter(|_| {
+            !type_analysis::is_option_type(ty)
+                && !type_analysis::is_vec_type(ty)
+                && !type_analysis::is_map_type(ty)
+        })
+    }
+
+    /// `type_analysis::is_primitive_type`, unless `kind` says otherwise
+    pub fn is_primitive_type(&self, ty: &syn::Type) -> bool {
+        match self.declared_kind(ty) {
+            Some(kind) => kind == TypeKind::Primitive,
+            None => type_analysis::is_primitive_type(ty),
+        }
+    }
+
+    /// `type_analysis::is_custom_type`, unless `kind` says otherwise
+    pub fn is_custom_type(&self, ty: &syn::Type) -> bool {
+        match self.declared_kind(ty) {
+            Some(kind) => kind != TypeKind::Primitive,
+            None => type_analysis::is_custom_type(ty),
+        }
+    }
+
+    /// `type_analysis::is_enum_type`, unless `kind` says otherwise
+    pub fn is_enum_type(&self, ty: &syn::Type) -> bool {
+        match self.declared_kind(ty) {
+            Some(kind) => kind == TypeKind::Enum,
+            None => type_analysis::is_enum_type(ty),
+        }
+    }
+
+    /// Path to the generated conversion error, qualified by `error_mod` when it is set
+    pub fn default_error_path(&self) -> syn::Path {
+        let error_type_name = format!("{}{DEFAULT_CONVERSION_ERROR_SUFFIX}", self.struct_name);
+        let error_type_path = match self.error_mod {
+            Some(error_mod) => format!("{error_mod}::{error_type_name}"),
+            None => error_type_name,
+        };
+        syn::parse_str(&error_type_path).expect("Failed to parse error type path")
+    }
+
+    /// Field name carried by the generated error's variants: a `&'static str` under
+    /// `static_errors`, otherwise an owned `String`
+    pub fn error_field_name(&self, proto_field: &syn::Ident) -> proc_macro2::TokenStream {
+        let proto_field_name = crate::utils::ident_name(proto_field);
+        if self.static_errors {
+            quote! { #proto_field_name }
+        } else {
+            quote! { #proto_field_name.to_string() }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CollectionType {
+    Map,
+    Vec,
+    Set,
+    Deque,
+}
+
+impl CollectionType {
+    pub fn from_field_type(field_type: &syn::Type) -> Option<Self> {
+        let type_str = quote!(#field_type).to_string();
+
+        if type_str.contains("HashMap") || type_str.contains("BTreeMap") {
+            Some(Self::Map)
+        } else if type_str.contains("Vec") {
+            Some(Self::Vec)
+        } else if type_str.contains("HashSet") || type_str.contains("BTreeSet") {
+            Some(Self::Set)
+        } else if type_str.contains("VecDeque") {
+            Some(Self::Deque)
+        } else {
+            None
+        }
+    }
+}
+
+/// Replaces `Self` with the struct type; leading a path (`Self::from_proto`), the type is
+/// written qualified as `<Struct<T>>::from_proto`.
+fn desugar_self(
+    tokens: proc_macro2::TokenStream,
+    struct_type: &proc_macro2::TokenStream,
+    leading: bool,
+) -> proc_macro2::TokenStream {
+    use proc_macro2::TokenTree;
+
+    let mut leading = leading;
+    tokens
+        .into_iter()
+        .map(|token| {
+            let at_start = std::mem::take(&mut leading);
+            match token {
+                TokenTree::Ident(ident) if ident == "Self" && at_start => {
+                    quote! { <#struct_type> }
+                }
+                TokenTree::Ident(ident) if ident == "Self" => struct_type.clone(),
+                TokenTree::Group(group) => {
+                    let mut desugared = proc_macro2::Group::new(
+                        group.delimiter(),
+                        desugar_self(group.stream(), struct_type, false),
+                    );
+                    desugared.set_span(group.span());
+                    TokenTree::Group(desugared).into()
+                }
+                token => token.into(),
+            }
+        })
+        .collect()
+}
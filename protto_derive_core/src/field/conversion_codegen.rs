@@ -0,0 +1,3711 @@
+use crate::analysis::{
+    attribute_parser::{BoolEnumMapping, ResultOneofMapping, UnknownVariantPolicy},
+    expect_analysis::ExpectMode,
+    optionality::NonePolicy,
+    type_analysis::{
+        self, BoundedCollection, CapacityOverflow, DecimalAs, DurationUnit, EmptyRepeated,
+        PathEncoding, UnknownI32Policy, UnspecifiedPolicy,
+    },
+};
+use crate::debug::CallStackDebug;
+use crate::field::{
+    FieldProcessingContext,
+    conversion_strategy::{
+        CollectionStrategy, DirectStrategy, FieldConversionStrategy, OptionStrategy,
+    },
+    custom_conversion::CustomConversionStrategy,
+    error_mode::ErrorMode,
+    info::{ProtoFieldInfo, RustFieldInfo},
+};
+use quote::quote;
+
+impl FieldConversionStrategy {
+    /// Wraps a generated `field: expr` conversion in a `tracing` trace span named after the
+    /// direction and recording the struct and field, for the `trace-runtime` feature
+    pub fn instrument(
+        &self,
+        ctx: &FieldProcessingContext,
+        span_name: &str,
+        conversion: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let Ok(syn::FieldValue { member, expr, .. }) =
+            syn::parse2::<syn::FieldValue>(conversion.clone())
+        else {
+            return conversion;
+        };
+        let struct_name = ctx.struct_name.to_string();
+        let field_name = crate::utils::ident_name(ctx.field_name);
+
+        quote! {
+            #member: {
+                let _span = ::protto::trace::tracing::trace_span!(
+                    #span_name,
+                    "struct" = #struct_name,
+                    field = #field_name
+                )
+                .entered();
+                #expr
+            }
+        }
+    }
+
+    /// Generate proto->rust conversion code using new simplified logic
+    pub fn generate_proto_to_rust_conversion(
+        &self,
+        ctx: &FieldProcessingContext,
+        field: &syn::Field,
+        rust_field_info: &RustFieldInfo,
+        proto_field_info: &ProtoFieldInfo,
+    ) -> proc_macro2::TokenStream {
+        let field_name = ctx.field_name;
+        let proto_field = &ctx.proto_field_ident;
+
+        match self {
+            Self::Ignore => generate_ignore_proto_to_rust(ctx),
+
+            // Bound from the FromProtoParts impl before the derived fields are converted
+            Self::Manual => quote! { #field_name },
+
+            Self::Custom(custom_strategy) => generate_custom_proto_to_rust(
+                custom_strategy,
+                field,
+                field_name,
+                proto_field,
+                None,
+                ctx,
+            ),
+
+            Self::CustomWithError(custom_strategy, error_mode) => generate_custom_proto_to_rust(
+                custom_strategy,
+                field,
+                field_name,
+                proto_field,
+                Some(error_mode),
+                ctx,
+            ),
+
+            Self::Direct(direct_strategy) => {
+                generate_direct_proto_to_rust(direct_strategy, field_name, proto_field, ctx)
+            }
+
+            Self::Option(option_strategy) => generate_option_proto_to_rust(
+                option_strategy,
+                field_name,
+                proto_field,
+                ctx,
+                rust_field_info,
+                proto_field_info,
+            ),
+
+            Self::Transparent(error_mode) => generate_transparent_proto_to_rust(
+                error_mode,
+                ctx,
+                rust_field_info,
+                proto_field_info,
+            ),
+
+            Self::Collection(collection_strategy) => {
+                generate_collection_proto_to_rust(collection_strategy, ctx)
+            }
+
+            Self::Path(encoding) => {
+                generate_path_proto_to_rust(encoding, field_name, proto_field, rust_field_info)
+            }
+
+            Self::Wrapper => quote! {
+                #field_name: proto_struct.#proto_field.map(|wrapper| {
+                    ::protto::wrappers::WrapperValue::into_value(wrapper).into()
+                })
+            },
+
+            Self::Bytes if rust_field_info.is_option => {
+                quote! { #field_name: proto_struct.#proto_field.map(Into::into) }
+            }
+            Self::Bytes => quote! { #field_name: proto_struct.#proto_field.into() },
+
+            Self::Group(mappings) => generate_group_proto_to_rust(mappings, ctx),
+
+            Self::BoolEnum(mapping) => generate_bool_enum_proto_to_rust(mapping, ctx),
+
+            Self::ResultOneof(mapping, error_mode) => {
+                generate_result_oneof_proto_to_rust(mapping, error_mode, ctx)
+            }
+
+            Self::EnumAsI32(policy) => {
+                generate_enum_as_i32_proto_to_rust(policy, ctx, rust_field_info)
+            }
+
+            Self::EnumMap(policy) => generate_enum_map_proto_to_rust(policy, ctx),
+
+            Self::EnumKeyMap(policy) => generate_enum_key_map_proto_to_rust(policy, ctx),
+
+            Self::EnumUnspecified(policy) => {
+                generate_unspecified_proto_to_rust(policy, ctx, rust_field_info, proto_field_info)
+            }
+
+            Self::Boxed(error_mode) => generate_boxed_proto_to_rust(error_mode, ctx),
+
+            Self::PresenceOf => quote! { #field_name: proto_struct.#proto_field.is_some() },
+
+            Self::PackedBytes(_) => {
+                let error_expr = generate_field_error_expr(
+                    ctx,
+                    proto_field,
+                    quote! { InvalidValue },
+                    Some(quote! { proto_struct.#proto_field }),
+                );
+                quote! {
+                    #field_name: ::protto::pod::from_bytes(&proto_struct.#proto_field)
+                        .map_err(|_| #error_expr)?
+                }
+            }
+
+            Self::Decimal(decimal) => generate_decimal_proto_to_rust(decimal, ctx, rust_field_info),
+            Self::Duration(unit) => generate_duration_proto_to_rust(*unit, ctx, rust_field_info),
+            Self::WithStr => generate_with_str_proto_to_rust(ctx, rust_field_info),
+            Self::Json => generate_json_proto_to_rust(ctx, rust_field_info),
+            Self::MapBy(key) => {
+                let key = crate::utils::field_ident(key);
+                quote! {
+                    #field_name: proto_struct.#proto_field
+                        .into_iter()
+                        .map(|element| (element.#key.clone().into(), element.into()))
+                        .collect()
+                }
+            }
+
+            Self::Bounded(collection, overflow) => {
+                generate_bounded_proto_to_rust(collection, overflow, ctx)
+            }
+        }
+    }
+
+    /// Generate rust->proto conversion code using new simplified logic
+    pub fn generate_rust_to_proto_conversion(
+        &self,
+        ctx: &FieldProcessingContext,
+        _field: &syn::Field,
+        rust_field_info: &RustFieldInfo,
+        proto_field_info: &ProtoFieldInfo,
+    ) -> proc_macro2::TokenStream {
+        let field_name = ctx.field_name;
+        let proto_field = &ctx.proto_field_ident;
+
+        match self {
+            Self::Ignore => {
+                // Ignored fields are not included in proto struct
+                quote! { /* field ignored */ }
+            }
+
+            // Written by the FromProtoParts impl once the derived fields are in place
+            Self::Manual => quote! { #proto_field: ::core::default::Default::default() },
+
+            Self::Custom(custom_strategy) | Self::CustomWithError(custom_strategy, _) => {
+                generate_custom_rust_to_proto(
+                    custom_strategy,
+                    ctx,
+                    proto_field,
+                    rust_field_info,
+                    proto_field_info,
+                )
+            }
+
+            Self::Direct(direct_strategy) => {
+                generate_direct_rust_to_proto(direct_strategy, ctx, field_name, proto_field)
+            }
+
+            Self::Option(option_strategy) => generate_option_rust_to_proto(
+                option_strategy,
+                ctx,
+                field_name,
+                proto_field,
+                rust_field_info,
+                proto_field_info,
+            ),
+
+            Self::Transparent(error_mode) => generate_transparent_rust_to_proto(
+                error_mode,
+                field_name,
+                proto_field,
+                rust_field_info,
+                proto_field_info,
+            ),
+
+            Self::Collection(collection_strategy) => {
+                generate_collection_rust_to_proto(collection_strategy, ctx)
+            }
+
+            Self::Path(encoding) => {
+                generate_path_rust_to_proto(encoding, ctx, field_name, proto_field, rust_field_info)
+            }
+
+            Self::Wrapper => quote! {
+                #proto_field: #field_name.map(|value| {
+                    ::protto::wrappers::WrapperValue::from_value(value.into())
+                })
+            },
+
+            Self::Bytes if rust_field_info.is_option => {
+                quote! { #proto_field: #field_name.map(Into::into) }
+            }
+            Self::Bytes => quote! { #proto_field: #field_name.into() },
+
+            Self::Group(mappings) => generate_group_rust_to_proto(mappings, field_name),
+
+            Self::BoolEnum(mapping) => generate_bool_enum_rust_to_proto(mapping, ctx),
+
+            Self::ResultOneof(mapping, _) => {
+                generate_result_oneof_rust_to_proto(mapping, ctx, rust_field_info)
+            }
+
+            Self::EnumAsI32(_) if rust_field_info.is_option => {
+                quote! { #proto_field: #field_name.map(|value| value as i32) }
+            }
+            Self::EnumAsI32(_) => quote! { #proto_field: #field_name as i32 },
+
+            Self::EnumMap(_) => quote! {
+                #proto_field: #field_name
+                    .into_iter()
+                    .map(|(key, value)| (key, i32::from(value)))
+                    .collect()
+            },
+
+            // A BTreeMap is walked in key order, so the proto map is filled deterministically
+            Self::EnumKeyMap(_) => quote! {
+                #proto_field: #field_name
+                    .into_iter()
+                    .map(|(key, value)| (key as i32, value.into()))
+                    .collect()
+            },
+
+            // The rust enum has no variant for 0, so only a `None` writes it
+            Self::EnumUnspecified(_) => {
+                match (rust_field_info.is_option, proto_field_info.is_optional()) {
+                    (false, false) => quote! { #proto_field: i32::from(#field_name) },
+                    (false, true) => quote! { #proto_field: Some(i32::from(#field_name)) },
+                    (true, false) => quote! { #proto_field: #field_name.map_or(0, i32::from) },
+                    (true, true) => quote! { #proto_field: #field_name.map(i32::from) },
+                }
+            }
+
+            Self::PackedBytes(_) => {
+                quote! { #proto_field: ::protto::pod::to_bytes(&#field_name) }
+            }
+
+            Self::Decimal(decimal) => generate_decimal_rust_to_proto(decimal, ctx, rust_field_info),
+            Self::Duration(unit) => generate_duration_rust_to_proto(*unit, ctx, rust_field_info),
+            Self::WithStr => generate_with_str_rust_to_proto(ctx, rust_field_info),
+            Self::Json => generate_json_rust_to_proto(ctx, rust_field_info),
+
+            // A HashMap iterates in no particular order, so the elements are sorted by key
+            Self::MapBy(_) => quote! {
+                #proto_field: {
+                    let mut entries: ::std::vec::Vec<_> = #field_name.into_iter().collect();
+                    entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+                    entries.into_iter().map(|(_, value)| value.into()).collect()
+                }
+            },
+
+            Self::Boxed(_) => generate_boxed_rust_to_proto(ctx, rust_field_info),
+
+            // A bool cannot rebuild the proto field, so it is left unset
+            Self::PresenceOf => quote! { #proto_field: ::core::option::Option::None },
+
+            Self::Bounded(_, _) => {
+                quote! { #proto_field: #field_name.into_iter().map(Into::into).collect() }
+            }
+        }
+    }
+}
+
+// -- Proto-to-Rust generation functions --
+fn generate_ignore_proto_to_rust(ctx: &FieldProcessingContext) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+
+    let _trace = CallStackDebug::new(
+        "field::conversion_codegen",
+        "generate_ignore_proto_to_rust",
+        ctx.struct_name,
+        field_name,
+    );
+
+    if let Some(default_fn_name) = &ctx.default_fn {
+        let default_fn_path: syn::Path =
+            syn::parse_str(default_fn_name).expect("Failed to parse default_fn function path");
+        quote! { #field_name: #default_fn_path() }
+    } else {
+        quote! { #field_name: Default::default() }
+    }
+}
+
+fn generate_custom_proto_to_rust(
+    custom_strategy: &CustomConversionStrategy,
+    field: &syn::Field,
+    field_name: &syn::Ident,
+    proto_field: &syn::Ident,
+    _error_mode: Option<&ErrorMode>,
+    ctx: &FieldProcessingContext,
+) -> proc_macro2::TokenStream {
+    let rust_field_info = RustFieldInfo::analyze(ctx, field);
+    let proto_field_info = ProtoFieldInfo::infer_from(ctx, field, &rust_field_info);
+
+    let _trace = CallStackDebug::new(
+        "field::conversion_codegen",
+        "generate_custom_proto_to_rust",
+        ctx.struct_name,
+        field_name,
+    );
+
+    match custom_strategy {
+        CustomConversionStrategy::FromFn(fn_path)
+        | CustomConversionStrategy::Bidirectional(fn_path, _) => {
+            let from_fn = ctx.custom_fn_path(fn_path);
+            let proto_field_name = crate::utils::ident_name(proto_field);
+            // `from_proto_fn_ref` functions borrow the proto field instead of consuming it
+            let (proto_value, as_ref) = if ctx.protto_meta.from_proto_by_ref {
+                (quote! { &proto_struct.#proto_field }, quote! { .as_ref() })
+            } else {
+                (quote! { proto_struct.#proto_field }, quote! {})
+            };
+
+            // `depends_on` fields are converted first into locals of the same name
+            let dependencies = ctx
+                .protto_meta
+                .depends_on
+                .iter()
+                .map(|name| crate::utils::field_ident(name));
+
+            let call = if proto_field_info.is_optional()
+                && !proto_field_info.is_repeated()
+                && !rust_field_info.is_option
+            {
+                quote! {
+                    #from_fn(
+                        proto_struct.#proto_field #as_ref .expect(&format!(
+                            "Proto field {} is required for custom conversion",
+                            #proto_field_name
+                        ))
+                        #(, &#dependencies)*
+                    )
+                }
+            } else {
+                quote! { #from_fn(#proto_value #(, &#dependencies)*) }
+            };
+
+            if ctx.protto_meta.from_proto_fallible {
+                let converted = generate_fallible_custom_call(ctx, proto_field, call);
+                quote! { #field_name: #converted }
+            } else {
+                quote! { #field_name: #call }
+            }
+        }
+        CustomConversionStrategy::IntoFn(_) => {
+            // Fallback to .into() for proto->rust when only rust->proto function provided
+            quote! { #field_name: proto_struct.#proto_field.into() }
+        }
+    }
+}
+
+/// Unwraps the `Result` of a `try_from_proto_fn`: panics under `expect(panic)`, converts the
+/// error with `From` into a struct-level `error_type` without an `error_fn`, and otherwise reports
+/// it through the `error_fn` or as `InvalidValue`
+fn generate_fallible_custom_call(
+    ctx: &FieldProcessingContext,
+    proto_field: &syn::Ident,
+    call: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if ctx.expect_mode == ExpectMode::Panic {
+        let proto_field_name = crate::utils::ident_name(proto_field);
+        quote! {
+            #call.expect(&format!("Proto field {} could not be converted", #proto_field_name))
+        }
+    } else if ctx.struct_level_error_type.is_some() && !ctx.has_error_fn() {
+        quote! { #call? }
+    } else if ctx.error_fn_takes_value() {
+        let error_expr = generate_field_error_expr(
+            ctx,
+            proto_field,
+            quote! { InvalidValue },
+            Some(quote! { err }),
+        );
+        quote! { #call.map_err(|err| #error_expr)? }
+    } else {
+        let error_expr = generate_field_error_expr(ctx, proto_field, quote! { InvalidValue }, None);
+        quote! { #call.map_err(|_| #error_expr)? }
+    }
+}
+
+fn generate_direct_proto_to_rust(
+    direct_strategy: &DirectStrategy,
+    field_name: &syn::Ident,
+    proto_field: &syn::Ident,
+    ctx: &FieldProcessingContext,
+) -> proc_macro2::TokenStream {
+    let _trace = CallStackDebug::new(
+        "field::conversion_codegen",
+        "generate_direct_proto_to_rust",
+        ctx.struct_name,
+        field_name,
+    );
+
+    match direct_strategy {
+        DirectStrategy::Assignment => {
+            quote! { #field_name: proto_struct.#proto_field }
+        }
+        DirectStrategy::WithConversion => {
+            let converted = into_rust(ctx, ctx.field_type, quote! { proto_struct.#proto_field });
+            quote! { #field_name: #converted }
+        }
+    }
+}
+
+fn generate_option_proto_to_rust(
+    option_strategy: &OptionStrategy,
+    field_name: &syn::Ident,
+    proto_field: &syn::Ident,
+    ctx: &FieldProcessingContext,
+    rust_field_info: &RustFieldInfo,
+    proto_field_info: &ProtoFieldInfo,
+) -> proc_macro2::TokenStream {
+    let _trace = CallStackDebug::new(
+        "field::conversion_codegen",
+        "generate_option_proto_to_rust",
+        ctx.struct_name,
+        field_name,
+    );
+
+    match option_strategy {
+        OptionStrategy::Wrap(_) => {
+            _trace.decision("wrap_option", "wrap field in Some()");
+            let converted = into_rust(
+                ctx,
+                &option_value_type(ctx.field_type),
+                quote! { proto_struct.#proto_field },
+            );
+            quote! { #field_name: Some(#converted) }
+        }
+        OptionStrategy::Unwrap(error_mode) => {
+            _trace.decision("unwrap_option", "unwrap field considering error mode");
+            generate_unwrap_with_error_mode(
+                error_mode,
+                field_name,
+                proto_field,
+                ctx,
+                rust_field_info,
+                proto_field_info,
+                &_trace,
+            )
+        }
+        OptionStrategy::Map => {
+            _trace.decision("map_option", "unwrap field and map");
+            let converted = into_rust(ctx, &option_value_type(ctx.field_type), quote! { v });
+            quote! { #field_name: proto_struct.#proto_field.map(|v| #converted) }
+        }
+        OptionStrategy::TryMap => {
+            _trace.decision("try_map_option", "map through TryFrom and transpose");
+            if ctx.error_fn_takes_value() {
+                let error_expr = generate_field_error_expr(
+                    ctx,
+                    proto_field,
+                    quote! { InvalidValue },
+                    Some(quote! { value }),
+                );
+                quote! {
+                    #field_name: proto_struct.#proto_field
+                        .map(|value| TryInto::try_into(value.clone()).map_err(|_| #error_expr))
+                        .transpose()?
+                }
+            } else {
+                let error_expr =
+                    generate_field_error_expr(ctx, proto_field, quote! { InvalidValue }, None);
+                quote! {
+                    #field_name: proto_struct.#proto_field
+                        .map(TryInto::try_into)
+                        .transpose()
+                        .map_err(|_| #error_expr)?
+                }
+            }
+        }
+    }
+}
+
+fn generate_transparent_proto_to_rust(
+    error_mode: &ErrorMode,
+    ctx: &FieldProcessingContext,
+    _rust_field_info: &RustFieldInfo,
+    proto_field_info: &ProtoFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let field_type = ctx.field_type;
+    let proto_field_name = crate::utils::ident_name(proto_field);
+
+    let _trace = CallStackDebug::new(
+        "field::conversion_codegen",
+        "generate_transparent_proto_to_rust",
+        ctx.struct_name,
+        field_name,
+    );
+
+    if let Some(collection) = type_analysis::newtype_collection(field_type) {
+        let elements =
+            convert_collection_elements(collection, quote! { proto_struct.#proto_field });
+        quote! {
+            #field_name: <#field_type as ::protto::newtype::CollectionNewtype>::from_collection(
+                #elements
+            )
+        }
+    } else if proto_field_info.is_optional() {
+        let error_message = quote! {
+            &format!("Proto field {} is required for transparent conversion", #proto_field_name)
+        };
+
+        // Check if the Rust field is Option<TransparentWrapper>
+        if let Some(inner_type) = type_analysis::get_inner_type_from_option(field_type) {
+            // This is Option<TransparentWrapper> -> proto_optional
+            match error_mode {
+                ErrorMode::None => {
+                    quote! { #field_name: proto_struct.#proto_field.map(#inner_type::from) }
+                }
+                ErrorMode::Panic => {
+                    quote! {
+                        #field_name: Some(#inner_type::from(
+                            proto_struct.#proto_field.expect(#error_message)
+                        ))
+                    }
+                }
+                ErrorMode::Error => {
+                    let error_expr =
+                        generate_field_error_expr(ctx, proto_field, quote! { MissingField }, None);
+                    quote! {
+                        #field_name: #inner_type::from(
+                            proto_struct.#proto_field
+                                .or_else(::protto::options::lenient_missing)
+                                .ok_or_else(|| #error_expr)?
+                        )
+                    }
+                }
+                ErrorMode::Default(default_fn) => {
+                    let default_expr = generate_default_expr(default_fn);
+                    quote! {
+                        #field_name: proto_struct.#proto_field.map(#inner_type::from).or_else(|| Some(#default_expr))
+                    }
+                }
+            }
+        } else {
+            let conversion_expr =
+                generate_conversion_expr(ctx, error_mode, proto_field, field_type, &error_message);
+            quote! { #field_name: #field_type::from(#conversion_expr) }
+        }
+    } else {
+        quote! { #field_name: #field_type::from(proto_struct.#proto_field) }
+    }
+}
+
+fn generate_conversion_expr(
+    ctx: &FieldProcessingContext,
+    error_mode: &ErrorMode,
+    proto_field: &syn::Ident,
+    field_type: &syn::Type,
+    error_message: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match error_mode {
+        ErrorMode::None | ErrorMode::Panic => {
+            quote! { proto_struct.#proto_field.expect(#error_message) }
+        }
+        ErrorMode::Error => {
+            let error_expr =
+                generate_field_error_expr(ctx, proto_field, quote! { MissingField }, None);
+            quote! {
+                proto_struct.#proto_field
+                    .or_else(::protto::options::lenient_missing)
+                    .ok_or_else(|| #error_expr)?
+            }
+        }
+        ErrorMode::Default(Some(default_fn)) => {
+            let default_fn_path: syn::Path =
+                syn::parse_str(default_fn).expect("Failed to parse default function");
+            quote! {
+                proto_struct.#proto_field.unwrap_or_else(|| {
+                    let default_val: #field_type = #default_fn_path();
+                    default_val.into()
+                })
+            }
+        }
+        ErrorMode::Default(None) => {
+            quote! { proto_struct.#proto_field.unwrap_or_default() }
+        }
+    }
+}
+
+fn generate_default_expr(default_fn: &Option<String>) -> proc_macro2::TokenStream {
+    default_fn
+        .as_ref()
+        .map(|default_fn| {
+            let default_fn_path: syn::Path =
+                syn::parse_str(default_fn).expect("Failed to parse default function");
+            quote! { #default_fn_path() }
+        })
+        .unwrap_or_else(|| quote! { Default::default() })
+}
+
+fn generate_collection_proto_to_rust(
+    collection_strategy: &CollectionStrategy,
+    ctx: &FieldProcessingContext,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+
+    let _trace = CallStackDebug::new(
+        "field::conversion_codegen",
+        "generate_collection_proto_to_rust",
+        ctx.struct_name,
+        field_name,
+    );
+    let converted = generate_collect(ctx, quote! { proto_struct.#proto_field }, false);
+
+    match collection_strategy {
+        CollectionStrategy::Collect(error_mode) => match error_mode {
+            ErrorMode::Default(Some(default_fn)) => {
+                let default_fn_path: syn::Path =
+                    syn::parse_str(default_fn).expect("Failed to parse default function");
+                quote! {
+                    #field_name: if proto_struct.#proto_field.is_empty() {
+                        #default_fn_path()
+                    } else {
+                        #converted
+                    }
+                }
+            }
+            ErrorMode::Default(None) => {
+                quote! {
+                    #field_name: if proto_struct.#proto_field.is_empty() {
+                        Default::default()
+                    } else {
+                        #converted
+                    }
+                }
+            }
+            ErrorMode::Error if ctx.default_fn.is_some() => {
+                let default_fn_path: syn::Path = ctx
+                    .default_fn
+                    .as_ref()
+                    .and_then(|default_fn| syn::parse_str(default_fn).ok())
+                    .expect("Failed to parse default function");
+                quote! {
+                    #field_name: if proto_struct.#proto_field.is_empty() {
+                        #default_fn_path()
+                    } else {
+                        #converted
+                    }
+                }
+            }
+            ErrorMode::Error if ctx.has_error_fn() => {
+                let error_expr = ctx
+                    .error_fn_call(proto_field, Some(quote! { proto_struct.#proto_field }))
+                    .unwrap();
+                quote! {
+                    #field_name: if proto_struct.#proto_field.is_empty() {
+                        return Err(#error_expr);
+                    } else {
+                        #converted
+                    }
+                }
+            }
+            ErrorMode::Error => {
+                quote! {
+                    #field_name: #converted
+                }
+            }
+            ErrorMode::Panic | ErrorMode::None => {
+                quote! {
+                    #field_name: #converted
+                }
+            }
+        },
+        CollectionStrategy::MapOption(EmptyRepeated::SomeEmpty)
+            if is_option_vec_type(ctx.field_type) =>
+        {
+            quote! { #field_name: Some(#converted) }
+        }
+        CollectionStrategy::MapOption(_) => {
+            // Check if rust field is Option<Vec<T>> -> handle empty vec as None
+            if is_option_vec_type(ctx.field_type) {
+                quote! {
+                    #field_name: if proto_struct.#proto_field.is_empty() {
+                        None
+                    } else {
+                        Some(#converted)
+                    }
+                }
+            } else {
+                // Option<Vec<T>> case where we map the option
+                let converted_vec = generate_collect(ctx, quote! { vec }, false);
+                quote! {
+                    #field_name: proto_struct.#proto_field.map(|vec| #converted_vec)
+                }
+            }
+        }
+        CollectionStrategy::DirectAssignment => {
+            quote! { #field_name: proto_struct.#proto_field }
+        }
+        CollectionStrategy::InPlace => {
+            quote! { #field_name: proto_struct.#proto_field.into_iter().map(Into::into).collect() }
+        }
+        CollectionStrategy::Memoized => {
+            quote! { #field_name: ::protto::memoize::from_messages(proto_struct.#proto_field) }
+        }
+        CollectionStrategy::TryCollect(empty_repeated) => {
+            _trace.decision(
+                "try_collect",
+                "try_into each element and collect the results",
+            );
+            let elements = if ctx.error_fn_takes_value() {
+                let error_expr = generate_field_error_expr(
+                    ctx,
+                    proto_field,
+                    quote! { InvalidValue },
+                    Some(quote! { value }),
+                );
+                quote! {
+                    proto_struct.#proto_field
+                        .into_iter()
+                        .map(|value| TryInto::try_into(value.clone()).map_err(|_| #error_expr))
+                        .collect::<Result<_, _>>()?
+                }
+            } else {
+                let error_expr =
+                    generate_field_error_expr(ctx, proto_field, quote! { InvalidValue }, None);
+                quote! {
+                    proto_struct.#proto_field
+                        .into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<Result<_, _>>()
+                        .map_err(|_| #error_expr)?
+                }
+            };
+            match empty_repeated {
+                None => quote! { #field_name: #elements },
+                Some(EmptyRepeated::SomeEmpty) => quote! { #field_name: Some(#elements) },
+                Some(EmptyRepeated::None) => quote! {
+                    #field_name: if proto_struct.#proto_field.is_empty() {
+                        None
+                    } else {
+                        Some(#elements)
+                    }
+                },
+            }
+        }
+    }
+}
+
+fn generate_path_proto_to_rust(
+    encoding: &Option<PathEncoding>,
+    field_name: &syn::Ident,
+    proto_field: &syn::Ident,
+    rust_field_info: &RustFieldInfo,
+) -> proc_macro2::TokenStream {
+    let decode_fn = match encoding {
+        Some(PathEncoding::Bytes) => quote! { ::protto::path_encoding::from_bytes },
+        _ => quote! { ::protto::path_encoding::from_string },
+    };
+
+    if rust_field_info.is_option {
+        quote! { #field_name: proto_struct.#proto_field.map(#decode_fn) }
+    } else {
+        quote! { #field_name: #decode_fn(proto_struct.#proto_field) }
+    }
+}
+
+/// A `Result` field reads the oneof's ok and err variants, converting either value with `Into`.
+/// The match has no catch-all arm, so a oneof with other fields does not compile. A prost oneof
+/// enum has no `Default`, so a missing oneof stays missing under lenient options.
+fn generate_result_oneof_proto_to_rust(
+    mapping: &ResultOneofMapping,
+    error_mode: &ErrorMode,
+    ctx: &FieldProcessingContext,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let proto_field_name = crate::utils::ident_name(proto_field);
+    let (ok_variant, err_variant) = result_oneof_variant_paths(mapping, ctx);
+
+    let matched = quote! {
+        proto_struct.#proto_field.map(|oneof| match oneof {
+            #ok_variant(value) => ::core::result::Result::Ok(value.into()),
+            #err_variant(value) => ::core::result::Result::Err(value.into()),
+        })
+    };
+
+    let default_fn = |default_fn: &String| {
+        syn::parse_str::<syn::Path>(default_fn).expect("Failed to parse default function")
+    };
+    let is_rust_option = type_analysis::is_option_type(ctx.field_type);
+    let converted = match (error_mode, is_rust_option) {
+        (ErrorMode::Default(Some(fn_name)), true) => {
+            let default_fn = default_fn(fn_name);
+            quote! { #matched.or_else(|| #default_fn()) }
+        }
+        (_, true) => matched,
+        (ErrorMode::None | ErrorMode::Panic, false) => quote! {
+            #matched.expect(&format!("Proto field {} is required", #proto_field_name))
+        },
+        (ErrorMode::Error, false) => {
+            let error_expr =
+                generate_field_error_expr(ctx, proto_field, quote! { MissingField }, None);
+            quote! { #matched.ok_or_else(|| #error_expr)? }
+        }
+        (ErrorMode::Default(Some(fn_name)), false) => {
+            let default_fn = default_fn(fn_name);
+            quote! { #matched.unwrap_or_else(|| #default_fn()) }
+        }
+        (ErrorMode::Default(None), false) => quote! { #matched.unwrap_or_default() },
+    };
+
+    quote! { #field_name: #converted }
+}
+
+fn generate_result_oneof_rust_to_proto(
+    mapping: &ResultOneofMapping,
+    ctx: &FieldProcessingContext,
+    rust_field_info: &RustFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let (ok_variant, err_variant) = result_oneof_variant_paths(mapping, ctx);
+
+    let to_oneof = |result: proc_macro2::TokenStream| {
+        quote! {
+            match #result {
+                ::core::result::Result::Ok(value) => #ok_variant(value.into()),
+                ::core::result::Result::Err(value) => #err_variant(value.into()),
+            }
+        }
+    };
+    if rust_field_info.is_option {
+        let to_oneof = to_oneof(quote! { result });
+        quote! { #proto_field: #field_name.map(|result| #to_oneof) }
+    } else {
+        let to_oneof = to_oneof(quote! { #field_name });
+        quote! { #proto_field: Some(#to_oneof) }
+    }
+}
+
+/// Paths of the prost oneof variants a `result_oneof` field maps `Ok` and `Err` onto. Without
+/// `proto_enum`, the oneof enum is where prost generates it: named after the oneof, in the
+/// module named after the message.
+fn result_oneof_variant_paths(
+    mapping: &ResultOneofMapping,
+    ctx: &FieldProcessingContext,
+) -> (syn::Path, syn::Path) {
+    let proto_enum = mapping.proto_enum.clone().unwrap_or_else(|| {
+        let (message_prefix, message) = match ctx.proto_name.rsplit_once("::") {
+            Some((prefix, message)) => (format!("{prefix}::"), message),
+            None => (String::new(), ctx.proto_name),
+        };
+        format!(
+            "{message_prefix}{}::{}",
+            crate::utils::to_snake_case(message),
+            crate::utils::to_upper_camel_case(&crate::utils::ident_name(&ctx.proto_field_ident))
+        )
+    });
+    let variant_path = |variant: &str| -> syn::Path {
+        syn::parse_str(&format!(
+            "{}::{proto_enum}::{}",
+            ctx.proto_module,
+            crate::utils::to_upper_camel_case(variant)
+        ))
+        .expect("Failed to parse result_oneof variant path")
+    };
+
+    (variant_path(&mapping.ok), variant_path(&mapping.err))
+}
+
+fn generate_bool_enum_proto_to_rust(
+    mapping: &BoolEnumMapping,
+    ctx: &FieldProcessingContext,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let (true_variant, false_variant) = bool_enum_variant_paths(mapping, ctx);
+    let proto_field_name = crate::utils::ident_name(proto_field);
+    // Read leniently, an unknown value is the proto default `0`
+    let lenient_value = quote! { #true_variant as i32 == 0 };
+
+    let unknown_arm = match mapping.unknown {
+        UnknownVariantPolicy::Value(value) => quote! { _ => #value },
+        UnknownVariantPolicy::Panic => quote! {
+            other => panic!(
+                "Proto field {} has value {} matching neither bool_enum variant",
+                #proto_field_name,
+                other
+            )
+        },
+        UnknownVariantPolicy::Error if ctx.error_fn_takes_value() => {
+            let error_expr = generate_field_error_expr(
+                ctx,
+                proto_field,
+                quote! { InvalidValue },
+                Some(quote! { other }),
+            );
+            quote! {
+                _ if ::protto::options::current().unknown_enum.is_lenient() => #lenient_value,
+                other => return Err(#error_expr)
+            }
+        }
+        UnknownVariantPolicy::Error => {
+            let error_expr =
+                generate_field_error_expr(ctx, proto_field, quote! { InvalidValue }, None);
+            quote! {
+                _ if ::protto::options::current().unknown_enum.is_lenient() => #lenient_value,
+                _ => return Err(#error_expr)
+            }
+        }
+    };
+
+    quote! {
+        #field_name: match proto_struct.#proto_field {
+            value if value == #true_variant as i32 => true,
+            value if value == #false_variant as i32 => false,
+            #unknown_arm,
+        }
+    }
+}
+
+/// Reads the enum back from the proto `int32` with `TryFrom<i32>`, handling values matching no
+/// variant per the `enum_as_i32` policy
+fn generate_enum_as_i32_proto_to_rust(
+    policy: &UnknownI32Policy,
+    ctx: &FieldProcessingContext,
+    rust_field_info: &RustFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let proto_field_name = crate::utils::ident_name(proto_field);
+    let enum_type = type_analysis::get_inner_type_from_option(ctx.field_type)
+        .unwrap_or_else(|| ctx.field_type.clone());
+    let try_from = match policy {
+        UnknownI32Policy::Error => {
+            quote! { ::protto::options::enum_or_default::<#enum_type>(value) }
+        }
+        _ => quote! { <#enum_type as TryFrom<i32>>::try_from(value) },
+    };
+
+    let convert = match policy {
+        UnknownI32Policy::Panic => {
+            let enum_name = quote!(#enum_type).to_string();
+            quote! {
+                #try_from.unwrap_or_else(|_| panic!(
+                    "Proto field {} has value {} matching no variant of {}",
+                    #proto_field_name,
+                    value,
+                    #enum_name
+                ))
+            }
+        }
+        UnknownI32Policy::Default => quote! { #try_from.unwrap_or_default() },
+        UnknownI32Policy::Error => {
+            let error_expr = generate_field_error_expr(
+                ctx,
+                proto_field,
+                quote! { InvalidValue },
+                Some(quote! { value }),
+            );
+            quote! { #try_from.map_err(|_| #error_expr) }
+        }
+    };
+
+    match (rust_field_info.is_option, policy) {
+        (true, UnknownI32Policy::Error) => quote! {
+            #field_name: proto_struct.#proto_field.map(|value| #convert).transpose()?
+        },
+        (true, _) => quote! { #field_name: proto_struct.#proto_field.map(|value| #convert) },
+        (false, UnknownI32Policy::Error) => quote! {
+            #field_name: {
+                let value = proto_struct.#proto_field;
+                #convert?
+            }
+        },
+        (false, _) => quote! {
+            #field_name: {
+                let value = proto_struct.#proto_field;
+                #convert
+            }
+        },
+    }
+}
+
+/// Collects the proto map, reading each `i32` value back through the prost enum named like the
+/// rust value enum and handling values matching no variant per the `enum_values` policy
+fn generate_enum_map_proto_to_rust(
+    policy: &UnknownI32Policy,
+    ctx: &FieldProcessingContext,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let proto_field_name = crate::utils::ident_name(proto_field);
+    let value_type = type_analysis::get_value_type_from_map(ctx.field_type)
+        .expect("enum_values field is validated to be a map");
+    let value_name = match &value_type {
+        syn::Type::Path(type_path) => type_path.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
+    }
+    .expect("enum_values map value must name an enum");
+    let proto_enum: syn::Path = syn::parse_str(&format!("{}::{value_name}", ctx.proto_module))
+        .expect("Failed to parse proto enum path");
+    let try_from =
+        quote! { <#proto_enum as TryFrom<i32>>::try_from(value).map(<#value_type>::from) };
+
+    match policy {
+        UnknownI32Policy::Panic => quote! {
+            #field_name: proto_struct.#proto_field
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = #try_from.unwrap_or_else(|_| panic!(
+                        "Proto field {} has value {} matching no variant of {}",
+                        #proto_field_name,
+                        value,
+                        #value_name
+                    ));
+                    (key, value)
+                })
+                .collect()
+        },
+        UnknownI32Policy::Default => quote! {
+            #field_name: proto_struct.#proto_field
+                .into_iter()
+                .map(|(key, value)| (key, #try_from.unwrap_or_default()))
+                .collect()
+        },
+        UnknownI32Policy::Error => {
+            let error_expr = generate_field_error_expr(
+                ctx,
+                proto_field,
+                quote! { InvalidValue },
+                Some(quote! { value }),
+            );
+            let try_from = quote! {
+                ::protto::options::enum_or_default::<#proto_enum>(value).map(<#value_type>::from)
+            };
+            quote! {
+                #field_name: proto_struct.#proto_field
+                    .into_iter()
+                    .map(|(key, value)| {
+                        #try_from.map(|converted| (key, converted)).map_err(|_| #error_expr)
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+        }
+    }
+}
+
+fn generate_enum_key_map_proto_to_rust(
+    policy: &UnknownI32Policy,
+    ctx: &FieldProcessingContext,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let proto_field_name = crate::utils::ident_name(proto_field);
+    let key_type = type_analysis::get_key_type_from_map(ctx.field_type)
+        .expect("enum_keys field is validated to be a map");
+
+    match policy {
+        UnknownI32Policy::Panic => {
+            let key_name = quote!(#key_type).to_string();
+            quote! {
+                #field_name: proto_struct.#proto_field
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let key = <#key_type as TryFrom<i32>>::try_from(key).unwrap_or_else(|_| panic!(
+                            "Proto field {} has key {} matching no variant of {}",
+                            #proto_field_name,
+                            key,
+                            #key_name
+                        ));
+                        (key, value.into())
+                    })
+                    .collect()
+            }
+        }
+        UnknownI32Policy::Default => quote! {
+            #field_name: proto_struct.#proto_field
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = <#key_type as TryFrom<i32>>::try_from(key).unwrap_or_default();
+                    (key, value.into())
+                })
+                .collect()
+        },
+        UnknownI32Policy::Error => {
+            let error_expr = generate_field_error_expr(
+                ctx,
+                proto_field,
+                quote! { InvalidValue },
+                Some(quote! { key }),
+            );
+            quote! {
+                #field_name: proto_struct.#proto_field
+                    .into_iter()
+                    .map(|(key, value)| {
+                        ::protto::options::enum_or_default::<#key_type>(key)
+                            .map(|converted| (converted, value.into()))
+                            .map_err(|_| #error_expr)
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+        }
+    }
+}
+
+/// Collects the repeated proto field into a `SmallVec`, or into an `ArrayVec` handling elements
+/// past its capacity per the `overflow` policy
+fn generate_bounded_proto_to_rust(
+    collection: &BoundedCollection,
+    overflow: &CapacityOverflow,
+    ctx: &FieldProcessingContext,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let proto_field_name = crate::utils::ident_name(proto_field);
+
+    match (collection, overflow) {
+        (BoundedCollection::SmallVec, _) => {
+            quote! { #field_name: proto_struct.#proto_field.into_iter().map(Into::into).collect() }
+        }
+        (BoundedCollection::ArrayVec, CapacityOverflow::Truncate) => {
+            quote! { #field_name: ::protto::bounded::truncate(proto_struct.#proto_field) }
+        }
+        (BoundedCollection::ArrayVec, CapacityOverflow::Panic) => quote! {
+            #field_name: ::protto::bounded::try_collect(proto_struct.#proto_field)
+                .unwrap_or_else(|err| panic!("Proto field {}: {}", #proto_field_name, err))
+        },
+        (BoundedCollection::ArrayVec, CapacityOverflow::Error) => {
+            let converted = quote! { ::protto::bounded::try_collect(proto_struct.#proto_field) };
+            let checked = if ctx.error_fn_takes_value() {
+                let error_expr = generate_field_error_expr(
+                    ctx,
+                    proto_field,
+                    quote! { InvalidValue },
+                    Some(quote! { err }),
+                );
+                quote! { #converted.map_err(|err| #error_expr)? }
+            } else {
+                let error_expr =
+                    generate_field_error_expr(ctx, proto_field, quote! { InvalidValue }, None);
+                quote! { #converted.map_err(|_| #error_expr)? }
+            };
+            quote! {
+                #field_name: if ::protto::options::current().overflow.is_lenient() {
+                    ::protto::bounded::truncate(proto_struct.#proto_field)
+                } else {
+                    #checked
+                }
+            }
+        }
+    }
+}
+
+/// Decodes the proto string or units/nanos message; a missing units/nanos message is zero unless
+/// the field is `expect`
+fn generate_decimal_proto_to_rust(
+    decimal: &DecimalAs,
+    ctx: &FieldProcessingContext,
+    rust_field_info: &RustFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let proto_field_name = crate::utils::ident_name(proto_field);
+
+    let decoded = match (decimal, rust_field_info.is_option) {
+        (DecimalAs::String, false) => {
+            quote! { ::protto::decimal::from_string(&proto_struct.#proto_field) }
+        }
+        (DecimalAs::String, true) => quote! {
+            proto_struct.#proto_field.as_deref().map(::protto::decimal::from_string).transpose()
+        },
+        (DecimalAs::UnitsNanos, true) => quote! {
+            proto_struct.#proto_field
+                .as_ref()
+                .map(|message| ::protto::decimal::from_message(Some(message)))
+                .transpose()
+        },
+        (DecimalAs::UnitsNanos, false) => match ctx.expect_mode {
+            ExpectMode::None => {
+                quote! { ::protto::decimal::from_message(proto_struct.#proto_field.as_ref()) }
+            }
+            ExpectMode::Panic => quote! {
+                ::protto::decimal::from_message(Some(
+                    proto_struct.#proto_field
+                        .as_ref()
+                        .expect(&format!("Proto field {} is required", #proto_field_name)),
+                ))
+            },
+            ExpectMode::Error => {
+                let error_expr =
+                    generate_field_error_expr(ctx, proto_field, quote! { MissingField }, None);
+                quote! {
+                    ::protto::decimal::from_message(
+                        proto_struct.#proto_field
+                            .as_ref()
+                            .map(Some)
+                            .or_else(::protto::options::lenient_missing)
+                            .ok_or_else(|| #error_expr)?,
+                    )
+                }
+            }
+        },
+    };
+
+    if ctx.expect_mode == ExpectMode::Panic {
+        quote! {
+            #field_name: #decoded.unwrap_or_else(|err| {
+                panic!("Proto field {} is not a valid decimal: {}", #proto_field_name, err)
+            })
+        }
+    } else {
+        let error_expr = generate_field_error_expr(
+            ctx,
+            proto_field,
+            quote! { InvalidValue },
+            Some(quote! { proto_struct.#proto_field }),
+        );
+        quote! { #field_name: #decoded.map_err(|_| #error_expr)? }
+    }
+}
+
+/// Decodes the proto `int64` count of units; counts the duration type cannot hold are
+/// `InvalidValue`, or panic under `expect(panic)`
+fn generate_duration_proto_to_rust(
+    unit: DurationUnit,
+    ctx: &FieldProcessingContext,
+    rust_field_info: &RustFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let proto_field_name = crate::utils::ident_name(proto_field);
+    let unit = unit.runtime_path();
+
+    let decoded = if rust_field_info.is_option {
+        quote! {
+            proto_struct.#proto_field
+                .map(|value| ::protto::duration::from_units(value, #unit))
+                .transpose()
+        }
+    } else {
+        quote! { ::protto::duration::from_units(proto_struct.#proto_field, #unit) }
+    };
+
+    if ctx.expect_mode == ExpectMode::Panic {
+        quote! {
+            #field_name: #decoded.unwrap_or_else(|err| {
+                panic!("Proto field {} is not a valid duration: {}", #proto_field_name, err)
+            })
+        }
+    } else {
+        let error_expr = generate_field_error_expr(
+            ctx,
+            proto_field,
+            quote! { InvalidValue },
+            Some(quote! { proto_struct.#proto_field }),
+        );
+        quote! { #field_name: #decoded.map_err(|_| #error_expr)? }
+    }
+}
+
+/// Parses the proto string with `FromStr`; strings that do not parse are `InvalidValue`, or
+/// panic under `expect(panic)`
+/// Matches a proto enum value of 0 before converting the rest with `Into`. An unset `optional`
+/// proto field is unspecified as well, except on an `Option` rust field, where it is `None`.
+fn generate_unspecified_proto_to_rust(
+    policy: &UnspecifiedPolicy,
+    ctx: &FieldProcessingContext,
+    rust_field_info: &RustFieldInfo,
+    proto_field_info: &ProtoFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let enum_type = type_analysis::get_inner_type_from_option(ctx.field_type)
+        .unwrap_or_else(|| ctx.field_type.clone());
+
+    let unspecified = match policy {
+        UnspecifiedPolicy::Error => {
+            let error_expr = generate_field_error_expr(
+                ctx,
+                proto_field,
+                quote! { InvalidValue },
+                Some(quote! { proto_struct.#proto_field }),
+            );
+            quote! { return Err(#error_expr) }
+        }
+        UnspecifiedPolicy::Default => quote! { ::core::default::Default::default() },
+        UnspecifiedPolicy::MapTo(variant) => {
+            let variant = syn::Ident::new(variant, proc_macro2::Span::call_site());
+            if rust_field_info.is_option {
+                quote! { Some(<#enum_type>::#variant) }
+            } else {
+                quote! { <#enum_type>::#variant }
+            }
+        }
+    };
+
+    match (rust_field_info.is_option, proto_field_info.is_optional()) {
+        (false, false) => quote! {
+            #field_name: match proto_struct.#proto_field {
+                0 => #unspecified,
+                value => <#enum_type>::from(value),
+            }
+        },
+        (false, true) => quote! {
+            #field_name: match proto_struct.#proto_field.unwrap_or_default() {
+                0 => #unspecified,
+                value => <#enum_type>::from(value),
+            }
+        },
+        (true, false) => quote! {
+            #field_name: match proto_struct.#proto_field {
+                0 => #unspecified,
+                value => Some(<#enum_type>::from(value)),
+            }
+        },
+        (true, true) => quote! {
+            #field_name: match proto_struct.#proto_field {
+                None => None,
+                Some(0) => #unspecified,
+                Some(value) => Some(<#enum_type>::from(value)),
+            }
+        },
+    }
+}
+
+fn generate_with_str_proto_to_rust(
+    ctx: &FieldProcessingContext,
+    rust_field_info: &RustFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let proto_field_name = crate::utils::ident_name(proto_field);
+
+    let parsed = if rust_field_info.is_option {
+        quote! { proto_struct.#proto_field.as_deref().map(str::parse).transpose() }
+    } else {
+        quote! { proto_struct.#proto_field.parse() }
+    };
+
+    if ctx.expect_mode == ExpectMode::Panic {
+        quote! {
+            #field_name: #parsed.unwrap_or_else(|_| {
+                panic!("Proto field {} could not be parsed", #proto_field_name)
+            })
+        }
+    } else {
+        let error_expr = generate_field_error_expr(
+            ctx,
+            proto_field,
+            quote! { InvalidValue },
+            Some(quote! { proto_struct.#proto_field }),
+        );
+        quote! { #field_name: #parsed.map_err(|_| #error_expr)? }
+    }
+}
+
+fn generate_json_proto_to_rust(
+    ctx: &FieldProcessingContext,
+    rust_field_info: &RustFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let proto_field_name = crate::utils::ident_name(proto_field);
+
+    let decoded = if rust_field_info.is_option {
+        quote! {
+            proto_struct.#proto_field
+                .as_deref()
+                .map(::protto::json::serde_json::from_str)
+                .transpose()
+        }
+    } else {
+        quote! { ::protto::json::serde_json::from_str(&proto_struct.#proto_field) }
+    };
+
+    if ctx.expect_mode == ExpectMode::Panic {
+        quote! {
+            #field_name: #decoded.unwrap_or_else(|err| {
+                panic!("Proto field {} is not valid JSON: {}", #proto_field_name, err)
+            })
+        }
+    } else {
+        let error_expr = generate_field_error_expr(
+            ctx,
+            proto_field,
+            quote! { InvalidValue },
+            Some(quote! { proto_struct.#proto_field }),
+        );
+        quote! { #field_name: #decoded.map_err(|_| #error_expr)? }
+    }
+}
+
+/// Unboxes the proto `Option<Box<T>>`, converting the message into the rust field, or into a
+/// `Box` when the rust side is boxed as well (as in recursive types)
+fn generate_boxed_proto_to_rust(
+    error_mode: &ErrorMode,
+    ctx: &FieldProcessingContext,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let rust_option = type_analysis::get_inner_type_from_option(ctx.field_type);
+    let rust_type = rust_option.as_ref().unwrap_or(ctx.field_type);
+    let proto_field_name = crate::utils::ident_name(proto_field);
+
+    // A missing message read leniently is the default message, before it is converted
+    let source = if matches!(error_mode, ErrorMode::Error) && rust_option.is_none() {
+        quote! { proto_struct.#proto_field.or_else(::protto::options::lenient_missing) }
+    } else {
+        quote! { proto_struct.#proto_field }
+    };
+    let unboxed = if type_analysis::is_box_type(rust_type) {
+        quote! { #source.map(|boxed| ::std::boxed::Box::new((*boxed).into())) }
+    } else {
+        quote! { #source.map(|boxed| (*boxed).into()) }
+    };
+
+    let default_fn = |default_fn: &String| {
+        syn::parse_str::<syn::Path>(default_fn).expect("Failed to parse default function")
+    };
+    let converted = match (error_mode, rust_option.is_some()) {
+        (ErrorMode::Default(Some(fn_name)), true) => {
+            let default_fn = default_fn(fn_name);
+            quote! { #unboxed.or_else(|| #default_fn()) }
+        }
+        (_, true) => unboxed,
+        (ErrorMode::None | ErrorMode::Panic, false) => quote! {
+            #unboxed.expect(&format!("Proto field {} is required", #proto_field_name))
+        },
+        (ErrorMode::Error, false) => {
+            let error_expr =
+                generate_field_error_expr(ctx, proto_field, quote! { MissingField }, None);
+            quote! { #unboxed.ok_or_else(|| #error_expr)? }
+        }
+        (ErrorMode::Default(Some(fn_name)), false) => {
+            let default_fn = default_fn(fn_name);
+            quote! { #unboxed.unwrap_or_else(|| #default_fn()) }
+        }
+        (ErrorMode::Default(None), false) => quote! { #unboxed.unwrap_or_default() },
+    };
+
+    quote! { #field_name: #converted }
+}
+
+fn generate_group_proto_to_rust(
+    mappings: &[(String, String)],
+    ctx: &FieldProcessingContext,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let field_type = ctx.field_type;
+
+    let _trace = CallStackDebug::new(
+        "field::conversion_codegen",
+        "generate_group_proto_to_rust",
+        ctx.struct_name,
+        field_name,
+    );
+
+    let sub_fields = group_idents(mappings).map(|(sub_field, proto_field)| {
+        quote! { #sub_field: proto_struct.#proto_field.into() }
+    });
+
+    quote! {
+        #field_name: #field_type {
+            #(#sub_fields),*
+        }
+    }
+}
+
+// -- Rust-to-Proto generation functions --
+fn generate_custom_rust_to_proto(
+    custom_strategy: &CustomConversionStrategy,
+    ctx: &FieldProcessingContext,
+    proto_field: &syn::Ident,
+    rust_field_info: &RustFieldInfo,
+    proto_field_info: &ProtoFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    match custom_strategy {
+        CustomConversionStrategy::IntoFn(fn_path)
+        | CustomConversionStrategy::Bidirectional(_, fn_path) => {
+            let into_fn = ctx.custom_fn_path(fn_path);
+
+            if proto_field_info.is_optional() && !rust_field_info.is_option {
+                quote! { #proto_field: Some(#into_fn(#field_name)) }
+            } else {
+                quote! { #proto_field: #into_fn(#field_name) }
+            }
+        }
+        CustomConversionStrategy::FromFn(_) => {
+            // Fallback to .into() for rust->proto when only proto->rust function provided
+            if proto_field_info.is_optional() {
+                quote! { #proto_field: Some(#field_name.into()) }
+            } else {
+                quote! { #proto_field: #field_name.into() }
+            }
+        }
+    }
+}
+
+fn generate_direct_rust_to_proto(
+    direct_strategy: &DirectStrategy,
+    ctx: &FieldProcessingContext,
+    field_name: &syn::Ident,
+    proto_field: &syn::Ident,
+) -> proc_macro2::TokenStream {
+    match direct_strategy {
+        DirectStrategy::Assignment => {
+            quote! { #proto_field: #field_name }
+        }
+        DirectStrategy::WithConversion => {
+            let converted = into_proto(ctx, ctx.field_type, quote! { #field_name });
+            quote! { #proto_field: #converted }
+        }
+    }
+}
+
+fn generate_option_rust_to_proto(
+    option_strategy: &OptionStrategy,
+    ctx: &FieldProcessingContext,
+    field_name: &syn::Ident,
+    proto_field: &syn::Ident,
+    rust_field_info: &RustFieldInfo,
+    proto_field_info: &ProtoFieldInfo,
+) -> proc_macro2::TokenStream {
+    let converted_v = into_proto(ctx, &option_value_type(ctx.field_type), quote! { v });
+    // `none_as_empty` sets an optional proto field even for a rust `None`
+    let map_option = if ctx.protto_meta.none_as == Some(NonePolicy::Empty) {
+        quote! { #proto_field: Some(#field_name.map(|v| #converted_v).unwrap_or_default()) }
+    } else {
+        quote! { #proto_field: #field_name.map(|v| #converted_v) }
+    };
+    match option_strategy {
+        OptionStrategy::Wrap(none_as) => {
+            generate_wrap_rust_to_proto(none_as, ctx, field_name, proto_field)
+        }
+        OptionStrategy::Unwrap(_)
+            if rust_field_info.is_option && proto_field_info.is_optional() =>
+        {
+            map_option
+        }
+        OptionStrategy::Unwrap(_) => {
+            let converted = into_proto(ctx, ctx.field_type, quote! { #field_name });
+            quote! { #proto_field: Some(#converted) }
+        }
+        OptionStrategy::Map | OptionStrategy::TryMap => map_option,
+    }
+}
+
+/// Write a rust `Option<T>` into a required proto field, handling `None` per `none_as`
+fn generate_wrap_rust_to_proto(
+    none_as: &Option<NonePolicy>,
+    ctx: &FieldProcessingContext,
+    field_name: &syn::Ident,
+    proto_field: &syn::Ident,
+) -> proc_macro2::TokenStream {
+    let value_type = option_value_type(ctx.field_type);
+    match none_as {
+        Some(NonePolicy::Default | NonePolicy::Empty) => {
+            let converted = into_proto(ctx, &value_type, quote! { v });
+            quote! { #proto_field: #field_name.map(|v| #converted).unwrap_or_default() }
+        }
+        Some(NonePolicy::Panic | NonePolicy::Unset) | None => {
+            let rust_field_name = crate::utils::ident_name(field_name);
+            let proto_field_name = crate::utils::ident_name(proto_field);
+            let converted = into_proto(
+                ctx,
+                &value_type,
+                quote! {
+                    #field_name
+                        .expect(&format!("Rust field {} is None but proto field {} is required", #rust_field_name, #proto_field_name))
+                },
+            );
+            quote! {
+                #proto_field: #converted
+            }
+        }
+        Some(NonePolicy::Error) => {
+            let error_expr =
+                generate_field_error_expr(ctx, proto_field, quote! { MissingField }, None);
+            let converted = into_proto(
+                ctx,
+                &value_type,
+                quote! { #field_name.ok_or_else(|| #error_expr)? },
+            );
+            quote! {
+                #proto_field: #converted
+            }
+        }
+    }
+}
+
+/// Error raised by a fallible field conversion: the effective error_fn if there is one,
+/// otherwise the given variant of the generated conversion error. `value` is the offending
+/// value handed to an `error_fn_with_value`.
+fn generate_field_error_expr(
+    ctx: &FieldProcessingContext,
+    proto_field: &syn::Ident,
+    default_variant: proc_macro2::TokenStream,
+    value: Option<proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    if let Some(error_call) = ctx.error_fn_call(proto_field, value) {
+        error_call
+    } else {
+        let error_type = ctx.default_error_path();
+        let error_field = ctx.error_field_name(proto_field);
+        quote! { #error_type::#default_variant(#error_field) }
+    }
+}
+
+fn generate_path_rust_to_proto(
+    encoding: &Option<PathEncoding>,
+    ctx: &FieldProcessingContext,
+    field_name: &syn::Ident,
+    proto_field: &syn::Ident,
+    rust_field_info: &RustFieldInfo,
+) -> proc_macro2::TokenStream {
+    let encode_fn = match encoding {
+        Some(PathEncoding::Utf8Strict) => quote! { ::protto::path_encoding::to_utf8_strict },
+        Some(PathEncoding::Lossy) | None => quote! { ::protto::path_encoding::to_utf8_lossy },
+        Some(PathEncoding::Bytes) => quote! { ::protto::path_encoding::to_bytes },
+    };
+
+    match (encoding, rust_field_info.is_option) {
+        (Some(PathEncoding::Utf8Strict), is_option) => {
+            let error_expr = generate_field_error_expr(
+                ctx,
+                proto_field,
+                quote! { InvalidValue },
+                Some(quote! { value }),
+            );
+            if is_option {
+                quote! {
+                    #proto_field: #field_name.map(#encode_fn).transpose().map_err(|value| #error_expr)?
+                }
+            } else {
+                quote! { #proto_field: #encode_fn(#field_name).map_err(|value| #error_expr)? }
+            }
+        }
+        (_, true) => quote! { #proto_field: #field_name.map(#encode_fn) },
+        (_, false) => quote! { #proto_field: #encode_fn(#field_name) },
+    }
+}
+
+/// Encodes as a proto string, which cannot fail, or as a units/nanos message, which fails when
+/// the units overflow `i64`
+fn generate_decimal_rust_to_proto(
+    decimal: &DecimalAs,
+    ctx: &FieldProcessingContext,
+    rust_field_info: &RustFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let proto_field_name = crate::utils::ident_name(proto_field);
+
+    if *decimal == DecimalAs::String {
+        return if rust_field_info.is_option {
+            quote! { #proto_field: #field_name.map(::protto::decimal::to_string) }
+        } else {
+            quote! { #proto_field: ::protto::decimal::to_string(#field_name) }
+        };
+    }
+
+    let encode = |value: proc_macro2::TokenStream| {
+        if ctx.expect_mode == ExpectMode::Panic {
+            quote! {
+                ::protto::decimal::to_message(#value).unwrap_or_else(|err| {
+                    panic!("Decimal for proto field {} cannot be encoded: {}", #proto_field_name, err)
+                })
+            }
+        } else {
+            let error_expr = generate_field_error_expr(
+                ctx,
+                proto_field,
+                quote! { InvalidValue },
+                Some(value.clone()),
+            );
+            quote! { ::protto::decimal::to_message(#value).map_err(|_| #error_expr) }
+        }
+    };
+
+    let fallible = ctx.expect_mode != ExpectMode::Panic;
+    match (rust_field_info.is_option, fallible) {
+        (true, false) => {
+            let encoded = encode(quote! { value });
+            quote! { #proto_field: #field_name.map(|value| #encoded) }
+        }
+        (true, true) => {
+            let encoded = encode(quote! { value });
+            quote! { #proto_field: #field_name.map(|value| #encoded).transpose()? }
+        }
+        (false, false) => {
+            let encoded = encode(quote! { #field_name });
+            quote! { #proto_field: Some(#encoded) }
+        }
+        (false, true) => {
+            let encoded = encode(quote! { #field_name });
+            quote! { #proto_field: Some(#encoded?) }
+        }
+    }
+}
+
+/// Encodes the duration as a whole count of units, failing when the count overflows `i64`
+fn generate_duration_rust_to_proto(
+    unit: DurationUnit,
+    ctx: &FieldProcessingContext,
+    rust_field_info: &RustFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let proto_field_name = crate::utils::ident_name(proto_field);
+    let unit = unit.runtime_path();
+
+    let encoded = if rust_field_info.is_option {
+        quote! {
+            #field_name
+                .as_ref()
+                .map(|value| ::protto::duration::to_units(value, #unit))
+                .transpose()
+        }
+    } else {
+        quote! { ::protto::duration::to_units(&#field_name, #unit) }
+    };
+
+    if ctx.expect_mode == ExpectMode::Panic {
+        quote! {
+            #proto_field: #encoded.unwrap_or_else(|err| {
+                panic!("Duration for proto field {} cannot be encoded: {}", #proto_field_name, err)
+            })
+        }
+    } else {
+        let error_expr = generate_field_error_expr(
+            ctx,
+            proto_field,
+            quote! { InvalidValue },
+            Some(quote! { #field_name }),
+        );
+        quote! { #proto_field: #encoded.map_err(|_| #error_expr)? }
+    }
+}
+
+/// Writes the field's `Display` output to the proto string
+fn generate_with_str_rust_to_proto(
+    ctx: &FieldProcessingContext,
+    rust_field_info: &RustFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    if rust_field_info.is_option {
+        quote! { #proto_field: #field_name.as_ref().map(ToString::to_string) }
+    } else {
+        quote! { #proto_field: #field_name.to_string() }
+    }
+}
+
+fn generate_json_rust_to_proto(
+    ctx: &FieldProcessingContext,
+    rust_field_info: &RustFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let proto_field_name = crate::utils::ident_name(proto_field);
+
+    let encoded = if rust_field_info.is_option {
+        quote! {
+            #field_name
+                .as_ref()
+                .map(::protto::json::serde_json::to_string)
+                .transpose()
+        }
+    } else {
+        quote! { ::protto::json::serde_json::to_string(&#field_name) }
+    };
+
+    if ctx.expect_mode == ExpectMode::Panic {
+        quote! {
+            #proto_field: #encoded.unwrap_or_else(|err| {
+                panic!("Value for proto field {} cannot be written as JSON: {}", #proto_field_name, err)
+            })
+        }
+    } else {
+        let error_expr = generate_field_error_expr(
+            ctx,
+            proto_field,
+            quote! { InvalidValue },
+            Some(quote! { #field_name }),
+        );
+        quote! { #proto_field: #encoded.map_err(|_| #error_expr)? }
+    }
+}
+
+fn generate_boxed_rust_to_proto(
+    ctx: &FieldProcessingContext,
+    rust_field_info: &RustFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let rust_option = type_analysis::get_inner_type_from_option(ctx.field_type);
+    let rust_type = rust_option.as_ref().unwrap_or(ctx.field_type);
+
+    let boxed = |value: proc_macro2::TokenStream| {
+        if type_analysis::is_box_type(rust_type) {
+            quote! { ::std::boxed::Box::new((*#value).into()) }
+        } else {
+            quote! { ::std::boxed::Box::new(#value.into()) }
+        }
+    };
+
+    if rust_field_info.is_option {
+        let boxed_value = boxed(quote! { value });
+        quote! { #proto_field: #field_name.map(|value| #boxed_value) }
+    } else {
+        let boxed_value = boxed(quote! { #field_name });
+        quote! { #proto_field: Some(#boxed_value) }
+    }
+}
+
+fn generate_bool_enum_rust_to_proto(
+    mapping: &BoolEnumMapping,
+    ctx: &FieldProcessingContext,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let (true_variant, false_variant) = bool_enum_variant_paths(mapping, ctx);
+
+    quote! {
+        #proto_field: if #field_name {
+            #true_variant as i32
+        } else {
+            #false_variant as i32
+        }
+    }
+}
+
+/// Paths of the proto enum variants a `bool_enum` field maps `true` and `false` onto
+fn bool_enum_variant_paths(
+    mapping: &BoolEnumMapping,
+    ctx: &FieldProcessingContext,
+) -> (syn::Path, syn::Path) {
+    let variant_path = |variant: &str| -> syn::Path {
+        syn::parse_str(&format!(
+            "{}::{}::{variant}",
+            ctx.proto_module, mapping.proto_enum
+        ))
+        .expect("Failed to parse bool_enum variant path")
+    };
+
+    (
+        variant_path(&mapping.true_variant),
+        variant_path(&mapping.false_variant),
+    )
+}
+
+fn generate_transparent_rust_to_proto(
+    _error_mode: &ErrorMode,
+    field_name: &syn::Ident,
+    proto_field: &syn::Ident,
+    rust_field_info: &RustFieldInfo,
+    proto_field_info: &ProtoFieldInfo,
+) -> proc_macro2::TokenStream {
+    let field_type = &rust_field_info.field_type;
+    if let Some(collection) = type_analysis::newtype_collection(field_type) {
+        let collection_value = quote! {
+            <#field_type as ::protto::newtype::CollectionNewtype>::into_collection(#field_name)
+        };
+        let elements = convert_collection_elements(collection, collection_value);
+        quote! { #proto_field: #elements }
+    } else if proto_field_info.is_optional() {
+        type_analysis::get_inner_type_from_option(&rust_field_info.field_type)
+            .map(|_inner_type| {
+                quote! { #proto_field: #field_name.map(|inner| inner.into()) }
+            })
+            .unwrap_or_else(|| quote! { #proto_field: Some(#field_name.into()) })
+    } else {
+        quote! { #proto_field: #field_name.into() }
+    }
+}
+
+/// Rebuilds the collection of a newtype transparent field, converting each element, or each key
+/// and value of a map, with `Into`
+fn convert_collection_elements(
+    collection: type_analysis::NewtypeCollection,
+    collection_value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match collection {
+        type_analysis::NewtypeCollection::Vec => {
+            quote! { #collection_value.into_iter().map(Into::into).collect() }
+        }
+        type_analysis::NewtypeCollection::Map => {
+            quote! {
+                #collection_value
+                    .into_iter()
+                    .map(|(key, value)| (key.into(), value.into()))
+                    .collect()
+            }
+        }
+    }
+}
+
+fn generate_collection_rust_to_proto(
+    collection_strategy: &CollectionStrategy,
+    ctx: &FieldProcessingContext,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+
+    match collection_strategy {
+        CollectionStrategy::Collect(_) | CollectionStrategy::TryCollect(None) => {
+            let converted = generate_collect(ctx, quote! { #field_name }, true);
+            quote! { #proto_field: #converted }
+        }
+        CollectionStrategy::MapOption(_) | CollectionStrategy::TryCollect(Some(_)) => {
+            let converted = generate_collect(ctx, quote! { vec }, true);
+            quote! {
+                #proto_field: #field_name.map(|vec| #converted).unwrap_or_default()
+            }
+        }
+        CollectionStrategy::DirectAssignment => {
+            quote! { #proto_field: #field_name }
+        }
+        CollectionStrategy::InPlace => {
+            quote! { #proto_field: #field_name.into_iter().map(Into::into).collect() }
+        }
+        CollectionStrategy::Memoized => {
+            quote! { #proto_field: ::protto::memoize::to_messages(#field_name) }
+        }
+    }
+}
+
+/// Converts every element of `source`. Vectors (the rust field, or the proto repeated field it
+/// maps to) go through `protto::collection::convert_vec`, which collects elements of the same
+/// layout in place and fills a vector allocated once at the final length otherwise; maps convert
+/// each key and value, so a `BTreeMap` (walked in key order) can hold converted values; other
+/// collections are collected.
+fn generate_collect(
+    ctx: &FieldProcessingContext,
+    source: proc_macro2::TokenStream,
+    to_proto: bool,
+) -> proc_macro2::TokenStream {
+    let field_type = ctx.field_type;
+    let convert = |ty: Option<syn::Type>, value| match ty {
+        Some(ty) if to_proto => into_proto(ctx, &ty, value),
+        Some(ty) => into_rust(ctx, &ty, value),
+        None => quote! { #value.into() },
+    };
+    let vec_element = type_analysis::get_inner_type_from_vec(&option_value_type(field_type));
+
+    if type_analysis::is_vec_type(field_type) || is_option_vec_type(field_type) {
+        let element = match vec_element.as_ref().and_then(|ty| repr_type(ctx, ty)) {
+            Some(_) => {
+                let converted = convert(vec_element.clone(), quote! { element });
+                quote! { |element| #converted }
+            }
+            None => quote! { Into::into },
+        };
+        quote! { ::protto::collection::convert_vec(#source, #element) }
+    } else if type_analysis::is_map_type(field_type) {
+        let value = convert(
+            type_analysis::get_value_type_from_map(field_type),
+            quote! { value },
+        );
+        quote! { #source.into_iter().map(|(key, value)| (key.into(), #value)).collect() }
+    } else {
+        quote! { #source.into_iter().map(Into::into).collect() }
+    }
+}
+
+fn generate_group_rust_to_proto(
+    mappings: &[(String, String)],
+    field_name: &syn::Ident,
+) -> proc_macro2::TokenStream {
+    let proto_fields = group_idents(mappings).map(|(sub_field, proto_field)| {
+        quote! { #proto_field: #field_name.#sub_field.into() }
+    });
+
+    quote! { #(#proto_fields),* }
+}
+
+// -- Helper functions --
+
+/// The value type `ty` when it may implement `protto::ProtoRepr`: a custom type, as opposed to
+/// primitives, enums, `Option`s, collections and the proto module's own types, which convert with
+/// `Into` as before.
+fn repr_type<'a>(ctx: &FieldProcessingContext, ty: &'a syn::Type) -> Option<&'a syn::Type> {
+    const RUST_PRIMITIVES: &[&str] = &[
+        "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+        "f32", "f64", "bool", "char", "String",
+    ];
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let is_builtin = type_path.qself.is_some()
+        || RUST_PRIMITIVES
+            .iter()
+            .any(|primitive| type_path.path.is_ident(primitive));
+    (!is_builtin
+        && !ctx.is_primitive_type(ty)
+        && !ctx.is_enum_type(ty)
+        && !type_analysis::is_option_type(ty)
+        && !type_analysis::is_vec_type(ty)
+        && !type_analysis::is_map_type(ty)
+        && !type_analysis::is_box_type(ty)
+        && !type_analysis::is_proto_type(ty, ctx.proto_module))
+    .then_some(ty)
+}
+
+/// Converts a proto value into the rust value type `ty`, through its `ProtoRepr` when it has
+/// one; see [`repr_type`].
+fn into_rust(
+    ctx: &FieldProcessingContext,
+    ty: &syn::Type,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match repr_type(ctx, ty) {
+        Some(ty) => quote! { ::protto::__repr_from_proto!(#ty, #value) },
+        None => quote! { #value.into() },
+    }
+}
+
+/// Converts a value of the rust value type `ty` into its proto value, through its `ProtoRepr`
+/// when it has one; see [`repr_type`].
+fn into_proto(
+    ctx: &FieldProcessingContext,
+    ty: &syn::Type,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match repr_type(ctx, ty) {
+        Some(ty) => quote! { ::protto::__repr_to_proto!(#ty, #value) },
+        None => quote! { #value.into() },
+    }
+}
+fn group_idents(
+    mappings: &[(String, String)],
+) -> impl Iterator<Item = (syn::Ident, syn::Ident)> + '_ {
+    mappings.iter().map(|(sub_field, proto_field)| {
+        (
+            crate::utils::field_ident(sub_field),
+            crate::utils::field_ident(proto_field),
+        )
+    })
+}
+
+fn generate_unwrap_with_error_mode(
+    error_mode: &ErrorMode,
+    field_name: &syn::Ident,
+    proto_field: &syn::Ident,
+    ctx: &FieldProcessingContext,
+    rust_field_info: &RustFieldInfo,
+    proto_field_info: &ProtoFieldInfo,
+    trace: &CallStackDebug,
+) -> proc_macro2::TokenStream {
+    trace.checkpoint_data(
+        "error_mode_factors",
+        &[
+            ("mode", &format!("{error_mode:?}")),
+            (
+                "field_error_fn",
+                &format!("{:?}", ctx.field_level_error_fn()),
+            ),
+            (
+                "struct_error_fn",
+                &format!("{:?}", ctx.struct_level_error_fn),
+            ),
+            ("rust_is_option", &rust_field_info.is_option.to_string()),
+            (
+                "proto_is_option",
+                &proto_field_info.is_optional().to_string(),
+            ),
+        ],
+    );
+
+    let converted_v = into_rust(ctx, &option_value_type(ctx.field_type), quote! { v });
+    match error_mode {
+        ErrorMode::None | ErrorMode::Panic => {
+            trace.decision("unwrap_with_expect", "Required field with panic on missing");
+            let proto_field_name = crate::utils::ident_name(proto_field);
+            let converted = into_rust(
+                ctx,
+                ctx.field_type,
+                quote! { proto_struct.#proto_field.expect(&format!("Proto field {} is required", #proto_field_name)) },
+            );
+            quote! {
+                #field_name: #converted
+            }
+        }
+
+        ErrorMode::Error if rust_field_info.is_option && proto_field_info.is_optional() => {
+            trace.decision(
+                "optional_with_error",
+                "Option<T> -> Option<T> erroring through the error_fn cascade",
+            );
+            let error_expr =
+                generate_field_error_expr(ctx, proto_field, quote! { MissingField }, None);
+            let converted = into_rust(
+                ctx,
+                &option_value_type(ctx.field_type),
+                quote! {
+                    proto_struct.#proto_field
+                        .or_else(::protto::options::lenient_missing)
+                        .ok_or_else(|| #error_expr)?
+                },
+            );
+            quote! {
+                #field_name: Some(#converted)
+            }
+        }
+        ErrorMode::Error => {
+            trace.decision(
+                "unwrap_with_error",
+                "Required field erroring through the error_fn cascade",
+            );
+            let error_expr =
+                generate_field_error_expr(ctx, proto_field, quote! { MissingField }, None);
+            let converted = into_rust(
+                ctx,
+                ctx.field_type,
+                quote! {
+                    proto_struct.#proto_field
+                        .or_else(::protto::options::lenient_missing)
+                        .ok_or_else(|| #error_expr)?
+                },
+            );
+            quote! {
+                #field_name: #converted
+            }
+        }
+
+        ErrorMode::Default(Some(default_fn)) if rust_field_info.is_option => {
+            trace.decision(
+                "optional_with_default_fn",
+                "Option<T> fiel with custom default function",
+            );
+            let default_fn: syn::Path =
+                syn::parse_str(default_fn).expect("Failed to parse default function");
+            quote! {
+                #field_name: proto_struct.#proto_field.map(|v| #converted_v).or_else(|| #default_fn())
+            }
+        }
+        ErrorMode::Default(Some(default_fn)) => {
+            trace.decision(
+                "nonoptional_with_default_fn",
+                "T field with custom default function",
+            );
+            let default_fn: syn::Path =
+                syn::parse_str(default_fn).expect("Failed to parse default function");
+            quote! {
+                #field_name: proto_struct.#proto_field.map(|v| #converted_v).unwrap_or_else(|| #default_fn())
+            }
+        }
+        ErrorMode::Default(None) => {
+            trace.decision("unwrap_with_default_trait", "Field with Default trait");
+            quote! {
+                #field_name: proto_struct.#proto_field.map(|v| #converted_v).unwrap_or_default()
+            }
+        }
+    }
+}
+
+/// The `T` of an `Option<T>` field, or the field's type itself.
+fn option_value_type(field_type: &syn::Type) -> syn::Type {
+    type_analysis::get_inner_type_from_option(field_type).unwrap_or_else(|| field_type.clone())
+}
+
+fn is_option_vec_type(field_type: &syn::Type) -> bool {
+    type_analysis::get_inner_type_from_option(field_type)
+        .map(|inner| type_analysis::is_vec_type(&inner))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+pub mod test_helpers {
+    use crate::field::FieldProcessingContext;
+    use syn::parse::Parser;
+
+    /// Create a mock field processing context for testing
+    pub fn create_mock_context(
+        struct_name: &str,
+        field_name: &str,
+        field_type: &str,
+        proto_module: &str,
+        attributes: &[&str],
+    ) -> (syn::Field, FieldProcessingContext<'static>) {
+        // Parse field type
+        let field_type: syn::Type = syn::parse_str(field_type).unwrap();
+
+        // Create attributes from string descriptions
+        let mut attrs = Vec::new();
+        for attr_str in attributes {
+            if !attr_str.is_empty() {
+                let attr_tokens: proc_macro2::TokenStream =
+                    format!("#[protto({})]", attr_str).parse().unwrap();
+                let attrs_parsed: Vec<syn::Attribute> =
+                    syn::Attribute::parse_outer.parse2(attr_tokens).unwrap();
+                attrs.extend(attrs_parsed);
+            }
+        }
+
+        // Create field
+        let field: syn::Field = syn::Field {
+            attrs,
+            vis: syn::Visibility::Public(Default::default()),
+            mutability: syn::FieldMutability::None,
+            ident: Some(syn::Ident::new(field_name, proc_macro2::Span::call_site())),
+            colon_token: Some(syn::Token![:](proc_macro2::Span::call_site())),
+            ty: field_type.clone(),
+        };
+
+        let field_static: &'static syn::Field = Box::leak(field.clone().into());
+
+        // Create leaked strings for 'static lifetime in tests
+        let struct_name_static = Box::leak(struct_name.to_string().into_boxed_str());
+        let proto_module_static = Box::leak(proto_module.to_string().into_boxed_str());
+        let proto_name_static = Box::leak(struct_name.to_string().into_boxed_str());
+
+        // Create identifiers
+        let struct_ident =
+            Box::leak(syn::Ident::new(struct_name_static, proc_macro2::Span::call_site()).into());
+
+        // Use FieldProcessingContext::new constructor
+        let context = FieldProcessingContext::new(
+            struct_ident,
+            Box::leak(syn::Generics::default().into()),
+            field_static,
+            &None, // struct_level_error_type
+            &None, // struct_level_error_fn
+            false, // struct_level_error_fn_with_value
+            &None, // error_mod
+            false, // static_errors
+            proto_module_static,
+            proto_name_static,
+        );
+
+        (field, context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::optionality::FieldOptionality;
+    use crate::debug;
+    use crate::field::info::ProtoMapping;
+
+    #[test]
+    fn test_code_generation_produces_valid_tokens() {
+        // Test that all strategy types produce valid TokenStream output
+        let test_cases = vec![
+            ("ignore_field", FieldConversionStrategy::Ignore),
+            (
+                "direct_assignment",
+                FieldConversionStrategy::Direct(DirectStrategy::Assignment),
+            ),
+            (
+                "direct_conversion",
+                FieldConversionStrategy::Direct(DirectStrategy::WithConversion),
+            ),
+        ];
+
+        for (name, strategy) in test_cases {
+            let (field, ctx) = test_helpers::create_mock_context(
+                "TestStruct",
+                "test_field",
+                "String",
+                "proto",
+                &[],
+            );
+            let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+            let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+
+            let proto_to_rust = strategy.generate_proto_to_rust_conversion(
+                &ctx,
+                &field,
+                &rust_field_info,
+                &proto_field_info,
+            );
+            let rust_to_proto = strategy.generate_rust_to_proto_conversion(
+                &ctx,
+                &field,
+                &rust_field_info,
+                &proto_field_info,
+            );
+
+            // Verify tokens are not empty and parse correctly
+            assert!(
+                !proto_to_rust.is_empty(),
+                "Proto->Rust generation failed for {}",
+                name
+            );
+            assert!(
+                !rust_to_proto.is_empty() || matches!(strategy, FieldConversionStrategy::Ignore),
+                "Rust->Proto generation failed for {}",
+                name
+            );
+
+            // Verify the generated code contains the field name
+            let proto_to_rust_str = proto_to_rust.to_string();
+            let rust_to_proto_str = rust_to_proto.to_string();
+
+            assert!(
+                proto_to_rust_str.contains("test_field"),
+                "Generated proto->rust code should contain field name: {}",
+                proto_to_rust_str
+            );
+
+            if !matches!(strategy, FieldConversionStrategy::Ignore) {
+                assert!(
+                    rust_to_proto_str.contains("test_field"),
+                    "Generated rust->proto code should contain field name: {}",
+                    rust_to_proto_str
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_error_mode_code_generation() {
+        let error_modes = vec![
+            ErrorMode::None,
+            ErrorMode::Panic,
+            ErrorMode::Default(None),
+            ErrorMode::Default(Some("test_default".to_string())),
+        ];
+
+        for error_mode in error_modes {
+            let strategy = FieldConversionStrategy::Transparent(error_mode.clone());
+
+            let (field, ctx) = test_helpers::create_mock_context(
+                "TestStruct",
+                "test_field",
+                "Option<TransparentWrapper>",
+                "proto",
+                &["transparent"], // Remove expect attributes
+            );
+
+            let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+            let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+            let proto_field_info = ProtoFieldInfo {
+                type_name: proto_field_info.type_name,
+                mapping: proto_field_info.mapping,
+                optionality: FieldOptionality::Optional,
+            };
+
+            let proto_to_rust = strategy.generate_proto_to_rust_conversion(
+                &ctx,
+                &field,
+                &rust_field_info,
+                &proto_field_info,
+            );
+            let code_str = proto_to_rust.to_string();
+            println!("Generated code for {:?}: {}", error_mode, code_str); // Debug print
+
+            match &error_mode {
+                ErrorMode::Panic => {
+                    assert!(code_str.contains("expect"), "Panic mode should use expect");
+                    assert!(
+                        code_str.contains("Some"),
+                        "Should wrap result in Some for Option field"
+                    );
+                }
+                ErrorMode::Default(Some(_)) => {
+                    assert!(
+                        code_str.contains("test_default"),
+                        "Should use custom default"
+                    );
+                }
+                ErrorMode::Default(None) => {
+                    assert!(
+                        code_str.contains("Default :: default"),
+                        "Should use Default trait"
+                    );
+                    assert!(
+                        code_str.contains("or_else"),
+                        "Should use or_else for Option handling"
+                    );
+                }
+                _ => {} // Other modes have different patterns
+            }
+        }
+    }
+
+    #[test]
+    fn test_transparent_optional_error_mode_code_generation() {
+        let error_modes = vec![
+            ErrorMode::None,
+            ErrorMode::Panic,
+            ErrorMode::Default(None),
+            ErrorMode::Default(Some("test_default".to_string())),
+        ];
+
+        for error_mode in error_modes {
+            let strategy = FieldConversionStrategy::Transparent(error_mode.clone());
+
+            // Create a context where proto field is actually optional
+            // This would be a case like: Option<TransparentWrapper> -> Option<inner_proto_type>
+            let (field, ctx) = test_helpers::create_mock_context(
+                "TestStruct",
+                "test_field",
+                "Option<TransparentWrapper>",
+                "proto",
+                &["transparent"],
+            );
+
+            let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+
+            // Manually create proto field info that's optional to trigger the error mode branches
+            let proto_field_info = ProtoFieldInfo {
+                type_name: "Option<inner_type>".to_string(),
+                mapping: ProtoMapping::Scalar,
+                optionality: FieldOptionality::Optional, // This makes is_optional() return true
+            };
+
+            let proto_to_rust = strategy.generate_proto_to_rust_conversion(
+                &ctx,
+                &field,
+                &rust_field_info,
+                &proto_field_info,
+            );
+            let code_str = proto_to_rust.to_string();
+            println!("Generated code for {:?}: {}", error_mode, code_str);
+
+            match &error_mode {
+                ErrorMode::Panic => {
+                    assert!(code_str.contains("expect"), "Panic mode should use expect");
+                }
+                ErrorMode::Error => {
+                    assert!(
+                        code_str.contains("ok_or_else"),
+                        "Error mode should use ok_or_else"
+                    );
+                    assert!(
+                        code_str.contains("TestStructConversionError"),
+                        "Should use generated error type"
+                    );
+                }
+                ErrorMode::Default(Some(_)) => {
+                    assert!(
+                        code_str.contains("test_default"),
+                        "Should use custom default"
+                    );
+                }
+                ErrorMode::Default(None) => {
+                    assert!(
+                        code_str.contains("Default :: default"),
+                        "Should use Default trait"
+                    );
+                    assert!(
+                        code_str.contains("or_else"),
+                        "Should use or_else for Option handling"
+                    );
+                }
+                ErrorMode::None => {
+                    assert!(
+                        code_str.contains("map"),
+                        "None mode should use map for optional"
+                    );
+                    assert!(
+                        code_str.contains("TransparentWrapper :: from"),
+                        "Should map with From conversion"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_strategy_code_generation() {
+        let custom_strategies = vec![
+            CustomConversionStrategy::FromFn("custom_from".to_string()),
+            CustomConversionStrategy::IntoFn("custom_into".to_string()),
+            CustomConversionStrategy::Bidirectional(
+                "custom_from".to_string(),
+                "custom_into".to_string(),
+            ),
+        ];
+
+        for custom_strategy in custom_strategies {
+            let strategy = FieldConversionStrategy::Custom(custom_strategy.clone());
+            let (field, ctx) = test_helpers::create_mock_context(
+                "TestStruct",
+                "test_field",
+                "CustomType",
+                "proto",
+                &[],
+            );
+            let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+            let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+
+            let proto_to_rust = strategy.generate_proto_to_rust_conversion(
+                &ctx,
+                &field,
+                &rust_field_info,
+                &proto_field_info,
+            );
+            let rust_to_proto = strategy.generate_rust_to_proto_conversion(
+                &ctx,
+                &field,
+                &rust_field_info,
+                &proto_field_info,
+            );
+
+            let proto_to_rust_str = proto_to_rust.to_string();
+            let rust_to_proto_str = rust_to_proto.to_string();
+
+            match &custom_strategy {
+                CustomConversionStrategy::FromFn(fn_name) => {
+                    assert!(
+                        proto_to_rust_str.contains(fn_name),
+                        "Should use custom from function"
+                    );
+                }
+                CustomConversionStrategy::IntoFn(fn_name) => {
+                    assert!(
+                        rust_to_proto_str.contains(fn_name),
+                        "Should use custom into function"
+                    );
+                }
+                CustomConversionStrategy::Bidirectional(from_fn, into_fn) => {
+                    assert!(
+                        proto_to_rust_str.contains(from_fn),
+                        "Should use custom from function"
+                    );
+                    assert!(
+                        rust_to_proto_str.contains(into_fn),
+                        "Should use custom into function"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_group_code_generation() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "address",
+            "Address",
+            "proto",
+            &["group(street, city = \"city_name\")"],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(
+            strategy,
+            FieldConversionStrategy::Group(vec![
+                ("street".to_string(), "street".to_string()),
+                ("city".to_string(), "city_name".to_string()),
+            ])
+        );
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains("address : Address {"));
+        assert!(proto_to_rust.contains("city : proto_struct . city_name . into ()"));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.contains("street : address . street . into ()"));
+        assert!(rust_to_proto.contains("city_name : address . city . into ()"));
+    }
+
+    #[test]
+    fn test_message_vec_converts_without_an_intermediate_vec() {
+        let (field, ctx) =
+            test_helpers::create_mock_context("TestStruct", "tracks", "Vec<Track>", "proto", &[]);
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(
+            strategy,
+            FieldConversionStrategy::Collection(CollectionStrategy::Collect(ErrorMode::None))
+        );
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains(
+            ":: protto :: collection :: convert_vec (proto_struct . tracks , | element | \
+                 :: protto :: __repr_from_proto ! (Track , element))"
+        ));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.contains(":: protto :: collection :: convert_vec (tracks ,"));
+        assert!(!rust_to_proto.contains("collect ()"));
+    }
+
+    #[test]
+    fn test_primitive_vecs_collect_in_place() {
+        for field_type in ["Vec<u64>", "Vec<String>"] {
+            let (field, ctx) =
+                test_helpers::create_mock_context("TestStruct", "ids", field_type, "proto", &[]);
+            let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+            let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+            let strategy = FieldConversionStrategy::from_field_info(
+                &ctx,
+                &field,
+                &rust_field_info,
+                &proto_field_info,
+            );
+            assert_eq!(
+                strategy,
+                FieldConversionStrategy::Collection(CollectionStrategy::InPlace),
+                "{field_type}"
+            );
+
+            let rust_to_proto = strategy
+                .generate_rust_to_proto_conversion(
+                    &ctx,
+                    &field,
+                    &rust_field_info,
+                    &proto_field_info,
+                )
+                .to_string();
+            assert_eq!(
+                rust_to_proto,
+                "ids : ids . into_iter () . map (Into :: into) . collect ()"
+            );
+        }
+    }
+
+    #[test]
+    fn test_transparent_collection_newtypes_convert_their_elements() {
+        use crate::analysis::type_analysis::NewtypeCollection;
+
+        crate::registry::register_collection_newtype("TagList", NewtypeCollection::Vec);
+        crate::registry::register_collection_newtype("LabelMap", NewtypeCollection::Map);
+
+        let cases = [
+            (
+                "TagList",
+                "proto_struct . tags . into_iter () . map (Into :: into) . collect ()",
+            ),
+            (
+                "LabelMap",
+                "proto_struct . tags . into_iter () . map (| (key , value) | \
+                 (key . into () , value . into ())) . collect ()",
+            ),
+        ];
+        for (field_type, elements) in cases {
+            let (field, ctx) = test_helpers::create_mock_context(
+                "TestStruct",
+                "tags",
+                field_type,
+                "proto",
+                &["transparent"],
+            );
+            let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+            let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+            let strategy = FieldConversionStrategy::from_field_info(
+                &ctx,
+                &field,
+                &rust_field_info,
+                &proto_field_info,
+            );
+            assert!(
+                matches!(strategy, FieldConversionStrategy::Transparent(_)),
+                "{field_type}"
+            );
+
+            let proto_to_rust = strategy
+                .generate_proto_to_rust_conversion(
+                    &ctx,
+                    &field,
+                    &rust_field_info,
+                    &proto_field_info,
+                )
+                .to_string();
+            assert_eq!(
+                proto_to_rust,
+                format!(
+                    "tags : < {field_type} as :: protto :: newtype :: CollectionNewtype > :: \
+                     from_collection ({elements})"
+                )
+            );
+
+            let rust_to_proto = strategy
+                .generate_rust_to_proto_conversion(
+                    &ctx,
+                    &field,
+                    &rust_field_info,
+                    &proto_field_info,
+                )
+                .to_string();
+            assert!(
+                rust_to_proto.starts_with(&format!(
+                    "tags : < {field_type} as :: protto :: newtype :: CollectionNewtype > :: \
+                     into_collection (tags) . into_iter ()"
+                )),
+                "{rust_to_proto}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_memoized_vec_converts_through_cache() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "resources",
+            "Vec<std::sync::Arc<Resource>>",
+            "proto",
+            &["memoize"],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(
+            strategy,
+            FieldConversionStrategy::Collection(CollectionStrategy::Memoized)
+        );
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert_eq!(
+            proto_to_rust,
+            "resources : :: protto :: memoize :: from_messages (proto_struct . resources)"
+        );
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert_eq!(
+            rust_to_proto,
+            "resources : :: protto :: memoize :: to_messages (resources)"
+        );
+    }
+
+    #[test]
+    fn test_fields_of_proto_types_are_moved() {
+        for (field_type, proto_module, attrs) in [
+            ("Option<proto::Header>", "proto", &[][..]),
+            ("Option<proto::Header>", "proto", &["proto_optional"][..]),
+            ("Option<crate::proto::Header>", "crate::proto", &[][..]),
+            ("Vec<proto::media::v1::Track>", "proto::media::v1", &[][..]),
+            ("HashMap<String, proto::Track>", "proto", &[][..]),
+        ] {
+            let (field, ctx) = test_helpers::create_mock_context(
+                "TestStruct",
+                "value",
+                field_type,
+                proto_module,
+                attrs,
+            );
+            let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+            let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+            let strategy = FieldConversionStrategy::from_field_info(
+                &ctx,
+                &field,
+                &rust_field_info,
+                &proto_field_info,
+            );
+
+            let proto_to_rust = strategy
+                .generate_proto_to_rust_conversion(
+                    &ctx,
+                    &field,
+                    &rust_field_info,
+                    &proto_field_info,
+                )
+                .to_string();
+            assert_eq!(
+                proto_to_rust, "value : proto_struct . value",
+                "{field_type}"
+            );
+            let rust_to_proto = strategy
+                .generate_rust_to_proto_conversion(
+                    &ctx,
+                    &field,
+                    &rust_field_info,
+                    &proto_field_info,
+                )
+                .to_string();
+            assert_eq!(rust_to_proto, "value : value", "{field_type}");
+        }
+    }
+
+    #[test]
+    fn test_proto_field_type_overrides_scalar_assignment() {
+        for (attrs, expected) in [
+            (
+                &[][..],
+                FieldConversionStrategy::Direct(DirectStrategy::Assignment),
+            ),
+            (
+                &["proto_field_type = \"AccountId\""][..],
+                FieldConversionStrategy::Direct(DirectStrategy::WithConversion),
+            ),
+            (
+                &["proto_field_type = \"u64\""][..],
+                FieldConversionStrategy::Direct(DirectStrategy::Assignment),
+            ),
+        ] {
+            let (field, ctx) =
+                test_helpers::create_mock_context("TestStruct", "id", "u64", "proto", attrs);
+            let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+            let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+            let strategy = FieldConversionStrategy::from_field_info(
+                &ctx,
+                &field,
+                &rust_field_info,
+                &proto_field_info,
+            );
+            assert_eq!(strategy, expected, "{attrs:?}");
+        }
+    }
+
+    #[test]
+    fn test_proto_boxed_field_is_unboxed_and_boxed() {
+        for (field_type, attrs, proto_to_rust, rust_to_proto) in [
+            (
+                "Option<Box<Node>>",
+                &["proto_boxed"][..],
+                "parent : proto_struct . parent . map (| boxed | :: std :: boxed :: Box :: new ((* boxed) . into ()))",
+                "parent : parent . map (| value | :: std :: boxed :: Box :: new ((* value) . into ()))",
+            ),
+            (
+                "Header",
+                &["proto_boxed", "default"][..],
+                "parent : proto_struct . parent . map (| boxed | (* boxed) . into ()) . unwrap_or_else (|| Default :: default ())",
+                "parent : Some (:: std :: boxed :: Box :: new (parent . into ()))",
+            ),
+        ] {
+            let (field, ctx) = test_helpers::create_mock_context(
+                "TestStruct",
+                "parent",
+                field_type,
+                "proto",
+                attrs,
+            );
+            let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+            let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+            let strategy = FieldConversionStrategy::from_field_info(
+                &ctx,
+                &field,
+                &rust_field_info,
+                &proto_field_info,
+            );
+            assert!(
+                matches!(strategy, FieldConversionStrategy::Boxed(_)),
+                "{field_type}"
+            );
+
+            let generated = strategy
+                .generate_proto_to_rust_conversion(
+                    &ctx,
+                    &field,
+                    &rust_field_info,
+                    &proto_field_info,
+                )
+                .to_string();
+            assert_eq!(generated, proto_to_rust);
+            let generated = strategy
+                .generate_rust_to_proto_conversion(
+                    &ctx,
+                    &field,
+                    &rust_field_info,
+                    &proto_field_info,
+                )
+                .to_string();
+            assert_eq!(generated, rust_to_proto);
+        }
+    }
+
+    #[test]
+    fn test_path_code_generation() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "log_dir",
+            "Option<std::path::PathBuf>",
+            "proto",
+            &["path_encoding = \"utf8_strict\""],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(
+            strategy,
+            FieldConversionStrategy::Path(Some(PathEncoding::Utf8Strict))
+        );
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains(
+            "log_dir : proto_struct . log_dir . map (:: protto :: path_encoding :: from_string)"
+        ));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.contains("transpose ()"));
+        assert!(rust_to_proto.contains("TestStructConversionError :: InvalidValue"));
+    }
+
+    #[test]
+    fn test_bytes_code_generation() {
+        for (field_type, proto_to_rust, rust_to_proto) in [
+            (
+                "Vec<u8>",
+                "payload : proto_struct . payload . into ()",
+                "payload : payload . into ()",
+            ),
+            (
+                "Option<bytes::Bytes>",
+                "payload : proto_struct . payload . map (Into :: into)",
+                "payload : payload . map (Into :: into)",
+            ),
+        ] {
+            let (field, ctx) = test_helpers::create_mock_context(
+                "TestStruct",
+                "payload",
+                field_type,
+                "proto",
+                &[],
+            );
+            let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+            let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+            let strategy = FieldConversionStrategy::from_field_info(
+                &ctx,
+                &field,
+                &rust_field_info,
+                &proto_field_info,
+            );
+            assert_eq!(strategy, FieldConversionStrategy::Bytes);
+
+            let generated = strategy
+                .generate_proto_to_rust_conversion(
+                    &ctx,
+                    &field,
+                    &rust_field_info,
+                    &proto_field_info,
+                )
+                .to_string();
+            assert_eq!(generated, proto_to_rust);
+            let generated = strategy
+                .generate_rust_to_proto_conversion(
+                    &ctx,
+                    &field,
+                    &rust_field_info,
+                    &proto_field_info,
+                )
+                .to_string();
+            assert_eq!(generated, rust_to_proto);
+        }
+    }
+
+    #[test]
+    fn test_bool_enum_code_generation() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "enabled",
+            "bool",
+            "proto",
+            &[
+                "bool_enum(proto_enum = \"FeatureState\", true = \"Enabled\", false = \"Disabled\", unknown = \"false\")",
+            ],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(
+            strategy,
+            FieldConversionStrategy::BoolEnum(BoolEnumMapping {
+                proto_enum: "FeatureState".to_string(),
+                true_variant: "Enabled".to_string(),
+                false_variant: "Disabled".to_string(),
+                unknown: UnknownVariantPolicy::Value(false),
+            })
+        );
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(
+            proto_to_rust
+                .contains("value if value == proto :: FeatureState :: Enabled as i32 => true")
+        );
+        assert!(proto_to_rust.contains("_ => false"));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.contains("proto :: FeatureState :: Disabled as i32"));
+    }
+
+    #[test]
+    fn test_fallible_option_code_generation() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "track",
+            "Option<ValidTrack>",
+            "proto",
+            &["fallible"],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(
+            strategy,
+            FieldConversionStrategy::Option(OptionStrategy::TryMap)
+        );
+        assert!(
+            strategy
+                .validate_for_context(&ctx, &rust_field_info, &proto_field_info)
+                .is_ok()
+        );
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains(
+            "track : proto_struct . track . map (TryInto :: try_into) . transpose () . map_err (| _ | TestStructConversionError :: InvalidValue"
+        ));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.contains(
+            "track : track . map (| v | :: protto :: __repr_to_proto ! (ValidTrack , v))"
+        ));
+    }
+
+    #[test]
+    fn test_fallible_collection_code_generation() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "tracks",
+            "Option<Vec<ValidTrack>>",
+            "proto",
+            &["fallible"],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(
+            strategy,
+            FieldConversionStrategy::Collection(CollectionStrategy::TryCollect(Some(
+                EmptyRepeated::None
+            )))
+        );
+        assert!(
+            strategy
+                .validate_for_context(&ctx, &rust_field_info, &proto_field_info)
+                .is_ok()
+        );
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains("if proto_struct . tracks . is_empty () { None }"));
+        assert!(proto_to_rust.contains(
+            "Some (proto_struct . tracks . into_iter () . map (TryInto :: try_into) . collect :: < Result < _ , _ >> () . map_err (| _ | TestStructConversionError :: InvalidValue"
+        ));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.contains("tracks : tracks . map (| vec |"));
+    }
+
+    #[test]
+    fn test_custom_types_convert_through_repr() {
+        for (field_type, attrs, proto_to_rust, rust_to_proto) in [
+            (
+                "Money",
+                &["proto_required"][..],
+                "amount : :: protto :: __repr_from_proto ! (Money , proto_struct . amount)",
+                "amount : :: protto :: __repr_to_proto ! (Money , amount)",
+            ),
+            (
+                "Option<Money>",
+                &[][..],
+                "amount : proto_struct . amount . map (| v | :: protto :: __repr_from_proto ! (Money , v))",
+                "amount : amount . map (| v | :: protto :: __repr_to_proto ! (Money , v))",
+            ),
+            (
+                "u64",
+                &[][..],
+                "amount : proto_struct . amount",
+                "amount : amount",
+            ),
+        ] {
+            let (field, ctx) = test_helpers::create_mock_context(
+                "TestStruct",
+                "amount",
+                field_type,
+                "proto",
+                attrs,
+            );
+            let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+            let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+            let strategy = FieldConversionStrategy::from_field_info(
+                &ctx,
+                &field,
+                &rust_field_info,
+                &proto_field_info,
+            );
+
+            let code = strategy
+                .generate_proto_to_rust_conversion(
+                    &ctx,
+                    &field,
+                    &rust_field_info,
+                    &proto_field_info,
+                )
+                .to_string();
+            assert_eq!(code, proto_to_rust, "{field_type}");
+            let code = strategy
+                .generate_rust_to_proto_conversion(
+                    &ctx,
+                    &field,
+                    &rust_field_info,
+                    &proto_field_info,
+                )
+                .to_string();
+            assert_eq!(code, rust_to_proto, "{field_type}");
+        }
+    }
+
+    #[test]
+    fn test_btree_map_converts_each_entry() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "tracks",
+            "BTreeMap<String, Track>",
+            "proto",
+            &[],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(
+            strategy,
+            FieldConversionStrategy::Collection(CollectionStrategy::Collect(ErrorMode::None))
+        );
+        let entries = |repr| {
+            format!(
+                ". into_iter () . map (| (key , value) | (key . into () , \
+                 :: protto :: {repr} ! (Track , value))) . collect ()"
+            )
+        };
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains(&format!(
+            "tracks : proto_struct . tracks {}",
+            entries("__repr_from_proto")
+        )));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.contains(&format!("tracks : tracks {}", entries("__repr_to_proto"))));
+    }
+
+    #[test]
+    fn test_result_oneof_code_generation() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "GetUserResponse",
+            "outcome",
+            "Result<User, ErrorInfo>",
+            "proto",
+            &["result_oneof(ok = \"user\", err = \"error_info\")"],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert!(matches!(
+            strategy,
+            FieldConversionStrategy::ResultOneof(_, ErrorMode::None)
+        ));
+        assert!(
+            strategy
+                .validate_for_context(&ctx, &rust_field_info, &proto_field_info)
+                .is_ok()
+        );
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains(
+            "proto :: get_user_response :: Outcome :: User (value) => :: core :: result :: Result :: Ok (value . into ())"
+        ));
+        assert!(proto_to_rust.contains(
+            "proto :: get_user_response :: Outcome :: ErrorInfo (value) => :: core :: result :: Result :: Err (value . into ())"
+        ));
+        assert!(
+            proto_to_rust
+                .contains(". expect (& format ! (\"Proto field {} is required\" , \"outcome\"))")
+        );
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.starts_with("outcome : Some (match outcome {"));
+        assert!(rust_to_proto.contains(
+            ":: core :: result :: Result :: Err (value) => proto :: get_user_response :: Outcome :: ErrorInfo (value . into ())"
+        ));
+
+        let (field, ctx) = test_helpers::create_mock_context(
+            "GetUserResponse",
+            "lookup",
+            "u64",
+            "proto",
+            &["result_oneof(ok = \"ok\", err = \"err\")"],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert!(
+            strategy
+                .validate_for_context(&ctx, &rust_field_info, &proto_field_info)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_enum_keys_code_generation() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "quotas",
+            "BTreeMap<Tier, u32>",
+            "proto",
+            &["enum_keys = \"error\""],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(
+            strategy,
+            FieldConversionStrategy::EnumKeyMap(UnknownI32Policy::Error)
+        );
+        assert!(
+            strategy
+                .validate_for_context(&ctx, &rust_field_info, &proto_field_info)
+                .is_ok()
+        );
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains(
+            ":: protto :: options :: enum_or_default :: < Tier > (key) . map (| converted | (converted , value . into ())) . map_err (| _ | TestStructConversionError :: InvalidValue"
+        ));
+        assert!(proto_to_rust.contains(". collect :: < Result < _ , _ >> () ?"));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.contains(". map (| (key , value) | (key as i32 , value . into ()))"));
+    }
+
+    #[test]
+    fn test_pod_slice_code_generation() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "samples",
+            "Vec<Sample>",
+            "proto",
+            &["bytes_as = \"pod_slice\""],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(
+            strategy,
+            FieldConversionStrategy::PackedBytes(crate::analysis::type_analysis::BytesAs::PodSlice)
+        );
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains(
+            "samples : :: protto :: pod :: from_bytes (& proto_struct . samples) . map_err (| _ | TestStructConversionError :: InvalidValue"
+        ));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.contains("samples : :: protto :: pod :: to_bytes (& samples)"));
+    }
+
+    #[test]
+    fn test_decimal_units_nanos_code_generation() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "total",
+            "Decimal",
+            "proto",
+            &["decimal = \"units_nanos\""],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(
+            strategy,
+            FieldConversionStrategy::Decimal(DecimalAs::UnitsNanos)
+        );
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains(
+            "total : :: protto :: decimal :: from_message (proto_struct . total . as_ref ()) . map_err (| _ | TestStructConversionError :: InvalidValue"
+        ));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.contains(
+            "total : Some (:: protto :: decimal :: to_message (total) . map_err (| _ | TestStructConversionError :: InvalidValue"
+        ));
+    }
+
+    #[test]
+    fn test_duration_millis_code_generation() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "timeout",
+            "Option<Duration>",
+            "proto",
+            &["duration = \"millis\""],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(
+            strategy,
+            FieldConversionStrategy::Duration(DurationUnit::Millis)
+        );
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains(
+            "timeout : proto_struct . timeout . map (| value | :: protto :: duration :: from_units (value , :: protto :: duration :: DurationUnit :: Millis)) . transpose () . map_err (| _ | TestStructConversionError :: InvalidValue"
+        ));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.contains(
+            "timeout : timeout . as_ref () . map (| value | :: protto :: duration :: to_units (value , :: protto :: duration :: DurationUnit :: Millis)) . transpose () . map_err (| _ | TestStructConversionError :: InvalidValue"
+        ));
+    }
+
+    #[test]
+    fn test_with_str_code_generation() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "address",
+            "IpAddr",
+            "proto",
+            &["with_str"],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(strategy, FieldConversionStrategy::WithStr);
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains(
+            "address : proto_struct . address . parse () . map_err (| _ | TestStructConversionError :: InvalidValue"
+        ));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.contains("address : address . to_string ()"));
+    }
+
+    #[test]
+    fn test_json_code_generation() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "settings",
+            "Option<Settings>",
+            "proto",
+            &["json"],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(strategy, FieldConversionStrategy::Json);
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains(
+            "settings : proto_struct . settings . as_deref () . map (:: protto :: json :: serde_json :: from_str) . transpose () . map_err (| _ | TestStructConversionError :: InvalidValue"
+        ));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.contains(
+            "settings : settings . as_ref () . map (:: protto :: json :: serde_json :: to_string) . transpose () . map_err"
+        ));
+    }
+
+    #[test]
+    fn test_unspecified_code_generation() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "phase",
+            "Phase",
+            "proto",
+            &["unspecified = \"map_to = Pending\""],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(
+            strategy,
+            FieldConversionStrategy::EnumUnspecified(UnspecifiedPolicy::MapTo(
+                "Pending".to_string()
+            ))
+        );
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains(
+            "phase : match proto_struct . phase { 0 => < Phase > :: Pending , value => < Phase > :: from (value) , }"
+        ));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(rust_to_proto.contains("phase : i32 :: from (phase)"));
+
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "phase",
+            "Option<Phase>",
+            "proto",
+            &["unspecified = \"error\""],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains(
+            "None => None , Some (0) => return Err (TestStructConversionError :: InvalidValue"
+        ));
+        assert!(proto_to_rust.contains("Some (value) => Some (< Phase > :: from (value))"));
+    }
+
+    #[test]
+    fn test_map_by_code_generation() {
+        let (field, ctx) = test_helpers::create_mock_context(
+            "TestStruct",
+            "settings",
+            "HashMap<String, Setting>",
+            "proto",
+            &["map_by = \"key\""],
+        );
+        let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+        let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+        let strategy = FieldConversionStrategy::from_field_info(
+            &ctx,
+            &field,
+            &rust_field_info,
+            &proto_field_info,
+        );
+        assert_eq!(strategy, FieldConversionStrategy::MapBy("key".to_string()));
+        assert!(
+            strategy
+                .validate_for_context(&ctx, &rust_field_info, &proto_field_info)
+                .is_ok()
+        );
+
+        let proto_to_rust = strategy
+            .generate_proto_to_rust_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(proto_to_rust.contains(
+            ". map (| element | (element . key . clone () . into () , element . into ()))"
+        ));
+
+        let rust_to_proto = strategy
+            .generate_rust_to_proto_conversion(&ctx, &field, &rust_field_info, &proto_field_info)
+            .to_string();
+        assert!(
+            rust_to_proto
+                .contains("entries . sort_unstable_by (| (a , _) , (b , _) | a . cmp (b))")
+        );
+    }
+
+    #[test]
+    fn test_custom_strategy_with_error_code_generation() {
+        let custom_strategy = CustomConversionStrategy::Bidirectional(
+            "custom_from".to_string(),
+            "custom_into".to_string(),
+        );
+
+        let error_modes = vec![ErrorMode::Panic, ErrorMode::Error, ErrorMode::Default(None)];
+
+        for error_mode in error_modes {
+            let strategy = FieldConversionStrategy::CustomWithError(
+                custom_strategy.clone(),
+                error_mode.clone(),
+            );
+            let (field, ctx) = test_helpers::create_mock_context(
+                "TestStruct",
+                "test_field",
+                "CustomComplexType",
+                "proto",
+                &[
+                    "proto_to_rust_fn = \"custom_from\"",
+                    "rust_to_proto_fn = \"custom_into\"",
+                ],
+            );
+            let rust_field_info = RustFieldInfo::analyze(&ctx, &field);
+            let proto_field_info = ProtoFieldInfo::infer_from(&ctx, &field, &rust_field_info);
+
+            let proto_to_rust = strategy.generate_proto_to_rust_conversion(
+                &ctx,
+                &field,
+                &rust_field_info,
+                &proto_field_info,
+            );
+            let code_str = proto_to_rust.to_string();
+            println!("generated code: {}", debug::format_rust_code(&code_str));
+
+            // Should contain the custom function name
+            assert!(
+                code_str.contains("custom_from"),
+                "Should use custom from function"
+            );
+
+            // Should have appropriate error handling based on mode
+            match &error_mode {
+                ErrorMode::Panic => {
+                    assert!(code_str.contains("expect"), "Panic mode should use expect");
+                }
+                ErrorMode::Error => {
+                    assert!(
+                        code_str.contains("expect"),
+                        "Error mode should use expect for custom conversion"
+                    );
+                    assert!(
+                        code_str.contains("custom_from"),
+                        "Should use custom from function"
+                    );
+                }
+                ErrorMode::Default(None) => {
+                    assert!(
+                        code_str.contains("expect"),
+                        "Custom conversion uses expect for all error modes"
+                    );
+                    assert!(
+                        code_str.contains("custom_from"),
+                        "Should use custom from function"
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+}
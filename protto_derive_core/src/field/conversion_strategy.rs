@@ -0,0 +1,1446 @@
+use crate::analysis::{
+    attribute_parser::{BoolEnumMapping, ResultOneofMapping, UnknownVariantPolicy},
+    expect_analysis::ExpectMode,
+    optionality::NonePolicy,
+    type_analysis::{
+        self, BoundedCollection, BytesAs, CapacityOverflow, DecimalAs, DurationUnit, EmptyRepeated,
+        PathEncoding, UnknownI32Policy, UnspecifiedPolicy,
+    },
+};
+use crate::debug::CallStackDebug;
+use crate::field::{
+    FieldProcessingContext,
+    custom_conversion::CustomConversionStrategy,
+    error_mode::ErrorMode,
+    info::{self as field_info, ProtoFieldInfo, RustFieldInfo},
+};
+
+/// Consolidated field conversion strategy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldConversionStrategy {
+    /// Field is ignored in proto conversion
+    Ignore,
+
+    /// Field is built by the user's `FromProtoParts` impl; the derive only reserves its proto
+    /// field
+    Manual,
+
+    /// Uses custom user-provided functions
+    Custom(CustomConversionStrategy),
+
+    /// Custom functions that need error handling
+    CustomWithError(CustomConversionStrategy, ErrorMode),
+
+    /// Direct assignment or conversion between compatible types
+    Direct(DirectStrategy),
+
+    /// Handles optionality mismatches between rust and proto
+    Option(OptionStrategy),
+
+    /// Transparent wrapper field conversion
+    Transparent(ErrorMode),
+
+    /// Collection (Vec, etc.) conversions
+    Collection(CollectionStrategy),
+
+    /// Repeated proto field collected into a `SmallVec` or `ArrayVec` (behind the features of the
+    /// same names); `overflow` decides what an `ArrayVec` does with elements past its capacity
+    Bounded(BoundedCollection, CapacityOverflow),
+
+    /// `PathBuf` / `OsString` (or `Option` of either) encoded as a proto string or bytes field;
+    /// the encoding must be given explicitly via `path_encoding`
+    Path(Option<PathEncoding>),
+
+    /// `Vec<u8>` or `bytes::Bytes` (or `Option` of either) for a proto bytes field, converted
+    /// with `Into` so it works whichever type prost is configured to generate
+    Bytes,
+
+    /// Rust sub-struct assembled from / split into several flat proto fields,
+    /// as (sub-struct field, proto field) pairs
+    Group(Vec<(String, String)>),
+
+    /// Rust `bool` mapped onto two designated variants of a proto enum
+    BoolEnum(BoolEnumMapping),
+
+    /// Rust `Result<T, E>` (or `Option` of one) carried by a proto oneof with an ok and an err
+    /// field; the error mode applies when a required rust field's oneof is unset
+    ResultOneof(ResultOneofMapping, ErrorMode),
+
+    /// Rust enum (or `Option` of one) carried by a plain proto `int32` through its discriminant,
+    /// read back with `TryFrom<i32>`; the policy decides what an unknown value converts to
+    EnumAsI32(UnknownI32Policy),
+
+    /// `HashMap` / `BTreeMap` with rust enum values for a proto map of enum values, which prost
+    /// carries as `i32`; each value goes through the prost enum's `TryFrom<i32>` and the policy
+    /// decides what an unknown value converts to
+    EnumMap(UnknownI32Policy),
+
+    /// `HashMap` / `BTreeMap` keyed by a rust enum for a proto map with `int32` keys, since proto
+    /// map keys cannot be enums; each key is read back with the enum's `TryFrom<i32>` and the
+    /// policy decides what an unknown key converts to. Values convert with `Into`.
+    EnumKeyMap(UnknownI32Policy),
+
+    /// Rust enum (or `Option` of one) for a proto enum whose `*_UNSPECIFIED = 0` value the rust
+    /// enum has no variant for; the policy decides what 0 converts to, and other values convert
+    /// with `Into`
+    EnumUnspecified(UnspecifiedPolicy),
+
+    /// Rust `Vec` of plain-old-data elements packed into a proto bytes field
+    PackedBytes(BytesAs),
+
+    /// `rust_decimal::Decimal` (or `Option` of it) carried as a proto string or units/nanos
+    /// message; invalid values are errors, or panics under `expect(panic)`
+    Decimal(DecimalAs),
+
+    /// `Duration` (or `Option` of it) carried as a whole number of seconds, millis or nanos in a
+    /// proto `int64`
+    Duration(DurationUnit),
+
+    /// A `FromStr + Display` type (or `Option` of one) carried as a proto string, set via
+    /// `with_str`
+    WithStr,
+
+    /// A `Serialize + DeserializeOwned` type (or `Option` of one) carried as JSON in a proto
+    /// string, set via `json`
+    Json,
+
+    /// `HashMap` / `BTreeMap` carried as a repeated proto message, keyed by the named field of
+    /// each element; the elements go back to proto sorted by key, set via `map_by`
+    MapBy(String),
+
+    /// `Option<T>` for a `google.protobuf` wrapper message field such as `UInt64Value`,
+    /// recognized from the build metadata with the `wrapper_types` feature
+    Wrapper,
+
+    /// Message field prost generates boxed (`Option<Box<T>>`), declared via `proto_boxed` or
+    /// recorded in the build metadata; the error mode applies when the rust field is required
+    Boxed(ErrorMode),
+
+    /// Rust `bool` reading whether an optional proto field is set, via `presence_of`; the proto
+    /// field is left unset when converting back
+    PresenceOf,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DirectStrategy {
+    /// T -> T (same types, no conversion)
+    Assignment,
+
+    /// T -> U (different types, use Into trait)
+    WithConversion,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionStrategy {
+    /// T -> Some(T) (required -> optional); a rust `None` is written to proto per the
+    /// `none_as` policy, which must be given explicitly
+    Wrap(Option<NonePolicy>),
+
+    /// Some(T) -> T (optional -> required)
+    Unwrap(ErrorMode),
+
+    /// Option<T> -> Option<U> (optional -> optional)
+    Map,
+
+    /// Option<T> -> Option<U> where the nested proto -> rust conversion is `TryFrom`
+    TryMap,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollectionStrategy {
+    /// Vec<T> -> Vec<U> with conversion
+    Collect(ErrorMode),
+
+    /// Option<Vec<T>> -> Option<Vec<U>>; `empty_repeated` picks what an empty repeated field
+    /// converts to
+    MapOption(EmptyRepeated),
+
+    /// Vec<ProtoType> -> Vec<ProtoType> or HashMap<K, ProtoType> -> HashMap<K, ProtoType>
+    /// (no conversion)
+    DirectAssignment,
+
+    /// Vec<T> <-> Vec<T> or Vec<Newtype> <-> Vec<T>: the element layouts match, so the
+    /// conversion is collected in place, reusing the source allocation
+    InPlace,
+
+    /// Vec<Message> <-> Vec<Arc<T>> where identical elements are converted once and shared,
+    /// set via `memoize`
+    Memoized,
+
+    /// Vec<T> or Option<Vec<T>> (with its `empty_repeated` policy) where each element's proto ->
+    /// rust conversion is `TryFrom`, set via `fallible`
+    TryCollect(Option<EmptyRepeated>),
+}
+
+impl FieldConversionStrategy {
+    /// Create consolidated strategy from field analysis using simplified decision tree
+    ///
+    /// Attribute-driven strategies are checked first, in order, and the first match wins:
+    /// `ignore` over everything else, then `manual`, `group`, `bool_enum`, `result_oneof`,
+    /// `enum_as_i32`,
+    /// `enum_values`, `bytes_as`, `decimal`, `duration`, `with_str`, boxed fields, `memoize`, `fallible`,
+    /// custom functions, `transparent` and wrapper messages. For the remaining scalar and message fields:
+    ///
+    /// - an explicit default (`default`, `default_fn`, `expect = "default"`) unwraps the proto
+    ///   value with that default, whatever the optionality on either side
+    /// - required on both sides converts directly; `expect` has nothing to act on
+    /// - an `Option` rust field for a required proto field wraps, writing `None` per `none_as`
+    /// - a required rust field for an optional proto field unwraps in the `expect` error mode
+    /// - optional on both sides maps through the `Option`, unless `expect` asks to unwrap
+    pub fn from_field_info(
+        ctx: &FieldProcessingContext,
+        _field: &syn::Field,
+        rust_field_info: &RustFieldInfo,
+        proto_field_info: &ProtoFieldInfo,
+    ) -> Self {
+        let trace = CallStackDebug::with_context(
+            "field::conversion_strategy::FieldConversionStrategy",
+            "from_field_info",
+            ctx.struct_name,
+            ctx.field_name,
+            &[
+                (
+                    "rust_has_ignore",
+                    &rust_field_info.has_proto_ignore.to_string(),
+                ),
+                ("rust_is_option", &rust_field_info.is_option.to_string()),
+                ("rust_is_vec", &rust_field_info.is_vec.to_string()),
+                (
+                    "proto_is_optional",
+                    &proto_field_info.is_optional().to_string(),
+                ),
+                (
+                    "proto_is_repeated",
+                    &proto_field_info.is_repeated().to_string(),
+                ),
+            ],
+        );
+
+        // Handle special cases first (sequential elimination)
+        if rust_field_info.has_proto_ignore {
+            trace.decision("proto_ignore", "Field marked with #[protto(ignore)]");
+            Self::Ignore
+        } else if ctx.protto_meta.manual {
+            trace.decision("manual", "Field built through FromProtoParts");
+            Self::Manual
+        } else if ctx.protto_meta.presence_of.is_some() {
+            trace.decision(
+                "presence_of",
+                "bool read from an optional proto field's presence",
+            );
+            Self::PresenceOf
+        } else if let Some(group) = &ctx.protto_meta.group {
+            trace.decision("group_fields", "Sub-struct mapped to flat proto fields");
+            Self::Group(group.clone())
+        } else if let Some(mapping) = &ctx.protto_meta.bool_enum {
+            trace.decision("bool_enum", "bool mapped onto two proto enum variants");
+            Self::BoolEnum(mapping.clone())
+        } else if let Some(mapping) = &ctx.protto_meta.result_oneof {
+            trace.decision("result_oneof", "Result carried by an ok/err proto oneof");
+            Self::ResultOneof(
+                mapping.clone(),
+                ErrorMode::from_field_context(ctx, rust_field_info),
+            )
+        } else if let Some(policy) = ctx.protto_meta.enum_as_i32 {
+            trace.decision("enum_as_i32", "enum carried by its i32 discriminant");
+            Self::EnumAsI32(policy)
+        } else if let Some(policy) = ctx.protto_meta.enum_values {
+            trace.decision("enum_values", "map values carried as proto enum i32s");
+            Self::EnumMap(policy)
+        } else if let Some(policy) = ctx.protto_meta.enum_keys {
+            trace.decision("enum_keys", "map keys carried as enum i32 discriminants");
+            Self::EnumKeyMap(policy)
+        } else if let Some(policy) = &ctx.protto_meta.unspecified {
+            trace.decision("unspecified", "enum whose proto value 0 has no variant");
+            Self::EnumUnspecified(policy.clone())
+        } else if let Some(bytes_as) = ctx.protto_meta.bytes_as {
+            trace.decision("bytes_as", "Vec packed into a proto bytes field");
+            Self::PackedBytes(bytes_as)
+        } else if let Some(decimal) = ctx.protto_meta.decimal {
+            trace.decision(
+                "decimal",
+                "Decimal carried as a proto string or units/nanos",
+            );
+            Self::Decimal(decimal)
+        } else if let Some(unit) = ctx.protto_meta.duration {
+            trace.decision("duration", "Duration carried as a proto int64 count");
+            Self::Duration(unit)
+        } else if ctx.protto_meta.with_str {
+            trace.decision(
+                "with_str",
+                "FromStr + Display type carried as a proto string",
+            );
+            Self::WithStr
+        } else if ctx.protto_meta.json {
+            trace.decision("json", "serde type carried as JSON in a proto string");
+            Self::Json
+        } else if let Some(key) = &ctx.protto_meta.map_by {
+            trace.decision("map_by", "map keyed by a field of repeated proto messages");
+            Self::MapBy(key.clone())
+        } else if ctx.is_proto_boxed() {
+            trace.decision("proto_boxed", "Proto Option<Box<T>> unboxed / boxed");
+            Self::Boxed(ErrorMode::from_field_context(ctx, rust_field_info))
+        } else if ctx.protto_meta.memoize {
+            trace.decision("memoize", "Vec<Arc<T>> converted through a memoizing cache");
+            Self::Collection(CollectionStrategy::Memoized)
+        } else if ctx.protto_meta.fallible
+            && Self::is_collection_conversion(rust_field_info, proto_field_info)
+        {
+            trace.decision(
+                "try_collect",
+                "Vec<T> -> Vec<U> through TryFrom per element",
+            );
+            let empty_repeated = Self::is_option_vec_type(&rust_field_info.field_type)
+                .then(|| ctx.protto_meta.empty_repeated.unwrap_or_default());
+            Self::Collection(CollectionStrategy::TryCollect(empty_repeated))
+        } else if ctx.protto_meta.fallible {
+            trace.decision("try_map_optional", "Option<T> -> Option<U> through TryFrom");
+            Self::Option(OptionStrategy::TryMap)
+        } else if let Some(custom_strategy) =
+            CustomConversionStrategy::from_field_info(ctx.struct_name, rust_field_info)
+        {
+            trace.decision("custom_functions", "Custom conversion functions detected");
+            if Self::custom_needs_error_handling(ctx, rust_field_info, proto_field_info) {
+                let error_mode = ErrorMode::from_field_context(ctx, rust_field_info);
+                Self::CustomWithError(custom_strategy, error_mode)
+            } else {
+                Self::Custom(custom_strategy)
+            }
+        } else if rust_field_info.has_transparent {
+            trace.decision("transparent_field", "Transparent wrapper detected");
+            let error_mode = ErrorMode::from_field_context(ctx, rust_field_info);
+            Self::Transparent(error_mode)
+        } else if ctx.is_proto_wrapper() {
+            trace.decision("wrapper_field", "Option<T> <-> Option<wrapper message>");
+            Self::Wrapper
+        } else if Self::is_os_path_field(rust_field_info) {
+            trace.decision(
+                "os_path_field",
+                "PathBuf/OsString encoded as string or bytes",
+            );
+            Self::Path(ctx.protto_meta.path_encoding)
+        } else if Self::is_bytes_field(ctx, rust_field_info, proto_field_info) {
+            trace.decision("bytes_field", "Vec<u8> / Bytes converted with Into");
+            Self::Bytes
+        } else if let Some(collection) =
+            type_analysis::bounded_collection_type(&rust_field_info.field_type)
+        {
+            trace.decision(
+                "bounded_collection",
+                "SmallVec / ArrayVec collected from a repeated field",
+            );
+            Self::Bounded(
+                collection,
+                ctx.protto_meta.overflow.unwrap_or(CapacityOverflow::Error),
+            )
+        } else if Self::is_collection_conversion(rust_field_info, proto_field_info) {
+            trace.decision("collection_conversion", "Collection type detected");
+            Self::Collection(Self::determine_collection_strategy(
+                ctx,
+                rust_field_info,
+                proto_field_info,
+                &trace,
+            ))
+        } else if rust_field_info.has_default || ctx.default_fn.is_some() {
+            if rust_field_info.is_option
+                && proto_field_info.is_optional()
+                && ctx.default_fn.is_none()
+                && !rust_field_info.has_default
+            {
+                Self::map_optional(ctx, rust_field_info, &trace)
+            } else {
+                trace.decision("default_field", "Field has default value");
+                let error_mode = ErrorMode::from_field_context(ctx, rust_field_info);
+                Self::Option(OptionStrategy::Unwrap(error_mode))
+            }
+        } else {
+            // Handle optionality patterns (simple 2x2 matrix)
+            let rust_optional = rust_field_info.is_option;
+            let proto_optional = proto_field_info.is_optional();
+
+            match (rust_optional, proto_optional) {
+                (true, false) => {
+                    trace.decision("wrap_optional", "Rust Option<T> -> Proto T");
+                    Self::Option(OptionStrategy::Wrap(ctx.protto_meta.none_as))
+                }
+                (false, true) => {
+                    trace.decision("unwrap_optional", "Proto Option<T> -> Rust T");
+                    let error_mode = ErrorMode::from_field_context(ctx, rust_field_info);
+                    trace.checkpoint_data(
+                        "optional_strategy",
+                        &[("error_mode", &format!("{error_mode:?}"))],
+                    );
+                    Self::Option(OptionStrategy::Unwrap(error_mode))
+                }
+                (true, true) if rust_field_info.expect_mode == ExpectMode::None => {
+                    Self::map_optional(ctx, rust_field_info, &trace)
+                }
+                (true, true) => {
+                    trace.decision(
+                        "unwrap_optional_for_some_wrap",
+                        "Proto optional -> unwrap -> wrap in Some",
+                    );
+                    let error_mode = ErrorMode::from_field_context(ctx, rust_field_info);
+                    Self::Option(OptionStrategy::Unwrap(error_mode))
+                }
+                (false, false) => {
+                    trace.decision("direct_conversion", "Both required -> direct conversion");
+                    Self::Direct(Self::determine_direct_strategy(
+                        ctx,
+                        rust_field_info,
+                        proto_field_info,
+                        &trace,
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Option<T> -> Option<U>, moved as is when the rust field already holds the prost type
+    fn map_optional(
+        ctx: &FieldProcessingContext,
+        rust_field_info: &RustFieldInfo,
+        trace: &CallStackDebug,
+    ) -> Self {
+        if Self::holds_proto_values(ctx, rust_field_info)
+            && ctx.protto_meta.none_as != Some(NonePolicy::Empty)
+        {
+            trace.decision(
+                "proto_option_direct",
+                "Option<ProtoType> -> direct assignment",
+            );
+            Self::Direct(DirectStrategy::Assignment)
+        } else {
+            trace.decision("map_optional", "Option<T> -> Option<U>");
+            Self::Option(OptionStrategy::Map)
+        }
+    }
+
+    /// The rust field holds prost values where prost generates them, as in `Option<proto::T>`,
+    /// `Vec<proto::T>` or `HashMap<K, proto::T>`, so they need no conversion.
+    fn holds_proto_values(ctx: &FieldProcessingContext, rust_field_info: &RustFieldInfo) -> bool {
+        let field_type = &rust_field_info.field_type;
+        let value_type = type_analysis::get_inner_type_from_option(field_type)
+            .or_else(|| type_analysis::get_inner_type_from_vec(field_type))
+            .or_else(|| {
+                type_analysis::is_hash_map_type(field_type)
+                    .then(|| type_analysis::get_value_type_from_map(field_type))
+                    .flatten()
+            });
+        value_type
+            .is_some_and(|value_type| type_analysis::is_proto_type(&value_type, ctx.proto_module))
+            && ctx.protto_meta.proto_field_type.is_none()
+    }
+
+    /// Determine if custom functions need error handling based on field context
+    fn custom_needs_error_handling(
+        ctx: &FieldProcessingContext,
+        rust_field_info: &RustFieldInfo,
+        proto_field_info: &ProtoFieldInfo,
+    ) -> bool {
+        // Custom functions need error handling when:
+
+        // 1. Explicit error handling attributes
+        if rust_field_info.expect_mode != crate::analysis::expect_analysis::ExpectMode::None {
+            return true;
+        }
+
+        // 2. Field has error function specified
+        if ctx.struct_level_error_fn.is_some() {
+            return true;
+        }
+
+        // 3. Proto optional -> Rust required pattern (needs unwrapping)
+        if proto_field_info.is_optional() && !rust_field_info.is_option {
+            return true;
+        }
+
+        // 4. Collection that might be empty but rust expects content
+        if proto_field_info.is_repeated()
+            && rust_field_info.is_vec
+            && !rust_field_info.has_default
+            && !rust_field_info.is_option
+        {
+            return true;
+        }
+
+        // 5. Complex custom types with bidirectional functions get error handling by default (compatibility)
+        if !rust_field_info.is_option
+            && !rust_field_info.is_primitive
+            && rust_field_info.is_custom
+            && rust_field_info.from_proto_fn.is_some()
+            && rust_field_info.to_proto_fn.is_some()
+        {
+            return true;
+        }
+
+        false
+    }
+
+    fn is_collection_conversion(
+        rust_field_info: &RustFieldInfo,
+        proto_field_info: &ProtoFieldInfo,
+    ) -> bool {
+        rust_field_info.is_vec
+            || proto_field_info.is_repeated()
+            || Self::is_option_vec_type(&rust_field_info.field_type)
+    }
+
+    fn is_os_path_field(rust_field_info: &RustFieldInfo) -> bool {
+        type_analysis::is_os_path_type(&rust_field_info.field_type)
+            || type_analysis::get_inner_type_from_option(&rust_field_info.field_type)
+                .map(|inner| type_analysis::is_os_path_type(&inner))
+                .unwrap_or(false)
+    }
+
+    /// prost has no `u8` scalar, so a `Vec<u8>` always maps to a proto bytes field, which prost
+    /// generates as either `Vec<u8>` or `Bytes`. Optionality mismatches and defaults take the
+    /// general paths.
+    fn is_bytes_field(
+        ctx: &FieldProcessingContext,
+        rust_field_info: &RustFieldInfo,
+        proto_field_info: &ProtoFieldInfo,
+    ) -> bool {
+        let value_type = type_analysis::get_inner_type_from_option(&rust_field_info.field_type)
+            .unwrap_or_else(|| rust_field_info.field_type.clone());
+        type_analysis::is_bytes_type(&value_type)
+            && rust_field_info.is_option == proto_field_info.is_optional()
+            && !rust_field_info.has_default
+            && ctx.default_fn.is_none()
+            && ctx.protto_meta.proto_field_type.is_none()
+    }
+
+    fn is_option_vec_type(field_type: &syn::Type) -> bool {
+        type_analysis::get_inner_type_from_option(field_type)
+            .map(|inner| type_analysis::is_vec_type(&inner))
+            .unwrap_or(false)
+    }
+
+    fn determine_collection_strategy(
+        ctx: &FieldProcessingContext,
+        rust_field_info: &RustFieldInfo,
+        _proto_field_info: &ProtoFieldInfo,
+        trace: &CallStackDebug,
+    ) -> CollectionStrategy {
+        let _trace = CallStackDebug::with_context(
+            "field::converstion_strategy::FieldConversionStrategy",
+            "determine_collection_strategy",
+            ctx.struct_name,
+            &rust_field_info.field_name,
+            &[
+                ("rust.has_default", &rust_field_info.has_default.to_string()),
+                (
+                    "rust.expect_mode",
+                    &format!("{:?}", rust_field_info.expect_mode),
+                ),
+                ("ctx.default_fn", &format!("{:?}", ctx.default_fn)),
+            ],
+        );
+
+        if Self::is_option_vec_type(&rust_field_info.field_type) {
+            trace.decision("option_vec", "Option<Vec<T>> detected");
+            CollectionStrategy::MapOption(ctx.protto_meta.empty_repeated.unwrap_or_default())
+        } else if Self::holds_proto_values(ctx, rust_field_info) {
+            // Check for direct assignment (proto types)
+            trace.decision(
+                "proto_collection_direct",
+                "Vec<ProtoType> | HashMap<K, ProtoType> -> direct assignment",
+            );
+            CollectionStrategy::DirectAssignment
+        } else if let Some(inner_type) =
+            type_analysis::get_inner_type_from_vec(&rust_field_info.field_type)
+            && ctx.is_primitive_type(&inner_type)
+            && !rust_field_info.has_default
+            && ctx.default_fn.is_none()
+        {
+            trace.decision("vec_in_place", "Vec<primitive> -> in-place collection");
+            CollectionStrategy::InPlace
+        } else if rust_field_info.has_default || ctx.default_fn.is_some() {
+            trace.decision(
+                "collection_with_default",
+                "Collection with default detected",
+            );
+            // Only apply error handling for collections when there's a default (matches old system)
+            let error_mode = ErrorMode::from_field_context(ctx, rust_field_info);
+            match error_mode {
+                ErrorMode::Error => {
+                    trace.decision(
+                        "rust_has_default_or_default_fn_w_error",
+                        "Vec<ProtoType> -> Standard collection conversion",
+                    );
+                    CollectionStrategy::Collect(ErrorMode::Error)
+                }
+                ErrorMode::Default(_) => {
+                    let default_fn = if ctx.default_fn.is_none() && rust_field_info.has_default {
+                        None
+                    } else {
+                        ctx.default_fn.clone()
+                    };
+
+                    trace.decision("collection_default", "Collection with default value");
+                    CollectionStrategy::Collect(ErrorMode::Default(default_fn))
+                }
+                _ => {
+                    trace.decision(
+                        "rust_has_default_or_default_fn_wo_error",
+                        "Vec<ProtoType> -> Standard collection w default conversion",
+                    );
+                    CollectionStrategy::Collect(ErrorMode::Default(ctx.default_fn.clone()))
+                }
+            }
+        } else {
+            trace.decision("standard_collection", "Standard collection conversion");
+            let error_mode = ErrorMode::None;
+            CollectionStrategy::Collect(error_mode)
+        }
+    }
+
+    fn determine_direct_strategy(
+        ctx: &FieldProcessingContext,
+        rust_field_info: &RustFieldInfo,
+        proto_field_info: &ProtoFieldInfo,
+        trace: &CallStackDebug,
+    ) -> DirectStrategy {
+        // Check if types are identical or can be directly assigned
+        if Self::types_are_identical(ctx, rust_field_info, proto_field_info) {
+            trace.decision(
+                "identical_types",
+                "Types are identical -> direct assignment",
+            );
+            DirectStrategy::Assignment
+        } else {
+            trace.decision("conversion_needed", "Types differ -> conversion with Into");
+            DirectStrategy::WithConversion
+        }
+    }
+
+    /// A declared `proto_field_type` replaces the prost default the other checks assume.
+    fn types_are_identical(
+        ctx: &FieldProcessingContext,
+        rust_field_info: &RustFieldInfo,
+        proto_field_info: &ProtoFieldInfo,
+    ) -> bool {
+        if let Some(proto_type) = &ctx.protto_meta.proto_field_type {
+            return *proto_type == rust_field_info.type_name();
+        }
+
+        (rust_field_info.is_primitive && proto_field_info.mapping == field_info::ProtoMapping::Scalar) // Primitive scalar types
+            || type_analysis::is_proto_type(&rust_field_info.field_type, ctx.proto_module) // Proto types (same module)
+    }
+
+    /// Validate that default_fn is not used with repeated/collection fields
+    fn validate_default_fn_compatibility(
+        ctx: &FieldProcessingContext,
+        rust_field_info: &RustFieldInfo,
+        proto_field_info: &ProtoFieldInfo,
+    ) -> Result<(), FieldGenerationError> {
+        // Reject default_fn on repeated fields - they can't be "missing", only empty
+        if ctx.default_fn.is_some() && (proto_field_info.is_repeated() || rust_field_info.is_vec) {
+            return Err(FieldGenerationError::ConversionValidation(format!(
+                "default_fn cannot be used with repeated/collection fields. \
+                     Proto3 repeated fields cannot be 'missing' (only empty []). \
+                     Field '{}' is a collection type.",
+                rust_field_info.field_name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Get a human-readable description of this strategy
+    #[allow(unused)]
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Ignore => "field ignored - not in proto",
+            Self::Manual => "field built by the user's FromProtoParts impl",
+            Self::Direct(direct) => match direct {
+                DirectStrategy::Assignment => "direct assignment (no conversion)",
+                DirectStrategy::WithConversion => "direct conversion with Into",
+            },
+            Self::Option(option) => match option {
+                OptionStrategy::Wrap(_) => "wrap value in Some()",
+                OptionStrategy::Unwrap(_) => "unwrap Optional with error handling",
+                OptionStrategy::Map => "map through optional conversion",
+                OptionStrategy::TryMap => "map through fallible optional conversion",
+            },
+            Self::Transparent(_) => "transparent wrapper conversion",
+            Self::Path(_) => "os path encoded as proto string or bytes",
+            Self::Bytes => "bytes converted between Vec<u8> and Bytes",
+            Self::Group(_) => "sub-struct grouped from flat proto fields",
+            Self::BoolEnum(_) => "bool matched against two proto enum variants",
+            Self::ResultOneof(_, _) => "result matched against an ok/err proto oneof",
+            Self::EnumAsI32(_) => "enum converted through its i32 discriminant",
+            Self::EnumMap(_) => "map values converted through the proto enum",
+            Self::EnumKeyMap(_) => "map keys converted through their i32 discriminant",
+            Self::EnumUnspecified(_) => "enum with a policy for the unspecified proto value",
+            Self::PackedBytes(_) => "vec packed into proto bytes",
+            Self::Decimal(DecimalAs::String) => "decimal as proto string",
+            Self::Decimal(DecimalAs::UnitsNanos) => "decimal as proto units/nanos message",
+            Self::Duration(DurationUnit::Seconds) => "duration as proto int64 seconds",
+            Self::Duration(DurationUnit::Millis) => "duration as proto int64 millis",
+            Self::Duration(DurationUnit::Nanos) => "duration as proto int64 nanos",
+            Self::WithStr => "parsed from and displayed as proto string",
+            Self::Json => "serialized as JSON into a proto string",
+            Self::MapBy(_) => "map keyed by a field of repeated proto messages",
+            Self::Wrapper => "option through proto wrapper message",
+            Self::Boxed(_) => "boxed proto message",
+            Self::PresenceOf => "bool set when the proto field is present",
+            Self::Bounded(BoundedCollection::SmallVec, _) => "collect into a SmallVec",
+            Self::Bounded(BoundedCollection::ArrayVec, _) => "collect into a bounded ArrayVec",
+            Self::Collection(collection) => match collection {
+                CollectionStrategy::Collect(_) => "collect vector with conversion",
+                CollectionStrategy::MapOption(EmptyRepeated::None) => "map optional vector",
+                CollectionStrategy::MapOption(EmptyRepeated::SomeEmpty) => {
+                    "map optional vector, keeping empty as Some"
+                }
+                CollectionStrategy::DirectAssignment => "direct vector assignment",
+                CollectionStrategy::InPlace => "collect vector in place",
+                CollectionStrategy::Memoized => "convert vector through a memoizing cache",
+                CollectionStrategy::TryCollect(_) => "collect vector through fallible conversion",
+            },
+            Self::Custom(custom) | Self::CustomWithError(custom, ErrorMode::None) => match custom {
+                CustomConversionStrategy::FromFn(_) => "custom proto->rust function",
+                CustomConversionStrategy::IntoFn(_) => "custom rust->proto function",
+                CustomConversionStrategy::Bidirectional(_, _) => "bidirectional custom functions",
+            },
+            Self::CustomWithError(custom, ErrorMode::Error) => match custom {
+                CustomConversionStrategy::FromFn(_) => "custom proto->rust function + error",
+                CustomConversionStrategy::IntoFn(_) => "custom rust->proto function + error",
+                CustomConversionStrategy::Bidirectional(_, _) => {
+                    "bidirectional custom functions + error"
+                }
+            },
+            Self::CustomWithError(custom, ErrorMode::Panic) => match custom {
+                CustomConversionStrategy::FromFn(_) => "custom proto->rust function + panic",
+                CustomConversionStrategy::IntoFn(_) => "custom rust->proto function + panic",
+                CustomConversionStrategy::Bidirectional(_, _) => {
+                    "bidirectional custom functions + panic"
+                }
+            },
+            Self::CustomWithError(custom, ErrorMode::Default(_)) => match custom {
+                CustomConversionStrategy::FromFn(_) => "custom proto->rust function + default",
+                CustomConversionStrategy::IntoFn(_) => "custom rust->proto function + default",
+                CustomConversionStrategy::Bidirectional(_, _) => {
+                    "bidirectional custom functions + default"
+                }
+            },
+        }
+    }
+
+    /// Whether the field converts through other types' conversions (nested messages, collections,
+    /// custom functions), as opposed to assigning or casting a scalar. Under the `trace-runtime`
+    /// feature these conversions run inside a span.
+    pub fn is_nested_conversion(&self, rust_field_info: &RustFieldInfo) -> bool {
+        match self {
+            Self::Custom(_)
+            | Self::CustomWithError(_, _)
+            | Self::Transparent(_)
+            | Self::Bounded(_, _)
+            | Self::Boxed(_)
+            | Self::ResultOneof(_, _)
+            | Self::EnumMap(_)
+            | Self::EnumKeyMap(_) => true,
+            Self::Collection(collection) => !matches!(
+                collection,
+                CollectionStrategy::DirectAssignment | CollectionStrategy::InPlace
+            ),
+            Self::Direct(DirectStrategy::WithConversion) | Self::Option(_) => {
+                !rust_field_info.is_primitive && !rust_field_info.is_enum
+            }
+            _ => false,
+        }
+    }
+
+    /// Get the category of this strategy for grouping
+    #[allow(unused)]
+    pub fn category(&self) -> &'static str {
+        match self {
+            Self::Ignore => "ignore",
+            Self::Manual => "manual",
+            Self::Custom(_) | Self::CustomWithError(_, _) => "custom",
+            Self::Direct(_) => "direct",
+            Self::Option(_) => "option",
+            Self::Transparent(_) => "transparent",
+            Self::Collection(_) => "collection",
+            Self::Path(_) => "path",
+            Self::Bytes => "bytes",
+            Self::Group(_) => "group",
+            Self::BoolEnum(_) => "bool_enum",
+            Self::ResultOneof(_, _) => "result_oneof",
+            Self::EnumAsI32(_) => "enum_as_i32",
+            Self::EnumMap(_) => "enum_values",
+            Self::EnumKeyMap(_) => "enum_keys",
+            Self::EnumUnspecified(_) => "unspecified",
+            Self::PackedBytes(_) => "bytes_as",
+            Self::Decimal(_) => "decimal",
+            Self::Duration(_) => "duration",
+            Self::WithStr => "with_str",
+            Self::Json => "json",
+            Self::MapBy(_) => "map_by",
+            Self::Wrapper => "wrapper",
+            Self::Boxed(_) => "proto_boxed",
+            Self::Bounded(_, _) => "bounded_collection",
+            Self::PresenceOf => "presence_of",
+        }
+    }
+}
+
+/// Migration error types
+#[derive(Debug)]
+pub enum FieldGenerationError {
+    ConversionValidation(String),
+}
+
+impl std::fmt::Display for FieldGenerationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldGenerationError::ConversionValidation(msg) => {
+                write!(f, "field conversion validation failed: {}", msg)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FieldGenerationError {}
+
+/// Main entry point for field conversion with migration support
+pub fn generate_field_conversions(
+    field: &syn::Field,
+    ctx: &FieldProcessingContext,
+) -> Result<(proc_macro2::TokenStream, proc_macro2::TokenStream), FieldGenerationError> {
+    // Analyze field using new system
+    let rust_field_info = RustFieldInfo::analyze(ctx, field);
+    let proto_field_info = ProtoFieldInfo::infer_from(ctx, field, &rust_field_info);
+    let strategy =
+        FieldConversionStrategy::from_field_info(ctx, field, &rust_field_info, &proto_field_info);
+    strategy.validate_for_context(ctx, &rust_field_info, &proto_field_info)?;
+
+    let _trace = CallStackDebug::with_context(
+        "field::conversion_strategy",
+        "generate_field_conversions",
+        ctx.struct_name,
+        &rust_field_info.field_name,
+        &[
+            ("conversion_category", strategy.category()),
+            ("conversion_strategy", strategy.description()),
+        ],
+    );
+
+    // Generate code
+    let proto_to_rust =
+        strategy.generate_proto_to_rust_conversion(ctx, field, &rust_field_info, &proto_field_info);
+    let rust_to_proto =
+        strategy.generate_rust_to_proto_conversion(ctx, field, &rust_field_info, &proto_field_info);
+
+    if cfg!(feature = "trace-runtime") && strategy.is_nested_conversion(&rust_field_info) {
+        return Ok((
+            strategy.instrument(ctx, "from_proto", proto_to_rust),
+            strategy.instrument(ctx, "into_proto", rust_to_proto),
+        ));
+    }
+
+    Ok((proto_to_rust, rust_to_proto))
+}
+
+// Integration with existing field analysis
+impl FieldConversionStrategy {
+    /// Validate that this strategy is compatible with the given context
+    pub fn validate_for_context(
+        &self,
+        ctx: &FieldProcessingContext,
+        rust_field_info: &RustFieldInfo,
+        proto_field_info: &ProtoFieldInfo,
+    ) -> Result<(), FieldGenerationError> {
+        Self::validate_default_fn_compatibility(ctx, rust_field_info, proto_field_info)?;
+        if ctx.protto_meta.overflow.is_some()
+            && !matches!(self, Self::Bounded(BoundedCollection::ArrayVec, _))
+        {
+            return Err(FieldGenerationError::ConversionValidation(
+                "overflow applies to ArrayVec fields; a SmallVec spills to the heap instead"
+                    .to_string(),
+            ));
+        }
+        if ctx.protto_meta.manual && rust_field_info.has_proto_ignore {
+            return Err(FieldGenerationError::ConversionValidation(
+                "manual and ignore are exclusive: an ignored field is filled from Default, a \
+                manual one by the FromProtoParts impl"
+                    .to_string(),
+            ));
+        }
+        if ctx.protto_meta.manual
+            && (rust_field_info.from_proto_fn.is_some()
+                || rust_field_info.to_proto_fn.is_some()
+                || rust_field_info.has_default
+                || ctx.default_fn.is_some())
+        {
+            return Err(FieldGenerationError::ConversionValidation(
+                "a manual field is converted entirely by the FromProtoParts impl; move the \
+                custom functions and defaults into it"
+                    .to_string(),
+            ));
+        }
+        if matches!(
+            ctx.protto_meta.none_as,
+            Some(NonePolicy::Empty | NonePolicy::Unset)
+        ) && !(rust_field_info.is_option
+            && matches!(
+                self,
+                Self::Option(_) | Self::Direct(DirectStrategy::Assignment)
+            ))
+        {
+            return Err(FieldGenerationError::ConversionValidation(
+                "none_as_empty and none_as_unset choose how a rust None is written to the proto \
+                field, so they apply to Option fields converted through the Option itself, not \
+                through custom functions, collections or transparent wrappers"
+                    .to_string(),
+            ));
+        }
+        if ctx.protto_meta.empty_repeated.is_some()
+            && !matches!(
+                self,
+                Self::Collection(
+                    CollectionStrategy::MapOption(_) | CollectionStrategy::TryCollect(Some(_))
+                )
+            )
+        {
+            return Err(FieldGenerationError::ConversionValidation(
+                "empty_repeated applies to Option<Vec<T>> fields mapped from a repeated proto field"
+                    .to_string(),
+            ));
+        }
+        // Use the existing validation logic from the new system
+        match self {
+            FieldConversionStrategy::Ignore if !rust_field_info.has_proto_ignore => {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "Ignore strategy requires #[protto(ignore)] attribute".to_string(),
+                ));
+            }
+            FieldConversionStrategy::Custom(custom_strategy) => {
+                custom_strategy
+                    .validate()
+                    .map_err(FieldGenerationError::ConversionValidation)?;
+            }
+            FieldConversionStrategy::Transparent(_) if !rust_field_info.has_transparent => {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "Transparent strategy requires #[protto(transparent)] attribute".to_string(),
+                ));
+            }
+            FieldConversionStrategy::Group(_)
+                if rust_field_info.is_option
+                    || !matches!(rust_field_info.field_type, syn::Type::Path(_)) =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "group requires a (non-Option) struct type constructed from the proto fields"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::Option(OptionStrategy::Wrap(None)) => {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "Option<T> maps to a required proto field, so a None value has no proto \
+                    representation. Choose how None is written with \
+                    #[protto(none_as = \"default\" | \"error\" | \"panic\")]"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::Option(OptionStrategy::Wrap(Some(NonePolicy::Unset))) => {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "none_as_unset leaves the proto field unset, but it is required and cannot be; \
+                    write None as the field's default with none_as_empty, or choose \
+                    #[protto(none_as = \"error\" | \"panic\")]"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::Option(OptionStrategy::Wrap(Some(NonePolicy::Error)))
+            | FieldConversionStrategy::Path(Some(PathEncoding::Utf8Strict))
+                if ctx.struct_level_error_type.is_some() && !ctx.has_error_fn() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "a fallible rust -> proto conversion (none_as = \"error\" or \
+                    path_encoding = \"utf8_strict\") with a struct-level error_type requires an \
+                    error_fn"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::BoolEnum(_)
+                if !type_analysis::is_bool_type(&rust_field_info.field_type) =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "bool_enum requires a bool field".to_string(),
+                ));
+            }
+            FieldConversionStrategy::BoolEnum(BoolEnumMapping {
+                unknown: UnknownVariantPolicy::Error,
+                ..
+            }) if ctx.struct_level_error_type.is_some() && !ctx.has_error_fn() => {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "bool_enum unknown = \"error\" with a struct-level error_type requires an \
+                    error_fn"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::ResultOneof(_, _)
+                if !type_analysis::get_inner_type_from_option(&rust_field_info.field_type)
+                    .as_ref()
+                    .map_or_else(
+                        || type_analysis::is_result_type(&rust_field_info.field_type),
+                        type_analysis::is_result_type,
+                    ) =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "result_oneof requires a Result<T, E> or Option<Result<T, E>> field"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::ResultOneof(_, _) if ctx.protto_meta.oneof.is_some() => {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "result_oneof already maps the field to its proto oneof; name the oneof with \
+                    proto_name instead of oneof"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::EnumAsI32(_)
+                if rust_field_info.is_vec
+                    || rust_field_info.is_option != proto_field_info.is_optional() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "enum_as_i32 requires an enum field for a proto int32, or an Option of one \
+                    for an optional proto int32"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::EnumAsI32(UnknownI32Policy::Error)
+                if ctx.struct_level_error_type.is_some() && !ctx.has_error_fn() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "enum_as_i32 = \"error\" with a struct-level error_type requires an error_fn"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::EnumMap(_)
+                if rust_field_info.is_option
+                    || type_analysis::get_value_type_from_map(&rust_field_info.field_type)
+                        .is_none() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "enum_values requires a HashMap<K, V> or BTreeMap<K, V> field whose V is the \
+                    rust enum of the proto map's enum values"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::EnumMap(UnknownI32Policy::Error)
+                if ctx.struct_level_error_type.is_some() && !ctx.has_error_fn() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "enum_values = \"error\" with a struct-level error_type requires an error_fn"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::EnumMap(_) if ctx.protto_meta.enum_keys.is_some() => {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "enum_keys cannot be combined with enum_values: proto map keys are plain \
+                    int32s, so the values must convert with Into"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::EnumKeyMap(_)
+                if rust_field_info.is_option
+                    || type_analysis::get_key_type_from_map(&rust_field_info.field_type)
+                        .is_none() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "enum_keys requires a HashMap<K, V> or BTreeMap<K, V> field whose K is a rust \
+                    enum carried by the proto map's int32 keys"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::EnumUnspecified(_)
+                if rust_field_info.is_vec
+                    || Self::is_option_vec_type(&rust_field_info.field_type)
+                    || type_analysis::get_key_type_from_map(&rust_field_info.field_type)
+                        .is_some() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "unspecified requires a field of a derived enum, or an Option of one"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::EnumUnspecified(UnspecifiedPolicy::Error)
+                if ctx.struct_level_error_type.is_some() && !ctx.has_error_fn() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "unspecified = \"error\" with a struct-level error_type requires an error_fn"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::PackedBytes(_) if !cfg!(feature = "zerocopy") => {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "bytes_as = \"pod_slice\" requires the `zerocopy` feature of protto"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::PackedBytes(_)
+                if !rust_field_info.is_vec || rust_field_info.is_option =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "bytes_as = \"pod_slice\" requires a Vec<T> field".to_string(),
+                ));
+            }
+            FieldConversionStrategy::PackedBytes(_)
+                if ctx.struct_level_error_type.is_some() && !ctx.has_error_fn() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "bytes_as decoding can fail, so a struct-level error_type requires an error_fn"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::Decimal(_) if !cfg!(feature = "decimal") => {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "decimal requires the `decimal` feature of protto".to_string(),
+                ));
+            }
+            FieldConversionStrategy::Decimal(_)
+                if !type_analysis::get_inner_type_from_option(&rust_field_info.field_type)
+                    .as_ref()
+                    .map_or_else(
+                        || type_analysis::is_decimal_type(&rust_field_info.field_type),
+                        type_analysis::is_decimal_type,
+                    ) =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "decimal requires a Decimal or Option<Decimal> field".to_string(),
+                ));
+            }
+            FieldConversionStrategy::Decimal(_)
+                if ctx.expect_mode != ExpectMode::Panic
+                    && ctx.struct_level_error_type.is_some()
+                    && !ctx.has_error_fn() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "decimal conversions can fail, so a struct-level error_type requires an \
+                    error_fn unless the field is expect(panic)"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::Duration(_)
+                if !type_analysis::get_inner_type_from_option(&rust_field_info.field_type)
+                    .as_ref()
+                    .map_or_else(
+                        || type_analysis::is_duration_type(&rust_field_info.field_type),
+                        type_analysis::is_duration_type,
+                    ) =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "duration requires a Duration or Option<Duration> field".to_string(),
+                ));
+            }
+            FieldConversionStrategy::Duration(_)
+                if ctx.expect_mode != ExpectMode::Panic
+                    && ctx.struct_level_error_type.is_some()
+                    && !ctx.has_error_fn() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "duration conversions can overflow, so a struct-level error_type requires an \
+                    error_fn unless the field is expect(panic)"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::WithStr
+                if rust_field_info.is_vec
+                    || Self::is_option_vec_type(&rust_field_info.field_type) =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "with_str requires a field of a FromStr + Display type, or an Option of one"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::WithStr
+                if ctx.expect_mode != ExpectMode::Panic
+                    && ctx.struct_level_error_type.is_some()
+                    && !ctx.has_error_fn() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "with_str conversions can fail to parse, so a struct-level error_type requires \
+                    an error_fn unless the field is expect(panic)"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::Json if !cfg!(feature = "json") => {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "json fields require the `json` feature of protto".to_string(),
+                ));
+            }
+            FieldConversionStrategy::Json
+                if ctx.expect_mode != ExpectMode::Panic
+                    && ctx.struct_level_error_type.is_some()
+                    && !ctx.has_error_fn() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "json conversions can fail in either direction, so a struct-level error_type \
+                    requires an error_fn unless the field is expect(panic)"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::MapBy(_)
+                if rust_field_info.is_option
+                    || type_analysis::get_key_type_from_map(&rust_field_info.field_type)
+                        .is_none() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "map_by requires a HashMap<K, V> or BTreeMap<K, V> field whose V converts \
+                    from the repeated proto message"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::MapBy(_) if !proto_field_info.is_repeated() => {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "map_by requires a repeated message proto field".to_string(),
+                ));
+            }
+            FieldConversionStrategy::Bounded(BoundedCollection::SmallVec, _)
+                if !cfg!(feature = "smallvec") =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "SmallVec fields require the `smallvec` feature of protto".to_string(),
+                ));
+            }
+            FieldConversionStrategy::Bounded(BoundedCollection::ArrayVec, _)
+                if !cfg!(feature = "arrayvec") =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "ArrayVec fields require the `arrayvec` feature of protto".to_string(),
+                ));
+            }
+            FieldConversionStrategy::Bounded(
+                BoundedCollection::ArrayVec,
+                CapacityOverflow::Error,
+            ) if ctx.struct_level_error_type.is_some() && !ctx.has_error_fn() => {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "an ArrayVec can overflow, so a struct-level error_type requires an error_fn \
+                    unless the field sets overflow = \"panic\" or \"truncate\""
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::Wrapper if !rust_field_info.is_option => {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "a proto wrapper message field such as UInt64Value requires an Option<T> field"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::Option(OptionStrategy::TryMap)
+                if !rust_field_info.is_option || !proto_field_info.is_optional() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "fallible requires an Option<T> field mapped to an optional proto field, or a \
+                    Vec<T> or Option<Vec<T>> field mapped to a repeated one"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::Collection(CollectionStrategy::TryCollect(_))
+                if !proto_field_info.is_repeated()
+                    || type_analysis::get_inner_type_from_vec(&rust_field_info.field_type)
+                        .or_else(|| {
+                            type_analysis::get_inner_type_from_option(&rust_field_info.field_type)
+                                .and_then(|inner| type_analysis::get_inner_type_from_vec(&inner))
+                        })
+                        .is_none() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "fallible on a collection requires a Vec<T> or Option<Vec<T>> field mapped to \
+                    a repeated proto message field"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::Option(OptionStrategy::TryMap)
+            | FieldConversionStrategy::Collection(CollectionStrategy::TryCollect(_))
+                if ctx.struct_level_error_type.is_some() && !ctx.has_error_fn() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "fallible nested conversions with a struct-level error_type require an error_fn"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::Collection(CollectionStrategy::Memoized)
+                if !cfg!(feature = "memoize") =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "memoize requires the `memoize` feature of protto".to_string(),
+                ));
+            }
+            FieldConversionStrategy::Collection(CollectionStrategy::Memoized)
+                if !type_analysis::get_inner_type_from_vec(&rust_field_info.field_type)
+                    .is_some_and(|inner| type_analysis::is_arc_type(&inner))
+                    || !proto_field_info.is_repeated() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "memoize requires a Vec<Arc<T>> field mapped to a repeated proto message field"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::Boxed(_)
+                if rust_field_info.is_vec || proto_field_info.is_repeated() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "proto_boxed applies to a singular message field, which prost generates as \
+                    Option<Box<T>>"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::Path(None) => {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "PathBuf/OsString fields need an explicit proto encoding: \
+                    #[protto(path_encoding = \"utf8_strict\" | \"lossy\" | \"bytes\")]"
+                        .to_string(),
+                ));
+            }
+            FieldConversionStrategy::Collection(_)
+                if !rust_field_info.is_vec && !proto_field_info.is_repeated() =>
+            {
+                return Err(FieldGenerationError::ConversionValidation(
+                    "Collection strategy requires Vec or repeated field".to_string(),
+                ));
+            }
+            _ => {
+                // Other strategies have their own validation logic
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strategy_descriptions() {
+        let ignore = FieldConversionStrategy::Ignore;
+        assert_eq!(ignore.description(), "field ignored - not in proto");
+        assert_eq!(ignore.category(), "ignore");
+
+        let direct = FieldConversionStrategy::Direct(DirectStrategy::Assignment);
+        assert_eq!(direct.category(), "direct");
+        assert!(direct.description().contains("direct assignment"));
+
+        let path = FieldConversionStrategy::Path(Some(PathEncoding::Lossy));
+        assert_eq!(path.category(), "path");
+
+        let group =
+            FieldConversionStrategy::Group(vec![("street".to_string(), "street".to_string())]);
+        assert_eq!(group.category(), "group");
+    }
+
+    #[test]
+    fn test_optionality_expect_and_default_matrix() {
+        let wrap = |policy| FieldConversionStrategy::Option(OptionStrategy::Wrap(policy));
+        let unwrap = |mode| FieldConversionStrategy::Option(OptionStrategy::Unwrap(mode));
+        let default_impl = || unwrap(ErrorMode::Default(Some("Default::default".to_string())));
+        let default_fn = || unwrap(ErrorMode::Default(Some("default_name".to_string())));
+        let direct = FieldConversionStrategy::Direct(DirectStrategy::Assignment);
+        let map = FieldConversionStrategy::Option(OptionStrategy::Map);
+        let none_as_default = wrap(Some(NonePolicy::Default));
+
+        // (rust type, field attributes, expected strategy)
+        let matrix = [
+            ("String", "proto_required", direct.clone()),
+            ("String", "proto_required, expect", direct.clone()),
+            ("String", "proto_required, expect(panic)", direct.clone()),
+            ("String", "proto_required, default", default_impl()),
+            (
+                "String",
+                "proto_required, default = \"default_name\"",
+                default_fn(),
+            ),
+            ("String", "proto_optional", unwrap(ErrorMode::None)),
+            ("String", "proto_optional, expect", unwrap(ErrorMode::Error)),
+            (
+                "String",
+                "proto_optional, expect(panic)",
+                unwrap(ErrorMode::Panic),
+            ),
+            ("String", "proto_optional, default", default_impl()),
+            (
+                "String",
+                "proto_optional, expect = \"default\"",
+                default_impl(),
+            ),
+            (
+                "String",
+                "proto_optional, default = \"default_name\"",
+                default_fn(),
+            ),
+            ("Option<String>", "", map.clone()),
+            (
+                "Option<String>",
+                "proto_required, none_as = \"default\"",
+                none_as_default.clone(),
+            ),
+            (
+                "Option<String>",
+                "proto_required, expect, none_as = \"default\"",
+                none_as_default.clone(),
+            ),
+            (
+                "Option<String>",
+                "proto_required, expect(panic), none_as = \"default\"",
+                none_as_default.clone(),
+            ),
+            (
+                "Option<String>",
+                "proto_required, default, none_as = \"default\"",
+                default_impl(),
+            ),
+            (
+                "Option<String>",
+                "proto_required, default = \"default_name\", none_as = \"default\"",
+                default_fn(),
+            ),
+            ("Option<String>", "proto_optional", map.clone()),
+            (
+                "Option<String>",
+                "proto_optional, expect",
+                unwrap(ErrorMode::Error),
+            ),
+            (
+                "Option<String>",
+                "proto_optional, expect(panic)",
+                unwrap(ErrorMode::Panic),
+            ),
+            ("Option<String>", "proto_optional, default", default_impl()),
+            (
+                "Option<String>",
+                "proto_optional, default = \"default_name\"",
+                default_fn(),
+            ),
+        ];
+
+        let analyze = |field_type: &str, attributes: &str| {
+            let input = syn::parse_str(&format!(
+                "#[protto(module = \"proto\", proto_name = \"TestStruct\")] \
+                struct TestStruct {{ #[protto({attributes})] name: {field_type} }}"
+            ))
+            .unwrap();
+            crate::testing::analyze_field(&input, "name")
+        };
+
+        for (field_type, attributes, expected) in matrix {
+            let analysis = analyze(field_type, attributes);
+
+            let case = format!("{field_type} with {attributes:?}");
+            assert_eq!(analysis.strategy, expected, "{case}");
+            assert!(
+                analysis.validation.is_ok(),
+                "{case}: {:?}",
+                analysis.validation
+            );
+        }
+
+        // a None for a required proto field has no representation until none_as picks one
+        let analysis = analyze("Option<String>", "proto_required");
+        assert_eq!(analysis.strategy, wrap(None));
+        assert!(
+            analysis
+                .validation
+                .is_err_and(|err| err.to_string().contains("none_as")),
+        );
+    }
+}
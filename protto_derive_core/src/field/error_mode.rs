@@ -86,7 +86,7 @@ impl ErrorMode {
     //                 quote! { #error_fn(stringify!(#proto_field_name)) }
     //             } else {
     //                 // Priority 3: Default error generation (only when no error_type)
-    //                 let default_error_name = &ctx.default_error_ident();
+    //                 let default_error_name = &ctx.default_error_path();
     //                 quote! {
     //                     #default_error_name::MissingField(stringify!(#proto_field_name).to_string())
     //                 }
@@ -0,0 +1,100 @@
+use crate::analysis::type_analysis;
+use crate::debug::CallStackDebug;
+use crate::field::{
+    FieldProcessingContext,
+    conversion_strategy::{self, FieldConversionStrategy, FieldGenerationError, OptionStrategy},
+    info::{ProtoFieldInfo, RustFieldInfo},
+};
+use quote::quote;
+
+/// Generate both proto->rust and rust->proto conversions for a field in a single pass
+/// This replaces the double iteration approach
+pub fn generate_bidirectional_field_conversion(
+    field: &syn::Field,
+    ctx: &FieldProcessingContext,
+) -> Result<(proc_macro2::TokenStream, proc_macro2::TokenStream), FieldGenerationError> {
+    let _trace = CallStackDebug::with_context(
+        "field::field_processor",
+        "generate_bidirectional_field_conversion",
+        ctx.struct_name,
+        ctx.field_name,
+        &[
+            ("rust_field_type", &quote!(ctx.field_type).to_string()),
+            ("proto_field_ident", &ctx.proto_field_ident.to_string()),
+        ],
+    );
+
+    conversion_strategy::generate_field_conversions(field, ctx)
+        .map(|(from_proto, to_proto)| {
+            _trace.generated_code(
+                &from_proto,
+                ctx.struct_name,
+                ctx.field_name,
+                "bidirectional_proto_to_rust",
+                &[("conversion_direction", "proto -> rust")],
+            );
+
+            _trace.generated_code(
+                &to_proto,
+                ctx.struct_name,
+                ctx.field_name,
+                "bidirectional_rust_to_proto",
+                &[("conversion_direction", "rust -> proto")],
+            );
+
+            (from_proto, to_proto)
+        })
+        .map_err(|err| {
+            _trace.error(format!(
+                "Migration error for field '{}': {err}",
+                ctx.field_name
+            ));
+
+            err
+        })
+}
+
+/// Whether the proto field a rust field converts from is optional, i.e. may be absent
+pub fn is_proto_field_optional(field: &syn::Field, ctx: &FieldProcessingContext) -> bool {
+    let rust_field_info = RustFieldInfo::analyze(ctx, field);
+    ProtoFieldInfo::infer_from(ctx, field, &rust_field_info).is_optional()
+}
+
+/// Whether a field converts by unwrapping an optional proto message only because its rust type
+/// is an unrecognized custom type, which is also how an unannotated type alias of a scalar reads
+pub fn unwraps_unrecognized_message(field: &syn::Field, ctx: &FieldProcessingContext) -> bool {
+    let rust_field_info = RustFieldInfo::analyze(ctx, field);
+    if !rust_field_info.is_custom
+        || rust_field_info.is_option
+        || rust_field_info.is_enum
+        || rust_field_info.from_proto_fn.is_some()
+        || rust_field_info.to_proto_fn.is_some()
+        || ctx.protto_meta.proto_field_type.is_some()
+        || type_analysis::is_proto_type(&rust_field_info.field_type, ctx.proto_module)
+    {
+        return false;
+    }
+
+    let proto_field_info = ProtoFieldInfo::infer_from(ctx, field, &rust_field_info);
+    matches!(
+        FieldConversionStrategy::from_field_info(ctx, field, &rust_field_info, &proto_field_info),
+        FieldConversionStrategy::Option(OptionStrategy::Unwrap(_))
+    )
+}
+
+/// The category and description of the conversion strategy chosen for a field, e.g. `direct`
+/// and "direct assignment (no conversion)"
+pub fn conversion_labels(
+    field: &syn::Field,
+    ctx: &FieldProcessingContext,
+) -> (&'static str, &'static str) {
+    let rust_field_info = RustFieldInfo::analyze(ctx, field);
+    let proto_field_info = ProtoFieldInfo::infer_from(ctx, field, &rust_field_info);
+    let strategy = conversion_strategy::FieldConversionStrategy::from_field_info(
+        ctx,
+        field,
+        &rust_field_info,
+        &proto_field_info,
+    );
+    (strategy.category(), strategy.description())
+}
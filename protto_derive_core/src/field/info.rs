@@ -1,5 +1,9 @@
 use crate::analysis::{
-    attribute_parser, expect_analysis::ExpectMode, optionality::FieldOptionality, type_analysis,
+    attribute_parser,
+    expect_analysis::ExpectMode,
+    optionality::FieldOptionality,
+    proto_metadata::{self, SchemaKind, SchemaShape},
+    type_analysis,
 };
 use crate::debug::CallStackDebug;
 use crate::field::context::{CollectionType, FieldProcessingContext};
@@ -48,9 +52,9 @@ impl RustFieldInfo {
         let field_type = ctx.field_type.clone();
         let is_option = type_analysis::is_option_type(&field_type);
         let is_vec = type_analysis::is_vec_type(&field_type);
-        let is_primitive = type_analysis::is_primitive_type(&field_type);
-        let is_custom = type_analysis::is_custom_type(&field_type);
-        let is_enum = type_analysis::is_enum_type(&field_type);
+        let is_primitive = ctx.is_primitive_type(&field_type);
+        let is_custom = ctx.is_custom_type(&field_type);
+        let is_enum = ctx.is_enum_type(&field_type);
 
         Self {
             field_name,
@@ -86,14 +90,23 @@ pub enum ProtoMapping {
     Scalar,        // proto scalar field
     Optional,      // proto optional field
     Repeated,      // proto repeated field
+    Map,           // proto map field
     Message,       // proto message field
     CustomDerived, // handled by custom derive functions
 }
 
 impl ProtoMapping {
+    /// Map fields count as repeated: prost encodes them as repeated entries and they convert
+    /// through the same collection strategies.
     #[inline]
     pub fn is_repeated(&self) -> bool {
-        matches!(self, Self::Repeated)
+        matches!(self, Self::Repeated | Self::Map)
+    }
+
+    #[allow(unused)]
+    #[inline]
+    pub fn is_map(&self) -> bool {
+        matches!(self, Self::Map)
     }
 
     #[inline]
@@ -163,19 +176,56 @@ impl ProtoFieldInfo {
             ],
         );
 
-        let type_name = Self::infer_proto_type_name(ctx, rust_field_info);
-
-        let info =
-            if rust_field_info.from_proto_fn.is_some() || rust_field_info.to_proto_fn.is_some() {
-                // Priority 1 - Handle custom derive scenarios first
-                Self::infer_for_custom_derive(ctx, field, rust_field_info, type_name, &_trace)
-            } else if Self::is_any_collection_type(ctx.field_type) {
-                // Priority 2 - Handle collection types (including nested Options)
-                Self::infer_for_collection_type(ctx, field, rust_field_info, type_name, &_trace)
+        let type_name = ctx
+            .protto_meta
+            .proto_field_type
+            .clone()
+            .unwrap_or_else(|| Self::infer_proto_type_name(ctx, rust_field_info));
+
+        let info = if ctx.protto_meta.oneof.is_some() || ctx.protto_meta.result_oneof.is_some() {
+            // A prost oneof is always an optional field holding the oneof enum
+            _trace.decision("oneof_field", "oneof -> optional proto field");
+            Self {
+                type_name,
+                mapping: ProtoMapping::Optional,
+                optionality: FieldOptionality::Optional,
+            }
+        } else if let Some(info) =
+            Self::infer_from_schema_metadata(ctx, rust_field_info, &type_name, &_trace)
+        {
+            // Priority 0 - The proto schema recorded by `protto::build`, when available
+            info
+        } else if ctx.protto_meta.enum_as_i32.is_some() {
+            // An enum_as_i32 field is a plain int32, optional exactly when the rust field is
+            _trace.decision("enum_as_i32", "enum -> proto int32");
+            let (mapping, optionality) = if rust_field_info.is_option {
+                (ProtoMapping::Optional, FieldOptionality::Optional)
             } else {
-                // Priority 3 - Handle standard field patterns
-                Self::infer_for_standard_field(ctx, field, rust_field_info, type_name, &_trace)
+                (ProtoMapping::Scalar, FieldOptionality::Required)
             };
+            Self {
+                type_name: "i32".to_string(),
+                mapping,
+                optionality,
+            }
+        } else if ctx.protto_meta.enum_values.is_some() {
+            // prost carries the values of a map with enum values as i32
+            _trace.decision("enum_values", "map of enums -> proto map of int32");
+            Self {
+                type_name,
+                mapping: ProtoMapping::Map,
+                optionality: FieldOptionality::Required,
+            }
+        } else if rust_field_info.from_proto_fn.is_some() || rust_field_info.to_proto_fn.is_some() {
+            // Priority 1 - Handle custom derive scenarios first
+            Self::infer_for_custom_derive(ctx, field, rust_field_info, type_name, &_trace)
+        } else if Self::is_any_collection_type(ctx.field_type) {
+            // Priority 2 - Handle collection types (including nested Options)
+            Self::infer_for_collection_type(ctx, field, rust_field_info, type_name, &_trace)
+        } else {
+            // Priority 3 - Handle standard field patterns
+            Self::infer_for_standard_field(ctx, field, rust_field_info, type_name, &_trace)
+        };
 
         _trace.checkpoint_data(
             "proto_field_info",
@@ -191,6 +241,55 @@ impl ProtoFieldInfo {
         info
     }
 
+    /// Takes the field's shape from build metadata instead of inferring it from the Rust side.
+    /// Custom conversion functions and explicit `proto_optional`/`proto_required` keep their
+    /// existing inference, since they describe the shape the user converts against.
+    fn infer_from_schema_metadata(
+        ctx: &FieldProcessingContext,
+        rust_field_info: &RustFieldInfo,
+        type_name: &str,
+        trace: &CallStackDebug,
+    ) -> Option<Self> {
+        if rust_field_info.from_proto_fn.is_some()
+            || rust_field_info.to_proto_fn.is_some()
+            || ctx.protto_meta.has_explicit_optionality()
+        {
+            return None;
+        }
+
+        let schema = proto_metadata::lookup_field(
+            ctx.proto_module,
+            ctx.proto_name,
+            &ctx.proto_field_ident.to_string(),
+        )?;
+
+        let (mapping, optionality) = match (schema.shape, schema.kind) {
+            (SchemaShape::Repeated, _) => (ProtoMapping::Repeated, FieldOptionality::Required),
+            (SchemaShape::Map, _) => (ProtoMapping::Map, FieldOptionality::Required),
+            (SchemaShape::Optional, _) => (ProtoMapping::Optional, FieldOptionality::Optional),
+            (SchemaShape::Required, SchemaKind::Message) => {
+                (ProtoMapping::Message, FieldOptionality::Required)
+            }
+            (SchemaShape::Required, SchemaKind::Scalar | SchemaKind::Enum) => {
+                (ProtoMapping::Scalar, FieldOptionality::Required)
+            }
+        };
+
+        trace.decision(
+            "schema_metadata",
+            &format!(
+                "proto schema declares {:?} {:?} {}",
+                schema.shape, schema.kind, schema.type_name
+            ),
+        );
+
+        Some(Self {
+            type_name: type_name.to_string(),
+            mapping,
+            optionality,
+        })
+    }
+
     fn is_any_collection_type(field_type: &syn::Type) -> bool {
         // Handle Option<Vec<T>>, Option<HashMap<K,V>>, etc.
         if let Some(inner_type) = type_analysis::get_inner_type_from_option(field_type) {
@@ -230,7 +329,13 @@ impl ProtoFieldInfo {
         } else if attribute_parser::get_proto_field_name(field).is_some() {
             //todo: fix why switch based on whether proto field is explicitly identified vs conventionally?
 
-            if rust_field_info.is_enum && !rust_field_info.is_option {
+            if ctx.protto_meta.get_proto_optionality() == Some(&FieldOptionality::Required) {
+                trace.decision(
+                    "custom_derive_required",
+                    "Custom derive + proto_required -> required proto field",
+                );
+                ProtoMapping::Scalar
+            } else if rust_field_info.is_enum && !rust_field_info.is_option {
                 trace.decision(
                     "custom_derive_enum_to_optional_proto",
                     "Enum + custom derive -> likely optional proto field",
@@ -263,6 +368,20 @@ impl ProtoFieldInfo {
             .unwrap_or_else(|| {
                 if mapping.is_repeated() {
                     FieldOptionality::Required // repeated fields are never optional
+                } else if ctx.protto_meta.from_proto_fallible && !rust_field_info.is_option {
+                    // `expect` unwraps the Result of a try_from_proto_fn, so it says nothing
+                    // about the proto field; only the build metadata can show it is optional
+                    trace.decision(
+                        "custom_derive_fallible",
+                        "try_from_proto_fn -> proto field optional only per the schema",
+                    );
+                    proto_metadata::lookup_field(
+                        ctx.proto_module,
+                        ctx.proto_name,
+                        &ctx.proto_field_ident.to_string(),
+                    )
+                    .filter(|schema| schema.shape == SchemaShape::Optional)
+                    .map_or(FieldOptionality::Required, |_| FieldOptionality::Optional)
                 } else {
                     Self::determine_optionality_from_context(ctx, field, trace)
                 }
@@ -580,7 +699,7 @@ impl ProtoFieldInfo {
         // Option<T> always becomes optional in proto
         let inner_mapping =
             if let Some(inner_type) = type_analysis::get_inner_type_from_option(ctx.field_type) {
-                if type_analysis::is_primitive_type(&inner_type) {
+                if ctx.is_primitive_type(&inner_type) {
                     ProtoMapping::Scalar
                 } else {
                     ProtoMapping::Message
@@ -780,16 +899,14 @@ impl ProtoFieldInfo {
         // Key insight: Option<CustomType> in rust often maps to optional message in proto
         if type_analysis::is_option_type(ctx.field_type) {
             if let Some(inner_type) = type_analysis::get_inner_type_from_option(ctx.field_type) {
-                if type_analysis::is_custom_type(&inner_type)
-                    || type_analysis::is_enum_type(&inner_type)
-                {
+                if ctx.is_custom_type(&inner_type) || ctx.is_enum_type(&inner_type) {
                     trace.decision(
                         "option_custom_type",
                         "Option<CustomType> -> likely optional proto message",
                     );
                     return FieldOptionality::Optional;
                 }
-                if type_analysis::is_primitive_type(&inner_type) {
+                if ctx.is_primitive_type(&inner_type) {
                     trace.decision(
                         "option_primitive_type",
                         "Option<PrimitiveType> -> likely optional proto scalar",
@@ -803,7 +920,7 @@ impl ProtoFieldInfo {
         }
 
         // Priority 4: Custom types without Option wrapper
-        if type_analysis::is_enum_type(ctx.field_type) {
+        if ctx.is_enum_type(ctx.field_type) {
             // Enums map to i32 in proto, typically required unless explicitly marked optional
             if Self::has_optional_indicators(ctx, field) {
                 trace.decision(
@@ -818,9 +935,7 @@ impl ProtoFieldInfo {
         }
 
         // Priority 5: Custom types (non-enum)
-        if type_analysis::is_custom_type(ctx.field_type)
-            && !type_analysis::is_enum_type(ctx.field_type)
-        {
+        if ctx.is_custom_type(ctx.field_type) && !ctx.is_enum_type(ctx.field_type) {
             // Check if it's a transparent field first
             if attribute_parser::has_transparent_attr(field) {
                 // Transparent fields map to their inner type - follow existing transparent logic
@@ -866,7 +981,7 @@ impl ProtoFieldInfo {
                 "proto field is likely optional",
             );
             Some(FieldOptionality::Optional)
-        } else if type_analysis::is_primitive_type(ctx.field_type) {
+        } else if ctx.is_primitive_type(ctx.field_type) {
             trace.decision("primitive_no_indicators", "proto field likely required");
             Some(FieldOptionality::Required)
         } else {
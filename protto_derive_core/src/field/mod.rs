@@ -0,0 +1,23 @@
+mod context;
+mod conversion_codegen;
+mod conversion_strategy;
+mod custom_conversion;
+mod error_mode;
+mod generator;
+mod info;
+
+pub use context::FieldProcessingContext;
+pub use generator::{
+    conversion_labels, generate_bidirectional_field_conversion, is_proto_field_optional,
+    unwraps_unrecognized_message,
+};
+
+#[cfg(any(test, feature = "testing"))]
+pub use conversion_strategy::{
+    CollectionStrategy, DirectStrategy, FieldConversionStrategy, FieldGenerationError,
+    OptionStrategy,
+};
+#[cfg(any(test, feature = "testing"))]
+pub use error_mode::ErrorMode;
+#[cfg(any(test, feature = "testing"))]
+pub use info::{ProtoFieldInfo, ProtoMapping, RustFieldInfo};
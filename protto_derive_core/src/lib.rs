@@ -0,0 +1,674 @@
+//! The code generation behind the `protto_derive` macros, as a library so it can be driven and
+//! inspected outside a proc-macro crate. Each macro in `protto_derive` forwards to the function of
+//! the same name here; the `testing` feature adds the `testing` module, field analysis on its own.
+
+use crate::analysis::attribute_parser;
+use crate::analysis::macro_input::ParsedInput;
+use crate::debug::CallStackDebug;
+use proc_macro2::TokenStream;
+use syn::{self, DeriveInput};
+
+mod constants {
+    pub const PRIMITIVE_TYPES: &[&str] =
+        &["i32", "u32", "i64", "u64", "f32", "f64", "bool", "String"];
+    pub const DEFAULT_PROTO_MODULE: &str = "proto";
+
+    pub const PROTTO_ATTRIBUTE: &str = "protto";
+    pub const DEFAULT_CONVERSION_ERROR_SUFFIX: &str = "ConversionError";
+    pub const PRESENCE_SUFFIX: &str = "Presence";
+    pub const PROTO_PARTS_SUFFIX: &str = "ProtoParts";
+    pub const VIEW_SUFFIX: &str = "View";
+    pub const USE_DEFAULT_IMPL: &str = "Default::default";
+}
+
+mod analysis;
+mod arena_generator;
+#[cfg(feature = "meta-file")]
+mod coverage;
+mod debug;
+mod enum_generator;
+mod field;
+mod nested_generator;
+mod report;
+mod rules;
+mod schema;
+mod stats;
+mod struct_generator;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod tuple_generator;
+
+mod utils {
+    use syn::ext::IdentExt;
+
+    /// Identifier for a field named in an attribute value. Keywords become raw identifiers, the
+    /// way prost generates fields such as `r#type`, and an explicit `r#` prefix is accepted.
+    pub fn field_ident(name: &str) -> syn::Ident {
+        let name = name.strip_prefix("r#").unwrap_or(name);
+        match syn::parse_str::<syn::Ident>(name) {
+            Ok(ident) => ident,
+            Err(_) => syn::Ident::new_raw(name, proc_macro2::Span::call_site()),
+        }
+    }
+
+    /// The field name an identifier stands for, without any `r#` prefix, for messages.
+    pub fn ident_name(ident: &syn::Ident) -> String {
+        ident.unraw().to_string()
+    }
+
+    /// Splits a name into words the way prost-build (through `heck`) does: at any character that
+    /// is not alphanumeric, between a lowercase letter (or digits after one) and an uppercase
+    /// letter, and before the last capital of an acronym followed by lowercase. Digits join the
+    /// word before them: `HTTPServer2` is `HTTP`, `Server2` and `IPv4Addr` is `I`, `Pv4`, `Addr`.
+    pub fn words(s: &str) -> Vec<&str> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mode {
+            Boundary,
+            Lowercase,
+            Uppercase,
+        }
+
+        let mut words = Vec::new();
+        for word in s.split(|c: char| !c.is_alphanumeric()) {
+            let mut chars = word.char_indices().peekable();
+            let mut start = 0;
+            let mut mode = Mode::Boundary;
+            while let Some((i, c)) = chars.next() {
+                let Some(&(next_i, next)) = chars.peek() else {
+                    words.push(&word[start..]);
+                    break;
+                };
+                let next_mode = if c.is_lowercase() {
+                    Mode::Lowercase
+                } else if c.is_uppercase() {
+                    Mode::Uppercase
+                } else {
+                    mode
+                };
+                if next_mode == Mode::Lowercase && next.is_uppercase() {
+                    words.push(&word[start..next_i]);
+                    start = next_i;
+                    mode = Mode::Boundary;
+                } else if mode == Mode::Uppercase && c.is_uppercase() && next.is_lowercase() {
+                    words.push(&word[start..i]);
+                    start = i;
+                    mode = Mode::Boundary;
+                } else {
+                    mode = next_mode;
+                }
+            }
+        }
+        words
+    }
+
+    /// The SCREAMING_SNAKE_CASE form of a name, as protoc style writes enum values and prost
+    /// reads them: `HTTPStatus` -> `HTTP_STATUS`, `HttpServer2` -> `HTTP_SERVER2`.
+    pub fn to_screaming_snake_case(s: &str) -> String {
+        words(s)
+            .iter()
+            .map(|word| word.to_uppercase())
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+
+    /// The snake_case name prost generates for a field or for a message's nested types module,
+    /// e.g. `GetUserResponse` -> `get_user_response`. Acronyms stay one word: `HTTPRequest` ->
+    /// `http_request`.
+    pub fn to_snake_case(s: &str) -> String {
+        words(s)
+            .iter()
+            .map(|word| word.to_lowercase())
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+
+    /// The UpperCamelCase name prost generates for a type, enum variant or oneof, e.g.
+    /// `payment_method` -> `PaymentMethod` and `HTTP_STATUS` -> `HttpStatus`.
+    pub fn to_upper_camel_case(s: &str) -> String {
+        words(s)
+            .iter()
+            .flat_map(|word| {
+                let mut chars = word.chars();
+                let first = chars.next().into_iter().flat_map(char::to_uppercase);
+                first.chain(chars.flat_map(char::to_lowercase))
+            })
+            .collect()
+    }
+
+    /// Whether two names give prost the same UpperCamelCase identifier, so `HTTP_SERVER_2`,
+    /// `HTTP_SERVER2` and `HttpServer2` all name the same enum value.
+    pub fn same_prost_name(a: &str, b: &str) -> bool {
+        to_upper_camel_case(a) == to_upper_camel_case(b)
+    }
+
+    /// Puts each generated item under the struct- or enum-level `cfg` predicates. A
+    /// `compile_error!` stays unconditional, so a misused attribute fails in every build.
+    pub fn with_cfg(
+        generated: proc_macro2::TokenStream,
+        predicates: &[syn::Meta],
+    ) -> proc_macro2::TokenStream {
+        if predicates.is_empty() {
+            return generated;
+        }
+        let Ok(file) = syn::parse2::<syn::File>(generated.clone()) else {
+            return generated;
+        };
+        let items = file.items.into_iter().map(|mut item| {
+            let is_compile_error = matches!(&item, syn::Item::Macro(item_macro)
+                if item_macro.mac.path.segments.last().is_some_and(|segment| segment.ident == "compile_error"));
+            if !is_compile_error && let Some(attrs) = item_attrs(&mut item) {
+                attrs.splice(0..0, predicates.iter().map(|predicate| syn::parse_quote!(#[cfg(#predicate)])));
+            }
+            item
+        });
+        quote::quote! { #(#items)* }
+    }
+
+    fn item_attrs(item: &mut syn::Item) -> Option<&mut Vec<syn::Attribute>> {
+        match item {
+            syn::Item::Const(item) => Some(&mut item.attrs),
+            syn::Item::Enum(item) => Some(&mut item.attrs),
+            syn::Item::Fn(item) => Some(&mut item.attrs),
+            syn::Item::Impl(item) => Some(&mut item.attrs),
+            syn::Item::Macro(item) => Some(&mut item.attrs),
+            syn::Item::Mod(item) => Some(&mut item.attrs),
+            syn::Item::Static(item) => Some(&mut item.attrs),
+            syn::Item::Struct(item) => Some(&mut item.attrs),
+            syn::Item::Trait(item) => Some(&mut item.attrs),
+            syn::Item::Type(item) => Some(&mut item.attrs),
+            syn::Item::Use(item) => Some(&mut item.attrs),
+            _ => None,
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn cfg_predicates_gate_every_item_but_compile_errors() {
+            let generated = quote::quote! {
+                impl From<proto::User> for User { fn from(_: proto::User) -> Self { todo!() } }
+                pub enum UserConversionError {}
+                compile_error!("misused");
+            };
+            let code = with_cfg(generated, &[syn::parse_quote!(feature = "grpc")]).to_string();
+            assert!(code.starts_with("# [cfg (feature = \"grpc\")] impl From"));
+            assert!(code.contains("# [cfg (feature = \"grpc\")] pub enum UserConversionError"));
+            assert!(code.ends_with("} compile_error ! (\"misused\") ;"));
+        }
+
+        #[test]
+        fn prost_nested_names() {
+            assert_eq!(to_snake_case("GetUserResponse"), "get_user_response");
+            assert_eq!(to_snake_case("HTTPRequest"), "http_request");
+            assert_eq!(to_snake_case("Track2Message"), "track2_message");
+            assert_eq!(to_upper_camel_case("result"), "Result");
+            assert_eq!(to_upper_camel_case("payment_method"), "PaymentMethod");
+            assert_eq!(to_upper_camel_case("Ok"), "Ok");
+        }
+
+        #[test]
+        fn keyword_field_names_become_raw_idents() {
+            assert_eq!(field_ident("name").to_string(), "name");
+            assert_eq!(field_ident("type").to_string(), "r#type");
+            assert_eq!(field_ident("r#else").to_string(), "r#else");
+            assert_eq!(ident_name(&field_ident("r#struct")), "struct");
+        }
+
+        #[test]
+        fn screaming_snake_case_names() {
+            assert_eq!(to_screaming_snake_case("Status"), "STATUS");
+            assert_eq!(
+                to_screaming_snake_case("SubscriptionTier"),
+                "SUBSCRIPTION_TIER"
+            );
+            assert_eq!(to_screaming_snake_case("OK"), "OK");
+            assert_eq!(to_screaming_snake_case("HTTPStatus"), "HTTP_STATUS");
+            assert_eq!(to_screaming_snake_case("HTTPServer2"), "HTTP_SERVER2");
+            assert_eq!(to_screaming_snake_case("IPv4Addr"), "I_PV4_ADDR");
+            assert_eq!(to_screaming_snake_case("Ipv4Addr"), "IPV4_ADDR");
+            assert_eq!(to_screaming_snake_case("STATUS_OK"), "STATUS_OK");
+        }
+
+        /// Cases from heck, which prost-build names everything through
+        #[test]
+        fn words_split_like_heck() {
+            assert_eq!(to_snake_case("XMLHttpRequest"), "xml_http_request");
+            assert_eq!(to_snake_case("FIELD_NAME11"), "field_name11");
+            assert_eq!(to_snake_case("99BOTTLES"), "99bottles");
+            assert_eq!(to_snake_case("abc123DEF456"), "abc123_def456");
+            assert_eq!(to_snake_case("ABC123Def456"), "abc123_def456");
+            assert_eq!(to_snake_case("ABcDE"), "a_bc_de");
+            assert_eq!(to_snake_case("displayName"), "display_name");
+            assert_eq!(to_snake_case("start_ms"), "start_ms");
+            assert_eq!(to_upper_camel_case("SHOUTY_SNAKE_CASE"), "ShoutySnakeCase");
+            assert_eq!(to_upper_camel_case("XMLHttpRequest"), "XmlHttpRequest");
+            assert_eq!(to_upper_camel_case("HTTP_SERVER_2"), "HttpServer2");
+        }
+
+        #[test]
+        fn prost_names_compare_through_upper_camel_case() {
+            assert!(same_prost_name("HTTP_SERVER_2", "HttpServer2"));
+            assert!(same_prost_name("HTTP_SERVER2", "HTTPServer2"));
+            assert!(same_prost_name("STATUS_OK", "StatusOk"));
+            assert!(!same_prost_name("STATUS_OK", "Ok"));
+        }
+    }
+}
+
+mod registry {
+    use crate::analysis::type_analysis::NewtypeCollection;
+    use std::collections::{HashMap, HashSet};
+    use std::sync::{Mutex, OnceLock};
+
+    /// Global registry for tracking enum types across macro invocations
+    static ENUM_TYPE_REGISTRY: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+    /// Initialize the global enum registry
+    fn get_enum_registry() -> &'static Mutex<HashSet<String>> {
+        ENUM_TYPE_REGISTRY.get_or_init(|| Mutex::new(HashSet::new()))
+    }
+
+    /// Register a type as an enum (called during enum processing)
+    pub fn register_enum_type(type_name: &str) {
+        if let Ok(mut registry) = get_enum_registry().lock() {
+            registry.insert(type_name.to_string());
+        }
+    }
+
+    /// Check if a type is registered as an enum (called during field processing)
+    pub fn is_registered_enum_type(type_name: &str) -> bool {
+        get_enum_registry()
+            .lock()
+            .map(|registry| registry.contains(type_name))
+            .unwrap_or(false)
+    }
+
+    /// Global registry for newtypes over a `Vec` or map, whose elements convert one by one when
+    /// they are transparent fields, by type name
+    static COLLECTION_NEWTYPE_REGISTRY: OnceLock<Mutex<HashMap<String, NewtypeCollection>>> =
+        OnceLock::new();
+
+    fn get_collection_newtype_registry() -> &'static Mutex<HashMap<String, NewtypeCollection>> {
+        COLLECTION_NEWTYPE_REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+    }
+
+    /// Register a newtype over a collection (called during tuple struct processing)
+    pub fn register_collection_newtype(type_name: &str, collection: NewtypeCollection) {
+        if let Ok(mut registry) = get_collection_newtype_registry().lock() {
+            registry.insert(type_name.to_string(), collection);
+        }
+    }
+
+    /// The collection a registered newtype wraps (called during field processing)
+    pub fn registered_collection_newtype(type_name: &str) -> Option<NewtypeCollection> {
+        get_collection_newtype_registry()
+            .lock()
+            .ok()
+            .and_then(|registry| registry.get(type_name).copied())
+    }
+
+    /// Global registry for the convention functions of `#[protto_rules]` modules, by module name
+    static RULES_REGISTRY: OnceLock<Mutex<HashMap<String, HashSet<String>>>> = OnceLock::new();
+
+    fn get_rules_registry() -> &'static Mutex<HashMap<String, HashSet<String>>> {
+        RULES_REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+    }
+
+    /// Register the convention functions of a rules module (called during module processing)
+    pub fn register_rules(module_name: &str, functions: Vec<String>) {
+        if let Ok(mut registry) = get_rules_registry().lock() {
+            registry.insert(module_name.to_string(), functions.into_iter().collect());
+        }
+    }
+
+    /// The convention functions of a registered rules module (called during struct processing)
+    pub fn registered_rules(module_name: &str) -> Option<HashSet<String>> {
+        get_rules_registry()
+            .lock()
+            .ok()
+            .and_then(|registry| registry.get(module_name).cloned())
+    }
+
+    /// Global registry for the schema read from `#[protto_schema]` modules, by module name
+    static SCHEMA_REGISTRY: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+    fn get_schema_registry() -> &'static Mutex<HashMap<String, String>> {
+        SCHEMA_REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+    }
+
+    /// Register the metadata rendered for a schema module (called during module processing)
+    pub fn register_schema(module_name: &str, schema: String) {
+        if let Ok(mut registry) = get_schema_registry().lock() {
+            registry.insert(module_name.to_string(), schema);
+        }
+    }
+
+    /// The metadata of every registered schema module, in module name order (called during
+    /// field processing)
+    pub fn registered_schema() -> String {
+        get_schema_registry()
+            .lock()
+            .map(|registry| {
+                let mut modules: Vec<_> = registry.iter().collect();
+                modules.sort();
+                modules
+                    .into_iter()
+                    .map(|(_, schema)| schema.as_str())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Generates the `Protto` conversions for a struct or enum.
+pub fn derive_protto(input: TokenStream) -> TokenStream {
+    let ast: DeriveInput = syn::parse2(input).unwrap();
+
+    // -- phase 0 - rewrite legacy #[proto(...)] attributes, warning at each one --
+    #[cfg(feature = "proto-compat")]
+    let (ast, legacy_warnings) = match analysis::legacy_attributes::migrate(ast) {
+        Ok((ast, legacy)) => (
+            ast,
+            analysis::legacy_attributes::deprecation_warnings(&legacy),
+        ),
+        Err(err) => return err.to_compile_error(),
+    };
+    #[cfg(not(feature = "proto-compat"))]
+    let legacy_warnings = proc_macro2::TokenStream::new();
+
+    // -- phase 0.1 - add the workspace defaults from protto.toml the type does not set itself --
+    let (ast, defaults_dependency) = match analysis::workspace_defaults::load() {
+        Ok(Some(defaults)) => match defaults.apply(ast) {
+            Ok(ast) => (ast, defaults.track_file()),
+            Err(err) => return err.to_compile_error(),
+        },
+        Ok(None) => (ast, proc_macro2::TokenStream::new()),
+        Err(err) => return err.to_compile_error(),
+    };
+
+    if let Err(err) = attribute_parser::validate_attributes(&ast) {
+        return err.to_compile_error();
+    }
+    if let Some(err) = analysis::proto_metadata::conflict_error() {
+        return err.to_compile_error();
+    }
+    let cfg_predicates = match attribute_parser::get_cfg_predicates(&ast.attrs) {
+        Ok(predicates) => predicates,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    // -- phase 0.25 - replace the type aliases fields name via alias_of with their targets --
+    let ast = match analysis::type_aliases::resolve_type_aliases(ast) {
+        Ok(ast) => ast,
+        Err(err) => return err.to_compile_error(),
+    };
+    let parsed_input = ParsedInput::new(ast.clone());
+
+    // -- phase 0.3 - give fields of types with use_rules convention functions those functions --
+    let ast = match attribute_parser::get_struct_level_use_rules(&ast.attrs) {
+        Some(rules_path) => match rules::apply_rules(
+            ast,
+            &rules_path,
+            &parsed_input.proto_module,
+            &parsed_input.proto_name,
+        ) {
+            Ok(ast) => ast,
+            Err(err) => return err.to_compile_error(),
+        },
+        None => ast,
+    };
+
+    // -- phase 0.5 - ignore rust fields the proto message lacks, under extra_fields = "default" --
+    let (ast, extra_fields_note) =
+        if attribute_parser::has_struct_level_extra_fields_default(&ast.attrs) {
+            match analysis::extra_fields::mark_extra_fields(
+                ast,
+                &parsed_input.proto_module,
+                &parsed_input.proto_name,
+            ) {
+                Ok(marked) => marked,
+                Err(err) => return err.to_compile_error(),
+            }
+        } else {
+            (ast, proc_macro2::TokenStream::new())
+        };
+
+    let name = parsed_input.name;
+
+    let _trace = CallStackDebug::new("protto_derive::lib", "protto_derive", &name, "");
+
+    // -- phase 1 - check if this is an enum type with #[proto(enum)] --
+    // oneof enums carry values, so they convert through From rather than as i32 enums, and
+    // enum_as_i32 enums convert by their discriminants without a prost enum type
+    let oneofs = attribute_parser::get_enum_level_oneofs(&ast.attrs);
+    let enum_as_i32 = attribute_parser::has_enum_level_enum_as_i32(&ast.attrs);
+    if let syn::Data::Enum(_) = &ast.data
+        && oneofs.is_empty()
+        && !enum_as_i32
+    {
+        registry::register_enum_type(&ast.ident.to_string())
+    }
+    if let syn::Data::Struct(data_struct) = &ast.data
+        && let syn::Fields::Unnamed(fields_unnamed) = &data_struct.fields
+        && !parsed_input.transparent
+        && let Some(collection) = tuple_generator::wrapped_collection(fields_unnamed)
+    {
+        registry::register_collection_newtype(&ast.ident.to_string(), collection);
+    }
+
+    // -- phase 2 - process the struct/enum --
+    let generated = match &ast.data {
+        syn::Data::Struct(_) if parsed_input.passthrough => {
+            struct_generator::generate_passthrough_implementations(
+                &name,
+                &parsed_input.generics,
+                parsed_input.any_type_url.as_deref(),
+                parsed_input.estimated_size,
+            )
+        }
+        syn::Data::Struct(data_struct) if parsed_input.arena => {
+            arena_generator::generate_arena_implementations(
+                &name,
+                &parsed_input.generics,
+                &data_struct.fields,
+                &parsed_input.proto_path,
+            )
+        }
+        syn::Data::Struct(data_struct) => match &data_struct.fields {
+            syn::Fields::Named(fields_named) => {
+                let config = struct_generator::StructImplConfig {
+                    name: &name,
+                    fields: &fields_named.named,
+                    proto_module: &parsed_input.proto_module,
+                    proto_name: &parsed_input.proto_name,
+                    proto_path: &parsed_input.proto_path,
+                    struct_level_error_type: &parsed_input.struct_level_error_type,
+                    struct_level_error_fn: &parsed_input.struct_level_error_fn,
+                    struct_level_error_fn_with_value: parsed_input.struct_level_error_fn_with_value,
+                    proto_ignored_fields: &parsed_input.proto_ignored_fields,
+                    proto_only: &parsed_input.proto_only,
+                    generics: &parsed_input.generics,
+                    resolver_type: &parsed_input.resolver_type,
+                    error_mod: &parsed_input.error_mod,
+                    static_errors: parsed_input.static_errors,
+                    presence: parsed_input.presence,
+                    field_paths: parsed_input.field_paths,
+                    partial: parsed_input.partial,
+                    proto_parts: parsed_input.proto_parts,
+                    view: parsed_input.view,
+                    validate: parsed_input.validate,
+                    debug_roundtrip: parsed_input.debug_roundtrip,
+                    estimated_size: parsed_input.estimated_size,
+                    pure_custom_fns: parsed_input.pure_custom_fns,
+                    both_conversions: parsed_input.both_conversions,
+                    transparent: parsed_input.transparent,
+                    any_type_url: &parsed_input.any_type_url,
+                    conversions_only: false,
+                    vis: &parsed_input.vis,
+                    error_vis: &parsed_input.error_vis,
+                };
+
+                if parsed_input.sources.is_empty() {
+                    struct_generator::generate_struct_implementations_for_targets(
+                        config,
+                        &parsed_input.also_proto,
+                    )
+                } else {
+                    struct_generator::generate_sources_implementations(
+                        config,
+                        &parsed_input.sources,
+                    )
+                }
+            }
+            syn::Fields::Unnamed(_) if parsed_input.transparent => {
+                let transparent_impls = tuple_generator::generate_transparent_tuple_implementations(
+                    &name,
+                    &parsed_input.proto_path,
+                    &parsed_input.generics,
+                );
+                let any_impl = parsed_input.any_type_url.as_deref().map(|type_url| {
+                    struct_generator::generate_to_proto_any(
+                        &name,
+                        &parsed_input.generics,
+                        &parsed_input.proto_path,
+                        type_url,
+                    )
+                });
+                quote::quote! {
+                    #transparent_impls
+                    #any_impl
+                }
+            }
+            syn::Fields::Unnamed(_) if !parsed_input.also_proto.is_empty() => {
+                syn::Error::new_spanned(
+                    &ast.ident,
+                    "also_proto requires a struct with named fields, whose field attributes map \
+                    onto each proto message",
+                )
+                .to_compile_error()
+            }
+            syn::Fields::Unnamed(_) if !parsed_input.sources.is_empty() => syn::Error::new_spanned(
+                &ast.ident,
+                "sources requires a struct with named fields, each naming the proto message \
+                    it belongs to",
+            )
+            .to_compile_error(),
+            syn::Fields::Unnamed(_) if !parsed_input.proto_only.is_empty() => {
+                syn::Error::new_spanned(
+                    &ast.ident,
+                    "proto_only requires a struct with named fields, converting to a whole proto \
+                    message",
+                )
+                .to_compile_error()
+            }
+            syn::Fields::Unnamed(_) if parsed_input.any_type_url.is_some() => {
+                syn::Error::new_spanned(
+                    &ast.ident,
+                    "any_type_url requires a struct converting to a whole proto message: one \
+                    with named fields, or a transparent newtype of a message",
+                )
+                .to_compile_error()
+            }
+            syn::Fields::Unnamed(fields_unnamed) => {
+                tuple_generator::generate_tuple_implementations(&name, fields_unnamed)
+            }
+            syn::Fields::Unit => {
+                panic!("Protto does not support unit structs");
+            }
+        },
+        syn::Data::Enum(data_enum) => {
+            let variants = &data_enum.variants;
+            let non_exhaustive_oneof =
+                attribute_parser::get_enum_level_non_exhaustive_oneof(&ast.attrs);
+            if non_exhaustive_oneof && oneofs.is_empty() {
+                syn::Error::new_spanned(
+                    &ast.ident,
+                    "non_exhaustive_oneof requires the enum-level `oneof` attribute",
+                )
+                .to_compile_error()
+            } else if enum_as_i32 && !oneofs.is_empty() {
+                syn::Error::new_spanned(
+                    &ast.ident,
+                    "enum_as_i32 and oneof are exclusive: a oneof enum's variants carry values",
+                )
+                .to_compile_error()
+            } else if enum_as_i32 {
+                enum_generator::generate_i32_enum_conversions(&name, variants)
+            } else if oneofs.is_empty() {
+                enum_generator::generate_enum_conversions(
+                    &name,
+                    variants,
+                    &parsed_input.proto_module,
+                )
+            } else {
+                enum_generator::generate_oneof_conversions(
+                    &name,
+                    variants,
+                    &parsed_input.proto_module,
+                    &oneofs,
+                    non_exhaustive_oneof,
+                    parsed_input.estimated_size,
+                )
+            }
+        }
+        _ => panic!("Protto only supports structs and enums, not unions"),
+    };
+
+    _trace.generated_code(&generated, name, "", "bidirectional_proto_to_rust", &[]);
+
+    utils::with_cfg(
+        quote::quote! {
+            #generated
+            #extra_fields_note
+            #legacy_warnings
+            #defaults_dependency
+        },
+        &cfg_predicates,
+    )
+}
+
+/// Registers a module of conversion conventions marked `#[protto_rules]`.
+pub fn protto_rules(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            attr.into_iter()
+                .next()
+                .map_or_else(proc_macro2::Span::call_site, |token| token.span()),
+            "protto_rules takes no arguments",
+        )
+        .to_compile_error();
+    }
+    syn::parse2(item)
+        .and_then(rules::register_rules_module)
+        .unwrap_or_else(syn::Error::into_compile_error)
+}
+
+/// Registers the prost-generated module marked `#[protto_schema]`, resolving `include!` paths
+/// against `source_dir`, the directory of the file invoking the macro.
+pub fn protto_schema(
+    attr: TokenStream,
+    item: TokenStream,
+    source_dir: Option<&std::path::Path>,
+) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            attr.into_iter()
+                .next()
+                .map_or_else(proc_macro2::Span::call_site, |token| token.span()),
+            "protto_schema takes no arguments",
+        )
+        .to_compile_error();
+    }
+    syn::parse2(item)
+        .and_then(|module| schema::register_schema_module(module, source_dir))
+        .unwrap_or_else(syn::Error::into_compile_error)
+}
+
+/// Generates the structs of the `include_nested!` invocation.
+pub fn include_nested(input: TokenStream) -> TokenStream {
+    syn::parse2(input)
+        .and_then(nested_generator::generate_nested_mirrors)
+        .unwrap_or_else(syn::Error::into_compile_error)
+}
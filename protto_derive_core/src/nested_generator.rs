@@ -0,0 +1,338 @@
+//! Mirror structs for the nested messages of a proto message, generated by `include_nested!`.
+//!
+//! The message tree is read from the schema metadata written by `protto::build`, so only the
+//! field shapes and types recorded there are known. Each nested message becomes a plain struct
+//! with one public field per proto field and a `Protto` derive; messages named in `except` are
+//! left to the user, who writes them by hand under the same name.
+
+use crate::analysis::proto_metadata::{self, ProtoMetadata, SchemaField, SchemaKind, SchemaShape};
+use crate::{constants, utils};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use std::collections::{BTreeMap, HashSet};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+
+/// `include_nested!(proto::Order)` or `include_nested!(proto::Order, except = "Customer, ...")`
+pub struct IncludeNestedInput {
+    pub message: syn::Path,
+    pub except: HashSet<String>,
+}
+
+impl Parse for IncludeNestedInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let message = input.parse()?;
+        let mut except = HashSet::new();
+
+        if input.parse::<Option<syn::Token![,]>>()?.is_some() && !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            if key != "except" {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "expected `except = \"Message, ...\"`",
+                ));
+            }
+            input.parse::<syn::Token![=]>()?;
+            let names: syn::LitStr = input.parse()?;
+            except = names
+                .value()
+                .split(',')
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect();
+            input.parse::<Option<syn::Token![,]>>()?;
+        }
+
+        Ok(Self { message, except })
+    }
+}
+
+pub fn generate_nested_mirrors(input: IncludeNestedInput) -> syn::Result<TokenStream> {
+    let Some(metadata) = proto_metadata::load() else {
+        return Err(syn::Error::new(
+            input.message.span(),
+            "include_nested! reads the proto schema metadata; call \
+             protto::build::generate_metadata from the build script",
+        ));
+    };
+    generate_from_metadata(&metadata, &input)
+}
+
+fn generate_from_metadata(
+    metadata: &ProtoMetadata,
+    input: &IncludeNestedInput,
+) -> syn::Result<TokenStream> {
+    let span = input.message.span();
+    let mut segments: Vec<String> = input
+        .message
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect();
+    let root = segments.pop().unwrap_or_default();
+    let module = if segments.is_empty() {
+        constants::DEFAULT_PROTO_MODULE.to_string()
+    } else {
+        segments.join("::")
+    };
+
+    let root_schema = metadata.message_schema(&module, &root).ok_or_else(|| {
+        syn::Error::new(
+            span,
+            format!("message `{root}` is not in the proto metadata"),
+        )
+    })?;
+
+    let mut skipped = input.except.clone();
+    skipped.insert(root);
+    let mut pending = nested_message_names(root_schema.fields);
+    let mut visited = HashSet::new();
+    let mut mirrors = Vec::new();
+
+    while let Some(name) = pending.pop() {
+        if skipped.contains(&name) || !visited.insert(name.clone()) {
+            continue;
+        }
+        let hand_write = |reason: String| {
+            syn::Error::new(
+                span,
+                format!("{reason}; write a type for `{name}` by hand and add it to `except`"),
+            )
+        };
+
+        let schema = metadata.message_schema(&module, &name).ok_or_else(|| {
+            hand_write(format!(
+                "nested message `{name}` is not in the proto metadata"
+            ))
+        })?;
+        if is_nested_declaration(schema.qualified_name) {
+            return Err(hand_write(format!(
+                "`{}` is declared inside another message",
+                schema.qualified_name
+            )));
+        }
+        if let Some(oneof) = schema.oneofs.first() {
+            return Err(hand_write(format!("`{name}` has oneof `{oneof}`")));
+        }
+
+        let fields: BTreeMap<_, _> = schema.fields.iter().collect();
+        let mut mirror_fields = Vec::new();
+        for (field_name, field) in fields {
+            let field_type = mirror_field_type(field)
+                .map_err(|reason| hand_write(format!("field `{name}.{field_name}` {reason}")))?;
+            let field_ident = utils::field_ident(field_name);
+            mirror_fields.push(quote! { pub #field_ident: #field_type });
+        }
+        pending.extend(nested_message_names(schema.fields));
+
+        let ident = format_ident!("{}", name);
+        let doc = format!("Mirror of the proto message `{}`.", schema.qualified_name);
+        mirrors.push(quote! {
+            #[doc = #doc]
+            #[derive(::protto::Protto, Debug, Clone, PartialEq)]
+            #[protto(module = #module, proto_name = #name)]
+            pub struct #ident {
+                #(#mirror_fields,)*
+            }
+        });
+    }
+
+    Ok(quote! { #(#mirrors)* })
+}
+
+/// Message types referenced by the fields, in reverse field-name order so they are popped in
+/// field-name order.
+fn nested_message_names(fields: &std::collections::HashMap<String, SchemaField>) -> Vec<String> {
+    let mut names: Vec<_> = fields
+        .iter()
+        .filter(|(_, field)| field.kind == SchemaKind::Message && !field.is_wrapper())
+        .map(|(field_name, field)| (field_name, simple_name(value_type(field)).to_string()))
+        .collect();
+    names.sort();
+    names.into_iter().rev().map(|(_, name)| name).collect()
+}
+
+fn mirror_field_type(field: &SchemaField) -> Result<TokenStream, String> {
+    if field.is_wrapper() {
+        return wrapper_value_type(&field.type_name)
+            .map(|value| quote! { Option<#value> })
+            .ok_or_else(|| {
+                "holds a wrapper message; enable the `wrapper_types` feature".to_string()
+            });
+    }
+
+    let element = match field.kind {
+        SchemaKind::Scalar => scalar_type(value_type(field))
+            .ok_or_else(|| format!("has unsupported scalar type `{}`", field.type_name))?,
+        SchemaKind::Enum => quote! { i32 },
+        SchemaKind::Message => {
+            let ident = format_ident!("{}", simple_name(value_type(field)));
+            quote! { #ident }
+        }
+    };
+
+    match field.shape {
+        SchemaShape::Required => Ok(element),
+        SchemaShape::Optional if field.boxed => Ok(quote! { Option<Box<#element>> }),
+        SchemaShape::Optional => Ok(quote! { Option<#element> }),
+        SchemaShape::Repeated => Ok(quote! { Vec<#element> }),
+        SchemaShape::Map if field.kind == SchemaKind::Message => {
+            Err("is a map of messages".to_string())
+        }
+        SchemaShape::Map => {
+            let key = map_key_type(&field.type_name)
+                .and_then(scalar_type)
+                .ok_or_else(|| format!("has unsupported map type `{}`", field.type_name))?;
+            Ok(quote! { ::std::collections::HashMap<#key, #element> })
+        }
+    }
+}
+
+fn scalar_type(proto_type: &str) -> Option<TokenStream> {
+    let rust_type = match proto_type {
+        "double" => quote! { f64 },
+        "float" => quote! { f32 },
+        "int32" | "sint32" | "sfixed32" => quote! { i32 },
+        "int64" | "sint64" | "sfixed64" => quote! { i64 },
+        "uint32" | "fixed32" => quote! { u32 },
+        "uint64" | "fixed64" => quote! { u64 },
+        "bool" => quote! { bool },
+        "string" => quote! { String },
+        "bytes" => quote! { Vec<u8> },
+        _ => return None,
+    };
+    Some(rust_type)
+}
+
+/// The scalar a wrapper message holds, when the derive converts wrapper fields.
+fn wrapper_value_type(type_name: &str) -> Option<TokenStream> {
+    if !cfg!(feature = "wrapper_types") {
+        return None;
+    }
+    let proto_type = match simple_name(type_name) {
+        "DoubleValue" => "double",
+        "FloatValue" => "float",
+        "Int64Value" => "int64",
+        "UInt64Value" => "uint64",
+        "Int32Value" => "int32",
+        "UInt32Value" => "uint32",
+        "BoolValue" => "bool",
+        "StringValue" => "string",
+        "BytesValue" => "bytes",
+        _ => return None,
+    };
+    scalar_type(proto_type)
+}
+
+/// The element type of the field: the value type of a map, the declared type otherwise.
+fn value_type(field: &SchemaField) -> &str {
+    match field.shape {
+        SchemaShape::Map => {
+            map_types(&field.type_name).map_or(&field.type_name, |(_, value)| value)
+        }
+        _ => &field.type_name,
+    }
+}
+
+fn map_key_type(type_name: &str) -> Option<&str> {
+    map_types(type_name).map(|(key, _)| key)
+}
+
+fn map_types(type_name: &str) -> Option<(&str, &str)> {
+    type_name
+        .strip_prefix("map<")?
+        .strip_suffix('>')?
+        .split_once(',')
+}
+
+fn simple_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// prost generates messages declared inside another message in a submodule named after the
+/// parent, which a mirror's `proto_name` cannot reach. Packages are lowercase by convention, so
+/// an uppercase segment before the name marks a parent message.
+fn is_nested_declaration(qualified_name: &str) -> bool {
+    let mut segments = qualified_name.rsplit('.').skip(1);
+    segments
+        .next()
+        .is_some_and(|parent| parent.starts_with(|c: char| c.is_ascii_uppercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METADATA: &str = "\
+message shop.Order
+field id required scalar uint64
+field customer optional message Customer
+field items repeated message LineItem
+message shop.Customer
+field name required scalar string
+message shop.LineItem
+field product optional message Product
+field quantity required scalar uint32
+field status required enum Status
+field labels map scalar map<string,string>
+message shop.Product
+field sku required scalar string
+field type required scalar string
+rename type r#type
+field price optional scalar double
+message shop.Payment
+field id required scalar uint64
+oneof method
+message shop.Invoice
+field payment optional message Payment
+";
+
+    fn generate(input: &str) -> syn::Result<String> {
+        let metadata = ProtoMetadata::parse(METADATA);
+        let input: IncludeNestedInput = syn::parse_str(input)?;
+        generate_from_metadata(&metadata, &input).map(|tokens| tokens.to_string())
+    }
+
+    #[test]
+    fn test_mirrors_nested_messages_but_not_root() {
+        let code = generate("proto::Order").unwrap();
+        assert!(code.contains("pub struct Customer"));
+        assert!(code.contains("pub struct LineItem"));
+        assert!(code.contains("pub struct Product"));
+        assert!(!code.contains("pub struct Order"));
+        assert!(code.contains("pub product : Option < Product >"));
+        assert!(code.contains("pub status : i32"));
+        assert!(code.contains("pub r#type : String"));
+        assert!(code.contains("pub price : Option < f64 >"));
+        assert!(code.contains(":: std :: collections :: HashMap < String , String >"));
+    }
+
+    #[test]
+    fn test_except_leaves_messages_to_hand_written_types() {
+        let code = generate(r#"proto::Order, except = "LineItem, Customer""#).unwrap();
+        assert!(code.is_empty(), "{code}");
+    }
+
+    #[test]
+    fn test_messages_with_oneofs_must_be_hand_written() {
+        let err = generate("proto::Invoice").unwrap_err().to_string();
+        assert!(err.contains("`Payment` has oneof `method`"), "{err}");
+        assert!(generate(r#"proto::Invoice, except = "Payment""#).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_root_message() {
+        let err = generate("proto::Missing").unwrap_err().to_string();
+        assert!(
+            err.contains("`Missing` is not in the proto metadata"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_nested_declarations_are_detected() {
+        assert!(is_nested_declaration("shop.Order.Line"));
+        assert!(!is_nested_declaration("shop.Order"));
+        assert!(!is_nested_declaration("Order"));
+    }
+}
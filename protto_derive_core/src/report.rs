@@ -0,0 +1,174 @@
+//! Opt-in compile-time reports on the generated conversions, selected with the `PROTTO_REPORT`
+//! environment variable as a comma-separated list of report names.
+//!
+//! `PROTTO_REPORT=clones` warns at every struct field whose generated conversion clones or
+//! allocates: `clone`, `to_string`, `to_owned`, `to_vec`, `format!` and `collect`, per direction.
+//! Errors built only when a conversion fails (`ok_or_else`, `map_err`, `Err`, `panic!`) are left
+//! out.
+//! Each finding is a `deprecated` warning spanned at the field, so it shows in the build output
+//! next to the field without failing builds that do not deny warnings.
+//!
+//! The derive also reads `PROTTO_REPORT` through `option_env!` whenever a report is on, so cargo
+//! rebuilds the crate once it is unset; turning a report on for a crate already built needs a
+//! rebuild, e.g. `cargo clean -p <crate>`.
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+use std::sync::OnceLock;
+
+pub const REPORT_ENV: &str = "PROTTO_REPORT";
+
+static REPORTS: OnceLock<Vec<String>> = OnceLock::new();
+
+fn reports() -> &'static [String] {
+    REPORTS.get_or_init(|| {
+        std::env::var(REPORT_ENV)
+            .unwrap_or_default()
+            .split(',')
+            .map(|report| report.trim().to_string())
+            .filter(|report| !report.is_empty())
+            .collect()
+    })
+}
+
+/// Whether `PROTTO_REPORT` asks for the clone and allocation report.
+pub fn clones_enabled() -> bool {
+    reports().iter().any(|report| report == "clones")
+}
+
+/// The cloning and allocating operations a generated conversion performs, in order of first
+/// appearance.
+pub fn clone_costs(conversion: &TokenStream) -> Vec<&'static str> {
+    let mut costs = Vec::new();
+    collect_costs(conversion.clone(), &mut costs);
+    costs
+}
+
+fn collect_costs(tokens: TokenStream, costs: &mut Vec<&'static str>) {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        let cost = match &token {
+            TokenTree::Group(group) => {
+                collect_costs(group.stream(), costs);
+                None
+            }
+            // Errors are only built when the conversion fails
+            TokenTree::Ident(ident)
+                if ["ok_or_else", "map_err", "Err"].iter().any(|f| ident == f) =>
+            {
+                tokens.next_if(|next| matches!(next, TokenTree::Group(_)));
+                None
+            }
+            TokenTree::Ident(ident) if ident == "panic" => {
+                tokens.next_if(
+                    |next| matches!(next, TokenTree::Punct(punct) if punct.as_char() == '!'),
+                );
+                tokens.next_if(|next| matches!(next, TokenTree::Group(_)));
+                None
+            }
+            TokenTree::Ident(ident) if ident == "format" => match tokens.peek() {
+                Some(TokenTree::Punct(punct)) if punct.as_char() == '!' => Some("format!"),
+                _ => None,
+            },
+            TokenTree::Ident(ident) => ["clone", "to_string", "to_owned", "to_vec", "collect"]
+                .into_iter()
+                .find(|cost| ident == cost),
+            _ => None,
+        };
+        if let Some(cost) = cost
+            && !costs.contains(&cost)
+        {
+            costs.push(cost);
+        }
+    }
+}
+
+/// A warning at `field` listing what each direction of its conversion clones or allocates, or
+/// nothing when neither does.
+pub fn clone_warning(
+    struct_name: &syn::Ident,
+    field: &syn::Ident,
+    proto_to_rust: &TokenStream,
+    rust_to_proto: &TokenStream,
+) -> TokenStream {
+    let directions: Vec<String> = [
+        ("proto -> rust", proto_to_rust),
+        ("rust -> proto", rust_to_proto),
+    ]
+    .into_iter()
+    .filter_map(|(direction, conversion)| {
+        let costs = clone_costs(conversion);
+        (!costs.is_empty()).then(|| format!("{direction} uses {}", costs.join(", ")))
+    })
+    .collect();
+    if directions.is_empty() {
+        return quote! {};
+    }
+
+    let note = format!(
+        "PROTTO_REPORT=clones: `{struct_name}.{}` {}",
+        crate::utils::ident_name(field),
+        directions.join("; ")
+    );
+    quote_spanned! { field.span()=>
+        const _: () = {
+            #[deprecated(note = #note)]
+            struct ConversionCost;
+            let _ = ConversionCost;
+        };
+    }
+}
+
+/// Ties the derive output to the environment variable `var`, so cargo rebuilds when it is turned
+/// off.
+pub fn env_dependency(var: &str) -> TokenStream {
+    quote! {
+        const _: ::core::option::Option<&str> = ::core::option_env!(#var);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clone_costs_found_in_nested_groups() {
+        let conversion = quote! {
+            items: proto_struct.items.into_iter().map(|item| item.name.clone()).collect(),
+            label: proto_struct.label.expect(&format!("Proto field {} is required", "label"))
+        };
+        assert_eq!(clone_costs(&conversion), ["clone", "collect", "format!"]);
+
+        let failure_only = quote! {
+            id: proto_struct.id.ok_or_else(|| OrderConversionError::MissingField("id".to_string()))?,
+            kind: proto_struct.kind.unwrap_or_else(|| panic!("{}", value.to_string())),
+            flag: match proto_struct.flag {
+                1 => true,
+                _ => return Err(OrderConversionError::InvalidValue("flag".to_string())),
+            }
+        };
+        assert!(clone_costs(&failure_only).is_empty());
+        assert!(
+            clone_costs(&quote! { id: proto_struct.id, format: proto_struct.format }).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_clone_warning_lists_each_direction() {
+        let struct_name: syn::Ident = syn::parse_quote!(Order);
+        let field: syn::Ident = syn::parse_quote!(tags);
+        let warning = clone_warning(
+            &struct_name,
+            &field,
+            &quote! { tags: proto_struct.tags.iter().map(|tag| tag.to_string()).collect() },
+            &quote! { tags: tags },
+        )
+        .to_string();
+        assert!(warning.contains(
+            "\"PROTTO_REPORT=clones: `Order.tags` proto -> rust uses to_string, collect\""
+        ));
+
+        let quiet = clone_warning(&struct_name, &field, &quote! { id: id }, &quote! { id: id });
+        assert!(quiet.is_empty());
+    }
+}
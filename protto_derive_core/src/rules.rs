@@ -0,0 +1,248 @@
+//! Project-wide conversion conventions. A module marked `#[protto_rules]` holds functions named
+//! `from_proto_<Type>` and `to_proto_<Type>`; a struct with `#[protto(use_rules = "module")]`
+//! converts every field of type `<Type>` through them, as if the field carried
+//! `from_proto_fn`/`to_proto_fn` itself. A proc macro cannot look into another module, so the
+//! attribute records the function names in a registry when the module expands: the rules module
+//! must come before the structs using it in the same crate, as with enums.
+
+use crate::analysis::attribute_parser;
+use crate::analysis::proto_metadata::{self, SchemaShape};
+use crate::{registry, utils};
+use quote::quote;
+
+const FROM_PROTO_PREFIX: &str = "from_proto_";
+const TO_PROTO_PREFIX: &str = "to_proto_";
+
+/// Registers the convention functions of a `#[protto_rules]` module and returns the module
+/// unchanged, save for allowing the non-snake-case names the convention calls for.
+pub fn register_rules_module(module: syn::ItemMod) -> syn::Result<proc_macro2::TokenStream> {
+    let Some((_, items)) = &module.content else {
+        return Err(syn::Error::new_spanned(
+            &module,
+            "protto_rules needs an inline module, `mod rules { ... }`, so its functions can be \
+            read when it expands",
+        ));
+    };
+
+    let functions: Vec<String> = items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Fn(item_fn) => Some(item_fn.sig.ident.to_string()),
+            _ => None,
+        })
+        .filter(|name| rule_type(name).is_some())
+        .collect();
+    if functions.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &module.ident,
+            format!(
+                "`{}` declares no convention functions; protto_rules looks for functions named \
+                `{FROM_PROTO_PREFIX}<Type>` and `{TO_PROTO_PREFIX}<Type>`",
+                module.ident
+            ),
+        ));
+    }
+    registry::register_rules(&module.ident.to_string(), functions);
+
+    Ok(quote! {
+        #[allow(non_snake_case)]
+        #module
+    })
+}
+
+/// Adds `from_proto_fn`/`to_proto_fn` to every field whose type has a convention function in the
+/// `use_rules` module. Fields that already say how they convert keep their own attributes. Custom
+/// functions take the proto field as declared, so a field the build metadata shows is not
+/// optional on the proto message is also marked `proto_required`.
+pub fn apply_rules(
+    mut ast: syn::DeriveInput,
+    rules_path: &syn::Path,
+    proto_module: &str,
+    proto_name: &str,
+) -> syn::Result<syn::DeriveInput> {
+    let module_name = rules_path
+        .segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+        .unwrap_or_default();
+    let Some(functions) = registry::registered_rules(&module_name) else {
+        return Err(syn::Error::new_spanned(
+            rules_path,
+            format!(
+                "no #[protto_rules] module named `{module_name}` has been expanded before `{}`; \
+                define the rules module above the struct, in the same crate",
+                ast.ident
+            ),
+        ));
+    };
+
+    let syn::Data::Struct(data_struct) = &mut ast.data else {
+        return Ok(ast);
+    };
+    let rules = quote!(#rules_path).to_string().replace(' ', "");
+    for field in data_struct.fields.iter_mut() {
+        let Some(meta) = rule_candidate(field) else {
+            continue;
+        };
+        let Some(type_name) = type_name(&field.ty) else {
+            continue;
+        };
+
+        let mut keys = Vec::new();
+        for (key, prefix) in [
+            ("from_proto_fn", FROM_PROTO_PREFIX),
+            ("to_proto_fn", TO_PROTO_PREFIX),
+        ] {
+            let function = format!("{prefix}{type_name}");
+            if functions.contains(&function) {
+                let key = syn::Ident::new(key, proc_macro2::Span::call_site());
+                let value = format!("{rules}::{function}");
+                keys.push(quote!(#key = #value));
+            }
+        }
+        if keys.is_empty() {
+            continue;
+        }
+        if !meta.has_explicit_optionality()
+            && proto_field_is_required(field, proto_module, proto_name)
+        {
+            keys.push(quote!(proto_required));
+        }
+        field.attrs.push(syn::parse_quote!(#[protto(#(#keys),*)]));
+    }
+    Ok(ast)
+}
+
+/// The type a convention function is for, from its name
+fn rule_type(function: &str) -> Option<&str> {
+    function
+        .strip_prefix(FROM_PROTO_PREFIX)
+        .or_else(|| function.strip_prefix(TO_PROTO_PREFIX))
+        .filter(|type_name| !type_name.is_empty())
+}
+
+/// The last segment of a plain type path, e.g. `Money` for `crate::money::Money`. Rules match the
+/// field's type exactly, so `Option<Money>` and `Vec<Money>` name `Option` and `Vec`.
+fn type_name(ty: &syn::Type) -> Option<String> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    type_path
+        .path
+        .segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+}
+
+/// The field's protto attributes, unless they already say how the field converts
+fn rule_candidate(field: &syn::Field) -> Option<attribute_parser::ProtoFieldMeta> {
+    if attribute_parser::has_proto_ignore(field) || attribute_parser::has_transparent_attr(field) {
+        return None;
+    }
+    attribute_parser::ProtoFieldMeta::from_field(field)
+        .ok()
+        .filter(|meta| {
+            meta.from_proto_fn.is_none()
+                && meta.to_proto_fn.is_none()
+                && meta.resolve_with.is_none()
+                && !meta.manual
+        })
+}
+
+fn proto_field_is_required(field: &syn::Field, proto_module: &str, proto_name: &str) -> bool {
+    let Some(field_name) = attribute_parser::get_proto_field_name(field)
+        .or_else(|| field.ident.as_ref().map(utils::ident_name))
+    else {
+        return false;
+    };
+    proto_metadata::lookup_field(proto_module, proto_name, &field_name)
+        .is_some_and(|schema| schema.shape == SchemaShape::Required)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field_attrs(input: &str, rules: &str) -> Vec<String> {
+        let ast = apply_rules(
+            syn::parse_str(input).unwrap(),
+            &syn::parse_str(rules).unwrap(),
+            "proto",
+            "Invoice",
+        )
+        .unwrap();
+        let syn::Data::Struct(data_struct) = ast.data else {
+            unreachable!()
+        };
+        data_struct
+            .fields
+            .iter()
+            .map(|field| {
+                let attrs = &field.attrs;
+                quote!(#(#attrs)*).to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rules_module_registers_its_convention_functions() {
+        let module: syn::ItemMod = syn::parse_quote! {
+            mod money_rules {
+                pub fn from_proto_Money(cents: i64) -> Money { Money(cents) }
+                pub fn to_proto_Money(money: Money) -> i64 { money.0 }
+                pub fn to_proto_Instant(instant: Instant) -> i64 { 0 }
+                fn helper() {}
+            }
+        };
+        let code = register_rules_module(module).unwrap().to_string();
+        assert!(code.starts_with("# [allow (non_snake_case)] mod money_rules"));
+
+        let attrs = field_attrs(
+            "struct Invoice {
+                total: Money,
+                issued: crate::time::Instant,
+                #[protto(from_proto_fn = \"cents_to_money\")] tax: Money,
+                #[protto(ignore)] draft: Money,
+                fees: Option<Money>,
+                memo: String,
+            }",
+            "crate::money_rules",
+        );
+        assert_eq!(
+            attrs,
+            [
+                "# [protto (from_proto_fn = \"crate::money_rules::from_proto_Money\" , \
+                 to_proto_fn = \"crate::money_rules::to_proto_Money\")]",
+                "# [protto (to_proto_fn = \"crate::money_rules::to_proto_Instant\")]",
+                "# [protto (from_proto_fn = \"cents_to_money\")]",
+                "# [protto (ignore)]",
+                "",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rules_module_must_be_expanded_first() {
+        let Err(err) = apply_rules(
+            syn::parse_str("struct Invoice { total: Money }").unwrap(),
+            &syn::parse_str("unknown_rules").unwrap(),
+            "proto",
+            "Invoice",
+        ) else {
+            panic!("an unregistered rules module is an error");
+        };
+        assert!(
+            err.to_string()
+                .contains("no #[protto_rules] module named `unknown_rules`")
+        );
+
+        let module: syn::ItemMod = syn::parse_quote!(
+            mod empty_rules {
+                fn helper() {}
+            }
+        );
+        let err = register_rules_module(module).unwrap_err();
+        assert!(err.to_string().contains("declares no convention functions"));
+    }
+}
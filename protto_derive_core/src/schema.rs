@@ -0,0 +1,639 @@
+//! Proto schema read from the prost-generated code itself. A module marked `#[protto_schema]`
+//! holds prost's structs, written inline or pulled in with `include!`; the `#[prost(...)]`
+//! attribute on each field spells out whether prost generated it plain, as an `Option`, a `Vec`
+//! or a map, so the attribute records those shapes in the same format as
+//! `protto::build::generate_metadata` and the derives read them as they would the build
+//! metadata. A proc macro cannot look into another module, so as with `#[protto_rules]` the schema
+//! module must come before the structs converting to its messages in the same crate.
+
+use crate::{registry, utils};
+use quote::quote;
+use std::path::{Path, PathBuf};
+use syn::punctuated::Punctuated;
+
+const SCALAR_TYPES: &[&str] = &[
+    "double", "float", "int32", "int64", "uint32", "uint64", "sint32", "sint64", "fixed32",
+    "fixed64", "sfixed32", "sfixed64", "bool", "string", "bytes",
+];
+
+/// The `google.protobuf` wrapper message prost generates as `Option` of each Rust type.
+const WRAPPER_TYPES: &[(&str, &str)] = &[
+    ("f64", "DoubleValue"),
+    ("f32", "FloatValue"),
+    ("i64", "Int64Value"),
+    ("u64", "UInt64Value"),
+    ("i32", "Int32Value"),
+    ("u32", "UInt32Value"),
+    ("bool", "BoolValue"),
+    ("String", "StringValue"),
+    ("Bytes", "BytesValue"),
+];
+
+/// Registers the messages and enums prost generated in a `#[protto_schema]` module and returns
+/// the module unchanged. `source_dir` is the directory of the file declaring the module, against
+/// which `include!` resolves relative paths.
+pub fn register_schema_module(
+    module: syn::ItemMod,
+    source_dir: Option<&Path>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let Some((_, items)) = &module.content else {
+        return Err(syn::Error::new_spanned(
+            &module,
+            "protto_schema needs an inline module, `mod proto { ... }`, holding the prost-generated \
+            code or an `include!` of it",
+        ));
+    };
+
+    let mut schema = String::new();
+    let scope = Scope {
+        package: vec![module.ident.to_string()],
+        messages: Vec::new(),
+    };
+    render_items(items, &scope, source_dir, &mut schema)?;
+    if !schema.lines().any(|line| line.starts_with("message ")) {
+        return Err(syn::Error::new_spanned(
+            &module.ident,
+            format!(
+                "`{}` declares no prost messages; protto_schema looks for structs deriving \
+                `prost::Message`",
+                module.ident
+            ),
+        ));
+    }
+    registry::register_schema(&module.ident.to_string(), schema);
+
+    Ok(quote!(#module))
+}
+
+/// Where items are declared: the modules standing in for the proto package, then the messages a
+/// nested type is declared in.
+#[derive(Clone)]
+struct Scope {
+    package: Vec<String>,
+    messages: Vec<String>,
+}
+
+impl Scope {
+    fn qualified(&self, name: &str) -> String {
+        let mut path = self.package.clone();
+        path.extend(self.messages.iter().cloned());
+        path.push(name.to_string());
+        path.join(".")
+    }
+}
+
+fn render_items(
+    items: &[syn::Item],
+    scope: &Scope,
+    source_dir: Option<&Path>,
+    out: &mut String,
+) -> syn::Result<()> {
+    for item in items {
+        match item {
+            syn::Item::Struct(item_struct) if derives(&item_struct.attrs, "Message") => {
+                render_message(item_struct, scope, out)?;
+            }
+            syn::Item::Enum(item_enum) if derives(&item_enum.attrs, "Enumeration") => {
+                render_enum(item_enum, items, scope, out);
+            }
+            syn::Item::Mod(item_mod) => {
+                let Some((_, nested_items)) = &item_mod.content else {
+                    continue;
+                };
+                // prost declares the types nested in a message in a module named after it
+                let module_name = utils::ident_name(&item_mod.ident);
+                let message = items.iter().find_map(|item| match item {
+                    syn::Item::Struct(item_struct)
+                        if derives(&item_struct.attrs, "Message")
+                            && utils::to_snake_case(&item_struct.ident.to_string())
+                                == module_name =>
+                    {
+                        Some(item_struct.ident.to_string())
+                    }
+                    _ => None,
+                });
+                let mut nested = scope.clone();
+                match message {
+                    Some(message) => nested.messages.push(message),
+                    None => nested.package.push(module_name),
+                }
+                render_items(nested_items, &nested, source_dir, out)?;
+            }
+            syn::Item::Macro(item_macro) if item_macro.mac.path.is_ident("include") => {
+                let path = include_path(&item_macro.mac, source_dir)?;
+                let content = std::fs::read_to_string(&path).map_err(|err| {
+                    syn::Error::new_spanned(
+                        &item_macro.mac,
+                        format!("cannot read `{}`: {err}", path.display()),
+                    )
+                })?;
+                let file = syn::parse_file(&content).map_err(|err| {
+                    syn::Error::new_spanned(
+                        &item_macro.mac,
+                        format!("cannot parse `{}`: {err}", path.display()),
+                    )
+                })?;
+                render_items(&file.items, scope, path.parent(), out)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Whether the item derives a trait named `name`, such as `::prost::Message`.
+fn derives(attrs: &[syn::Attribute], name: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<syn::Path, syn::Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .any(|path| {
+            path.segments
+                .last()
+                .is_some_and(|segment| segment.ident == name)
+        })
+}
+
+fn render_message(
+    item_struct: &syn::ItemStruct,
+    scope: &Scope,
+    out: &mut String,
+) -> syn::Result<()> {
+    out.push_str(&format!(
+        "message {}\n",
+        scope.qualified(&item_struct.ident.to_string())
+    ));
+    let mut oneofs = Vec::new();
+    for field in &item_struct.fields {
+        let (Some(ident), Some(attr)) = (
+            &field.ident,
+            field
+                .attrs
+                .iter()
+                .find(|attr| attr.path().is_ident("prost")),
+        ) else {
+            continue;
+        };
+        let name = utils::ident_name(ident);
+        let prost = ProstField::parse(attr)?;
+        if prost.oneof {
+            oneofs.push(ident.to_string());
+            continue;
+        }
+        let Some(line) = prost.render(&name, &field.ty) else {
+            continue;
+        };
+        out.push_str(&line);
+        if ident.to_string().starts_with("r#") {
+            out.push_str(&format!("rename {name} {ident}\n"));
+        }
+    }
+    for oneof in oneofs {
+        out.push_str(&format!("oneof {oneof}\n"));
+    }
+    Ok(())
+}
+
+/// What a field's `#[prost(...)]` attribute says about it.
+#[derive(Default)]
+struct ProstField {
+    scalar: Option<String>,
+    message: bool,
+    enumeration: Option<String>,
+    map: Option<String>,
+    label: Option<String>,
+    boxed: bool,
+    oneof: bool,
+}
+
+impl ProstField {
+    fn parse(attr: &syn::Attribute) -> syn::Result<Self> {
+        let mut prost = ProstField::default();
+        let metas =
+            attr.parse_args_with(Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)?;
+        for meta in metas {
+            let Some(key) = meta.path().get_ident().map(ToString::to_string) else {
+                continue;
+            };
+            let value = match &meta {
+                syn::Meta::NameValue(syn::MetaNameValue {
+                    value:
+                        syn::Expr::Lit(syn::ExprLit {
+                            lit: syn::Lit::Str(value),
+                            ..
+                        }),
+                    ..
+                }) => Some(value.value()),
+                _ => None,
+            };
+            match (key.as_str(), value) {
+                ("message", None) => prost.message = true,
+                ("optional" | "repeated" | "required", None) => prost.label = Some(key),
+                ("boxed", None) => prost.boxed = true,
+                ("enumeration", Some(enumeration)) => prost.enumeration = Some(enumeration),
+                ("map" | "btree_map", Some(map)) => prost.map = Some(map),
+                ("oneof", Some(_)) => prost.oneof = true,
+                (scalar, _) if SCALAR_TYPES.contains(&scalar) => prost.scalar = Some(key),
+                _ => {}
+            }
+        }
+        Ok(prost)
+    }
+
+    /// The metadata line for the field, if prost's attribute names a type.
+    fn render(&self, name: &str, ty: &syn::Type) -> Option<String> {
+        let repeated = self.label.as_deref() == Some("repeated");
+        let (shape, kind, type_name) = if let Some(map) = &self.map {
+            let (key, value) = map.split_once(',')?;
+            let (kind, value) = map_value(value.trim());
+            ("map", kind, format!("map<{},{value}>", key.trim()))
+        } else if let Some(enumeration) = &self.enumeration {
+            ("", "enum", simple_name(enumeration).to_string())
+        } else if self.message {
+            ("", "message", message_type_name(ty, repeated)?)
+        } else {
+            ("", "scalar", self.scalar.clone()?)
+        };
+        let shape = match (shape, self.label.as_deref()) {
+            ("map", _) => "map",
+            (_, Some(label)) => label,
+            // prost generates singular message fields as `Option` in proto3
+            _ if self.message => "optional",
+            _ => "required",
+        };
+        let boxed = self.boxed || (self.message && !repeated && type_contains(ty, "Box"));
+        Some(format!(
+            "field {name} {shape} {kind} {type_name}{}\n",
+            if boxed { " boxed" } else { "" }
+        ))
+    }
+}
+
+/// The kind and type name of a map's value, written by prost as `message(Track)`,
+/// `enumeration(Status)` or a scalar type.
+fn map_value(value: &str) -> (&'static str, String) {
+    let inner = |prefix: &str| {
+        value
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_prefix('('))
+            .and_then(|rest| rest.strip_suffix(')'))
+            .map(|name| simple_name(name.trim()).to_string())
+    };
+    if let Some(name) = inner("message") {
+        ("message", name)
+    } else if let Some(name) = inner("enumeration") {
+        ("enum", name)
+    } else {
+        ("scalar", value.to_string())
+    }
+}
+
+/// The message a field holds, from its Rust type. prost generates the well-known wrapper
+/// messages as an `Option` of the wrapped value, named here as the wrapper.
+fn message_type_name(ty: &syn::Type, repeated: bool) -> Option<String> {
+    let mut ty = unwrap_type(ty, "Option");
+    if repeated {
+        ty = unwrap_type(ty, "Vec");
+    }
+    let ty = unwrap_type(ty, "Box");
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let mut name = segment.ident.to_string();
+    if name == "Vec" && type_contains(ty, "u8") {
+        name = "Bytes".to_string();
+    }
+    Some(match WRAPPER_TYPES.iter().find(|(rust, _)| *rust == name) {
+        Some((_, wrapper)) => format!("google.protobuf.{wrapper}"),
+        None => name,
+    })
+}
+
+/// The type argument of `ty` when it is the generic type `wrapper`, or `ty` itself.
+fn unwrap_type<'a>(ty: &'a syn::Type, wrapper: &str) -> &'a syn::Type {
+    if let syn::Type::Path(type_path) = ty
+        && let Some(segment) = type_path.path.segments.last()
+        && segment.ident == wrapper
+        && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+        && let Some(syn::GenericArgument::Type(inner)) = args.args.first()
+    {
+        return inner;
+    }
+    ty
+}
+
+/// Whether `ident` names `ty` or one of its type arguments.
+fn type_contains(ty: &syn::Type, ident: &str) -> bool {
+    let syn::Type::Path(type_path) = ty else {
+        return false;
+    };
+    type_path.path.segments.iter().any(|segment| {
+        segment.ident == ident
+            || match &segment.arguments {
+                syn::PathArguments::AngleBracketed(args) => args.args.iter().any(
+                    |arg| matches!(arg, syn::GenericArgument::Type(inner) if type_contains(inner, ident)),
+                ),
+                _ => false,
+            }
+    })
+}
+
+fn simple_name(path: &str) -> &str {
+    path.rsplit(['.', ':']).next().unwrap_or(path)
+}
+
+/// An enum's values, named as prost's `as_str_name` spells them, with the numbers the enum
+/// declares. An enum without `as_str_name` in the module is recorded without its values.
+fn render_enum(item_enum: &syn::ItemEnum, items: &[syn::Item], scope: &Scope, out: &mut String) {
+    out.push_str(&format!(
+        "enum {}\n",
+        scope.qualified(&item_enum.ident.to_string())
+    ));
+    let names = str_names(&item_enum.ident, items);
+    for variant in &item_enum.variants {
+        let (Some(name), Some((_, discriminant))) = (
+            names
+                .iter()
+                .find(|(ident, _)| *ident == variant.ident)
+                .map(|(_, name)| name),
+            &variant.discriminant,
+        ) else {
+            continue;
+        };
+        if let Some(number) = discriminant_value(discriminant) {
+            out.push_str(&format!("value {name} {number}\n"));
+        }
+    }
+}
+
+/// The `Self::Variant => "NAME"` arms of the `as_str_name` prost generates for an enum.
+fn str_names(enum_ident: &syn::Ident, items: &[syn::Item]) -> Vec<(syn::Ident, String)> {
+    let as_str_name = items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Impl(item_impl) if item_impl.trait_.is_none() => Some(item_impl),
+            _ => None,
+        })
+        .filter(|item_impl| {
+            matches!(&*item_impl.self_ty, syn::Type::Path(type_path)
+                if type_path.path.is_ident(enum_ident))
+        })
+        .flat_map(|item_impl| &item_impl.items)
+        .find_map(|item| match item {
+            syn::ImplItem::Fn(method) if method.sig.ident == "as_str_name" => Some(method),
+            _ => None,
+        });
+    let Some(method) = as_str_name else {
+        return Vec::new();
+    };
+    method
+        .block
+        .stmts
+        .iter()
+        .filter_map(|stmt| match stmt {
+            syn::Stmt::Expr(syn::Expr::Match(expr_match), _) => Some(expr_match),
+            _ => None,
+        })
+        .flat_map(|expr_match| &expr_match.arms)
+        .filter_map(|arm| {
+            let syn::Pat::Path(pat) = &arm.pat else {
+                return None;
+            };
+            let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(name),
+                ..
+            }) = &*arm.body
+            else {
+                return None;
+            };
+            Some((pat.path.segments.last()?.ident.clone(), name.value()))
+        })
+        .collect()
+}
+
+fn discriminant_value(expr: &syn::Expr) -> Option<i32> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(value),
+            ..
+        }) => value.base10_parse().ok(),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => discriminant_value(expr).map(|value: i32| -value),
+        _ => None,
+    }
+}
+
+/// The file an `include!` reads: a string literal, or a `concat!` of literals and `env!`s such
+/// as `concat!(env!("OUT_DIR"), "/service.rs")`. Relative paths resolve against `source_dir`.
+fn include_path(mac: &syn::Macro, source_dir: Option<&Path>) -> syn::Result<PathBuf> {
+    let expr: syn::Expr = mac.parse_body()?;
+    let path = PathBuf::from(eval_str(&expr).ok_or_else(|| {
+        syn::Error::new_spanned(
+            mac,
+            "protto_schema reads `include!` of a string literal or of `concat!` of literals and \
+            `env!`s",
+        )
+    })?);
+    match source_dir {
+        Some(dir) if path.is_relative() => Ok(dir.join(path)),
+        _ => Ok(path),
+    }
+}
+
+fn eval_str(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(value),
+            ..
+        }) => Some(value.value()),
+        syn::Expr::Macro(expr_macro) if expr_macro.mac.path.is_ident("concat") => expr_macro
+            .mac
+            .parse_body_with(Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated)
+            .ok()?
+            .iter()
+            .map(eval_str)
+            .collect(),
+        syn::Expr::Macro(expr_macro) if expr_macro.mac.path.is_ident("env") => {
+            let name: syn::LitStr = expr_macro.mac.parse_body().ok()?;
+            std::env::var(name.value()).ok()
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::proto_metadata::{self, SchemaKind, SchemaShape};
+
+    #[test]
+    fn test_schema_module_records_prost_field_shapes() {
+        let module: syn::ItemMod = syn::parse_quote! {
+            pub mod schema_proto {
+                #[derive(Clone, PartialEq, ::prost::Message)]
+                pub struct Album {
+                    #[prost(uint64, tag = "1")]
+                    pub id: u64,
+                    #[prost(string, optional, tag = "2")]
+                    pub subtitle: ::core::option::Option<::prost::alloc::string::String>,
+                    #[prost(string, repeated, tag = "3")]
+                    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+                    #[prost(map = "string, message(Track)", tag = "4")]
+                    pub tracks: ::std::collections::HashMap<::prost::alloc::string::String, Track>,
+                    #[prost(enumeration = "Genre", optional, tag = "5")]
+                    pub genre: ::core::option::Option<i32>,
+                    #[prost(message, optional, boxed, tag = "6")]
+                    pub sequel: ::core::option::Option<::prost::alloc::boxed::Box<Album>>,
+                    #[prost(string, tag = "7")]
+                    pub r#type: ::prost::alloc::string::String,
+                    #[prost(message, optional, tag = "8")]
+                    pub rating: ::core::option::Option<u32>,
+                    #[prost(oneof = "album::Release", tags = "9, 10")]
+                    pub release: ::core::option::Option<album::Release>,
+                }
+                pub mod album {
+                    #[derive(Clone, PartialEq, ::prost::Message)]
+                    pub struct Credit {
+                        #[prost(message, repeated, tag = "1")]
+                        pub artists: ::prost::alloc::vec::Vec<super::Track>,
+                    }
+                    #[derive(Clone, PartialEq, ::prost::Oneof)]
+                    pub enum Release {
+                        #[prost(string, tag = "9")]
+                        Digital(::prost::alloc::string::String),
+                    }
+                }
+                #[derive(Clone, PartialEq, ::prost::Message)]
+                pub struct Track {}
+                #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+                #[repr(i32)]
+                pub enum Genre {
+                    Unspecified = 0,
+                    Jazz = 1,
+                }
+                impl Genre {
+                    pub fn as_str_name(&self) -> &'static str {
+                        match self {
+                            Self::Unspecified => "GENRE_UNSPECIFIED",
+                            Self::Jazz => "GENRE_JAZZ",
+                        }
+                    }
+                }
+            }
+        };
+        let code = register_schema_module(module, None).unwrap().to_string();
+        assert!(code.starts_with("pub mod schema_proto"));
+
+        let field = |message, field| {
+            let schema = proto_metadata::lookup_field("schema_proto", message, field).unwrap();
+            (schema.shape, schema.kind, schema.type_name, schema.boxed)
+        };
+        use SchemaKind::*;
+        use SchemaShape::*;
+        assert_eq!(
+            field("Album", "id"),
+            (Required, Scalar, "uint64".into(), false)
+        );
+        assert_eq!(
+            field("Album", "subtitle"),
+            (Optional, Scalar, "string".into(), false)
+        );
+        assert_eq!(
+            field("Album", "tags"),
+            (Repeated, Scalar, "string".into(), false)
+        );
+        assert_eq!(
+            field("Album", "tracks"),
+            (Map, Message, "map<string,Track>".into(), false)
+        );
+        assert_eq!(
+            field("Album", "genre"),
+            (Optional, Enum, "Genre".into(), false)
+        );
+        assert_eq!(
+            field("Album", "sequel"),
+            (Optional, Message, "Album".into(), true)
+        );
+        assert_eq!(
+            field("Album", "rating"),
+            (
+                Optional,
+                Message,
+                "google.protobuf.UInt32Value".into(),
+                false
+            )
+        );
+        assert_eq!(
+            field("album::Credit", "artists"),
+            (Repeated, Message, "Track".into(), false)
+        );
+        assert_eq!(
+            proto_metadata::prost_field_ident("schema_proto", "Album", "type_").as_deref(),
+            Some("r#type")
+        );
+        assert!(proto_metadata::lookup_field("schema_proto", "Album", "release").is_none());
+        assert_eq!(
+            proto_metadata::lookup_enum_values("schema_proto", "Genre"),
+            Some((
+                "schema_proto.Genre".to_string(),
+                vec![
+                    ("GENRE_UNSPECIFIED".to_string(), 0),
+                    ("GENRE_JAZZ".to_string(), 1)
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_schema_module_reads_included_files() {
+        let dir = std::env::temp_dir().join(format!("protto_schema_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("billing.rs"),
+            "#[derive(Clone, PartialEq, ::prost::Message)]
+            pub struct Invoice {
+                #[prost(int64, repeated, tag = \"1\")]
+                pub line_cents: ::prost::alloc::vec::Vec<i64>,
+            }",
+        )
+        .unwrap();
+
+        let module: syn::ItemMod = syn::parse_quote! {
+            mod included_proto {
+                pub mod billing {
+                    include!(concat!("bill", "ing.rs"));
+                }
+            }
+        };
+        register_schema_module(module, Some(&dir)).unwrap();
+        let lines =
+            proto_metadata::lookup_field("included_proto::billing", "Invoice", "line_cents")
+                .unwrap();
+        assert_eq!(lines.shape, SchemaShape::Repeated);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let module: syn::ItemMod = syn::parse_quote! {
+            mod missing_proto {
+                include!("missing.rs");
+            }
+        };
+        let err = register_schema_module(module, Some(&dir)).unwrap_err();
+        assert!(err.to_string().contains("cannot read"));
+
+        let module: syn::ItemMod = syn::parse_quote!(
+            mod empty_proto {
+                pub struct Plain {}
+            }
+        );
+        let err = register_schema_module(module, None).unwrap_err();
+        assert!(err.to_string().contains("declares no prost messages"));
+    }
+}
@@ -0,0 +1,113 @@
+//! Compile-time statistics on the conversion strategies the derive picks, turned on with
+//! `PROTTO_STATS=1`.
+//!
+//! Every struct derive adds its fields to a tally kept for the crate being compiled, and rewrites
+//! `$OUT_DIR/protto_stats.json` with the totals so far: the number of derived structs and fields,
+//! and the fields per strategy category (`direct`, `collection`, ...) and per strategy (the
+//! category's variants, e.g. "unwrap Optional with error handling"). Comparing the files before
+//! and after a refactor shows fields that fell back to a different heuristic. Crates without a
+//! build script have no `OUT_DIR` and write nothing; write failures are ignored so they never
+//! break the derive.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+pub const STATS_ENV: &str = "PROTTO_STATS";
+const STATS_FILE_NAME: &str = "protto_stats.json";
+
+/// The strategy category and description of each field, per derived struct. Keyed by struct so a
+/// struct expanded again within the same compilation is not counted twice.
+static TALLY: Mutex<BTreeMap<String, Vec<(&'static str, &'static str)>>> =
+    Mutex::new(BTreeMap::new());
+
+/// Whether `PROTTO_STATS` asks for the strategy statistics.
+pub fn stats_enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        std::env::var(STATS_ENV)
+            .map(|value| !matches!(value.trim(), "" | "0" | "false"))
+            .unwrap_or(false)
+    })
+}
+
+/// Adds a struct's fields to the tally and rewrites the statistics file.
+pub fn record(struct_key: String, fields: Vec<(&'static str, &'static str)>) {
+    let Some(out_dir) = std::env::var_os("OUT_DIR") else {
+        return;
+    };
+    let Ok(mut tally) = TALLY.lock() else {
+        return;
+    };
+    tally.insert(struct_key, fields);
+    let _ = std::fs::write(PathBuf::from(out_dir).join(STATS_FILE_NAME), render(&tally));
+}
+
+fn render(tally: &BTreeMap<String, Vec<(&'static str, &'static str)>>) -> String {
+    let mut categories = BTreeMap::new();
+    let mut strategies = BTreeMap::new();
+    for (category, strategy) in tally.values().flatten() {
+        *categories.entry(*category).or_insert(0usize) += 1;
+        *strategies.entry(*strategy).or_insert(0usize) += 1;
+    }
+    let fields: usize = categories.values().sum();
+
+    format!(
+        "{{\n  \"structs\": {},\n  \"fields\": {fields},\n  \"categories\": {},\n  \"strategies\": {}\n}}\n",
+        tally.len(),
+        render_counts(&categories),
+        render_counts(&strategies)
+    )
+}
+
+fn render_counts(counts: &BTreeMap<&str, usize>) -> String {
+    if counts.is_empty() {
+        return "{}".to_string();
+    }
+    let entries: Vec<String> = counts
+        .iter()
+        .map(|(name, count)| format!("    \"{}\": {count}", escape(name)))
+        .collect();
+    format!("{{\n{}\n  }}", entries.join(",\n"))
+}
+
+fn escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counts_fields_per_strategy() {
+        let mut tally = BTreeMap::new();
+        tally.insert(
+            "Track proto::Track".to_string(),
+            vec![
+                ("direct", "direct assignment (no conversion)"),
+                ("option", "unwrap Optional with error handling"),
+            ],
+        );
+        tally.insert(
+            "Album proto::Album".to_string(),
+            vec![
+                ("direct", "direct assignment (no conversion)"),
+                ("collection", "collect vector with conversion"),
+            ],
+        );
+
+        assert_eq!(
+            render(&tally),
+            "{\n  \"structs\": 2,\n  \"fields\": 4,\n  \"categories\": {\n    \"collection\": 1,\n    \
+             \"direct\": 2,\n    \"option\": 1\n  },\n  \"strategies\": {\n    \
+             \"collect vector with conversion\": 1,\n    \
+             \"direct assignment (no conversion)\": 2,\n    \
+             \"unwrap Optional with error handling\": 1\n  }\n}\n"
+        );
+        assert_eq!(
+            render(&BTreeMap::new()),
+            "{\n  \"structs\": 0,\n  \"fields\": 0,\n  \"categories\": {},\n  \"strategies\": {}\n}\n"
+        );
+    }
+}
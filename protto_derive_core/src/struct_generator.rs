@@ -0,0 +1,3224 @@
+use crate::analysis::attribute_parser::ProtoOnlyField;
+use crate::analysis::macro_input::ProtoTarget;
+use crate::analysis::{attribute_parser, error_analysis, type_analysis};
+use crate::debug::CallStackDebug;
+use crate::field::{self, FieldProcessingContext};
+use quote::{quote, quote_spanned};
+use std::collections::HashSet;
+use syn::spanned::Spanned;
+
+#[allow(unused)]
+#[derive(Clone, Copy)]
+pub struct StructImplConfig<'a> {
+    pub name: &'a syn::Ident,
+    pub fields: &'a syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    pub proto_module: &'a str,
+    pub proto_name: &'a str,
+    pub proto_path: &'a syn::Path,
+    pub struct_level_error_type: &'a Option<syn::Type>,
+    pub struct_level_error_fn: &'a Option<String>,
+    pub struct_level_error_fn_with_value: bool,
+    pub proto_ignored_fields: &'a HashSet<String>,
+    pub proto_only: &'a [ProtoOnlyField],
+    pub generics: &'a syn::Generics,
+    pub resolver_type: &'a Option<syn::Type>,
+    pub error_mod: &'a Option<syn::Ident>,
+    pub static_errors: bool,
+    pub presence: bool,
+    pub field_paths: bool,
+    pub partial: bool,
+    pub proto_parts: bool,
+    pub view: bool,
+    pub validate: bool,
+    pub debug_roundtrip: bool,
+    pub estimated_size: bool,
+    pub pure_custom_fns: bool,
+    pub both_conversions: bool,
+    pub transparent: bool,
+    pub any_type_url: &'a Option<String>,
+    /// Generate only the From/Into conversions, for a further proto message named by
+    /// `also_proto`; the error type and every other item are generated once, for the primary one
+    pub conversions_only: bool,
+    pub vis: &'a syn::Visibility,
+    pub error_vis: &'a syn::Visibility,
+}
+
+/// Struct-level `also_proto`: everything for the primary proto message, then the From/Into
+/// conversions for each further message, built from the same field attributes
+pub fn generate_struct_implementations_for_targets(
+    config: StructImplConfig,
+    also_proto: &[ProtoTarget],
+) -> proc_macro2::TokenStream {
+    let primary = generate_struct_implementations(config);
+    let further = also_proto.iter().map(|target| {
+        generate_struct_implementations(StructImplConfig {
+            proto_module: &target.module,
+            proto_name: &target.name,
+            proto_path: &target.path,
+            conversions_only: true,
+            ..config
+        })
+    });
+    quote! {
+        #primary
+        #(#further)*
+    }
+}
+
+/// Struct-level `sources`: the struct's fields are split across several proto messages delivered
+/// together, so it converts from and into the tuple of those messages. Each field converts as it
+/// would for its own message, with `proto_struct` bound to the tuple element its `source` names.
+pub fn generate_sources_implementations(
+    config: StructImplConfig,
+    sources: &[(syn::Ident, ProtoTarget)],
+) -> proc_macro2::TokenStream {
+    let _trace = CallStackDebug::with_context(
+        "struct_generator",
+        "generate_sources_implementations",
+        config.name,
+        "",
+        &[(
+            "sources",
+            &sources
+                .iter()
+                .map(|(name, target)| format!("{name} = {}::{}", target.module, target.name))
+                .collect::<Vec<_>>()
+                .join(","),
+        )],
+    );
+
+    let struct_name = config.name;
+    let (impl_generics, ty_generics, where_clause) = config.generics.split_for_impl();
+
+    if config.generics.lifetimes().next().is_some() {
+        let error_msg = format!(
+            "`{struct_name}` borrows its data, which sources does not support; the proto -> rust \
+            conversion of the tuple needs owned fields"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    let (conversion_error_def, error_conversions, needs_try_from, needs_try_into) =
+        generate_error_definitions_if_needed(
+            struct_name,
+            config.fields,
+            config.struct_level_error_type,
+            config.error_mod,
+            config.error_vis,
+            config.static_errors,
+            false,
+        );
+    let actual_error_type = get_actual_error_type(
+        needs_try_from || needs_try_into,
+        config.struct_level_error_type,
+        &default_error_path(struct_name, config.error_mod),
+    );
+
+    // Per source: the field locals converted from its message, borrowing conversions first, and
+    // the proto field initializers written into it
+    let mut source_bindings = vec![(Vec::new(), Vec::new()); sources.len()];
+    let mut source_fields = vec![Vec::new(); sources.len()];
+    let mut mapped_fields = Vec::new();
+    let mut ignored_fields = Vec::new();
+    let mut destructured_fields = Vec::new();
+    let mut conversion_errors = Vec::new();
+
+    for field in config.fields {
+        let field_name = field.ident.as_ref().unwrap();
+        let source = attribute_parser::ProtoFieldMeta::from_field(field)
+            .expect("field attributes are checked by validate_attributes")
+            .source;
+        let index = source
+            .and_then(|source| sources.iter().position(|(name, _)| *name == source))
+            .unwrap_or_default();
+        let target = &sources[index].1;
+
+        let ctx = FieldProcessingContext::new(
+            struct_name,
+            config.generics,
+            field,
+            config.struct_level_error_type,
+            config.struct_level_error_fn,
+            config.struct_level_error_fn_with_value,
+            config.error_mod,
+            config.static_errors,
+            &target.module,
+            &target.name,
+        );
+
+        let (proto_to_rust, rust_to_proto) =
+            match field::generate_bidirectional_field_conversion(field, &ctx) {
+                Ok(conversions) => conversions,
+                Err(error_msg) => {
+                    conversion_errors.push(format!("Field '{field_name}': {error_msg}"));
+                    continue;
+                }
+            };
+
+        if attribute_parser::has_proto_ignore(field) {
+            ignored_fields.push(proto_to_rust);
+            destructured_fields.push(quote! { #field_name: _ });
+            continue;
+        }
+
+        let field_value = match syn::parse2::<syn::FieldValue>(proto_to_rust) {
+            Ok(field_value) => field_value,
+            Err(error) => return error.to_compile_error(),
+        };
+        let value = &field_value.expr;
+        let binding = quote! { let #field_name = #value; };
+        let (borrowing, consuming) = &mut source_bindings[index];
+        if ctx.protto_meta.from_proto_by_ref {
+            borrowing.push(binding);
+        } else {
+            consuming.push(binding);
+        }
+        source_fields[index].push(rust_to_proto);
+        mapped_fields.push(field_name);
+        destructured_fields.push(quote! { #field_name });
+    }
+
+    if !conversion_errors.is_empty() {
+        let combined_error = conversion_errors.join("\n");
+        return quote! { compile_error!(#combined_error); };
+    }
+
+    let proto_types: Vec<syn::Path> = sources
+        .iter()
+        .map(|(_, target)| target.path.clone())
+        .collect();
+    let tuple_type = quote! { (#(#proto_types,)*) };
+    let indices = (0..sources.len()).map(syn::Index::from);
+    let bind_sources =
+        source_bindings
+            .iter()
+            .zip(indices)
+            .map(|((borrowing, consuming), index)| {
+                quote! {
+                    let proto_struct = protos.#index;
+                    #(#borrowing)*
+                    #(#consuming)*
+                }
+            });
+    let rust_struct = quote! {
+        Self {
+            #(#mapped_fields,)*
+            #(#ignored_fields,)*
+        }
+    };
+
+    let proto_rest = config
+        .partial
+        .then(|| quote! { ..::core::default::Default::default() });
+    let proto_messages = proto_types
+        .iter()
+        .zip(&source_fields)
+        .map(|(proto_type, fields)| {
+            quote! {
+                #proto_type {
+                    #(#fields,)*
+                    #proto_rest
+                }
+            }
+        });
+    let protos = quote! { (#(#proto_messages,)*) };
+
+    let from_trait_impl = if needs_try_from {
+        quote! {
+            impl #impl_generics TryFrom<#tuple_type> for #struct_name #ty_generics #where_clause {
+                type Error = #actual_error_type;
+
+                fn try_from(protos: #tuple_type) -> Result<Self, Self::Error> {
+                    #(#bind_sources)*
+                    Ok(#rust_struct)
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics From<#tuple_type> for #struct_name #ty_generics #where_clause {
+                fn from(protos: #tuple_type) -> Self {
+                    #(#bind_sources)*
+                    #rust_struct
+                }
+            }
+        }
+    };
+
+    let into_trait_impl = if needs_try_into {
+        quote! {
+            impl #impl_generics TryFrom<#struct_name #ty_generics> for #tuple_type #where_clause {
+                type Error = #actual_error_type;
+
+                fn try_from(my_struct: #struct_name #ty_generics) -> Result<Self, Self::Error> {
+                    let #struct_name { #(#destructured_fields,)* } = my_struct;
+                    Ok(#protos)
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics From<#struct_name #ty_generics> for #tuple_type #where_clause {
+                fn from(my_struct: #struct_name #ty_generics) -> Self {
+                    let #struct_name { #(#destructured_fields,)* } = my_struct;
+                    #protos
+                }
+            }
+        }
+    };
+
+    quote! {
+        #conversion_error_def
+        #error_conversions
+        #from_trait_impl
+        #into_trait_impl
+    }
+}
+
+pub fn generate_struct_implementations(config: StructImplConfig) -> proc_macro2::TokenStream {
+    let proto_path = &config.proto_path;
+
+    let _trace = CallStackDebug::with_context(
+        "struct_generator",
+        "generate_struct_implementations",
+        config.name,
+        "",
+        &[
+            ("proto_module", config.proto_module),
+            ("proto_name", config.proto_name),
+            ("proto_path", &quote! { #proto_path }.to_string()),
+            (
+                "struct_level_error_type",
+                &config
+                    .struct_level_error_type
+                    .as_ref()
+                    .map(|et| quote! { #et }.to_string())
+                    .unwrap_or_else(|| "none".to_string()),
+            ),
+            (
+                "struct_level_error_fn",
+                &config
+                    .struct_level_error_fn
+                    .clone()
+                    .unwrap_or_else(|| "none".to_string()),
+            ),
+            (
+                "proto_ignored_fields",
+                &config
+                    .proto_ignored_fields
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+        ],
+    );
+
+    let struct_name = config.name;
+    let fields = config.fields;
+    let (impl_generics, ty_generics, where_clause) = config.generics.split_for_impl();
+
+    if config.transparent && config.proto_parts {
+        let error_msg = format!(
+            "`{struct_name}` is transparent, so it has no fields for proto_parts to hold; \
+            remove proto_parts"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    if config.transparent {
+        let transparent_impls = generate_transparent_implementations(&config);
+        let any_impl = config.any_type_url.as_deref().map(|type_url| {
+            generate_to_proto_any(struct_name, config.generics, config.proto_path, type_url)
+        });
+        return quote! {
+            #transparent_impls
+            #any_impl
+        };
+    }
+
+    // Structs borrowing their data can only be converted into proto; the proto message owns its
+    // data, so there is nothing for the borrowed fields to point at in the other direction.
+    let is_borrowed = config.generics.lifetimes().next().is_some();
+    if is_borrowed {
+        let from_proto_only_fields = find_from_proto_only_fields(fields);
+        if !from_proto_only_fields.is_empty() {
+            let error_msg = format!(
+                "`{struct_name}` borrows its data, so only the rust -> proto conversion is generated. \
+                From-proto conversion is only possible into owned types; remove the from-proto \
+                attributes on: {}",
+                from_proto_only_fields.join(", ")
+            );
+            return quote! { compile_error!(#error_msg); };
+        }
+    }
+
+    // Fields resolved through async lookups can't be converted synchronously; the proto -> rust
+    // conversion becomes an async `from_proto_async` taking the resolver instead.
+    let resolved_fields = find_resolved_fields(fields);
+    let is_resolved = !resolved_fields.is_empty();
+    if is_resolved && (config.resolver_type.is_none() || config.struct_level_error_type.is_none()) {
+        let error_msg = format!(
+            "`{struct_name}` resolves fields with resolve_with ({}), which requires a struct-level \
+            resolver type and an error_type the resolver errors convert into, e.g. \
+            #[protto(resolver = MyResolver, error_type = MyError, error_fn = \"MyError::missing_field\")]",
+            resolved_fields.join(", ")
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    if config.both_conversions && is_resolved {
+        let error_msg = format!(
+            "`{struct_name}` uses conversions = \"both\", which pairs the synchronous TryFrom \
+            conversions with panicking ones; structs with resolve_with fields convert from proto \
+            asynchronously"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    if config.presence && (is_borrowed || is_resolved) {
+        let error_msg = format!(
+            "`{struct_name}` enables presence, which builds on the synchronous proto -> rust \
+            conversion; borrowed structs and structs with resolve_with fields do not have one"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    if config.debug_roundtrip && (is_borrowed || is_resolved) {
+        let error_msg = format!(
+            "`{struct_name}` enables debug_roundtrip, which checks the synchronous proto -> rust \
+            conversion; borrowed structs and structs with resolve_with fields do not have one"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    if config.proto_parts && (is_borrowed || is_resolved) {
+        let error_msg = format!(
+            "`{struct_name}` enables proto_parts, which builds on the synchronous proto -> rust \
+            conversion; borrowed structs and structs with resolve_with fields do not have one"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    if config.view && (!config.generics.params.is_empty() || is_resolved) {
+        let error_msg = format!(
+            "`{struct_name}` enables view, whose `to_owned` builds on the synchronous proto -> \
+            rust conversion; generic structs and structs with resolve_with fields are not supported"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    if config.view
+        && let Some(field) = fields
+            .iter()
+            .find(|field| field.ident.as_ref().is_some_and(|ident| ident == "proto"))
+    {
+        return syn::Error::new_spanned(
+            field,
+            "view keeps the borrowed proto message in a field named `proto`; rename this field",
+        )
+        .to_compile_error();
+    }
+
+    if config.conversions_only && is_resolved {
+        let error_msg = format!(
+            "`{struct_name}` resolves fields with resolve_with, so its proto -> rust conversion is \
+            the inherent `from_proto_async`, which can only take one proto message; remove \
+            also_proto"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    if config.estimated_size && !cfg!(feature = "size-estimate") {
+        let error_msg = format!(
+            "`{struct_name}` enables estimated_size, which requires the `size-estimate` feature \
+            of protto"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    if config.validate && !cfg!(feature = "validate") {
+        let error_msg = format!(
+            "`{struct_name}` enables validate, which requires the `validate` feature of protto"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    if config.validate && (is_borrowed || config.static_errors) {
+        let error_msg = format!(
+            "`{struct_name}` enables validate, which checks the proto message before the proto -> \
+            rust conversion and carries the validator's error; borrowed structs have no such \
+            conversion, and static_errors cannot hold the error"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    let (conversion_error_def, error_conversions, needs_try_from, needs_try_into) =
+        generate_error_definitions_if_needed(
+            struct_name,
+            fields,
+            config.struct_level_error_type,
+            config.error_mod,
+            config.error_vis,
+            config.static_errors,
+            config.validate,
+        );
+    let needs_try_from = needs_try_from && !is_borrowed;
+
+    if config.any_type_url.is_some() && needs_try_into {
+        let error_msg = format!(
+            "`{struct_name}` sets any_type_url, which packs the proto message from an infallible \
+            rust -> proto conversion; none_as = \"error\" makes it TryFrom"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    let actual_error_type = get_actual_error_type(
+        needs_try_from || needs_try_into || is_resolved,
+        config.struct_level_error_type,
+        &default_error_path(struct_name, config.error_mod),
+    );
+
+    let proto_ignored_fields = config.proto_ignored_fields;
+    if let Some(field) = config.proto_only.iter().find(|field| {
+        proto_ignored_fields.iter().any(|ignored| {
+            crate::utils::field_ident(ignored) == crate::utils::field_ident(&field.proto_field)
+        })
+    }) {
+        let error_msg = format!(
+            "`{}` is listed in both ignore and proto_only; a proto_only field is computed, so \
+            remove it from ignore",
+            field.proto_field
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    // Generate bidirectional conversions in single pass
+    let mut field_conversions = Vec::new();
+    let mut conversion_errors = Vec::new();
+    let mut mapped_proto_fields = Vec::new();
+    let mut proto_field_owners = Vec::new();
+    let mut field_paths = Vec::new();
+    let mut proto_borrowing_fields = HashSet::new();
+    let mut presence_fields = Vec::new();
+    let mut size_terms = Vec::new();
+    let mut custom_fn_signatures = Vec::new();
+    let mut error_fn_signatures = Vec::new();
+    let mut checked_error_fns = HashSet::new();
+    let mut message_field_checks = Vec::new();
+    let mut manual_fields = Vec::new();
+    let mut preserved_fields = Vec::new();
+    let mut presence_of_fields = Vec::new();
+    let mut field_dependencies = Vec::new();
+    let mut parts_fields = Vec::new();
+    let mut view_fields = Vec::new();
+    #[cfg(feature = "meta-file")]
+    let mut coverage_fields = Vec::new();
+    let mut stats_fields = Vec::new();
+
+    for field in fields {
+        let field_name = field.ident.as_ref().unwrap();
+
+        let _trace = CallStackDebug::with_context(
+            "struct_impl",
+            "generate_struct_implementations",
+            config.name,
+            field_name,
+            &[],
+        );
+
+        let ctx = FieldProcessingContext::new(
+            struct_name,
+            config.generics,
+            field,
+            config.struct_level_error_type,
+            config.struct_level_error_fn,
+            config.struct_level_error_fn_with_value,
+            config.error_mod,
+            config.static_errors,
+            config.proto_module,
+            config.proto_name,
+        );
+
+        match field::generate_bidirectional_field_conversion(field, &ctx) {
+            Ok((proto_to_rust, rust_to_proto)) => {
+                let proto_to_rust = match &ctx.protto_meta.resolve_with {
+                    Some(resolve_fn) => generate_resolved_field(&ctx, resolve_fn),
+                    None if ctx.protto_meta.preserve_unknown => {
+                        preserved_fields.push(field_name);
+                        quote! {
+                            #field_name: ::core::option::Option::Some(
+                                ::core::clone::Clone::clone(&proto_struct)
+                            )
+                        }
+                    }
+                    None => proto_to_rust,
+                };
+                field_conversions.push((field_name, proto_to_rust, rust_to_proto));
+                if ctx.protto_meta.presence_of.is_some() {
+                    // Only reads the proto field, so it shares it with a field converting it
+                    proto_borrowing_fields.insert(field_name);
+                    presence_of_fields
+                        .push((field_name, crate::utils::ident_name(&ctx.proto_field_ident)));
+                }
+                if !attribute_parser::has_proto_ignore(field) {
+                    let proto_fields = mapped_proto_field_idents(&ctx);
+                    if ctx.protto_meta.presence_of.is_none() {
+                        proto_field_owners.extend(
+                            proto_fields
+                                .iter()
+                                .map(|proto_field| (crate::utils::ident_name(proto_field), field)),
+                        );
+                    }
+                    mapped_proto_fields.extend(proto_fields);
+                    field_paths.push((field_name, proto_field_paths(&ctx)));
+                }
+                if ctx.protto_meta.from_proto_by_ref {
+                    proto_borrowing_fields.insert(field_name);
+                }
+                if ctx.protto_meta.manual {
+                    manual_fields.push((field_name, &field.ty));
+                }
+                if !ctx.protto_meta.depends_on.is_empty() {
+                    field_dependencies.push((field_name, ctx.protto_meta.depends_on.clone()));
+                }
+                if config.view
+                    && !attribute_parser::has_proto_ignore(field)
+                    && let Some((view_type, view_value)) = view_field(field, &ctx)
+                {
+                    view_fields.push((field, view_type, view_value));
+                }
+                if config.proto_parts {
+                    parts_fields.push((
+                        field_name,
+                        &field.ty,
+                        attribute_parser::has_proto_ignore(field),
+                    ));
+                }
+                if config.presence
+                    && !attribute_parser::has_proto_ignore(field)
+                    && ctx.protto_meta.group.is_none()
+                    && field::is_proto_field_optional(field, &ctx)
+                {
+                    presence_fields.push((field_name, ctx.proto_field_ident.clone()));
+                }
+                if config.estimated_size && !attribute_parser::has_proto_ignore(field) {
+                    size_terms.push(estimated_field_size(&ctx));
+                }
+                if config.pure_custom_fns
+                    && !attribute_parser::has_proto_ignore(field)
+                    && !attribute_parser::has_transparent_attr(field)
+                {
+                    custom_fn_signatures.extend(generate_custom_fn_signature_checks(&ctx));
+                }
+                if (needs_try_from || needs_try_into)
+                    && !attribute_parser::has_proto_ignore(field)
+                    && error_analysis::reports_field_errors(field, &ctx.protto_meta)
+                    && let Some(error_fn) = ctx.get_effective_field_error_fn()
+                    && checked_error_fns.insert(quote! { #error_fn }.to_string())
+                {
+                    error_fn_signatures.push(generate_error_fn_signature_check(
+                        &ctx,
+                        &error_fn,
+                        &actual_error_type,
+                    ));
+                }
+                if !attribute_parser::has_proto_ignore(field)
+                    && field::unwraps_unrecognized_message(field, &ctx)
+                {
+                    message_field_checks.push(generate_message_field_check(&ctx, field));
+                }
+                if crate::stats::stats_enabled() {
+                    stats_fields.push(field::conversion_labels(field, &ctx));
+                }
+                #[cfg(feature = "meta-file")]
+                coverage_fields.push(crate::coverage::FieldRecord {
+                    rust_field: crate::utils::ident_name(field_name),
+                    strategy: field::conversion_labels(field, &ctx).0,
+                    proto_fields: if attribute_parser::has_proto_ignore(field) {
+                        Vec::new()
+                    } else {
+                        mapped_proto_field_idents(&ctx)
+                            .iter()
+                            .map(crate::utils::ident_name)
+                            .collect()
+                    },
+                });
+            }
+            Err(error_msg) => {
+                conversion_errors.push((field_name, error_msg));
+            }
+        }
+    }
+
+    // Handle any conversion errors
+    if !conversion_errors.is_empty() {
+        let error_msgs: Vec<String> = conversion_errors
+            .iter()
+            .map(|(field_name, error)| format!("Field '{}': {}", field_name, error))
+            .collect();
+        let combined_error = error_msgs.join("\n");
+        return quote! { compile_error!(#combined_error); };
+    }
+
+    if preserved_fields.len() > 1 {
+        let error_msg = format!(
+            "`{struct_name}` preserves its proto message in more than one field ({}); mark one \
+            field preserve_unknown",
+            preserved_fields
+                .iter()
+                .map(|field_name| crate::utils::ident_name(field_name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+    if config.conversions_only && !preserved_fields.is_empty() {
+        let error_msg = format!(
+            "`{struct_name}` preserves its primary proto message with preserve_unknown, which \
+            cannot be the base of the further messages named by also_proto; remove also_proto"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+    let preserved_field = preserved_fields.first();
+
+    if !config.conversions_only
+        && let Err(error) = check_proto_field_conflicts(
+            &proto_field_owners,
+            proto_ignored_fields,
+            config.proto_only,
+        )
+    {
+        return error.to_compile_error();
+    }
+
+    // A presence_of field leaves its proto field unset, unless something else writes it
+    for (presence_field, proto_field) in &presence_of_fields {
+        let unprefixed = |name: &str| name.trim_start_matches("r#").to_string();
+        let written_elsewhere = preserved_field.is_some()
+            || proto_field_owners
+                .iter()
+                .any(|(owned, _)| unprefixed(owned) == unprefixed(proto_field))
+            || proto_ignored_fields
+                .iter()
+                .any(|ignored| unprefixed(ignored) == unprefixed(proto_field))
+            || config
+                .proto_only
+                .iter()
+                .any(|only| unprefixed(&only.proto_field) == unprefixed(proto_field));
+        if written_elsewhere
+            && let Some((_, _, rust_to_proto)) = field_conversions
+                .iter_mut()
+                .find(|(field_name, _, _)| field_name == presence_field)
+        {
+            *rust_to_proto = quote! {};
+        }
+    }
+
+    #[cfg(feature = "meta-file")]
+    if !config.conversions_only {
+        crate::coverage::record(&crate::coverage::StructRecord {
+            rust_name: struct_name.to_string(),
+            proto_module: config.proto_module.to_string(),
+            proto_name: config.proto_name.to_string(),
+            fields: coverage_fields,
+        });
+    }
+
+    let stats_dependency = if crate::stats::stats_enabled() && !config.conversions_only {
+        crate::stats::record(
+            format!(
+                "{struct_name} {}::{}",
+                config.proto_module, config.proto_name
+            ),
+            stats_fields,
+        );
+        crate::report::env_dependency(crate::stats::STATS_ENV)
+    } else {
+        quote! {}
+    };
+
+    let clone_report = if crate::report::clones_enabled() && !config.conversions_only {
+        let warnings =
+            field_conversions
+                .iter()
+                .map(|(field_name, proto_to_rust, rust_to_proto)| {
+                    crate::report::clone_warning(
+                        struct_name,
+                        field_name,
+                        proto_to_rust,
+                        rust_to_proto,
+                    )
+                });
+        let env_dependency = crate::report::env_dependency(crate::report::REPORT_ENV);
+        quote! {
+            #env_dependency
+            #(#warnings)*
+        }
+    } else {
+        quote! {}
+    };
+
+    let (bind_dependencies, dependency_bound) =
+        match generate_dependency_bindings(struct_name, &field_conversions, &field_dependencies) {
+            Ok(bindings) => bindings,
+            Err(err) => return err.to_compile_error(),
+        };
+
+    // Generate From and Into implementations. Fields converted from a borrowed proto field are
+    // initialized first, before any other field moves out of the proto struct, so sharing a proto
+    // field between a borrowing and a consuming conversion never requires a clone.
+    let (borrowing_conversions, consuming_conversions): (Vec<_>, Vec<_>) = field_conversions
+        .iter()
+        .partition(|(field_name, _, _)| proto_borrowing_fields.contains(field_name));
+    let proto_to_rust_fields: Vec<_> = borrowing_conversions
+        .into_iter()
+        .chain(consuming_conversions)
+        .map(|(field_name, proto_to_rust, _)| {
+            if dependency_bound.contains(field_name) || preserved_field == Some(field_name) {
+                quote! { #field_name }
+            } else {
+                proto_to_rust.clone()
+            }
+        })
+        .filter(|ts| !ts.is_empty())
+        .collect();
+    let rust_to_proto_fields: Vec<_> = field_conversions
+        .iter()
+        .map(|(_, _, rust_to_proto)| rust_to_proto)
+        .filter(|ts| !ts.is_empty())
+        .collect();
+
+    // Destructure every rust field exhaustively, so a field the conversion does not account for
+    // is a compile error rather than silently left out of the proto message
+    let destructured_fields: Vec<_> = field_conversions
+        .iter()
+        .map(|(field_name, _, rust_to_proto)| {
+            let presence_of = presence_of_fields
+                .iter()
+                .any(|(presence_field, _)| presence_field == field_name);
+            if (rust_to_proto.is_empty() || presence_of) && preserved_field != Some(field_name) {
+                quote! { #field_name: _ }
+            } else {
+                quote! { #field_name }
+            }
+        })
+        .collect();
+
+    // A preserved message is the base of the rust -> proto conversion: the proto fields the struct
+    // does not map, including struct-level `ignore` ones, keep the values it was converted from
+    let proto_ignore_defaults = if preserved_field.is_some() {
+        Vec::new()
+    } else {
+        generate_proto_ignore_defaults(proto_ignored_fields)
+    };
+    let (proto_only_bindings, proto_only_values) =
+        generate_proto_only_values(struct_name, config.proto_only, &quote! { self });
+    let (try_proto_only_bindings, _) =
+        generate_proto_only_values(struct_name, config.proto_only, &quote! { my_struct });
+    if config.partial {
+        _trace.decision(
+            "partial_struct",
+            "unmapped proto fields -> ..Default::default()",
+        );
+    }
+    let proto_rest = match preserved_field {
+        Some(field_name) => {
+            _trace.decision(
+                "preserve_unknown",
+                "unmapped proto fields -> ..preserved message",
+            );
+            Some(quote! { ..::core::option::Option::unwrap_or_default(#field_name) })
+        }
+        None => config
+            .partial
+            .then(|| quote! { ..::core::default::Default::default() }),
+    };
+
+    let proto_type_path = format!("{}::{}", config.proto_module, config.proto_name);
+    let proto_type: syn::Path = syn::parse_str(&proto_type_path).unwrap();
+
+    let (bind_manual_parts, proto_message) = if manual_fields.is_empty() {
+        (
+            quote! {},
+            quote! {
+                #proto_type {
+                    #(#rust_to_proto_fields,)*
+                    #(#proto_ignore_defaults,)*
+                    #(#proto_only_values,)*
+                    #proto_rest
+                }
+            },
+        )
+    } else {
+        let (bind_manual_parts, write_manual_parts) =
+            generate_manual_parts(struct_name, &ty_generics, &proto_type, &manual_fields);
+        (
+            bind_manual_parts,
+            quote! {
+                {
+                    let mut proto_struct = #proto_type {
+                        #(#rust_to_proto_fields,)*
+                        #(#proto_ignore_defaults,)*
+                        #(#proto_only_values,)*
+                        #proto_rest
+                    };
+                    #write_manual_parts
+                    proto_struct
+                }
+            },
+        )
+    };
+
+    // `depends_on` fields and the fields they depend on are converted into locals after the
+    // manual parts, in dependency order
+    let bind_preserved = preserved_field.map(|field_name| {
+        quote! {
+            let #field_name = ::core::option::Option::Some(::core::clone::Clone::clone(&proto_struct));
+        }
+    });
+    let bind_fields = quote! {
+        #bind_preserved
+        #bind_manual_parts
+        #bind_dependencies
+    };
+
+    let validate_proto = config.validate.then(|| {
+        quote! { ::protto::validate::ValidateProto::validate_proto(&proto_struct)?; }
+    });
+
+    let from_trait_impl = if is_borrowed {
+        _trace.decision(
+            "borrowed_struct",
+            "lifetime parameters -> rust -> proto only",
+        );
+        quote! {}
+    } else if let Some(resolver_type) = config.resolver_type.as_ref().filter(|_| is_resolved) {
+        _trace.decision(
+            "resolved_struct",
+            "resolve_with fields -> async from_proto_async",
+        );
+        quote! {
+            impl #impl_generics #struct_name #ty_generics #where_clause {
+                pub async fn from_proto_async(
+                    proto_struct: #proto_type,
+                    resolver: &#resolver_type,
+                ) -> Result<Self, #actual_error_type> {
+                    #validate_proto
+                    #bind_fields
+                    Ok(Self {
+                        #(#proto_to_rust_fields,)*
+                    })
+                }
+            }
+        }
+    } else if config.debug_roundtrip {
+        let (save_original, check_round_trip) =
+            generate_round_trip_check(struct_name, &proto_type, needs_try_into);
+        if needs_try_from {
+            quote! {
+                impl #impl_generics TryFrom<#proto_type> for #struct_name #ty_generics #where_clause {
+                    type Error = #actual_error_type;
+
+                    fn try_from(proto_struct: #proto_type) -> Result<Self, Self::Error> {
+                        #validate_proto
+                        #save_original
+                        #bind_fields
+                        let rust_struct = Self {
+                            #(#proto_to_rust_fields,)*
+                        };
+                        #check_round_trip
+                        Ok(rust_struct)
+                    }
+                }
+            }
+        } else {
+            quote! {
+                impl #impl_generics From<#proto_type> for #struct_name #ty_generics #where_clause {
+                    fn from(proto_struct: #proto_type) -> Self {
+                        #save_original
+                        #bind_fields
+                        let rust_struct = Self {
+                            #(#proto_to_rust_fields,)*
+                        };
+                        #check_round_trip
+                        rust_struct
+                    }
+                }
+            }
+        }
+    } else if needs_try_from {
+        quote! {
+            impl #impl_generics TryFrom<#proto_type> for #struct_name #ty_generics #where_clause {
+                type Error = #actual_error_type;
+
+                fn try_from(proto_struct: #proto_type) -> Result<Self, Self::Error> {
+                    #validate_proto
+                    #bind_fields
+                    Ok(Self {
+                        #(#proto_to_rust_fields,)*
+                    })
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics From<#proto_type> for #struct_name #ty_generics #where_clause {
+                fn from(proto_struct: #proto_type) -> Self {
+                    #bind_fields
+                    Self {
+                        #(#proto_to_rust_fields,)*
+                    }
+                }
+            }
+        }
+    };
+
+    let into_trait_impl = if needs_try_into {
+        _trace.decision(
+            "fallible_rust_to_proto",
+            "none_as = \"error\" -> TryFrom<rust> for proto",
+        );
+        quote! {
+            impl #impl_generics TryFrom<#struct_name #ty_generics> for #proto_type #where_clause {
+                type Error = #actual_error_type;
+
+                fn try_from(my_struct: #struct_name #ty_generics) -> Result<Self, Self::Error> {
+                    #(#try_proto_only_bindings)*
+                    let #struct_name { #(#destructured_fields,)* } = my_struct;
+                    Ok(#proto_message)
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics Into<#proto_type> for #struct_name #ty_generics #where_clause {
+                fn into(self) -> #proto_type {
+                    #(#proto_only_bindings)*
+                    let #struct_name { #(#destructured_fields,)* } = self;
+                    #proto_message
+                }
+            }
+        }
+    };
+
+    if config.conversions_only {
+        return quote! {
+            #from_trait_impl
+            #into_trait_impl
+        };
+    }
+
+    let presence_impl = if config.presence {
+        generate_presence(
+            struct_name,
+            config.vis,
+            config.generics,
+            &proto_type,
+            &presence_fields,
+            needs_try_from.then_some(&actual_error_type),
+        )
+    } else {
+        quote! {}
+    };
+
+    let proto_parts_impl = if config.proto_parts {
+        generate_proto_parts(
+            struct_name,
+            config.vis,
+            config.generics,
+            &proto_type,
+            &parts_fields,
+            needs_try_from.then_some(&actual_error_type),
+        )
+    } else {
+        quote! {}
+    };
+
+    let view_impl = if config.view {
+        generate_view(
+            struct_name,
+            config.vis,
+            &proto_type,
+            &view_fields,
+            needs_try_from.then_some(&actual_error_type),
+        )
+    } else {
+        quote! {}
+    };
+
+    let panicking_conversions = if config.both_conversions {
+        generate_panicking_conversions(
+            struct_name,
+            config.generics,
+            &proto_type,
+            needs_try_from,
+            needs_try_into,
+        )
+    } else {
+        quote! {}
+    };
+
+    let proto_json_impl = if cfg!(feature = "json") {
+        generate_proto_json_impl(struct_name, config.generics, &proto_type)
+    } else {
+        quote! {}
+    };
+
+    let custom_fn_signature_checks = if custom_fn_signatures.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            const _: () = {
+                #[allow(dead_code)]
+                fn __protto_custom_fn_signatures #impl_generics () #where_clause {
+                    #(#custom_fn_signatures)*
+                }
+            };
+        }
+    };
+
+    let error_fn_signature_checks = if error_fn_signatures.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            const _: () = {
+                #[allow(dead_code)]
+                fn __protto_error_fn_signatures #impl_generics () #where_clause {
+                    #(#error_fn_signatures)*
+                }
+            };
+        }
+    };
+
+    let message_field_checks = if message_field_checks.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            const _: () = {
+                #[allow(dead_code)]
+                fn __protto_message_fields(proto: &#proto_type) {
+                    #(#message_field_checks)*
+                }
+            };
+        }
+    };
+
+    let field_paths_impl = if config.field_paths {
+        generate_field_paths(struct_name, config.generics, &field_paths)
+    } else {
+        quote! {}
+    };
+
+    let estimated_size_impl = if config.estimated_size {
+        generate_estimated_size(struct_name, config.generics, &size_terms)
+    } else {
+        quote! {}
+    };
+
+    let any_impl = config
+        .any_type_url
+        .as_deref()
+        .map(|type_url| generate_to_proto_any(struct_name, config.generics, &proto_type, type_url));
+
+    // a generic struct has no single instantiation for the test to convert into
+    let schema_drift_test = if cfg!(feature = "schema-drift-tests")
+        && config.generics.params.is_empty()
+        && !is_resolved
+    {
+        generate_schema_drift_test(struct_name, &proto_type, &mapped_proto_fields)
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        #conversion_error_def
+        #error_conversions
+        #from_trait_impl
+        #into_trait_impl
+        #panicking_conversions
+        #presence_impl
+        #proto_parts_impl
+        #view_impl
+        #field_paths_impl
+        #estimated_size_impl
+        #any_impl
+        #proto_json_impl
+        #custom_fn_signature_checks
+        #error_fn_signature_checks
+        #message_field_checks
+        #clone_report
+        #stats_dependency
+        #schema_drift_test
+    }
+}
+
+/// Struct-level `debug_roundtrip`: under `debug_assertions`, the proto -> rust conversion keeps a
+/// clone of the proto message and asserts that converting its result back yields an equal message,
+/// so lossy mappings surface during development. Release builds compile neither statement.
+fn generate_round_trip_check(
+    struct_name: &syn::Ident,
+    proto_type: &syn::Path,
+    fallible_into: bool,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let message = format!(
+        "`{struct_name}` does not round-trip: converting it back does not give the proto message \
+        it was converted from"
+    );
+    let round_trip = if fallible_into {
+        quote! {
+            <#proto_type as ::core::convert::TryFrom<Self>>::try_from(
+                ::core::clone::Clone::clone(&rust_struct),
+            )
+            .ok()
+        }
+    } else {
+        quote! {
+            Some(::core::convert::Into::<#proto_type>::into(
+                ::core::clone::Clone::clone(&rust_struct),
+            ))
+        }
+    };
+    (
+        quote! {
+            #[cfg(debug_assertions)]
+            let original_proto = ::core::clone::Clone::clone(&proto_struct);
+        },
+        quote! {
+            #[cfg(debug_assertions)]
+            assert_eq!(#round_trip, Some(original_proto), #message);
+        },
+    )
+}
+
+/// Struct-level `transparent`: the struct converts to and from the proto message by delegating
+/// to its single field's own conversion, wrapping and unwrapping it
+fn generate_transparent_implementations(config: &StructImplConfig) -> proc_macro2::TokenStream {
+    let struct_name = config.name;
+    let proto_path = config.proto_path;
+    let field_name = config.fields[0]
+        .ident
+        .as_ref()
+        .expect("named struct fields have identifiers");
+    let (impl_generics, ty_generics, where_clause) = config.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics From<#proto_path> for #struct_name #ty_generics #where_clause {
+            fn from(proto: #proto_path) -> Self {
+                Self { #field_name: proto.into() }
+            }
+        }
+
+        impl #impl_generics From<#struct_name #ty_generics> for #proto_path #where_clause {
+            fn from(value: #struct_name #ty_generics) -> Self {
+                value.#field_name.into()
+            }
+        }
+    }
+}
+
+/// Under `conversions = "both"`, adds `from_proto`/`into_proto` methods that delegate to the
+/// fallible `TryFrom` conversions and panic on bad data. A `From` impl is not an option: it would
+/// bring the standard blanket `TryFrom` along, which conflicts with the generated one.
+fn generate_panicking_conversions(
+    struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    proto_type: &syn::Path,
+    needs_try_from: bool,
+    needs_try_into: bool,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let proto_name = quote! { #proto_type }.to_string().replace(' ', "");
+
+    let from_proto = needs_try_from.then(|| {
+        let message = format!("failed to convert {proto_name} into {struct_name}: {{err:?}}");
+        quote! {
+            pub fn from_proto(proto_struct: #proto_type) -> Self {
+                Self::try_from(proto_struct).unwrap_or_else(|err| panic!(#message))
+            }
+        }
+    });
+    let into_proto = needs_try_into.then(|| {
+        let message = format!("failed to convert {struct_name} into {proto_name}: {{err:?}}");
+        quote! {
+            pub fn into_proto(self) -> #proto_type {
+                #proto_type::try_from(self).unwrap_or_else(|err| panic!(#message))
+            }
+        }
+    });
+
+    if from_proto.is_none() && into_proto.is_none() {
+        return quote! {};
+    }
+
+    quote! {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #from_proto
+            #into_proto
+        }
+    }
+}
+
+/// Names the proto message a struct converts through (behind the `json` feature), which gives it
+/// the `to_proto_json`/`from_proto_json` methods of `protto::json::ProtoJson`
+fn generate_proto_json_impl(
+    struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    proto_type: &syn::Path,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::protto::json::ProtoJson for #struct_name #ty_generics #where_clause {
+            type Proto = #proto_type;
+        }
+    }
+}
+
+/// Under `any_type_url` (behind the `any` feature), implements the dyn-compatible
+/// `protto::any::ToProtoAny`, which packs a converted clone of the struct under the type URL so
+/// trait objects of different message types can be sent as `prost_types::Any`
+pub fn generate_to_proto_any(
+    struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    proto_type: &syn::Path,
+    type_url: &str,
+) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "any") {
+        let error_msg = format!(
+            "`{struct_name}` sets any_type_url, which requires the `any` feature of protto"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::protto::any::ToProtoAny for #struct_name #ty_generics #where_clause {
+            fn type_url(&self) -> &'static str {
+                #type_url
+            }
+
+            fn to_proto_any(&self) -> ::protto::any::prost_types::Any {
+                let proto_struct: #proto_type = ::core::clone::Clone::clone(self).into();
+                ::protto::any::pack(#type_url, &proto_struct)
+            }
+        }
+    }
+}
+
+/// Under `custom_fns_are = "pure"`, coerces each custom function of a field to the exact function
+/// pointer type its conversion calls, so a mismatch reports the expected and found signatures at
+/// the field instead of an inference failure inside the generated struct literal
+fn generate_custom_fn_signature_checks(
+    ctx: &FieldProcessingContext,
+) -> Vec<proc_macro2::TokenStream> {
+    let field_type = ctx.field_type;
+    let span = ctx.field_name.span();
+    let mut checks = Vec::new();
+
+    if let Some(from_fn) = ctx.protto_meta.get_proto_to_rust_fn() {
+        let from_fn = ctx.custom_fn_path(from_fn);
+        // each `depends_on` field follows the proto value as a reference
+        let dependencies = ctx.protto_meta.depends_on.iter().map(|_| quote! { &_ });
+        let signature = if ctx.protto_meta.from_proto_by_ref {
+            quote! { fn(&_ #(, #dependencies)*) -> #field_type }
+        } else if ctx.protto_meta.from_proto_fallible {
+            quote! { fn(_ #(, #dependencies)*) -> ::core::result::Result<#field_type, _> }
+        } else {
+            quote! { fn(_ #(, #dependencies)*) -> #field_type }
+        };
+        checks.push(quote_spanned! { span=> let _: #signature = #from_fn; });
+    }
+
+    if let Some(to_fn) = ctx.protto_meta.get_rust_to_proto_fn() {
+        let to_fn = ctx.custom_fn_path(to_fn);
+        checks.push(quote_spanned! { span=> let _: fn(#field_type) -> _ = #to_fn; });
+    }
+
+    checks
+}
+
+/// Calls the `error_fn` a field errors through the way its conversion does, returning the error
+/// with `?` into the struct's error type, so a wrong arity or an unconvertible return type is
+/// reported at the first field using the function. An `error_fn_with_value` receives `&()`, which
+/// its documented `&dyn Debug` value parameter accepts.
+fn generate_error_fn_signature_check(
+    ctx: &FieldProcessingContext,
+    error_fn: &syn::Path,
+    error_type: &syn::Type,
+) -> proc_macro2::TokenStream {
+    let span = ctx.field_name.span();
+    let error_fn: syn::Path = syn::LitStr::new(&quote! { #error_fn }.to_string(), span)
+        .parse()
+        .expect("Failed to parse error function path");
+    let call = if ctx.error_fn_takes_value() {
+        quote_spanned! { span=> #error_fn(field, &()) }
+    } else {
+        quote_spanned! { span=> #error_fn(field) }
+    };
+
+    quote_spanned! { span=>
+        let _ = |field: &'static str| -> ::core::result::Result<(), #error_type> {
+            ::core::result::Result::Err(#call)?
+        };
+    }
+}
+
+/// Requires the proto field of a field read as an optional message to be an `Option`, reporting
+/// a mismatch at the field's type with a note on `alias_of`, rather than only as a missing method
+/// inside the generated conversion
+/// Struct-level `passthrough`: the struct is the proto message itself, such as a prost message
+/// given the derive by prost-build's `type_attribute`. Core's identity `From` already converts it
+/// to and from itself, which the `protto::version` traits build on, so only the struct's own
+/// items are generated, with `Self` as the proto type and no field analysis.
+pub fn generate_passthrough_implementations(
+    struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    any_type_url: Option<&str>,
+    estimated_size: bool,
+) -> proc_macro2::TokenStream {
+    if estimated_size && !cfg!(feature = "size-estimate") {
+        let error_msg = format!(
+            "`{struct_name}` enables estimated_size, which requires the `size-estimate` feature \
+            of protto"
+        );
+        return quote! { compile_error!(#error_msg); };
+    }
+
+    let proto_type: syn::Path = syn::parse_quote!(Self);
+    let proto_json_impl = if cfg!(feature = "json") {
+        generate_proto_json_impl(struct_name, generics, &proto_type)
+    } else {
+        quote! {}
+    };
+    let any_impl = any_type_url
+        .map(|type_url| generate_to_proto_any(struct_name, generics, &proto_type, type_url));
+    let estimated_size_impl = if estimated_size {
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_generics ::protto::size::EstimatedProtoSize for #struct_name #ty_generics #where_clause {
+                const LENGTH_DELIMITED: bool = true;
+
+                fn estimated_proto_size(&self) -> usize {
+                    ::protto::size::prost::Message::encoded_len(self)
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        #proto_json_impl
+        #any_impl
+        #estimated_size_impl
+    }
+}
+
+fn generate_message_field_check(
+    ctx: &FieldProcessingContext,
+    field: &syn::Field,
+) -> proc_macro2::TokenStream {
+    let proto_field = &ctx.proto_field_ident;
+    quote_spanned! { field.ty.span()=>
+        ::protto::type_alias::message_field(&proto.#proto_field);
+    }
+}
+
+/// Generates the `{Struct}Presence` companion recording which optional proto fields were set, and
+/// `from_proto_with_presence`, which reads it off the proto message before converting it
+fn generate_presence(
+    struct_name: &syn::Ident,
+    vis: &syn::Visibility,
+    generics: &syn::Generics,
+    proto_type: &syn::Path,
+    presence_fields: &[(&syn::Ident, syn::Ident)],
+    error_type: Option<&syn::Type>,
+) -> proc_macro2::TokenStream {
+    let presence_name = syn::Ident::new(
+        &format!("{struct_name}{}", crate::constants::PRESENCE_SUFFIX),
+        struct_name.span(),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let field_names: Vec<_> = presence_fields
+        .iter()
+        .map(|(field_name, _)| field_name)
+        .collect();
+    let proto_fields = presence_fields.iter().map(|(_, proto_field)| proto_field);
+
+    let constructor = match error_type {
+        Some(error_type) => quote! {
+            pub fn from_proto_with_presence(
+                proto_struct: #proto_type,
+            ) -> Result<(Self, #presence_name), #error_type> {
+                let presence = #presence_name {
+                    #(#field_names: proto_struct.#proto_fields.is_some(),)*
+                };
+                Ok((Self::try_from(proto_struct)?, presence))
+            }
+        },
+        None => quote! {
+            pub fn from_proto_with_presence(proto_struct: #proto_type) -> (Self, #presence_name) {
+                let presence = #presence_name {
+                    #(#field_names: proto_struct.#proto_fields.is_some(),)*
+                };
+                (Self::from(proto_struct), presence)
+            }
+        },
+    };
+
+    quote! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+        #vis struct #presence_name {
+            #(pub #field_names: bool,)*
+        }
+
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #constructor
+        }
+    }
+}
+
+/// Under `proto_parts`, generates `<Struct>ProtoParts` with every field as a public `Option`,
+/// converted from the proto message through the struct's own conversion. Converted fields are
+/// `Some`, and `ignore` fields start as `None` for the caller to fill before `finish` builds the
+/// struct, which fails on the first field still `None`.
+fn generate_proto_parts(
+    struct_name: &syn::Ident,
+    vis: &syn::Visibility,
+    generics: &syn::Generics,
+    proto_type: &syn::Path,
+    parts_fields: &[(&syn::Ident, &syn::Type, bool)],
+    error_type: Option<&syn::Type>,
+) -> proc_macro2::TokenStream {
+    let parts_name = syn::Ident::new(
+        &format!("{struct_name}{}", crate::constants::PROTO_PARTS_SUFFIX),
+        struct_name.span(),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let field_names: Vec<_> = parts_fields.iter().map(|(name, _, _)| *name).collect();
+    let field_types = parts_fields.iter().map(|(_, ty, _)| ty);
+    let field_strs = field_names
+        .iter()
+        .map(|name| crate::utils::ident_name(name));
+    let converted: Vec<_> = parts_fields
+        .iter()
+        .filter(|(_, _, ignored)| !ignored)
+        .map(|(name, _, _)| name)
+        .collect();
+    let ignored = parts_fields
+        .iter()
+        .filter(|(_, _, ignored)| *ignored)
+        .map(|(name, _, _)| name);
+
+    let parts_from_value = quote! {
+        Self {
+            #(#converted: Some(#converted),)*
+            #(#ignored: None,)*
+        }
+    };
+    let conversion = match error_type {
+        Some(error_type) => quote! {
+            impl #impl_generics TryFrom<#proto_type> for #parts_name #ty_generics #where_clause {
+                type Error = #error_type;
+
+                fn try_from(proto_struct: #proto_type) -> Result<Self, Self::Error> {
+                    let #struct_name { #(#converted,)* .. } =
+                        <#struct_name #ty_generics as TryFrom<#proto_type>>::try_from(proto_struct)?;
+                    Ok(#parts_from_value)
+                }
+            }
+        },
+        None => quote! {
+            impl #impl_generics From<#proto_type> for #parts_name #ty_generics #where_clause {
+                fn from(proto_struct: #proto_type) -> Self {
+                    let #struct_name { #(#converted,)* .. } =
+                        <#struct_name #ty_generics as From<#proto_type>>::from(proto_struct);
+                    #parts_from_value
+                }
+            }
+        },
+    };
+
+    quote! {
+        #vis struct #parts_name #generics #where_clause {
+            #(pub #field_names: Option<#field_types>,)*
+        }
+
+        #conversion
+
+        impl #impl_generics #parts_name #ty_generics #where_clause {
+            pub fn finish(self) -> Result<#struct_name #ty_generics, ::protto::parts::MissingPart> {
+                Ok(#struct_name {
+                    #(#field_names: self.#field_names.ok_or(::protto::parts::MissingPart {
+                        field: #field_strs,
+                    })?,)*
+                })
+            }
+        }
+    }
+}
+
+/// Under `view`, generates `<Struct>View<'a>` over a borrowed proto message: the fields it can
+/// borrow or copy, strings and bytes as `&'a str` and `&'a [u8]`, `to_owned` running the full
+/// conversion on a clone of the message, and `proto` for everything else
+fn generate_view(
+    struct_name: &syn::Ident,
+    vis: &syn::Visibility,
+    proto_type: &syn::Path,
+    view_fields: &[(
+        &syn::Field,
+        proc_macro2::TokenStream,
+        proc_macro2::TokenStream,
+    )],
+    error_type: Option<&syn::Type>,
+) -> proc_macro2::TokenStream {
+    let view_name = syn::Ident::new(
+        &format!("{struct_name}{}", crate::constants::VIEW_SUFFIX),
+        struct_name.span(),
+    );
+    let field_vis = view_fields.iter().map(|(field, _, _)| &field.vis);
+    let field_names: Vec<_> = view_fields
+        .iter()
+        .map(|(field, _, _)| field.ident.as_ref().unwrap())
+        .collect();
+    let field_types = view_fields.iter().map(|(_, view_type, _)| view_type);
+    let field_values = view_fields.iter().map(|(_, _, view_value)| view_value);
+
+    let to_owned = match error_type {
+        Some(error_type) => quote! {
+            pub fn to_owned(&self) -> Result<#struct_name, #error_type> {
+                <#struct_name as TryFrom<#proto_type>>::try_from(::core::clone::Clone::clone(self.proto))
+            }
+        },
+        None => quote! {
+            pub fn to_owned(&self) -> #struct_name {
+                <#struct_name as From<#proto_type>>::from(::core::clone::Clone::clone(self.proto))
+            }
+        },
+    };
+
+    quote! {
+        #[derive(Debug, Clone)]
+        #vis struct #view_name<'a> {
+            #(#field_vis #field_names: #field_types,)*
+            proto: &'a #proto_type,
+        }
+
+        impl<'a> #view_name<'a> {
+            pub fn new(proto: &'a #proto_type) -> Self {
+                Self {
+                    #(#field_names: #field_values,)*
+                    proto,
+                }
+            }
+
+            /// The proto message the view borrows from
+            pub fn proto(&self) -> &'a #proto_type {
+                self.proto
+            }
+
+            #to_owned
+        }
+
+        impl<'a> From<&'a #proto_type> for #view_name<'a> {
+            fn from(proto: &'a #proto_type) -> Self {
+                Self::new(proto)
+            }
+        }
+    }
+}
+
+/// The view type and value of a field converted as-is from its proto field: strings and bytes
+/// borrowed, alone, optional or, for strings, repeated; proto scalars copied. Fields with
+/// conversion attributes or of other types are left out of the view.
+fn view_field(
+    field: &syn::Field,
+    ctx: &FieldProcessingContext,
+) -> Option<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    let converts_as_is = attribute_parser::protto_metas(&field.attrs)
+        .is_ok_and(|metas| metas.iter().all(|meta| meta.path().is_ident("proto_name")))
+        && ctx.protto_meta.from_proto_fn.is_none()
+        && ctx.protto_meta.to_proto_fn.is_none();
+    if !converts_as_is {
+        return None;
+    }
+
+    let proto_field = &ctx.proto_field_ident;
+    let proto_optional = field::is_proto_field_optional(field, ctx);
+    let inner_type = type_analysis::get_inner_type_from_option(ctx.field_type);
+    let is_string = |ty: &syn::Type| matches!(ty, syn::Type::Path(p) if p.path.is_ident("String"));
+    let is_byte_vec = |ty: &syn::Type| {
+        type_analysis::get_inner_type_from_vec(ty)
+            .is_some_and(|element| matches!(&element, syn::Type::Path(p) if p.path.is_ident("u8")))
+    };
+    let is_scalar = |ty: &syn::Type| {
+        matches!(ty, syn::Type::Path(p) if ["bool", "i32", "i64", "u32", "u64", "f32", "f64"]
+            .iter()
+            .any(|scalar| p.path.is_ident(scalar)))
+    };
+
+    match (&inner_type, proto_optional) {
+        (None, false) if is_string(ctx.field_type) => {
+            Some((quote! { &'a str }, quote! { proto.#proto_field.as_str() }))
+        }
+        (None, false) if is_byte_vec(ctx.field_type) => Some((
+            quote! { &'a [u8] },
+            quote! { proto.#proto_field.as_slice() },
+        )),
+        (None, false)
+            if type_analysis::get_inner_type_from_vec(ctx.field_type)
+                .is_some_and(|element| is_string(&element)) =>
+        {
+            Some((
+                quote! { ::std::vec::Vec<&'a str> },
+                quote! { proto.#proto_field.iter().map(::std::string::String::as_str).collect() },
+            ))
+        }
+        (None, false) if is_scalar(ctx.field_type) => {
+            let ty = ctx.field_type;
+            Some((quote! { #ty }, quote! { proto.#proto_field }))
+        }
+        (Some(inner), true) if is_string(inner) => Some((
+            quote! { ::core::option::Option<&'a str> },
+            quote! { proto.#proto_field.as_deref() },
+        )),
+        (Some(inner), true) if is_byte_vec(inner) => Some((
+            quote! { ::core::option::Option<&'a [u8]> },
+            quote! { proto.#proto_field.as_deref() },
+        )),
+        (Some(inner), true) if is_scalar(inner) => {
+            let ty = ctx.field_type;
+            Some((quote! { #ty }, quote! { proto.#proto_field }))
+        }
+        _ => None,
+    }
+}
+
+/// Under `estimated_size`, implements `protto::size::EstimatedProtoSize` as the sum of the
+/// estimated sizes of the mapped fields
+fn generate_estimated_size(
+    struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    size_terms: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::protto::size::EstimatedProtoSize for #struct_name #ty_generics #where_clause {
+            const LENGTH_DELIMITED: bool = true;
+
+            fn estimated_proto_size(&self) -> usize {
+                0 #(+ #size_terms)*
+            }
+        }
+    }
+}
+
+/// The estimated size of one field, following the shape of its rust type. Oneof enums size the
+/// whole oneof field, and group sub-structs their flattened proto fields, so neither adds a key.
+fn estimated_field_size(ctx: &FieldProcessingContext) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let span = field_name.span();
+    let inner_type = type_analysis::get_inner_type_from_option(ctx.field_type);
+    let value_type = inner_type.as_ref().unwrap_or(ctx.field_type);
+
+    let value_size = if ctx.protto_meta.oneof.is_some() || ctx.protto_meta.group.is_some() {
+        quote_spanned! { span=> ::protto::size::EstimatedProtoSize::estimated_proto_size(value) }
+    } else if type_analysis::is_bytes_type(value_type) {
+        quote_spanned! { span=> ::protto::size::bytes_size(value) }
+    } else if type_analysis::is_map_type(value_type) {
+        quote_spanned! { span=> ::protto::size::map_size(value) }
+    } else if type_analysis::is_vec_type(value_type)
+        || type_analysis::bounded_collection_type(value_type).is_some()
+    {
+        quote_spanned! { span=> ::protto::size::repeated_size(value) }
+    } else {
+        quote_spanned! { span=> ::protto::size::field_size(value) }
+    };
+
+    if inner_type.is_some() {
+        quote! { self.#field_name.as_ref().map_or(0, |value| #value_size) }
+    } else {
+        quote! { { let value = &self.#field_name; #value_size } }
+    }
+}
+
+/// Under `field_paths`, associated constants naming the proto fields the struct maps: `FIELD_PATHS`
+/// lists them all, in field order, and each rust field mapped to a single proto field gets a
+/// `<FIELD>_PATH` constant, so field masks and log keys follow the schema instead of repeating its
+/// names as literals.
+fn generate_field_paths(
+    struct_name: &syn::Ident,
+    generics: &syn::Generics,
+    field_paths: &[(&syn::Ident, Vec<String>)],
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let all_paths = field_paths.iter().flat_map(|(_, paths)| paths);
+    let field_consts =
+        field_paths
+            .iter()
+            .filter_map(|(field_name, paths)| match paths.as_slice() {
+                [path] => {
+                    let field_name = crate::utils::ident_name(field_name);
+                    let const_name = quote::format_ident!("{}_PATH", field_name.to_uppercase());
+                    let doc = format!("Proto field path of `{field_name}`.");
+                    Some(quote! {
+                        #[doc = #doc]
+                        pub const #const_name: &'static str = #path;
+                    })
+                }
+                _ => None,
+            });
+
+    quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            /// Proto field paths mapped by the derive, in field order.
+            pub const FIELD_PATHS: &'static [&'static str] = &[#(#all_paths),*];
+            #(#field_consts)*
+        }
+    }
+}
+
+/// Names of the proto fields a (non-ignored) rust field maps to, without any `r#` prefix.
+fn proto_field_paths(ctx: &FieldProcessingContext) -> Vec<String> {
+    match &ctx.protto_meta.group {
+        Some(mappings) => mappings
+            .iter()
+            .map(|(_, proto_field)| {
+                crate::utils::ident_name(&crate::utils::field_ident(proto_field))
+            })
+            .collect(),
+        None => vec![crate::utils::ident_name(&ctx.proto_field_ident)],
+    }
+}
+
+/// The proto fields a (non-ignored) rust field reads and writes. A `bool_enum` that folds unknown
+/// values into a bool is lossy by design (e.g. the unspecified zero value), so it is left out.
+fn mapped_proto_field_idents(ctx: &FieldProcessingContext) -> Vec<syn::Ident> {
+    if let Some(mapping) = &ctx.protto_meta.bool_enum
+        && matches!(
+            mapping.unknown,
+            attribute_parser::UnknownVariantPolicy::Value(_)
+        )
+    {
+        return Vec::new();
+    }
+
+    match &ctx.protto_meta.group {
+        Some(mappings) => mappings
+            .iter()
+            .map(|(_, proto_field)| crate::utils::field_ident(proto_field))
+            .collect(),
+        None => vec![ctx.proto_field_ident.clone()],
+    }
+}
+
+/// Every proto field may be written by one rust field only: two fields renamed onto the same
+/// proto field, or a field also named by the struct-level `ignore` or `proto_only`, would
+/// otherwise surface as a duplicate field in the generated proto literal. Each offending rust
+/// field gets its own error.
+fn check_proto_field_conflicts(
+    owners: &[(String, &syn::Field)],
+    proto_ignored_fields: &HashSet<String>,
+    proto_only: &[ProtoOnlyField],
+) -> syn::Result<()> {
+    let rust_name =
+        |field: &syn::Field| crate::utils::ident_name(field.ident.as_ref().expect("named field"));
+    let names =
+        |name: &String, proto_field: &str| name.strip_prefix("r#").unwrap_or(name) == proto_field;
+
+    let mut errors: Option<syn::Error> = None;
+    let mut push = |error: syn::Error| match errors.as_mut() {
+        Some(errors) => errors.combine(error),
+        None => errors = Some(error),
+    };
+
+    let mut reported = HashSet::new();
+    for (proto_field, field) in owners {
+        let mut others: Vec<String> = owners
+            .iter()
+            .filter(|(other_proto_field, other)| {
+                other_proto_field == proto_field && !std::ptr::eq(*other, *field)
+            })
+            .map(|(_, other)| format!("`{}`", rust_name(other)))
+            .collect();
+        others.dedup();
+        if !others.is_empty() && reported.insert((proto_field, rust_name(field))) {
+            push(syn::Error::new_spanned(
+                field,
+                format!(
+                    "`{}` maps to proto field `{proto_field}`, as does {}; rename one with \
+                    proto_name or ignore it",
+                    rust_name(field),
+                    others.join(", ")
+                ),
+            ));
+        }
+        if proto_ignored_fields
+            .iter()
+            .any(|ignored| names(ignored, proto_field))
+        {
+            push(syn::Error::new_spanned(
+                field,
+                format!(
+                    "`{}` maps to proto field `{proto_field}`, which the struct-level ignore \
+                    fills with its default; remove it from ignore",
+                    rust_name(field)
+                ),
+            ));
+        }
+        if proto_only
+            .iter()
+            .any(|only| names(&only.proto_field, proto_field))
+        {
+            push(syn::Error::new_spanned(
+                field,
+                format!(
+                    "`{}` maps to proto field `{proto_field}`, which proto_only computes; \
+                    remove it from proto_only",
+                    rust_name(field)
+                ),
+            ));
+        }
+    }
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(()),
+    }
+}
+
+/// Generates a canary test (behind the `schema-drift-tests` feature) asserting that a default
+/// proto message survives the round trip through the rust struct on every mapped field. A
+/// regenerated proto whose field shapes changed in ways the inference silently absorbs shows up
+/// as a failing field here. A struct the default message cannot be converted into, such as one
+/// with an `expect` field, fails the test with the reason the round trip could not run.
+fn generate_schema_drift_test(
+    struct_name: &syn::Ident,
+    proto_type: &syn::Path,
+    mapped_proto_fields: &[syn::Ident],
+) -> proc_macro2::TokenStream {
+    let test_name = syn::Ident::new(
+        &format!("__protto_schema_drift_{struct_name}"),
+        struct_name.span(),
+    );
+    let mapped_proto_field_names: Vec<_> = mapped_proto_fields
+        .iter()
+        .map(crate::utils::ident_name)
+        .collect();
+
+    quote! {
+        #[cfg(test)]
+        #[test]
+        #[allow(non_snake_case)]
+        fn #test_name() {
+            let original = <#proto_type as ::core::default::Default>::default();
+            let candidate = original.clone();
+            let rust_struct = match ::std::panic::catch_unwind(move || {
+                <#struct_name as ::core::convert::TryFrom<#proto_type>>::try_from(candidate)
+            }) {
+                ::core::result::Result::Ok(::core::result::Result::Ok(rust_struct)) => rust_struct,
+                ::core::result::Result::Ok(::core::result::Result::Err(_)) => panic!(
+                    "schema drift: the round trip could not run, as a default {} fails to convert \
+                    into {}, typically because a field it requires is unset in a default message",
+                    stringify!(#proto_type),
+                    stringify!(#struct_name),
+                ),
+                ::core::result::Result::Err(_) => panic!(
+                    "schema drift: the round trip could not run, as converting a default {} into \
+                    {} panicked, typically because a field it requires is unset in a default \
+                    message",
+                    stringify!(#proto_type),
+                    stringify!(#struct_name),
+                ),
+            };
+            let round_trip = <#proto_type as ::core::convert::TryFrom<#struct_name>>::try_from(rust_struct)
+                .unwrap_or_else(|_| panic!("{} failed to convert back into proto", stringify!(#struct_name)));
+
+            #(
+                assert_eq!(
+                    round_trip.#mapped_proto_fields,
+                    original.#mapped_proto_fields,
+                    "schema drift: {}.{} did not survive the round trip",
+                    stringify!(#proto_type),
+                    #mapped_proto_field_names,
+                );
+            )*
+        }
+    }
+}
+
+/// Main orchestration function for generating all error-related definitions
+fn generate_error_definitions_if_needed(
+    name: &syn::Ident,
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    struct_level_error_type: &Option<syn::Type>,
+    error_mod: &Option<syn::Ident>,
+    error_vis: &syn::Visibility,
+    static_errors: bool,
+    validate: bool,
+) -> (
+    proc_macro2::TokenStream,
+    proc_macro2::TokenStream,
+    bool,
+    bool,
+) {
+    let requirements = error_analysis::analyze_error_requirements(fields, struct_level_error_type);
+    // validating the proto message makes the proto -> rust conversion fallible on its own
+    let needs_try_from = requirements.needs_try_from || validate;
+
+    let conversion_error_def = if (needs_try_from || requirements.needs_try_into)
+        && (requirements.needs_default_error || validate)
+        && struct_level_error_type.is_none()
+    {
+        match error_mod {
+            // a dedicated module keeps the error out of the struct's scope, so same-named structs
+            // in glob re-exported modules don't collide on their error types. The module carries
+            // the visibility: relative visibilities like `pub(super)` would mean something else
+            // on the error inside it.
+            Some(error_mod) => {
+                let error_def = generate_conversion_error_enum(
+                    name,
+                    &syn::parse_quote! { pub },
+                    requirements.needs_invalid_value,
+                    static_errors,
+                    validate,
+                );
+                quote! {
+                    #error_vis mod #error_mod {
+                        #error_def
+                    }
+                }
+            }
+            None => generate_conversion_error_enum(
+                name,
+                error_vis,
+                requirements.needs_invalid_value,
+                static_errors,
+                validate,
+            ),
+        }
+    } else {
+        quote! {}
+    };
+
+    let error_conversions = if requirements.needs_error_conversions {
+        generate_error_conversions(&default_error_path(name, error_mod), static_errors)
+    } else {
+        quote! {}
+    };
+
+    (
+        conversion_error_def,
+        error_conversions,
+        needs_try_from,
+        requirements.needs_try_into,
+    )
+}
+
+/// Collect the fields whose proto -> rust conversion is resolved through an async lookup
+fn find_resolved_fields(
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+) -> Vec<String> {
+    fields
+        .iter()
+        .filter(|field| !attribute_parser::has_proto_ignore(field))
+        .filter(|field| {
+            attribute_parser::ProtoFieldMeta::from_field(field)
+                .map(|meta| meta.resolve_with.is_some())
+                .unwrap_or(false)
+        })
+        .filter_map(|field| field.ident.as_ref().map(|ident| ident.to_string()))
+        .collect()
+}
+
+/// Generates the initializer awaiting a field's `resolve_with` lookup on the proto value
+fn generate_resolved_field(
+    ctx: &FieldProcessingContext,
+    resolve_fn: &str,
+) -> proc_macro2::TokenStream {
+    let field_name = ctx.field_name;
+    let proto_field = &ctx.proto_field_ident;
+    let resolve_fn: syn::Path =
+        syn::parse_str(resolve_fn).expect("Failed to parse resolve_with function path");
+
+    quote! { #field_name: #resolve_fn(resolver, proto_struct.#proto_field).await? }
+}
+
+/// Collect the fields carrying attributes that only apply to the proto -> rust direction
+fn find_from_proto_only_fields(
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+) -> Vec<String> {
+    fields
+        .iter()
+        .filter(|field| !attribute_parser::has_proto_ignore(field))
+        .filter(|field| {
+            attribute_parser::ProtoFieldMeta::from_field(field)
+                .map(|meta| {
+                    meta.expect
+                        || meta.error_fn.is_some()
+                        || meta.default_fn.is_some()
+                        || meta.from_proto_fn.is_some()
+                        || meta.resolve_with.is_some()
+                })
+                .unwrap_or(false)
+        })
+        .filter_map(|field| field.ident.as_ref().map(|ident| ident.to_string()))
+        .collect()
+}
+
+/// Binds the fields named in `depends_on`, and the fields depending on them, to locals of the
+/// same name in dependency order, so a field's `from_proto_fn` receives the fields it depends on
+/// already converted. Returns the bindings and the bound fields, which the struct literal then
+/// takes from the locals. A dependency that is not a field of the struct, or a cycle, is an error.
+fn generate_dependency_bindings<'a>(
+    struct_name: &syn::Ident,
+    field_conversions: &[(
+        &'a syn::Ident,
+        proc_macro2::TokenStream,
+        proc_macro2::TokenStream,
+    )],
+    field_dependencies: &[(&'a syn::Ident, Vec<String>)],
+) -> syn::Result<(proc_macro2::TokenStream, HashSet<&'a syn::Ident>)> {
+    let field_named = |name: &str| {
+        field_conversions
+            .iter()
+            .map(|(field_name, _, _)| *field_name)
+            .find(|field_name| {
+                crate::utils::ident_name(field_name) == name.trim_start_matches("r#")
+            })
+    };
+    let mut graph: Vec<(&syn::Ident, Vec<&syn::Ident>)> = Vec::new();
+    for (field_name, dependencies) in field_dependencies {
+        let mut resolved = Vec::new();
+        for dependency in dependencies {
+            let Some(dependency_field) = field_named(dependency) else {
+                return Err(syn::Error::new_spanned(
+                    field_name,
+                    format!(
+                        "`{}` depends_on `{dependency}`, which is not a field of `{struct_name}`",
+                        crate::utils::ident_name(field_name)
+                    ),
+                ));
+            };
+            resolved.push(dependency_field);
+        }
+        graph.push((field_name, resolved));
+    }
+    // Depth-first, so each field is bound after everything it depends on; `path` holds the
+    // fields being visited, so meeting one of them again closes a cycle
+    fn visit<'a>(
+        field_name: &'a syn::Ident,
+        graph: &[(&'a syn::Ident, Vec<&'a syn::Ident>)],
+        path: &mut Vec<&'a syn::Ident>,
+        order: &mut Vec<&'a syn::Ident>,
+    ) -> syn::Result<()> {
+        if order.contains(&field_name) {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|visiting| *visiting == field_name) {
+            let cycle = path[start..]
+                .iter()
+                .chain([&field_name])
+                .map(|name| crate::utils::ident_name(name))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(syn::Error::new_spanned(
+                field_name,
+                format!("depends_on cycle: {cycle}"),
+            ));
+        }
+        path.push(field_name);
+        let dependencies = graph
+            .iter()
+            .find(|(name, _)| *name == field_name)
+            .map(|(_, dependencies)| dependencies.as_slice())
+            .unwrap_or_default();
+        for dependency in dependencies {
+            visit(dependency, graph, path, order)?;
+        }
+        path.pop();
+        order.push(field_name);
+        Ok(())
+    }
+
+    let mut order = Vec::new();
+    for (field_name, _) in &graph {
+        visit(field_name, &graph, &mut Vec::new(), &mut order)?;
+    }
+
+    let bindings = order.iter().map(|field_name| {
+        let (_, proto_to_rust, _) = field_conversions
+            .iter()
+            .find(|(name, _, _)| name == field_name)
+            .expect("dependency is a field of the struct");
+        match syn::parse2::<syn::FieldValue>(proto_to_rust.clone()) {
+            Ok(field_value) => {
+                let expr = field_value.expr;
+                quote! { let #field_name = #expr; }
+            }
+            Err(err) => err.to_compile_error(),
+        }
+    });
+    let bind_dependencies = quote! { #(#bindings)* };
+    Ok((bind_dependencies, order.into_iter().collect()))
+}
+
+/// Generates the calls into the user's `FromProtoParts` impl for the `manual` fields: binding
+/// them from the borrowed proto message before the derived fields move out of it, and writing
+/// them into the proto message built from the derived fields. A single manual field is its own
+/// `Parts`; several are a tuple in declaration order.
+fn generate_manual_parts(
+    struct_name: &syn::Ident,
+    ty_generics: &syn::TypeGenerics,
+    proto_type: &syn::Path,
+    manual_fields: &[(&syn::Ident, &syn::Type)],
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let field_names: Vec<_> = manual_fields.iter().map(|(name, _)| name).collect();
+    let field_types: Vec<_> = manual_fields.iter().map(|(_, ty)| ty).collect();
+    let (pattern, parts_type) = match (field_names.as_slice(), field_types.as_slice()) {
+        ([field_name], [field_type]) => (quote! { #field_name }, quote! { #field_type }),
+        _ => (
+            quote! { (#(#field_names),*) },
+            quote! { (#(#field_types),*) },
+        ),
+    };
+    let parts_trait = quote! {
+        <#struct_name #ty_generics as ::protto::parts::FromProtoParts<#proto_type>>
+    };
+
+    (
+        quote! {
+            let #pattern: #parts_type = #parts_trait::from_proto_parts(&proto_struct);
+        },
+        quote! {
+            #parts_trait::into_proto_parts(#pattern, &mut proto_struct);
+        },
+    )
+}
+
+/// Generate Default::default() assignments for `ignore` fields
+fn generate_proto_ignore_defaults(
+    proto_ignored_fields: &HashSet<String>,
+) -> Vec<proc_macro2::TokenStream> {
+    proto_ignored_fields
+        .iter()
+        .map(|field_name| {
+            let field_ident = crate::utils::field_ident(field_name);
+
+            quote! {
+                #field_ident: Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Struct-level `proto_only`: binds each computed proto field's value from the whole struct,
+/// named by `source`, before the struct is destructured, and the proto field assignments using
+/// them. A leading `Self` in the function path names the struct, since the `TryFrom` impl is on
+/// the proto message.
+fn generate_proto_only_values(
+    struct_name: &syn::Ident,
+    proto_only: &[ProtoOnlyField],
+    source: &proc_macro2::TokenStream,
+) -> (Vec<proc_macro2::TokenStream>, Vec<proc_macro2::TokenStream>) {
+    proto_only
+        .iter()
+        .map(|field| {
+            let field_ident = crate::utils::field_ident(&field.proto_field);
+            let binding =
+                quote::format_ident!("__proto_only_{}", crate::utils::ident_name(&field_ident));
+            let mut to_proto_fn: syn::Path = syn::parse_str(&field.to_proto_fn)
+                .expect("proto_only is checked by validate_attributes");
+            if let Some(first) = to_proto_fn.segments.first_mut()
+                && first.ident == "Self"
+            {
+                first.ident = struct_name.clone();
+            }
+
+            (
+                quote! { let #binding = #to_proto_fn(&#source); },
+                quote! { #field_ident: #binding },
+            )
+        })
+        .unzip()
+}
+
+/// Generates the default error name for a struct
+pub fn default_error_name(struct_name: &syn::Ident) -> syn::Ident {
+    syn::Ident::new(
+        &format!(
+            "{struct_name}{}",
+            crate::constants::DEFAULT_CONVERSION_ERROR_SUFFIX
+        ),
+        struct_name.span(),
+    )
+}
+
+/// Path to the generated error, inside `error_mod` when one is given
+fn default_error_path(struct_name: &syn::Ident, error_mod: &Option<syn::Ident>) -> syn::Path {
+    let error_name = default_error_name(struct_name);
+    match error_mod {
+        Some(error_mod) => syn::parse_quote! { #error_mod::#error_name },
+        None => syn::Path::from(error_name),
+    }
+}
+
+/// Determines the actual error type to use in trait implementations
+fn get_actual_error_type(
+    needs_try_from: bool,
+    struct_level_error_type: &Option<syn::Type>,
+    error_path: &syn::Path,
+) -> syn::Type {
+    if needs_try_from {
+        struct_level_error_type.clone().unwrap_or_else(|| {
+            syn::Type::Path(syn::TypePath {
+                qself: None,
+                path: error_path.clone(),
+            })
+        })
+    } else {
+        syn::parse_str("String").unwrap()
+    }
+}
+
+/// Generates the conversion error enum definition. Under `static_errors` the variants carry the
+/// `&'static str` field name, so building an error never allocates.
+fn generate_conversion_error_enum(
+    struct_name: &syn::Ident,
+    vis: &syn::Visibility,
+    needs_invalid_value: bool,
+    static_errors: bool,
+    validate: bool,
+) -> proc_macro2::TokenStream {
+    let error_name = default_error_name(struct_name);
+    let (field_type, derives) = if static_errors {
+        (
+            quote! { &'static str },
+            quote! { #[derive(Debug, Clone, Copy, PartialEq, Eq)] },
+        )
+    } else {
+        (
+            quote! { String },
+            quote! { #[derive(Debug, Clone, PartialEq)] },
+        )
+    };
+
+    let (invalid_value_variant, invalid_value_display) = if needs_invalid_value {
+        (
+            quote! { InvalidValue(#field_type), },
+            quote! { Self::InvalidValue(field) => write!(f, "Invalid value for field: {field}"), },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    let (validation_variant, validation_display, validation_conversion) = if validate {
+        (
+            quote! { Validation(::protto::validate::ValidationError), },
+            quote! { Self::Validation(err) => write!(f, "Validation failed: {err}"), },
+            quote! {
+                impl From<::protto::validate::ValidationError> for #error_name {
+                    fn from(err: ::protto::validate::ValidationError) -> Self {
+                        Self::Validation(err)
+                    }
+                }
+            },
+        )
+    } else {
+        (quote! {}, quote! {}, quote! {})
+    };
+
+    quote! {
+        #derives
+        #vis enum #error_name {
+            MissingField(#field_type),
+            #invalid_value_variant
+            #validation_variant
+        }
+
+        impl std::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    Self::MissingField(field) => write!(f, "Missing required field: {field}"),
+                    #invalid_value_display
+                    #validation_display
+                }
+            }
+        }
+
+        impl std::error::Error for #error_name {}
+
+        #validation_conversion
+    }
+}
+
+/// Generates error conversion implementations
+fn generate_error_conversions(
+    error_path: &syn::Path,
+    static_errors: bool,
+) -> proc_macro2::TokenStream {
+    let field_type = if static_errors {
+        quote! { &'static str }
+    } else {
+        quote! { String }
+    };
+
+    quote! {
+        impl From<#field_type> for #error_path {
+            fn from(err: #field_type) -> Self {
+                Self::MissingField(err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate_for(input: &str) -> String {
+        let ast: syn::DeriveInput = syn::parse_str(input).unwrap();
+        let parsed = crate::analysis::macro_input::ParsedInput::new(ast.clone());
+        let syn::Data::Struct(data_struct) = &ast.data else {
+            panic!("expected struct");
+        };
+        let syn::Fields::Named(fields_named) = &data_struct.fields else {
+            panic!("expected named fields");
+        };
+
+        let config = StructImplConfig {
+            name: &parsed.name,
+            fields: &fields_named.named,
+            proto_module: &parsed.proto_module,
+            proto_name: &parsed.proto_name,
+            proto_path: &parsed.proto_path,
+            struct_level_error_type: &parsed.struct_level_error_type,
+            struct_level_error_fn: &parsed.struct_level_error_fn,
+            struct_level_error_fn_with_value: parsed.struct_level_error_fn_with_value,
+            proto_ignored_fields: &parsed.proto_ignored_fields,
+            proto_only: &parsed.proto_only,
+            generics: &parsed.generics,
+            resolver_type: &parsed.resolver_type,
+            error_mod: &parsed.error_mod,
+            static_errors: parsed.static_errors,
+            presence: parsed.presence,
+            field_paths: parsed.field_paths,
+            partial: parsed.partial,
+            proto_parts: parsed.proto_parts,
+            view: parsed.view,
+            validate: parsed.validate,
+            debug_roundtrip: parsed.debug_roundtrip,
+            estimated_size: parsed.estimated_size,
+            pure_custom_fns: parsed.pure_custom_fns,
+            both_conversions: parsed.both_conversions,
+            transparent: parsed.transparent,
+            any_type_url: &parsed.any_type_url,
+            conversions_only: false,
+            vis: &parsed.vis,
+            error_vis: &parsed.error_vis,
+        };
+        if parsed.sources.is_empty() {
+            generate_struct_implementations_for_targets(config, &parsed.also_proto).to_string()
+        } else {
+            generate_sources_implementations(config, &parsed.sources).to_string()
+        }
+    }
+
+    #[test]
+    fn test_field_paths_list_mapped_proto_fields() {
+        let code = generate_for(
+            "#[protto(field_paths)] struct Owned { id: u64, \
+             #[protto(proto_name = \"type\")] kind: String, \
+             #[protto(group(street, city = \"town\"))] address: Address, \
+             #[protto(ignore)] cache: Vec<u8> }",
+        );
+        assert!(code.contains(
+            "pub const FIELD_PATHS : & 'static [& 'static str] = & [\"id\" , \"type\" , \"street\" , \"town\"] ;"
+        ));
+        assert!(code.contains("pub const ID_PATH : & 'static str = \"id\" ;"));
+        assert!(code.contains("pub const KIND_PATH : & 'static str = \"type\" ;"));
+        assert!(!code.contains("ADDRESS_PATH"));
+        assert!(!code.contains("CACHE_PATH"));
+
+        let code = generate_for("struct Owned { id: u64 }");
+        assert!(!code.contains("FIELD_PATHS"));
+        assert!(!code.contains("ID_PATH"));
+    }
+
+    #[test]
+    fn test_borrowed_struct_generates_rust_to_proto_only() {
+        let code = generate_for("struct View<'a> { name: &'a str, id: u64 }");
+        assert!(code.contains("impl < 'a > Into < proto :: View > for View < 'a >"));
+        assert!(!code.contains("From < proto :: View >"));
+        assert!(!code.contains("compile_error"));
+    }
+
+    #[test]
+    fn test_borrowed_struct_rejects_from_proto_attributes() {
+        let code = generate_for(
+            "struct View<'a> { #[protto(default)] name: &'a str, #[protto(expect)] id: u64 }",
+        );
+        assert!(code.contains("compile_error"));
+        assert!(code.contains("only possible into owned types"));
+        assert!(code.contains("name, id"));
+    }
+
+    #[test]
+    fn test_rust_to_proto_destructures_every_field() {
+        let code = generate_for(
+            "struct Owned { id: u64, name: String, #[protto(ignore)] cache: Vec<u8> }",
+        );
+        assert!(code.contains("let Owned { id , name , cache : _ , } = self ;"));
+        assert!(code.contains("id : id"));
+        assert!(!code.contains("my_struct"));
+
+        let code = generate_for(
+            "struct Owned { #[protto(proto_required, none_as = \"error\")] name: Option<String> }",
+        );
+        assert!(code.contains("let Owned { name , } = my_struct ;"));
+    }
+
+    #[test]
+    fn test_partial_struct_defaults_unmapped_proto_fields() {
+        let code = generate_for("#[protto(partial)] struct Summary { id: u64, name: String }");
+        assert!(code.contains("name : name , .. :: core :: default :: Default :: default () }"));
+
+        let code = generate_for("struct Summary { id: u64, name: String }");
+        assert!(!code.contains(".. :: core :: default :: Default :: default ()"));
+    }
+
+    #[test]
+    fn test_proto_parts_hold_fields_until_finish() {
+        let code = generate_for(
+            "#[protto(proto_parts)] pub struct Order { id: u64, #[protto(ignore)] total: u64 }",
+        );
+        assert!(code.contains(
+            "pub struct OrderProtoParts { pub id : Option < u64 > , pub total : Option < u64 > , }"
+        ));
+        assert!(code.contains("impl From < proto :: Order > for OrderProtoParts"));
+        assert!(code.contains(
+            "let Order { id , .. } = < Order as From < proto :: Order >> :: from (proto_struct) ;"
+        ));
+        assert!(code.contains("Self { id : Some (id) , total : None , }"));
+        assert!(code.contains(
+            "total : self . total . ok_or (:: protto :: parts :: MissingPart { field : \"total\" , }) ?"
+        ));
+
+        let code = generate_for(
+            "#[protto(proto_parts)] struct Order { #[protto(proto_optional, expect)] id: u64 }",
+        );
+        assert!(code.contains("impl TryFrom < proto :: Order > for OrderProtoParts"));
+        assert!(code.contains("type Error = OrderConversionError"));
+
+        let code = generate_for("#[protto(proto_parts, transparent)] struct OrderId { id: u64 }");
+        assert!(code.contains("compile_error"));
+    }
+
+    #[test]
+    fn test_expect_error_reads_missing_fields_under_the_current_options() {
+        let code = generate_for("struct Owned { #[protto(proto_optional, expect)] id: u64 }");
+        assert!(code.contains(
+            "proto_struct . id . or_else (:: protto :: options :: lenient_missing) . ok_or_else"
+        ));
+        assert!(!code.contains("from_proto_with_options"));
+    }
+
+    #[test]
+    fn test_none_as_error_generates_fallible_rust_to_proto() {
+        let code = generate_for(
+            "pub struct Owned { #[protto(proto_required, none_as = \"error\")] name: Option<String> }",
+        );
+        assert!(code.contains("impl TryFrom < Owned > for proto :: Owned"));
+        assert!(code.contains("type Error = OwnedConversionError"));
+        assert!(code.contains("pub enum OwnedConversionError"));
+        assert!(!code.contains("impl Into < proto :: Owned >"));
+    }
+
+    #[test]
+    fn test_none_as_empty_and_unset_choose_how_none_is_written() {
+        let code = generate_for(
+            "struct Profile { #[protto(proto_optional, none_as_empty)] nickname: Option<String>, \
+             #[protto(proto_optional, none_as_unset)] bio: Option<String>, \
+             #[protto(proto_required, none_as_empty)] motto: Option<String> }",
+        );
+        // field values may be wrapped in trace spans under `trace-runtime`
+        assert!(code.contains("Some (nickname . map (| v | v . into ()) . unwrap_or_default ())"));
+        assert!(code.contains("bio . map (| v | v . into ())"));
+        assert!(!code.contains("Some (bio . map"));
+        assert!(code.contains("motto . map (| v | v . into ()) . unwrap_or_default ()"));
+        assert!(!code.contains("Some (motto . map"));
+
+        let code = generate_for(
+            "struct Profile { #[protto(proto_required, none_as_unset)] motto: Option<String> }",
+        );
+        assert!(code.contains("none_as_unset leaves the proto field unset, but it is required"));
+
+        let code = generate_for("struct Profile { #[protto(none_as_empty)] motto: String }");
+        assert!(code.contains("apply to Option fields converted through the Option itself"));
+    }
+
+    #[test]
+    fn test_option_to_required_without_none_as_is_rejected() {
+        let code = generate_for("struct Owned { #[protto(proto_required)] name: Option<String> }");
+        assert!(code.contains("compile_error"));
+        assert!(code.contains("none_as"));
+    }
+
+    #[test]
+    fn test_schema_drift_test_checks_mapped_fields() {
+        let name: syn::Ident = syn::parse_str("Customer").unwrap();
+        let proto_type: syn::Path = syn::parse_str("proto::CustomerMessage").unwrap();
+        let fields: Vec<syn::Ident> = vec![
+            syn::parse_str("name").unwrap(),
+            syn::parse_str("postal_code").unwrap(),
+        ];
+
+        let code = generate_schema_drift_test(&name, &proto_type, &fields).to_string();
+        assert!(code.contains("fn __protto_schema_drift_Customer ()"));
+        assert!(code.contains("round_trip . name , original . name"));
+        assert!(code.contains("round_trip . postal_code , original . postal_code"));
+        assert!(!code.contains("return"));
+    }
+
+    #[cfg(feature = "schema-drift-tests")]
+    #[test]
+    fn test_schema_drift_test_fails_when_a_default_message_misses_an_expect_field() {
+        let code =
+            generate_for("struct Customer { #[protto(expect)] name: String, postal_code: String }");
+        assert!(code.contains("fn __protto_schema_drift_Customer ()"));
+        assert!(code.contains("the round trip could not run"));
+        assert!(code.contains("round_trip . postal_code , original . postal_code"));
+        assert!(!code.contains("return ;"));
+    }
+
+    #[test]
+    fn test_from_proto_fn_ref_borrows_and_runs_first() {
+        let code = generate_for(
+            "struct Summary { track: proto::Track, #[protto(proto_name = \"album\", from_proto_fn_ref = \"summarize\", to_proto_fn = \"skip\")] label: String }",
+        );
+        // under `trace-runtime` the custom function runs inside a span block
+        assert!(code.contains("summarize (& proto_struct . album)"));
+        let label_pos = code.find("label :").unwrap();
+        let track_pos = code.find("track : proto_struct . track").unwrap();
+        assert!(label_pos < track_pos);
+    }
+
+    #[test]
+    fn test_resolve_with_generates_async_from_proto() {
+        let code = generate_for(
+            "#[protto(resolver = Directory, error_type = LookupError, error_fn = \"LookupError::missing\")] \
+            struct Order { id: u64, #[protto(proto_name = \"owner_id\", resolve_with = \"Directory::fetch_user\")] owner: User }",
+        );
+        assert!(code.contains("pub async fn from_proto_async (proto_struct : proto :: Order , resolver : & Directory ,) -> Result < Self , LookupError >"));
+        assert!(code.contains(
+            "owner : Directory :: fetch_user (resolver , proto_struct . owner_id) . await ?"
+        ));
+        assert!(!code.contains("From < proto :: Order > for Order"));
+        assert!(code.contains("impl Into < proto :: Order > for Order"));
+    }
+
+    #[test]
+    fn test_resolve_with_requires_resolver_and_error_type() {
+        let code = generate_for(
+            "struct Order { #[protto(resolve_with = \"Directory::fetch_user\")] owner: User }",
+        );
+        assert!(code.contains("compile_error"));
+        assert!(code.contains("resolver"));
+    }
+
+    #[test]
+    fn test_error_mod_scopes_generated_error() {
+        let code = generate_for(
+            "#[protto(proto_name = \"CustomerMessage\", error_mod = \"customer_errors\")] \
+            pub struct Customer { #[protto(proto_required, none_as = \"error\")] street: Option<String> }",
+        );
+        assert!(code.contains("pub mod customer_errors { # [derive (Debug , Clone , PartialEq)] pub enum CustomerConversionError"));
+        assert!(code.contains("type Error = customer_errors :: CustomerConversionError"));
+        assert!(code.contains("customer_errors :: CustomerConversionError :: MissingField"));
+        assert!(
+            code.contains("impl From < String > for customer_errors :: CustomerConversionError")
+        );
+    }
+
+    #[test]
+    fn test_generated_error_inherits_struct_visibility() {
+        let code = generate_for("struct Owned { #[protto(expect(error))] name: String }");
+        assert!(code.contains("# [derive (Debug , Clone , PartialEq)] enum OwnedConversionError"));
+
+        let code = generate_for(
+            "#[protto(presence)] pub(crate) struct Owned { #[protto(expect(error))] name: String }",
+        );
+        assert!(code.contains("pub (crate) enum OwnedConversionError"));
+        assert!(code.contains("pub (crate) struct OwnedPresence"));
+
+        let code = generate_for(
+            "#[protto(error_vis = \"pub(crate)\")] \
+            pub struct Owned { #[protto(expect(error))] name: String }",
+        );
+        assert!(code.contains("pub (crate) enum OwnedConversionError"));
+
+        let code = generate_for(
+            "#[protto(error_mod = \"owned_errors\")] \
+            pub(super) struct Owned { #[protto(expect(error))] name: String }",
+        );
+        assert!(code.contains(
+            "pub (super) mod owned_errors { # [derive (Debug , Clone , PartialEq)] pub enum OwnedConversionError"
+        ));
+    }
+
+    #[test]
+    fn test_static_errors_carry_static_field_names() {
+        let code = generate_for(
+            "#[protto(proto_name = \"TrackWithOptionals\", static_errors)] \
+            struct LeanTrack { #[protto(expect(error))] name: String }",
+        );
+        assert!(code.contains("MissingField (& 'static str)"));
+        assert!(code.contains("LeanTrackConversionError :: MissingField (\"name\")"));
+        assert!(!code.contains("to_string ()"));
+        assert!(code.contains("impl From < & 'static str > for LeanTrackConversionError"));
+    }
+
+    #[test]
+    fn test_presence_tracks_optional_proto_fields() {
+        let code = generate_for(
+            "#[protto(presence)] pub struct Contact { id: u64, email: Option<String>, \
+            #[protto(expect)] address: Address, tags: Vec<String>, #[protto(ignore)] cache: u64 }",
+        );
+        assert!(
+            code.contains("pub struct ContactPresence { pub email : bool , pub address : bool , }")
+        );
+        assert!(code.contains("-> Result < (Self , ContactPresence) , ContactConversionError >"));
+        assert!(code.contains("email : proto_struct . email . is_some () ,"));
+        assert!(code.contains("Ok ((Self :: try_from (proto_struct) ? , presence))"));
+
+        let code = generate_for("#[protto(presence)] struct View<'a> { name: &'a str }");
+        assert!(code.contains("compile_error"));
+        assert!(code.contains("enables presence"));
+    }
+
+    #[test]
+    fn test_estimated_size_follows_field_shapes() {
+        let code = generate_for(
+            "#[protto(estimated_size)] struct Batch { id: u64, blob: Vec<u8>, tags: Vec<String>, \
+            labels: HashMap<String, String>, note: Option<String>, #[protto(ignore)] cache: u64 }",
+        );
+        if !cfg!(feature = "size-estimate") {
+            assert!(code.contains("requires the `size-estimate` feature"));
+            return;
+        }
+        assert!(code.contains("impl :: protto :: size :: EstimatedProtoSize for Batch"));
+        assert!(code.contains("let value = & self . id ; :: protto :: size :: field_size (value)"));
+        assert!(code.contains(":: protto :: size :: bytes_size (value)"));
+        assert!(code.contains(":: protto :: size :: repeated_size (value)"));
+        assert!(code.contains(":: protto :: size :: map_size (value)"));
+        assert!(code.contains(
+            "self . note . as_ref () . map_or (0 , | value | :: protto :: size :: field_size (value))"
+        ));
+        assert!(!code.contains("self . cache"));
+    }
+
+    #[test]
+    fn test_debug_roundtrip_asserts_in_debug_builds() {
+        let code =
+            generate_for("#[protto(debug_roundtrip)] struct User { id: u64, email: String }");
+        assert!(code.contains(
+            "# [cfg (debug_assertions)] let original_proto = :: core :: clone :: Clone :: clone (& proto_struct) ;"
+        ));
+        assert!(code.contains(
+            "# [cfg (debug_assertions)] assert_eq ! (Some (:: core :: convert :: Into :: < proto :: User > :: into (:: core :: clone :: Clone :: clone (& rust_struct) ,)) , Some (original_proto) , \"`User` does not round-trip"
+        ));
+
+        let code = generate_for(
+            "#[protto(debug_roundtrip)] struct User { #[protto(proto_required, none_as = \"error\")] id: Option<u64> }",
+        );
+        assert!(code.contains(
+            "< proto :: User as :: core :: convert :: TryFrom < Self >> :: try_from (:: core :: clone :: Clone :: clone (& rust_struct) ,) . ok ()"
+        ));
+
+        let code = generate_for("#[protto(debug_roundtrip)] struct View<'a> { name: &'a str }");
+        assert!(code.contains("borrowed structs and structs with resolve_with fields"));
+    }
+
+    #[test]
+    fn test_validate_checks_the_proto_before_converting() {
+        let code = generate_for("#[protto(validate)] struct User { id: u64, email: String }");
+        if !cfg!(feature = "validate") {
+            assert!(code.contains("requires the `validate` feature"));
+            return;
+        }
+        assert!(code.contains(
+            "fn try_from (proto_struct : proto :: User) -> Result < Self , Self :: Error > { \
+            :: protto :: validate :: ValidateProto :: validate_proto (& proto_struct) ? ;"
+        ));
+        assert!(code.contains("Validation (:: protto :: validate :: ValidationError) ,"));
+        assert!(code.contains(
+            "impl From < :: protto :: validate :: ValidationError > for UserConversionError"
+        ));
+
+        let code = generate_for(
+            "#[protto(validate, error_type = E, error_fn = \"E::missing\")] struct User { id: u64 }",
+        );
+        assert!(code.contains("type Error = E ;"));
+        assert!(!code.contains("UserConversionError"));
+
+        let code = generate_for("#[protto(validate, static_errors)] struct User { id: u64 }");
+        assert!(code.contains("static_errors cannot hold the error"));
+    }
+
+    #[test]
+    fn test_fields_mapping_one_proto_field_are_reported() {
+        let code = generate_for(
+            "struct User { #[protto(proto_name = \"name\")] display_name: String, \
+            name: String, id: u64 }",
+        );
+        assert!(code.contains(
+            "`display_name` maps to proto field `name`, as does `name`; rename one with proto_name"
+        ));
+        assert!(code.contains("`name` maps to proto field `name`, as does `display_name`;"));
+        assert!(!code.contains("impl From"));
+
+        let code = generate_for(
+            "#[protto(ignore = \"id\", proto_only(checksum = \"sum\"))] struct User { id: u64, \
+            #[protto(proto_name = \"checksum\")] check: u32 }",
+        );
+        assert!(code.contains("`id` maps to proto field `id`, which the struct-level ignore"));
+        assert!(code.contains("`check` maps to proto field `checksum`, which proto_only computes"));
+
+        let code = generate_for(
+            "struct User { #[protto(proto_name = \"name\")] display_name: String, \
+            #[protto(ignore)] name: String }",
+        );
+        assert!(!code.contains("maps to proto field"));
+    }
+
+    #[test]
+    fn test_proto_only_fields_are_computed_from_the_struct() {
+        let code = generate_for(
+            "#[protto(proto_only(checksum = \"compute_checksum\", r#type = \"Self::kind\"))] \
+            struct Upload { id: u64 }",
+        );
+        assert!(code.contains(
+            "fn into (self) -> proto :: Upload { let __proto_only_checksum = compute_checksum (& self) ; \
+            let __proto_only_type = Upload :: kind (& self) ; let Upload { id , } = self ;"
+        ));
+        assert!(code.contains("checksum : __proto_only_checksum , r#type : __proto_only_type ,"));
+
+        let code = generate_for(
+            "#[protto(ignore = \"checksum\", proto_only(checksum = \"compute_checksum\"))] \
+            struct Upload { id: u64 }",
+        );
+        assert!(code.contains("listed in both ignore and proto_only"));
+    }
+
+    #[test]
+    fn test_passthrough_converts_the_message_to_itself() {
+        let code = generate_passthrough_implementations(
+            &syn::parse_quote!(Ping),
+            &syn::Generics::default(),
+            None,
+            false,
+        )
+        .to_string();
+        assert!(!code.contains("impl From"));
+        assert!(!code.contains("ConversionError"));
+        if cfg!(feature = "json") {
+            assert!(code.contains("type Proto = Self ;"));
+        }
+
+        let code = generate_passthrough_implementations(
+            &syn::parse_quote!(Ping),
+            &syn::Generics::default(),
+            None,
+            true,
+        )
+        .to_string();
+        if !cfg!(feature = "size-estimate") {
+            assert!(code.contains("requires the `size-estimate` feature"));
+            return;
+        }
+        assert!(code.contains(":: protto :: size :: prost :: Message :: encoded_len (self)"));
+    }
+
+    #[test]
+    fn test_any_type_url_packs_proto_message() {
+        let code = generate_for(
+            "#[protto(proto_name = \"OrderPlaced\", \
+            any_type_url = \"type.googleapis.com/shop.OrderPlaced\")] struct Placed { id: u64 }",
+        );
+        if !cfg!(feature = "any") {
+            assert!(code.contains("requires the `any` feature"));
+            return;
+        }
+        assert!(code.contains("impl :: protto :: any :: ToProtoAny for Placed"));
+        assert!(code.contains(
+            "let proto_struct : proto :: OrderPlaced = :: core :: clone :: Clone :: clone (self) . into () ;"
+        ));
+        assert!(code.contains(
+            ":: protto :: any :: pack (\"type.googleapis.com/shop.OrderPlaced\" , & proto_struct)"
+        ));
+    }
+
+    #[test]
+    fn test_try_from_proto_fn_panics_on_the_result_under_expect_panic() {
+        let code = generate_for(
+            "struct StrictHeader { \
+            #[protto(try_from_proto_fn = \"parse_request_id\", \
+            to_proto_fn = \"format_request_id\", expect(panic))] request_id: u64 }",
+        );
+        assert!(code.contains("parse_request_id (proto_struct . request_id) . expect ("));
+        assert!(!code.contains("request_id . expect"));
+    }
+
+    #[test]
+    fn test_custom_fns_take_a_renamed_required_field_as_is() {
+        // as `use_rules` marks a field the build metadata shows is required
+        let code = generate_for(
+            "struct LedgerEntry { \
+            #[protto(proto_name = \"amount_cents\", from_proto_fn = \"from_cents\", \
+            to_proto_fn = \"to_cents\", proto_required)] amount: Amount }",
+        );
+        assert!(code.contains("from_cents (proto_struct . amount_cents)"));
+        assert!(code.contains("to_cents (amount)"));
+        assert!(!code.contains("Some (to_cents"));
+        assert!(!code.contains("expect"));
+    }
+
+    #[test]
+    fn test_depends_on_converts_dependencies_first() {
+        let code = generate_for(
+            "struct Payment { \
+            #[protto(from_proto_fn = \"to_amount\", depends_on = \"currency\", proto_required)] \
+            amount: Amount, \
+            currency: String, \
+            memo: String }",
+        );
+        let bind_currency = code.find("let currency =").expect("currency is bound");
+        let bind_amount = code.find("let amount =").expect("amount is bound");
+        assert!(bind_currency < bind_amount);
+        assert!(code.contains("to_amount (proto_struct . amount , & currency)"));
+        assert!(code.contains("Self { amount , currency ,"));
+
+        let code = generate_for(
+            "struct Payment { \
+            #[protto(from_proto_fn = \"to_amount\", depends_on = \"rate\")] amount: Amount, \
+            #[protto(from_proto_fn = \"to_rate\", depends_on = \"amount\")] rate: Rate }",
+        );
+        assert!(code.contains("depends_on cycle: amount -> rate -> amount"));
+
+        let code = generate_for(
+            "struct Payment { \
+            #[protto(from_proto_fn = \"to_fee\", depends_on = \"curency\")] fee: Amount }",
+        );
+        assert!(code.contains("`fee` depends_on `curency`, which is not a field of `Payment`"));
+    }
+
+    #[test]
+    fn test_depends_on_reads_a_renamed_required_field() {
+        let code = generate_for(
+            "struct Quote { \
+            #[protto(proto_name = \"amount_minor\", from_proto_fn = \"to_amount\", \
+            to_proto_fn = \"from_amount\", depends_on = \"currency\", proto_required)] \
+            amount: Amount, \
+            currency: String }",
+        );
+        assert!(code.contains("to_amount (proto_struct . amount_minor , & currency)"));
+        assert!(code.contains("from_amount (amount)"));
+        assert!(!code.contains("Some (from_amount"));
+    }
+
+    #[test]
+    fn test_manual_fields_go_through_from_proto_parts() {
+        let code = generate_for(
+            "struct Schedule { name: String, #[protto(manual)] start: u64, \
+            #[protto(manual)] end: u64 }",
+        );
+        let parts_trait =
+            "< Schedule as :: protto :: parts :: FromProtoParts < proto :: Schedule >>";
+        assert!(code.contains(&format!(
+            "let (start , end) : (u64 , u64) = {parts_trait} :: from_proto_parts (& proto_struct) ;"
+        )));
+        assert!(code.contains("Self { name : proto_struct . name , start , end , }"));
+        assert!(code.contains("start : :: core :: default :: Default :: default ()"));
+        assert!(code.contains(&format!(
+            "{parts_trait} :: into_proto_parts ((start , end) , & mut proto_struct) ; proto_struct"
+        )));
+
+        let code = generate_for("struct Schedule { #[protto(manual)] start: u64 }");
+        assert!(code.contains("let start : u64 ="));
+        assert!(code.contains(":: into_proto_parts (start , & mut proto_struct)"));
+    }
+
+    #[test]
+    fn test_trace_runtime_spans_nested_fields_only() {
+        let code = generate_for(
+            "struct Order { id: u64, tracks: Vec<Track>, #[protto(expect)] header: Header }",
+        );
+        let span = |name: &str, field: &str| {
+            format!(
+                ":: protto :: trace :: tracing :: trace_span ! (\"{name}\" , \"struct\" = \"Order\" , field = \"{field}\")"
+            )
+        };
+        assert_eq!(
+            code.contains(&span("from_proto", "tracks")),
+            cfg!(feature = "trace-runtime")
+        );
+        assert_eq!(
+            code.contains(&span("into_proto", "header")),
+            cfg!(feature = "trace-runtime")
+        );
+        assert!(!code.contains("field = \"id\""));
+    }
+
+    #[test]
+    fn test_pure_custom_fns_assert_signatures() {
+        let code = generate_for(
+            "#[protto(custom_fns_are = \"pure\")] struct Track { \
+            #[protto(from_proto_fn = \"from_ms\", to_proto_fn = \"to_ms\")] duration: Duration, \
+            #[protto(proto_name = \"header\", from_proto_fn_ref = \"id_of\")] id: String, \
+            title: String }",
+        );
+        assert!(code.contains("fn __protto_custom_fn_signatures ()"));
+        assert!(code.contains("let _ : fn (_) -> Duration = from_ms ;"));
+        assert!(code.contains("let _ : fn (Duration) -> _ = to_ms ;"));
+        assert!(code.contains("let _ : fn (& _) -> String = id_of ;"));
+
+        let code = generate_for(
+            "struct Track { #[protto(from_proto_fn = \"from_ms\")] duration: Duration }",
+        );
+        assert!(!code.contains("__protto_custom_fn_signatures"));
+    }
+
+    #[test]
+    fn test_unrecognized_message_fields_checked_for_type_aliases() {
+        let code = generate_for(
+            "struct Account { home: Home, header: proto::Header, id: u64, \
+            #[protto(from_proto_fn = \"f\", to_proto_fn = \"g\")] region: Region }",
+        );
+        assert!(code.contains("fn __protto_message_fields (proto : & proto :: Account)"));
+        assert!(code.contains(":: protto :: type_alias :: message_field (& proto . home) ;"));
+        assert_eq!(code.matches("message_field (").count(), 1);
+
+        let code = generate_for("struct Account { id: u64, name: String }");
+        assert!(!code.contains("__protto_message_fields"));
+    }
+
+    #[test]
+    fn test_also_proto_adds_conversions_per_message() {
+        let code = generate_for(
+            "#[protto(module = \"proto_v1\", proto_name = \"User\", \
+            also_proto = \"proto_v2::User, api::v3::Member\")] \
+            struct User { #[protto(expect)] id: u64, name: String }",
+        );
+        for proto_type in [
+            "proto_v1 :: User",
+            "proto_v2 :: User",
+            "api :: v3 :: Member",
+        ] {
+            assert!(code.contains(&format!("impl TryFrom < {proto_type} > for User")));
+            assert!(code.contains(&format!("impl Into < {proto_type} > for User")));
+        }
+        assert_eq!(code.matches("enum UserConversionError").count(), 1);
+    }
+
+    #[test]
+    fn test_view_borrows_strings_and_bytes() {
+        let code = generate_for(
+            "#[protto(view)] pub struct Article { pub id: u64, pub title: String, body: Vec<u8>, \
+            pub subtitle: Option<String>, pub tags: Vec<String>, \
+            #[protto(proto_name = \"slug\")] pub handle: String, \
+            #[protto(expect)] pub rank: u32, pub author: Author }",
+        );
+        assert!(code.contains("pub struct ArticleView < 'a >"));
+        assert!(code.contains("pub id : u64 , pub title : & 'a str , body : & 'a [u8] ,"));
+        assert!(code.contains("pub subtitle : :: core :: option :: Option < & 'a str > ,"));
+        assert!(code.contains("pub tags : :: std :: vec :: Vec < & 'a str > ,"));
+        assert!(code.contains("handle : proto . slug . as_str () ,"));
+        assert!(!code.contains("pub rank : u32 , proto"));
+        assert!(!code.contains("author : proto"));
+        assert!(
+            code.contains(
+                "pub fn to_owned (& self) -> Result < Article , ArticleConversionError >"
+            )
+        );
+        assert!(code.contains("impl < 'a > From < & 'a proto :: Article > for ArticleView < 'a >"));
+
+        let code = generate_for("#[protto(view)] struct Wrapper<T> { id: u64, value: T }");
+        assert!(code.contains("generic structs and structs with resolve_with fields"));
+    }
+
+    #[test]
+    fn test_preserve_unknown_is_the_base_of_the_proto_message() {
+        let code = generate_for(
+            "#[protto(ignore = \"shard\")] struct User { id: u64, \
+            #[protto(preserve_unknown)] original: Option<proto::User> }",
+        );
+        assert!(code.contains(
+            "let original = :: core :: option :: Option :: Some (:: core :: clone :: Clone :: clone \
+            (& proto_struct)) ;"
+        ));
+        assert!(code.contains("Self { id : proto_struct . id , original , }"));
+        assert!(code.contains("let User { id , original , } = self ;"));
+        assert!(code.contains(
+            "proto :: User { id : id , .. :: core :: option :: Option :: unwrap_or_default (original) }"
+        ));
+        assert!(!code.contains("shard"));
+
+        let code = generate_for(
+            "struct User { #[protto(preserve_unknown)] a: Option<proto::User>, \
+            #[protto(preserve_unknown)] b: Option<proto::User> }",
+        );
+        assert!(code.contains("preserves its proto message in more than one field (a, b)"));
+    }
+
+    #[test]
+    fn test_custom_fns_name_generic_params_and_self() {
+        let code = generate_for(
+            "#[protto(custom_fns_are = \"pure\")] struct Tagged<T: Codec> { \
+            #[protto(from_proto_fn = \"decode_as::<T>\", to_proto_fn = \"<T as Codec>::encode\")] \
+            value: T, \
+            #[protto(from_proto_fn = \"Self::id_from_proto\", to_proto_fn = \"cast::<Self, u64>\")] \
+            id: u64 }",
+        );
+        assert!(code.contains("decode_as :: < T > (proto_struct . value"));
+        assert!(code.contains("< T as Codec > :: encode (value)"));
+        assert!(code.contains("< Tagged < T > > :: id_from_proto (proto_struct . id)"));
+        assert!(code.contains("cast :: < Tagged < T > , u64 > (id)"));
+        assert!(code.contains("let _ : fn (_) -> u64 = < Tagged < T > > :: id_from_proto ;"));
+        assert!(!code.contains("Self :: id_from_proto"));
+    }
+
+    #[test]
+    fn test_presence_of_reads_before_the_field_moves() {
+        let code = generate_for(
+            "struct Record { #[protto(presence_of = \"audit\")] has_audit: bool, id: u64 }",
+        );
+        assert!(code.contains("Self { has_audit : proto_struct . audit . is_some () , id"));
+        assert!(code.contains("audit : :: core :: option :: Option :: None"));
+        assert!(code.contains("let Record { has_audit : _ , id , } = self ;"));
+
+        let code = generate_for(
+            "struct Record { audit: Option<Audit>, \
+            #[protto(presence_of = \"audit\")] has_audit: bool }",
+        );
+        assert!(code.contains("Self { has_audit : proto_struct . audit . is_some () , audit :"));
+        assert!(code.contains("let Record { audit , has_audit : _ , } = self ;"));
+        assert!(!code.contains("Option :: None"));
+    }
+
+    #[test]
+    fn test_sources_convert_the_tuple_of_messages() {
+        let code = generate_for(
+            "#[protto(sources(header = \"proto::Header\", body = \"proto::Body\"))] \
+            struct Envelope { #[protto(source = \"header\")] id: String, \
+            #[protto(source = \"body\")] text: String, #[protto(ignore)] cache: Vec<u8> }",
+        );
+        assert!(code.contains("impl From < (proto :: Header , proto :: Body ,) > for Envelope"));
+        assert!(code.contains("impl From < Envelope > for (proto :: Header , proto :: Body ,)"));
+        assert!(code.contains(
+            "let proto_struct = protos . 0 ; let id = proto_struct . id ; \
+            let proto_struct = protos . 1 ; let text = proto_struct . text ;"
+        ));
+        assert!(code.contains("let Envelope { id , text , cache : _ , } = my_struct ;"));
+        assert!(
+            code.contains("(proto :: Header { id : id , } , proto :: Body { text : text , } ,)")
+        );
+    }
+
+    #[test]
+    fn test_kind_overrides_type_classification() {
+        // unregistered `Level` reads as a message and `levels::Level` as a scalar until `kind`
+        // says otherwise
+        let code = generate_for(
+            "struct Reading { #[protto(kind = \"primitive\")] millis: Millis, \
+            #[protto(kind = \"enum\")] low: Level, high: Level, \
+            #[protto(kind = \"message\")] origin: geo::Point }",
+        );
+        assert!(code.contains("millis : proto_struct . millis ,"));
+        assert!(code.contains("low : proto_struct . low . into () ,"));
+        assert!(code.contains("proto_struct . high . expect"));
+        assert!(code.contains("proto_struct . origin . expect"));
+        assert!(code.contains("Some (:: protto :: __repr_to_proto ! (geo :: Point , origin))"));
+    }
+
+    #[test]
+    fn test_error_fns_signatures_checked_once_per_function() {
+        let code = generate_for(
+            "#[protto(error_type = E, error_fn = \"E::missing\")] struct Order { \
+            #[protto(expect, error_fn = \"E::no_id\")] id: u64, \
+            #[protto(expect)] header: Header, #[protto(expect)] footer: Header, name: String }",
+        );
+        assert!(code.contains("fn __protto_error_fn_signatures ()"));
+        let check = |call: &str| {
+            format!(
+                "let _ = | field : & 'static str | -> :: core :: result :: Result < () , E > \
+                {{ :: core :: result :: Result :: Err ({call}) ? }} ;"
+            )
+        };
+        assert!(code.contains(&check("E :: no_id (field)")));
+        assert_eq!(code.matches(&check("E :: missing (field)")).count(), 1);
+
+        let code = generate_for(
+            "#[protto(error_fn_with_value = \"rejected\")] struct Order { #[protto(expect)] id: u64 }",
+        );
+        assert!(code.contains(
+            &check("rejected (field , & ())").replace(", E >", ", OrderConversionError >")
+        ));
+
+        let code = generate_for("struct Order { #[protto(expect)] id: u64 }");
+        assert!(!code.contains("__protto_error_fn_signatures"));
+    }
+
+    #[test]
+    fn test_proto_json_impl_names_proto_message() {
+        let name: syn::Ident = syn::parse_str("View").unwrap();
+        let generics: syn::Generics = syn::parse_str("<'a>").unwrap();
+        let proto_type: syn::Path = syn::parse_str("proto::ViewMessage").unwrap();
+
+        let code = generate_proto_json_impl(&name, &generics, &proto_type).to_string();
+        assert_eq!(
+            code,
+            "impl < 'a > :: protto :: json :: ProtoJson for View < 'a > { type Proto = proto :: ViewMessage ; }"
+        );
+    }
+
+    #[test]
+    fn test_both_conversions_add_panicking_methods() {
+        let code = generate_for(
+            "#[protto(conversions = \"both\")] struct Owned { #[protto(expect)] name: String, \
+            #[protto(proto_required, none_as = \"error\")] street: Option<String> }",
+        );
+        assert!(code.contains("impl TryFrom < proto :: Owned > for Owned"));
+        assert!(code.contains("impl TryFrom < Owned > for proto :: Owned"));
+        assert!(code.contains("pub fn from_proto (proto_struct : proto :: Owned) -> Self { Self :: try_from (proto_struct) . unwrap_or_else (| err | panic ! (\"failed to convert proto::Owned into Owned: {err:?}\")) }"));
+        assert!(code.contains(
+            "pub fn into_proto (self) -> proto :: Owned { proto :: Owned :: try_from (self)"
+        ));
+
+        let code = generate_for("#[protto(conversions = \"both\")] struct Owned { name: String }");
+        assert!(!code.contains("fn from_proto ("));
+        assert!(!code.contains("fn into_proto ("));
+    }
+
+    #[test]
+    fn test_transparent_struct_delegates_to_its_field() {
+        let code = generate_for(
+            "#[protto(transparent, proto_name = \"Uuid\")] struct UserId { uuid: Uuid }",
+        );
+        assert_eq!(
+            code,
+            "impl From < proto :: Uuid > for UserId { fn from (proto : proto :: Uuid) -> Self { Self { uuid : proto . into () } } } \
+            impl From < UserId > for proto :: Uuid { fn from (value : UserId) -> Self { value . uuid . into () } }"
+        );
+    }
+
+    #[test]
+    fn test_owned_struct_generates_both_directions() {
+        let code = generate_for("struct Owned { name: String }");
+        assert!(code.contains("impl From < proto :: Owned > for Owned"));
+        assert!(code.contains("impl Into < proto :: Owned > for Owned"));
+    }
+}
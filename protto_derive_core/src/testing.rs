@@ -0,0 +1,84 @@
+//! The field analysis behind each generated conversion, exposed under the `testing` feature so
+//! tests outside this crate can check which strategy a field declaration selects without
+//! expanding and compiling it.
+//!
+//! ```rust
+//! use protto_derive_core::testing::{ErrorMode, FieldConversionStrategy, OptionStrategy};
+//!
+//! let input: syn::DeriveInput = syn::parse_quote! {
+//!     #[protto(module = "proto", proto_name = "Track")]
+//!     struct Track {
+//!         #[protto(proto_optional, expect)]
+//!         name: String,
+//!     }
+//! };
+//! let analysis = protto_derive_core::testing::analyze_field(&input, "name");
+//! assert_eq!(
+//!     analysis.strategy,
+//!     FieldConversionStrategy::Option(OptionStrategy::Unwrap(ErrorMode::Error))
+//! );
+//! assert!(analysis.validation.is_ok());
+//! ```
+
+use crate::analysis::macro_input::ParsedInput;
+use crate::field::FieldProcessingContext;
+
+pub use crate::analysis::expect_analysis::ExpectMode;
+pub use crate::analysis::optionality::{FieldOptionality, NonePolicy};
+pub use crate::field::{
+    CollectionStrategy, DirectStrategy, ErrorMode, FieldConversionStrategy, FieldGenerationError,
+    OptionStrategy, ProtoFieldInfo, ProtoMapping, RustFieldInfo,
+};
+
+/// What the derive concludes about one field: its rust and proto shapes, the conversion strategy
+/// chosen from them, and whether that strategy holds up for the field's attributes.
+#[derive(Debug)]
+pub struct FieldAnalysis {
+    pub rust: RustFieldInfo,
+    pub proto: ProtoFieldInfo,
+    pub strategy: FieldConversionStrategy,
+    pub validation: Result<(), FieldGenerationError>,
+}
+
+/// Analyzes the named field of a struct deriving `Protto`, as the derive would for its
+/// conversions. Panics when `input` is not a struct with that field.
+pub fn analyze_field(input: &syn::DeriveInput, field_name: &str) -> FieldAnalysis {
+    let parsed_input = ParsedInput::new(input.clone());
+    let syn::Data::Struct(data_struct) = &input.data else {
+        panic!("{} is not a struct", input.ident);
+    };
+    let field = data_struct
+        .fields
+        .iter()
+        .find(|field| {
+            field
+                .ident
+                .as_ref()
+                .is_some_and(|ident| ident == field_name)
+        })
+        .unwrap_or_else(|| panic!("{} has no field named {field_name}", input.ident));
+
+    let ctx = FieldProcessingContext::new(
+        &parsed_input.name,
+        &parsed_input.generics,
+        field,
+        &parsed_input.struct_level_error_type,
+        &parsed_input.struct_level_error_fn,
+        parsed_input.struct_level_error_fn_with_value,
+        &parsed_input.error_mod,
+        parsed_input.static_errors,
+        &parsed_input.proto_module,
+        &parsed_input.proto_name,
+    );
+    let rust = RustFieldInfo::analyze(&ctx, field);
+    let proto = ProtoFieldInfo::infer_from(&ctx, field, &rust);
+    let strategy = FieldConversionStrategy::from_field_info(&ctx, field, &rust, &proto);
+    let validation = strategy.validate_for_context(&ctx, &rust, &proto);
+
+    FieldAnalysis {
+        rust,
+        proto,
+        strategy,
+        validation,
+    }
+}
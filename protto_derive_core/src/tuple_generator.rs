@@ -0,0 +1,90 @@
+use crate::analysis::type_analysis::{self, NewtypeCollection};
+use quote::quote;
+
+pub fn generate_tuple_implementations(
+    name: &syn::Ident,
+    fields_unnamed: &syn::FieldsUnnamed,
+) -> proc_macro2::TokenStream {
+    if fields_unnamed.unnamed.len() != 1 {
+        panic!(
+            "Protto only supports tuple structs with exactly one field, found {}",
+            fields_unnamed.unnamed.len()
+        );
+    }
+
+    let inner_type = &fields_unnamed.unnamed[0].ty;
+
+    // a transparent field of a newtype over a collection converts the elements, so it reaches
+    // the collection through the trait rather than through `From`
+    let collection_impl = wrapped_collection(fields_unnamed).map(|_| {
+        quote! {
+            impl ::protto::newtype::CollectionNewtype for #name {
+                type Collection = #inner_type;
+
+                fn from_collection(collection: #inner_type) -> Self {
+                    #name(collection)
+                }
+
+                fn into_collection(self) -> #inner_type {
+                    self.0
+                }
+            }
+        }
+    });
+
+    quote! {
+        impl From<#inner_type> for #name {
+            fn from(value: #inner_type) -> Self {
+                #name(value)
+            }
+        }
+
+        impl From<#name> for #inner_type {
+            fn from(my: #name) -> Self {
+                my.0
+            }
+        }
+
+        #collection_impl
+    }
+}
+
+/// The collection a single-field tuple struct wraps, e.g. `Vec<Tag>` in `struct Tags(Vec<Tag>)`
+pub fn wrapped_collection(fields_unnamed: &syn::FieldsUnnamed) -> Option<NewtypeCollection> {
+    let [field] = fields_unnamed.unnamed.iter().collect::<Vec<_>>()[..] else {
+        return None;
+    };
+
+    if type_analysis::is_vec_type(&field.ty) {
+        Some(NewtypeCollection::Vec)
+    } else if type_analysis::is_map_type(&field.ty) {
+        Some(NewtypeCollection::Map)
+    } else {
+        None
+    }
+}
+
+/// Struct-level `transparent`: the newtype converts to and from the proto message by delegating
+/// to its field's own conversion, e.g. `struct UserId(Uuid)` with `Uuid` converting from
+/// `proto::Uuid`, or wrapping `proto::Uuid` itself
+pub fn generate_transparent_tuple_implementations(
+    name: &syn::Ident,
+    proto_path: &syn::Path,
+    generics: &syn::Generics,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics From<#proto_path> for #name #ty_generics #where_clause {
+            fn from(proto: #proto_path) -> Self {
+                #name(proto.into())
+            }
+        }
+
+        impl #impl_generics From<#name #ty_generics> for #proto_path #where_clause {
+            fn from(value: #name #ty_generics) -> Self {
+                value.0.into()
+            }
+        }
+    }
+}
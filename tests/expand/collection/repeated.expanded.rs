@@ -0,0 +1,71 @@
+use protto::Protto;
+use std::collections::HashMap;
+mod proto {
+    pub struct Track {
+        pub track_id: u64,
+    }
+    pub struct State {
+        pub tracks: Vec<Track>,
+        pub raw_tracks: Vec<Track>,
+        pub tags: Vec<String>,
+        pub by_name: std::collections::HashMap<String, Track>,
+    }
+}
+pub struct Track {
+    pub track_id: u64,
+}
+impl From<proto::Track> for Track {
+    fn from(proto_struct: proto::Track) -> Self {
+        Self {
+            track_id: proto_struct.track_id,
+        }
+    }
+}
+impl Into<proto::Track> for Track {
+    fn into(self) -> proto::Track {
+        let Track { track_id } = self;
+        proto::Track { track_id: track_id }
+    }
+}
+pub struct State {
+    pub tracks: Vec<Track>,
+    pub raw_tracks: Vec<proto::Track>,
+    pub tags: Vec<String>,
+    pub by_name: HashMap<String, proto::Track>,
+}
+impl From<proto::State> for State {
+    fn from(proto_struct: proto::State) -> Self {
+        Self {
+            tracks: ::protto::collection::convert_vec(
+                proto_struct.tracks,
+                |element| {
+                    #[allow(unused_imports)]
+                    use ::protto::repr::{ViaInto as _, ViaRepr as _};
+                    (&&::protto::repr::Select::<Track>::new()).select_from_proto(element)
+                },
+            ),
+            raw_tracks: proto_struct.raw_tracks,
+            tags: proto_struct.tags.into_iter().map(Into::into).collect(),
+            by_name: proto_struct.by_name,
+        }
+    }
+}
+impl Into<proto::State> for State {
+    fn into(self) -> proto::State {
+        let State { tracks, raw_tracks, tags, by_name } = self;
+        proto::State {
+            tracks: ::protto::collection::convert_vec(
+                tracks,
+                |element| {
+                    #[allow(unused_imports)]
+                    use ::protto::repr::{ViaInto as _, ViaRepr as _};
+                    (&&::protto::repr::Select::<Track>::new()).select_to_proto(element)
+                },
+            ),
+            raw_tracks: raw_tracks,
+            tags: tags.into_iter().map(Into::into).collect(),
+            by_name: by_name,
+        }
+    }
+}
+fn main() {}
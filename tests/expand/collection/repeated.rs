@@ -0,0 +1,30 @@
+use protto::Protto;
+use std::collections::HashMap;
+
+mod proto {
+    pub struct Track {
+        pub track_id: u64,
+    }
+
+    pub struct State {
+        pub tracks: Vec<Track>,
+        pub raw_tracks: Vec<Track>,
+        pub tags: Vec<String>,
+        pub by_name: std::collections::HashMap<String, Track>,
+    }
+}
+
+#[derive(Protto)]
+pub struct Track {
+    pub track_id: u64,
+}
+
+#[derive(Protto)]
+pub struct State {
+    pub tracks: Vec<Track>,
+    pub raw_tracks: Vec<proto::Track>,
+    pub tags: Vec<String>,
+    pub by_name: HashMap<String, proto::Track>,
+}
+
+fn main() {}
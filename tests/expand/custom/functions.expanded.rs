@@ -0,0 +1,36 @@
+use protto::Protto;
+mod proto {
+    pub struct Event {
+        pub id: u64,
+        pub labels: String,
+    }
+}
+pub struct Event {
+    pub id: u64,
+    #[protto(from_proto_fn = "split_labels", to_proto_fn = "join_labels")]
+    pub labels: Vec<String>,
+}
+impl From<proto::Event> for Event {
+    fn from(proto_struct: proto::Event) -> Self {
+        Self {
+            id: proto_struct.id,
+            labels: split_labels(proto_struct.labels),
+        }
+    }
+}
+impl Into<proto::Event> for Event {
+    fn into(self) -> proto::Event {
+        let Event { id, labels } = self;
+        proto::Event {
+            id: id,
+            labels: join_labels(labels),
+        }
+    }
+}
+fn split_labels(labels: String) -> Vec<String> {
+    labels.split(',').map(str::to_string).collect()
+}
+fn join_labels(labels: Vec<String>) -> String {
+    labels.join(",")
+}
+fn main() {}
@@ -0,0 +1,25 @@
+use protto::Protto;
+
+mod proto {
+    pub struct Event {
+        pub id: u64,
+        pub labels: String,
+    }
+}
+
+#[derive(Protto)]
+pub struct Event {
+    pub id: u64,
+    #[protto(from_proto_fn = "split_labels", to_proto_fn = "join_labels")]
+    pub labels: Vec<String>,
+}
+
+fn split_labels(labels: String) -> Vec<String> {
+    labels.split(',').map(str::to_string).collect()
+}
+
+fn join_labels(labels: Vec<String>) -> String {
+    labels.join(",")
+}
+
+fn main() {}
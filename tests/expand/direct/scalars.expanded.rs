@@ -0,0 +1,45 @@
+use protto::Protto;
+mod proto {
+    pub struct Track {
+        pub track_id: u64,
+        pub title: String,
+        pub plays: u32,
+    }
+}
+pub struct TrackId(pub u64);
+impl From<u64> for TrackId {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+impl From<TrackId> for u64 {
+    fn from(value: TrackId) -> Self {
+        value.0
+    }
+}
+pub struct Track {
+    #[protto(transparent)]
+    pub track_id: TrackId,
+    pub title: String,
+    pub plays: u32,
+}
+impl From<proto::Track> for Track {
+    fn from(proto_struct: proto::Track) -> Self {
+        Self {
+            track_id: TrackId::from(proto_struct.track_id),
+            title: proto_struct.title,
+            plays: proto_struct.plays,
+        }
+    }
+}
+impl Into<proto::Track> for Track {
+    fn into(self) -> proto::Track {
+        let Track { track_id, title, plays } = self;
+        proto::Track {
+            track_id: track_id.into(),
+            title: title,
+            plays: plays,
+        }
+    }
+}
+fn main() {}
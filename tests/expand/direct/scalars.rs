@@ -0,0 +1,33 @@
+use protto::Protto;
+
+mod proto {
+    pub struct Track {
+        pub track_id: u64,
+        pub title: String,
+        pub plays: u32,
+    }
+}
+
+pub struct TrackId(pub u64);
+
+impl From<u64> for TrackId {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<TrackId> for u64 {
+    fn from(value: TrackId) -> Self {
+        value.0
+    }
+}
+
+#[derive(Protto)]
+pub struct Track {
+    #[protto(transparent)]
+    pub track_id: TrackId,
+    pub title: String,
+    pub plays: u32,
+}
+
+fn main() {}
@@ -0,0 +1,88 @@
+use protto::Protto;
+mod proto {
+    pub enum Status {
+        Ok = 0,
+        NotFound = 1,
+    }
+}
+pub enum Status {
+    Ok,
+    NotFound,
+}
+impl From<i32> for Status {
+    fn from(value: i32) -> Self {
+        let proto_val = <proto::Status>::from_i32(value)
+            .unwrap_or_else(|| {
+                ::core::panicking::panic_fmt(
+                    format_args!("Unknown enum value: {0}", value),
+                );
+            });
+        let proto_str = proto_val.as_str_name();
+        match proto_str {
+            candidate if candidate == "Ok" || candidate == "STATUS_OK"
+                || candidate == "OK" => Status::Ok,
+            candidate if candidate == "NotFound" || candidate == "STATUS_NOT_FOUND"
+                || candidate == "NOT_FOUND" => Status::NotFound,
+            _ => {
+                ::core::panicking::panic_fmt(
+                    format_args!(
+                        "No matching Rust variant for proto enum string: {0}", proto_str
+                    ),
+                );
+            }
+        }
+    }
+}
+impl From<Status> for i32 {
+    fn from(rust_enum: Status) -> Self {
+        let proto: proto::Status = rust_enum.into();
+        proto as i32
+    }
+}
+impl From<Status> for proto::Status {
+    fn from(rust_enum: Status) -> Self {
+        match rust_enum {
+            Status::Ok => {
+                <proto::Status>::from_str_name("STATUS_OK")
+                    .or_else(|| <proto::Status>::from_str_name("OK"))
+                    .unwrap_or_else(|| {
+                        ::core::panicking::panic_fmt(
+                            format_args!(
+                                "No matching proto variant for {0:?}", rust_enum
+                            ),
+                        );
+                    })
+            }
+            Status::NotFound => {
+                <proto::Status>::from_str_name("STATUS_NOT_FOUND")
+                    .or_else(|| <proto::Status>::from_str_name("NOT_FOUND"))
+                    .unwrap_or_else(|| {
+                        ::core::panicking::panic_fmt(
+                            format_args!(
+                                "No matching proto variant for {0:?}", rust_enum
+                            ),
+                        );
+                    })
+            }
+        }
+    }
+}
+impl From<proto::Status> for Status {
+    fn from(proto_enum: proto::Status) -> Self {
+        let proto_str = proto_enum.as_str_name();
+        match proto_str {
+            candidate if candidate == "Ok" || candidate == "STATUS_OK"
+                || candidate == "OK" => Status::Ok,
+            candidate if candidate == "NotFound" || candidate == "STATUS_NOT_FOUND"
+                || candidate == "NOT_FOUND" => Status::NotFound,
+            _ => {
+                ::core::panicking::panic_fmt(
+                    format_args!(
+                        "No matching Rust variant for proto enum string: {0}", proto_str
+                    ),
+                );
+            }
+        }
+    }
+}
+fn main() {}
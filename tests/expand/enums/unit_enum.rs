@@ -0,0 +1,16 @@
+use protto::Protto;
+
+mod proto {
+    pub enum Status {
+        Ok = 0,
+        NotFound = 1,
+    }
+}
+
+#[derive(Protto)]
+pub enum Status {
+    Ok,
+    NotFound,
+}
+
+fn main() {}
@@ -0,0 +1,139 @@
+use protto::Protto;
+mod proto {
+    pub struct Track {
+        pub track_id: u64,
+    }
+    pub struct Playlist {
+        pub name: Option<String>,
+        pub featured: Option<Track>,
+    }
+}
+pub struct Track {
+    pub track_id: u64,
+}
+impl From<proto::Track> for Track {
+    fn from(proto_struct: proto::Track) -> Self {
+        Self {
+            track_id: proto_struct.track_id,
+        }
+    }
+}
+impl Into<proto::Track> for Track {
+    fn into(self) -> proto::Track {
+        let Track { track_id } = self;
+        proto::Track { track_id: track_id }
+    }
+}
+pub struct Playlist {
+    #[protto(expect(error))]
+    pub name: String,
+    #[protto(expect(error))]
+    pub featured: Track,
+}
+pub enum PlaylistConversionError {
+    MissingField(String),
+}
+#[automatically_derived]
+impl ::core::fmt::Debug for PlaylistConversionError {
+    #[inline]
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        match self {
+            PlaylistConversionError::MissingField(__self_0) => {
+                ::core::fmt::Formatter::debug_tuple_field1_finish(
+                    f,
+                    "MissingField",
+                    &__self_0,
+                )
+            }
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::clone::Clone for PlaylistConversionError {
+    #[inline]
+    fn clone(&self) -> PlaylistConversionError {
+        match self {
+            PlaylistConversionError::MissingField(__self_0) => {
+                PlaylistConversionError::MissingField(
+                    ::core::clone::Clone::clone(__self_0),
+                )
+            }
+        }
+    }
+}
+#[automatically_derived]
+impl ::core::marker::StructuralPartialEq for PlaylistConversionError {}
+#[automatically_derived]
+impl ::core::cmp::PartialEq for PlaylistConversionError {
+    #[inline]
+    fn eq(&self, other: &PlaylistConversionError) -> bool {
+        match (self, other) {
+            (
+                PlaylistConversionError::MissingField(__self_0),
+                PlaylistConversionError::MissingField(__arg1_0),
+            ) => __self_0 == __arg1_0,
+        }
+    }
+}
+impl std::fmt::Display for PlaylistConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingField(field) => {
+                f.write_fmt(format_args!("Missing required field: {0}", field))
+            }
+        }
+    }
+}
+impl std::error::Error for PlaylistConversionError {}
+impl From<String> for PlaylistConversionError {
+    fn from(err: String) -> Self {
+        Self::MissingField(err)
+    }
+}
+impl TryFrom<proto::Playlist> for Playlist {
+    type Error = PlaylistConversionError;
+    fn try_from(proto_struct: proto::Playlist) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: proto_struct
+                .name
+                .or_else(::protto::options::lenient_missing)
+                .ok_or_else(|| PlaylistConversionError::MissingField(
+                    "name".to_string(),
+                ))?
+                .into(),
+            featured: {
+                #[allow(unused_imports)]
+                use ::protto::repr::{ViaInto as _, ViaRepr as _};
+                (&&::protto::repr::Select::<Track>::new())
+                    .select_from_proto(
+                        proto_struct
+                            .featured
+                            .or_else(::protto::options::lenient_missing)
+                            .ok_or_else(|| PlaylistConversionError::MissingField(
+                                "featured".to_string(),
+                            ))?,
+                    )
+            },
+        })
+    }
+}
+impl Into<proto::Playlist> for Playlist {
+    fn into(self) -> proto::Playlist {
+        let Playlist { name, featured } = self;
+        proto::Playlist {
+            name: Some(name.into()),
+            featured: Some({
+                #[allow(unused_imports)]
+                use ::protto::repr::{ViaInto as _, ViaRepr as _};
+                (&&::protto::repr::Select::<Track>::new()).select_to_proto(featured)
+            }),
+        }
+    }
+}
+const _: () = {
+    #[allow(dead_code)]
+    fn __protto_message_fields(proto: &proto::Playlist) {
+        ::protto::type_alias::message_field(&proto.featured);
+    }
+};
+fn main() {}
@@ -0,0 +1,27 @@
+use protto::Protto;
+
+mod proto {
+    pub struct Track {
+        pub track_id: u64,
+    }
+
+    pub struct Playlist {
+        pub name: Option<String>,
+        pub featured: Option<Track>,
+    }
+}
+
+#[derive(Protto)]
+pub struct Track {
+    pub track_id: u64,
+}
+
+#[derive(Protto)]
+pub struct Playlist {
+    #[protto(expect(error))]
+    pub name: String,
+    #[protto(expect(error))]
+    pub featured: Track,
+}
+
+fn main() {}
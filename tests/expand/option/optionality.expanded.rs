@@ -0,0 +1,82 @@
+use protto::Protto;
+mod proto {
+    pub struct Header {
+        pub request_id: String,
+    }
+    pub struct Request {
+        pub header: Option<Header>,
+        pub name: Option<String>,
+        pub retries: u32,
+    }
+}
+pub struct Header {
+    pub request_id: String,
+}
+impl From<proto::Header> for Header {
+    fn from(proto_struct: proto::Header) -> Self {
+        Self {
+            request_id: proto_struct.request_id,
+        }
+    }
+}
+impl Into<proto::Header> for Header {
+    fn into(self) -> proto::Header {
+        let Header { request_id } = self;
+        proto::Header {
+            request_id: request_id,
+        }
+    }
+}
+pub struct Request {
+    #[protto(expect(panic))]
+    pub header: Header,
+    #[protto(proto_optional)]
+    pub name: Option<String>,
+    #[protto(proto_required, none_as = "default")]
+    pub retries: Option<u32>,
+}
+impl From<proto::Request> for Request {
+    fn from(proto_struct: proto::Request) -> Self {
+        Self {
+            header: {
+                #[allow(unused_imports)]
+                use ::protto::repr::{ViaInto as _, ViaRepr as _};
+                (&&::protto::repr::Select::<Header>::new())
+                    .select_from_proto(
+                        proto_struct
+                            .header
+                            .expect(
+                                &::alloc::__export::must_use({
+                                    ::alloc::fmt::format(
+                                        format_args!("Proto field {0} is required", "header"),
+                                    )
+                                }),
+                            ),
+                    )
+            },
+            name: proto_struct.name.map(|v| v.into()),
+            retries: Some(proto_struct.retries.into()),
+        }
+    }
+}
+impl Into<proto::Request> for Request {
+    fn into(self) -> proto::Request {
+        let Request { header, name, retries } = self;
+        proto::Request {
+            header: Some({
+                #[allow(unused_imports)]
+                use ::protto::repr::{ViaInto as _, ViaRepr as _};
+                (&&::protto::repr::Select::<Header>::new()).select_to_proto(header)
+            }),
+            name: name.map(|v| v.into()),
+            retries: retries.map(|v| v.into()).unwrap_or_default(),
+        }
+    }
+}
+const _: () = {
+    #[allow(dead_code)]
+    fn __protto_message_fields(proto: &proto::Request) {
+        ::protto::type_alias::message_field(&proto.header);
+    }
+};
+fn main() {}
@@ -0,0 +1,30 @@
+use protto::Protto;
+
+mod proto {
+    pub struct Header {
+        pub request_id: String,
+    }
+
+    pub struct Request {
+        pub header: Option<Header>,
+        pub name: Option<String>,
+        pub retries: u32,
+    }
+}
+
+#[derive(Protto)]
+pub struct Header {
+    pub request_id: String,
+}
+
+#[derive(Protto)]
+pub struct Request {
+    #[protto(expect(panic))]
+    pub header: Header,
+    #[protto(proto_optional)]
+    pub name: Option<String>,
+    #[protto(proto_required, none_as = "default")]
+    pub retries: Option<u32>,
+}
+
+fn main() {}
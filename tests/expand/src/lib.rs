@@ -0,0 +1,34 @@
+// ABOUTME: Expansion snapshots of the derive, one directory of fixtures per strategy family.
+// ABOUTME: Each fixture must expand to its checked-in `.expanded.rs`; MACROTEST=overwrite refreshes them.
+
+#![cfg(test)]
+
+#[test]
+fn collection() {
+    macrotest::expand("collection/*.rs");
+}
+
+#[test]
+fn custom() {
+    macrotest::expand("custom/*.rs");
+}
+
+#[test]
+fn direct() {
+    macrotest::expand("direct/*.rs");
+}
+
+#[test]
+fn enums() {
+    macrotest::expand("enums/*.rs");
+}
+
+#[test]
+fn errors() {
+    macrotest::expand("errors/*.rs");
+}
+
+#[test]
+fn option() {
+    macrotest::expand("option/*.rs");
+}
@@ -11,6 +11,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             "cargo:warning=Generating metadata for {} proto files",
             proto_files.len()
         );
+        protto::build::generate_metadata(&proto_files)?;
     } else {
         println!("cargo:warning=No proto files found for metadata generation");
     }
@@ -46,6 +47,8 @@ fn generate_prost_protos() -> Result<Vec<Box<Path>>, Box<dyn std::error::Error>>
 
     println!("cargo:warning=Found proto files: {:?}", proto_files);
 
+    let descriptor_path = Path::new(&env::var("OUT_DIR")?).join("service_descriptor.bin");
+
     tonic_build::configure()
         .build_server(true)
         .build_client(true)
@@ -81,7 +84,34 @@ fn generate_prost_protos() -> Result<Vec<Box<Path>>, Box<dyn std::error::Error>>
             "service.HasStraight",
             "#[cfg_attr(test, derive(proptest_derive::Arbitrary))]",
         )
+        .type_attribute(
+            "service.BeaconMessage",
+            "#[derive(::protto::Protto)] #[protto(passthrough, estimated_size, \
+            any_type_url = \"type.googleapis.com/service.BeaconMessage\")]",
+        )
+        .type_attribute(
+            "service.EnrollmentMessage",
+            "#[derive(validator::Validate)]",
+        )
+        .field_attribute(
+            "service.EnrollmentMessage.email",
+            "#[validate(email(message = \"value must be a valid email address\"))]",
+        )
+        .field_attribute(
+            "service.EnrollmentMessage.seats",
+            "#[validate(range(min = 1, message = \"value must be at least 1\"))]",
+        )
+        .boxed(".service.TreeNodeMessage.header")
+        .bytes([".service.BlobMessage"])
+        .btree_map([".service.SortedIndexMessage.labels"])
+        .file_descriptor_set_path(&descriptor_path)
         .compile_protos(&proto_files, &[proto_dir])?;
 
+    // serde impls following the protobuf JSON mapping, for the `json` feature tests
+    let descriptor_set = std::fs::read(&descriptor_path)?;
+    pbjson_build::Builder::new()
+        .register_descriptors(&descriptor_set)?
+        .build(&[".service"])?;
+
     Ok(proto_files)
 }
@@ -12,8 +12,7 @@ pub struct ValidationEdgeCaseStruct {
     #[protto(
         from_proto_fn = "impossible_conversion",
         to_proto_fn = "impossible_conversion_back",
-        expect,
-        default,
+        expect = "default",
         proto_name = "should_fail_validation"
     )]
     pub impossible_combination_field: String,
@@ -181,7 +180,7 @@ pub struct EqualityOperatorTestStruct {
 #[protto(module = "proto", proto_name = "ComplexExpectMessage")]
 pub struct LogicalOperatorTestStruct {
     // Fields with attributes that create complex && vs || conditions
-    #[protto(expect, default, proto_name = "field_with_panic")]
+    #[protto(expect = "default", proto_name = "field_with_panic")]
     pub and_or_condition_field1: String,
 
     #[protto(transparent, proto_optional, proto_name = "field_with_error")]
@@ -517,7 +516,7 @@ mod additional_mutation_tests {
         )]
         pub struct CompoundEdgeCaseStruct {
             // DMR: Multiple attributes that create complex boolean chains
-            #[protto(expect, default, proto_optional, proto_name = "empty_vs_none")]
+            #[protto(expect(default), proto_optional, proto_name = "empty_vs_none")]
             pub complex_boolean_chain: String,
 
             // DMR: Collection + transparent + optional combination
@@ -0,0 +1,95 @@
+// ABOUTME: Tests for the `required` and `optional` field aliases and their expanded equivalents.
+// ABOUTME: Covers missing required values, optional pass-through, and parity with the long form.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(module = "proto", proto_name = "SimpleMessage")]
+pub struct AliasedSimple {
+    #[protto(required)]
+    pub required_field: String,
+    #[protto(required)]
+    pub required_number: u64,
+    #[protto(optional)]
+    pub optional_field: Option<String>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(module = "proto", proto_name = "SimpleMessage")]
+pub struct ExpandedSimple {
+    #[protto(proto_optional, expect(error))]
+    pub required_field: String,
+    #[protto(proto_optional, expect(error))]
+    pub required_number: u64,
+    #[protto(proto_optional)]
+    pub optional_field: Option<String>,
+}
+
+fn proto_simple() -> proto::SimpleMessage {
+    proto::SimpleMessage {
+        required_field: Some("name".to_string()),
+        required_number: Some(7),
+        optional_field: None,
+    }
+}
+
+#[test]
+fn test_aliases_round_trip() {
+    let simple = AliasedSimple::try_from(proto_simple()).unwrap();
+    assert_eq!(
+        simple,
+        AliasedSimple {
+            required_field: "name".to_string(),
+            required_number: 7,
+            optional_field: None,
+        }
+    );
+    let proto: proto::SimpleMessage = simple.into();
+    assert_eq!(proto, proto_simple());
+}
+
+#[test]
+fn test_required_alias_reports_missing_field() {
+    let proto = proto::SimpleMessage {
+        required_number: None,
+        ..proto_simple()
+    };
+    assert_eq!(
+        AliasedSimple::try_from(proto).unwrap_err(),
+        AliasedSimpleConversionError::MissingField("required_number".to_string())
+    );
+}
+
+#[test]
+fn test_optional_alias_passes_values_through() {
+    let proto = proto::SimpleMessage {
+        optional_field: Some("note".to_string()),
+        ..proto_simple()
+    };
+    let simple = AliasedSimple::try_from(proto.clone()).unwrap();
+    assert_eq!(simple.optional_field.as_deref(), Some("note"));
+    assert_eq!(Into::<proto::SimpleMessage>::into(simple), proto);
+}
+
+#[test]
+fn test_aliases_match_their_expansion() {
+    for proto in [
+        proto_simple(),
+        proto::SimpleMessage {
+            required_field: None,
+            ..proto_simple()
+        },
+        proto::SimpleMessage {
+            optional_field: Some("note".to_string()),
+            ..proto_simple()
+        },
+    ] {
+        let aliased = AliasedSimple::try_from(proto.clone()).map_err(|e| format!("{e:?}"));
+        let expanded = ExpandedSimple::try_from(proto).map_err(|e| format!("{e:?}"));
+        assert_eq!(
+            aliased.map(Into::<proto::SimpleMessage>::into),
+            expanded.map(Into::<proto::SimpleMessage>::into)
+        );
+    }
+}
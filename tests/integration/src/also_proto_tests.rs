@@ -0,0 +1,75 @@
+// ABOUTME: Tests for struct-level `also_proto`, converting one struct to several message versions.
+// ABOUTME: Covers the conversions for each version and code generic over the version traits.
+
+use crate::basic_types::Status;
+use crate::proto;
+use protto::Protto;
+use protto::version::{ProtoConvertFrom, ProtoConvertTo};
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(
+    proto_name = "ProfileV1Message",
+    also_proto = "proto::ProfileV2Message",
+    partial
+)]
+pub struct Profile {
+    pub id: u64,
+    #[protto(proto_name = "display_name")]
+    pub name: String,
+    pub status: Status,
+}
+
+fn profile() -> Profile {
+    Profile {
+        id: 7,
+        name: "Ada".to_string(),
+        status: Status::Found,
+    }
+}
+
+/// Re-encodes a profile through whichever message version the caller picks
+fn through_version<P>(profile: Profile) -> Profile
+where
+    Profile: ProtoConvertTo<P> + ProtoConvertFrom<P>,
+    <Profile as ProtoConvertFrom<P>>::Error: std::fmt::Debug,
+{
+    let proto: P = profile.convert_to_proto();
+    Profile::convert_from_proto(proto).unwrap()
+}
+
+#[test]
+fn test_converts_to_each_version() {
+    let v1: proto::ProfileV1Message = profile().into();
+    assert_eq!(v1.id, 7);
+    assert_eq!(v1.display_name, "Ada");
+    assert_eq!(v1.status, proto::Status::Found as i32);
+
+    let v2: proto::ProfileV2Message = profile().into();
+    assert_eq!(v2.display_name, "Ada");
+    assert_eq!(v2.locale, "");
+}
+
+#[test]
+fn test_converts_from_each_version() {
+    let v2 = proto::ProfileV2Message {
+        id: 7,
+        display_name: "Ada".to_string(),
+        status: proto::Status::Found as i32,
+        locale: "en-GB".to_string(),
+    };
+    assert_eq!(Profile::try_from(v2).unwrap(), profile());
+    let v1: proto::ProfileV1Message = profile().into();
+    assert_eq!(Profile::try_from(v1).unwrap(), profile());
+}
+
+#[test]
+fn test_generic_over_the_version() {
+    assert_eq!(
+        through_version::<proto::ProfileV1Message>(profile()),
+        profile()
+    );
+    assert_eq!(
+        through_version::<proto::ProfileV2Message>(profile()),
+        profile()
+    );
+}
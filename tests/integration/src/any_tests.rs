@@ -0,0 +1,95 @@
+// ABOUTME: Tests for `any_type_url`, packing derived structs into prost_types::Any.
+// ABOUTME: Converts heterogeneous trait objects and unpacks them by type URL.
+
+use crate::proto;
+use protto::Protto;
+use protto::any::{ToProtoAny, UnpackError};
+
+const ORDER_PLACED_URL: &str = "type.googleapis.com/service.OrderPlacedEvent";
+const ORDER_CANCELLED_URL: &str = "type.googleapis.com/service.OrderCancelledEvent";
+
+trait DomainEvent: ToProtoAny {
+    fn order_id(&self) -> &str;
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(
+    proto_name = "OrderPlacedEvent",
+    any_type_url = "type.googleapis.com/service.OrderPlacedEvent"
+)]
+pub struct OrderPlaced {
+    pub order_id: String,
+    pub total_cents: u64,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(
+    proto_name = "OrderCancelledEvent",
+    any_type_url = "type.googleapis.com/service.OrderCancelledEvent"
+)]
+pub struct OrderCancelled {
+    pub order_id: String,
+    pub reason: String,
+}
+
+impl DomainEvent for OrderPlaced {
+    fn order_id(&self) -> &str {
+        &self.order_id
+    }
+}
+
+impl DomainEvent for OrderCancelled {
+    fn order_id(&self) -> &str {
+        &self.order_id
+    }
+}
+
+#[test]
+fn test_trait_objects_pack_under_their_type_urls() {
+    let events: Vec<Box<dyn DomainEvent>> = vec![
+        Box::new(OrderPlaced {
+            order_id: "o-1".to_string(),
+            total_cents: 1250,
+        }),
+        Box::new(OrderCancelled {
+            order_id: "o-1".to_string(),
+            reason: "duplicate".to_string(),
+        }),
+    ];
+
+    let packed: Vec<prost_types::Any> = events.iter().map(|event| event.to_proto_any()).collect();
+    assert_eq!(events[0].order_id(), "o-1");
+    assert_eq!(events[1].type_url(), ORDER_CANCELLED_URL);
+    assert_eq!(packed[0].type_url, ORDER_PLACED_URL);
+    assert_eq!(packed[1].type_url, ORDER_CANCELLED_URL);
+
+    let placed: proto::OrderPlacedEvent =
+        protto::any::unpack(&packed[0], ORDER_PLACED_URL).unwrap();
+    assert_eq!(
+        OrderPlaced::from(placed),
+        OrderPlaced {
+            order_id: "o-1".to_string(),
+            total_cents: 1250,
+        }
+    );
+
+    let cancelled: proto::OrderCancelledEvent =
+        protto::any::unpack(&packed[1], ORDER_CANCELLED_URL).unwrap();
+    assert_eq!(cancelled.reason, "duplicate");
+}
+
+#[test]
+fn test_unpack_rejects_other_type_urls() {
+    let any = OrderPlaced {
+        order_id: "o-2".to_string(),
+        total_cents: 10,
+    }
+    .to_proto_any();
+
+    let result = protto::any::unpack::<proto::OrderCancelledEvent>(&any, ORDER_CANCELLED_URL);
+    assert!(matches!(
+        result,
+        Err(UnpackError::TypeUrl { expected, found })
+            if expected == ORDER_CANCELLED_URL && found == ORDER_PLACED_URL
+    ));
+}
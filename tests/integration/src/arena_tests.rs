@@ -0,0 +1,70 @@
+// ABOUTME: Tests for struct-level `arena`, converting proto messages into bumpalo-backed structs.
+// ABOUTME: Covers strings, bytes, repeated fields, nested arena structs, enums and renamed fields.
+
+use crate::basic_types::Status;
+use crate::proto;
+use protto::Protto;
+use protto::arena::Bump;
+use protto::arena::bumpalo::collections::Vec as BumpVec;
+
+#[derive(Protto, Debug)]
+#[protto(proto_name = "Header", arena)]
+pub struct ArenaHeader<'a> {
+    pub request_id: &'a str,
+    pub timestamp: i64,
+}
+
+#[derive(Protto, Debug)]
+#[protto(proto_name = "ParcelMessage", arena)]
+pub struct Parcel<'a> {
+    #[protto(proto_name = "user")]
+    pub user_name: &'a str,
+    pub tags: BumpVec<'a, &'a str>,
+    pub header: Option<ArenaHeader<'a>>,
+    pub payload: &'a [u8],
+    pub status: Status,
+    #[protto(ignore)]
+    pub retries: u32,
+}
+
+fn message() -> proto::ParcelMessage {
+    proto::ParcelMessage {
+        user: "ada".to_string(),
+        tags: vec!["urgent".to_string(), "billing".to_string()],
+        header: Some(proto::Header {
+            request_id: "req-1".to_string(),
+            timestamp: 42,
+        }),
+        payload: vec![1, 2, 3],
+        status: proto::Status::Found as i32,
+    }
+}
+
+#[test]
+fn test_converts_into_the_arena() {
+    let bump = Bump::new();
+    let parcel = {
+        let message = message();
+        Parcel::from_proto_in(&message, &bump)
+    };
+
+    assert_eq!(parcel.user_name, "ada");
+    assert_eq!(parcel.tags.as_slice(), &["urgent", "billing"]);
+    let header = parcel.header.as_ref().unwrap();
+    assert_eq!(header.request_id, "req-1");
+    assert_eq!(header.timestamp, 42);
+    assert_eq!(parcel.payload, &[1, 2, 3]);
+    assert_eq!(parcel.status, Status::Found);
+    assert_eq!(parcel.retries, 0);
+    assert!(bump.allocated_bytes() > 0);
+}
+
+#[test]
+fn test_missing_message_field_stays_none() {
+    let bump = Bump::new();
+    let message = proto::ParcelMessage {
+        header: None,
+        ..message()
+    };
+    assert!(Parcel::from_proto_in(&message, &bump).header.is_none());
+}
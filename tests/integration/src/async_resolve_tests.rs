@@ -0,0 +1,121 @@
+// ABOUTME: Tests for async proto -> rust conversion with `resolve_with` field lookups.
+// ABOUTME: Resolved fields await a resolver call while the remaining fields convert synchronously.
+
+use crate::proto;
+use protto::Protto;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackRecord {
+    pub id: u64,
+    pub title: String,
+}
+
+impl From<TrackRecord> for u64 {
+    fn from(record: TrackRecord) -> Self {
+        record.id
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CatalogError {
+    UnknownTrack(u64),
+    MissingField(String),
+}
+
+impl CatalogError {
+    pub fn missing_field(field: &str) -> Self {
+        Self::MissingField(field.to_string())
+    }
+}
+
+pub struct Catalog {
+    tracks: HashMap<u64, String>,
+}
+
+impl Catalog {
+    pub async fn fetch_track(catalog: &Catalog, id: u64) -> Result<TrackRecord, CatalogError> {
+        catalog
+            .tracks
+            .get(&id)
+            .map(|title| TrackRecord {
+                id,
+                title: title.clone(),
+            })
+            .ok_or(CatalogError::UnknownTrack(id))
+    }
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(
+    proto_name = "Track",
+    resolver = Catalog,
+    error_type = CatalogError,
+    error_fn = "CatalogError::missing_field"
+)]
+pub struct ResolvedTrack {
+    #[protto(
+        proto_name = "track_id",
+        proto_required,
+        resolve_with = "Catalog::fetch_track"
+    )]
+    pub record: TrackRecord,
+}
+
+fn catalog() -> Catalog {
+    Catalog {
+        tracks: HashMap::from([(7, "Blue in Green".to_string())]),
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn from_proto_async_resolves_field() {
+    let track = block_on(ResolvedTrack::from_proto_async(
+        proto::Track { track_id: 7 },
+        &catalog(),
+    ))
+    .unwrap();
+
+    assert_eq!(
+        track.record,
+        TrackRecord {
+            id: 7,
+            title: "Blue in Green".to_string(),
+        }
+    );
+}
+
+#[test]
+fn from_proto_async_propagates_resolver_error() {
+    let result = block_on(ResolvedTrack::from_proto_async(
+        proto::Track { track_id: 99 },
+        &catalog(),
+    ));
+
+    assert_eq!(result, Err(CatalogError::UnknownTrack(99)));
+}
+
+#[test]
+fn resolved_struct_converts_back_to_proto() {
+    let track = block_on(ResolvedTrack::from_proto_async(
+        proto::Track { track_id: 7 },
+        &catalog(),
+    ))
+    .unwrap();
+
+    let proto_track: proto::Track = track.into();
+    assert_eq!(proto_track, proto::Track { track_id: 7 });
+}
@@ -90,8 +90,8 @@ pub struct ConflictingAttributeTestStruct {
     #[protto(default_fn = "first_default_fn", proto_name = "required_field")]
     pub precedence_test_field: String,
 
-    // Test expect vs default precedence
-    #[protto(expect, default, proto_name = "required_number")]
+    // Test expect falling back to a default, stated in one attribute
+    #[protto(expect = "default", proto_name = "required_number")]
     pub expect_vs_default_field: u64,
 }
 
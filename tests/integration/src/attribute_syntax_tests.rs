@@ -0,0 +1,66 @@
+// ABOUTME: Tests for the alternate `key(value)` attribute syntax.
+// ABOUTME: Nested and `key = value` forms of the same key must generate identical conversions.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name("CustomerMessage"))]
+pub struct NestedSyntaxCustomer {
+    pub name: String,
+    #[protto(proto_required, none_as("default"))]
+    pub street: Option<String>,
+    pub city: String,
+    #[protto(proto_name("postal_code"))]
+    pub zip: String,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "CustomerMessage")]
+pub struct ValueSyntaxCustomer {
+    pub name: String,
+    #[protto(proto_required, none_as = "default")]
+    pub street: Option<String>,
+    pub city: String,
+    #[protto(proto_name = "postal_code")]
+    pub zip: String,
+}
+
+fn customer_message() -> proto::CustomerMessage {
+    proto::CustomerMessage {
+        name: "Ada".to_string(),
+        street: "1 Main St".to_string(),
+        city: "Springfield".to_string(),
+        postal_code: "12345".to_string(),
+    }
+}
+
+#[test]
+fn nested_syntax_round_trips() {
+    let customer: NestedSyntaxCustomer = customer_message().into();
+    assert_eq!(customer.zip, "12345");
+    assert_eq!(customer.street, Some("1 Main St".to_string()));
+
+    let proto_msg: proto::CustomerMessage = customer.into();
+    assert_eq!(proto_msg, customer_message());
+}
+
+#[test]
+fn nested_and_value_syntax_convert_identically() {
+    let nested: NestedSyntaxCustomer = customer_message().into();
+    let value: ValueSyntaxCustomer = customer_message().into();
+    assert_eq!(nested.zip, value.zip);
+    assert_eq!(nested.street, value.street);
+
+    let nested = NestedSyntaxCustomer {
+        street: None,
+        ..nested
+    };
+    let value = ValueSyntaxCustomer {
+        street: None,
+        ..value
+    };
+    let nested_proto: proto::CustomerMessage = nested.into();
+    let value_proto: proto::CustomerMessage = value.into();
+    assert_eq!(nested_proto, value_proto);
+}
@@ -0,0 +1,89 @@
+// ABOUTME: Tests for `protto::batch`, converting vectors of proto messages into derived structs.
+// ABOUTME: Covers sequential and rayon conversions and the index reported for the first failure.
+
+use crate::proto;
+use protto::Protto;
+use protto::batch::{convert_batch, convert_batch_par};
+use std::time::Duration;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "TimeoutsMessage")]
+pub struct BatchTimeouts {
+    #[protto(proto_name = "connect_timeout_ms", duration = "millis")]
+    pub connect: Duration,
+    #[protto(proto_name = "idle_timeout_seconds", duration = "seconds")]
+    pub idle: Option<Duration>,
+    #[protto(proto_name = "clock_skew_nanos", duration = "nanos")]
+    pub clock_skew: chrono::TimeDelta,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "Header")]
+pub struct BatchHeader {
+    pub request_id: String,
+    pub timestamp: i64,
+}
+
+fn proto_batch(len: i64) -> Vec<proto::TimeoutsMessage> {
+    (0..len)
+        .map(|connect_timeout_ms| proto::TimeoutsMessage {
+            connect_timeout_ms,
+            idle_timeout_seconds: None,
+            clock_skew_nanos: 0,
+        })
+        .collect()
+}
+
+#[test]
+fn test_convert_batch_preserves_order() {
+    let timeouts: Vec<BatchTimeouts> = convert_batch(proto_batch(100)).unwrap();
+    assert_eq!(timeouts.len(), 100);
+    assert!(
+        timeouts
+            .iter()
+            .enumerate()
+            .all(|(index, t)| t.connect == Duration::from_millis(index as u64))
+    );
+}
+
+#[test]
+fn test_convert_batch_reports_first_failing_index() {
+    let mut protos = proto_batch(100);
+    protos[42].connect_timeout_ms = -1;
+    protos[70].connect_timeout_ms = -1;
+
+    let (index, err) = convert_batch::<_, BatchTimeouts>(protos).unwrap_err();
+    assert_eq!(index, 42);
+    assert_eq!(
+        err,
+        BatchTimeoutsConversionError::InvalidValue("connect_timeout_ms".to_string())
+    );
+}
+
+#[test]
+fn test_convert_batch_par_matches_sequential() {
+    let protos = proto_batch(50_000);
+    let sequential: Vec<BatchTimeouts> = convert_batch(protos.clone()).unwrap();
+    let parallel: Vec<BatchTimeouts> = convert_batch_par(protos).unwrap();
+    assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn test_convert_batch_par_reports_lowest_failing_index() {
+    let mut protos = proto_batch(50_000);
+    protos[49_000].connect_timeout_ms = -1;
+    protos[1_234].connect_timeout_ms = -1;
+
+    let (index, _) = convert_batch_par::<_, BatchTimeouts>(protos).unwrap_err();
+    assert_eq!(index, 1_234);
+}
+
+#[test]
+fn test_convert_batch_of_infallible_conversions() {
+    let protos = vec![proto::Header {
+        request_id: "req-1".to_string(),
+        timestamp: 42,
+    }];
+    let headers: Result<Vec<BatchHeader>, _> = convert_batch(protos);
+    assert_eq!(headers.unwrap()[0].timestamp, 42);
+}
@@ -0,0 +1,77 @@
+// ABOUTME: Tests for the `bool_enum(...)` field attribute.
+// ABOUTME: A Rust bool maps onto two designated variants of a legacy proto enum.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "FeatureFlagMessage")]
+pub struct FeatureFlag {
+    pub name: String,
+    #[protto(
+        proto_name = "state",
+        bool_enum(proto_enum = "FeatureState", true = "Enabled", false = "Disabled", unknown = "false")
+    )]
+    pub enabled: bool,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "FeatureFlagMessage")]
+pub struct StrictFeatureFlag {
+    pub name: String,
+    #[protto(
+        proto_name = "state",
+        bool_enum(proto_enum = "FeatureState", true = "Enabled", false = "Disabled", unknown = "error")
+    )]
+    pub enabled: bool,
+}
+
+fn flag_message(state: proto::FeatureState) -> proto::FeatureFlagMessage {
+    proto::FeatureFlagMessage {
+        name: "dark_mode".to_string(),
+        state: state as i32,
+    }
+}
+
+#[test]
+fn bool_enum_from_proto() {
+    let enabled: FeatureFlag = flag_message(proto::FeatureState::Enabled).into();
+    assert!(enabled.enabled);
+
+    let disabled: FeatureFlag = flag_message(proto::FeatureState::Disabled).into();
+    assert!(!disabled.enabled);
+}
+
+#[test]
+fn bool_enum_to_proto() {
+    let flag = FeatureFlag {
+        name: "dark_mode".to_string(),
+        enabled: true,
+    };
+    let proto_msg: proto::FeatureFlagMessage = flag.into();
+    assert_eq!(proto_msg, flag_message(proto::FeatureState::Enabled));
+
+    let flag = StrictFeatureFlag {
+        name: "dark_mode".to_string(),
+        enabled: false,
+    };
+    let proto_msg: proto::FeatureFlagMessage = flag.into();
+    assert_eq!(proto_msg, flag_message(proto::FeatureState::Disabled));
+}
+
+#[test]
+fn bool_enum_unknown_uses_policy() {
+    let flag: FeatureFlag = flag_message(proto::FeatureState::Unspecified).into();
+    assert!(!flag.enabled);
+
+    let result = StrictFeatureFlag::try_from(flag_message(proto::FeatureState::Unspecified));
+    assert_eq!(
+        result,
+        Err(StrictFeatureFlagConversionError::InvalidValue(
+            "state".to_string()
+        ))
+    );
+
+    let strict = StrictFeatureFlag::try_from(flag_message(proto::FeatureState::Enabled)).unwrap();
+    assert!(strict.enabled);
+}
@@ -0,0 +1,61 @@
+// ABOUTME: Tests for `from_proto_fn_ref`, custom functions that borrow the proto field.
+// ABOUTME: The function reads a nested message by reference instead of taking ownership of it.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "Request")]
+pub struct RequestSummary {
+    #[protto(
+        proto_name = "header",
+        proto_optional,
+        from_proto_fn_ref = "request_id_of",
+        to_proto_fn = "header_for"
+    )]
+    pub request_id: String,
+    pub payload: String,
+}
+
+fn request_id_of(header: &proto::Header) -> String {
+    header.request_id.clone()
+}
+
+fn header_for(request_id: String) -> proto::Header {
+    proto::Header {
+        request_id,
+        timestamp: 0,
+    }
+}
+
+#[test]
+fn from_proto_fn_ref_reads_borrowed_message() {
+    let request = proto::Request {
+        header: Some(proto::Header {
+            request_id: "req-7".to_string(),
+            timestamp: 1_700_000_000,
+        }),
+        payload: "body".to_string(),
+    };
+
+    let summary: RequestSummary = request.into();
+    assert_eq!(
+        summary,
+        RequestSummary {
+            request_id: "req-7".to_string(),
+            payload: "body".to_string(),
+        }
+    );
+}
+
+#[test]
+fn from_proto_fn_ref_with_to_proto_fn() {
+    let summary = RequestSummary {
+        request_id: "req-8".to_string(),
+        payload: "body".to_string(),
+    };
+
+    let request: proto::Request = summary.into();
+    assert_eq!(request.header.unwrap().request_id, "req-8");
+    assert_eq!(request.payload, "body");
+}
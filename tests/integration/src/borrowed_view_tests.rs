@@ -0,0 +1,40 @@
+// ABOUTME: Tests for deriving on structs with lifetime parameters (borrowed views).
+// ABOUTME: Borrowed structs only get the rust -> proto conversion.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "Header")]
+pub struct HeaderView<'a> {
+    pub request_id: &'a str,
+    pub timestamp: i64,
+}
+
+#[test]
+fn borrowed_view_to_proto() {
+    let request_id = String::from("req-123");
+    let view = HeaderView {
+        request_id: &request_id,
+        timestamp: 42,
+    };
+
+    let proto_header: proto::Header = view.into();
+    assert_eq!(proto_header.request_id, "req-123");
+    assert_eq!(proto_header.timestamp, 42);
+}
+
+#[test]
+fn borrowed_view_does_not_outlive_proto() {
+    let proto_header = {
+        let request_id = String::from("scoped");
+        let view = HeaderView {
+            request_id: request_id.as_str(),
+            timestamp: 7,
+        };
+        let proto_header: proto::Header = view.into();
+        proto_header
+    };
+
+    assert_eq!(proto_header.request_id, "scoped");
+}
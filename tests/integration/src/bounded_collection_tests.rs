@@ -0,0 +1,79 @@
+// ABOUTME: Tests for repeated fields collected into SmallVec and ArrayVec targets.
+// ABOUTME: Covers round trips and the error, panic and truncate overflow policies of ArrayVec.
+
+use crate::proto;
+use arrayvec::ArrayVec;
+use protto::Protto;
+use smallvec::SmallVec;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "BoundedListMessage")]
+pub struct BoundedList {
+    pub tags: ArrayVec<String, 2>,
+    pub ids: SmallVec<[u64; 2]>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "BoundedListMessage")]
+pub struct TruncatedList {
+    #[protto(overflow = "truncate")]
+    pub tags: ArrayVec<String, 2>,
+    pub ids: Vec<u64>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "BoundedListMessage")]
+pub struct StrictList {
+    #[protto(overflow = "panic")]
+    pub tags: ArrayVec<String, 2>,
+    pub ids: SmallVec<[u64; 1]>,
+}
+
+fn message(tags: &[&str], ids: &[u64]) -> proto::BoundedListMessage {
+    proto::BoundedListMessage {
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        ids: ids.to_vec(),
+    }
+}
+
+#[test]
+fn test_bounded_collections_round_trip() {
+    let proto_msg = message(&["a", "b"], &[1, 2, 3]);
+
+    let list = BoundedList::try_from(proto_msg.clone()).unwrap();
+    assert_eq!(list.tags.as_slice(), ["a", "b"]);
+    assert_eq!(list.ids.as_slice(), [1, 2, 3]);
+    assert!(list.ids.spilled());
+
+    let back: proto::BoundedListMessage = list.into();
+    assert_eq!(back, proto_msg);
+}
+
+#[test]
+fn test_overflow_error_is_invalid_value() {
+    let result = BoundedList::try_from(message(&["a", "b", "c"], &[]));
+    assert_eq!(
+        result,
+        Err(BoundedListConversionError::InvalidValue("tags".to_string()))
+    );
+}
+
+#[test]
+fn test_overflow_truncate_keeps_leading_elements() {
+    let list: TruncatedList = message(&["a", "b", "c"], &[7]).into();
+    assert_eq!(list.tags.as_slice(), ["a", "b"]);
+    assert_eq!(list.ids, vec![7]);
+}
+
+#[test]
+fn test_overflow_panic_within_capacity() {
+    let list: StrictList = message(&["a"], &[]).into();
+    assert_eq!(list.tags.as_slice(), ["a"]);
+    assert!(list.ids.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "Proto field tags: 3 elements exceed the capacity of 2")]
+fn test_overflow_panic() {
+    let _: StrictList = message(&["a", "b", "c"], &[]).into();
+}
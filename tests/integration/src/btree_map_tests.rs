@@ -0,0 +1,108 @@
+// ABOUTME: Tests for BTreeMap fields of proto maps, with converted values and enum keys.
+// ABOUTME: Covers `enum_keys` policies and the key-ordered rust -> proto conversion.
+
+use crate::proto;
+use crate::shared_types::{Track, TrackId};
+use protto::Protto;
+use std::collections::BTreeMap;
+
+#[derive(Protto, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[protto(enum_as_i32)]
+pub enum Tier {
+    #[default]
+    Free = 0,
+    Team = 3,
+    Enterprise = 7,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "SortedIndexMessage")]
+pub struct SortedIndex {
+    pub tracks: BTreeMap<String, Track>,
+    #[protto(enum_keys = "error")]
+    pub quotas: BTreeMap<Tier, u32>,
+    #[protto(enum_keys = "default")]
+    pub labels: BTreeMap<Tier, String>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "SortedIndexMessage")]
+pub struct TrustedSortedIndex {
+    pub tracks: BTreeMap<String, Track>,
+    #[protto(enum_keys)]
+    pub quotas: BTreeMap<Tier, u32>,
+    #[protto(enum_keys)]
+    pub labels: BTreeMap<Tier, String>,
+}
+
+fn index() -> SortedIndex {
+    SortedIndex {
+        tracks: BTreeMap::from([
+            (
+                "b-side".to_string(),
+                Track {
+                    id: TrackId::new(2),
+                },
+            ),
+            (
+                "a-side".to_string(),
+                Track {
+                    id: TrackId::new(1),
+                },
+            ),
+        ]),
+        quotas: BTreeMap::from([(Tier::Enterprise, 500), (Tier::Free, 5)]),
+        labels: BTreeMap::from([
+            (Tier::Team, "team".to_string()),
+            (Tier::Free, "free".to_string()),
+        ]),
+    }
+}
+
+#[test]
+fn test_btree_maps_round_trip() {
+    let proto_index: proto::SortedIndexMessage = index().into();
+    assert_eq!(proto_index.tracks["a-side"].track_id, 1);
+    assert_eq!(proto_index.quotas[&7], 500);
+
+    assert_eq!(SortedIndex::try_from(proto_index.clone()), Ok(index()));
+    assert_eq!(
+        TrustedSortedIndex::from(proto_index).quotas,
+        BTreeMap::from([(Tier::Free, 5), (Tier::Enterprise, 500)])
+    );
+}
+
+#[test]
+fn test_rust_to_proto_follows_key_order() {
+    // prost generates `labels` as a BTreeMap, so the encoding follows the enum's key order
+    let proto_index: proto::SortedIndexMessage = index().into();
+    assert_eq!(
+        proto_index.labels.into_iter().collect::<Vec<_>>(),
+        vec![(0, "free".to_string()), (3, "team".to_string())]
+    );
+}
+
+#[test]
+fn test_enum_keys_policies() {
+    let mut proto_index: proto::SortedIndexMessage = index().into();
+    proto_index.labels.insert(42, "unknown".to_string());
+    proto_index.labels.remove(&0);
+    let converted = SortedIndex::try_from(proto_index.clone()).unwrap();
+    assert_eq!(converted.labels[&Tier::Free], "unknown");
+
+    proto_index.quotas.insert(42, 1);
+    assert_eq!(
+        SortedIndex::try_from(proto_index),
+        Err(SortedIndexConversionError::InvalidValue(
+            "quotas".to_string()
+        ))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Proto field quotas has key 42 matching no variant of Tier")]
+fn test_enum_keys_panic_on_unknown_key() {
+    let mut proto_index: proto::SortedIndexMessage = index().into();
+    proto_index.quotas.insert(42, 1);
+    let _ = TrustedSortedIndex::from(proto_index);
+}
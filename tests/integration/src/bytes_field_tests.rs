@@ -0,0 +1,50 @@
+// ABOUTME: Tests for proto bytes fields generated as either Vec<u8> or bytes::Bytes by prost.
+// ABOUTME: Covers Vec<u8> rust fields against Bytes proto fields and the reverse, required and optional.
+
+use crate::proto;
+use prost::bytes::Bytes;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "BlobMessage")]
+pub struct Blob {
+    pub payload: Vec<u8>,
+    pub checksum: Option<Vec<u8>>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "RawBlobMessage")]
+pub struct SharedBlob {
+    pub payload: Bytes,
+    pub checksum: Option<Bytes>,
+}
+
+#[test]
+fn vec_fields_convert_to_and_from_bytes_proto_fields() {
+    let proto_msg = proto::BlobMessage {
+        payload: Bytes::from_static(b"payload"),
+        checksum: Some(Bytes::from_static(&[0xca, 0xfe])),
+    };
+
+    let blob = Blob::from(proto_msg.clone());
+    assert_eq!(blob.payload, b"payload".to_vec());
+    assert_eq!(blob.checksum, Some(vec![0xca, 0xfe]));
+
+    let round_tripped: proto::BlobMessage = blob.into();
+    assert_eq!(round_tripped, proto_msg);
+}
+
+#[test]
+fn bytes_fields_convert_to_and_from_vec_proto_fields() {
+    let proto_msg = proto::RawBlobMessage {
+        payload: b"payload".to_vec(),
+        checksum: None,
+    };
+
+    let blob = SharedBlob::from(proto_msg.clone());
+    assert_eq!(blob.payload, Bytes::from_static(b"payload"));
+    assert_eq!(blob.checksum, None);
+
+    let round_tripped: proto::RawBlobMessage = blob.into();
+    assert_eq!(round_tripped, proto_msg);
+}
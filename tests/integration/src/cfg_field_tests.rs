@@ -0,0 +1,47 @@
+// ABOUTME: Tests for fields gated behind cargo features with `#[cfg(...)]`.
+// ABOUTME: A `cfg_attr` struct-level ignore fills the proto field with its default when the feature is off.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "Header")]
+#[cfg_attr(not(feature = "premium"), protto(ignore = "timestamp"))]
+pub struct GatedHeader {
+    pub request_id: String,
+    #[cfg(feature = "premium")]
+    pub timestamp: i64,
+}
+
+fn header() -> proto::Header {
+    proto::Header {
+        request_id: "req-1".to_string(),
+        timestamp: 42,
+    }
+}
+
+#[cfg(feature = "premium")]
+#[test]
+fn cfg_field_maps_when_enabled() {
+    let gated: GatedHeader = header().into();
+    assert_eq!(gated.timestamp, 42);
+
+    let back: proto::Header = gated.into();
+    assert_eq!(back, header());
+}
+
+#[cfg(not(feature = "premium"))]
+#[test]
+fn cfg_field_is_skipped_when_disabled() {
+    let gated: GatedHeader = header().into();
+    assert_eq!(gated.request_id, "req-1");
+
+    let back: proto::Header = gated.into();
+    assert_eq!(
+        back,
+        proto::Header {
+            request_id: "req-1".to_string(),
+            timestamp: 0,
+        }
+    );
+}
@@ -0,0 +1,50 @@
+// ABOUTME: Tests for struct- and enum-level `cfg`, compiling the generated items only under a predicate.
+// ABOUTME: The proto module here exists only with the `grpc` feature, so builds without it must not convert.
+
+use protto::Protto;
+
+/// Stands in for a proto module compiled only when the crate's `grpc` feature is on
+#[cfg(feature = "grpc")]
+mod grpc_proto {
+    pub use crate::proto::{Header, Status};
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(cfg(feature = "grpc"), module = "grpc_proto", proto_name = "Header")]
+pub struct RequestHeader {
+    pub request_id: String,
+    pub timestamp: i64,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone, Copy)]
+#[protto(cfg = "feature = \"grpc\"", module = "grpc_proto")]
+pub enum Status {
+    Ok,
+    MovedPermanently,
+    Found,
+    NotFound,
+}
+
+#[test]
+fn test_domain_types_exist_in_every_build() {
+    let header = RequestHeader {
+        request_id: "req-1".to_string(),
+        timestamp: 42,
+    };
+    assert_eq!(header.clone(), header);
+    assert_eq!(Status::Found, Status::Found);
+}
+
+#[cfg(feature = "grpc")]
+#[test]
+fn test_conversions_exist_with_the_feature() {
+    let header = RequestHeader {
+        request_id: "req-1".to_string(),
+        timestamp: 42,
+    };
+    let proto: grpc_proto::Header = header.clone().into();
+    assert_eq!(RequestHeader::from(proto), header);
+
+    let status: grpc_proto::Status = Status::NotFound.into();
+    assert_eq!(Status::from(status), Status::NotFound);
+}
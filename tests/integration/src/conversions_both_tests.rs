@@ -0,0 +1,79 @@
+// ABOUTME: Tests for the struct-level `conversions = "both"` attribute.
+// ABOUTME: Panicking `from_proto`/`into_proto` sit beside the fallible TryFrom conversions.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "TrackWithOptionals", conversions = "both")]
+pub struct TrustedTrack {
+    pub track_id: u64,
+    #[protto(expect)]
+    pub name: String,
+    pub duration: Option<u32>,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "CustomerMessage", conversions = "both")]
+pub struct TrustedCustomer {
+    pub name: String,
+    #[protto(proto_required, none_as = "error")]
+    pub street: Option<String>,
+    pub city: String,
+    pub postal_code: String,
+}
+
+fn track_message(name: Option<&str>) -> proto::TrackWithOptionals {
+    proto::TrackWithOptionals {
+        track_id: 5,
+        name: name.map(str::to_string),
+        duration: None,
+    }
+}
+
+#[test]
+fn from_proto_matches_try_from() {
+    let track = TrustedTrack::from_proto(track_message(Some("intro")));
+    assert_eq!(
+        Ok(track.clone()),
+        TrustedTrack::try_from(track_message(Some("intro")))
+    );
+    assert_eq!(track.name, "intro");
+
+    assert_eq!(
+        TrustedTrack::try_from(track_message(None)),
+        Err(TrustedTrackConversionError::MissingField(
+            "name".to_string()
+        ))
+    );
+}
+
+#[test]
+#[should_panic(expected = "failed to convert proto::TrackWithOptionals into TrustedTrack")]
+fn from_proto_panics_on_bad_data() {
+    TrustedTrack::from_proto(track_message(None));
+}
+
+#[test]
+fn into_proto_matches_try_from() {
+    let customer = TrustedCustomer {
+        name: "Ada".to_string(),
+        street: Some("1 Main St".to_string()),
+        city: "London".to_string(),
+        postal_code: "N1".to_string(),
+    };
+    let message = customer.clone().into_proto();
+    assert_eq!(Ok(message), proto::CustomerMessage::try_from(customer));
+}
+
+#[test]
+#[should_panic(expected = "failed to convert TrustedCustomer into proto::CustomerMessage")]
+fn into_proto_panics_on_bad_data() {
+    let customer = TrustedCustomer {
+        name: "Ada".to_string(),
+        street: None,
+        city: "London".to_string(),
+        postal_code: "N1".to_string(),
+    };
+    customer.into_proto();
+}
@@ -0,0 +1,49 @@
+// ABOUTME: Tests for the conversion coverage report enabled by the `meta-file` feature.
+// ABOUTME: Every derive in this crate records itself in OUT_DIR; the report matches them to the protos.
+
+use protto::build::{coverage, parse_proto_content, read_coverage, render_coverage_report};
+
+fn service_rows() -> Vec<protto::build::CoverageRow> {
+    let proto =
+        std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/proto/service.proto"))
+            .expect("service.proto is readable");
+    let derived = read_coverage(env!("OUT_DIR")).expect("coverage records are readable");
+    coverage(&[parse_proto_content(&proto)], &derived)
+}
+
+#[test]
+fn derived_structs_are_recorded_with_their_strategies() {
+    let derived = read_coverage(env!("OUT_DIR")).unwrap();
+    let account = derived
+        .iter()
+        .find(|derived| derived.proto_path == "wire::Account")
+        .expect("the proto_field_type_tests derive is recorded");
+
+    assert_eq!(account.rust_name, "Account");
+    let id = account
+        .fields
+        .iter()
+        .find(|field| field.rust_name == "id")
+        .unwrap();
+    assert_eq!(id.strategy, "direct");
+    assert_eq!(id.proto_fields, vec!["id".to_string()]);
+}
+
+#[test]
+fn report_covers_messages_with_derived_types() {
+    let rows = service_rows();
+    let customer = rows
+        .iter()
+        .find(|row| row.message == "service.CustomerMessage")
+        .unwrap();
+    assert!(customer.derived.contains(&"TrustedCustomer".to_string()));
+    assert_eq!(customer.mapped_fields, customer.total_fields);
+
+    let report = render_coverage_report(&rows);
+    assert!(report.starts_with("message"));
+    assert!(report.contains(&format!(
+        "{} of {} messages have a derived type",
+        rows.iter().filter(|row| !row.derived.is_empty()).count(),
+        rows.len()
+    )));
+}
@@ -0,0 +1,63 @@
+// ABOUTME: Tests for struct-level `debug_roundtrip`, asserting proto -> rust conversions are lossless.
+// ABOUTME: Covers a faithful mapping, a lossy `partial` one, and a fallible conversion.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "Header", debug_roundtrip)]
+pub struct CheckedHeader {
+    pub request_id: String,
+    pub timestamp: i64,
+}
+
+// Drops `timestamp`, which `partial` fills with its default on the way back
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "Header", partial, debug_roundtrip)]
+pub struct LossyHeader {
+    pub request_id: String,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "TrackWithOptionals", debug_roundtrip)]
+pub struct CheckedTrack {
+    pub track_id: u64,
+    #[protto(expect)]
+    pub name: String,
+    pub duration: Option<u32>,
+}
+
+fn header(timestamp: i64) -> proto::Header {
+    proto::Header {
+        request_id: "req-1".to_string(),
+        timestamp,
+    }
+}
+
+#[test]
+fn test_lossless_conversion_passes() {
+    let checked = CheckedHeader::from(header(42));
+    assert_eq!(checked.timestamp, 42);
+
+    // Nothing is lost while the dropped field holds its default
+    let lossy = LossyHeader::from(header(0));
+    assert_eq!(lossy.request_id, "req-1");
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "`LossyHeader` does not round-trip")]
+fn test_lossy_conversion_panics_in_debug_builds() {
+    let _ = LossyHeader::from(header(42));
+}
+
+#[test]
+fn test_fallible_conversion_is_checked() {
+    let track = proto::TrackWithOptionals {
+        track_id: 7,
+        name: Some("intro".to_string()),
+        duration: None,
+    };
+    let checked = CheckedTrack::try_from(track).unwrap();
+    assert_eq!(checked.name, "intro");
+}
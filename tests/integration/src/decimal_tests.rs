@@ -0,0 +1,170 @@
+// ABOUTME: Tests for the `decimal = "string" | "units_nanos"` field attribute on rust_decimal fields.
+// ABOUTME: Covers round trips, nano rounding, invalid and overflowing values, and expect(panic).
+
+use crate::proto;
+use protto::Protto;
+use rust_decimal::Decimal;
+
+protto::impl_units_nanos!(proto::MoneyAmount);
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "PriceMessage")]
+pub struct Price {
+    #[protto(decimal = "string")]
+    pub amount: Decimal,
+    #[protto(decimal = "units_nanos")]
+    pub total: Decimal,
+    #[protto(decimal = "string")]
+    pub discount: Option<Decimal>,
+    #[protto(decimal = "units_nanos")]
+    pub refund: Option<Decimal>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "PriceMessage", ignore = "discount, refund")]
+pub struct QuotedPrice {
+    #[protto(decimal = "string", expect(panic))]
+    pub amount: Decimal,
+    #[protto(decimal = "units_nanos", expect(panic))]
+    pub total: Decimal,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "PriceMessage", ignore = "discount, refund")]
+pub struct BilledPrice {
+    #[protto(decimal = "string")]
+    pub amount: Decimal,
+    #[protto(decimal = "units_nanos", expect)]
+    pub total: Decimal,
+}
+
+fn money(units: i64, nanos: i32) -> proto::MoneyAmount {
+    proto::MoneyAmount { units, nanos }
+}
+
+fn price_message() -> proto::PriceMessage {
+    proto::PriceMessage {
+        amount: "12.50".to_string(),
+        total: Some(money(-3, -250_000_000)),
+        discount: Some("0.1".to_string()),
+        refund: None,
+    }
+}
+
+#[test]
+fn decimal_fields_round_trip() {
+    let price = Price::try_from(price_message()).unwrap();
+    assert_eq!(price.amount, Decimal::new(1250, 2));
+    assert_eq!(price.total, Decimal::new(-325, 2));
+    assert_eq!(price.discount, Some(Decimal::new(1, 1)));
+    assert_eq!(price.refund, None);
+
+    let round_tripped = proto::PriceMessage::try_from(price).unwrap();
+    assert_eq!(round_tripped, price_message());
+}
+
+#[test]
+fn decimal_missing_units_nanos_message_is_zero() {
+    let price = Price::try_from(proto::PriceMessage {
+        total: None,
+        ..price_message()
+    })
+    .unwrap();
+    assert_eq!(price.total, Decimal::ZERO);
+}
+
+#[test]
+fn decimal_units_nanos_rounds_to_nanos() {
+    let price = Price {
+        amount: Decimal::new(1, 0),
+        total: Decimal::new(10_000_000_005, 10),
+        discount: None,
+        refund: Some(Decimal::new(-4, 10)),
+    };
+
+    let proto_msg = proto::PriceMessage::try_from(price).unwrap();
+    assert_eq!(proto_msg.total, Some(money(1, 1)));
+    assert_eq!(proto_msg.refund, Some(money(0, 0)));
+}
+
+#[test]
+fn decimal_invalid_string_is_an_error() {
+    let result = Price::try_from(proto::PriceMessage {
+        amount: "twelve".to_string(),
+        ..price_message()
+    });
+    assert_eq!(
+        result,
+        Err(PriceConversionError::InvalidValue("amount".to_string()))
+    );
+}
+
+#[test]
+fn decimal_invalid_nanos_is_an_error() {
+    let result = Price::try_from(proto::PriceMessage {
+        refund: Some(money(1, -5)),
+        ..price_message()
+    });
+    assert_eq!(
+        result,
+        Err(PriceConversionError::InvalidValue("refund".to_string()))
+    );
+}
+
+#[test]
+fn decimal_units_overflow_is_an_error() {
+    let price = Price {
+        amount: Decimal::ZERO,
+        total: Decimal::MAX,
+        discount: None,
+        refund: None,
+    };
+
+    assert_eq!(
+        proto::PriceMessage::try_from(price),
+        Err(PriceConversionError::InvalidValue("total".to_string()))
+    );
+}
+
+#[test]
+fn decimal_expect_requires_units_nanos_message() {
+    let result = BilledPrice::try_from(proto::PriceMessage {
+        total: None,
+        ..price_message()
+    });
+    assert_eq!(
+        result,
+        Err(BilledPriceConversionError::MissingField(
+            "total".to_string()
+        ))
+    );
+}
+
+#[test]
+fn decimal_expect_panic_converts_infallibly() {
+    let price = QuotedPrice::from(price_message());
+    assert_eq!(price.total, Decimal::new(-325, 2));
+
+    let proto_msg: proto::PriceMessage = price.into();
+    assert_eq!(proto_msg.amount, "12.50");
+    assert_eq!(proto_msg.discount, None);
+}
+
+#[test]
+#[should_panic(expected = "Proto field amount is not a valid decimal")]
+fn decimal_expect_panic_panics_on_invalid_string() {
+    let _ = QuotedPrice::from(proto::PriceMessage {
+        amount: "12,50".to_string(),
+        ..price_message()
+    });
+}
+
+#[test]
+#[should_panic(expected = "Decimal for proto field total cannot be encoded")]
+fn decimal_expect_panic_panics_on_overflow() {
+    let _: proto::PriceMessage = QuotedPrice {
+        amount: Decimal::ZERO,
+        total: Decimal::MAX,
+    }
+    .into();
+}
@@ -0,0 +1,65 @@
+// ABOUTME: Tests for field-level `depends_on`, passing already converted fields to a custom
+// ABOUTME: `from_proto_fn`, e.g. reading a minor-unit amount according to its currency.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Amount {
+    pub minor: i64,
+    pub decimal_places: u32,
+}
+
+pub fn amount_in_currency(minor: i64, currency: &str) -> Amount {
+    let decimal_places = match currency {
+        "JPY" => 0,
+        _ => 2,
+    };
+    Amount {
+        minor,
+        decimal_places,
+    }
+}
+
+pub fn amount_to_minor(amount: Amount) -> i64 {
+    amount.minor
+}
+
+// `amount` comes first, but is converted after `currency`, which it depends on
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "QuoteMessage")]
+pub struct Quote {
+    #[protto(
+        proto_name = "amount_minor",
+        from_proto_fn = "amount_in_currency",
+        to_proto_fn = "amount_to_minor",
+        depends_on = "currency",
+        proto_required
+    )]
+    pub amount: Amount,
+    pub currency: String,
+}
+
+fn quote_message(currency: &str) -> proto::QuoteMessage {
+    proto::QuoteMessage {
+        amount_minor: 1250,
+        currency: currency.to_string(),
+    }
+}
+
+#[test]
+fn test_custom_fn_receives_converted_dependency() {
+    let quote = Quote::from(quote_message("USD"));
+    assert_eq!(quote.amount.decimal_places, 2);
+    assert_eq!(quote.currency, "USD");
+
+    let quote = Quote::from(quote_message("JPY"));
+    assert_eq!(quote.amount.decimal_places, 0);
+}
+
+#[test]
+fn test_depends_on_round_trips() {
+    let quote = Quote::from(quote_message("EUR"));
+    let message: proto::QuoteMessage = quote.into();
+    assert_eq!(message, quote_message("EUR"));
+}
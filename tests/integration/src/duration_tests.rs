@@ -0,0 +1,112 @@
+// ABOUTME: Tests for `duration`, carrying std and chrono durations as int64 seconds, millis or nanos.
+// ABOUTME: Covers round trips, optional fields, and overflow handling under the error and panic modes.
+
+use crate::proto;
+use protto::Protto;
+use std::time::Duration;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "TimeoutsMessage")]
+pub struct Timeouts {
+    #[protto(proto_name = "connect_timeout_ms", duration = "millis")]
+    pub connect: Duration,
+    #[protto(proto_name = "idle_timeout_seconds", duration = "seconds")]
+    pub idle: Option<Duration>,
+    #[protto(proto_name = "clock_skew_nanos", duration = "nanos")]
+    pub clock_skew: chrono::TimeDelta,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "TimeoutsMessage")]
+pub struct TrustedTimeouts {
+    #[protto(proto_name = "connect_timeout_ms", duration = "millis", expect(panic))]
+    pub connect: Duration,
+    #[protto(
+        proto_name = "idle_timeout_seconds",
+        duration = "seconds",
+        expect(panic)
+    )]
+    pub idle: Option<Duration>,
+    #[protto(proto_name = "clock_skew_nanos", duration = "nanos", expect(panic))]
+    pub clock_skew: chrono::TimeDelta,
+}
+
+fn proto_timeouts(connect_timeout_ms: i64) -> proto::TimeoutsMessage {
+    proto::TimeoutsMessage {
+        connect_timeout_ms,
+        idle_timeout_seconds: Some(90),
+        clock_skew_nanos: -250,
+    }
+}
+
+#[test]
+fn test_duration_round_trip() {
+    let timeouts = Timeouts::try_from(proto_timeouts(1_500)).unwrap();
+    assert_eq!(timeouts.connect, Duration::from_millis(1_500));
+    assert_eq!(timeouts.idle, Some(Duration::from_secs(90)));
+    assert_eq!(timeouts.clock_skew, chrono::TimeDelta::nanoseconds(-250));
+
+    let proto = proto::TimeoutsMessage::try_from(timeouts).unwrap();
+    assert_eq!(proto, proto_timeouts(1_500));
+}
+
+#[test]
+fn test_duration_truncates_to_whole_units() {
+    let timeouts = Timeouts {
+        connect: Duration::from_micros(1_999),
+        idle: Some(Duration::from_millis(59_999)),
+        clock_skew: chrono::TimeDelta::zero(),
+    };
+    let proto = proto::TimeoutsMessage::try_from(timeouts).unwrap();
+    assert_eq!(proto.connect_timeout_ms, 1);
+    assert_eq!(proto.idle_timeout_seconds, Some(59));
+}
+
+#[test]
+fn test_missing_optional_duration_is_none() {
+    let proto = proto::TimeoutsMessage {
+        idle_timeout_seconds: None,
+        ..proto_timeouts(10)
+    };
+    let timeouts = Timeouts::try_from(proto).unwrap();
+    assert_eq!(timeouts.idle, None);
+    let proto = proto::TimeoutsMessage::try_from(timeouts).unwrap();
+    assert_eq!(proto.idle_timeout_seconds, None);
+}
+
+#[test]
+fn test_negative_std_duration_is_invalid_value() {
+    let result = Timeouts::try_from(proto_timeouts(-1));
+    assert_eq!(
+        result.unwrap_err(),
+        TimeoutsConversionError::InvalidValue("connect_timeout_ms".to_string())
+    );
+}
+
+#[test]
+fn test_overflowing_duration_is_invalid_value() {
+    let timeouts = Timeouts {
+        connect: Duration::MAX,
+        idle: None,
+        clock_skew: chrono::TimeDelta::zero(),
+    };
+    let result = proto::TimeoutsMessage::try_from(timeouts);
+    assert_eq!(
+        result.unwrap_err(),
+        TimeoutsConversionError::InvalidValue("connect_timeout_ms".to_string())
+    );
+}
+
+#[test]
+fn test_expect_panic_durations_are_infallible() {
+    let timeouts: TrustedTimeouts = proto_timeouts(250).into();
+    assert_eq!(timeouts.connect, Duration::from_millis(250));
+    let proto: proto::TimeoutsMessage = timeouts.into();
+    assert_eq!(proto, proto_timeouts(250));
+}
+
+#[test]
+#[should_panic(expected = "Proto field connect_timeout_ms is not a valid duration")]
+fn test_expect_panic_duration_out_of_range_panics() {
+    let _: TrustedTimeouts = proto_timeouts(-5).into();
+}
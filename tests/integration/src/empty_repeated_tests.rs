@@ -0,0 +1,126 @@
+// ABOUTME: Tests for `empty_repeated`, choosing what an empty repeated field means on Option<Vec<T>>.
+// ABOUTME: Checks both policies round trip consistently from proto and normalize from rust.
+
+use crate::proto;
+use crate::shared_types::*;
+use proptest::prelude::*;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "VecOptionMessage")]
+pub struct AbsentWhenEmpty {
+    #[protto(empty_repeated = "none")]
+    pub optional_tracks: Option<Vec<Track>>,
+    #[protto(empty_repeated = "none")]
+    pub optional_strings: Option<Vec<String>>,
+    pub optional_proto_tracks: Option<Vec<proto::Track>>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "VecOptionMessage")]
+pub struct PresentWhenEmpty {
+    #[protto(empty_repeated = "some_empty")]
+    pub optional_tracks: Option<Vec<Track>>,
+    #[protto(empty_repeated = "some_empty")]
+    pub optional_strings: Option<Vec<String>>,
+    #[protto(empty_repeated = "some_empty")]
+    pub optional_proto_tracks: Option<Vec<proto::Track>>,
+}
+
+fn proto_message(strings: Vec<String>) -> proto::VecOptionMessage {
+    proto::VecOptionMessage {
+        optional_tracks: vec![],
+        optional_strings: strings,
+        optional_proto_tracks: vec![],
+    }
+}
+
+#[test]
+fn test_none_policy_reads_empty_as_absent() {
+    let rust: AbsentWhenEmpty = proto_message(vec![]).into();
+    assert_eq!(rust.optional_tracks, None);
+    assert_eq!(rust.optional_strings, None);
+    assert_eq!(rust.optional_proto_tracks, None);
+}
+
+#[test]
+fn test_some_empty_policy_reads_empty_as_present() {
+    let rust: PresentWhenEmpty = proto_message(vec![]).into();
+    assert_eq!(rust.optional_tracks, Some(vec![]));
+    assert_eq!(rust.optional_strings, Some(vec![]));
+    assert_eq!(rust.optional_proto_tracks, Some(vec![]));
+}
+
+#[test]
+fn test_some_empty_policy_keeps_elements() {
+    let proto = proto::VecOptionMessage {
+        optional_tracks: vec![proto::Track { track_id: 7 }],
+        optional_strings: vec!["a".to_string()],
+        optional_proto_tracks: vec![proto::Track { track_id: 8 }],
+    };
+    let rust: PresentWhenEmpty = proto.into();
+    assert_eq!(
+        rust.optional_tracks,
+        Some(vec![Track {
+            id: TrackId::new(7)
+        }])
+    );
+    assert_eq!(rust.optional_strings, Some(vec!["a".to_string()]));
+    assert_eq!(
+        rust.optional_proto_tracks,
+        Some(vec![proto::Track { track_id: 8 }])
+    );
+}
+
+#[test]
+fn test_absent_and_empty_both_encode_as_empty_repeated() {
+    let absent = PresentWhenEmpty {
+        optional_tracks: None,
+        optional_strings: None,
+        optional_proto_tracks: None,
+    };
+    let empty = PresentWhenEmpty {
+        optional_tracks: Some(vec![]),
+        optional_strings: Some(vec![]),
+        optional_proto_tracks: Some(vec![]),
+    };
+    let absent_proto: proto::VecOptionMessage = absent.into();
+    let empty_proto: proto::VecOptionMessage = empty.clone().into();
+    assert_eq!(absent_proto, empty_proto);
+    assert_eq!(PresentWhenEmpty::from(absent_proto), empty);
+}
+
+proptest! {
+    /// proto -> rust -> proto is the identity under either policy
+    #[test]
+    fn prop_test_proto_round_trip_is_identity(strings in prop::collection::vec(".*", 0..4)) {
+        let original = proto_message(strings);
+
+        let none_round_trip: proto::VecOptionMessage = AbsentWhenEmpty::from(original.clone()).into();
+        prop_assert_eq!(&none_round_trip, &original);
+
+        let some_round_trip: proto::VecOptionMessage = PresentWhenEmpty::from(original.clone()).into();
+        prop_assert_eq!(&some_round_trip, &original);
+    }
+
+    /// rust -> proto -> rust collapses the one value each policy cannot represent
+    #[test]
+    fn prop_test_rust_round_trip_normalizes(strings in prop::option::of(prop::collection::vec(".*", 0..4))) {
+        let none_rust = AbsentWhenEmpty {
+            optional_tracks: None,
+            optional_strings: strings.clone(),
+            optional_proto_tracks: None,
+        };
+        let none_round_trip = AbsentWhenEmpty::from(Into::<proto::VecOptionMessage>::into(none_rust));
+        let expected = strings.clone().filter(|strings| !strings.is_empty());
+        prop_assert_eq!(none_round_trip.optional_strings, expected);
+
+        let some_rust = PresentWhenEmpty {
+            optional_tracks: None,
+            optional_strings: strings.clone(),
+            optional_proto_tracks: None,
+        };
+        let some_round_trip = PresentWhenEmpty::from(Into::<proto::VecOptionMessage>::into(some_rust));
+        prop_assert_eq!(some_round_trip.optional_strings, Some(strings.unwrap_or_default()));
+    }
+}
@@ -0,0 +1,51 @@
+// ABOUTME: Tests for enums converting by the proto values the build metadata records, including
+// ABOUTME: a variant named after an `allow_alias` value that prost omits from its generated enum.
+
+use crate::proto;
+use protto::Protto;
+
+// Proto: JOB_PHASE_UNSPECIFIED = 0, JOB_PHASE_STARTED = 1, JOB_PHASE_RUNNING = 1 (alias),
+// JOB_PHASE_DONE = 4. prost generates only `Started` for the value 1.
+#[derive(Protto, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobPhase {
+    Unspecified,
+    Running,
+    Done,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "JobRunMessage")]
+pub struct JobRun {
+    pub name: String,
+    pub phase: JobPhase,
+}
+
+#[test]
+fn test_alias_variant_converts_by_number() {
+    assert_eq!(i32::from(JobPhase::Running), 1);
+    assert_eq!(
+        proto::JobPhase::from(JobPhase::Running),
+        proto::JobPhase::Started
+    );
+    assert_eq!(JobPhase::from(proto::JobPhase::Started), JobPhase::Running);
+    assert_eq!(JobPhase::from(4), JobPhase::Done);
+}
+
+#[test]
+fn test_struct_field_reads_alias_value() {
+    let message = proto::JobRunMessage {
+        name: "nightly".to_string(),
+        phase: proto::JobPhase::Started as i32,
+    };
+    let job: JobRun = message.clone().into();
+    assert_eq!(job.phase, JobPhase::Running);
+
+    let back: proto::JobRunMessage = job.into();
+    assert_eq!(back, message);
+}
+
+#[test]
+#[should_panic(expected = "Unknown enum value: 2")]
+fn test_value_outside_the_proto_enum_panics() {
+    let _ = JobPhase::from(2);
+}
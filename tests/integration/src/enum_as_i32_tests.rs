@@ -0,0 +1,82 @@
+// ABOUTME: Tests for `enum_as_i32`, carrying rust enums in plain proto int32 fields.
+// ABOUTME: Covers the enum-level TryFrom<i32> impl and the error, panic and default policies.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, Copy, PartialEq, Default)]
+#[protto(enum_as_i32)]
+pub enum JobStatus {
+    #[default]
+    Queued = 0,
+    Running = 5,
+    Finished = 9,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "JobMessage")]
+pub struct Job {
+    #[protto(enum_as_i32 = "error")]
+    pub status: JobStatus,
+    #[protto(enum_as_i32 = "default")]
+    pub previous_status: Option<JobStatus>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "JobMessage")]
+pub struct TrustedJob {
+    #[protto(enum_as_i32)]
+    pub status: JobStatus,
+    #[protto(enum_as_i32)]
+    pub previous_status: Option<JobStatus>,
+}
+
+#[test]
+fn test_discriminants_round_trip() {
+    assert_eq!(i32::from(JobStatus::Finished), 9);
+    assert_eq!(JobStatus::try_from(5), Ok(JobStatus::Running));
+    assert_eq!(
+        JobStatus::try_from(4),
+        Err(protto::enums::UnknownEnumValue {
+            target: "JobStatus",
+            value: 4
+        })
+    );
+
+    let job = Job {
+        status: JobStatus::Running,
+        previous_status: Some(JobStatus::Queued),
+    };
+    let proto_job: proto::JobMessage = job.clone().into();
+    assert_eq!(proto_job.status, 5);
+    assert_eq!(proto_job.previous_status, Some(0));
+    assert_eq!(Job::try_from(proto_job), Ok(job));
+}
+
+#[test]
+fn test_unknown_value_policies() {
+    let result = Job::try_from(proto::JobMessage {
+        status: 3,
+        previous_status: None,
+    });
+    assert_eq!(
+        result,
+        Err(JobConversionError::InvalidValue("status".to_string()))
+    );
+
+    let job = Job::try_from(proto::JobMessage {
+        status: 9,
+        previous_status: Some(42),
+    })
+    .unwrap();
+    assert_eq!(job.previous_status, Some(JobStatus::Queued));
+}
+
+#[test]
+#[should_panic(expected = "Proto field status has value 3 matching no variant of JobStatus")]
+fn test_unknown_value_panics_by_default() {
+    let _ = TrustedJob::from(proto::JobMessage {
+        status: 3,
+        previous_status: None,
+    });
+}
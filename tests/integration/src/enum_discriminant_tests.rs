@@ -0,0 +1,57 @@
+// ABOUTME: Tests for enums with explicit discriminants, converting by number instead of name.
+// ABOUTME: Covers non-contiguous proto enum values and variants named unlike the proto values.
+
+use crate::proto;
+use protto::Protto;
+
+// Proto: ACCOUNT_STATE_UNSPECIFIED = 0, ACCOUNT_STATE_ACTIVE = 1, ACCOUNT_STATE_DISABLED = 5.
+// The variant names differ from the proto values; the discriminants tie them together.
+#[derive(Protto, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AccountState {
+    #[default]
+    Unknown = 0,
+    Enabled = 1,
+    Suspended = 5,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "AccountMessage")]
+pub struct Account {
+    pub owner: String,
+    pub state: AccountState,
+}
+
+#[test]
+fn test_discriminants_map_to_proto_values() {
+    let cases = [
+        (AccountState::Unknown, proto::AccountState::Unspecified),
+        (AccountState::Enabled, proto::AccountState::Active),
+        (AccountState::Suspended, proto::AccountState::Disabled),
+    ];
+    for (rust_state, proto_state) in cases {
+        assert_eq!(proto::AccountState::from(rust_state), proto_state);
+        assert_eq!(AccountState::from(proto_state), rust_state);
+        assert_eq!(i32::from(rust_state), proto_state as i32);
+        assert_eq!(AccountState::from(proto_state as i32), rust_state);
+    }
+}
+
+#[test]
+fn test_struct_field_round_trips_by_number() {
+    let message = proto::AccountMessage {
+        owner: "ada".to_string(),
+        state: proto::AccountState::Disabled as i32,
+    };
+    let account: Account = message.clone().into();
+    assert_eq!(account.state, AccountState::Suspended);
+
+    let back: proto::AccountMessage = account.into();
+    assert_eq!(back, message);
+    assert_eq!(back.state, 5);
+}
+
+#[test]
+#[should_panic(expected = "No matching Rust variant for proto enum value: 3")]
+fn test_unknown_number_panics() {
+    let _ = AccountState::from(3);
+}
@@ -0,0 +1,82 @@
+// ABOUTME: Tests for `enum_values`, converting proto maps with enum values carried as i32.
+// ABOUTME: Covers HashMap and BTreeMap fields under the error, panic and default policies.
+
+use crate::proto;
+use protto::Protto;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Protto, Debug, Clone, Copy, PartialEq, Default)]
+pub enum RouteState {
+    #[default]
+    Active,
+    Draining,
+    Disabled,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "RouteTableMessage")]
+pub struct RouteTable {
+    #[protto(enum_values = "error")]
+    pub routes: HashMap<String, RouteState>,
+    #[protto(enum_values = "default")]
+    pub shards: BTreeMap<u32, RouteState>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "RouteTableMessage")]
+pub struct TrustedRouteTable {
+    #[protto(enum_values)]
+    pub routes: HashMap<String, RouteState>,
+    #[protto(enum_values)]
+    pub shards: BTreeMap<u32, RouteState>,
+}
+
+#[test]
+fn test_enum_values_round_trip() {
+    let table = RouteTable {
+        routes: HashMap::from([
+            ("/api".to_string(), RouteState::Active),
+            ("/legacy".to_string(), RouteState::Disabled),
+        ]),
+        shards: BTreeMap::from([(3, RouteState::Draining)]),
+    };
+
+    let proto_table: proto::RouteTableMessage = table.clone().into();
+    assert_eq!(
+        proto_table.routes["/legacy"],
+        proto::RouteState::Disabled as i32
+    );
+    assert_eq!(proto_table.shards[&3], proto::RouteState::Draining as i32);
+    assert_eq!(RouteTable::try_from(proto_table), Ok(table));
+}
+
+#[test]
+fn test_unknown_enum_values_per_policy() {
+    let result = RouteTable::try_from(proto::RouteTableMessage {
+        routes: HashMap::from([("/api".to_string(), 42)]),
+        shards: HashMap::new(),
+    });
+    assert_eq!(
+        result,
+        Err(RouteTableConversionError::InvalidValue(
+            "routes".to_string()
+        ))
+    );
+
+    let table = RouteTable::try_from(proto::RouteTableMessage {
+        routes: HashMap::new(),
+        shards: HashMap::from([(1, 42), (2, proto::RouteState::Disabled as i32)]),
+    })
+    .unwrap();
+    assert_eq!(table.shards[&1], RouteState::Active);
+    assert_eq!(table.shards[&2], RouteState::Disabled);
+}
+
+#[test]
+#[should_panic(expected = "Proto field routes has value 7 matching no variant of RouteState")]
+fn test_unknown_enum_value_panics_by_default() {
+    let _ = TrustedRouteTable::from(proto::RouteTableMessage {
+        routes: HashMap::from([("/api".to_string(), 7)]),
+        shards: HashMap::new(),
+    });
+}
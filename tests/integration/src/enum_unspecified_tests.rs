@@ -0,0 +1,94 @@
+// ABOUTME: Tests for field-level `unspecified`, converting a proto enum's 0 value for rust enums
+// ABOUTME: without an unspecified variant: as an error, the default variant, a named one or None.
+
+use crate::proto;
+use protto::Protto;
+
+// Proto: TASK_PHASE_UNSPECIFIED = 0, TASK_PHASE_QUEUED = 1, TASK_PHASE_FINISHED = 2. The rust
+// enum leaves the unspecified value out, which the build metadata check allows.
+#[derive(Protto, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TaskPhase {
+    #[default]
+    Queued,
+    Finished,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "TaskMessage")]
+pub struct Task {
+    #[protto(unspecified = "error")]
+    pub phase: TaskPhase,
+    #[protto(unspecified = "default")]
+    pub reported_phase: Option<TaskPhase>,
+    #[protto(unspecified = "map_to = Finished")]
+    pub last_phase: Option<TaskPhase>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "TaskMessage")]
+pub struct TaskSummary {
+    #[protto(unspecified = "default")]
+    pub phase: TaskPhase,
+    #[protto(unspecified = "map_to = Queued")]
+    pub reported_phase: TaskPhase,
+    #[protto(unspecified = "default")]
+    pub last_phase: TaskPhase,
+}
+
+fn message(phase: i32, reported_phase: i32, last_phase: Option<i32>) -> proto::TaskMessage {
+    proto::TaskMessage {
+        phase,
+        reported_phase,
+        last_phase,
+    }
+}
+
+#[test]
+fn unspecified_phase_is_an_error() {
+    let err = Task::try_from(message(0, 1, None)).unwrap_err();
+    assert!(err.to_string().contains("phase"));
+}
+
+#[test]
+fn unspecified_option_fields_follow_the_policy() {
+    let task = Task::try_from(message(2, 0, Some(0))).unwrap();
+    assert_eq!(
+        task,
+        Task {
+            phase: TaskPhase::Finished,
+            reported_phase: None,
+            last_phase: Some(TaskPhase::Finished),
+        }
+    );
+
+    let task = Task::try_from(message(1, 2, None)).unwrap();
+    assert_eq!(task.reported_phase, Some(TaskPhase::Finished));
+    assert_eq!(task.last_phase, None);
+}
+
+#[test]
+fn none_is_written_as_unspecified() {
+    let task = Task {
+        phase: TaskPhase::Queued,
+        reported_phase: None,
+        last_phase: None,
+    };
+    let proto_msg: proto::TaskMessage = task.into();
+    assert_eq!(proto_msg, message(1, 0, None));
+}
+
+#[test]
+fn unspecified_converts_to_a_variant() {
+    let summary = TaskSummary::from(message(0, 0, None));
+    assert_eq!(
+        summary,
+        TaskSummary {
+            phase: TaskPhase::Queued,
+            reported_phase: TaskPhase::Queued,
+            last_phase: TaskPhase::Queued,
+        }
+    );
+
+    let proto_msg: proto::TaskMessage = summary.into();
+    assert_eq!(proto_msg, message(1, 1, Some(1)));
+}
@@ -0,0 +1,132 @@
+// ABOUTME: Tests for the error_fn precedence cascade: field error_fn, then struct error_fn,
+// ABOUTME: then the generated `<Struct>ConversionError`, including fields under `error_mod`.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Debug, PartialEq)]
+pub enum CascadeError {
+    Field(&'static str),
+    Struct(String),
+}
+
+impl CascadeError {
+    pub fn field(field: &'static str) -> Self {
+        Self::Field(field)
+    }
+
+    pub fn struct_default(field: &str) -> Self {
+        Self::Struct(field.to_string())
+    }
+}
+
+#[derive(Protto, PartialEq, Debug)]
+#[protto(
+    proto_name = "SimpleMessage",
+    error_type = CascadeError,
+    error_fn = CascadeError::struct_default
+)]
+pub struct CascadeMessage {
+    #[protto(expect, error_fn = "CascadeError::field")]
+    pub required_field: String,
+    #[protto(expect)]
+    pub required_number: u64,
+    #[protto(expect)]
+    pub optional_field: Option<String>,
+}
+
+#[derive(Protto, PartialEq, Debug)]
+#[protto(proto_name = "SimpleMessage", error_mod = "cascade_errors")]
+pub struct GeneratedCascadeMessage {
+    #[protto(expect, error_fn = "field_missing")]
+    pub required_field: String,
+    #[protto(expect)]
+    pub required_number: u64,
+    #[protto(expect)]
+    pub optional_field: Option<String>,
+}
+
+pub fn field_missing(field: &str) -> cascade_errors::GeneratedCascadeMessageConversionError {
+    cascade_errors::GeneratedCascadeMessageConversionError::MissingField(format!(
+        "{field} (field error_fn)"
+    ))
+}
+
+fn complete() -> proto::SimpleMessage {
+    proto::SimpleMessage {
+        required_field: Some("id".to_string()),
+        required_number: Some(7),
+        optional_field: Some("note".to_string()),
+    }
+}
+
+#[test]
+fn test_field_error_fn_overrides_struct_error_fn() {
+    let message = proto::SimpleMessage {
+        required_field: None,
+        ..complete()
+    };
+    assert_eq!(
+        CascadeMessage::try_from(message),
+        Err(CascadeError::Field("required_field"))
+    );
+}
+
+#[test]
+fn test_struct_error_fn_covers_fields_without_their_own() {
+    let message = proto::SimpleMessage {
+        required_number: None,
+        ..complete()
+    };
+    assert_eq!(
+        CascadeMessage::try_from(message),
+        Err(CascadeError::Struct("required_number".to_string()))
+    );
+
+    let message = proto::SimpleMessage {
+        optional_field: None,
+        ..complete()
+    };
+    assert_eq!(
+        CascadeMessage::try_from(message),
+        Err(CascadeError::Struct("optional_field".to_string()))
+    );
+
+    assert_eq!(
+        CascadeMessage::try_from(complete()),
+        Ok(CascadeMessage {
+            required_field: "id".to_string(),
+            required_number: 7,
+            optional_field: Some("note".to_string()),
+        })
+    );
+}
+
+#[test]
+fn test_generated_error_is_the_last_resort_under_error_mod() {
+    let message = proto::SimpleMessage {
+        required_field: None,
+        ..complete()
+    };
+    assert_eq!(
+        GeneratedCascadeMessage::try_from(message),
+        Err(
+            cascade_errors::GeneratedCascadeMessageConversionError::MissingField(
+                "required_field (field error_fn)".to_string()
+            )
+        )
+    );
+
+    let message = proto::SimpleMessage {
+        optional_field: None,
+        ..complete()
+    };
+    assert_eq!(
+        GeneratedCascadeMessage::try_from(message),
+        Err(
+            cascade_errors::GeneratedCascadeMessageConversionError::MissingField(
+                "optional_field".to_string()
+            )
+        )
+    );
+}
@@ -0,0 +1,116 @@
+// ABOUTME: Tests for the `error_fn_with_value` attribute.
+// ABOUTME: The error function receives the offending proto value alongside the field name.
+
+use crate::fallible_nested_tests::ValidTrack;
+use crate::proto;
+use protto::Protto;
+use std::fmt::Debug;
+
+#[derive(Debug, PartialEq)]
+pub struct RejectedValue {
+    pub field: &'static str,
+    pub value: String,
+}
+
+pub fn rejected_value(field: &'static str, value: &dyn Debug) -> RejectedValue {
+    RejectedValue {
+        field,
+        value: format!("{value:?}"),
+    }
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "FeatureFlagMessage", error_type = RejectedValue)]
+pub struct AuditedFeatureFlag {
+    pub name: String,
+    #[protto(
+        proto_name = "state",
+        error_fn_with_value = "rejected_value",
+        bool_enum(proto_enum = "FeatureState", true = "Enabled", false = "Disabled", unknown = "error")
+    )]
+    pub enabled: bool,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "Track")]
+pub struct AuditedTrack {
+    pub track_id: u64,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(
+    proto_name = "HasOptional",
+    error_type = RejectedValue,
+    error_fn_with_value = rejected_value
+)]
+pub struct AuditedHasOptional {
+    #[protto(expect)]
+    pub track: AuditedTrack,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "HasOptional", error_type = RejectedValue)]
+pub struct AuditedMaybeValidTrack {
+    #[protto(fallible, error_fn_with_value = "rejected_value")]
+    pub track: Option<ValidTrack>,
+}
+
+#[test]
+fn error_fn_with_value_receives_unknown_enum_value() {
+    let message = proto::FeatureFlagMessage {
+        name: "dark_mode".to_string(),
+        state: 42,
+    };
+    assert_eq!(
+        AuditedFeatureFlag::try_from(message),
+        Err(RejectedValue {
+            field: "state",
+            value: "42".to_string(),
+        })
+    );
+
+    let message = proto::FeatureFlagMessage {
+        name: "dark_mode".to_string(),
+        state: proto::FeatureState::Enabled as i32,
+    };
+    assert!(AuditedFeatureFlag::try_from(message).unwrap().enabled);
+}
+
+#[test]
+fn error_fn_with_value_receives_none_for_missing_field() {
+    let result = AuditedHasOptional::try_from(proto::HasOptional { track: None });
+    assert_eq!(
+        result,
+        Err(RejectedValue {
+            field: "track",
+            value: "None".to_string(),
+        })
+    );
+
+    let present = proto::HasOptional {
+        track: Some(proto::Track { track_id: 7 }),
+    };
+    let audited = AuditedHasOptional::try_from(present.clone()).unwrap();
+    let back: proto::HasOptional = audited.into();
+    assert_eq!(back, present);
+}
+
+#[test]
+fn error_fn_with_value_receives_rejected_nested_message() {
+    let result = AuditedMaybeValidTrack::try_from(proto::HasOptional {
+        track: Some(proto::Track { track_id: 0 }),
+    });
+    assert_eq!(
+        result,
+        Err(RejectedValue {
+            field: "track",
+            value: "Track { track_id: 0 }".to_string(),
+        })
+    );
+
+    let valid = AuditedMaybeValidTrack::try_from(proto::HasOptional {
+        track: Some(proto::Track { track_id: 3 }),
+    })
+    .unwrap();
+    assert_eq!(valid.track, Some(ValidTrack { id: 3 }));
+}
@@ -0,0 +1,98 @@
+// ABOUTME: Tests for the struct-level `error_mod` attribute.
+// ABOUTME: Generated error types live in a named module so glob re-exports of same-named structs don't collide.
+
+use crate::proto;
+
+pub mod billing {
+    use crate::proto;
+    use protto::Protto;
+
+    #[derive(Protto, PartialEq, Debug, Clone)]
+    #[protto(proto_name = "CustomerMessage", error_mod = "billing_errors")]
+    pub struct Customer {
+        pub name: String,
+        #[protto(proto_required, none_as = "error")]
+        pub street: Option<String>,
+        pub city: String,
+        pub postal_code: String,
+    }
+
+    /// Hand-written type sharing the name the derive would otherwise generate in this scope
+    #[derive(Debug, PartialEq)]
+    pub struct CustomerConversionError;
+
+    pub fn into_proto(customer: Customer) -> Result<proto::CustomerMessage, String> {
+        proto::CustomerMessage::try_from(customer).map_err(|err| err.to_string())
+    }
+}
+
+pub mod shipping {
+    use crate::proto;
+    use protto::Protto;
+
+    #[derive(Protto, PartialEq, Debug, Clone)]
+    #[protto(proto_name = "CustomerMessage", error_mod = "shipping_errors")]
+    pub struct Recipient {
+        pub name: String,
+        #[protto(proto_required, none_as = "error")]
+        pub street: Option<String>,
+        pub city: String,
+        pub postal_code: String,
+    }
+}
+
+mod reexports {
+    pub use super::billing::*;
+    pub use super::shipping::*;
+}
+
+#[test]
+fn error_mod_holds_generated_error() {
+    let customer = reexports::Customer {
+        name: "Ada".to_string(),
+        street: None,
+        city: "Springfield".to_string(),
+        postal_code: "12345".to_string(),
+    };
+
+    let result = proto::CustomerMessage::try_from(customer);
+    assert_eq!(
+        result,
+        Err(reexports::billing_errors::CustomerConversionError::MissingField("street".to_string()))
+    );
+}
+
+#[test]
+fn error_mod_leaves_struct_scope_free() {
+    assert_eq!(
+        billing::CustomerConversionError,
+        reexports::CustomerConversionError
+    );
+
+    let recipient = reexports::Recipient {
+        name: "Ada".to_string(),
+        street: Some("1 Main St".to_string()),
+        city: "Springfield".to_string(),
+        postal_code: "12345".to_string(),
+    };
+    let proto_msg = proto::CustomerMessage::try_from(recipient).unwrap();
+    assert_eq!(proto_msg.street, "1 Main St");
+
+    let err =
+        shipping::shipping_errors::RecipientConversionError::MissingField("street".to_string());
+    assert_eq!(err.to_string(), "Missing required field: street");
+}
+
+#[test]
+fn error_mod_error_converts_to_string() {
+    let customer = billing::Customer {
+        name: "Ada".to_string(),
+        street: None,
+        city: "Springfield".to_string(),
+        postal_code: "12345".to_string(),
+    };
+    assert_eq!(
+        billing::into_proto(customer),
+        Err("Missing required field: street".to_string())
+    );
+}
@@ -0,0 +1,86 @@
+// ABOUTME: Tests for the visibility of generated error types.
+// ABOUTME: Errors inherit the struct's visibility unless `error_vis` overrides it.
+
+use crate::proto;
+
+mod ledger {
+    use super::proto;
+    use protto::Protto;
+
+    // A crate-visible struct whose fallible conversion exposes its generated error
+    #[derive(Protto, PartialEq, Debug, Clone)]
+    #[protto(proto_name = "CustomerMessage")]
+    pub(crate) struct Account {
+        pub name: String,
+        #[protto(proto_required, none_as = "error")]
+        pub street: Option<String>,
+        pub city: String,
+        pub postal_code: String,
+    }
+
+    #[derive(Protto, PartialEq, Debug, Clone)]
+    #[protto(proto_name = "CustomerMessage", error_mod = "payee_errors")]
+    pub(super) struct Payee {
+        pub name: String,
+        #[protto(proto_required, none_as = "error")]
+        pub street: Option<String>,
+        pub city: String,
+        pub postal_code: String,
+    }
+
+    // Private struct whose error is still reachable from the rest of the crate
+    #[derive(Protto, PartialEq, Debug, Clone)]
+    #[protto(proto_name = "CustomerMessage", error_vis = "pub(crate)")]
+    struct Ledger {
+        pub name: String,
+        #[protto(proto_required, none_as = "error")]
+        pub street: Option<String>,
+        pub city: String,
+        pub postal_code: String,
+    }
+
+    pub(super) fn ledger_proto() -> super::proto::CustomerMessage {
+        super::proto::CustomerMessage::try_from(Ledger {
+            name: "main".to_string(),
+            street: Some("1 Main St".to_string()),
+            city: "Springfield".to_string(),
+            postal_code: "00001".to_string(),
+        })
+        .unwrap()
+    }
+}
+
+#[test]
+fn test_error_inherits_crate_visibility() {
+    let account = ledger::Account {
+        name: "Ada".to_string(),
+        street: None,
+        city: "London".to_string(),
+        postal_code: "N1".to_string(),
+    };
+    let result: Result<proto::CustomerMessage, ledger::AccountConversionError> = account.try_into();
+    assert_eq!(
+        result.unwrap_err(),
+        ledger::AccountConversionError::MissingField("street".to_string())
+    );
+}
+
+#[test]
+fn test_error_mod_inherits_struct_visibility() {
+    let payee = ledger::Payee {
+        name: "Ada".to_string(),
+        street: None,
+        city: "London".to_string(),
+        postal_code: "N1".to_string(),
+    };
+    let result: Result<proto::CustomerMessage, ledger::payee_errors::PayeeConversionError> =
+        payee.try_into();
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_error_vis_overrides_struct_visibility() {
+    let error = ledger::LedgerConversionError::MissingField("street".to_string());
+    assert_eq!(error.to_string(), "Missing required field: street");
+    assert_eq!(ledger::ledger_proto().street, "1 Main St");
+}
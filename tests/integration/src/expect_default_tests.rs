@@ -0,0 +1,67 @@
+// ABOUTME: Tests for the `expect` forms stating a field's missing-value policy in one attribute.
+// ABOUTME: Covers expect = "default", expect(default = "fn") and expect(error) on optional proto fields.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "OptionalMessage")]
+pub struct Counter {
+    pub id: u64,
+    #[protto(expect = "default")]
+    pub name: String,
+    #[protto(expect(default = "default_count"))]
+    pub count: u32,
+    #[protto(expect(error))]
+    pub priority: u32,
+    pub tags: Vec<String>,
+}
+
+pub fn default_count() -> u32 {
+    10
+}
+
+fn optional_message() -> proto::OptionalMessage {
+    proto::OptionalMessage {
+        id: 1,
+        name: Some("requests".to_string()),
+        count: Some(3),
+        priority: Some(2),
+        tags: vec!["http".to_string()],
+    }
+}
+
+#[test]
+fn expect_forms_convert_present_values() {
+    let counter = Counter::try_from(optional_message()).unwrap();
+    assert_eq!(counter.name, "requests");
+    assert_eq!(counter.count, 3);
+    assert_eq!(counter.priority, 2);
+
+    let proto_msg: proto::OptionalMessage = counter.into();
+    assert_eq!(proto_msg, optional_message());
+}
+
+#[test]
+fn expect_default_falls_back_on_missing_values() {
+    let counter = Counter::try_from(proto::OptionalMessage {
+        name: None,
+        count: None,
+        ..optional_message()
+    })
+    .unwrap();
+    assert_eq!(counter.name, "");
+    assert_eq!(counter.count, 10);
+}
+
+#[test]
+fn expect_error_reports_missing_values() {
+    let result = Counter::try_from(proto::OptionalMessage {
+        priority: None,
+        ..optional_message()
+    });
+    assert_eq!(
+        result,
+        Err(CounterConversionError::MissingField("priority".to_string()))
+    );
+}
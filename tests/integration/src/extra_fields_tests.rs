@@ -0,0 +1,49 @@
+// ABOUTME: Tests for `extra_fields = "default"`, which ignores rust fields the proto message lacks.
+// ABOUTME: Missing fields are found in the build metadata, filled from Default and listed in EXTRA_FIELDS.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(
+    proto_name = "TrackWithOptionals",
+    extra_fields = "default",
+    field_paths
+)]
+pub struct AnnotatedTrack {
+    pub track_id: u64,
+    pub name: Option<String>,
+    pub duration: Option<u32>,
+    pub play_count: u32,
+    pub favorite: bool,
+}
+
+#[test]
+fn test_extra_fields_are_listed() {
+    assert_eq!(AnnotatedTrack::EXTRA_FIELDS, ["play_count", "favorite"]);
+    assert_eq!(
+        AnnotatedTrack::FIELD_PATHS,
+        ["track_id", "name", "duration"]
+    );
+}
+
+#[test]
+fn test_extra_fields_default_from_proto_and_skip_to_proto() {
+    let proto_track = proto::TrackWithOptionals {
+        track_id: 7,
+        name: Some("intro".to_string()),
+        duration: None,
+    };
+
+    let track = AnnotatedTrack::from(proto_track.clone());
+    assert_eq!(track.play_count, 0);
+    assert!(!track.favorite);
+
+    let played = AnnotatedTrack {
+        play_count: 12,
+        favorite: true,
+        ..track
+    };
+    let round_tripped: proto::TrackWithOptionals = played.into();
+    assert_eq!(round_tripped, proto_track);
+}
@@ -0,0 +1,165 @@
+// ABOUTME: Tests for the `fallible` field attribute on Option-wrapped and repeated nested messages.
+// ABOUTME: Nested TryFrom conversions are mapped over the Option or Vec and propagate to the parent.
+
+use crate::proto;
+use protto::Protto;
+
+/// Track whose proto -> rust conversion rejects the reserved id 0
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidTrack {
+    pub id: u64,
+}
+
+impl TryFrom<proto::Track> for ValidTrack {
+    type Error = String;
+
+    fn try_from(track: proto::Track) -> Result<Self, Self::Error> {
+        if track.track_id == 0 {
+            Err("track id 0 is reserved".to_string())
+        } else {
+            Ok(Self { id: track.track_id })
+        }
+    }
+}
+
+impl From<ValidTrack> for proto::Track {
+    fn from(track: ValidTrack) -> Self {
+        Self { track_id: track.id }
+    }
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "HasOptional")]
+pub struct MaybeValidTrack {
+    #[protto(fallible)]
+    pub track: Option<ValidTrack>,
+}
+
+/// A derived child with an infallible conversion composes through the blanket `TryFrom`
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "Track")]
+pub struct PlainTrack {
+    pub track_id: u64,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "HasOptional")]
+pub struct MaybePlainTrack {
+    #[protto(fallible)]
+    pub track: Option<PlainTrack>,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "AlbumMessage")]
+pub struct Album {
+    #[protto(fallible)]
+    pub tracks: Vec<ValidTrack>,
+    #[protto(fallible)]
+    pub bonus_tracks: Option<Vec<ValidTrack>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PlaylistError {
+    BadTrack(String),
+}
+
+impl PlaylistError {
+    pub fn bad_track(field: &str) -> Self {
+        Self::BadTrack(field.to_string())
+    }
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(
+    proto_name = "HasOptional",
+    error_type = PlaylistError,
+    error_fn = "PlaylistError::bad_track"
+)]
+pub struct CheckedMaybeValidTrack {
+    #[protto(fallible)]
+    pub track: Option<ValidTrack>,
+}
+
+#[test]
+fn fallible_some_converts() {
+    let proto_msg = proto::HasOptional {
+        track: Some(proto::Track { track_id: 7 }),
+    };
+
+    let rust = MaybeValidTrack::try_from(proto_msg.clone()).unwrap();
+    assert_eq!(rust.track, Some(ValidTrack { id: 7 }));
+
+    let back: proto::HasOptional = rust.into();
+    assert_eq!(back, proto_msg);
+}
+
+#[test]
+fn fallible_none_stays_none() {
+    let rust = MaybeValidTrack::try_from(proto::HasOptional { track: None }).unwrap();
+    assert_eq!(rust.track, None);
+
+    let back: proto::HasOptional = rust.into();
+    assert_eq!(back.track, None);
+}
+
+#[test]
+fn fallible_nested_error_propagates() {
+    let result = MaybeValidTrack::try_from(proto::HasOptional {
+        track: Some(proto::Track { track_id: 0 }),
+    });
+    assert_eq!(
+        result,
+        Err(MaybeValidTrackConversionError::InvalidValue(
+            "track".to_string()
+        ))
+    );
+}
+
+#[test]
+fn fallible_nested_error_uses_error_fn() {
+    let result = CheckedMaybeValidTrack::try_from(proto::HasOptional {
+        track: Some(proto::Track { track_id: 0 }),
+    });
+    assert_eq!(result, Err(PlaylistError::BadTrack("track".to_string())));
+}
+
+#[test]
+fn fallible_composes_with_infallible_child() {
+    let rust = MaybePlainTrack::try_from(proto::HasOptional {
+        track: Some(proto::Track { track_id: 3 }),
+    })
+    .unwrap();
+    assert_eq!(rust.track, Some(PlainTrack { track_id: 3 }));
+}
+
+#[test]
+fn fallible_collections_convert_each_element() {
+    let proto_msg = proto::AlbumMessage {
+        tracks: vec![proto::Track { track_id: 1 }, proto::Track { track_id: 2 }],
+        bonus_tracks: vec![],
+    };
+
+    let rust = Album::try_from(proto_msg.clone()).unwrap();
+    assert_eq!(
+        rust.tracks,
+        vec![ValidTrack { id: 1 }, ValidTrack { id: 2 }]
+    );
+    assert_eq!(rust.bonus_tracks, None);
+
+    let back: proto::AlbumMessage = rust.into();
+    assert_eq!(back, proto_msg);
+}
+
+#[test]
+fn fallible_collection_element_error_propagates() {
+    let result = Album::try_from(proto::AlbumMessage {
+        tracks: vec![proto::Track { track_id: 1 }],
+        bonus_tracks: vec![proto::Track { track_id: 0 }],
+    });
+    assert_eq!(
+        result,
+        Err(AlbumConversionError::InvalidValue(
+            "bonus_tracks".to_string()
+        ))
+    );
+}
@@ -0,0 +1,39 @@
+// ABOUTME: Tests for the `FIELD_PATHS` and `<FIELD>_PATH` constants generated per struct.
+// ABOUTME: Paths name the proto fields, so renamed and ignored fields are reflected.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "Header", field_paths)]
+pub struct LoggedHeader {
+    #[protto(proto_name = "request_id")]
+    pub id: String,
+    pub timestamp: i64,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(
+    proto_name = "MixedBehaviorTrack",
+    ignore = "optional_with_default",
+    field_paths
+)]
+pub struct PartialTrack {
+    pub track_id: u64,
+    pub required_field: String,
+    #[protto(ignore)]
+    pub cached: Vec<u8>,
+}
+
+#[test]
+fn field_paths_name_proto_fields() {
+    assert_eq!(LoggedHeader::FIELD_PATHS, ["request_id", "timestamp"]);
+    assert_eq!(LoggedHeader::ID_PATH, "request_id");
+    assert_eq!(LoggedHeader::TIMESTAMP_PATH, "timestamp");
+}
+
+#[test]
+fn field_paths_skip_ignored_fields() {
+    assert_eq!(PartialTrack::FIELD_PATHS, ["track_id", "required_field"]);
+    assert_eq!(PartialTrack::TRACK_ID_PATH, "track_id");
+}
@@ -0,0 +1,112 @@
+// ABOUTME: Tests for custom conversion functions on generic structs naming the struct's generic
+// ABOUTME: parameters through turbofish and qualified paths, and the struct itself through `Self`.
+
+use crate::proto;
+use protto::Protto;
+
+pub trait Codec: Sized {
+    fn decode(encoded: String) -> Self;
+    fn encode(self) -> String;
+}
+
+impl Codec for u32 {
+    fn decode(encoded: String) -> Self {
+        encoded.parse().unwrap_or_default()
+    }
+
+    fn encode(self) -> String {
+        self.to_string()
+    }
+}
+
+impl Codec for bool {
+    fn decode(encoded: String) -> Self {
+        encoded == "yes"
+    }
+
+    fn encode(self) -> String {
+        if self { "yes" } else { "no" }.to_string()
+    }
+}
+
+fn decode_as<T: Codec>(encoded: String) -> T {
+    T::decode(encoded)
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "TaggedValueMessage", custom_fns_are = "pure")]
+pub struct TaggedValue<T: Codec> {
+    #[protto(
+        from_proto_fn = "decode_as::<T>",
+        to_proto_fn = "T::encode",
+        proto_required
+    )]
+    pub value: T,
+    #[protto(
+        from_proto_fn = "Self::id_from_proto",
+        to_proto_fn = "Self::id_to_proto"
+    )]
+    pub id: u64,
+}
+
+impl<T: Codec> TaggedValue<T> {
+    fn id_from_proto(id: u64) -> u64 {
+        id + 1000
+    }
+
+    fn id_to_proto(id: u64) -> u64 {
+        id - 1000
+    }
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "TaggedValueMessage")]
+pub struct QualifiedValue<T: Codec> {
+    #[protto(
+        from_proto_fn = "<T as Codec>::decode",
+        to_proto_fn = "<T as Codec>::encode",
+        proto_required
+    )]
+    pub value: T,
+    pub id: u64,
+}
+
+fn tagged_message(value: &str) -> proto::TaggedValueMessage {
+    proto::TaggedValueMessage {
+        value: value.to_string(),
+        id: 7,
+    }
+}
+
+#[test]
+fn test_turbofish_and_self_fns_instantiate_the_generic_struct() {
+    let tagged = TaggedValue::<u32>::from(tagged_message("42"));
+    assert_eq!(
+        tagged,
+        TaggedValue {
+            value: 42,
+            id: 1007
+        }
+    );
+
+    let proto: proto::TaggedValueMessage = tagged.into();
+    assert_eq!(proto, tagged_message("42"));
+}
+
+#[test]
+fn test_self_fns_follow_each_instantiation() {
+    let tagged = TaggedValue::<bool>::from(tagged_message("yes"));
+    assert!(tagged.value);
+
+    let proto: proto::TaggedValueMessage = tagged.into();
+    assert_eq!(proto, tagged_message("yes"));
+}
+
+#[test]
+fn test_qualified_associated_fns_on_the_field_type() {
+    let qualified = QualifiedValue::<u32>::from(tagged_message("5"));
+    assert_eq!(qualified, QualifiedValue { value: 5, id: 7 });
+
+    let proto: proto::TaggedValueMessage = qualified.into();
+    assert_eq!(proto, tagged_message("5"));
+}
@@ -0,0 +1,71 @@
+// ABOUTME: Tests for the `group(...)` field attribute.
+// ABOUTME: A Rust sub-struct is assembled from, and split back into, flat proto fields.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Address {
+    pub street: String,
+    pub city: String,
+    pub zip: String,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "CustomerMessage")]
+pub struct Customer {
+    pub name: String,
+    #[protto(group(street, city, zip = "postal_code"))]
+    pub address: Address,
+}
+
+#[test]
+fn group_from_proto() {
+    let proto_msg = proto::CustomerMessage {
+        name: "Ada".to_string(),
+        street: "1 Main St".to_string(),
+        city: "Springfield".to_string(),
+        postal_code: "12345".to_string(),
+    };
+
+    let customer: Customer = proto_msg.into();
+    assert_eq!(customer.name, "Ada");
+    assert_eq!(
+        customer.address,
+        Address {
+            street: "1 Main St".to_string(),
+            city: "Springfield".to_string(),
+            zip: "12345".to_string(),
+        }
+    );
+}
+
+#[test]
+fn group_to_proto() {
+    let customer = Customer {
+        name: "Grace".to_string(),
+        address: Address {
+            street: "2 Side St".to_string(),
+            city: "Arlington".to_string(),
+            zip: "22201".to_string(),
+        },
+    };
+
+    let proto_msg: proto::CustomerMessage = customer.into();
+    assert_eq!(proto_msg.name, "Grace");
+    assert_eq!(proto_msg.street, "2 Side St");
+    assert_eq!(proto_msg.city, "Arlington");
+    assert_eq!(proto_msg.postal_code, "22201");
+}
+
+#[test]
+fn group_roundtrip() {
+    let original = Customer {
+        name: "Linus".to_string(),
+        address: Address::default(),
+    };
+
+    let proto_msg: proto::CustomerMessage = original.clone().into();
+    let roundtrip: Customer = proto_msg.into();
+    assert_eq!(roundtrip, original);
+}
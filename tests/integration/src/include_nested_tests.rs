@@ -0,0 +1,75 @@
+// ABOUTME: Tests for `include_nested!`, which mirrors the nested messages of a proto message.
+// ABOUTME: The root and `except` messages are hand-written; the rest are generated from metadata.
+
+use crate::proto;
+use protto::Protto;
+use std::collections::HashMap;
+
+protto::include_nested!(proto::OrderMessage, except = "ShippingAddressMessage");
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+pub struct ShippingAddressMessage {
+    pub city: String,
+    #[protto(proto_name = "postal_code")]
+    pub zip: String,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "OrderMessage")]
+pub struct Order {
+    pub order_id: u64,
+    pub buyer: Option<BuyerMessage>,
+    pub items: Vec<LineItemMessage>,
+}
+
+fn order_message() -> proto::OrderMessage {
+    proto::OrderMessage {
+        order_id: 11,
+        buyer: Some(proto::BuyerMessage {
+            name: "Ada".to_string(),
+            address: Some(proto::ShippingAddressMessage {
+                city: "London".to_string(),
+                postal_code: "N1".to_string(),
+            }),
+        }),
+        items: vec![proto::LineItemMessage {
+            sku: "tea".to_string(),
+            quantity: 2,
+            status: proto::Status::Found as i32,
+            notes: HashMap::from([("gift".to_string(), "yes".to_string())]),
+            discount: Some(0.5),
+        }],
+    }
+}
+
+#[test]
+fn include_nested_mirrors_nested_messages() {
+    let order: Order = order_message().into();
+
+    let buyer = order.buyer.as_ref().unwrap();
+    assert_eq!(buyer.name, "Ada");
+    assert_eq!(
+        buyer.address,
+        Some(ShippingAddressMessage {
+            city: "London".to_string(),
+            zip: "N1".to_string(),
+        })
+    );
+    assert_eq!(
+        order.items,
+        vec![LineItemMessage {
+            sku: "tea".to_string(),
+            quantity: 2,
+            status: proto::Status::Found as i32,
+            notes: HashMap::from([("gift".to_string(), "yes".to_string())]),
+            discount: Some(0.5),
+        }]
+    );
+}
+
+#[test]
+fn include_nested_round_trips() {
+    let order: Order = order_message().into();
+    let message: proto::OrderMessage = order.into();
+    assert_eq!(message, order_message());
+}
@@ -0,0 +1,96 @@
+// ABOUTME: Tests for field-level `json`, carrying serde types as JSON documents in proto strings.
+// ABOUTME: Values are serialized into the proto and parsed back, with invalid JSON as an error.
+
+use crate::proto;
+use protto::Protto;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WidgetSettings {
+    pub width: u32,
+    pub theme: String,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "WidgetMessage")]
+pub struct Widget {
+    pub name: String,
+    #[protto(json)]
+    pub settings: WidgetSettings,
+    #[protto(json)]
+    pub labels: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "WidgetMessage")]
+pub struct TrustedWidget {
+    pub name: String,
+    #[protto(json, expect(panic))]
+    pub settings: WidgetSettings,
+    #[protto(json, expect(panic))]
+    pub labels: Option<BTreeMap<String, String>>,
+}
+
+fn widget() -> Widget {
+    Widget {
+        name: "clock".to_string(),
+        settings: WidgetSettings {
+            width: 240,
+            theme: "dark".to_string(),
+        },
+        labels: Some(BTreeMap::from([("team".to_string(), "ui".to_string())])),
+    }
+}
+
+#[test]
+fn json_fields_round_trip() {
+    let proto_msg = proto::WidgetMessage::try_from(widget()).unwrap();
+    assert_eq!(proto_msg.settings, r#"{"width":240,"theme":"dark"}"#);
+    assert_eq!(proto_msg.labels.as_deref(), Some(r#"{"team":"ui"}"#));
+
+    assert_eq!(Widget::try_from(proto_msg).unwrap(), widget());
+}
+
+#[test]
+fn json_unset_optional_is_none() {
+    let widget = Widget::try_from(proto::WidgetMessage {
+        name: "clock".to_string(),
+        settings: r#"{"width":1,"theme":"light"}"#.to_string(),
+        labels: None,
+    })
+    .unwrap();
+    assert_eq!(widget.labels, None);
+}
+
+#[test]
+fn invalid_json_is_an_error() {
+    let err = Widget::try_from(proto::WidgetMessage {
+        name: "clock".to_string(),
+        settings: "{not json".to_string(),
+        labels: None,
+    })
+    .unwrap_err();
+    assert!(err.to_string().contains("settings"));
+}
+
+#[test]
+fn expect_panic_json_fields_convert_infallibly() {
+    let proto_msg: proto::WidgetMessage = TrustedWidget::from(proto::WidgetMessage {
+        name: "clock".to_string(),
+        settings: r#"{"width":240,"theme":"dark"}"#.to_string(),
+        labels: None,
+    })
+    .into();
+    assert_eq!(proto_msg.settings, r#"{"width":240,"theme":"dark"}"#);
+}
+
+#[test]
+#[should_panic(expected = "Proto field settings is not valid JSON")]
+fn expect_panic_json_field_panics_on_invalid_json() {
+    let _ = TrustedWidget::from(proto::WidgetMessage {
+        name: "clock".to_string(),
+        settings: String::new(),
+        labels: None,
+    });
+}
@@ -0,0 +1,60 @@
+// ABOUTME: Tests for the `json` feature's `to_proto_json`/`from_proto_json` helpers.
+// ABOUTME: JSON goes through the proto message, so it follows the protobuf JSON mapping.
+
+use crate::proto;
+use protto::Protto;
+use protto::json::{JsonError, ProtoJson};
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "Header")]
+pub struct JsonHeader {
+    pub request_id: String,
+    pub timestamp: i64,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "Track")]
+pub struct JsonTrack {
+    pub track_id: u64,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "HasOptional")]
+pub struct JsonRequiredTrack {
+    #[protto(expect)]
+    pub track: JsonTrack,
+}
+
+#[test]
+fn to_proto_json_uses_proto_json_names() {
+    let header = JsonHeader {
+        request_id: "req-1".to_string(),
+        timestamp: 42,
+    };
+
+    // lowerCamelCase names, and 64-bit integers as strings
+    let json = header.to_proto_json();
+    assert_eq!(json, r#"{"requestId":"req-1","timestamp":"42"}"#);
+    assert_eq!(JsonHeader::from_proto_json(&json).unwrap(), header);
+}
+
+#[test]
+fn from_proto_json_reports_decode_and_conversion_failures() {
+    assert!(matches!(
+        JsonHeader::from_proto_json(r#"{"requestId":7}"#),
+        Err(JsonError::Json(_))
+    ));
+
+    assert_eq!(
+        JsonRequiredTrack::from_proto_json(r#"{"track":{"trackId":"3"}}"#).unwrap(),
+        JsonRequiredTrack {
+            track: JsonTrack { track_id: 3 },
+        }
+    );
+    assert!(matches!(
+        JsonRequiredTrack::from_proto_json("{}"),
+        Err(JsonError::Conversion(
+            JsonRequiredTrackConversionError::MissingField(field)
+        )) if field == "track"
+    ));
+}
@@ -0,0 +1,109 @@
+// ABOUTME: Tests for proto fields named after rust keywords, which prost generates as raw identifiers.
+// ABOUTME: Covers raw and `type_`-style rust fields, proto_name values naming keywords, and messages.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "KeywordMessage")]
+pub struct RawFields {
+    pub r#type: String,
+    #[protto(expect(panic))]
+    pub r#else: String,
+    pub r#struct: String,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "KeywordMessage")]
+pub struct RenamedFields {
+    #[protto(proto_name = "type")]
+    pub kind: String,
+    #[protto(proto_name = "r#else", expect(error))]
+    pub fallback: String,
+    #[protto(proto_name = "struct")]
+    pub shape: String,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "KeywordMessage", ignore = "struct")]
+pub struct PartialFields {
+    #[protto(proto_name = "type")]
+    pub kind: String,
+    pub r#else: Option<String>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "KeywordMessage")]
+pub struct SuffixedFields {
+    pub type_: String,
+    pub else_: Option<String>,
+    pub struct_: String,
+}
+
+fn keyword_message() -> proto::KeywordMessage {
+    proto::KeywordMessage {
+        r#type: "track".to_string(),
+        r#else: Some("none".to_string()),
+        r#struct: "flat".to_string(),
+    }
+}
+
+#[test]
+fn raw_rust_fields_round_trip() {
+    let fields = RawFields::from(keyword_message());
+    assert_eq!(fields.r#type, "track");
+    assert_eq!(fields.r#else, "none");
+
+    let proto: proto::KeywordMessage = fields.into();
+    assert_eq!(proto, keyword_message());
+}
+
+#[test]
+#[should_panic(expected = "Proto field else is required")]
+fn expect_message_names_field_without_raw_prefix() {
+    let _ = RawFields::from(proto::KeywordMessage {
+        r#else: None,
+        ..keyword_message()
+    });
+}
+
+#[test]
+fn proto_name_accepts_keywords() {
+    let fields = RenamedFields::try_from(keyword_message()).unwrap();
+    assert_eq!(fields.kind, "track");
+    assert_eq!(fields.fallback, "none");
+    assert_eq!(fields.shape, "flat");
+
+    let err = RenamedFields::try_from(proto::KeywordMessage {
+        r#else: None,
+        ..keyword_message()
+    })
+    .unwrap_err();
+    assert_eq!(
+        err,
+        RenamedFieldsConversionError::MissingField("else".to_string())
+    );
+
+    let proto: proto::KeywordMessage = fields.into();
+    assert_eq!(proto, keyword_message());
+}
+
+#[test]
+fn struct_level_ignore_accepts_keywords() {
+    let fields = PartialFields::from(keyword_message());
+    assert_eq!(fields.kind, "track");
+
+    let proto: proto::KeywordMessage = fields.into();
+    assert_eq!(proto.r#struct, "");
+}
+
+#[test]
+fn trailing_underscore_fields_map_to_keyword_fields() {
+    let fields = SuffixedFields::from(keyword_message());
+    assert_eq!(fields.type_, "track");
+    assert_eq!(fields.else_.as_deref(), Some("none"));
+    assert_eq!(fields.struct_, "flat");
+
+    let proto: proto::KeywordMessage = fields.into();
+    assert_eq!(proto, keyword_message());
+}
@@ -0,0 +1,64 @@
+// ABOUTME: Tests for the field-level `kind`, declaring a field's type primitive, message or enum.
+// ABOUTME: Covers type aliases, path-qualified types and an enum declared after the struct using it.
+
+use crate::proto;
+use crate::shared_types::{self, TrackId};
+use protto::Protto;
+
+pub type Millis = u64;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "AlarmMessage")]
+pub struct Alarm {
+    // `Severity` derives `Protto` below this struct, so it is not in the enum registry yet
+    #[protto(kind = "enum")]
+    pub severity: severity::Severity,
+    #[protto(kind = "primitive")]
+    pub raised_at: Millis,
+    #[protto(kind = "message", expect(panic))]
+    pub track: shared_types::Track,
+    #[protto(kind = "enum")]
+    pub escalation: Option<severity::Severity>,
+}
+
+mod severity {
+    use crate::proto;
+    use protto::Protto;
+
+    #[derive(Protto, Debug, Clone, Copy, PartialEq)]
+    pub enum Severity {
+        Minor,
+        Major,
+    }
+}
+
+fn alarm() -> Alarm {
+    Alarm {
+        severity: severity::Severity::Major,
+        raised_at: 1_700_000_000_000,
+        track: shared_types::Track {
+            id: TrackId::new(9),
+        },
+        escalation: None,
+    }
+}
+
+#[test]
+fn test_declared_kinds_convert_to_proto() {
+    let proto_alarm: proto::AlarmMessage = alarm().into();
+    assert_eq!(proto_alarm.severity, proto::Severity::Major as i32);
+    assert_eq!(proto_alarm.raised_at, 1_700_000_000_000);
+    assert_eq!(proto_alarm.track, Some(proto::Track { track_id: 9 }));
+    assert_eq!(proto_alarm.escalation, None);
+}
+
+#[test]
+fn test_declared_kinds_round_trip() {
+    let escalated = Alarm {
+        escalation: Some(severity::Severity::Major),
+        ..alarm()
+    };
+    let proto_alarm: proto::AlarmMessage = escalated.clone().into();
+    assert_eq!(proto_alarm.escalation, Some(proto::Severity::Major as i32));
+    assert_eq!(Alarm::from(proto_alarm), escalated);
+}
@@ -0,0 +1,54 @@
+// ABOUTME: Tests for the `proto-compat` feature accepting `proto_convert_derive` attributes.
+// ABOUTME: Legacy `#[proto(...)]` keys convert like their `#[protto(...)]` replacements.
+
+// every legacy attribute below deliberately triggers the migration deprecation warning
+#![allow(deprecated)]
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackNumber(u64);
+
+fn track_number_from_proto(track_id: u64) -> TrackNumber {
+    TrackNumber(track_id)
+}
+
+fn track_number_to_proto(number: TrackNumber) -> u64 {
+    number.0
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[proto(module = "proto", rename = "Track")]
+pub struct LegacyTrack {
+    #[proto(
+        derive_from_with = "track_number_from_proto",
+        derive_into_with = "track_number_to_proto",
+        proto_required
+    )]
+    pub track_id: TrackNumber,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[proto(rename = "Track")]
+pub struct MixedTrack {
+    #[protto(proto_name = "track_id")]
+    pub id: u64,
+}
+
+#[test]
+fn legacy_attributes_round_trip() {
+    let proto_track = proto::Track { track_id: 42 };
+
+    let track = LegacyTrack::from(proto_track.clone());
+    assert_eq!(track.track_id, TrackNumber(42));
+
+    let back: proto::Track = track.into();
+    assert_eq!(back, proto_track);
+}
+
+#[test]
+fn legacy_and_new_attributes_mix() {
+    let track = MixedTrack::from(proto::Track { track_id: 7 });
+    assert_eq!(track.id, 7);
+}
@@ -2,6 +2,7 @@
 
 pub mod proto {
     tonic::include_proto!("service");
+    include!(concat!(env!("OUT_DIR"), "/service.serde.rs"));
 }
 
 mod basic_types;
@@ -13,22 +14,104 @@ mod shared_types;
 mod additional_edge_case_tests;
 #[cfg(test)]
 mod advanced_tests;
+mod alias_tests;
+mod also_proto_tests;
+mod any_tests;
+mod arena_tests;
+mod async_resolve_tests;
 mod attribute_parser_tests;
+mod attribute_syntax_tests;
 #[cfg(test)]
 mod basic_tests;
+mod batch_tests;
+mod bool_enum_tests;
 mod boolean_boundary_tests;
+mod borrowed_fn_tests;
+mod borrowed_view_tests;
 mod boundary_property_tests;
+mod bounded_collection_tests;
+mod btree_map_tests;
+mod bytes_field_tests;
+mod cfg_field_tests;
+mod cfg_predicate_tests;
 mod code_generation_edge_tests;
+mod conversions_both_tests;
+mod coverage_tests;
+mod debug_roundtrip_tests;
+mod decimal_tests;
 #[cfg(test)]
 mod default_tests;
+mod depends_on_tests;
+mod duration_tests;
 #[cfg(test)]
 mod edge_case_tests;
 #[cfg(test)]
+mod empty_repeated_tests;
+mod enum_alias_tests;
+mod enum_as_i32_tests;
+mod enum_discriminant_tests;
+mod enum_map_tests;
+mod enum_unspecified_tests;
+mod error_fn_cascade_tests;
+mod error_fn_with_value_tests;
+mod error_mod_tests;
+#[cfg(test)]
 mod error_tests;
+mod error_vis_tests;
+mod expect_default_tests;
+mod extra_fields_tests;
+mod fallible_nested_tests;
+mod field_paths_tests;
+mod generic_custom_fn_tests;
+mod group_tests;
+mod include_nested_tests;
 #[cfg(test)]
 mod integration_tests;
+mod json_field_tests;
+mod json_tests;
+mod keyword_field_tests;
+mod kind_tests;
+mod legacy_attribute_tests;
+mod manual_tests;
+mod map_by_tests;
+mod memoize_tests;
+mod nested_message_tests;
+mod none_as_tests;
+mod oneof_tests;
+mod options_tests;
+mod partial_tests;
+mod passthrough_tests;
+mod path_encoding_tests;
+mod pod_slice_tests;
+mod presence_of_tests;
+mod presence_tests;
+mod preserve_unknown_tests;
+mod proto_boxed_tests;
+mod proto_field_type_tests;
+mod proto_only_tests;
+mod proto_parts_tests;
+mod proto_passthrough_tests;
+mod proto_repr_tests;
+mod protto_schema_tests;
+mod pure_custom_fns_tests;
+mod result_oneof_tests;
+mod rules_tests;
+mod schema_metadata_tests;
+mod size_estimate_tests;
+mod sources_tests;
+mod static_errors_tests;
 mod strategy_selection_tests;
+mod tonic_tests;
+mod transparent_collection_tests;
+mod transparent_struct_tests;
+mod try_from_proto_fn_tests;
+mod type_alias_tests;
 mod type_inference_edge_tests;
+mod validate_tests;
+mod vec_conversion_tests;
+mod view_tests;
+mod with_str_tests;
+mod wrapper_type_tests;
 
 // Bug fix verification tests
 #[cfg(test)]
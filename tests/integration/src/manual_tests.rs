@@ -0,0 +1,107 @@
+// ABOUTME: Tests for `manual` fields, which the derive leaves to a FromProtoParts impl.
+// ABOUTME: Covers single and multiple manual fields alongside derived ones, in both directions.
+
+use crate::proto;
+use protto::Protto;
+use protto::parts::FromProtoParts;
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "MaintenanceWindowMessage")]
+pub struct MaintenanceWindow {
+    pub name: String,
+    #[protto(manual)]
+    pub window: Range<u64>,
+    #[protto(manual)]
+    pub labels: BTreeSet<String>,
+}
+
+impl FromProtoParts<proto::MaintenanceWindowMessage> for MaintenanceWindow {
+    type Parts = (Range<u64>, BTreeSet<String>);
+
+    fn from_proto_parts(proto: &proto::MaintenanceWindowMessage) -> Self::Parts {
+        let window = proto
+            .window
+            .split_once("..")
+            .and_then(|(start, end)| Some(start.parse().ok()?..end.parse().ok()?))
+            .unwrap_or(0..0);
+        let labels = proto
+            .labels
+            .split(',')
+            .filter(|label| !label.is_empty())
+            .map(str::to_string)
+            .collect();
+        (window, labels)
+    }
+
+    fn into_proto_parts(
+        (window, labels): Self::Parts,
+        proto: &mut proto::MaintenanceWindowMessage,
+    ) {
+        proto.window = format!("{}..{}", window.start, window.end);
+        proto.labels = labels.into_iter().collect::<Vec<_>>().join(",");
+    }
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "MaintenanceWindowMessage", ignore = "labels")]
+pub struct WindowOnly {
+    pub name: String,
+    #[protto(manual)]
+    pub window: Range<u64>,
+}
+
+impl FromProtoParts<proto::MaintenanceWindowMessage> for WindowOnly {
+    type Parts = Range<u64>;
+
+    fn from_proto_parts(proto: &proto::MaintenanceWindowMessage) -> Self::Parts {
+        MaintenanceWindow::from_proto_parts(proto).0
+    }
+
+    fn into_proto_parts(window: Self::Parts, proto: &mut proto::MaintenanceWindowMessage) {
+        proto.window = format!("{}..{}", window.start, window.end);
+    }
+}
+
+#[test]
+fn test_manual_fields_from_proto() {
+    let proto = proto::MaintenanceWindowMessage {
+        name: "nightly".to_string(),
+        window: "100..200".to_string(),
+        labels: "db,cache".to_string(),
+    };
+    let rust: MaintenanceWindow = proto.into();
+    assert_eq!(rust.name, "nightly");
+    assert_eq!(rust.window, 100..200);
+    assert_eq!(
+        rust.labels,
+        BTreeSet::from(["cache".to_string(), "db".to_string()])
+    );
+}
+
+#[test]
+fn test_manual_fields_into_proto() {
+    let rust = MaintenanceWindow {
+        name: "nightly".to_string(),
+        window: 5..9,
+        labels: BTreeSet::from(["db".to_string()]),
+    };
+    let proto: proto::MaintenanceWindowMessage = rust.clone().into();
+    assert_eq!(proto.name, "nightly");
+    assert_eq!(proto.window, "5..9");
+    assert_eq!(proto.labels, "db");
+    assert_eq!(MaintenanceWindow::from(proto), rust);
+}
+
+#[test]
+fn test_single_manual_field_is_its_own_parts() {
+    let rust = WindowOnly {
+        name: "weekly".to_string(),
+        window: 1..2,
+    };
+    let proto: proto::MaintenanceWindowMessage = rust.clone().into();
+    assert_eq!(proto.window, "1..2");
+    assert_eq!(proto.labels, "");
+    assert_eq!(WindowOnly::from(proto), rust);
+}
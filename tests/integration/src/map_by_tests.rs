@@ -0,0 +1,76 @@
+// ABOUTME: Tests for field-level `map_by`, keying maps by a field of repeated proto messages.
+// ABOUTME: Elements are collected into the map by key and written back to proto sorted by key.
+
+use crate::proto;
+use crate::shared_types::{Track, TrackId};
+use protto::Protto;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "PlaylistIndexMessage")]
+pub struct PlaylistIndex {
+    #[protto(map_by = "track_id")]
+    pub tracks: HashMap<u64, Track>,
+    #[protto(map_by = "key")]
+    pub flags: BTreeMap<String, proto::FeatureFlag>,
+}
+
+fn flag(key: &str, enabled: bool) -> proto::FeatureFlag {
+    proto::FeatureFlag {
+        key: key.to_string(),
+        enabled,
+    }
+}
+
+#[test]
+fn map_by_keys_elements_by_field() {
+    let index = PlaylistIndex::from(proto::PlaylistIndexMessage {
+        tracks: vec![proto::Track { track_id: 7 }, proto::Track { track_id: 3 }],
+        flags: vec![flag("shuffle", true), flag("repeat", false)],
+    });
+
+    assert_eq!(index.tracks.len(), 2);
+    assert_eq!(index.tracks[&7].id, TrackId::new(7));
+    assert_eq!(index.flags["shuffle"], flag("shuffle", true));
+}
+
+#[test]
+fn map_by_writes_elements_sorted_by_key() {
+    let index = PlaylistIndex {
+        tracks: [9, 1, 5]
+            .into_iter()
+            .map(|id| {
+                (
+                    id,
+                    Track {
+                        id: TrackId::new(id),
+                    },
+                )
+            })
+            .collect(),
+        flags: BTreeMap::from([
+            ("shuffle".to_string(), flag("shuffle", true)),
+            ("autoplay".to_string(), flag("autoplay", false)),
+        ]),
+    };
+
+    let proto_msg: proto::PlaylistIndexMessage = index.clone().into();
+    let track_ids: Vec<u64> = proto_msg.tracks.iter().map(|t| t.track_id).collect();
+    assert_eq!(track_ids, [1, 5, 9]);
+    assert_eq!(
+        proto_msg.flags,
+        [flag("autoplay", false), flag("shuffle", true)]
+    );
+
+    assert_eq!(PlaylistIndex::from(proto_msg), index);
+}
+
+#[test]
+fn map_by_keeps_the_last_element_of_a_duplicate_key() {
+    let index = PlaylistIndex::from(proto::PlaylistIndexMessage {
+        tracks: vec![],
+        flags: vec![flag("shuffle", true), flag("shuffle", false)],
+    });
+    assert_eq!(index.flags.len(), 1);
+    assert_eq!(index.flags["shuffle"], flag("shuffle", false));
+}
@@ -0,0 +1,78 @@
+// ABOUTME: Tests for the `memoize` field attribute on repeated nested messages.
+// ABOUTME: Identical sub-messages are converted once and shared through an Arc.
+
+use crate::proto;
+use protto::Protto;
+use std::sync::Arc;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "ResourceMessage")]
+pub struct Resource {
+    pub service_name: String,
+    pub host: String,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "SpanBatchMessage")]
+pub struct SpanBatch {
+    pub span_names: Vec<String>,
+    #[protto(memoize)]
+    pub resources: Vec<Arc<Resource>>,
+}
+
+fn resource_message(host: &str) -> proto::ResourceMessage {
+    proto::ResourceMessage {
+        service_name: "checkout".to_string(),
+        host: host.to_string(),
+    }
+}
+
+#[test]
+fn memoize_shares_identical_sub_messages() {
+    let proto_msg = proto::SpanBatchMessage {
+        span_names: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        resources: vec![
+            resource_message("web-1"),
+            resource_message("web-2"),
+            resource_message("web-1"),
+        ],
+    };
+
+    let batch: SpanBatch = proto_msg.clone().into();
+    assert_eq!(batch.resources.len(), 3);
+    assert!(Arc::ptr_eq(&batch.resources[0], &batch.resources[2]));
+    assert!(!Arc::ptr_eq(&batch.resources[0], &batch.resources[1]));
+    assert_eq!(batch.resources[1].host, "web-2");
+
+    let back: proto::SpanBatchMessage = batch.into();
+    assert_eq!(back, proto_msg);
+}
+
+#[test]
+fn memoize_converts_shared_values_to_messages() {
+    let shared = Arc::new(Resource {
+        service_name: "checkout".to_string(),
+        host: "web-1".to_string(),
+    });
+    let batch = SpanBatch {
+        span_names: Vec::new(),
+        resources: vec![
+            shared.clone(),
+            Arc::new(Resource {
+                service_name: "checkout".to_string(),
+                host: "web-2".to_string(),
+            }),
+            shared,
+        ],
+    };
+
+    let proto_msg: proto::SpanBatchMessage = batch.into();
+    assert_eq!(
+        proto_msg.resources,
+        vec![
+            resource_message("web-1"),
+            resource_message("web-2"),
+            resource_message("web-1"),
+        ]
+    );
+}
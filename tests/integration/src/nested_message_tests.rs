@@ -0,0 +1,72 @@
+// ABOUTME: Tests for targeting messages and oneofs prost nests in the module of their parent message.
+// ABOUTME: Covers same-named nested messages in different parents and a oneof beside them.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "cart_message::Item")]
+pub struct CartItem {
+    pub sku: String,
+    pub quantity: u32,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "wishlist_message::Item")]
+pub struct WishlistItem {
+    pub sku: String,
+    pub note: Option<String>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(oneof = "cart_message::Payment")]
+pub enum CartPayment {
+    CardToken(String),
+    VoucherId(u64),
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "CartMessage")]
+pub struct Cart {
+    pub items: Vec<CartItem>,
+    #[protto(oneof = "payment", expect(panic))]
+    pub payment: CartPayment,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "WishlistMessage")]
+pub struct Wishlist {
+    pub items: Vec<WishlistItem>,
+}
+
+#[test]
+fn test_nested_messages_round_trip() {
+    let cart = Cart {
+        items: vec![CartItem {
+            sku: "SKU-1".to_string(),
+            quantity: 2,
+        }],
+        payment: CartPayment::VoucherId(40),
+    };
+    let proto_cart: proto::CartMessage = cart.clone().into();
+    assert_eq!(proto_cart.items[0].quantity, 2);
+    assert_eq!(
+        proto_cart.payment,
+        Some(proto::cart_message::Payment::VoucherId(40))
+    );
+    assert_eq!(Cart::from(proto_cart), cart);
+}
+
+#[test]
+fn test_same_named_nested_messages_stay_apart() {
+    let proto_wishlist = proto::WishlistMessage {
+        items: vec![proto::wishlist_message::Item {
+            sku: "SKU-2".to_string(),
+            note: Some("gift".to_string()),
+        }],
+    };
+    let wishlist = Wishlist::from(proto_wishlist.clone());
+    assert_eq!(wishlist.items[0].note.as_deref(), Some("gift"));
+    let round_trip: proto::WishlistMessage = wishlist.into();
+    assert_eq!(round_trip, proto_wishlist);
+}
@@ -0,0 +1,158 @@
+// ABOUTME: Tests for the `none_as`, `none_as_empty` and `none_as_unset` field attributes.
+// ABOUTME: Rust `Option<T>` fields choose how `None` is written to required and optional fields.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "CustomerMessage")]
+pub struct DefaultingCustomer {
+    pub name: String,
+    #[protto(proto_required, none_as = "default")]
+    pub street: Option<String>,
+    pub city: String,
+    pub postal_code: String,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "CustomerMessage")]
+pub struct PanickingCustomer {
+    pub name: String,
+    #[protto(proto_required, none_as = "panic")]
+    pub street: Option<String>,
+    pub city: String,
+    pub postal_code: String,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "CustomerMessage")]
+pub struct StrictCustomer {
+    pub name: String,
+    #[protto(proto_required, none_as = "error")]
+    pub street: Option<String>,
+    pub city: String,
+    pub postal_code: String,
+}
+
+fn customer_message() -> proto::CustomerMessage {
+    proto::CustomerMessage {
+        name: "Ada".to_string(),
+        street: "1 Main St".to_string(),
+        city: "Springfield".to_string(),
+        postal_code: "12345".to_string(),
+    }
+}
+
+#[test]
+fn none_as_default_writes_proto_default() {
+    let customer = DefaultingCustomer {
+        name: "Ada".to_string(),
+        street: None,
+        city: "Springfield".to_string(),
+        postal_code: "12345".to_string(),
+    };
+
+    let proto_msg: proto::CustomerMessage = customer.into();
+    assert_eq!(proto_msg.street, "");
+}
+
+#[test]
+fn none_as_default_round_trips_some() {
+    let customer: DefaultingCustomer = customer_message().into();
+    assert_eq!(customer.street, Some("1 Main St".to_string()));
+
+    let proto_msg: proto::CustomerMessage = customer.into();
+    assert_eq!(proto_msg, customer_message());
+}
+
+#[test]
+#[should_panic(expected = "Rust field street is None but proto field street is required")]
+fn none_as_panic_panics_on_none() {
+    let customer = PanickingCustomer {
+        name: "Ada".to_string(),
+        street: None,
+        city: "Springfield".to_string(),
+        postal_code: "12345".to_string(),
+    };
+
+    let _proto_msg: proto::CustomerMessage = customer.into();
+}
+
+#[test]
+fn none_as_error_returns_missing_field() {
+    let customer = StrictCustomer {
+        name: "Ada".to_string(),
+        street: None,
+        city: "Springfield".to_string(),
+        postal_code: "12345".to_string(),
+    };
+
+    let result = proto::CustomerMessage::try_from(customer);
+    assert_eq!(
+        result,
+        Err(StrictCustomerConversionError::MissingField(
+            "street".to_string()
+        ))
+    );
+}
+
+#[test]
+fn none_as_error_converts_some() {
+    let customer: StrictCustomer = customer_message().into();
+    let proto_msg = proto::CustomerMessage::try_from(customer).unwrap();
+    assert_eq!(proto_msg, customer_message());
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "TrackWithOptionals")]
+pub struct EditedTrack {
+    pub track_id: u64,
+    #[protto(none_as_empty)]
+    pub name: Option<String>,
+    #[protto(none_as_unset)]
+    pub duration: Option<u32>,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "MixedBehaviorTrack")]
+pub struct SparseTrack {
+    pub track_id: u64,
+    #[protto(none_as_empty)]
+    pub required_field: Option<String>,
+    #[protto(none_as_empty)]
+    pub optional_with_default: Option<String>,
+}
+
+#[test]
+fn none_as_empty_sets_optional_field() {
+    let track = EditedTrack {
+        track_id: 1,
+        name: None,
+        duration: None,
+    };
+    let proto: proto::TrackWithOptionals = track.into();
+    assert_eq!(proto.name, Some(String::new()));
+    assert_eq!(proto.duration, None);
+
+    let track = EditedTrack {
+        track_id: 1,
+        name: Some("intro".to_string()),
+        duration: Some(90),
+    };
+    let proto: proto::TrackWithOptionals = track.clone().into();
+    assert_eq!(proto.name.as_deref(), Some("intro"));
+    assert_eq!(proto.duration, Some(90));
+    assert_eq!(EditedTrack::from(proto), track);
+}
+
+#[test]
+fn none_as_empty_writes_required_field_default() {
+    let track = SparseTrack {
+        track_id: 2,
+        required_field: None,
+        optional_with_default: Some("kept".to_string()),
+    };
+    let proto: proto::MixedBehaviorTrack = track.into();
+    assert_eq!(proto.required_field, "");
+    assert_eq!(proto.optional_with_default, "kept");
+}
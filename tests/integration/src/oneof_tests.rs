@@ -0,0 +1,183 @@
+// ABOUTME: Tests for sharing one rust enum across proto messages embedding the same oneof.
+// ABOUTME: Covers every listed oneof, `non_exhaustive_oneof` and unit variants for empty messages.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(oneof = "envelope_message::Payload, notice_message::Payload")]
+pub enum Payload {
+    Text(String),
+    Code(u64),
+    Header(proto::Header),
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "EnvelopeMessage")]
+pub struct Envelope {
+    pub id: String,
+    #[protto(oneof = "payload")]
+    pub body: Option<Payload>,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "NoticeMessage")]
+pub struct Notice {
+    pub priority: u32,
+    #[protto(oneof = "payload", expect(panic))]
+    pub payload: Payload,
+}
+
+#[test]
+fn envelope_round_trips_each_variant() {
+    let header = proto::Header {
+        request_id: "req-1".to_string(),
+        timestamp: 42,
+    };
+    for payload in [
+        Payload::Text("hello".to_string()),
+        Payload::Code(404),
+        Payload::Header(header),
+    ] {
+        let envelope = Envelope {
+            id: "env-1".to_string(),
+            body: Some(payload),
+        };
+        let proto_envelope: proto::EnvelopeMessage = envelope.clone().into();
+        assert_eq!(Envelope::from(proto_envelope), envelope);
+    }
+}
+
+#[test]
+fn envelope_without_payload() {
+    let proto_envelope = proto::EnvelopeMessage {
+        id: "env-2".to_string(),
+        payload: None,
+    };
+
+    let envelope = Envelope::from(proto_envelope);
+    assert_eq!(envelope.body, None);
+}
+
+#[test]
+fn shared_enum_converts_from_both_messages() {
+    let proto_notice = proto::NoticeMessage {
+        priority: 1,
+        payload: Some(proto::notice_message::Payload::Code(7)),
+    };
+    let notice = Notice::from(proto_notice.clone());
+    assert_eq!(notice.payload, Payload::Code(7));
+    let round_tripped: proto::NoticeMessage = notice.clone().into();
+    assert_eq!(round_tripped, proto_notice);
+
+    let envelope = Envelope {
+        id: "env-3".to_string(),
+        body: Some(notice.payload),
+    };
+    let proto_envelope: proto::EnvelopeMessage = envelope.into();
+    assert_eq!(
+        proto_envelope.payload,
+        Some(proto::envelope_message::Payload::Code(7))
+    );
+}
+
+#[test]
+#[should_panic]
+fn missing_required_payload_panics() {
+    let proto_notice = proto::NoticeMessage {
+        priority: 1,
+        payload: None,
+    };
+    let _ = Notice::from(proto_notice);
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(
+    oneof = "envelope_message::Payload",
+    non_exhaustive_oneof = "error_variant"
+)]
+pub enum PlainPayload {
+    Text(String),
+    Code(u64),
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "EnvelopeMessage")]
+pub struct PlainEnvelope {
+    pub id: String,
+    #[protto(oneof = "payload", fallible)]
+    pub body: Option<PlainPayload>,
+}
+
+#[test]
+fn non_exhaustive_oneof_converts_covered_variants() {
+    let proto_envelope = proto::EnvelopeMessage {
+        id: "env-4".to_string(),
+        payload: Some(proto::envelope_message::Payload::Text("hi".to_string())),
+    };
+    let envelope = PlainEnvelope::try_from(proto_envelope.clone()).unwrap();
+    assert_eq!(envelope.body, Some(PlainPayload::Text("hi".to_string())));
+
+    let round_tripped: proto::EnvelopeMessage = envelope.into();
+    assert_eq!(round_tripped, proto_envelope);
+}
+
+#[test]
+fn non_exhaustive_oneof_rejects_unmapped_variants() {
+    let header = proto::envelope_message::Payload::Header(proto::Header {
+        request_id: "req-2".to_string(),
+        timestamp: 1,
+    });
+    assert_eq!(
+        PlainPayload::try_from(header.clone()),
+        Err(protto::oneof::UnmappedOneofVariant {
+            target: "PlainPayload"
+        })
+    );
+
+    let proto_envelope = proto::EnvelopeMessage {
+        id: "env-5".to_string(),
+        payload: Some(header),
+    };
+    assert_eq!(
+        PlainEnvelope::try_from(proto_envelope),
+        Err(PlainEnvelopeConversionError::InvalidValue(
+            "payload".to_string()
+        ))
+    );
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(oneof = "heartbeat_message::Signal")]
+pub enum Signal {
+    Ping,
+    Note(String),
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "HeartbeatMessage")]
+pub struct Heartbeat {
+    #[protto(oneof = "signal")]
+    pub signal: Option<Signal>,
+}
+
+#[test]
+fn unit_variants_map_to_empty_messages() {
+    let ping = Heartbeat {
+        signal: Some(Signal::Ping),
+    };
+    let proto_ping: proto::HeartbeatMessage = ping.clone().into();
+    assert_eq!(
+        proto_ping.signal,
+        Some(proto::heartbeat_message::Signal::Ping(
+            proto::PingMessage {}
+        ))
+    );
+    assert_eq!(Heartbeat::from(proto_ping), ping);
+
+    let note = Heartbeat {
+        signal: Some(Signal::Note("slow".to_string())),
+    };
+    let proto_note: proto::HeartbeatMessage = note.clone().into();
+    assert_eq!(Heartbeat::from(proto_note), note);
+}
@@ -0,0 +1,128 @@
+// ABOUTME: Tests for `from_proto_with_options`, relaxing declared conversion errors at runtime.
+// ABOUTME: Covers strict and lenient missing_field, unknown_enum and overflow policies, also
+// ABOUTME: on the worker threads of a parallel batch conversion.
+
+use crate::proto;
+use arrayvec::ArrayVec;
+use protto::Protto;
+use protto::batch::convert_batch_par;
+use protto::options::{ConvertOptions, FromProtoWithOptions, Policy, with_options};
+
+#[derive(Protto, Debug, Clone, Copy, PartialEq)]
+#[protto(enum_as_i32)]
+pub enum StageStatus {
+    Pending = 0,
+    Done = 3,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "JobMessage")]
+pub struct Stage {
+    #[protto(enum_as_i32 = "error")]
+    pub status: StageStatus,
+    #[protto(enum_as_i32 = "error")]
+    pub previous_status: Option<StageStatus>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(module = "proto", proto_name = "SimpleMessage")]
+pub struct Settings {
+    #[protto(required)]
+    pub required_field: String,
+    #[protto(required)]
+    pub required_number: u64,
+    #[protto(optional)]
+    pub optional_field: Option<String>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "BoundedListMessage")]
+pub struct TagList {
+    #[protto(overflow = "error")]
+    pub tags: ArrayVec<String, 2>,
+    pub ids: Vec<u64>,
+}
+
+fn bare_settings() -> proto::SimpleMessage {
+    proto::SimpleMessage {
+        required_field: None,
+        required_number: Some(7),
+        optional_field: None,
+    }
+}
+
+#[test]
+fn test_strict_options_match_try_from() {
+    let strict = Settings::from_proto_with_options(bare_settings(), &ConvertOptions::STRICT);
+    assert_eq!(strict, Settings::try_from(bare_settings()));
+    assert_eq!(
+        strict.unwrap_err(),
+        SettingsConversionError::MissingField("required_field".to_string())
+    );
+    assert_eq!(ConvertOptions::default(), ConvertOptions::STRICT);
+}
+
+#[test]
+fn test_lenient_missing_field_reads_proto_default() {
+    let settings =
+        Settings::from_proto_with_options(bare_settings(), &ConvertOptions::LENIENT).unwrap();
+    assert_eq!(settings.required_field, "");
+    assert_eq!(settings.required_number, 7);
+    assert_eq!(settings.optional_field, None);
+
+    // Options apply only inside from_proto_with_options
+    assert!(Settings::try_from(bare_settings()).is_err());
+}
+
+#[test]
+fn test_lenient_unknown_enum_reads_zero() {
+    let proto_msg = proto::JobMessage {
+        status: 42,
+        previous_status: Some(-1),
+    };
+    assert!(Stage::from_proto_with_options(proto_msg.clone(), &ConvertOptions::STRICT).is_err());
+
+    let options = ConvertOptions {
+        unknown_enum: Policy::Lenient,
+        ..ConvertOptions::STRICT
+    };
+    let stage = Stage::from_proto_with_options(proto_msg, &options).unwrap();
+    assert_eq!(stage.status, StageStatus::Pending);
+    assert_eq!(stage.previous_status, Some(StageStatus::Pending));
+}
+
+#[test]
+fn test_lenient_overflow_truncates() {
+    let proto_msg = proto::BoundedListMessage {
+        tags: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        ids: vec![1],
+    };
+    let missing_only = ConvertOptions {
+        missing_field: Policy::Lenient,
+        ..ConvertOptions::STRICT
+    };
+    assert!(TagList::from_proto_with_options(proto_msg.clone(), &missing_only).is_err());
+
+    let list = TagList::from_proto_with_options(proto_msg, &ConvertOptions::LENIENT).unwrap();
+    assert_eq!(list.tags.as_slice(), ["a", "b"]);
+}
+
+#[test]
+fn test_lenient_missing_field_applies_to_parallel_batches() {
+    let protos = vec![bare_settings(); 10_000];
+    let (index, err) = convert_batch_par::<_, Settings>(protos.clone()).unwrap_err();
+    assert_eq!(index, 0);
+    assert_eq!(
+        err,
+        SettingsConversionError::MissingField("required_field".to_string())
+    );
+
+    let settings: Vec<Settings> =
+        with_options(&ConvertOptions::LENIENT, || convert_batch_par(protos)).unwrap();
+    assert_eq!(settings.len(), 10_000);
+    assert!(
+        settings
+            .iter()
+            .all(|settings| settings.required_field.is_empty() && settings.required_number == 7)
+    );
+}
@@ -0,0 +1,69 @@
+// ABOUTME: Tests for struct-level `partial`, deriving projection types from a subset of a message.
+// ABOUTME: Covers summary and detail types of one message and the defaults of unmapped fields.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "CustomerMessage", partial)]
+pub struct CustomerSummary {
+    pub name: String,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "CustomerMessage", partial)]
+pub struct CustomerLocation {
+    pub city: String,
+    #[protto(proto_name = "postal_code")]
+    pub zip: String,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "CustomerMessage")]
+pub struct CustomerDetail {
+    pub name: String,
+    pub street: String,
+    pub city: String,
+    pub postal_code: String,
+}
+
+fn proto_customer() -> proto::CustomerMessage {
+    proto::CustomerMessage {
+        name: "Ada".to_string(),
+        street: "1 Analytical Way".to_string(),
+        city: "London".to_string(),
+        postal_code: "NW1".to_string(),
+    }
+}
+
+#[test]
+fn test_projections_read_their_fields() {
+    let summary = CustomerSummary::from(proto_customer());
+    assert_eq!(summary.name, "Ada");
+
+    let location = CustomerLocation::from(proto_customer());
+    assert_eq!(location.city, "London");
+    assert_eq!(location.zip, "NW1");
+
+    let detail = CustomerDetail::from(proto_customer());
+    assert_eq!(detail.street, "1 Analytical Way");
+}
+
+#[test]
+fn test_projection_defaults_unmapped_proto_fields() {
+    let proto: proto::CustomerMessage = CustomerLocation::from(proto_customer()).into();
+    assert_eq!(
+        proto,
+        proto::CustomerMessage {
+            city: "London".to_string(),
+            postal_code: "NW1".to_string(),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_full_projection_round_trips() {
+    let proto: proto::CustomerMessage = CustomerDetail::from(proto_customer()).into();
+    assert_eq!(proto, proto_customer());
+}
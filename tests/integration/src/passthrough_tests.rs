@@ -0,0 +1,74 @@
+// ABOUTME: Tests for struct-level `passthrough`, deriving Protto on a prost message itself.
+// ABOUTME: Covers code generic over the conversion traits taking both domain and proto types.
+
+use crate::proto;
+use protto::Protto;
+use protto::version::{ProtoConvertFrom, ProtoConvertTo};
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "BeaconMessage")]
+pub struct Beacon {
+    pub origin: String,
+    pub seq: u64,
+}
+
+/// Encodes anything converting into a beacon message, domain type or the message itself
+fn encode<T: ProtoConvertTo<proto::BeaconMessage>>(value: T) -> proto::BeaconMessage {
+    value.convert_to_proto()
+}
+
+fn decode<T>(message: proto::BeaconMessage) -> T
+where
+    T: ProtoConvertFrom<proto::BeaconMessage>,
+    T::Error: std::fmt::Debug,
+{
+    T::convert_from_proto(message).unwrap()
+}
+
+fn message() -> proto::BeaconMessage {
+    proto::BeaconMessage {
+        origin: "edge-1".to_string(),
+        seq: 42,
+    }
+}
+
+#[test]
+fn test_generic_code_takes_domain_and_passthrough_types() {
+    let domain = Beacon {
+        origin: "edge-1".to_string(),
+        seq: 42,
+    };
+    assert_eq!(encode(domain.clone()), message());
+    assert_eq!(encode(message()), message());
+
+    assert_eq!(decode::<Beacon>(message()), domain);
+    assert_eq!(decode::<proto::BeaconMessage>(message()), message());
+}
+
+#[test]
+fn test_passthrough_items_use_the_message_itself() {
+    use protto::any::ToProtoAny;
+    use protto::json::ProtoJson;
+    use protto::options::FromProtoWithOptions;
+    use protto::size::EstimatedProtoSize;
+
+    let options = protto::options::ConvertOptions::default();
+    assert_eq!(
+        proto::BeaconMessage::from_proto_with_options(message(), &options),
+        Ok(message())
+    );
+    assert_eq!(
+        message().estimated_proto_size(),
+        prost::Message::encoded_len(&message())
+    );
+    assert_eq!(
+        message().to_proto_any().type_url,
+        "type.googleapis.com/service.BeaconMessage"
+    );
+
+    let json = message().to_proto_json();
+    assert_eq!(
+        proto::BeaconMessage::from_proto_json(&json).unwrap(),
+        message()
+    );
+}
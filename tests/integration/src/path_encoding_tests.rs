@@ -0,0 +1,98 @@
+// ABOUTME: Tests for the `path_encoding` field attribute.
+// ABOUTME: PathBuf and OsString fields are encoded as proto strings (strict or lossy) or bytes.
+
+use crate::proto;
+use protto::Protto;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "FileRefMessage")]
+pub struct LossyFileRef {
+    #[protto(path_encoding = "lossy")]
+    pub path: PathBuf,
+    #[protto(path_encoding = "bytes")]
+    pub raw_path: OsString,
+    #[protto(path_encoding = "lossy")]
+    pub log_dir: Option<PathBuf>,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "FileRefMessage")]
+pub struct StrictFileRef {
+    #[protto(path_encoding = "utf8_strict")]
+    pub path: PathBuf,
+    #[protto(path_encoding = "bytes")]
+    pub raw_path: PathBuf,
+    #[protto(path_encoding = "utf8_strict")]
+    pub log_dir: Option<PathBuf>,
+}
+
+fn file_ref_message() -> proto::FileRefMessage {
+    proto::FileRefMessage {
+        path: "/etc/app/config.toml".to_string(),
+        raw_path: b"/var/lib/app/data.bin".to_vec(),
+        log_dir: Some("/var/log/app".to_string()),
+    }
+}
+
+#[test]
+fn lossy_path_round_trip() {
+    let file_ref: LossyFileRef = file_ref_message().into();
+    assert_eq!(file_ref.path, PathBuf::from("/etc/app/config.toml"));
+    assert_eq!(file_ref.raw_path, OsString::from("/var/lib/app/data.bin"));
+    assert_eq!(file_ref.log_dir, Some(PathBuf::from("/var/log/app")));
+
+    let proto_msg: proto::FileRefMessage = file_ref.into();
+    assert_eq!(proto_msg, file_ref_message());
+}
+
+#[test]
+fn strict_path_round_trip() {
+    let file_ref: StrictFileRef = file_ref_message().into();
+    let proto_msg = proto::FileRefMessage::try_from(file_ref).unwrap();
+    assert_eq!(proto_msg, file_ref_message());
+}
+
+#[test]
+fn optional_path_absent() {
+    let proto_msg = proto::FileRefMessage {
+        log_dir: None,
+        ..file_ref_message()
+    };
+
+    let file_ref: StrictFileRef = proto_msg.clone().into();
+    assert_eq!(file_ref.log_dir, None);
+    assert_eq!(
+        proto::FileRefMessage::try_from(file_ref).unwrap(),
+        proto_msg
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_paths() {
+    use std::os::unix::ffi::OsStringExt;
+    let non_utf8 = PathBuf::from(OsString::from_vec(vec![b'/', b'a', 0xff]));
+
+    let strict = StrictFileRef {
+        path: non_utf8.clone(),
+        raw_path: non_utf8.clone(),
+        log_dir: None,
+    };
+    assert_eq!(
+        proto::FileRefMessage::try_from(strict),
+        Err(StrictFileRefConversionError::InvalidValue(
+            "path".to_string()
+        ))
+    );
+
+    let lossy = LossyFileRef {
+        path: non_utf8.clone(),
+        raw_path: non_utf8.clone().into_os_string(),
+        log_dir: None,
+    };
+    let proto_msg: proto::FileRefMessage = lossy.into();
+    assert_eq!(proto_msg.path, "/a\u{fffd}");
+    assert_eq!(proto_msg.raw_path, vec![b'/', b'a', 0xff]);
+}
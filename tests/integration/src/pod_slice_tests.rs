@@ -0,0 +1,109 @@
+// ABOUTME: Tests for the `bytes_as = "pod_slice"` field attribute.
+// ABOUTME: A Vec of plain-old-data structs is packed into a proto bytes field and decoded back.
+
+use crate::proto;
+use protto::Protto;
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
+
+#[derive(FromBytes, IntoBytes, Immutable, KnownLayout, Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct Sample {
+    pub timestamp: u32,
+    pub value: f32,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "TelemetryBatchMessage")]
+pub struct TelemetryBatch {
+    pub source: String,
+    #[protto(bytes_as = "pod_slice")]
+    pub samples: Vec<Sample>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TelemetryError {
+    Corrupt(String),
+}
+
+impl TelemetryError {
+    pub fn corrupt(field: &str) -> Self {
+        Self::Corrupt(field.to_string())
+    }
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(
+    proto_name = "TelemetryBatchMessage",
+    error_type = TelemetryError,
+    error_fn = "TelemetryError::corrupt"
+)]
+pub struct CheckedTelemetryBatch {
+    pub source: String,
+    #[protto(bytes_as = "pod_slice")]
+    pub samples: Vec<Sample>,
+}
+
+fn samples() -> Vec<Sample> {
+    vec![
+        Sample {
+            timestamp: 1,
+            value: 0.5,
+        },
+        Sample {
+            timestamp: 2,
+            value: -1.25,
+        },
+    ]
+}
+
+#[test]
+fn pod_slice_round_trips() {
+    let batch = TelemetryBatch {
+        source: "sensor-a".to_string(),
+        samples: samples(),
+    };
+
+    let proto_msg: proto::TelemetryBatchMessage = batch.clone().into();
+    assert_eq!(proto_msg.samples.len(), 2 * size_of::<Sample>());
+
+    let round_tripped = TelemetryBatch::try_from(proto_msg).unwrap();
+    assert_eq!(round_tripped, batch);
+}
+
+#[test]
+fn pod_slice_empty_bytes_decode_to_empty_vec() {
+    let proto_msg = proto::TelemetryBatchMessage {
+        source: "sensor-a".to_string(),
+        samples: Vec::new(),
+    };
+
+    let batch = TelemetryBatch::try_from(proto_msg).unwrap();
+    assert!(batch.samples.is_empty());
+}
+
+#[test]
+fn pod_slice_rejects_partial_element() {
+    let proto_msg = proto::TelemetryBatchMessage {
+        source: "sensor-a".to_string(),
+        samples: vec![0; size_of::<Sample>() + 3],
+    };
+
+    let result = TelemetryBatch::try_from(proto_msg);
+    assert_eq!(
+        result,
+        Err(TelemetryBatchConversionError::InvalidValue(
+            "samples".to_string()
+        ))
+    );
+}
+
+#[test]
+fn pod_slice_error_uses_error_fn() {
+    let proto_msg = proto::TelemetryBatchMessage {
+        source: "sensor-a".to_string(),
+        samples: vec![0; 5],
+    };
+
+    let result = CheckedTelemetryBatch::try_from(proto_msg);
+    assert_eq!(result, Err(TelemetryError::Corrupt("samples".to_string())));
+}
@@ -0,0 +1,75 @@
+// ABOUTME: Tests for presence_of, reading whether an optional proto message is set into a bool
+// ABOUTME: field without a domain type for the message itself.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "AuditedRecordMessage")]
+pub struct AuditedRecord {
+    pub id: u64,
+    #[protto(presence_of = "audit")]
+    pub has_audit: bool,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "AuditMessage")]
+pub struct Audit {
+    pub actor: String,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "AuditedRecordMessage")]
+pub struct DetailedRecord {
+    pub id: u64,
+    pub audit: Option<Audit>,
+    #[protto(presence_of = "audit")]
+    pub has_audit: bool,
+}
+
+fn audited_message() -> proto::AuditedRecordMessage {
+    proto::AuditedRecordMessage {
+        id: 3,
+        audit: Some(proto::AuditMessage {
+            actor: "ops".to_string(),
+        }),
+    }
+}
+
+#[test]
+fn test_presence_of_reads_whether_the_message_is_set() {
+    let record = AuditedRecord::from(audited_message());
+    assert_eq!(
+        record,
+        AuditedRecord {
+            id: 3,
+            has_audit: true,
+        }
+    );
+
+    let record = AuditedRecord::from(proto::AuditedRecordMessage { id: 4, audit: None });
+    assert!(!record.has_audit);
+}
+
+#[test]
+fn test_presence_of_leaves_the_message_unset() {
+    let proto: proto::AuditedRecordMessage = AuditedRecord {
+        id: 3,
+        has_audit: true,
+    }
+    .into();
+    assert_eq!(proto, proto::AuditedRecordMessage { id: 3, audit: None });
+}
+
+#[test]
+fn test_presence_of_alongside_the_converted_message() {
+    let record = DetailedRecord::from(audited_message());
+    assert!(record.has_audit);
+    assert_eq!(
+        record.audit.as_ref().map(|audit| audit.actor.as_str()),
+        Some("ops")
+    );
+
+    let proto: proto::AuditedRecordMessage = record.into();
+    assert_eq!(proto, audited_message());
+}
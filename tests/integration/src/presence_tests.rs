@@ -0,0 +1,93 @@
+// ABOUTME: Tests for the struct-level `presence` attribute.
+// ABOUTME: A companion struct records which optional proto fields were set before conversion.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "TrackWithOptionals", presence)]
+pub struct AuditedTrackInfo {
+    pub track_id: u64,
+    pub name: Option<String>,
+    #[protto(default)]
+    pub duration: u32,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "OptionalMessage", presence)]
+pub struct AuditedOptionalMessage {
+    pub id: u64,
+    #[protto(expect)]
+    pub name: String,
+    pub count: Option<u32>,
+    #[protto(default)]
+    pub priority: u32,
+    pub tags: Vec<String>,
+}
+
+#[test]
+fn presence_records_set_optional_fields() {
+    let (track, presence) = AuditedTrackInfo::from_proto_with_presence(proto::TrackWithOptionals {
+        track_id: 1,
+        name: None,
+        duration: Some(0),
+    });
+    assert_eq!(track.name, None);
+    assert_eq!(track.duration, 0);
+    assert_eq!(
+        presence,
+        AuditedTrackInfoPresence {
+            name: false,
+            duration: true,
+        }
+    );
+
+    // a defaulted field reads the same whether it was sent or not; presence tells them apart
+    let (track, presence) = AuditedTrackInfo::from_proto_with_presence(proto::TrackWithOptionals {
+        track_id: 1,
+        name: Some("intro".to_string()),
+        duration: None,
+    });
+    assert_eq!(track.duration, 0);
+    assert!(presence.name);
+    assert!(!presence.duration);
+}
+
+#[test]
+fn presence_follows_fallible_conversion() {
+    let message = proto::OptionalMessage {
+        id: 7,
+        name: Some("batch".to_string()),
+        count: None,
+        priority: Some(3),
+        tags: vec!["a".to_string()],
+    };
+    let (converted, presence) =
+        AuditedOptionalMessage::from_proto_with_presence(message.clone()).unwrap();
+    assert_eq!(
+        converted,
+        AuditedOptionalMessage::try_from(message).unwrap()
+    );
+    assert_eq!(
+        presence,
+        AuditedOptionalMessagePresence {
+            name: true,
+            count: false,
+            priority: true,
+        }
+    );
+
+    let missing_name = proto::OptionalMessage {
+        id: 7,
+        name: None,
+        count: Some(1),
+        priority: None,
+        tags: Vec::new(),
+    };
+    assert_eq!(
+        AuditedOptionalMessage::from_proto_with_presence(missing_name),
+        Err(AuditedOptionalMessageConversionError::MissingField(
+            "name".to_string()
+        ))
+    );
+}
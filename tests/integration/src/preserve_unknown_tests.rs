@@ -0,0 +1,62 @@
+// ABOUTME: Tests for preserve_unknown, keeping the proto message a struct was converted from so
+// ABOUTME: the proto fields the struct does not model survive the conversion back to proto.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "ProxiedUserMessage", ignore = "audit_trail")]
+pub struct ProxiedUser {
+    pub id: u64,
+    pub name: String,
+    #[protto(preserve_unknown)]
+    pub original: Option<proto::ProxiedUserMessage>,
+}
+
+fn user_message() -> proto::ProxiedUserMessage {
+    proto::ProxiedUserMessage {
+        id: 7,
+        name: "Ada".to_string(),
+        routing_hint: "eu-west".to_string(),
+        audit_trail: vec!["created".to_string()],
+    }
+}
+
+#[test]
+fn test_preserve_unknown_keeps_the_original_message() {
+    let user = ProxiedUser::from(user_message());
+    assert_eq!(user.id, 7);
+    assert_eq!(user.original, Some(user_message()));
+}
+
+#[test]
+fn test_preserve_unknown_round_trip_keeps_unmapped_fields() {
+    let mut user = ProxiedUser::from(user_message());
+    user.name = "Grace".to_string();
+    let proto: proto::ProxiedUserMessage = user.into();
+    assert_eq!(
+        proto,
+        proto::ProxiedUserMessage {
+            name: "Grace".to_string(),
+            ..user_message()
+        }
+    );
+}
+
+#[test]
+fn test_preserve_unknown_without_original_starts_from_default() {
+    let user = ProxiedUser {
+        id: 1,
+        name: "New".to_string(),
+        original: None,
+    };
+    let proto: proto::ProxiedUserMessage = user.into();
+    assert_eq!(
+        proto,
+        proto::ProxiedUserMessage {
+            id: 1,
+            name: "New".to_string(),
+            ..Default::default()
+        }
+    );
+}
@@ -0,0 +1,80 @@
+// ABOUTME: Tests for message fields prost generates boxed, as `Option<Box<T>>`.
+// ABOUTME: Recursive fields are detected from the schema metadata; configured ones use `proto_boxed`.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, PartialEq, Debug, Clone, Default)]
+#[protto(proto_name = "Header")]
+pub struct NodeHeader {
+    pub request_id: String,
+    pub timestamp: i64,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "TreeNodeMessage")]
+pub struct TreeNode {
+    pub label: String,
+    pub parent: Option<Box<TreeNode>>,
+    #[protto(proto_boxed, expect(panic))]
+    pub header: NodeHeader,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "TreeNodeMessage")]
+pub struct CheckedTreeNode {
+    pub label: String,
+    #[protto(proto_boxed)]
+    pub parent: Option<Box<TreeNode>>,
+    #[protto(proto_boxed, expect)]
+    pub header: NodeHeader,
+}
+
+fn header(request_id: &str) -> proto::Header {
+    proto::Header {
+        request_id: request_id.to_string(),
+        timestamp: 7,
+    }
+}
+
+fn node_message(
+    label: &str,
+    parent: Option<proto::TreeNodeMessage>,
+    header: Option<proto::Header>,
+) -> proto::TreeNodeMessage {
+    proto::TreeNodeMessage {
+        label: label.to_string(),
+        parent: parent.map(Box::new),
+        header: header.map(Box::new),
+    }
+}
+
+#[test]
+fn boxed_fields_round_trip() {
+    let root = node_message("root", None, Some(header("r")));
+    let leaf = node_message("leaf", Some(root), Some(header("l")));
+
+    let node: TreeNode = leaf.clone().into();
+    assert_eq!(node.header.request_id, "l");
+    let parent = node.parent.as_deref().unwrap();
+    assert_eq!(parent.label, "root");
+    assert_eq!(parent.parent, None);
+
+    let back: proto::TreeNodeMessage = node.into();
+    assert_eq!(back, leaf);
+}
+
+#[test]
+fn missing_boxed_field_uses_error_mode() {
+    let result = CheckedTreeNode::try_from(node_message("orphan", None, None));
+    assert_eq!(
+        result,
+        Err(CheckedTreeNodeConversionError::MissingField(
+            "header".to_string()
+        ))
+    );
+
+    let node = CheckedTreeNode::try_from(node_message("root", None, Some(header("r")))).unwrap();
+    assert_eq!(node.parent, None);
+    assert_eq!(node.header.request_id, "r");
+}
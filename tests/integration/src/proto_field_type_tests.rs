@@ -0,0 +1,68 @@
+// ABOUTME: Tests for the field-level `proto_field_type` attribute.
+// ABOUTME: Fields whose prost type a build config replaced with a newtype convert through Into.
+
+use protto::Protto;
+
+/// Stands in for prost output where `field_attribute`/`type_attribute` swapped a `uint64` for
+/// an id newtype.
+pub mod wire {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct AccountId(pub u64);
+
+    impl From<u64> for AccountId {
+        fn from(id: u64) -> Self {
+            Self(id)
+        }
+    }
+
+    impl From<AccountId> for u64 {
+        fn from(id: AccountId) -> Self {
+            id.0
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct Account {
+        pub id: AccountId,
+        pub owner_ids: Vec<AccountId>,
+        pub name: String,
+    }
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(module = "wire")]
+pub struct Account {
+    #[protto(proto_field_type = "wire::AccountId")]
+    pub id: u64,
+    #[protto(proto_field_type = "Vec<wire::AccountId>")]
+    pub owner_ids: Vec<u64>,
+    pub name: String,
+}
+
+#[test]
+fn newtype_proto_field_converts_into_primitive() {
+    let proto = wire::Account {
+        id: wire::AccountId(7),
+        owner_ids: vec![wire::AccountId(1), wire::AccountId(2)],
+        name: "ops".to_string(),
+    };
+
+    let account = Account::from(proto);
+    assert_eq!(account.id, 7);
+    assert_eq!(account.owner_ids, vec![1, 2]);
+    assert_eq!(account.name, "ops");
+}
+
+#[test]
+fn primitive_converts_into_newtype_proto_field() {
+    let account = Account {
+        id: 42,
+        owner_ids: vec![3],
+        name: "billing".to_string(),
+    };
+
+    let proto: wire::Account = account.into();
+    assert_eq!(proto.id, wire::AccountId(42));
+    assert_eq!(proto.owner_ids, vec![wire::AccountId(3)]);
+    assert_eq!(proto.name, "billing");
+}
@@ -0,0 +1,57 @@
+// ABOUTME: Tests for struct-level `proto_only`, computing proto fields the rust struct has no field for.
+// ABOUTME: Covers free functions and `Self::` methods, and the proto -> rust direction ignoring them.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(
+    proto_name = "DocumentMessage",
+    proto_only(word_count = "count_words", checksum = "Self::checksum")
+)]
+pub struct Document {
+    pub title: String,
+    pub body: String,
+}
+
+fn count_words(document: &Document) -> u32 {
+    document.body.split_whitespace().count() as u32
+}
+
+impl Document {
+    fn checksum(&self) -> u64 {
+        self.title
+            .bytes()
+            .chain(self.body.bytes())
+            .map(u64::from)
+            .sum()
+    }
+}
+
+fn document() -> Document {
+    Document {
+        title: "Hi".to_string(),
+        body: "one two three".to_string(),
+    }
+}
+
+#[test]
+fn test_proto_only_fields_are_computed_into_proto() {
+    let expected_checksum = document().checksum();
+    let proto: proto::DocumentMessage = document().into();
+    assert_eq!(proto.title, "Hi");
+    assert_eq!(proto.body, "one two three");
+    assert_eq!(proto.word_count, 3);
+    assert_eq!(proto.checksum, expected_checksum);
+}
+
+#[test]
+fn test_proto_only_fields_are_dropped_from_proto() {
+    let proto = proto::DocumentMessage {
+        title: "Hi".to_string(),
+        body: "one two three".to_string(),
+        word_count: 99,
+        checksum: 1,
+    };
+    assert_eq!(Document::from(proto), document());
+}
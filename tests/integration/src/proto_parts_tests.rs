@@ -0,0 +1,92 @@
+// ABOUTME: Tests for struct-level `proto_parts`, converting into an intermediate of `Option` fields.
+// ABOUTME: Covers filling ignored fields between conversion and `finish`, and conversion errors.
+
+use crate::proto;
+use protto::Protto;
+use protto::parts::MissingPart;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "CustomerMessage", proto_parts)]
+pub struct Customer {
+    pub name: String,
+    pub street: String,
+    pub city: String,
+    pub postal_code: String,
+    #[protto(ignore)]
+    pub mailing_label: String,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(module = "proto", proto_name = "SimpleMessage", proto_parts)]
+pub struct Account {
+    #[protto(required)]
+    pub required_field: String,
+    #[protto(required)]
+    pub required_number: u64,
+    #[protto(optional)]
+    pub optional_field: Option<String>,
+}
+
+fn customer_message() -> proto::CustomerMessage {
+    proto::CustomerMessage {
+        name: "Ada".to_string(),
+        street: "12 Analytical Way".to_string(),
+        city: "London".to_string(),
+        postal_code: "N1".to_string(),
+    }
+}
+
+#[test]
+fn test_parts_hold_converted_fields_and_leave_ignored_ones_unset() {
+    let parts = CustomerProtoParts::from(customer_message());
+    assert_eq!(parts.name.as_deref(), Some("Ada"));
+    assert_eq!(parts.postal_code.as_deref(), Some("N1"));
+    assert_eq!(parts.mailing_label, None);
+}
+
+#[test]
+fn test_finish_builds_struct_once_derived_fields_are_set() {
+    let mut parts = CustomerProtoParts::from(customer_message());
+    parts.mailing_label = Some(format!(
+        "{}, {} {}",
+        parts.street.as_deref().unwrap_or_default(),
+        parts.city.as_deref().unwrap_or_default(),
+        parts.postal_code.as_deref().unwrap_or_default(),
+    ));
+
+    let customer = parts.finish().unwrap();
+    assert_eq!(customer.name, "Ada");
+    assert_eq!(customer.mailing_label, "12 Analytical Way, London N1");
+}
+
+#[test]
+fn test_finish_reports_the_first_unset_field() {
+    let mut parts = CustomerProtoParts::from(customer_message());
+    parts.city = None;
+
+    let err = parts.finish().unwrap_err();
+    assert_eq!(err, MissingPart { field: "city" });
+    assert_eq!(err.to_string(), "part `city` was never set");
+}
+
+#[test]
+fn test_parts_surface_conversion_errors() {
+    let message = proto::SimpleMessage {
+        required_field: Some("id-7".to_string()),
+        required_number: Some(7),
+        optional_field: None,
+    };
+    let account = AccountProtoParts::try_from(message)
+        .unwrap()
+        .finish()
+        .unwrap();
+    assert_eq!(account.required_number, 7);
+    assert_eq!(account.optional_field, None);
+
+    let message = proto::SimpleMessage {
+        required_field: None,
+        required_number: Some(7),
+        optional_field: None,
+    };
+    assert!(AccountProtoParts::try_from(message).is_err());
+}
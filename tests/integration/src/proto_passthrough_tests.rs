@@ -0,0 +1,63 @@
+// ABOUTME: Tests for rust fields that already hold prost types, which convert as plain moves.
+// ABOUTME: Covers Option, Vec and HashMap positions and crate-qualified proto paths.
+
+use crate::proto;
+use protto::Protto;
+use std::collections::HashMap;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "PlaylistMessage")]
+pub struct Playlist {
+    pub header: Option<proto::Header>,
+    pub tracks: Vec<proto::Track>,
+    pub by_name: HashMap<String, proto::Track>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(module = "crate::proto", proto_name = "PlaylistMessage")]
+pub struct QualifiedPlaylist {
+    pub header: Option<crate::proto::Header>,
+    pub tracks: Vec<crate::proto::Track>,
+    pub by_name: HashMap<String, crate::proto::Track>,
+}
+
+fn playlist_message() -> proto::PlaylistMessage {
+    proto::PlaylistMessage {
+        header: Some(proto::Header {
+            request_id: "req-7".to_string(),
+            timestamp: 1_700_000_000,
+        }),
+        tracks: vec![proto::Track { track_id: 1 }, proto::Track { track_id: 2 }],
+        by_name: HashMap::from([("intro".to_string(), proto::Track { track_id: 1 })]),
+    }
+}
+
+#[test]
+fn test_prost_typed_fields_round_trip_unchanged() {
+    let message = playlist_message();
+    let playlist = Playlist::from(message.clone());
+    assert_eq!(playlist.header, message.header);
+    assert_eq!(playlist.tracks, message.tracks);
+    assert_eq!(playlist.by_name, message.by_name);
+
+    assert_eq!(Into::<proto::PlaylistMessage>::into(playlist), message);
+}
+
+#[test]
+fn test_missing_optional_message_stays_none() {
+    let message = proto::PlaylistMessage {
+        header: None,
+        ..playlist_message()
+    };
+    let playlist = Playlist::from(message.clone());
+    assert_eq!(playlist.header, None);
+    assert_eq!(Into::<proto::PlaylistMessage>::into(playlist), message);
+}
+
+#[test]
+fn test_crate_qualified_proto_paths_are_moved() {
+    let message = playlist_message();
+    let playlist = QualifiedPlaylist::from(message.clone());
+    assert_eq!(playlist.by_name["intro"].track_id, 1);
+    assert_eq!(Into::<proto::PlaylistMessage>::into(playlist), message);
+}
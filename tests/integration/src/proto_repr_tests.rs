@@ -0,0 +1,67 @@
+// ABOUTME: Tests for fields whose type implements `protto::ProtoRepr`, converting through the
+// ABOUTME: type's own proto representation without attributes, alone, in an Option or in a Vec.
+
+use crate::proto;
+use protto::Protto;
+
+/// Stands in for a library type publishing its own proto mapping: an amount of cents carried as
+/// a decimal string.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cents(pub i64);
+
+impl protto::ProtoRepr for Cents {
+    type ProtoType = String;
+
+    fn from_proto_repr(proto: String) -> Self {
+        Self(proto.parse().unwrap_or_default())
+    }
+
+    fn to_proto_repr(self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "InvoiceLineMessage")]
+pub struct InvoiceLine {
+    pub price: Cents,
+    pub adjustments: Vec<Cents>,
+    pub discount: Option<Cents>,
+}
+
+fn line_message() -> proto::InvoiceLineMessage {
+    proto::InvoiceLineMessage {
+        price: "125".to_string(),
+        adjustments: vec!["-5".to_string(), "3".to_string()],
+        discount: Some("10".to_string()),
+    }
+}
+
+#[test]
+fn test_repr_fields_convert_from_proto() {
+    let line: InvoiceLine = line_message().into();
+    assert_eq!(
+        line,
+        InvoiceLine {
+            price: Cents(125),
+            adjustments: vec![Cents(-5), Cents(3)],
+            discount: Some(Cents(10)),
+        }
+    );
+}
+
+#[test]
+fn test_repr_fields_roundtrip() {
+    let proto = line_message();
+    let back: proto::InvoiceLineMessage = InvoiceLine::from(proto.clone()).into();
+    assert_eq!(back, proto);
+
+    let line = InvoiceLine {
+        price: Cents(0),
+        adjustments: Vec::new(),
+        discount: None,
+    };
+    let proto: proto::InvoiceLineMessage = line.clone().into();
+    assert_eq!(proto.discount, None);
+    assert_eq!(InvoiceLine::from(proto), line);
+}
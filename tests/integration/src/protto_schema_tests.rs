@@ -0,0 +1,77 @@
+// ABOUTME: Tests for `#[protto_schema]`, reading field shapes from the `#[prost(...)]` attributes
+// ABOUTME: of prost-generated structs for messages the build metadata does not record.
+
+use protto::Protto;
+
+#[protto::protto_schema]
+pub mod schema_proto {
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ProfileMessage {
+        #[prost(string, optional, tag = "1")]
+        pub nickname: ::core::option::Option<::prost::alloc::string::String>,
+        #[prost(uint32, repeated, tag = "2")]
+        pub scores: ::prost::alloc::vec::Vec<u32>,
+        #[prost(message, optional, tag = "3")]
+        pub avatar: ::core::option::Option<AvatarMessage>,
+        #[prost(string, tag = "4")]
+        pub r#type: ::prost::alloc::string::String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AvatarMessage {
+        #[prost(string, tag = "1")]
+        pub url: ::prost::alloc::string::String,
+    }
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(
+    module = "crate::protto_schema_tests::schema_proto",
+    proto_name = "AvatarMessage"
+)]
+pub struct Avatar {
+    pub url: String,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(
+    module = "crate::protto_schema_tests::schema_proto",
+    proto_name = "ProfileMessage"
+)]
+pub struct Profile {
+    // `optional string` on the proto message, known from prost's attribute
+    pub nickname: String,
+    pub scores: Vec<u32>,
+    pub avatar: Option<Avatar>,
+    pub type_: String,
+}
+
+fn profile_message() -> schema_proto::ProfileMessage {
+    schema_proto::ProfileMessage {
+        nickname: Some("ada".to_string()),
+        scores: vec![3, 5],
+        avatar: Some(schema_proto::AvatarMessage {
+            url: "https://example.com/ada.png".to_string(),
+        }),
+        r#type: "admin".to_string(),
+    }
+}
+
+#[test]
+fn test_schema_shapes_convert_from_proto() {
+    let profile: Profile = profile_message().into();
+    assert_eq!(profile.nickname, "ada");
+    assert_eq!(profile.scores, vec![3, 5]);
+    assert_eq!(
+        profile.avatar.map(|avatar| avatar.url).as_deref(),
+        Some("https://example.com/ada.png")
+    );
+    assert_eq!(profile.type_, "admin");
+}
+
+#[test]
+fn test_schema_shapes_roundtrip() {
+    let proto = profile_message();
+    let back: schema_proto::ProfileMessage = Profile::from(proto.clone()).into();
+    assert_eq!(back, proto);
+}
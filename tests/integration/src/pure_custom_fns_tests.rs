@@ -0,0 +1,65 @@
+// ABOUTME: Tests for the struct-level `custom_fns_are = "pure"` contract.
+// ABOUTME: Custom functions with the exact expected signatures convert as usual.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "Request", custom_fns_are = "pure")]
+pub struct PureRequestSummary {
+    #[protto(
+        proto_name = "header",
+        proto_optional,
+        from_proto_fn_ref = "request_id_of",
+        to_proto_fn = "header_for"
+    )]
+    pub request_id: String,
+    #[protto(
+        proto_name = "payload",
+        from_proto_fn = "payload_length",
+        to_proto_fn = "payload_of_length"
+    )]
+    pub payload_len: u64,
+}
+
+fn request_id_of(header: &proto::Header) -> String {
+    header.request_id.clone()
+}
+
+fn header_for(request_id: String) -> proto::Header {
+    proto::Header {
+        request_id,
+        timestamp: 0,
+    }
+}
+
+fn payload_length(payload: String) -> u64 {
+    payload.len() as u64
+}
+
+fn payload_of_length(len: u64) -> String {
+    "x".repeat(len as usize)
+}
+
+#[test]
+fn pure_custom_fns_convert_both_directions() {
+    let request = proto::Request {
+        header: Some(proto::Header {
+            request_id: "req-9".to_string(),
+            timestamp: 0,
+        }),
+        payload: "xxxx".to_string(),
+    };
+
+    let summary: PureRequestSummary = request.clone().into();
+    assert_eq!(
+        summary,
+        PureRequestSummary {
+            request_id: "req-9".to_string(),
+            payload_len: 4,
+        }
+    );
+
+    let back: proto::Request = summary.into();
+    assert_eq!(back, request);
+}
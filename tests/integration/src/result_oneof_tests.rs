@@ -0,0 +1,71 @@
+// ABOUTME: Tests for `Result<T, E>` fields carried by proto oneofs with an ok and an err field.
+// ABOUTME: Covers both outcomes in each direction, `Option<Result>` and a missing oneof.
+
+use crate::proto;
+use crate::proto::lookup_response_message::{Cached, Outcome};
+use crate::shared_types::{Track, TrackId};
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "LookupFailureMessage")]
+pub struct LookupFailure {
+    pub code: u32,
+    pub reason: String,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "LookupResponseMessage")]
+pub struct LookupResponse {
+    #[protto(result_oneof(ok = "track", err = "failure"), expect)]
+    pub outcome: Result<Track, LookupFailure>,
+    #[protto(result_oneof(ok = "hit", err = "miss"))]
+    pub cached: Option<Result<u64, String>>,
+}
+
+#[test]
+fn test_ok_and_err_map_onto_the_oneof_variants() {
+    let found = LookupResponse {
+        outcome: Ok(Track {
+            id: TrackId::new(7),
+        }),
+        cached: Some(Ok(7)),
+    };
+    let proto: proto::LookupResponseMessage = found.clone().into();
+    assert_eq!(
+        proto.outcome,
+        Some(Outcome::Track(proto::Track { track_id: 7 }))
+    );
+    assert_eq!(proto.cached, Some(Cached::Hit(7)));
+    assert_eq!(LookupResponse::try_from(proto).unwrap(), found);
+
+    let failed = LookupResponse {
+        outcome: Err(LookupFailure {
+            code: 404,
+            reason: "no such track".to_string(),
+        }),
+        cached: Some(Err("evicted".to_string())),
+    };
+    let proto: proto::LookupResponseMessage = failed.clone().into();
+    assert_eq!(
+        proto.outcome,
+        Some(Outcome::Failure(proto::LookupFailureMessage {
+            code: 404,
+            reason: "no such track".to_string(),
+        }))
+    );
+    assert_eq!(proto.cached, Some(Cached::Miss("evicted".to_string())));
+    assert_eq!(LookupResponse::try_from(proto).unwrap(), failed);
+}
+
+#[test]
+fn test_unset_oneofs() {
+    let proto = proto::LookupResponseMessage {
+        outcome: Some(Outcome::Track(proto::Track { track_id: 1 })),
+        cached: None,
+    };
+    assert_eq!(LookupResponse::try_from(proto).unwrap().cached, None);
+
+    let err = LookupResponse::try_from(proto::LookupResponseMessage::default()).unwrap_err();
+    let LookupResponseConversionError::MissingField(field_name) = err;
+    assert_eq!(field_name, "outcome");
+}
@@ -0,0 +1,74 @@
+// ABOUTME: Tests for `#[protto_rules]` modules and struct-level `use_rules`, converting every field
+// ABOUTME: of a type through the module's `from_proto_<Type>`/`to_proto_<Type>` conventions.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Amount {
+    pub cents: i64,
+}
+
+#[protto::protto_rules]
+pub mod ledger_rules {
+    use super::Amount;
+
+    pub fn from_proto_Amount(cents: i64) -> Amount {
+        Amount { cents }
+    }
+
+    pub fn to_proto_Amount(amount: Amount) -> i64 {
+        amount.cents
+    }
+}
+
+pub fn fee_from_cents(cents: i64) -> Amount {
+    Amount { cents: cents * 100 }
+}
+
+pub fn fee_to_cents(fee: Amount) -> i64 {
+    fee.cents / 100
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(
+    proto_name = "LedgerEntryMessage",
+    use_rules = "crate::rules_tests::ledger_rules"
+)]
+pub struct LedgerEntry {
+    #[protto(proto_name = "amount_cents")]
+    pub amount: Amount,
+    // the field's own functions take precedence over the rules
+    #[protto(
+        proto_name = "fee_cents",
+        from_proto_fn = "fee_from_cents",
+        to_proto_fn = "fee_to_cents",
+        proto_required
+    )]
+    pub fee: Amount,
+    pub memo: String,
+}
+
+fn entry_message() -> proto::LedgerEntryMessage {
+    proto::LedgerEntryMessage {
+        amount_cents: 1250,
+        fee_cents: 3,
+        memo: "coffee".to_string(),
+    }
+}
+
+#[test]
+fn test_fields_convert_through_the_rules_module() {
+    let entry = LedgerEntry::from(entry_message());
+    assert_eq!(entry.amount, Amount { cents: 1250 });
+    assert_eq!(entry.memo, "coffee");
+
+    let message: proto::LedgerEntryMessage = entry.into();
+    assert_eq!(message, entry_message());
+}
+
+#[test]
+fn test_field_functions_take_precedence_over_rules() {
+    let entry = LedgerEntry::from(entry_message());
+    assert_eq!(entry.fee, Amount { cents: 300 });
+}
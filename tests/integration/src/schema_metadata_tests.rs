@@ -0,0 +1,71 @@
+// ABOUTME: Tests for field shapes read from the build metadata written by protto::build.
+// ABOUTME: Option, Vec and HashMap fields follow the proto schema rather than the Rust types.
+
+use crate::proto;
+use protto::Protto;
+use std::collections::HashMap;
+
+/// `retries` is a plain `uint32` in proto even though the Rust side is optional
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "SchemaShapedMessage")]
+pub struct SchemaShaped {
+    #[protto(none_as = "default")]
+    pub retries: Option<u32>,
+    pub tags: Vec<String>,
+    pub labels: HashMap<String, String>,
+    pub nickname: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels() -> HashMap<String, String> {
+        HashMap::from([("env".to_string(), "prod".to_string())])
+    }
+
+    #[test]
+    fn test_plain_proto_scalar_wraps_into_rust_option() {
+        let proto_msg = proto::SchemaShapedMessage {
+            retries: 3,
+            tags: vec!["a".to_string()],
+            labels: labels(),
+            nickname: None,
+        };
+
+        let rust: SchemaShaped = proto_msg.into();
+        assert_eq!(rust.retries, Some(3));
+        assert_eq!(rust.tags, vec!["a".to_string()]);
+        assert_eq!(rust.labels, labels());
+        assert_eq!(rust.nickname, None);
+    }
+
+    #[test]
+    fn test_rust_none_writes_proto_default() {
+        let rust = SchemaShaped {
+            retries: None,
+            tags: Vec::new(),
+            labels: HashMap::new(),
+            nickname: Some("ace".to_string()),
+        };
+
+        let proto_msg: proto::SchemaShapedMessage = rust.into();
+        assert_eq!(proto_msg.retries, 0);
+        assert!(proto_msg.labels.is_empty());
+        assert_eq!(proto_msg.nickname.as_deref(), Some("ace"));
+    }
+
+    #[test]
+    fn test_round_trip_preserves_map_and_optional_fields() {
+        let original = SchemaShaped {
+            retries: Some(5),
+            tags: vec!["x".to_string(), "y".to_string()],
+            labels: labels(),
+            nickname: Some("ace".to_string()),
+        };
+
+        let proto_msg: proto::SchemaShapedMessage = original.clone().into();
+        let back: SchemaShaped = proto_msg.into();
+        assert_eq!(back, original);
+    }
+}
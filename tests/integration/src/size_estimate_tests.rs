@@ -0,0 +1,65 @@
+// ABOUTME: Tests for the estimated_proto_size method of structs marked `estimated_size`.
+// ABOUTME: Estimates are compared with prost's encoded_len of the converted messages.
+
+use crate::proto;
+use prost::Message;
+use protto::Protto;
+use protto::size::EstimatedProtoSize;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "Header", estimated_size)]
+pub struct SizedHeader {
+    pub request_id: String,
+    pub timestamp: i64,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "Request", estimated_size)]
+pub struct SizedRequest {
+    pub header: Option<SizedHeader>,
+    pub payload: String,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "Track", estimated_size)]
+pub struct SizedTrack {
+    pub track_id: u64,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "State", estimated_size)]
+pub struct SizedState {
+    pub tracks: Vec<SizedTrack>,
+}
+
+#[test]
+fn test_estimate_matches_encoded_len() {
+    let request = SizedRequest {
+        header: Some(SizedHeader {
+            request_id: "req-1".to_string(),
+            timestamp: -42,
+        }),
+        payload: "x".repeat(200),
+    };
+    let proto_request: proto::Request = request.clone().into();
+    assert_eq!(request.estimated_proto_size(), proto_request.encoded_len());
+
+    let state = SizedState {
+        tracks: (1..=3)
+            .map(|id| SizedTrack { track_id: id * 100 })
+            .collect(),
+    };
+    let proto_state: proto::State = state.clone().into();
+    assert_eq!(state.estimated_proto_size(), proto_state.encoded_len());
+}
+
+#[test]
+fn test_default_values_are_overestimated() {
+    let request = SizedRequest {
+        header: None,
+        payload: String::new(),
+    };
+    let proto_request: proto::Request = request.clone().into();
+    assert_eq!(proto_request.encoded_len(), 0);
+    assert_eq!(request.estimated_proto_size(), 2);
+}
@@ -0,0 +1,69 @@
+// ABOUTME: Tests for struct-level sources, converting a struct to and from the tuple of the
+// ABOUTME: sibling proto messages its fields are split across, each field naming its message.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(sources(header = "proto::Header", body = "proto::EnvelopeBodyMessage"))]
+pub struct Envelope {
+    #[protto(source = "header")]
+    pub request_id: String,
+    #[protto(source = "header")]
+    pub timestamp: i64,
+    #[protto(source = "body")]
+    pub text: String,
+    #[protto(source = "body")]
+    pub tags: Vec<String>,
+    #[protto(source = "body", expect)]
+    pub priority: u32,
+    #[protto(ignore)]
+    pub received: bool,
+}
+
+fn messages() -> (proto::Header, proto::EnvelopeBodyMessage) {
+    (
+        proto::Header {
+            request_id: "req-1".to_string(),
+            timestamp: 1_700_000_000,
+        },
+        proto::EnvelopeBodyMessage {
+            text: "hello".to_string(),
+            tags: vec!["greeting".to_string()],
+            priority: Some(2),
+        },
+    )
+}
+
+#[test]
+fn test_sources_read_each_field_from_its_message() {
+    let envelope = Envelope::try_from(messages()).unwrap();
+    assert_eq!(
+        envelope,
+        Envelope {
+            request_id: "req-1".to_string(),
+            timestamp: 1_700_000_000,
+            text: "hello".to_string(),
+            tags: vec!["greeting".to_string()],
+            priority: 2,
+            received: false,
+        }
+    );
+}
+
+#[test]
+fn test_sources_write_each_field_to_its_message() {
+    let envelope = Envelope::try_from(messages()).unwrap();
+    let (header, body): (proto::Header, proto::EnvelopeBodyMessage) = envelope.into();
+    assert_eq!((header, body), messages());
+}
+
+#[test]
+fn test_sources_report_missing_fields() {
+    let (header, mut body) = messages();
+    body.priority = None;
+    assert_eq!(
+        Envelope::try_from((header, body)).unwrap_err(),
+        EnvelopeConversionError::MissingField("priority".to_string())
+    );
+}
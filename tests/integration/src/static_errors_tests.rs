@@ -0,0 +1,62 @@
+// ABOUTME: Tests for the struct-level `static_errors` attribute.
+// ABOUTME: Generated conversion errors carry `&'static str` field names instead of allocated Strings.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "TrackWithOptionals", static_errors)]
+pub struct LeanTrack {
+    #[protto(proto_required, none_as = "error")]
+    pub track_id: Option<u64>,
+    #[protto(expect(error))]
+    pub name: String,
+    pub duration: Option<u32>,
+}
+
+#[test]
+fn missing_proto_field_reports_static_name() {
+    let proto_track = proto::TrackWithOptionals {
+        track_id: 7,
+        name: None,
+        duration: Some(90),
+    };
+
+    let error = LeanTrack::try_from(proto_track).unwrap_err();
+    assert_eq!(error, LeanTrackConversionError::MissingField("name"));
+    assert_eq!(error.to_string(), "Missing required field: name");
+}
+
+#[test]
+fn rust_none_into_required_field_reports_static_name() {
+    let track = LeanTrack {
+        track_id: None,
+        name: "intro".to_string(),
+        duration: Some(90),
+    };
+
+    let result = proto::TrackWithOptionals::try_from(track);
+    assert_eq!(
+        result,
+        Err(LeanTrackConversionError::MissingField("track_id"))
+    );
+}
+
+#[test]
+fn static_error_is_copy() {
+    fn assert_copy<T: Copy>() {}
+    assert_copy::<LeanTrackConversionError>();
+}
+
+#[test]
+fn present_fields_round_trip() {
+    let proto_track = proto::TrackWithOptionals {
+        track_id: 7,
+        name: Some("intro".to_string()),
+        duration: Some(90),
+    };
+
+    let track = LeanTrack::try_from(proto_track.clone()).unwrap();
+    assert_eq!(track.name, "intro");
+    assert_eq!(proto::TrackWithOptionals::try_from(track), Ok(proto_track));
+}
@@ -0,0 +1,36 @@
+// ABOUTME: Tests for the `tonic` feature's extension traits on gRPC requests and responses.
+// ABOUTME: Request messages convert into derived types, and derived values into responses.
+
+use crate::proto;
+use protto::Protto;
+use protto::tonic::{IntoResponse, RequestExt};
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "HasOptional")]
+pub struct RequiredTrack {
+    #[protto(expect)]
+    pub track: proto::Track,
+}
+
+fn handle(
+    request: tonic::Request<proto::HasOptional>,
+) -> Result<tonic::Response<proto::HasOptional>, tonic::Status> {
+    let track: RequiredTrack = request.into_domain()?;
+    Ok(track.into_response())
+}
+
+#[test]
+fn handler_converts_request_and_response() {
+    let message = proto::HasOptional {
+        track: Some(proto::Track { track_id: 9 }),
+    };
+    let response = handle(tonic::Request::new(message.clone())).unwrap();
+    assert_eq!(response.into_inner(), message);
+}
+
+#[test]
+fn failed_request_conversion_is_invalid_argument() {
+    let status = handle(tonic::Request::new(proto::HasOptional { track: None })).unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    assert!(status.message().contains("track"));
+}
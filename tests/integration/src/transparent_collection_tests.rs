@@ -0,0 +1,99 @@
+// ABOUTME: Tests for transparent fields of newtypes over a Vec or map, converting the repeated or
+// ABOUTME: map proto field element by element.
+
+use crate::proto;
+use protto::Protto;
+use protto::newtype::CollectionNewtype;
+use std::collections::HashMap;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "LabelMessage")]
+pub struct Label {
+    pub name: String,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+pub struct Labels(Vec<Label>);
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+pub struct LabelsByKey(HashMap<String, Label>);
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+pub struct Keywords(Vec<String>);
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "TaggedPostMessage")]
+pub struct TaggedPost {
+    pub title: String,
+    #[protto(transparent)]
+    pub labels: Labels,
+    #[protto(transparent)]
+    pub labels_by_key: LabelsByKey,
+    #[protto(transparent)]
+    pub keywords: Keywords,
+}
+
+fn label(name: &str) -> proto::LabelMessage {
+    proto::LabelMessage {
+        name: name.to_string(),
+    }
+}
+
+fn tagged_post_message() -> proto::TaggedPostMessage {
+    proto::TaggedPostMessage {
+        title: "release notes".to_string(),
+        labels: vec![label("rust"), label("proto")],
+        labels_by_key: HashMap::from([("lang".to_string(), label("rust"))]),
+        keywords: vec!["derive".to_string(), "newtype".to_string()],
+    }
+}
+
+#[test]
+fn test_repeated_field_converts_into_a_vec_newtype() {
+    let post = TaggedPost::from(tagged_post_message());
+
+    let names: Vec<_> = post
+        .labels
+        .into_collection()
+        .into_iter()
+        .map(|label| label.name)
+        .collect();
+    assert_eq!(names, ["rust", "proto"]);
+    assert_eq!(
+        Vec::from(post.keywords),
+        ["derive".to_string(), "newtype".to_string()]
+    );
+}
+
+#[test]
+fn test_map_field_converts_into_a_map_newtype() {
+    let post = TaggedPost::from(tagged_post_message());
+
+    let by_key = post.labels_by_key.into_collection();
+    assert_eq!(by_key.len(), 1);
+    assert_eq!(by_key["lang"].name, "rust");
+}
+
+#[test]
+fn test_collection_newtypes_round_trip() {
+    let proto_msg = tagged_post_message();
+
+    let post = TaggedPost::from(proto_msg.clone());
+    let back: proto::TaggedPostMessage = post.into();
+    assert_eq!(back, proto_msg);
+}
+
+#[test]
+fn test_empty_collections_round_trip() {
+    let proto_msg = proto::TaggedPostMessage {
+        title: "draft".to_string(),
+        ..Default::default()
+    };
+
+    let post = TaggedPost::from(proto_msg.clone());
+    assert_eq!(post.labels, Labels::from(Vec::new()));
+    assert_eq!(post.keywords, Keywords::from(Vec::new()));
+
+    let back: proto::TaggedPostMessage = post.into();
+    assert_eq!(back, proto_msg);
+}
@@ -0,0 +1,87 @@
+// ABOUTME: Tests for struct-level `transparent`, which converts a single-field struct by delegating
+// ABOUTME: to its field: newtypes wrapping a proto message or a type converting from one.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "UuidMessage")]
+pub struct Uuid {
+    pub value: String,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(transparent, proto_name = "UuidMessage")]
+pub struct UserId(proto::UuidMessage);
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(transparent, proto_name = "UuidMessage")]
+pub struct AccountId(Uuid);
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(transparent, proto_name = "UuidMessage")]
+pub struct OwnerId {
+    pub uuid: Uuid,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "OwnershipMessage")]
+pub struct Ownership {
+    #[protto(expect(panic))]
+    pub owner: OwnerId,
+    pub viewers: Vec<AccountId>,
+}
+
+fn uuid_message(value: &str) -> proto::UuidMessage {
+    proto::UuidMessage {
+        value: value.to_string(),
+    }
+}
+
+#[test]
+fn transparent_newtype_wraps_proto_message() {
+    let user_id = UserId::from(uuid_message("u-1"));
+    assert_eq!(user_id, UserId(uuid_message("u-1")));
+
+    let proto_msg = proto::UuidMessage::from(user_id);
+    assert_eq!(proto_msg, uuid_message("u-1"));
+}
+
+#[test]
+fn transparent_newtype_delegates_to_inner_conversion() {
+    let account_id = AccountId::from(uuid_message("a-1"));
+    assert_eq!(
+        account_id,
+        AccountId(Uuid {
+            value: "a-1".to_string()
+        })
+    );
+
+    let proto_msg = proto::UuidMessage::from(account_id);
+    assert_eq!(proto_msg, uuid_message("a-1"));
+}
+
+#[test]
+fn transparent_named_struct_delegates_to_its_field() {
+    let owner_id = OwnerId::from(uuid_message("o-1"));
+    assert_eq!(owner_id.uuid.value, "o-1");
+
+    let proto_msg = proto::UuidMessage::from(owner_id);
+    assert_eq!(proto_msg, uuid_message("o-1"));
+}
+
+#[test]
+fn transparent_structs_convert_as_message_fields() {
+    let proto_msg = proto::OwnershipMessage {
+        owner: Some(uuid_message("o-1")),
+        viewers: vec![uuid_message("a-1"), uuid_message("a-2")],
+    };
+
+    let ownership = Ownership::from(proto_msg.clone());
+    assert_eq!(ownership.owner.uuid.value, "o-1");
+    assert_eq!(ownership.viewers.len(), 2);
+    assert_eq!(ownership.viewers[1].0.value, "a-2");
+
+    let round_tripped: proto::OwnershipMessage = ownership.into();
+    assert_eq!(round_tripped, proto_msg);
+}
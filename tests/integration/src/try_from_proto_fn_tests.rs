@@ -0,0 +1,115 @@
+// ABOUTME: Tests for `try_from_proto_fn`, custom functions that return `Result<T, E>`.
+// ABOUTME: Covers the generated error, `From` conversion into a struct error_type, and expect(panic).
+
+use crate::proto;
+use protto::Protto;
+use std::num::ParseIntError;
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "Header")]
+pub struct ParsedHeader {
+    #[protto(
+        try_from_proto_fn = "parse_request_id",
+        to_proto_fn = "format_request_id"
+    )]
+    pub request_id: u64,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum HeaderError {
+    BadRequestId(String),
+}
+
+impl From<ParseIntError> for HeaderError {
+    fn from(err: ParseIntError) -> Self {
+        Self::BadRequestId(err.to_string())
+    }
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "Header", error_type = HeaderError)]
+pub struct TypedHeader {
+    #[protto(
+        try_from_proto_fn = "parse_request_id",
+        to_proto_fn = "format_request_id"
+    )]
+    pub request_id: u64,
+    pub timestamp: i64,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "Header")]
+pub struct StrictHeader {
+    #[protto(
+        try_from_proto_fn = "parse_request_id",
+        to_proto_fn = "format_request_id",
+        expect(panic)
+    )]
+    pub request_id: u64,
+    pub timestamp: i64,
+}
+
+fn parse_request_id(request_id: String) -> Result<u64, ParseIntError> {
+    request_id.parse()
+}
+
+fn format_request_id(request_id: u64) -> String {
+    request_id.to_string()
+}
+
+fn header(request_id: &str) -> proto::Header {
+    proto::Header {
+        request_id: request_id.to_string(),
+        timestamp: 1_700_000_000,
+    }
+}
+
+#[test]
+fn try_from_proto_fn_converts_valid_value() {
+    let parsed = ParsedHeader::try_from(header("42")).unwrap();
+    assert_eq!(
+        parsed,
+        ParsedHeader {
+            request_id: 42,
+            timestamp: 1_700_000_000,
+        }
+    );
+
+    let back: proto::Header = parsed.into();
+    assert_eq!(back, header("42"));
+}
+
+#[test]
+fn try_from_proto_fn_reports_invalid_value() {
+    assert_eq!(
+        ParsedHeader::try_from(header("not-a-number")),
+        Err(ParsedHeaderConversionError::InvalidValue(
+            "request_id".to_string()
+        ))
+    );
+}
+
+#[test]
+fn try_from_proto_fn_error_converts_into_error_type() {
+    assert_eq!(
+        TypedHeader::try_from(header("7")).map(|h| h.request_id),
+        Ok(7)
+    );
+    assert!(matches!(
+        TypedHeader::try_from(header("x")),
+        Err(HeaderError::BadRequestId(_))
+    ));
+}
+
+#[test]
+fn try_from_proto_fn_with_expect_panic_uses_from() {
+    let strict: StrictHeader = header("9").into();
+    assert_eq!(strict.request_id, 9);
+}
+
+#[test]
+#[should_panic(expected = "Proto field request_id could not be converted")]
+fn try_from_proto_fn_with_expect_panic_panics_on_error() {
+    let _: StrictHeader = header("nine").into();
+}
@@ -0,0 +1,66 @@
+// ABOUTME: Tests for fields typed through type aliases and for proto modules imported under an alias.
+// ABOUTME: Covers `alias_of` on bare, `Option` and `Vec` fields and an alias of a derived struct.
+
+use crate::proto as api;
+use crate::shared_types::{Track, TrackId};
+use protto::Protto;
+
+pub type UserId = u64;
+pub type Friends = Vec<UserId>;
+pub type Home = Track;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(module = "api", proto_name = "AliasedAccountMessage")]
+pub struct AliasedAccount {
+    #[protto(alias_of = "u64")]
+    pub user_id: UserId,
+    #[protto(alias_of = "u64")]
+    pub referrer: Option<UserId>,
+    #[protto(alias_of = "Vec<u64>")]
+    pub friends: Friends,
+    pub home: Home,
+    pub header: Option<api::Header>,
+}
+
+fn account() -> AliasedAccount {
+    AliasedAccount {
+        user_id: 42,
+        referrer: Some(7),
+        friends: vec![3, 5],
+        home: Track {
+            id: TrackId::new(9),
+        },
+        header: Some(api::Header {
+            request_id: "req-1".to_string(),
+            timestamp: 1_700_000_000,
+        }),
+    }
+}
+
+#[test]
+fn test_aliased_fields_convert_as_their_targets() {
+    let proto: api::AliasedAccountMessage = account().into();
+    assert_eq!(proto.user_id, 42);
+    assert_eq!(proto.referrer, Some(7));
+    assert_eq!(proto.friends, vec![3, 5]);
+    assert_eq!(proto.home, Some(api::Track { track_id: 9 }));
+    assert_eq!(
+        proto
+            .header
+            .as_ref()
+            .map(|header| header.request_id.as_str()),
+        Some("req-1")
+    );
+}
+
+#[test]
+fn test_aliased_fields_round_trip() {
+    let proto: api::AliasedAccountMessage = account().into();
+    assert_eq!(AliasedAccount::from(proto), account());
+
+    let proto = api::AliasedAccountMessage {
+        referrer: None,
+        ..account().into()
+    };
+    assert_eq!(AliasedAccount::from(proto).referrer, None);
+}
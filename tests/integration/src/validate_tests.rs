@@ -0,0 +1,138 @@
+// ABOUTME: Tests for struct-level `validate`, checking proto messages before converting them.
+// ABOUTME: Covers the generated error's `Validation` variant, a custom error type built with `From`,
+// ABOUTME: and messages validated through the `validator::Validate` their codegen derives.
+
+use crate::proto;
+use protto::Protto;
+use protto::validate::{ValidateProto, ValidationError};
+
+impl ValidateProto for proto::SignupMessage {
+    fn validate_proto(&self) -> Result<(), ValidationError> {
+        if !self.email.contains('@') {
+            return Err(ValidationError::new(
+                "email",
+                "value must be a valid email address",
+            ));
+        }
+        if self.age < 13 {
+            return Err(ValidationError::new(
+                "age",
+                "value must be greater than or equal to 13",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "SignupMessage", validate)]
+pub struct Signup {
+    pub email: String,
+    pub age: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SignupError {
+    MissingField(String),
+    Rejected(ValidationError),
+}
+
+impl SignupError {
+    pub fn missing_field(field: &str) -> Self {
+        Self::MissingField(field.to_string())
+    }
+}
+
+impl From<ValidationError> for SignupError {
+    fn from(err: ValidationError) -> Self {
+        Self::Rejected(err)
+    }
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(
+    proto_name = "SignupMessage",
+    validate,
+    error_type = SignupError,
+    error_fn = "SignupError::missing_field"
+)]
+pub struct CheckedSignup {
+    pub email: String,
+    pub age: u32,
+}
+
+fn signup(email: &str, age: u32) -> proto::SignupMessage {
+    proto::SignupMessage {
+        email: email.to_string(),
+        age,
+    }
+}
+
+#[test]
+fn test_valid_messages_convert() {
+    let converted = Signup::try_from(signup("ada@example.com", 36)).unwrap();
+    assert_eq!(converted.email, "ada@example.com");
+
+    let round_tripped: proto::SignupMessage = converted.into();
+    assert_eq!(round_tripped, signup("ada@example.com", 36));
+}
+
+#[test]
+fn test_invalid_messages_fail_with_the_validation_error() {
+    let err = Signup::try_from(signup("ada", 36)).unwrap_err();
+    assert_eq!(
+        err,
+        SignupConversionError::Validation(ValidationError::new(
+            "email",
+            "value must be a valid email address"
+        ))
+    );
+    assert_eq!(
+        err.to_string(),
+        "Validation failed: email: value must be a valid email address"
+    );
+
+    assert_eq!(
+        CheckedSignup::try_from(signup("ada@example.com", 9)),
+        Err(SignupError::Rejected(ValidationError::new(
+            "age",
+            "value must be greater than or equal to 13"
+        )))
+    );
+}
+
+// EnrollmentMessage derives `validator::Validate` in build.rs, so it needs no `ValidateProto` impl
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "EnrollmentMessage", validate)]
+pub struct Enrollment {
+    pub email: String,
+    pub seats: u32,
+}
+
+fn enrollment(email: &str, seats: u32) -> proto::EnrollmentMessage {
+    proto::EnrollmentMessage {
+        email: email.to_string(),
+        seats,
+    }
+}
+
+#[test]
+fn test_messages_deriving_validate_are_checked_without_an_impl() {
+    let converted = Enrollment::try_from(enrollment("ada@example.com", 3)).unwrap();
+    assert_eq!(converted.seats, 3);
+
+    assert_eq!(
+        Enrollment::try_from(enrollment("ada", 3)),
+        Err(EnrollmentConversionError::Validation(ValidationError::new(
+            "email",
+            "value must be a valid email address"
+        )))
+    );
+    assert_eq!(
+        Enrollment::try_from(enrollment("ada@example.com", 0)),
+        Err(EnrollmentConversionError::Validation(ValidationError::new(
+            "seats",
+            "value must be at least 1"
+        )))
+    );
+}
@@ -0,0 +1,88 @@
+// ABOUTME: Tests for repeated field conversions of newtype and nested message elements.
+// ABOUTME: Newtype vectors reuse their allocation; message vectors are preallocated exactly.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, PartialEq, Debug, Clone, Copy)]
+pub struct TrackNumber(u64);
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "Header")]
+pub struct RequestHeader {
+    pub request_id: String,
+    pub timestamp: i64,
+}
+
+#[derive(Protto, PartialEq, Debug, Clone)]
+#[protto(proto_name = "HeaderBatchMessage")]
+pub struct HeaderBatch {
+    pub track_ids: Vec<TrackNumber>,
+    pub headers: Vec<RequestHeader>,
+}
+
+fn sample_batch(len: usize) -> HeaderBatch {
+    HeaderBatch {
+        track_ids: (0..len as u64).map(TrackNumber).collect(),
+        headers: (0..len as i64)
+            .map(|timestamp| RequestHeader {
+                request_id: format!("req-{timestamp}"),
+                timestamp,
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn batch_round_trips() {
+    let batch = sample_batch(3);
+
+    let proto_batch: proto::HeaderBatchMessage = batch.clone().into();
+    assert_eq!(proto_batch.track_ids, vec![0, 1, 2]);
+    assert_eq!(proto_batch.headers[2].request_id, "req-2");
+
+    assert_eq!(HeaderBatch::from(proto_batch), batch);
+}
+
+#[test]
+fn converted_message_vec_is_allocated_exactly() {
+    let batch = sample_batch(1000);
+
+    let proto_batch: proto::HeaderBatchMessage = batch.into();
+    assert_eq!(proto_batch.headers.len(), 1000);
+    assert_eq!(proto_batch.headers.capacity(), 1000);
+
+    let batch = HeaderBatch::from(proto_batch);
+    assert_eq!(batch.headers.capacity(), 1000);
+}
+
+#[test]
+fn newtype_vec_reuses_its_allocation() {
+    let mut track_ids = Vec::with_capacity(16);
+    track_ids.extend((0..10).map(TrackNumber));
+    let source = track_ids.as_ptr() as usize;
+    let batch = HeaderBatch {
+        track_ids,
+        headers: Vec::new(),
+    };
+
+    let proto_batch: proto::HeaderBatchMessage = batch.into();
+    assert_eq!(proto_batch.track_ids.as_ptr() as usize, source);
+    assert_eq!(proto_batch.track_ids.capacity(), 16);
+
+    let batch = HeaderBatch::from(proto_batch);
+    assert_eq!(batch.track_ids.as_ptr() as usize, source);
+    assert_eq!(batch.track_ids.capacity(), 16);
+    assert_eq!(batch.track_ids[9], TrackNumber(9));
+}
+
+#[test]
+fn empty_batch_round_trips() {
+    let proto_batch = proto::HeaderBatchMessage::default();
+    let batch = HeaderBatch::from(proto_batch.clone());
+    assert!(batch.track_ids.is_empty());
+    assert!(batch.headers.is_empty());
+
+    let back: proto::HeaderBatchMessage = batch.into();
+    assert_eq!(back, proto_batch);
+}
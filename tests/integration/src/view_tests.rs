@@ -0,0 +1,66 @@
+// ABOUTME: Tests for struct-level view, generating a `<Struct>View<'a>` that borrows strings and
+// ABOUTME: bytes from a proto message and converts to the owned struct with `to_owned`.
+
+use crate::proto;
+use protto::Protto;
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "ArticleMessage", view)]
+pub struct Article {
+    pub id: u64,
+    pub title: String,
+    pub body: Vec<u8>,
+    pub subtitle: Option<String>,
+    pub tags: Vec<String>,
+    #[protto(expect)]
+    pub rank: u32,
+}
+
+fn article_message() -> proto::ArticleMessage {
+    proto::ArticleMessage {
+        id: 3,
+        title: "Views".to_string(),
+        body: b"borrowed".to_vec(),
+        subtitle: Some("zero copy".to_string()),
+        tags: vec!["rust".to_string(), "proto".to_string()],
+        rank: Some(1),
+    }
+}
+
+#[test]
+fn test_view_borrows_from_the_message() {
+    let message = article_message();
+    let view = ArticleView::from(&message);
+    assert_eq!(view.id, 3);
+    assert_eq!(view.title, "Views");
+    assert_eq!(view.body, b"borrowed");
+    assert_eq!(view.subtitle, Some("zero copy"));
+    assert_eq!(view.tags, ["rust", "proto"]);
+    assert!(std::ptr::eq(view.title.as_ptr(), message.title.as_ptr()));
+}
+
+#[test]
+fn test_view_leaves_converted_fields_to_the_message() {
+    let message = article_message();
+    let view = ArticleView::new(&message);
+    assert_eq!(view.proto().rank, Some(1));
+}
+
+#[test]
+fn test_view_to_owned_runs_the_full_conversion() {
+    let message = article_message();
+    let view = ArticleView::new(&message);
+    assert_eq!(
+        view.to_owned().unwrap(),
+        Article::try_from(article_message()).unwrap()
+    );
+
+    let unranked = proto::ArticleMessage {
+        rank: None,
+        ..article_message()
+    };
+    assert_eq!(
+        ArticleView::new(&unranked).to_owned().unwrap_err(),
+        ArticleConversionError::MissingField("rank".to_string())
+    );
+}
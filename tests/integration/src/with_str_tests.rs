@@ -0,0 +1,81 @@
+// ABOUTME: Tests for field-level `with_str`, carrying FromStr + Display types as proto strings.
+// ABOUTME: Values are displayed into the proto and parsed back, with parse failures as errors.
+
+use crate::proto;
+use protto::Protto;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "EndpointMessage")]
+pub struct Endpoint {
+    #[protto(with_str)]
+    pub address: SocketAddr,
+    #[protto(with_str)]
+    pub fallback_ip: Option<IpAddr>,
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "EndpointMessage")]
+pub struct TrustedEndpoint {
+    #[protto(with_str, expect(panic))]
+    pub address: SocketAddr,
+    #[protto(with_str)]
+    pub fallback_ip: Option<IpAddr>,
+}
+
+#[test]
+fn with_str_round_trips() {
+    let endpoint = Endpoint {
+        address: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8080),
+        fallback_ip: Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+    };
+
+    let proto_msg: proto::EndpointMessage = endpoint.clone().into();
+    assert_eq!(proto_msg.address, "127.0.0.1:8080");
+    assert_eq!(proto_msg.fallback_ip.as_deref(), Some("10.0.0.1"));
+
+    let back = Endpoint::try_from(proto_msg).unwrap();
+    assert_eq!(back, endpoint);
+}
+
+#[test]
+fn with_str_unset_optional_is_none() {
+    let endpoint = Endpoint::try_from(proto::EndpointMessage {
+        address: "[::1]:443".to_string(),
+        fallback_ip: None,
+    })
+    .unwrap();
+    assert_eq!(endpoint.fallback_ip, None);
+}
+
+#[test]
+fn with_str_parse_failure_is_invalid_value() {
+    let result = Endpoint::try_from(proto::EndpointMessage {
+        address: "localhost".to_string(),
+        fallback_ip: None,
+    });
+    assert_eq!(
+        result,
+        Err(EndpointConversionError::InvalidValue("address".to_string()))
+    );
+
+    let result = Endpoint::try_from(proto::EndpointMessage {
+        address: "127.0.0.1:80".to_string(),
+        fallback_ip: Some("not an ip".to_string()),
+    });
+    assert_eq!(
+        result,
+        Err(EndpointConversionError::InvalidValue(
+            "fallback_ip".to_string()
+        ))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Proto field address could not be parsed")]
+fn with_str_expect_panic_panics_on_parse_failure() {
+    let _ = TrustedEndpoint::try_from(proto::EndpointMessage {
+        address: "localhost".to_string(),
+        fallback_ip: None,
+    });
+}
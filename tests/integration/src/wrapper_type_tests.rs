@@ -0,0 +1,55 @@
+// ABOUTME: Tests for the `wrapper_types` feature converting Option<T> fields through wrapper messages.
+// ABOUTME: Covers Some and None in both directions and conversion of the wrapped value with Into.
+
+use crate::proto;
+use protto::Protto;
+
+protto::impl_wrapper_types!(proto::UInt64Value => u64, proto::StringValue => String);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label(String);
+
+impl From<String> for Label {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Label> for String {
+    fn from(label: Label) -> Self {
+        label.0
+    }
+}
+
+#[derive(Protto, Debug, Clone, PartialEq)]
+#[protto(proto_name = "WrappedIdsMessage")]
+pub struct WrappedIds {
+    pub id: Option<u64>,
+    pub label: Option<Label>,
+}
+
+#[test]
+fn wrapper_messages_unwrap_to_options() {
+    let ids = WrappedIds::from(proto::WrappedIdsMessage {
+        id: Some(proto::UInt64Value { value: 5 }),
+        label: None,
+    });
+    assert_eq!(ids.id, Some(5));
+    assert_eq!(ids.label, None);
+}
+
+#[test]
+fn options_wrap_into_wrapper_messages() {
+    let proto_msg: proto::WrappedIdsMessage = WrappedIds {
+        id: None,
+        label: Some(Label("primary".to_string())),
+    }
+    .into();
+    assert_eq!(proto_msg.id, None);
+    assert_eq!(
+        proto_msg.label,
+        Some(proto::StringValue {
+            value: "primary".to_string()
+        })
+    );
+}